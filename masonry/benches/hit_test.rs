@@ -0,0 +1,108 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmarks of pointer hit-testing on large widget trees.
+//!
+//! The full-tree scan is a baseline of what hit-testing costs without the child index,
+//! checking the layout rect of every widget in the tree.
+//!
+//! Run with `cargo bench -p masonry --bench hit_test`.
+
+#![expect(
+    missing_docs,
+    reason = "`criterion_group!` generates undocumented functions"
+)]
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use masonry::testing::TestHarness;
+use masonry::widget::{Flex, SizedBox, WidgetRef};
+use masonry::{Point, Size, Widget, WidgetId};
+
+const WINDOW_SIZE: Size = Size::new(1200., 800.);
+
+/// Positions spread over the window, so that different widgets are hit.
+const POSITIONS: [Point; 4] = [
+    Point::new(2., 2.),
+    Point::new(300., 150.),
+    Point::new(2., 601.),
+    Point::new(390., 399.),
+];
+
+/// A column of `rows` fixed-size boxes, like a long list.
+fn long_column(rows: usize) -> impl Widget {
+    let mut column = Flex::column().gap(0.);
+    for _ in 0..rows {
+        column = column.with_child(SizedBox::empty().width(400.).height(4.));
+    }
+    column
+}
+
+/// A column of `rows` rows, each containing `columns` fixed-size boxes.
+fn flex_grid(rows: usize, columns: usize) -> impl Widget {
+    let mut column = Flex::column().gap(0.);
+    for _ in 0..rows {
+        let mut row = Flex::row().gap(0.);
+        for _ in 0..columns {
+            row = row.with_child(SizedBox::empty().width(4.).height(4.));
+        }
+        column = column.with_child(row);
+    }
+    column
+}
+
+/// The deepest, last widget whose layout rect contains `pos`, found by visiting every widget.
+fn scan_tree(widget: WidgetRef<'_, dyn Widget>, pos: Point) -> Option<WidgetId> {
+    let mut hit = widget
+        .ctx()
+        .window_layout_rect()
+        .contains(pos)
+        .then(|| widget.id());
+    for child in widget.children() {
+        if let Some(child_hit) = scan_tree(child, pos) {
+            hit = Some(child_hit);
+        }
+    }
+    hit
+}
+
+fn bench_tree(c: &mut Criterion, name: &str, widget: impl Widget) {
+    let mut harness = TestHarness::create_with_size(widget, WINDOW_SIZE);
+
+    c.bench_function(&format!("hit_test: find_widget_at_pos in {name}"), |b| {
+        let root = harness.root_widget();
+        let mut frame = 0;
+        b.iter(|| {
+            frame += 1;
+            root.find_widget_at_pos(POSITIONS[frame % POSITIONS.len()])
+                .map(|widget| widget.id())
+        });
+    });
+
+    c.bench_function(&format!("hit_test: full-tree scan of {name}"), |b| {
+        let root = harness.root_widget();
+        let mut frame = 0;
+        b.iter(|| {
+            frame += 1;
+            scan_tree(root, POSITIONS[frame % POSITIONS.len()])
+        });
+    });
+
+    c.bench_function(&format!("hit_test: pointer move over {name}"), |b| {
+        let mut frame = 0;
+        b.iter(|| {
+            frame += 1;
+            harness.mouse_move(POSITIONS[frame % POSITIONS.len()]);
+        });
+    });
+}
+
+fn long_list(c: &mut Criterion) {
+    bench_tree(c, "10k row column", long_column(10_000));
+}
+
+fn nested_grid(c: &mut Criterion) {
+    bench_tree(c, "10k node flex tree", flex_grid(100, 100));
+}
+
+criterion_group!(benches, long_list, nested_grid);
+criterion_main!(benches);
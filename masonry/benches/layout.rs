@@ -0,0 +1,92 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmarks of the layout pass on large widget trees.
+//!
+//! Run with `cargo bench -p masonry --bench layout`.
+
+#![expect(
+    missing_docs,
+    reason = "`criterion_group!` generates undocumented functions"
+)]
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use masonry::dpi::PhysicalSize;
+use masonry::testing::{widget_ids, TestHarness};
+use masonry::widget::{Flex, Label, LineBreaking, SizedBox};
+use masonry::{Size, Widget, WindowEvent};
+
+const WINDOW_SIZE: Size = Size::new(1200., 800.);
+
+/// A column of `rows` rows, each containing `columns` fixed-size boxes.
+fn flex_grid(rows: usize, columns: usize) -> impl Widget {
+    let mut column = Flex::column();
+    for _ in 0..rows {
+        let mut row = Flex::row();
+        for _ in 0..columns {
+            row = row.with_child(SizedBox::empty().width(4.).height(4.));
+        }
+        column = column.with_child(row);
+    }
+    column
+}
+
+fn flex_tree_layout(c: &mut Criterion) {
+    let mut harness = TestHarness::create_with_size(flex_grid(100, 100), WINDOW_SIZE);
+
+    c.bench_function("layout: 10k node flex tree", |b| {
+        b.iter(|| {
+            harness.edit_root_widget(|mut root| root.ctx.request_layout());
+        });
+    });
+}
+
+fn deep_nesting_relayout(c: &mut Criterion) {
+    const DEPTH: usize = 500;
+    let [leaf_id] = widget_ids();
+
+    let leaf = SizedBox::empty().width(10.).height(10.);
+    let mut widget: Box<dyn Widget> = Box::new(SizedBox::new_with_id(leaf, leaf_id));
+    for _ in 0..DEPTH {
+        widget = Box::new(SizedBox::new(widget));
+    }
+    let mut harness = TestHarness::create_with_size(widget, WINDOW_SIZE);
+
+    c.bench_function("layout: relayout of leaf nested 500 deep", |b| {
+        b.iter(|| {
+            harness.edit_widget(leaf_id, |mut leaf| leaf.ctx.request_layout());
+        });
+    });
+}
+
+fn text_heavy_relayout(c: &mut Criterion) {
+    const PARAGRAPH: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, \
+        sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. \
+        Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip \
+        ex ea commodo consequat.";
+
+    let mut column = Flex::column();
+    for _ in 0..500 {
+        column =
+            column.with_child(Label::new(PARAGRAPH).with_line_break_mode(LineBreaking::WordWrap));
+    }
+    let mut harness = TestHarness::create_with_size(column, WINDOW_SIZE);
+
+    // Alternate between two window widths, so that every paragraph has to be broken into lines again.
+    let sizes = [PhysicalSize::new(1200, 800), PhysicalSize::new(700, 800)];
+    let mut frame = 0;
+    c.bench_function("layout: 500 wrapped paragraphs after resize", |b| {
+        b.iter(|| {
+            frame += 1;
+            harness.process_window_event(WindowEvent::Resize(sizes[frame % 2]));
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    flex_tree_layout,
+    deep_nesting_relayout,
+    text_heavy_relayout
+);
+criterion_main!(benches);
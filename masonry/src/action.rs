@@ -4,6 +4,9 @@
 use std::any::Any;
 
 use crate::event::PointerButton;
+use crate::popup::PopupId;
+use crate::text::SpellingSuggestions;
+use crate::{Widget, WidgetId};
 
 // TODO - Refactor - See issue https://github.com/linebender/xilem/issues/335
 
@@ -17,10 +20,67 @@ pub enum Action {
     TextChanged(String),
     TextEntered(String),
     CheckboxChecked(bool),
+    /// A message posted by the page shown in a `WebView`, with `window.ipc.postMessage`.
+    WebViewMessage(String),
+    /// The page shown in a `WebView` finished loading the given URL.
+    WebViewLoaded(String),
+    /// An action submitted by the widget `widget` of the popup `popup`, which was opened by
+    /// the widget this action is for.
+    Popup {
+        popup: PopupId,
+        widget: WidgetId,
+        action: Box<Action>,
+    },
+    /// The popup opened by the widget this action is for was closed because the window
+    /// was clicked.
+    PopupClosed(PopupId),
+    /// A misspelled word of a text area with a spell checker was right-clicked.
+    SpellingSuggestions(SpellingSuggestions),
     // FIXME - This is a huge hack
     Other(Box<dyn Any + Send>),
 }
 
+impl Action {
+    /// Wrap an action of a type defined outside of Masonry, such as the
+    /// [`ActionSource::Action`] of a custom widget.
+    ///
+    /// Use [`downcast`](Self::downcast) to get it back.
+    pub fn typed<T: Any + Send>(action: T) -> Self {
+        Self::Other(Box::new(action))
+    }
+
+    /// Whether this is a [typed action](Self::typed) of type `T`.
+    pub fn is<T: Any>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+
+    /// A reference to the [typed action](Self::typed) of type `T`, if this is one.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        match self {
+            Self::Other(action) => action.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// The [typed action](Self::typed) of type `T`, or `self` if this isn't one.
+    pub fn downcast<T: Any>(self) -> Result<T, Self> {
+        match self {
+            Self::Other(action) => action.downcast().map(|action| *action).map_err(Self::Other),
+            action => Err(action),
+        }
+    }
+}
+
+/// A widget which submits [typed actions](Action::typed) of type [`Action`](Self::Action).
+///
+/// This lets app drivers handle the actions of the widget without naming their type,
+/// with [`ActionRouter::on_actions_of`](crate::ActionRouter::on_actions_of).
+pub trait ActionSource: Widget {
+    /// The type of the actions submitted by this widget, with
+    /// [`EventCtx::submit_typed_action`](crate::EventCtx::submit_typed_action).
+    type Action: Any + Send;
+}
+
 impl PartialEq for Action {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -28,6 +88,22 @@ impl PartialEq for Action {
             (Self::TextChanged(l0), Self::TextChanged(r0)) => l0 == r0,
             (Self::TextEntered(l0), Self::TextEntered(r0)) => l0 == r0,
             (Self::CheckboxChecked(l0), Self::CheckboxChecked(r0)) => l0 == r0,
+            (Self::WebViewMessage(l0), Self::WebViewMessage(r0)) => l0 == r0,
+            (Self::WebViewLoaded(l0), Self::WebViewLoaded(r0)) => l0 == r0,
+            (
+                Self::Popup {
+                    popup: l_popup,
+                    widget: l_widget,
+                    action: l_action,
+                },
+                Self::Popup {
+                    popup: r_popup,
+                    widget: r_widget,
+                    action: r_action,
+                },
+            ) => l_popup == r_popup && l_widget == r_widget && l_action == r_action,
+            (Self::PopupClosed(l0), Self::PopupClosed(r0)) => l0 == r0,
+            (Self::SpellingSuggestions(l0), Self::SpellingSuggestions(r0)) => l0 == r0,
             // FIXME
             // (Self::Other(val_l), Self::Other(val_r)) => false,
             _ => false,
@@ -42,6 +118,25 @@ impl std::fmt::Debug for Action {
             Self::TextChanged(text) => f.debug_tuple("TextChanged").field(text).finish(),
             Self::TextEntered(text) => f.debug_tuple("TextEntered").field(text).finish(),
             Self::CheckboxChecked(b) => f.debug_tuple("CheckboxChecked").field(b).finish(),
+            Self::WebViewMessage(message) => {
+                f.debug_tuple("WebViewMessage").field(message).finish()
+            }
+            Self::WebViewLoaded(url) => f.debug_tuple("WebViewLoaded").field(url).finish(),
+            Self::Popup {
+                popup,
+                widget,
+                action,
+            } => f
+                .debug_struct("Popup")
+                .field("popup", popup)
+                .field("widget", widget)
+                .field("action", action)
+                .finish(),
+            Self::PopupClosed(popup) => f.debug_tuple("PopupClosed").field(popup).finish(),
+            Self::SpellingSuggestions(suggestions) => f
+                .debug_tuple("SpellingSuggestions")
+                .field(suggestions)
+                .finish(),
             Self::Other(_) => write!(f, "Other(...)"),
         }
     }
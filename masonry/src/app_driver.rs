@@ -1,9 +1,19 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::any::Any;
+use std::sync::Arc;
+
+use winit::event::KeyEvent;
+use winit::keyboard::ModifiersState;
+
+#[cfg(feature = "event_loop_runner")]
 use crate::event_loop_runner::MasonryState;
+use crate::gpu::{GpuInfo, RenderError};
+use crate::monitor::{MonitorInfo, WindowPlacement};
+use crate::text::{measure_text, StyleProperty, TextMetrics};
 use crate::widget::WidgetMut;
-use crate::{Action, Widget, WidgetId};
+use crate::{Action, ActionSource, RenderRoot, Widget, WidgetId};
 
 pub struct DriverCtx<'a> {
     // TODO
@@ -23,7 +33,82 @@ pub trait AppDriver {
     /// A hook which will be executed when the application starts, to allow initial configuration of the `MasonryState`.
     ///
     /// Use cases include loading fonts.
+    #[cfg(feature = "event_loop_runner")]
     fn on_start(&mut self, state: &mut MasonryState) {}
+
+    #[allow(unused_variables)]
+    // reason: otherwise `render_root` would need to be named `_render_root` which behaves badly when using rust-analyzer to implement the trait
+    /// A hook which will be executed when the app is started by an [`AppHost`](crate::host::AppHost)
+    /// other than the winit event loop runner, which calls [`on_start`](Self::on_start) instead.
+    ///
+    /// Use cases include loading fonts.
+    fn on_host_start(&mut self, render_root: &mut RenderRoot) {}
+
+    #[allow(unused_variables)]
+    // reason: otherwise `event` would need to be named `_event` which behaves badly when using rust-analyzer to implement the trait
+    /// A hook which will be executed when a keyboard event wasn't handled by any widget.
+    ///
+    /// This happens when no widget has focus, or when the focused widget and its
+    /// ancestors didn't handle the event. Use cases include window-level keyboard shortcuts.
+    fn on_unhandled_key(
+        &mut self,
+        ctx: &mut DriverCtx<'_>,
+        event: &KeyEvent,
+        modifiers: ModifiersState,
+    ) {
+    }
+
+    #[allow(unused_variables)]
+    // reason: otherwise `ctx` would need to be named `_ctx` which behaves badly when using rust-analyzer to implement the trait
+    /// A hook which will be executed when the event loop is idle, after the idle work of
+    /// widgets (see [`EventCtx::schedule_idle`](crate::EventCtx::schedule_idle)).
+    ///
+    /// This lets apps split expensive work into chunks. Return whether a chunk of work was
+    /// done: while it does, this is called again when the event loop is next idle.
+    fn on_idle(&mut self, ctx: &mut DriverCtx<'_>) -> bool {
+        false
+    }
+
+    #[allow(unused_variables)]
+    // reason: otherwise `placement` would need to be named `_placement` which behaves badly when using rust-analyzer to implement the trait
+    /// A hook which will be executed when the window is moved or resized.
+    ///
+    /// See the [`monitor`](crate::monitor) module for details.
+    fn on_window_placement(
+        &mut self,
+        ctx: &mut DriverCtx<'_>,
+        placement: &WindowPlacement,
+        monitors: &[MonitorInfo],
+    ) {
+    }
+
+    #[allow(unused_variables)]
+    // reason: otherwise `info` would need to be named `_info` which behaves badly when using rust-analyzer to implement the trait
+    /// A hook which will be executed when the window starts being rendered with a GPU adapter,
+    /// e.g. to show which one in a diagnostics view.
+    ///
+    /// See the [`gpu`](crate::gpu) module for details.
+    fn on_gpu_adapter(&mut self, ctx: &mut DriverCtx<'_>, info: &GpuInfo) {}
+
+    #[allow(unused_variables)]
+    // reason: otherwise `error` would need to be named `_error` which behaves badly when using rust-analyzer to implement the trait
+    /// A hook which will be executed when the window couldn't be rendered.
+    ///
+    /// The errors are also logged. After a [fatal](RenderError::is_fatal) error, the event loop
+    /// exits once this returns, e.g. after the app has saved its state.
+    fn on_render_error(&mut self, ctx: &mut DriverCtx<'_>, error: &RenderError) {}
+
+    #[allow(unused_variables)]
+    // reason: otherwise `event` would need to be named `_event` which behaves badly when using rust-analyzer to implement the trait
+    /// A hook which will be executed when an item of the [tray](crate::tray) menu is selected.
+    #[cfg(feature = "tray")]
+    fn on_tray_menu(&mut self, ctx: &mut DriverCtx<'_>, event: crate::tray::TrayMenuEvent) {}
+
+    #[allow(unused_variables)]
+    // reason: otherwise `event` would need to be named `_event` which behaves badly when using rust-analyzer to implement the trait
+    /// A hook which will be executed when an item of the window [menu](crate::menu) is selected.
+    #[cfg(feature = "menu")]
+    fn on_menu(&mut self, ctx: &mut DriverCtx<'_>, event: crate::menu::MenuEvent) {}
 }
 
 impl DriverCtx<'_> {
@@ -34,6 +119,29 @@ impl DriverCtx<'_> {
         self.main_root_widget.downcast()
     }
 
+    /// Measure `text` as the widgets of the window would render it.
+    ///
+    /// See [`measure_text`] for details.
+    pub fn measure_text(
+        &mut self,
+        text: &str,
+        styles: impl IntoIterator<Item = StyleProperty>,
+        max_width: Option<f32>,
+    ) -> TextMetrics {
+        let (font_ctx, layout_ctx) = self.main_root_widget.ctx.text_contexts();
+        measure_text(font_ctx, layout_ctx, text, styles, max_width)
+    }
+
+    /// The [debug tag](crate::WidgetPod::set_debug_tag) of the widget `id`, if it has one.
+    pub fn widget_tag(&self, id: WidgetId) -> Option<Arc<str>> {
+        let ctx = &self.main_root_widget.ctx;
+        if ctx.widget_state.id == id {
+            return ctx.widget_state.debug_tag.clone();
+        }
+        let state = ctx.widget_state_children.reborrow().find(id)?;
+        state.item.debug_tag.clone()
+    }
+
     pub fn content_changed(&self) -> bool {
         let ctx = &self.main_root_widget.ctx;
         ctx.widget_state.needs_rewrite_passes()
@@ -41,3 +149,195 @@ impl DriverCtx<'_> {
             || ctx.global_state.focus_changed()
     }
 }
+
+// --- MARK: ACTION ROUTER ---
+
+/// Which widgets a route of an [`ActionRouter`] applies to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RouteKey {
+    /// The widget with this id.
+    Id(WidgetId),
+    /// The widgets with this [debug tag](crate::WidgetPod::set_debug_tag).
+    Tag(Arc<str>),
+}
+
+impl From<WidgetId> for RouteKey {
+    fn from(id: WidgetId) -> Self {
+        Self::Id(id)
+    }
+}
+
+impl From<&str> for RouteKey {
+    fn from(tag: &str) -> Self {
+        Self::Tag(tag.into())
+    }
+}
+
+type RouteHandler<State> =
+    Box<dyn FnMut(&mut State, &mut DriverCtx<'_>, WidgetId, Action) -> Result<(), Action>>;
+
+/// Dispatches the actions received by an [`AppDriver`] to handlers, keyed by the id or the
+/// [debug tag](crate::WidgetPod::set_debug_tag) of the widget which submitted them.
+///
+/// This saves drivers from matching on every widget id and downcasting
+/// [typed actions](Action::typed) by hand:
+///
+/// ```
+/// use masonry::{ActionRouter, WidgetId};
+///
+/// struct AppState {
+///     volume: f64,
+///     saved: bool,
+/// }
+///
+/// let slider_id = WidgetId::next();
+/// let mut router = ActionRouter::<AppState>::new();
+/// router
+///     .on_typed(slider_id, |state, _ctx, _id, volume: f64| state.volume = volume)
+///     .on("save_button", |state, _ctx, _id, _action| state.saved = true);
+/// ```
+///
+/// The driver then calls [`route`](Self::route) in [`AppDriver::on_action`].
+///
+/// `State` is the app state passed to the handlers.
+/// Routes are tried in the order they were added.
+pub struct ActionRouter<State = ()> {
+    routes: Vec<(RouteKey, RouteHandler<State>)>,
+}
+
+impl<State> Default for ActionRouter<State> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<State> ActionRouter<State> {
+    /// Create a router without routes.
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Handle all the actions of the widgets matching `key` with `handler`.
+    pub fn on(
+        &mut self,
+        key: impl Into<RouteKey>,
+        mut handler: impl FnMut(&mut State, &mut DriverCtx<'_>, WidgetId, Action) + 'static,
+    ) -> &mut Self {
+        self.routes.push((
+            key.into(),
+            Box::new(move |state, ctx, id, action| {
+                handler(state, ctx, id, action);
+                Ok(())
+            }),
+        ));
+        self
+    }
+
+    /// Handle the [typed actions](Action::typed) of type `T` of the widgets matching `key`
+    /// with `handler`.
+    ///
+    /// Actions of other types are left to the next routes.
+    pub fn on_typed<T: Any>(
+        &mut self,
+        key: impl Into<RouteKey>,
+        mut handler: impl FnMut(&mut State, &mut DriverCtx<'_>, WidgetId, T) + 'static,
+    ) -> &mut Self {
+        self.routes.push((
+            key.into(),
+            Box::new(move |state, ctx, id, action| {
+                handler(state, ctx, id, action.downcast::<T>()?);
+                Ok(())
+            }),
+        ));
+        self
+    }
+
+    /// Handle the actions declared by the widget type `W`, with [`ActionSource`],
+    /// of the widgets matching `key` with `handler`.
+    pub fn on_actions_of<W: ActionSource>(
+        &mut self,
+        key: impl Into<RouteKey>,
+        handler: impl FnMut(&mut State, &mut DriverCtx<'_>, WidgetId, W::Action) + 'static,
+    ) -> &mut Self {
+        self.on_typed::<W::Action>(key, handler)
+    }
+
+    /// Dispatch `action`, submitted by the widget `widget_id`, to the first matching route.
+    ///
+    /// Returns the action if no route handled it.
+    pub fn route(
+        &mut self,
+        state: &mut State,
+        ctx: &mut DriverCtx<'_>,
+        widget_id: WidgetId,
+        mut action: Action,
+    ) -> Result<(), Action> {
+        // The tag is only looked up if a route needs it, as this walks the widget tree.
+        let mut tag = None;
+        for (key, handler) in &mut self.routes {
+            let matches = match key {
+                RouteKey::Id(id) => *id == widget_id,
+                RouteKey::Tag(route_tag) => tag
+                    .get_or_insert_with(|| ctx.widget_tag(widget_id))
+                    .as_ref()
+                    .is_some_and(|tag| tag == route_tag),
+            };
+            if !matches {
+                continue;
+            }
+            match handler(state, ctx, widget_id, action) {
+                Ok(()) => return Ok(()),
+                Err(unhandled) => action = unhandled,
+            }
+        }
+        Err(action)
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Flex, Label, WidgetPod};
+
+    #[test]
+    fn route_actions_by_id_and_tag() {
+        let label_id = WidgetId::next();
+        let tagged_id = WidgetId::next();
+        let widget = Flex::column()
+            .with_child_pod(WidgetPod::new_with_id(Label::new("Id"), label_id).boxed())
+            .with_child_pod(
+                WidgetPod::new_with_id(Label::new("Tag"), tagged_id)
+                    .with_debug_tag("tagged")
+                    .boxed(),
+            );
+        let mut harness = TestHarness::create(widget);
+
+        let mut router = ActionRouter::<Vec<String>>::new();
+        router
+            .on_typed(label_id, |log, _, _, n: u32| log.push(format!("typed {n}")))
+            .on(label_id, |log, _, _, action| {
+                log.push(format!("{action:?}"))
+            })
+            .on("tagged", |log, _, _, _| log.push("tagged".into()));
+
+        let mut log = Vec::new();
+        harness.edit_root_widget(|root| {
+            let mut ctx = DriverCtx {
+                main_root_widget: root,
+            };
+            let mut route = |id, action| router.route(&mut log, &mut ctx, id, action);
+            assert_eq!(route(label_id, Action::typed(7_u32)), Ok(()));
+            // Actions of other types fall through to the next route
+            assert_eq!(route(label_id, Action::CheckboxChecked(true)), Ok(()));
+            assert_eq!(route(tagged_id, Action::typed(7_u32)), Ok(()));
+            let unrouted = WidgetId::next();
+            assert_eq!(
+                route(unrouted, Action::CheckboxChecked(true)),
+                Err(Action::CheckboxChecked(true))
+            );
+        });
+        assert_eq!(log, ["typed 7", "CheckboxChecked(true)", "tagged"]);
+    }
+}
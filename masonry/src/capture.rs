@@ -0,0 +1,358 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Capturing frames to disk, to debug rendering issues.
+//!
+//! A capture is a directory holding:
+//!
+//! - `frame.json`: a [`FrameCapture`], with the size of the frame, the GPU adapter which rendered
+//!   it, and the widget tree with the layout rects of the widgets.
+//! - `gpu.png`: the frame, as rendered by the adapter of the window.
+//! - `reference.png`: the same frame, as rendered by Vello's CPU implementation of its shaders.
+//!   This is what the GPU render should look like, so that a driver bug shows up as a
+//!   difference between the two.
+//! - `index.html`: a viewer which works in any web browser, without a server. It shows either
+//!   render or their difference, with the layout rects of the widgets over them.
+//!
+//! The Vello scene of the frame isn't written out: it references fonts and images which have no
+//! portable format, and its encoding changes between Vello versions. A capture therefore can't be
+//! re-rendered, only compared with the reference render.
+//!
+//! Frames are captured with [`MasonryState::capture_frame`]. If the `MASONRY_CAPTURE_DIR`
+//! environment variable is set, pressing <kbd>Ctrl</kbd>+<kbd>Shift</kbd>+<kbd>F12</kbd>
+//! also captures the next frame, to a new directory inside it. This lets users attach a
+//! capture to a bug report without the app having to expose a command for it.
+//!
+//! [`MasonryState::capture_frame`]: crate::event_loop_runner::MasonryState::capture_frame
+
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use vello::{AaSupport, RenderParams, Renderer, RendererOptions, Scene};
+use wgpu::{Device, Queue};
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::gpu::GpuInfo;
+use crate::headless::{encode_png, render_to_image};
+use crate::inspector::WidgetNode;
+
+/// The environment variable holding the directory the frames captured with the keyboard
+/// shortcut are written to.
+pub const CAPTURE_DIR_VAR: &str = "MASONRY_CAPTURE_DIR";
+
+/// The description of a captured frame, written to `frame.json`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FrameCapture {
+    /// The version of Masonry which captured the frame.
+    pub masonry_version: String,
+    /// The width of the renders, in physical pixels.
+    pub width: u32,
+    /// The height of the renders, in physical pixels.
+    pub height: u32,
+    /// The number of physical pixels per logical pixel.
+    pub scale_factor: f64,
+    /// The color drawn behind the widgets, as 8-bit RGBA.
+    pub background_color: [u8; 4],
+    /// The adapter which rendered `gpu.png`, if it's known.
+    pub adapter: Option<CapturedAdapter>,
+    /// The root widget and its descendants, with their layout rects in logical pixels.
+    pub root: WidgetNode,
+}
+
+/// The GPU adapter which rendered a captured frame.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CapturedAdapter {
+    /// The name of the adapter, e.g. `NVIDIA GeForce RTX 3060`.
+    pub name: String,
+    /// The PCI id of the vendor of the adapter, if it has one.
+    pub vendor: u32,
+    /// The PCI id of the adapter, if it has one.
+    pub device: u32,
+    /// E.g. `DiscreteGpu` or `Cpu`.
+    pub device_type: String,
+    /// The graphics API the adapter was used with, e.g. `Vulkan`.
+    pub backend: String,
+    /// The name of the driver, e.g. `NVIDIA` or `llvmpipe`, if the backend reports it.
+    pub driver: String,
+    /// The version of the driver, if the backend reports it.
+    pub driver_info: String,
+    /// Whether Vello ran its compute stages on the CPU.
+    pub software_rendering: bool,
+}
+
+impl From<&GpuInfo> for CapturedAdapter {
+    fn from(info: &GpuInfo) -> Self {
+        let adapter = &info.adapter;
+        Self {
+            name: adapter.name.clone(),
+            vendor: adapter.vendor,
+            device: adapter.device,
+            device_type: format!("{:?}", adapter.device_type),
+            backend: format!("{:?}", adapter.backend),
+            driver: adapter.driver.clone(),
+            driver_info: adapter.driver_info.clone(),
+            software_rendering: info.software_rendering,
+        }
+    }
+}
+
+/// An error which prevented a frame from being captured.
+#[derive(Debug)]
+pub enum CaptureError {
+    /// The capture couldn't be written.
+    Io(std::io::Error),
+    /// The scene couldn't be rendered.
+    Render(vello::Error),
+}
+
+impl std::fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "couldn't write the capture: {err}"),
+            Self::Render(err) => write!(f, "couldn't render the captured scene: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Render(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for CaptureError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<vello::Error> for CaptureError {
+    fn from(err: vello::Error) -> Self {
+        Self::Render(err)
+    }
+}
+
+/// The directory to capture the frame to, if `event` is the capture shortcut and
+/// [`CAPTURE_DIR_VAR`] is set.
+#[cfg_attr(
+    not(feature = "event_loop_runner"),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+pub(crate) fn shortcut_capture_dir(event: &KeyEvent, modifiers: ModifiersState) -> Option<PathBuf> {
+    let pressed = event.state == ElementState::Pressed
+        && !event.repeat
+        && event.logical_key == Key::Named(NamedKey::F12)
+        && modifiers.control_key()
+        && modifiers.shift_key();
+    if !pressed {
+        return None;
+    }
+    let dir = std::env::var_os(CAPTURE_DIR_VAR).filter(|dir| !dir.is_empty())?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    Some(Path::new(&dir).join(format!("frame-{millis}")))
+}
+
+/// Write the capture of a frame whose scene is `scene` to `dir`.
+///
+/// Only the renders of `scene` are written, see the [module level documentation](self).
+///
+/// `renderer` is the renderer of the window, which renders `gpu.png`.
+#[cfg_attr(
+    not(feature = "event_loop_runner"),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+pub(crate) fn write_capture(
+    dir: &Path,
+    device: &Device,
+    queue: &Queue,
+    renderer: &mut Renderer,
+    scene: &Scene,
+    render_params: &RenderParams,
+    capture: &FrameCapture,
+) -> Result<(), CaptureError> {
+    std::fs::create_dir_all(dir)?;
+
+    let gpu = render_to_image(device, queue, renderer, scene, render_params)?;
+    std::fs::write(dir.join("gpu.png"), encode_png(&gpu))?;
+
+    let mut reference_renderer = Renderer::new(
+        device,
+        RendererOptions {
+            surface_format: None,
+            use_cpu: true,
+            antialiasing_support: AaSupport::area_only(),
+            num_init_threads: NonZeroUsize::new(1),
+        },
+    )?;
+    let reference = render_to_image(device, queue, &mut reference_renderer, scene, render_params)?;
+    std::fs::write(dir.join("reference.png"), encode_png(&reference))?;
+
+    let json = serde_json::to_string_pretty(capture).expect("captures can always be serialized");
+    std::fs::write(dir.join("frame.json"), &json)?;
+    std::fs::write(dir.join("index.html"), viewer_html(&json))?;
+    Ok(())
+}
+
+/// The page of the viewer, with the capture inlined, as browsers don't let pages opened from
+/// the file system fetch other files.
+#[cfg_attr(
+    all(not(feature = "event_loop_runner"), not(test)),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+fn viewer_html(capture_json: &str) -> String {
+    // The JSON mustn't end the script it's in
+    VIEWER_HTML.replace("__CAPTURE__", &capture_json.replace("</", "<\\/"))
+}
+
+#[cfg_attr(
+    all(not(feature = "event_loop_runner"), not(test)),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+const VIEWER_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Masonry frame capture</title>
+<style>
+  body { display: flex; height: 100vh; margin: 0; font: 13px sans-serif; }
+  #frame { position: relative; flex: none; overflow: hidden; }
+  #frame > * { position: absolute; left: 0; top: 0; }
+  #reference.difference { mix-blend-mode: difference; }
+  #rects rect { fill: none; stroke: #f0f8; stroke-width: 0.5; }
+  #rects rect.selected { fill: #f0f4; stroke: #f0f; }
+  #side { flex: 1; overflow: auto; padding: 0 12px; border-left: 1px solid #888; }
+  #tree, #tree ul { list-style: none; padding-left: 12px; }
+  #tree li > span { cursor: default; }
+  #tree li > span.selected { background: #f0f4; }
+</style>
+</head>
+<body>
+<div id="frame">
+  <img id="gpu" src="gpu.png">
+  <img id="reference" src="reference.png" hidden>
+  <svg id="rects"></svg>
+</div>
+<div id="side">
+  <p>
+    <label><input type="radio" name="view" value="gpu" checked> GPU</label>
+    <label><input type="radio" name="view" value="reference"> Reference</label>
+    <label><input type="radio" name="view" value="difference"> Difference</label>
+    <label><input type="checkbox" id="show-rects" checked> Layout rects</label>
+  </p>
+  <pre id="info"></pre>
+  <ul id="tree"></ul>
+</div>
+<script>
+const CAPTURE = __CAPTURE__;
+const SVG = "http://www.w3.org/2000/svg";
+
+const frame = document.getElementById("frame");
+frame.style.width = CAPTURE.width + "px";
+frame.style.height = CAPTURE.height + "px";
+const rects = document.getElementById("rects");
+rects.setAttribute("width", CAPTURE.width);
+rects.setAttribute("height", CAPTURE.height);
+rects.setAttribute("viewBox", `0 0 ${CAPTURE.width / CAPTURE.scale_factor} ${CAPTURE.height / CAPTURE.scale_factor}`);
+
+const adapter = CAPTURE.adapter;
+document.getElementById("info").textContent = [
+  `Masonry ${CAPTURE.masonry_version}`,
+  `${CAPTURE.width}x${CAPTURE.height} pixels, scale factor ${CAPTURE.scale_factor}`,
+  adapter
+    ? `${adapter.name} (${adapter.device_type}, ${adapter.backend}${adapter.software_rendering ? ", software rendering" : ""})\n${adapter.driver} ${adapter.driver_info}`
+    : "Unknown adapter",
+].join("\n");
+
+let selected = [];
+function select(elements) {
+  for (const element of selected) element.classList.remove("selected");
+  selected = elements;
+  for (const element of selected) element.classList.add("selected");
+}
+
+function addNode(node, list) {
+  const [x0, y0, x1, y1] = node.layout_rect;
+  const rect = document.createElementNS(SVG, "rect");
+  rect.setAttribute("x", x0);
+  rect.setAttribute("y", y0);
+  rect.setAttribute("width", x1 - x0);
+  rect.setAttribute("height", y1 - y0);
+  rects.appendChild(rect);
+
+  const item = document.createElement("li");
+  const label = document.createElement("span");
+  label.textContent = `${node.type_name} #${node.id}`
+    + (node.debug_tag ? ` "${node.debug_tag}"` : "")
+    + (node.debug_text ? ` ${JSON.stringify(node.debug_text)}` : "")
+    + ` [${node.layout_rect.map((x) => Math.round(x * 100) / 100).join(", ")}]`;
+  label.addEventListener("mouseenter", () => select([label, rect]));
+  item.appendChild(label);
+  if (node.children.length > 0) {
+    const children = document.createElement("ul");
+    for (const child of node.children) addNode(child, children);
+    item.appendChild(children);
+  }
+  list.appendChild(item);
+}
+addNode(CAPTURE.root, document.getElementById("tree"));
+
+for (const input of document.querySelectorAll("input[name=view]")) {
+  input.addEventListener("change", () => {
+    const gpu = document.getElementById("gpu");
+    const reference = document.getElementById("reference");
+    gpu.hidden = input.value === "reference";
+    reference.hidden = input.value === "gpu";
+    reference.classList.toggle("difference", input.value === "difference");
+  });
+}
+document.getElementById("show-rects").addEventListener("change", (event) => {
+  rects.style.display = event.target.checked ? "" : "none";
+});
+</script>
+</body>
+</html>
+"#;
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Flex, Label};
+
+    #[test]
+    fn viewer_inlines_capture() {
+        let harness = TestHarness::create(
+            Flex::column()
+                .with_child(Label::new("</script>"))
+                .with_child(Label::new("Two")),
+        );
+        let capture = FrameCapture {
+            masonry_version: env!("CARGO_PKG_VERSION").into(),
+            width: 400,
+            height: 400,
+            scale_factor: 1.0,
+            background_color: [0, 0, 0, 255],
+            adapter: None,
+            root: WidgetNode::new(harness.root_widget()),
+        };
+        assert_eq!(capture.root.children.len(), 2);
+
+        let json = serde_json::to_string_pretty(&capture).unwrap();
+        let html = viewer_html(&json);
+        assert!(!html.contains("__CAPTURE__"));
+        // The label text doesn't end the script early
+        assert_eq!(html.matches("</script>").count(), 1);
+        let roundtrip: FrameCapture = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtrip, capture);
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed commands, which let distant widgets communicate without threading callbacks
+//! through their common ancestors.
+//!
+//! A widget sends a command with [`EventCtx::submit_command`], addressed to a [`CommandTarget`].
+//! Commands are routed by the update pass which follows, and delivered to the widgets they are
+//! addressed to as [`Update::Command`], in the order they were submitted.
+//!
+//! Widgets which handle a type of command wherever it's sent from, such as an editor handling
+//! the "save" command of a toolbar button, subscribe to it with
+//! [`EventCtx::subscribe_to_command`]. Commands sent to [`CommandTarget::Subscribers`] are
+//! delivered to each of them.
+//!
+//! [`EventCtx::submit_command`]: crate::EventCtx::submit_command
+//! [`EventCtx::subscribe_to_command`]: crate::EventCtx::subscribe_to_command
+//! [`Update::Command`]: crate::Update::Command
+
+use std::any::{Any, TypeId};
+use std::sync::Arc;
+
+use crate::WidgetId;
+
+/// Which widgets a [`Command`] is delivered to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommandTarget {
+    /// The widget with this id, if it's still in the tree.
+    Widget(WidgetId),
+    /// The focused widget, if there is one, after the focus changes requested
+    /// with the command have been applied.
+    Focused,
+    /// Every widget subscribed to the type of the command.
+    Subscribers,
+}
+
+/// A value sent between widgets, see the [module level documentation](self).
+#[derive(Clone)]
+pub struct Command {
+    sender: WidgetId,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+impl Command {
+    pub(crate) fn new(sender: WidgetId, value: impl Any + Send + Sync) -> Self {
+        Self {
+            sender,
+            value: Arc::new(value),
+        }
+    }
+
+    /// The widget which submitted this command.
+    pub fn sender(&self) -> WidgetId {
+        self.sender
+    }
+
+    /// Whether this command is of type `C`.
+    pub fn is<C: Any>(&self) -> bool {
+        self.value.is::<C>()
+    }
+
+    /// The value of this command, if it's of type `C`.
+    pub fn get<C: Any>(&self) -> Option<&C> {
+        self.value.downcast_ref()
+    }
+
+    /// The type of the value of this command.
+    pub(crate) fn type_id(&self) -> TypeId {
+        (*self.value).type_id()
+    }
+}
+
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Command")
+            .field("sender", &self.sender)
+            .finish_non_exhaustive()
+    }
+}
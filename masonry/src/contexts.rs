@@ -3,24 +3,41 @@
 
 //! The context types that are passed into various widget methods.
 
+use std::any::{Any, TypeId};
+use std::sync::Arc;
 use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
 use accesskit::TreeUpdate;
-use dpi::LogicalPosition;
+use dpi::{LogicalPosition, LogicalSize};
 use parley::{FontContext, LayoutContext};
 use tracing::{trace, warn};
 use tree_arena::{ArenaMutChildren, ArenaRefChildren};
 use vello::kurbo::Vec2;
 use vello::peniko::Color;
+use vello::Scene;
 use winit::window::ResizeDirection;
 
 use crate::action::Action;
+use crate::command::{Command, CommandTarget};
+use crate::monitor::{MonitorInfo, WindowPlacement};
 use crate::passes::layout::run_layout_on;
-use crate::render_root::{MutateCallback, RenderRootSignal, RenderRootState};
+use crate::popup::{PopupId, PopupRequest};
+use crate::render_root::{MutateCallback, RenderRootSignal, RenderRootState, Timer};
+use crate::resource_cache::ResourceCache;
 use crate::text::BrushIndex;
-use crate::theme::get_debug_color;
-use crate::widget::{WidgetMut, WidgetRef, WidgetState};
-use crate::{AllowRawMut, BoxConstraints, Insets, Point, Rect, Size, Widget, WidgetId, WidgetPod};
+use crate::theme::{get_debug_color, Density};
+use crate::widget::{
+    AccessOverrides, HitTest, ViewportCallback, Visibility, WidgetMut, WidgetPool, WidgetRef,
+    WidgetState,
+};
+use crate::{
+    AllowRawMut, BoxConstraints, Insets, Point, Rect, Size, TextEvent, TimerToken, Widget,
+    WidgetId, WidgetPod,
+};
 
 // Note - Most methods defined in this file revolve around `WidgetState` fields.
 // Consider reading `WidgetState` documentation (especially the documented naming scheme)
@@ -151,6 +168,51 @@ impl_context_method!(
             self.widget_state.id
         }
 
+        /// The string tag of the current widget, if it has one.
+        ///
+        /// See [`WidgetPod::set_debug_tag`].
+        pub fn debug_tag(&self) -> Option<&str> {
+            self.widget_state.debug_tag.as_deref()
+        }
+
+        /// The current time.
+        ///
+        /// Widgets should use this rather than [`Instant::now`], so that the passage of time
+        /// can be controlled in tests, e.g. with [`TestHarness::advance_time`].
+        ///
+        /// [`TestHarness::advance_time`]: crate::testing::TestHarness::advance_time
+        pub fn now(&self) -> Instant {
+            self.global_state.clock.now()
+        }
+
+        /// The monitors of the system.
+        ///
+        /// This is empty if the platform doesn't report monitors, or in tests.
+        pub fn monitors(&self) -> &[MonitorInfo] {
+            &self.global_state.monitors
+        }
+
+        /// Where the window is on the desktop, if known.
+        pub fn window_placement(&self) -> Option<&WindowPlacement> {
+            self.global_state.window_placement.as_ref()
+        }
+
+        /// How large built-in widgets and the paddings around them are.
+        ///
+        /// Widgets should scale their default sizes and hit targets with this. When it
+        /// changes, every widget is laid out again.
+        pub fn density(&self) -> Density {
+            self.global_state.density
+        }
+
+        /// The cache of decoded images and fonts shared by the widgets of the window.
+        ///
+        /// Widgets showing the same resource should load it from here, so that it's
+        /// only decoded once.
+        pub fn resource_cache(&self) -> &ResourceCache {
+            &self.global_state.resource_cache
+        }
+
         #[allow(dead_code)]
         /// Helper method to get a direct reference to a child widget from its `WidgetPod`.
         fn get_child<Child: Widget>(&self, child: &'_ WidgetPod<Child>) -> &'_ Child {
@@ -376,11 +438,33 @@ impl_context_method!(
             self.widget_state.has_focus
         }
 
+        /// The first widget of this subtree which can get text focus, in tree order.
+        ///
+        /// This is the widget to focus to move the focus "into" this widget, e.g. the text
+        /// input inside a labelled field.
+        pub fn first_focusable(&self) -> Option<WidgetId> {
+            self.widget_state.focus_chain.first().copied()
+        }
+
         /// Whether this widget gets pointer events and hovered status.
         pub fn accepts_pointer_interaction(&self) -> bool {
             self.widget_state.accepts_pointer_interaction
         }
 
+        /// Whether the pointer hits this widget, or passes through it.
+        ///
+        /// See [`HitTest`] for details.
+        pub fn hit_test(&self) -> HitTest {
+            self.widget_state.hit_test
+        }
+
+        /// Where this widget is stacked among its siblings.
+        ///
+        /// See [`WidgetPod::set_z_index`] for details.
+        pub fn z_index(&self) -> i32 {
+            self.widget_state.z_index
+        }
+
         /// Whether this widget gets text focus.
         pub fn accepts_focus(&self) -> bool {
             self.widget_state.accepts_focus
@@ -414,6 +498,19 @@ impl_context_method!(
         pub fn is_stashed(&self) -> bool {
             self.widget_state.is_stashed
         }
+
+        /// Whether this widget is shown, as set with [`set_visibility`].
+        ///
+        /// [`set_visibility`]: MutateCtx::set_visibility
+        pub fn visibility(&self) -> Visibility {
+            self.widget_state.visibility
+        }
+
+        /// Whether this widget or any of its ancestors is [hidden](Visibility::Hidden) or
+        /// [collapsed](Visibility::Collapsed).
+        pub fn is_hidden(&self) -> bool {
+            self.widget_state.is_hidden
+        }
     }
 );
 
@@ -473,6 +570,185 @@ impl MutateCtx<'_> {
     }
 }
 
+// --- MARK: ACCESS OVERRIDES ---
+impl MutateCtx<'_> {
+    /// The accessibility properties which override the ones set by this widget.
+    pub fn access_overrides(&self) -> &AccessOverrides {
+        &self.widget_state.access_overrides
+    }
+
+    /// Change the accessibility properties which override the ones set by this widget.
+    ///
+    /// This requests an [`accessibility`](crate::Widget::accessibility) pass.
+    pub fn access_overrides_mut(&mut self) -> &mut AccessOverrides {
+        self.request_accessibility_update();
+        &mut self.widget_state.access_overrides
+    }
+}
+
+// --- MARK: DEBUG TAG ---
+impl MutateCtx<'_> {
+    /// Change the string tag of this widget.
+    ///
+    /// See [`WidgetPod::set_debug_tag`].
+    pub fn set_debug_tag(&mut self, tag: Option<Arc<str>>) {
+        self.widget_state.debug_tag = tag;
+    }
+}
+
+// --- MARK: HIT TEST ---
+impl MutateCtx<'_> {
+    /// Set whether the pointer hits this widget, or passes through it to the widgets below.
+    ///
+    /// See [`WidgetPod::set_hit_test`].
+    pub fn set_hit_test(&mut self, mode: HitTest) {
+        self.widget_state.hit_test = mode;
+        self.global_state.needs_pointer_pass = true;
+    }
+}
+
+// --- MARK: VISIBILITY ---
+impl MutateCtx<'_> {
+    /// Set whether this widget is shown.
+    ///
+    /// See [`Visibility`] for details.
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        let previous = self.widget_state.visibility;
+        if previous == visibility {
+            return;
+        }
+        self.widget_state.visibility = visibility;
+        self.widget_state.needs_update_stashed = true;
+        if previous == Visibility::Collapsed || visibility == Visibility::Collapsed {
+            self.request_layout();
+        }
+        self.global_state.needs_pointer_pass = true;
+    }
+}
+
+// --- MARK: Z-INDEX ---
+impl_context_method!(MutateCtx<'_>, EventCtx<'_>, UpdateCtx<'_>, {
+    /// Set where this widget is stacked among its siblings, e.g. to raise a hovered card
+    /// above the other cards.
+    ///
+    /// See [`WidgetPod::set_z_index`].
+    pub fn set_z_index(&mut self, z_index: i32) {
+        if self.widget_state.z_index == z_index {
+            return;
+        }
+        self.widget_state.z_index = z_index;
+        // The parent appends the scenes of its children again, in the new order.
+        self.widget_state.needs_paint = true;
+        self.global_state.needs_pointer_pass = true;
+    }
+});
+
+// --- MARK: FOCUS ORDER ---
+impl MutateCtx<'_> {
+    /// Make this widget a focus group or not, overriding [`Widget::is_focus_group`].
+    pub fn set_focus_group(&mut self, is_focus_group: bool) {
+        self.widget_state.is_focus_group = Some(is_focus_group);
+    }
+
+    /// Set the position of this widget in the Tab order.
+    ///
+    /// Widgets with a tab index are focused before the others, in increasing order of index.
+    /// Widgets without one, or with the same one, are focused in tree order.
+    pub fn set_tab_index(&mut self, tab_index: Option<u32>) {
+        self.widget_state.tab_index = tab_index;
+    }
+
+    /// Request keyboard focus, e.g. when the app moves the focus to this widget.
+    ///
+    /// This behaves like [`EventCtx::request_focus`].
+    pub fn request_focus(&mut self) {
+        trace!("request_focus");
+        let id = self.widget_id();
+        self.global_state.next_focused_widget = Some(id);
+    }
+
+    /// Transfer focus to the widget with the given `WidgetId`.
+    ///
+    /// This behaves like [`EventCtx::set_focus`].
+    pub fn set_focus(&mut self, target: WidgetId) {
+        trace!("set_focus target={:?}", target);
+        self.global_state.next_focused_widget = Some(target);
+    }
+}
+
+// --- MARK: WIDGET POOL ---
+// Methods to reuse widget allocations and ids when children are replaced often.
+impl MutateCtx<'_> {
+    /// Remove a child from the tree, keeping its allocation and id for reuse.
+    ///
+    /// This behaves like [`remove_child`](Self::remove_child), but if the child has no children of
+    /// its own, the widget is kept in a pool from which [`take_recycled`](Self::take_recycled) can
+    /// return it. The ids of the child and its descendants are given to widget pods created by
+    /// [`new_pod`](Self::new_pod) once the next frame has been rendered.
+    ///
+    /// This is meant for containers with a lot of churn, such as virtual lists.
+    /// Don't use it for widgets whose id you keep around, e.g. from [`WidgetId::reserved`].
+    pub fn recycle_child(&mut self, child: WidgetPod<impl Widget>) {
+        let id = child.id();
+        let child_ref = self
+            .widget_children
+            .get_child(id)
+            .expect("recycle_child: child not found");
+        release_subtree_ids(
+            &mut self.global_state.widget_pool,
+            id,
+            &**child_ref.item,
+            child_ref.children,
+        );
+
+        let _ = self
+            .widget_state_children
+            .remove_child(id)
+            .expect("recycle_child: child not found");
+        let widget = self
+            .widget_children
+            .remove_child(id)
+            .expect("recycle_child: child not found");
+        self.global_state.scenes.remove(&id);
+        self.global_state.mirrored_scenes.remove(&id);
+        if widget.children_ids().is_empty() {
+            self.global_state.widget_pool.recycle_widget(widget);
+        }
+
+        self.children_changed();
+    }
+
+    /// Take a widget of type `W` previously passed to [`recycle_child`](Self::recycle_child), if any.
+    ///
+    /// The returned widget keeps the properties it had when it was recycled, and should be
+    /// updated before being added back to the tree.
+    pub fn take_recycled<W: Widget>(&mut self) -> Option<W> {
+        self.global_state.widget_pool.take_widget()
+    }
+
+    /// Create a new widget pod, reusing the id of a recycled widget if one is available.
+    ///
+    /// The pod must be added as a child of this widget, like any other new pod.
+    pub fn new_pod<W: Widget>(&mut self, widget: W) -> WidgetPod<W> {
+        WidgetPod::new_with_id(widget, self.global_state.widget_pool.next_id())
+    }
+}
+
+fn release_subtree_ids(
+    pool: &mut WidgetPool,
+    id: WidgetId,
+    widget: &dyn Widget,
+    children: ArenaRefChildren<'_, Box<dyn Widget>>,
+) {
+    pool.release_id(id);
+    for child_id in widget.children_ids() {
+        let Some(child) = children.get_child(child_id) else {
+            continue;
+        };
+        release_subtree_ids(pool, child_id, &**child.item, child.children);
+    }
+}
+
 // --- MARK: WIDGET_REF ---
 // Methods to get a child WidgetRef from a parent.
 impl<'w> QueryCtx<'w> {
@@ -549,11 +825,15 @@ impl_context_method!(MutateCtx<'_>, EventCtx<'_>, UpdateCtx<'_>, {
         self.widget_state.needs_layout = true;
     }
 
-    // TODO - Document better
     /// Request a [`compose`] pass.
     ///
     /// The compose pass is often cheaper than the layout pass, because it can only transform individual widgets' position.
+    /// Widgets which only move their children, such as a [`Portal`] being scrolled, should
+    /// request this instead of a layout: the children are then translated without being
+    /// laid out or painted again.
+    ///
     /// [`compose`]: crate::Widget::compose
+    /// [`Portal`]: crate::widget::Portal
     pub fn request_compose(&mut self) {
         trace!("request_compose");
         self.widget_state.needs_compose = true;
@@ -574,6 +854,7 @@ impl_context_method!(MutateCtx<'_>, EventCtx<'_>, UpdateCtx<'_>, {
         trace!("children_changed");
         self.widget_state.children_changed = true;
         self.widget_state.update_focus_chain = true;
+        self.widget_state.child_hit_index.take();
         self.request_layout();
     }
 
@@ -593,6 +874,7 @@ impl_context_method!(MutateCtx<'_>, EventCtx<'_>, UpdateCtx<'_>, {
             .remove_child(id)
             .expect("remove_child: child not found");
         self.global_state.scenes.remove(&child.id());
+        self.global_state.mirrored_scenes.remove(&id);
 
         self.children_changed();
     }
@@ -633,6 +915,26 @@ impl_context_method!(
             self.global_state.mutate_callbacks.push(callback);
         }
 
+        /// Queue a callback that will be called with a [`WidgetMut`] for this widget when the
+        /// event loop is idle.
+        ///
+        /// This is for expensive work which can be split into chunks, such as generating
+        /// thumbnails or indexing text: each chunk runs in its own callback, which queues
+        /// the next one, so that input keeps being handled between them.
+        /// The callbacks run in the order they were queued, for a limited time after each
+        /// batch of events. Callbacks of widgets which were removed are dropped.
+        pub fn schedule_idle(
+            &mut self,
+            f: impl FnOnce(WidgetMut<'_, Box<dyn Widget>>) + Send + 'static,
+        ) {
+            trace!("schedule_idle");
+            let callback = MutateCallback {
+                id: self.widget_state.id,
+                callback: Box::new(f),
+            };
+            self.global_state.idle_callbacks.push_back(callback);
+        }
+
         /// Queue a callback that will be called with a [`WidgetMut`] for the given child widget.
         ///
         /// The callbacks will be run in the order they were submitted during the mutate pass.
@@ -657,6 +959,14 @@ impl_context_method!(
                 .emit_signal(RenderRootSignal::Action(action, self.widget_state.id));
         }
 
+        /// Submit a [typed action](Action::typed), usually the [`ActionSource::Action`]
+        /// of this widget.
+        ///
+        /// [`ActionSource::Action`]: crate::ActionSource::Action
+        pub fn submit_typed_action<T: Any + Send>(&mut self, action: T) {
+            self.submit_action(Action::typed(action));
+        }
+
         /// Start a window drag.
         ///
         /// Moves the window with the left mouse button until the button is released.
@@ -701,6 +1011,43 @@ impl_context_method!(
                 .push_back(RenderRootSignal::Exit);
         }
 
+        /// Move and resize the window, e.g. to a position computed from [`monitors`](Self::monitors).
+        pub fn set_window_placement(&mut self, placement: WindowPlacement) {
+            trace!("set_window_placement");
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::SetWindowPlacement(placement));
+        }
+
+        /// Show `content` in a popup window of `size`, whose top-left corner is at `origin`
+        /// in this widget's coordinates.
+        ///
+        /// The popup can extend outside of the window. See the [`popup`](crate::popup) module
+        /// for details.
+        pub fn show_popup(&mut self, content: impl Widget, origin: Point, size: Size) -> PopupId {
+            trace!("show_popup");
+            let id = PopupId::next();
+            let position = self.widget_state.window_origin() + origin.to_vec2();
+            let request = PopupRequest {
+                content: Box::new(content),
+                position: LogicalPosition::new(position.x, position.y),
+                size: LogicalSize::new(size.width, size.height),
+                owner: self.widget_state.id,
+            };
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::ShowPopup(id, request));
+            id
+        }
+
+        /// Close a popup opened by [`show_popup`](Self::show_popup).
+        pub fn close_popup(&mut self, popup: PopupId) {
+            trace!("close_popup");
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::ClosePopup(popup));
+        }
+
         /// Show the window menu at a specified position.
         pub fn show_window_menu(&mut self, position: LogicalPosition<f64>) {
             trace!("show_window_menu");
@@ -709,12 +1056,88 @@ impl_context_method!(
                 .push_back(RenderRootSignal::ShowWindowMenu(position));
         }
 
-        /// Request a timer event.
+        /// Show `entries` in the menu bar of the window, replacing its current entries.
+        ///
+        /// See the [`menu`](crate::menu) module for details.
+        #[cfg(feature = "menu")]
+        pub fn set_menu(&mut self, entries: Vec<crate::menu::MenuEntry>) {
+            trace!("set_menu");
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::SetMenu(entries));
+        }
+
+        /// Set the callback rendering this widget's content, for a
+        /// [`RenderViewport`](crate::widget::RenderViewport).
+        pub(crate) fn set_viewport_callback(&mut self, callback: ViewportCallback) {
+            self.global_state
+                .viewport_callbacks
+                .insert(self.widget_state.id, callback);
+        }
+
+        /// Send a request to the native webview of this widget.
+        #[cfg(feature = "webview")]
+        pub(crate) fn request_web_view(&mut self, request: crate::widget::WebViewRequest) {
+            trace!("request_web_view");
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::WebView(self.widget_state.id, request));
+        }
+
+        /// Send a signal to parent widgets to scroll this area into view.
+        ///
+        /// `rect` is in local coordinates.
+        pub fn request_scroll_to(&mut self, rect: Rect) {
+            self.global_state
+                .scroll_request_targets
+                .push((self.widget_state.id, rect));
+        }
+
+        /// Request an [`Update::Timer`](crate::Update::Timer) event to be sent to this widget after `duration`.
         ///
         /// The return value is a token, which can be used to associate the
         /// request with the event.
-        pub fn request_timer(&mut self, _deadline: Duration) -> TimerToken {
-            todo!("request_timer");
+        ///
+        /// The event loop runner wakes up when the timer expires, so this can be used to
+        /// implement tooltips, caret blinking or auto-repeat without spawning threads.
+        /// Timers of widgets which were removed are dropped.
+        pub fn request_timer(&mut self, duration: Duration) -> TimerToken {
+            trace!("request_timer");
+            let token = TimerToken::next();
+            let deadline = self.global_state.clock.now() + duration;
+            self.global_state.timers.push(Timer {
+                deadline,
+                token,
+                widget: self.widget_state.id,
+            });
+            token
+        }
+
+        /// Send `command` to the widgets matching `target`.
+        ///
+        /// The command is delivered as [`Update::Command`](crate::Update::Command) in the update
+        /// pass following this one. See the [`command`](crate::command) module for details.
+        pub fn submit_command(&mut self, target: CommandTarget, command: impl Any + Send + Sync) {
+            trace!("submit_command");
+            let command = Command::new(self.widget_state.id, command);
+            self.global_state.commands.push((target, command));
+        }
+
+        /// Receive the commands of type `C` sent to [`CommandTarget::Subscribers`], until
+        /// [`unsubscribe_from_command`](Self::unsubscribe_from_command) is called.
+        pub fn subscribe_to_command<C: Any>(&mut self) {
+            let type_id = TypeId::of::<C>();
+            if !self.widget_state.command_subscriptions.contains(&type_id) {
+                self.widget_state.command_subscriptions.push(type_id);
+            }
+        }
+
+        /// Stop receiving the commands of type `C` sent to [`CommandTarget::Subscribers`].
+        pub fn unsubscribe_from_command<C: Any>(&mut self) {
+            let type_id = TypeId::of::<C>();
+            self.widget_state
+                .command_subscriptions
+                .retain(|subscription| *subscription != type_id);
         }
 
         /// Mark child widget as stashed.
@@ -734,12 +1157,37 @@ impl_context_method!(
                 child_state.is_explicitly_stashed = stashed;
             }
         }
+
+        /// Set the disabled state of a child widget.
+        ///
+        /// This is meant for state derived from the parent's own, e.g. a submit button which is
+        /// disabled until a form is complete. Like stashing, it can be set during layout.
+        ///
+        /// See [`set_disabled`](MutateCtx::set_disabled) for more information.
+        pub fn set_child_disabled(&mut self, child: &mut WidgetPod<impl Widget>, disabled: bool) {
+            let child_state = self.get_child_state_mut(child);
+            if child_state.is_explicitly_disabled != disabled {
+                child_state.needs_update_disabled = true;
+                child_state.is_explicitly_disabled = disabled;
+            }
+        }
+
+        /// Set the opacity with which a child widget and its descendants are painted,
+        /// between 0 (transparent) and 1 (opaque), e.g. to fade it in or out.
+        ///
+        /// This only changes how the scenes of the child are composed, so the child isn't painted again.
+        pub fn set_child_opacity(&mut self, child: &mut WidgetPod<impl Widget>, opacity: f64) {
+            let opacity = opacity.clamp(0., 1.);
+            let child_state = self.get_child_state_mut(child);
+            if child_state.opacity != opacity {
+                child_state.opacity = opacity;
+                child_state.needs_paint = true;
+                self.widget_state.needs_paint = true;
+            }
+        }
     }
 );
 
-// FIXME - Remove
-pub struct TimerToken;
-
 impl EventCtx<'_> {
     // TODO - clearly document all semantics of pointer capture when they've been decided on
     // TODO - Figure out cases where widget should be notified of pointer capture
@@ -796,15 +1244,6 @@ impl EventCtx<'_> {
             .push((self.widget_state.id, rect));
     }
 
-    /// Send a signal to parent widgets to scroll this area into view.
-    ///
-    /// `rect` is in local coordinates.
-    pub fn request_scroll_to(&mut self, rect: Rect) {
-        self.global_state
-            .scroll_request_targets
-            .push((self.widget_state.id, rect));
-    }
-
     /// Set the event as "handled", which stops its propagation to other
     /// widgets.
     pub fn set_handled(&mut self) {
@@ -866,6 +1305,17 @@ impl EventCtx<'_> {
             );
         }
     }
+
+    /// Send `event` to the focused widget once the current event is handled, as if it came
+    /// from the keyboard.
+    ///
+    /// This lets on-screen keyboards, like [`VirtualKeyboard`](crate::widget::VirtualKeyboard),
+    /// type into other widgets. The focus isn't moved, so a widget sending text events
+    /// shouldn't request focus itself.
+    pub fn submit_text_event(&mut self, event: TextEvent) {
+        trace!("submit_text_event {}", event.short_name());
+        self.global_state.synthesized_text_events.push_back(event);
+    }
 }
 
 impl RegisterCtx<'_> {
@@ -874,7 +1324,7 @@ impl RegisterCtx<'_> {
     /// Container widgets should call this on all their children in
     /// their implementation of [`Widget::register_children`].
     pub fn register_child(&mut self, child: &mut WidgetPod<impl Widget>) {
-        let Some(widget) = child.take_inner() else {
+        let Some((widget, settings)) = child.take_inner() else {
             return;
         };
 
@@ -884,7 +1334,12 @@ impl RegisterCtx<'_> {
         }
 
         let id = child.id();
-        let state = WidgetState::new(child.id(), widget.short_type_name());
+        let mut state = WidgetState::new(child.id(), widget.short_type_name());
+        state.access_overrides = settings.access_overrides;
+        state.debug_tag = settings.debug_tag;
+        state.hit_test = settings.hit_test;
+        state.z_index = settings.z_index;
+        state.visibility = settings.visibility;
 
         self.widget_children.insert_child(id, Box::new(widget));
         self.widget_state_children.insert_child(id, state);
@@ -1087,6 +1542,35 @@ impl LayoutCtx<'_> {
         self.widget_state.needs_paint = true;
     }
 
+    /// Set whether the [`compose`] method of this widget is called whenever the widget moves
+    /// in the window, e.g. because a [`Portal`] it's in was scrolled.
+    ///
+    /// This is for widgets which place their children relative to the visible area, such as
+    /// [`StickySection`], see [`ComposeCtx::ancestor_clip_rect`].
+    ///
+    /// [`compose`]: crate::Widget::compose
+    /// [`Portal`]: crate::widget::Portal
+    /// [`StickySection`]: crate::widget::StickySection
+    pub fn set_compose_on_move(&mut self, compose_on_move: bool) {
+        self.widget_state.compose_on_move = compose_on_move;
+    }
+
+    /// Keep a copy of the scene painted by the widget `target` and its descendants, for this
+    /// widget to paint again, e.g. as the thumbnail of a minimap.
+    ///
+    /// The copy is available with [`PaintCtx::mirrored_scene`], and this widget is painted
+    /// again whenever it changes. For the copy to be up to date, this widget should be painted
+    /// after `target`, e.g. by being a later sibling of one of its ancestors.
+    pub fn mirror_scene(&mut self, target: WidgetId) {
+        let id = self.widget_id();
+        let mirrored = self.global_state.mirrored_scenes.entry(target).or_default();
+        if !mirrored.mirrors.contains(&id) {
+            mirrored.mirrors.push(id);
+            self.widget_state.request_paint = true;
+            self.widget_state.needs_paint = true;
+        }
+    }
+
     /// Set the position of a child widget, in the parent's coordinate space.
     /// This will affect the parent's display rect.
     ///
@@ -1119,6 +1603,8 @@ impl LayoutCtx<'_> {
         }
         self.get_child_state_mut(child)
             .is_expecting_place_child_call = false;
+        // The child may have been resized, even if it didn't move.
+        self.widget_state.child_hit_index.take();
 
         self.widget_state.local_paint_rect = self
             .widget_state
@@ -1132,6 +1618,25 @@ impl ComposeCtx<'_> {
         self.widget_state.needs_compose
     }
 
+    /// The area of the window in which this widget can be visible, in window coordinates.
+    ///
+    /// This is the intersection of the clip paths of the ancestors of this widget, such as the
+    /// viewport of a [`Portal`](crate::widget::Portal) it's in, or `None` if they have none.
+    pub fn ancestor_clip_rect(&self) -> Option<Rect> {
+        self.widget_state.ancestor_clip
+    }
+
+    /// Request a [`paint`](crate::Widget::paint) pass.
+    ///
+    /// This is for widgets which only paint the part of themselves within
+    /// [`ancestor_clip_rect`](Self::ancestor_clip_rect), and must be painted again when it
+    /// shows another part of them.
+    pub fn request_paint_only(&mut self) {
+        trace!("request_paint_only");
+        self.widget_state.request_paint = true;
+        self.widget_state.needs_paint = true;
+    }
+
     /// Set a translation for the child widget.
     ///
     /// The translation is applied on top of the position from [`LayoutCtx::place_child`].
@@ -1157,6 +1662,7 @@ impl ComposeCtx<'_> {
         if translation != child.translation {
             child.translation = translation;
             child.translation_changed = true;
+            self.widget_state.child_hit_index.take();
         }
     }
 }
@@ -1182,6 +1688,15 @@ impl PaintCtx<'_> {
     pub fn debug_color(&self) -> Color {
         get_debug_color(self.widget_id().to_raw())
     }
+
+    /// The scene of `target` and its descendants registered with [`LayoutCtx::mirror_scene`],
+    /// and the window origin of `target` when it was painted.
+    ///
+    /// The scene is in window coordinates. This returns `None` if `target` wasn't painted yet.
+    pub fn mirrored_scene(&self, target: WidgetId) -> Option<(&Scene, Point)> {
+        let mirrored = self.global_state.mirrored_scenes.get(&target)?;
+        Some((&mirrored.scene, mirrored.origin?))
+    }
 }
 
 // --- MARK: RAW WRAPPERS ---
@@ -0,0 +1,400 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Embedding Masonry in an application which owns the GPU device and the event loop.
+//!
+//! Games and CAD apps which already render with wgpu can use an [`EmbeddedUi`] to draw
+//! widgets as an overlay. The host application:
+//!
+//! - Creates the `EmbeddedUi` with its own [`wgpu::Device`].
+//! - Feeds it input through [`render_root`](EmbeddedUi::render_root), e.g. with
+//!   [`RenderRoot::handle_pointer_event`], translating from its own event types.
+//! - Calls [`handle_signals`](EmbeddedUi::handle_signals) after each input, to route actions
+//!   to its [`AppDriver`] and learn whether the UI needs to be redrawn.
+//! - Calls [`render_to_texture`](EmbeddedUi::render_to_texture), then composites the texture
+//!   over its own rendering, e.g. with an alpha-blended fullscreen quad.
+//!
+//! Hosts which run the UI as an app, rather than as an overlay, can implement
+//! [`AppHost`](crate::host::AppHost) on top of an `EmbeddedUi`: they call
+//! [`AppDriver::on_host_start`] once it's created, and deliver the actions sent through their
+//! proxy with [`handle_action`](EmbeddedUi::handle_action).
+//!
+//! A Xilem app can be embedded by passing the root widget and driver returned by its
+//! `into_driver` method.
+//!
+//! Hosts which don't use wgpu, such as Qt apps or audio plugins, can instead give Masonry
+//! a native window or child view to render into, with an [`EmbeddedWindow`].
+
+#![allow(
+    unsafe_code,
+    reason = "Native window handles from the host can only be trusted by the caller"
+)]
+
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use accesskit::TreeUpdate;
+use vello::kurbo::Affine;
+use vello::util::{RenderContext, RenderSurface};
+use vello::{RenderParams, RendererOptions, Scene};
+use wgpu::{Device, PresentMode, Queue, SurfaceError, TextureFormat, TextureView};
+use winit::raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, RawDisplayHandle,
+    RawWindowHandle, WindowHandle,
+};
+
+use crate::dpi::PhysicalSize;
+use crate::event::WindowEvent;
+use crate::render_root::{RenderRoot, RenderRootOptions, RenderRootSignal, WindowSizePolicy};
+use crate::{Action, AppDriver, Color, DriverCtx, Widget, WidgetId};
+
+/// The settings of an [`EmbeddedUi`].
+#[derive(Clone, Debug)]
+pub struct EmbedOptions {
+    /// The size of the texture the widgets are rendered to.
+    pub size: PhysicalSize<u32>,
+    /// The number of texture pixels per logical pixel.
+    pub scale_factor: f64,
+    /// The color drawn behind the widgets, which is transparent by default.
+    pub background_color: Color,
+    /// Whether fonts installed on the system can be used, as well as the registered fonts.
+    pub use_system_fonts: bool,
+}
+
+impl Default for EmbedOptions {
+    fn default() -> Self {
+        Self {
+            size: PhysicalSize::new(800, 600),
+            scale_factor: 1.0,
+            background_color: Color::TRANSPARENT,
+            use_system_fonts: true,
+        }
+    }
+}
+
+/// Widgets driven and rendered by a host application.
+///
+/// See the [module docs](self) for how to use it.
+pub struct EmbeddedUi {
+    render_root: RenderRoot,
+    renderer: vello::Renderer,
+    background_color: Color,
+}
+
+impl EmbeddedUi {
+    /// Create the UI for `root_widget`, rendering with the host's `device`.
+    pub fn new(
+        device: &Device,
+        root_widget: impl Widget,
+        options: &EmbedOptions,
+    ) -> Result<Self, vello::Error> {
+        Self::with_surface_format(device, root_widget, options, None, false)
+    }
+
+    fn with_surface_format(
+        device: &Device,
+        root_widget: impl Widget,
+        options: &EmbedOptions,
+        surface_format: Option<TextureFormat>,
+        use_cpu: bool,
+    ) -> Result<Self, vello::Error> {
+        let renderer = vello::Renderer::new(
+            device,
+            RendererOptions {
+                surface_format,
+                use_cpu,
+                num_init_threads: NonZeroUsize::new(1),
+                antialiasing_support: vello::AaSupport::area_only(),
+            },
+        )?;
+        let mut render_root = RenderRoot::new(
+            root_widget,
+            RenderRootOptions {
+                use_system_fonts: options.use_system_fonts,
+                size_policy: WindowSizePolicy::User,
+                scale_factor: options.scale_factor,
+                test_font: None,
+            },
+        );
+        render_root.handle_window_event(WindowEvent::Resize(options.size));
+        Ok(Self {
+            render_root,
+            renderer,
+            background_color: options.background_color,
+        })
+    }
+
+    /// The widgets, which the host sends input events to.
+    pub fn render_root(&mut self) -> &mut RenderRoot {
+        &mut self.render_root
+    }
+
+    /// Change the size of the texture the widgets are rendered to, and its scale factor.
+    pub fn resize(&mut self, size: PhysicalSize<u32>, scale_factor: f64) {
+        if scale_factor != self.render_root.scale_factor {
+            self.render_root
+                .handle_window_event(WindowEvent::Rescale(scale_factor));
+        }
+        if size != self.render_root.size {
+            self.render_root
+                .handle_window_event(WindowEvent::Resize(size));
+        }
+    }
+
+    /// Change the color drawn behind the widgets.
+    pub fn set_background_color(&mut self, color: Color) {
+        self.background_color = color;
+    }
+
+    /// Route the actions emitted by widgets to `app_driver`.
+    ///
+    /// Returns the other signals emitted since the last call, which the host handles as it
+    /// sees fit. For instance, [`RenderRootSignal::RequestRedraw`] means that
+    /// [`render_to_texture`](Self::render_to_texture) should be called again, and
+    /// [`RenderRootSignal::SetCursor`] that the cursor icon changed.
+    pub fn handle_signals(&mut self, app_driver: &mut dyn AppDriver) -> Vec<RenderRootSignal> {
+        let mut signals = Vec::new();
+        while let Some(signal) = self.render_root.pop_signal() {
+            match signal {
+                RenderRootSignal::Action(action, widget_id) => {
+                    self.handle_action(app_driver, widget_id, action);
+                }
+                signal => signals.push(signal),
+            }
+        }
+        signals
+    }
+
+    /// Route `action` to `app_driver`, as if it had been emitted by the widget `widget_id`.
+    ///
+    /// Hosts use this to deliver the actions sent through their [`HostProxy`](crate::host::HostProxy).
+    pub fn handle_action(
+        &mut self,
+        app_driver: &mut dyn AppDriver,
+        widget_id: WidgetId,
+        action: Action,
+    ) {
+        self.render_root.edit_root_widget(|root| {
+            let mut ctx = DriverCtx {
+                main_root_widget: root,
+            };
+            app_driver.on_action(&mut ctx, widget_id, action);
+        });
+    }
+
+    /// Paint the widgets and render them to `texture`, which must have the current size of
+    /// the UI, as set with [`resize`](Self::resize).
+    ///
+    /// The texture must have the [`Rgba8Unorm`](wgpu::TextureFormat::Rgba8Unorm) format and
+    /// the [`STORAGE_BINDING`](wgpu::TextureUsages::STORAGE_BINDING) usage, as Vello renders
+    /// with compute shaders. Its colors aren't premultiplied by alpha.
+    ///
+    /// Returns the changes to the accessibility tree, for hosts which use AccessKit.
+    pub fn render_to_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        texture: &TextureView,
+    ) -> Result<TreeUpdate, vello::Error> {
+        let (scene, tree_update) = self.paint();
+        self.renderer
+            .render_to_texture(device, queue, &scene, texture, &self.render_params())?;
+        Ok(tree_update)
+    }
+
+    /// Paint the widgets, scaled to texture pixels.
+    fn paint(&mut self) -> (Arc<Scene>, TreeUpdate) {
+        let (scene, tree_update) = self.render_root.redraw();
+        let scale_factor = self.render_root.scale_factor;
+        if scale_factor == 1.0 {
+            (scene, tree_update)
+        } else {
+            let mut scaled = Scene::new();
+            scaled.append(&scene, Some(Affine::scale(scale_factor)));
+            (Arc::new(scaled), tree_update)
+        }
+    }
+
+    fn render_params(&self) -> RenderParams {
+        let PhysicalSize { width, height } = self.render_root.size;
+        RenderParams {
+            base_color: self.background_color,
+            width,
+            height,
+            antialiasing_method: vello::AaConfig::Area,
+        }
+    }
+}
+
+// --- MARK: NATIVE WINDOWS ---
+
+/// The raw handles of a native window or child view owned by the host, such as a Qt widget
+/// or the editor window of an audio plugin.
+#[derive(Clone, Copy, Debug)]
+pub struct NativeWindowHandle {
+    window: RawWindowHandle,
+    display: RawDisplayHandle,
+}
+
+impl NativeWindowHandle {
+    /// Wrap the raw handles of a native window.
+    ///
+    /// # Safety
+    ///
+    /// The handles must be valid, and stay valid until the [`EmbeddedWindow`] created with
+    /// them is dropped.
+    pub unsafe fn new(window: RawWindowHandle, display: RawDisplayHandle) -> Self {
+        Self { window, display }
+    }
+}
+
+// SAFETY: wgpu requires window handles to be `Send` and `Sync`, but only uses them to create
+// the surface. `EmbeddedWindow` is documented to be used from the thread owning the window.
+unsafe impl Send for NativeWindowHandle {}
+// SAFETY: See above.
+unsafe impl Sync for NativeWindowHandle {}
+
+impl HasWindowHandle for NativeWindowHandle {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        // SAFETY: The handle is valid for as long as it's used, as required by `Self::new`.
+        Ok(unsafe { WindowHandle::borrow_raw(self.window) })
+    }
+}
+
+impl HasDisplayHandle for NativeWindowHandle {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        // SAFETY: The handle is valid for as long as it's used, as required by `Self::new`.
+        Ok(unsafe { DisplayHandle::borrow_raw(self.display) })
+    }
+}
+
+/// An error from an [`EmbeddedWindow`].
+#[derive(Debug)]
+pub enum EmbedError {
+    /// Vello failed to create the surface or its renderer, or to render the scene.
+    Render(vello::Error),
+    /// The next texture of the window's surface couldn't be acquired.
+    Surface(SurfaceError),
+}
+
+impl std::fmt::Display for EmbedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Render(err) => write!(f, "rendering failed: {err}"),
+            Self::Surface(err) => write!(f, "failed to acquire the surface texture: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for EmbedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Render(err) => Some(err),
+            Self::Surface(err) => Some(err),
+        }
+    }
+}
+
+impl From<vello::Error> for EmbedError {
+    fn from(err: vello::Error) -> Self {
+        Self::Render(err)
+    }
+}
+
+impl From<SurfaceError> for EmbedError {
+    fn from(err: SurfaceError) -> Self {
+        Self::Surface(err)
+    }
+}
+
+/// Widgets rendered into a native window owned by the host.
+///
+/// The host forwards the input events of its window through [`ui`](Self::ui), and calls
+/// [`render`](Self::render) when the window needs to be repainted, e.g. after a
+/// [`RenderRootSignal::RequestRedraw`]. It must be used from the thread which owns the window.
+pub struct EmbeddedWindow {
+    ui: EmbeddedUi,
+    context: RenderContext,
+    surface: RenderSurface<'static>,
+}
+
+impl EmbeddedWindow {
+    /// Create a surface for `window` of `options.size`, and the UI for `root_widget`.
+    ///
+    /// This uses the first GPU device compatible with the window, or a software adapter if
+    /// there is none. It blocks while the device is requested, so isn't supported on the web.
+    pub fn new(
+        window: NativeWindowHandle,
+        root_widget: impl Widget,
+        options: &EmbedOptions,
+    ) -> Result<Self, EmbedError> {
+        let create_surface = |context: &mut RenderContext| {
+            pollster::block_on(context.create_surface(
+                window,
+                options.size.width,
+                options.size.height,
+                PresentMode::AutoVsync,
+            ))
+        };
+        let mut software_rendering = software_render::is_forced();
+        let mut context = if software_rendering {
+            software_render::render_context()
+        } else {
+            RenderContext::new()
+        };
+        let mut surface = create_surface(&mut context);
+        if surface.is_err() && !software_rendering {
+            context = software_render::render_context();
+            software_rendering = true;
+            surface = create_surface(&mut context);
+        }
+        let surface = surface?;
+        let device = &context.devices[surface.dev_id].device;
+        let ui = EmbeddedUi::with_surface_format(
+            device,
+            root_widget,
+            options,
+            Some(surface.format),
+            software_rendering,
+        )?;
+        Ok(Self {
+            ui,
+            context,
+            surface,
+        })
+    }
+
+    /// The UI, which the host sends input events to.
+    pub fn ui(&mut self) -> &mut EmbeddedUi {
+        &mut self.ui
+    }
+
+    /// Resize the surface and the UI, after the host resized the window.
+    pub fn resize(&mut self, size: PhysicalSize<u32>, scale_factor: f64) {
+        self.ui.resize(size, scale_factor);
+        if self.surface.config.width != size.width || self.surface.config.height != size.height {
+            self.context
+                .resize_surface(&mut self.surface, size.width, size.height);
+        }
+    }
+
+    /// Paint the widgets and present them in the window.
+    ///
+    /// Returns the changes to the accessibility tree, for hosts which use AccessKit.
+    pub fn render(&mut self) -> Result<TreeUpdate, EmbedError> {
+        let surface_texture = self.surface.surface.get_current_texture()?;
+        let device_handle = &self.context.devices[self.surface.dev_id];
+        let (scene, tree_update) = self.ui.paint();
+        let render_params = self.ui.render_params();
+        self.ui.renderer.render_to_surface(
+            &device_handle.device,
+            &device_handle.queue,
+            &scene,
+            &surface_texture,
+            &render_params,
+        )?;
+        surface_texture.present();
+        device_handle.device.poll(wgpu::Maintain::Poll);
+        Ok(tree_update)
+    }
+}
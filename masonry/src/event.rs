@@ -3,13 +3,17 @@
 
 //! Events.
 
+use std::num::NonZeroU64;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 
+use serde::{Deserialize, Serialize};
 use winit::event::{Force, Ime, KeyEvent, Modifiers};
-use winit::keyboard::ModifiersState;
+use winit::keyboard::{Key, ModifiersState};
 
+use crate::command::Command;
 use crate::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
-use crate::kurbo::Rect;
+use crate::kurbo::{Insets, Rect, Vec2};
 
 // TODO - Occluded(bool) event
 // TODO - winit ActivationTokenDone thing
@@ -23,12 +27,17 @@ use crate::kurbo::Rect;
 pub enum WindowEvent {
     Rescale(f64),
     Resize(PhysicalSize<u32>),
+    /// The parts of the window covered by system UI, such as a soft keyboard or status bar,
+    /// in logical pixels.
+    ///
+    /// The root widget is laid out in the rest of the window.
+    SetInsets(Insets),
     AnimFrame,
     RebuildAccessTree,
 }
 
 /// An indicator of which pointer button was pressed.
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum PointerButton {
     /// No mouse button.
@@ -189,18 +198,47 @@ pub enum PointerEvent {
     PointerMove(PointerState),
     PointerEnter(PointerState),
     PointerLeave(PointerState),
-    MouseWheel(LogicalPosition<f64>, PointerState),
+    MouseWheel(ScrollDelta, PointerState),
     HoverFile(PathBuf, PointerState),
     DropFile(PathBuf, PointerState),
     HoverFileCancel(PointerState),
     Pinch(f64, PointerState),
 }
 
+/// The distance scrolled by a [`PointerEvent::MouseWheel`] event.
+///
+/// Positive values scroll up and left, which moves the content down and right.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollDelta {
+    /// A number of lines, as reported for the ticks of most mouse wheels.
+    ///
+    /// A tick is usually one line vertically, and one column horizontally.
+    Lines(Vec2),
+    /// A distance in logical pixels, as reported by trackpads and smooth-scrolling wheels.
+    Pixels(Vec2),
+}
+
+impl ScrollDelta {
+    /// The scrolled distance in logical pixels, with `line_height` pixels per line.
+    pub fn to_pixels(self, line_height: f64) -> Vec2 {
+        match self {
+            Self::Lines(lines) => lines * line_height,
+            Self::Pixels(pixels) => pixels,
+        }
+    }
+}
+
 // TODO - Clipboard Paste?
 // TODO skip is_synthetic=true events
 #[derive(Debug, Clone)]
 pub enum TextEvent {
     KeyboardKey(KeyEvent, ModifiersState),
+    /// A key pressed on an on-screen keyboard, such as a
+    /// [`VirtualKeyboard`](crate::widget::VirtualKeyboard).
+    ///
+    /// Unlike [`KeyboardKey`](Self::KeyboardKey), there is no release, and the key doesn't
+    /// move the focus. See [`pressed_key`](Self::pressed_key) to handle both alike.
+    VirtualKey(Key, ModifiersState),
     Ime(Ime),
     ModifierChange(ModifiersState),
     // TODO - Document difference with Update focus change
@@ -310,6 +348,36 @@ pub enum Update {
 
     /// Called when a widget becomes or no longer is parent of a focused widget.
     ChildFocusChanged(bool),
+
+    /// Called when a timer requested with [`EventCtx::request_timer`] expires.
+    ///
+    /// The token is the one returned when the timer was requested.
+    ///
+    /// [`EventCtx::request_timer`]: crate::EventCtx::request_timer
+    Timer(TimerToken),
+
+    /// Called when a command submitted with [`EventCtx::submit_command`] is delivered to this
+    /// widget.
+    ///
+    /// See the [`command`](crate::command) module for how commands are routed.
+    ///
+    /// [`EventCtx::submit_command`]: crate::EventCtx::submit_command
+    Command(Command),
+}
+
+/// The identity of a timer, returned by [`EventCtx::request_timer`].
+///
+/// [`EventCtx::request_timer`]: crate::EventCtx::request_timer
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct TimerToken(NonZeroU64);
+
+impl TimerToken {
+    /// Allocate a new, unique `TimerToken`.
+    pub(crate) fn next() -> Self {
+        static TIMER_TOKEN_COUNTER: AtomicU64 = AtomicU64::new(1);
+        let id = TIMER_TOKEN_COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self(id.try_into().unwrap())
+    }
 }
 
 impl PointerEvent {
@@ -386,6 +454,7 @@ impl TextEvent {
         match self {
             TextEvent::KeyboardKey(KeyEvent { repeat: true, .. }, _) => "KeyboardKey (repeat)",
             TextEvent::KeyboardKey(_, _) => "KeyboardKey",
+            TextEvent::VirtualKey(_, _) => "VirtualKey",
             TextEvent::Ime(Ime::Disabled) => "Ime::Disabled",
             TextEvent::Ime(Ime::Enabled) => "Ime::Enabled",
             TextEvent::Ime(Ime::Commit(_)) => "Ime::Commit",
@@ -399,12 +468,33 @@ impl TextEvent {
     pub fn is_high_density(&self) -> bool {
         match self {
             TextEvent::KeyboardKey(_, _) => false,
+            TextEvent::VirtualKey(_, _) => false,
             TextEvent::Ime(_) => false,
             // Basically every mouse click/scroll event seems to produce a modifier change event.
             TextEvent::ModifierChange(_) => true,
             TextEvent::FocusChange(_) => false,
         }
     }
+
+    /// The key pressed, the text it types and the modifiers held, for a key press from
+    /// the keyboard or an on-screen keyboard.
+    pub fn pressed_key(&self) -> Option<(&Key, Option<&str>, ModifiersState)> {
+        match self {
+            TextEvent::KeyboardKey(key_event, mods) if key_event.state.is_pressed() => Some((
+                &key_event.logical_key,
+                key_event.text.as_ref().map(|text| text.as_str()),
+                *mods,
+            )),
+            TextEvent::VirtualKey(key, mods) => {
+                let text = match key {
+                    Key::Character(text) => Some(text.as_str()),
+                    _ => None,
+                };
+                Some((key, text, *mods))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl AccessEvent {
@@ -476,6 +566,8 @@ impl Update {
             Update::HoveredChanged(_) => "HoveredChanged",
             Update::FocusChanged(_) => "FocusChanged",
             Update::ChildFocusChanged(_) => "ChildFocusChanged",
+            Update::Timer(_) => "Timer",
+            Update::Command(_) => "Command",
         }
     }
 }
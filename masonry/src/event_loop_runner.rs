@@ -1,36 +1,71 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(target_arch = "wasm32")]
+use std::cell::RefCell;
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
+#[cfg(target_arch = "wasm32")]
+use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
 use accesskit_winit::Adapter;
-use tracing::{debug, info_span, warn};
-use vello::kurbo::Affine;
+use tracing::{debug, info, info_span, warn};
+use vello::kurbo::{Affine, Vec2};
 use vello::peniko::Color;
-use vello::util::{RenderContext, RenderSurface};
 use vello::{AaSupport, RenderParams, Renderer, RendererOptions, Scene};
-use wgpu::PresentMode;
 use winit::application::ApplicationHandler;
 use winit::error::EventLoopError;
 use winit::event::{
     DeviceEvent as WinitDeviceEvent, DeviceId, MouseButton as WinitMouseButton,
     WindowEvent as WinitWindowEvent,
 };
-use winit::event_loop::ActiveEventLoop;
-use winit::window::{Window, WindowAttributes, WindowId};
+use winit::event_loop::{ActiveEventLoop, ControlFlow};
+use winit::keyboard::{ModifiersState, PhysicalKey};
+use winit::window::{CursorIcon, ResizeDirection, Window, WindowAttributes, WindowId};
 
 use crate::app_driver::{AppDriver, DriverCtx};
-use crate::dpi::LogicalPosition;
-use crate::event::{PointerButton, PointerState, WindowEvent};
+use crate::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
+use crate::event::{PointerButton, PointerState, ScrollDelta, WindowEvent};
+use crate::frame_pacing::{FramePacing, FrameScheduler, FrameStats};
+use crate::gpu::{GpuInfo, GpuOptions, RenderError};
+use crate::host::{AppHost, HostProxy};
+#[cfg(feature = "inspector")]
+use crate::inspector::{InspectorServer, WidgetNode, DEFAULT_INSPECTOR_ADDR, INSPECTOR_ADDR_VAR};
+use crate::monitor::{monitor_infos, MonitorInfo, WindowPlacement};
+use crate::passes::event::run_focus_navigation;
+use crate::popup_windows::PopupWindows;
+use crate::render_context::{RenderContext, RenderSurface};
 use crate::render_root::{self, RenderRoot, WindowSizePolicy};
-use crate::{PointerEvent, TextEvent, Widget, WidgetId};
+use crate::replay::{
+    ImeEvent, Recorder, Replayer, Session, SessionEvent, TouchPhase, RECORD_SESSION_VAR,
+    REPLAY_SESSION_VAR,
+};
+use crate::viewport_compositor::ViewportCompositor;
+#[cfg(feature = "webview")]
+use crate::web_views::WebViews;
+use crate::{Action, PointerEvent, TextEvent, Widget, WidgetId};
 
 #[derive(Debug)]
 pub enum MasonryUserEvent {
     AccessKit(accesskit_winit::Event),
     // TODO: A more considered design here
     Action(crate::Action, WidgetId),
+    /// The render surface of the window has been created, on the web.
+    #[cfg(target_arch = "wasm32")]
+    SurfaceReady,
+    /// An item of the tray menu was selected.
+    #[cfg(feature = "tray")]
+    TrayMenu(crate::tray::TrayMenuEvent),
+    /// An item of the menu bar of the window was selected.
+    #[cfg(feature = "menu")]
+    Menu(crate::menu::MenuEvent),
 }
 
 impl From<accesskit_winit::Event> for MasonryUserEvent {
@@ -55,6 +90,16 @@ impl From<WinitMouseButton> for PointerButton {
     }
 }
 
+/// The state of the tray icon of the app.
+#[cfg(feature = "tray")]
+enum TrayState {
+    None,
+    /// The icon was set before the event loop started.
+    Pending(crate::tray::TrayOptions),
+    // Dropping the icon removes it from the tray
+    Created(tray_icon::TrayIcon),
+}
+
 pub enum WindowState<'a> {
     Uninitialized(WindowAttributes),
     Rendering {
@@ -66,6 +111,12 @@ pub enum WindowState<'a> {
         window: Arc<Window>,
         accesskit_adapter: Adapter,
     },
+    /// The render surface of the window is being created, which is asynchronous on the web.
+    #[cfg(target_arch = "wasm32")]
+    CreatingSurface {
+        window: Arc<Window>,
+        accesskit_adapter: Adapter,
+    },
 }
 
 /// The state of the Masonry application. If you run Masonry from an external Winit event loop, create a
@@ -75,12 +126,55 @@ pub struct MasonryState<'a> {
     render_cx: RenderContext,
     render_root: RenderRoot,
     pointer_state: PointerState,
+    /// The edge of the undecorated window the pointer is over, from which it can be resized.
+    resize_edge: Option<ResizeDirection>,
+    popups: PopupWindows,
+    /// The placement set before the window was created.
+    pending_placement: Option<WindowPlacement>,
     renderer: Option<Renderer>,
+    viewport_compositor: Option<ViewportCompositor>,
+    /// Whether `render_cx` is a [software](crate::software_render) render context.
+    software_rendering: bool,
+    gpu_options: GpuOptions,
+    /// The adapter the window is rendered with, once its surface has been created.
+    gpu_info: Option<GpuInfo>,
+    /// The adapter changes and rendering errors not yet reported to the app driver.
+    gpu_reports: Vec<GpuReport>,
+    /// Whether the window was created transparent, in which case the alpha of the rendered
+    /// frames is used by the compositor.
+    transparent: bool,
     // TODO: Winit doesn't seem to let us create these proxies from within the loop
     // The reasons for this are unclear
     proxy: EventLoopProxy,
     #[cfg(feature = "tracy")]
     frame: Option<tracing_tracy::client::Frame>,
+    frame_scheduler: FrameScheduler,
+    recorder: Option<Recorder>,
+    replayer: Option<Replayer>,
+    #[cfg(feature = "inspector")]
+    inspector: Option<InspectorServer>,
+    #[cfg(feature = "webview")]
+    web_views: WebViews,
+    /// The tray icon, or its settings until the event loop has started.
+    #[cfg(feature = "tray")]
+    tray: TrayState,
+    /// The menu bar of the window.
+    #[cfg(feature = "menu")]
+    menu: crate::menu::MenuBar,
+    /// The render context and surface created asynchronously on the web.
+    #[cfg(target_arch = "wasm32")]
+    pending_surface: PendingSurface,
+    /// The directory the next frame is captured to.
+    #[cfg(feature = "capture")]
+    pending_capture: Option<PathBuf>,
+    /// Whether the window is hidden, e.g. because it's in a background browser tab.
+    occluded: bool,
+    /// The size of the last frame presented to the surface, if the surface still shows it.
+    ///
+    /// While it does, frames whose scene didn't change aren't rendered again.
+    presented_size: Option<PhysicalSize<u32>>,
+    /// The touch which acts as the pointer. Other touches are ignored until it ends.
+    pointer_touch: Option<u64>,
 
     // Per-Window state
     // In future, this will support multiple windows
@@ -88,6 +182,16 @@ pub struct MasonryState<'a> {
     background_color: Color,
 }
 
+#[cfg(target_arch = "wasm32")]
+type PendingSurface =
+    Rc<RefCell<Option<(RenderContext, Result<RenderSurface<'static>, vello::Error>)>>>;
+
+/// What the app driver is told about rendering, once the signals are next handled.
+enum GpuReport {
+    Adapter(GpuInfo),
+    Error(RenderError),
+}
+
 struct MainState<'a> {
     masonry_state: MasonryState<'a>,
     app_driver: Box<dyn AppDriver>,
@@ -126,6 +230,16 @@ pub fn run(
     )
 }
 
+/// Run `root_widget` in a window created with the given attributes.
+///
+/// For the window to be see-through, create it with [`WindowAttributes::with_transparent`]
+/// and give `background_color` an alpha below 1. [`WindowAttributes::with_blur`] additionally
+/// blurs what's behind the window, on platforms which support it.
+///
+/// On the web, the window is a canvas, which is added to the page unless one is set with
+/// winit's `WindowAttributesExtWebSys::with_canvas`.
+/// The browser runs the event loop, so this returns as soon as the app has started.
+/// Rendering requires WebGPU, as Vello uses compute shaders.
 pub fn run_with(
     event_loop: EventLoop,
     window: WindowAttributes,
@@ -147,7 +261,68 @@ pub fn run_with(
         .app_driver
         .on_start(&mut main_state.masonry_state);
 
-    event_loop.run_app(&mut main_state)
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        event_loop.run_app(&mut main_state)
+    }
+    // On the web, the browser runs the event loop, so this returns immediately
+    #[cfg(target_arch = "wasm32")]
+    {
+        use winit::platform::web::EventLoopExtWebSys;
+        event_loop.spawn_app(main_state);
+        Ok(())
+    }
+}
+
+// --- MARK: HOST ---
+
+/// The [`AppHost`] which runs apps in a window of a winit event loop, with [`run_with`].
+pub struct WinitHost {
+    event_loop: EventLoop,
+    window_attributes: WindowAttributes,
+}
+
+impl WinitHost {
+    /// Create a host which runs the app in `event_loop`, in a window created with `window_attributes`.
+    pub fn new(event_loop: EventLoop, window_attributes: WindowAttributes) -> Self {
+        Self {
+            event_loop,
+            window_attributes,
+        }
+    }
+}
+
+impl AppHost for WinitHost {
+    type Error = EventLoopError;
+
+    fn proxy(&self) -> Arc<dyn HostProxy> {
+        Arc::new(self.event_loop.create_proxy())
+    }
+
+    fn run(
+        self,
+        root_widget: impl Widget,
+        app_driver: impl AppDriver + 'static,
+        background_color: Color,
+    ) -> Result<(), EventLoopError> {
+        run_with(
+            self.event_loop,
+            self.window_attributes,
+            root_widget,
+            app_driver,
+            background_color,
+        )
+    }
+}
+
+impl HostProxy for EventLoopProxy {
+    fn send_action(&self, action: Action, widget_id: WidgetId) -> Result<(), Action> {
+        self.send_event(MasonryUserEvent::Action(action, widget_id))
+            .map_err(|err| match err.0 {
+                MasonryUserEvent::Action(action, _) => action,
+                _ => unreachable!("only actions are sent"),
+            })
+    }
 }
 
 impl ApplicationHandler<MasonryUserEvent> for MainState<'_> {
@@ -197,7 +372,8 @@ impl ApplicationHandler<MasonryUserEvent> for MainState<'_> {
     // external event loops can let masonry handle these callbacks.
 
     fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        self.masonry_state.handle_about_to_wait(event_loop);
+        self.masonry_state
+            .handle_about_to_wait(event_loop, self.app_driver.as_mut());
     }
 
     fn new_events(
@@ -206,6 +382,8 @@ impl ApplicationHandler<MasonryUserEvent> for MainState<'_> {
         cause: winit::event::StartCause,
     ) {
         self.masonry_state.handle_new_events(event_loop, cause);
+        self.masonry_state
+            .handle_replay(event_loop, self.app_driver.as_mut());
     }
 
     fn exiting(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
@@ -224,11 +402,18 @@ impl MasonryState<'_> {
         root_widget: impl Widget,
         background_color: Color,
     ) -> Self {
-        let render_cx = RenderContext::new();
+        let gpu_options = GpuOptions::default();
+        let software_rendering = gpu_options.software_rendering();
+        let render_cx = RenderContext::new(&gpu_options, software_rendering);
         // TODO: We can't know this scale factor until later?
         let scale_factor = 1.0;
 
-        MasonryState {
+        let recorder = std::env::var_os(RECORD_SESSION_VAR)
+            .filter(|path| !path.is_empty())
+            .map(|path| Recorder::new(path.into()));
+        let replay_path = std::env::var_os(REPLAY_SESSION_VAR).filter(|path| !path.is_empty());
+
+        let mut state = MasonryState {
             render_cx,
             render_root: RenderRoot::new(
                 root_widget,
@@ -240,18 +425,57 @@ impl MasonryState<'_> {
                 },
             ),
             renderer: None,
+            viewport_compositor: None,
+            software_rendering,
+            gpu_options,
+            gpu_info: None,
+            gpu_reports: Vec::new(),
+            transparent: false,
             #[cfg(feature = "tracy")]
             frame: None,
+            frame_scheduler: FrameScheduler::new(FramePacing::default()),
             pointer_state: PointerState::empty(),
+            resize_edge: None,
+            popups: PopupWindows::default(),
+            pending_placement: None,
             proxy: event_loop.create_proxy(),
+            recorder,
+            replayer: None,
+            #[cfg(feature = "inspector")]
+            inspector: start_inspector(),
+            #[cfg(feature = "webview")]
+            web_views: WebViews::default(),
+            #[cfg(feature = "tray")]
+            tray: TrayState::None,
+            #[cfg(feature = "menu")]
+            menu: crate::menu::MenuBar::default(),
+            #[cfg(target_arch = "wasm32")]
+            pending_surface: PendingSurface::default(),
+            #[cfg(feature = "capture")]
+            pending_capture: None,
+            occluded: false,
+            presented_size: None,
+            pointer_touch: None,
 
             window: WindowState::Uninitialized(window),
             background_color,
+        };
+        if let Some(path) = replay_path {
+            match Session::load(&path) {
+                Ok(session) => state.replay_session(session),
+                Err(err) => warn!(
+                    "Couldn't load input session from {}: {err}",
+                    PathBuf::from(path).display()
+                ),
+            }
         }
+        state
     }
 
     // --- MARK: RESUMED ---
     pub fn handle_resumed(&mut self, event_loop: &ActiveEventLoop) {
+        #[cfg(feature = "tray")]
+        self.create_pending_tray();
         match std::mem::replace(
             &mut self.window,
             // TODO: Is there a better default value which could be used?
@@ -259,36 +483,53 @@ impl MasonryState<'_> {
         ) {
             WindowState::Uninitialized(attributes) => {
                 let visible = attributes.visible;
+                self.transparent = attributes.transparent();
                 let attributes = attributes.with_visible(false);
+                // Add the canvas of the window to the page, unless the app passed
+                // a canvas which already is in it.
+                #[cfg(target_arch = "wasm32")]
+                let attributes = {
+                    use winit::platform::web::WindowAttributesExtWebSys;
+                    attributes.with_append(true)
+                };
+
+                let monitors = monitor_infos(
+                    event_loop.available_monitors(),
+                    event_loop.primary_monitor(),
+                );
+                let attributes = match self.pending_placement.take() {
+                    Some(placement) => {
+                        let placement = placement.fit_to(&monitors);
+                        attributes
+                            .with_position(placement.position)
+                            .with_inner_size(placement.size)
+                            .with_maximized(placement.maximized)
+                    }
+                    None => attributes,
+                };
+                self.render_root.set_monitors(monitors);
 
                 let window = event_loop.create_window(attributes).unwrap();
+                let placement = WindowPlacement::of_window(&window, self.render_root.monitors());
+                self.render_root.set_window_placement(placement);
 
                 let adapter = Adapter::with_event_loop_proxy(&window, self.proxy.clone());
                 let window = Arc::new(window);
-                // https://github.com/rust-windowing/winit/issues/2308
-                #[cfg(target_os = "ios")]
-                let size = window.outer_size();
-                #[cfg(not(target_os = "ios"))]
-                let size = window.inner_size();
-                let surface = pollster::block_on(self.render_cx.create_surface(
-                    window.clone(),
-                    size.width,
-                    size.height,
-                    PresentMode::AutoVsync,
-                ))
-                .unwrap();
                 let scale_factor = window.scale_factor();
-                self.window = WindowState::Rendering {
-                    window,
-                    surface,
-                    accesskit_adapter: adapter,
-                };
+                self.frame_scheduler.set_refresh_rate_millihertz(
+                    window
+                        .current_monitor()
+                        .and_then(|monitor| monitor.refresh_rate_millihertz()),
+                );
                 self.render_root
                     .handle_window_event(WindowEvent::Rescale(scale_factor));
-                // Render one frame before showing the window to avoid flashing
-                if visible {
+                self.create_surface(window, adapter);
+                // Render one frame before showing the window to avoid flashing.
+                // On the web, the surface isn't ready yet, and the first frame is
+                // rendered once it is.
+                if visible && matches!(self.window, WindowState::Rendering { .. }) {
                     let (scene, tree_update) = self.render_root.redraw();
-                    self.render(scene);
+                    self.render(&scene);
                     if let WindowState::Rendering {
                         window,
                         accesskit_adapter,
@@ -304,26 +545,179 @@ impl MasonryState<'_> {
                 window,
                 accesskit_adapter,
             } => {
-                // https://github.com/rust-windowing/winit/issues/2308
-                #[cfg(target_os = "ios")]
-                let size = window.outer_size();
-                #[cfg(not(target_os = "ios"))]
-                let size = window.inner_size();
-                let surface = pollster::block_on(self.render_cx.create_surface(
-                    window.clone(),
-                    size.width,
-                    size.height,
-                    PresentMode::AutoVsync,
-                ))
-                .unwrap();
+                self.create_surface(window, accesskit_adapter);
+            }
+            window_state => {
+                // We have received a redundant resumed event. That's allowed by winit
+                self.window = window_state;
+            }
+        }
+    }
+
+    /// Create the render surface of `window`.
+    ///
+    /// On the web, the surface is created asynchronously, and the window is in the
+    /// [`WindowState::CreatingSurface`] state until it is ready.
+    fn create_surface(&mut self, window: Arc<Window>, accesskit_adapter: Adapter) {
+        // https://github.com/rust-windowing/winit/issues/2308
+        #[cfg(target_os = "ios")]
+        let size = window.outer_size();
+        #[cfg(not(target_os = "ios"))]
+        let size = window.inner_size();
+        let present_mode = self.frame_scheduler.pacing().present_mode();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut surface = pollster::block_on(self.render_cx.create_surface(
+                window.clone(),
+                size.width,
+                size.height,
+                present_mode,
+            ))
+            .map_err(RenderError::CreateSurface)
+            .and_then(|surface| self.check_adapter(surface));
+            if let Err(err) = &surface {
+                if !self.software_rendering {
+                    warn!("{err}, falling back to software rendering");
+                    self.render_cx = RenderContext::new(&self.gpu_options, true);
+                    self.software_rendering = true;
+                    // The renderer belonged to the previous device
+                    self.renderer = None;
+                    self.viewport_compositor = None;
+                    surface = pollster::block_on(self.render_cx.create_surface(
+                        window.clone(),
+                        size.width,
+                        size.height,
+                        present_mode,
+                    ))
+                    .map_err(RenderError::CreateSurface)
+                    .and_then(|surface| self.check_adapter(surface));
+                }
+            }
+            match surface {
+                Ok(mut surface) => {
+                    if self.transparent {
+                        enable_surface_transparency(&self.render_cx, &mut surface);
+                    }
+                    self.window = WindowState::Rendering {
+                        window,
+                        surface,
+                        accesskit_adapter,
+                    };
+                    self.presented_size = None;
+                }
+                Err(err) => {
+                    self.report_render_error(err);
+                    self.window = WindowState::Suspended {
+                        window,
+                        accesskit_adapter,
+                    };
+                }
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            // The browser can't be blocked on whilst it provides the GPU adapter, so the
+            // render context is lent to a task, which sends it back with the surface.
+            let mut render_cx = std::mem::replace(
+                &mut self.render_cx,
+                RenderContext::new(&self.gpu_options, self.software_rendering),
+            );
+            let pending_surface = self.pending_surface.clone();
+            let proxy = self.proxy.clone();
+            let surface_window = window.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let surface = render_cx
+                    .create_surface(surface_window, size.width, size.height, present_mode)
+                    .await;
+                *pending_surface.borrow_mut() = Some((render_cx, surface));
+                let _ = proxy.send_event(MasonryUserEvent::SurfaceReady);
+            });
+            self.window = WindowState::CreatingSurface {
+                window,
+                accesskit_adapter,
+            };
+        }
+    }
+
+    /// Check that the adapter of a newly created `surface` supports the
+    /// [required limits](GpuOptions::required_limits), and report it to the app if it does.
+    fn check_adapter<'s>(
+        &mut self,
+        surface: RenderSurface<'s>,
+    ) -> Result<RenderSurface<'s>, RenderError> {
+        let adapter = self.render_cx.devices[surface.dev_id].adapter();
+        let limits = adapter.limits();
+        let adapter = adapter.get_info();
+        if !self.gpu_options.supports(&limits) {
+            return Err(RenderError::InsufficientLimits(adapter));
+        }
+        info!(
+            "Rendering with {} ({:?}, {:?})",
+            adapter.name, adapter.backend, adapter.device_type
+        );
+        let info = GpuInfo {
+            adapter,
+            limits,
+            software_rendering: self.software_rendering,
+        };
+        self.gpu_info = Some(info.clone());
+        self.gpu_reports.push(GpuReport::Adapter(info));
+        Ok(surface)
+    }
+
+    /// Log `err`, and report it to the app when the signals are next handled.
+    fn report_render_error(&mut self, err: RenderError) {
+        tracing::error!("{err}");
+        self.gpu_reports.push(GpuReport::Error(err));
+    }
+
+    /// Start rendering to the surface created by [`create_surface`](Self::create_surface).
+    #[cfg(target_arch = "wasm32")]
+    fn handle_surface_ready(&mut self) {
+        let Some((render_cx, surface)) = self.pending_surface.borrow_mut().take() else {
+            return;
+        };
+        self.render_cx = render_cx;
+        let WindowState::CreatingSurface {
+            window,
+            accesskit_adapter,
+        } = std::mem::replace(
+            &mut self.window,
+            WindowState::Uninitialized(WindowAttributes::default()),
+        )
+        else {
+            debug_panic!("Created a surface for a window which wasn't waiting for one");
+            return;
+        };
+        match surface
+            .map_err(RenderError::CreateSurface)
+            .and_then(|surface| self.check_adapter(surface))
+        {
+            Ok(mut surface) => {
+                if self.transparent {
+                    enable_surface_transparency(&self.render_cx, &mut surface);
+                }
+                // The window may have been resized whilst the surface was being created
+                self.render_root
+                    .handle_window_event(WindowEvent::Resize(window.inner_size()));
+                window.set_visible(true);
+                window.request_redraw();
                 self.window = WindowState::Rendering {
                     window,
                     surface,
                     accesskit_adapter,
-                }
+                };
+                self.presented_size = None;
             }
-            _ => {
-                // We have received a redundant resumed event. That's allowed by winit
+            Err(err) => {
+                // Vello needs compute shaders, which WebGL doesn't have
+                warn!("The browser may not support WebGPU");
+                self.report_render_error(err);
+                self.window = WindowState::Suspended {
+                    window,
+                    accesskit_adapter,
+                };
             }
         }
     }
@@ -341,19 +735,23 @@ impl MasonryState<'_> {
                 accesskit_adapter,
             } => {
                 drop(surface);
+                self.presented_size = None;
+                // The popups are closed, as their surfaces have to be dropped too
+                self.popups = PopupWindows::default();
                 self.window = WindowState::Suspended {
                     window,
                     accesskit_adapter,
                 };
             }
-            _ => {
-                // We have received a redundant resumed event. That's allowed by winit
+            window_state => {
+                // We have received a redundant suspended event. That's allowed by winit
+                self.window = window_state;
             }
         }
     }
 
     // --- MARK: RENDER ---
-    fn render(&mut self, scene: Scene) {
+    fn render(&mut self, scene: &Scene) {
         let WindowState::Rendering {
             window, surface, ..
         } = &mut self.window
@@ -362,11 +760,7 @@ impl MasonryState<'_> {
             return;
         };
         let scale_factor = window.scale_factor();
-        // https://github.com/rust-windowing/winit/issues/2308
-        #[cfg(target_os = "ios")]
-        let size = window.outer_size();
-        #[cfg(not(target_os = "ios"))]
-        let size = window.inner_size();
+        let size = surface_size(window);
         let width = size.width;
         let height = size.height;
 
@@ -378,21 +772,39 @@ impl MasonryState<'_> {
             None
         } else {
             let mut new_scene = Scene::new();
-            new_scene.append(&scene, Some(Affine::scale(scale_factor)));
+            new_scene.append(scene, Some(Affine::scale(scale_factor)));
             Some(new_scene)
         };
-        let scene_ref = transformed_scene.as_ref().unwrap_or(&scene);
+        let scene_ref = transformed_scene.as_ref().unwrap_or(scene);
 
-        let Ok(surface_texture) = surface.surface.get_current_texture() else {
-            warn!("failed to acquire next swapchain texture");
-            return;
-        };
         let dev_id = surface.dev_id;
         let device = &self.render_cx.devices[dev_id].device;
         let queue = &self.render_cx.devices[dev_id].queue;
+        let surface_texture = match surface.surface.get_current_texture() {
+            Ok(surface_texture) => surface_texture,
+            Err(wgpu::SurfaceError::Timeout) => {
+                warn!("Timed out acquiring the next swapchain texture");
+                return;
+            }
+            Err(err @ (wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost)) => {
+                // E.g. after the GPU driver was reset: configure the surface again, and
+                // render the frame once it is.
+                debug!("Reconfiguring the render surface: {err}");
+                surface.surface.configure(device, &surface.config);
+                self.presented_size = None;
+                window.request_redraw();
+                return;
+            }
+            Err(err) => {
+                tracing::error!("Failed to acquire the next swapchain texture: {err}");
+                self.gpu_reports
+                    .push(GpuReport::Error(RenderError::Surface(err)));
+                return;
+            }
+        };
         let renderer_options = RendererOptions {
             surface_format: Some(surface.format),
-            use_cpu: false,
+            use_cpu: self.software_rendering,
             antialiasing_support: AaSupport {
                 area: true,
                 msaa8: false,
@@ -410,31 +822,79 @@ impl MasonryState<'_> {
         window.pre_present_notify();
         {
             let _render_span = tracing::info_span!("Rendering using Vello").entered();
-            self.renderer
-                .get_or_insert_with(|| {
-                    // Should be `expect`, when we up our MSRV.
-                    #[cfg_attr(not(feature = "tracy"), allow(unused_mut))]
-                    let mut renderer = Renderer::new(device, renderer_options).unwrap();
-                    #[cfg(feature = "tracy")]
-                    {
-                        let new_profiler = wgpu_profiler::GpuProfiler::new_with_tracy_client(
-                            wgpu_profiler::GpuProfilerSettings::default(),
-                            // We don't have access to the adapter until we get  https://github.com/linebender/vello/pull/634
-                            // Luckily, this `backend` is only used for visual display in the profiling, so we can just guess here
-                            wgpu::Backend::Vulkan,
-                            device,
-                            queue,
-                        )
-                        .unwrap_or(renderer.profiler);
-                        renderer.profiler = new_profiler;
+            if self.renderer.is_none() {
+                match new_renderer(&self.render_cx, dev_id, renderer_options) {
+                    Ok(renderer) => self.renderer = Some(renderer),
+                    Err(err) => {
+                        tracing::error!("Failed to create the renderer: {err}");
+                        self.gpu_reports
+                            .push(GpuReport::Error(RenderError::CreateRenderer(err)));
+                        return;
                     }
-                    renderer
-                })
-                .render_to_surface(device, queue, scene_ref, &surface_texture, &render_params)
-                .expect("failed to render to surface");
+                }
+            }
+            let renderer = self
+                .renderer
+                .as_mut()
+                .expect("the renderer was created above");
+            if let Err(err) = renderer.render_to_surface(
+                device,
+                queue,
+                scene_ref,
+                &surface_texture,
+                &render_params,
+            ) {
+                tracing::error!("Failed to render a frame: {err}");
+                self.gpu_reports
+                    .push(GpuReport::Error(RenderError::Render(err)));
+                return;
+            }
+            let surface_view = surface_texture
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            self.viewport_compositor
+                .get_or_insert_with(|| ViewportCompositor::new(device, surface.format))
+                .render(
+                    device,
+                    queue,
+                    &mut self.render_root,
+                    &surface_view,
+                    PhysicalSize::new(width, height),
+                );
         }
         surface_texture.present();
+        self.presented_size = Some(size);
         device.poll(wgpu::Maintain::Wait);
+        #[cfg(feature = "capture")]
+        if let Some(dir) = self.pending_capture.take() {
+            let Color { r, g, b, a } = self.background_color;
+            let capture = crate::capture::FrameCapture {
+                masonry_version: env!("CARGO_PKG_VERSION").into(),
+                width,
+                height,
+                scale_factor,
+                background_color: [r, g, b, a],
+                adapter: self.gpu_info.as_ref().map(Into::into),
+                root: crate::inspector::WidgetNode::new(self.render_root.get_root_widget()),
+            };
+            let renderer = self.renderer.as_mut().expect("the frame was rendered");
+            match crate::capture::write_capture(
+                &dir,
+                device,
+                queue,
+                renderer,
+                scene_ref,
+                &render_params,
+                &capture,
+            ) {
+                Ok(()) => info!("Captured the frame to {}", dir.display()),
+                Err(err) => {
+                    tracing::error!("Failed to capture the frame to {}: {err}", dir.display())
+                }
+            }
+        }
+        #[cfg(feature = "profiling")]
+        profiling::finish_frame!();
         #[cfg(feature = "tracy")]
         drop(self.frame.take());
     }
@@ -443,10 +903,21 @@ impl MasonryState<'_> {
     pub fn handle_window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
-        _: WindowId,
+        window_id: WindowId,
         event: WinitWindowEvent,
         app_driver: &mut dyn AppDriver,
     ) {
+        if let Some(popup) = self.popups.popup_of(window_id) {
+            self.popups.handle_window_event(
+                popup,
+                event,
+                &mut self.render_cx,
+                self.software_rendering,
+                &mut self.render_root,
+            );
+            self.handle_signals(event_loop, app_driver);
+            return;
+        }
         let WindowState::Rendering {
             window,
             accesskit_adapter,
@@ -459,22 +930,75 @@ impl MasonryState<'_> {
             );
             return;
         };
+        if window.id() != window_id {
+            // An event of a popup which was closed since
+            return;
+        }
         #[cfg(feature = "tracy")]
         if self.frame.is_none() {
             self.frame = Some(tracing_tracy::client::non_continuous_frame!("Masonry"));
         }
         accesskit_adapter.process_event(window, &event);
 
+        if self.recorder.is_some() || self.replayer.is_some() {
+            if let Some(session_event) = SessionEvent::from_winit(&event, window.scale_factor()) {
+                if let Some(recorder) = &mut self.recorder {
+                    recorder.record(session_event);
+                }
+                if self.replayer.is_some() {
+                    // The input comes from the replayed session instead
+                    return;
+                }
+            }
+        }
+
         match event {
             WinitWindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                 self.render_root
                     .handle_window_event(WindowEvent::Rescale(scale_factor));
+                // The window may have moved to another monitor, or the monitor's settings changed
+                self.render_root.set_monitors(monitor_infos(
+                    event_loop.available_monitors(),
+                    event_loop.primary_monitor(),
+                ));
+                update_window_placement(&mut self.render_root, window, app_driver);
+            }
+            WinitWindowEvent::Moved(_) => {
+                update_window_placement(&mut self.render_root, window, app_driver);
             }
             WinitWindowEvent::RedrawRequested => {
                 let _span = info_span!("redraw");
-                self.render_root.handle_window_event(WindowEvent::AnimFrame);
+                let frame_start = Instant::now();
+                self.frame_scheduler.begin_frame(frame_start);
+                if let Some(recorder) = &mut self.recorder {
+                    recorder.record(SessionEvent::AnimFrame);
+                }
+                // When replaying, the animation frames come from the replayed session
+                if self.replayer.is_none() {
+                    self.render_root.handle_window_event(WindowEvent::AnimFrame);
+                }
                 let (scene, tree_update) = self.render_root.redraw();
-                self.render(scene);
+                // The surface still shows the previous frame, so an identical frame doesn't
+                // need to be encoded and uploaded to the GPU again. Viewports are rendered
+                // by their own callbacks, which may change them on every frame.
+                let unchanged = !self.render_root.scene_changed()
+                    && self.presented_size == Some(surface_size(window))
+                    && self.render_root.global_state.viewport_callbacks.is_empty();
+                if unchanged {
+                    self.frame_scheduler.record_unchanged_frame();
+                } else {
+                    self.render(&scene);
+                }
+                #[cfg(feature = "webview")]
+                self.web_views.sync(&self.render_root);
+                self.popups.sync(&self.render_root);
+                #[cfg(feature = "inspector")]
+                if let Some(inspector) = &self.inspector {
+                    inspector.publish(WidgetNode::new(self.render_root.get_root_widget()));
+                }
+                let animating = self.render_root.root_state().needs_anim;
+                self.frame_scheduler
+                    .end_frame(frame_start, Instant::now(), animating);
                 let WindowState::Rendering {
                     accesskit_adapter, ..
                 } = &mut self.window
@@ -492,8 +1016,23 @@ impl MasonryState<'_> {
                 event_loop.exit();
             }
             WinitWindowEvent::Resized(size) => {
+                // On iOS, this is the size of the safe area, but the app covers the whole
+                // screen and is laid out around the system UI instead.
+                // https://github.com/rust-windowing/winit/issues/2308
+                #[cfg(target_os = "ios")]
+                let size = window.outer_size();
                 self.render_root
                     .handle_window_event(WindowEvent::Resize(size));
+                update_window_placement(&mut self.render_root, window, app_driver);
+            }
+            WinitWindowEvent::Occluded(occluded) => {
+                self.occluded = occluded;
+                // The window may be out of date, as frames weren't rendered whilst it was hidden
+                if !occluded {
+                    self.presented_size = None;
+                    self.frame_scheduler.request_frame();
+                    self.request_redraw_if_due();
+                }
             }
             WinitWindowEvent::ModifiersChanged(modifiers) => {
                 self.pointer_state.mods = modifiers;
@@ -505,10 +1044,23 @@ impl MasonryState<'_> {
                 event,
                 is_synthetic: false, // TODO: Introduce an escape hatch for synthetic keys
             } => {
-                self.render_root.handle_text_event(TextEvent::KeyboardKey(
-                    event,
-                    self.pointer_state.mods.state(),
-                ));
+                let modifiers = self.pointer_state.mods.state();
+                #[cfg(feature = "capture")]
+                if let Some(dir) = crate::capture::shortcut_capture_dir(&event, modifiers) {
+                    self.capture_frame(dir);
+                    return;
+                }
+                let handled = self
+                    .render_root
+                    .handle_text_event(TextEvent::KeyboardKey(event.clone(), modifiers));
+                if !handled.is_handled() {
+                    self.render_root.edit_root_widget(|root| {
+                        let mut driver_ctx = DriverCtx {
+                            main_root_widget: root,
+                        };
+                        app_driver.on_unhandled_key(&mut driver_ctx, &event, modifiers);
+                    });
+                }
             }
             WinitWindowEvent::Ime(ime) => {
                 self.render_root.handle_text_event(TextEvent::Ime(ime));
@@ -524,15 +1076,51 @@ impl MasonryState<'_> {
             WinitWindowEvent::CursorMoved { position, .. } => {
                 self.pointer_state.physical_position = position;
                 self.pointer_state.position = position.to_logical(window.scale_factor());
-                self.render_root
-                    .handle_pointer_event(PointerEvent::PointerMove(self.pointer_state.clone()));
+                // Undecorated windows are resized from their edges, which the content doesn't see
+                let resize_edge = resize_direction(window, position);
+                if resize_edge != self.resize_edge {
+                    self.resize_edge = resize_edge;
+                    match resize_edge {
+                        Some(direction) => {
+                            window.set_cursor(resize_cursor(direction));
+                            self.render_root
+                                .handle_pointer_event(PointerEvent::PointerLeave(
+                                    self.pointer_state.clone(),
+                                ));
+                        }
+                        None => window.set_cursor(self.render_root.cursor_icon()),
+                    }
+                }
+                if resize_edge.is_none() {
+                    self.render_root
+                        .handle_pointer_event(PointerEvent::PointerMove(
+                            self.pointer_state.clone(),
+                        ));
+                }
             }
             WinitWindowEvent::CursorLeft { .. } => {
                 self.render_root
                     .handle_pointer_event(PointerEvent::PointerLeave(self.pointer_state.clone()));
             }
+            WinitWindowEvent::MouseInput {
+                state: winit::event::ElementState::Pressed,
+                button: winit::event::MouseButton::Left,
+                ..
+            } if self.resize_edge.is_some() => {
+                // TODO - Handle return value?
+                let _ = window.drag_resize_window(self.resize_edge.unwrap());
+            }
             WinitWindowEvent::MouseInput { state, button, .. } => match state {
                 winit::event::ElementState::Pressed => {
+                    // Clicking the window dismisses the popups, such as menus
+                    for (popup, owner) in self.popups.dismiss_all() {
+                        self.render_root.global_state.emit_signal(
+                            render_root::RenderRootSignal::Action(
+                                crate::Action::PopupClosed(popup),
+                                owner,
+                            ),
+                        );
+                    }
                     self.render_root
                         .handle_pointer_event(PointerEvent::PointerDown(
                             button.into(),
@@ -550,10 +1138,11 @@ impl MasonryState<'_> {
             WinitWindowEvent::MouseWheel { delta, .. } => {
                 let delta = match delta {
                     winit::event::MouseScrollDelta::LineDelta(x, y) => {
-                        LogicalPosition::new(x as f64, y as f64)
+                        ScrollDelta::Lines(Vec2::new(x as f64, y as f64))
                     }
                     winit::event::MouseScrollDelta::PixelDelta(delta) => {
-                        delta.to_logical(window.scale_factor())
+                        let delta: LogicalPosition<f64> = delta.to_logical(window.scale_factor());
+                        ScrollDelta::Pixels(Vec2::new(delta.x, delta.y))
                     }
                 };
                 self.render_root
@@ -566,10 +1155,20 @@ impl MasonryState<'_> {
                 location,
                 phase,
                 force,
+                id,
                 ..
             }) => {
+                if phase == winit::event::TouchPhase::Started {
+                    self.render_root.handle_touch();
+                }
                 // FIXME: This is naïve and should be refined for actual use.
                 //        It will also interact with gesture discrimination.
+                // Only the first finger on the screen moves the pointer.
+                match (phase, self.pointer_touch) {
+                    (winit::event::TouchPhase::Started, None) => self.pointer_touch = Some(id),
+                    (_, Some(pointer_touch)) if pointer_touch == id => {}
+                    _ => return,
+                }
                 self.pointer_state.physical_position = location;
                 self.pointer_state.position = location.to_logical(window.scale_factor());
                 self.pointer_state.force = force;
@@ -586,11 +1185,17 @@ impl MasonryState<'_> {
                             ));
                     }
                     winit::event::TouchPhase::Ended => {
+                        self.pointer_touch = None;
                         self.render_root
                             .handle_pointer_event(PointerEvent::PointerUp(
                                 PointerButton::Primary,
                                 self.pointer_state.clone(),
                             ));
+                        // Nothing is hovered once the finger is lifted
+                        self.render_root
+                            .handle_pointer_event(PointerEvent::PointerLeave(
+                                self.pointer_state.clone(),
+                            ));
                     }
                     winit::event::TouchPhase::Moved => {
                         self.render_root
@@ -599,6 +1204,7 @@ impl MasonryState<'_> {
                             ));
                     }
                     winit::event::TouchPhase::Cancelled => {
+                        self.pointer_touch = None;
                         self.render_root
                             .handle_pointer_event(PointerEvent::PointerLeave(
                                 self.pointer_state.clone(),
@@ -653,22 +1259,319 @@ impl MasonryState<'_> {
                 .global_state
                 .signal_queue
                 .push_back(render_root::RenderRootSignal::Action(action, widget)),
+            #[cfg(target_arch = "wasm32")]
+            MasonryUserEvent::SurfaceReady => self.handle_surface_ready(),
+            #[cfg(feature = "tray")]
+            MasonryUserEvent::TrayMenu(event) => self.handle_tray_menu_event(app_driver, event),
+            #[cfg(feature = "menu")]
+            MasonryUserEvent::Menu(event) => self.handle_menu_event(app_driver, event),
         }
 
         self.handle_signals(event_loop, app_driver);
     }
 
-    // --- MARK: EMPTY WINIT HANDLERS ---
-    pub fn handle_about_to_wait(&mut self, _: &ActiveEventLoop) {}
+    // --- MARK: FRAME PACING ---
+    pub fn handle_about_to_wait(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        app_driver: &mut dyn AppDriver,
+    ) {
+        // We may have been woken up by a timer
+        self.render_root.handle_timers();
 
-    pub fn handle_new_events(&mut self, _: &ActiveEventLoop, _: winit::event::StartCause) {}
+        // The event loop is idle, so run the background work of widgets and of the app.
+        let idle_start = Instant::now();
+        let mut more_idle_work = self.render_root.run_idle_work(IDLE_BUDGET);
+        if idle_start.elapsed() < IDLE_BUDGET {
+            self.render_root.edit_root_widget(|root| {
+                let mut driver_ctx = DriverCtx {
+                    main_root_widget: root,
+                };
+                more_idle_work |= app_driver.on_idle(&mut driver_ctx);
+            });
+        } else {
+            more_idle_work = true;
+        }
+        self.handle_signals(event_loop, app_driver);
+        if more_idle_work || self.render_root.has_idle_work() {
+            // Come back as soon as the events which arrived in the meantime are handled
+            event_loop.set_control_flow(ControlFlow::Poll);
+            return;
+        }
 
-    pub fn handle_exiting(&mut self, _: &ActiveEventLoop) {}
+        // If a frame is being held back by the frame rate cap, a replayed event is due, or a
+        // widget's timer is running, wake up when it's due.
+        let replay_deadline = self.replayer.as_ref().and_then(Replayer::next_deadline);
+        let timer_deadline = self.render_root.next_timer_deadline();
+        // Frames aren't rendered whilst the window is hidden
+        let frame_deadline = if self.occluded {
+            None
+        } else {
+            self.frame_scheduler.next_frame_deadline()
+        };
+        let deadline = [frame_deadline, replay_deadline, timer_deadline]
+            .into_iter()
+            .flatten()
+            .min();
+        match deadline {
+            Some(deadline) => event_loop.set_control_flow(ControlFlow::WaitUntil(deadline)),
+            None => event_loop.set_control_flow(ControlFlow::Wait),
+        }
+    }
+
+    pub fn handle_new_events(&mut self, _: &ActiveEventLoop, _: winit::event::StartCause) {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        self.update_insets();
+        self.request_redraw_if_due();
+    }
+
+    /// Lay the app out around the system UI covering the window, such as the soft keyboard
+    /// or the notch.
+    ///
+    /// Winit doesn't report when this changes on mobile platforms, so it's checked whenever
+    /// the event loop wakes up.
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    fn update_insets(&mut self) {
+        let WindowState::Rendering { window, .. } = &self.window else {
+            return;
+        };
+        let Some(insets) = system_ui_insets(window) else {
+            return;
+        };
+        if insets != self.render_root.insets {
+            self.render_root
+                .handle_window_event(WindowEvent::SetInsets(insets));
+            self.frame_scheduler.request_frame();
+        }
+    }
+
+    /// Ask the window to redraw, if a frame was requested and the frame pacing allows it.
+    fn request_redraw_if_due(&mut self) {
+        let WindowState::Rendering { window, .. } = &self.window else {
+            return;
+        };
+        if self.occluded {
+            return;
+        }
+        if self.frame_scheduler.should_request_redraw(Instant::now()) {
+            window.request_redraw();
+        }
+    }
+
+    // --- MARK: CAPTURE ---
+    /// Capture the next frame to the directory `dir`, to debug rendering issues.
+    ///
+    /// The frame is rendered even if it's identical to the previous one. The outcome is logged.
+    /// See the [`capture`](crate::capture) module for details.
+    #[cfg(feature = "capture")]
+    pub fn capture_frame(&mut self, dir: impl Into<PathBuf>) {
+        self.pending_capture = Some(dir.into());
+        self.presented_size = None;
+        if let WindowState::Rendering { window, .. } = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    // --- MARK: REPLAY ---
+    /// Record the input of the window to a file at `path`, which can be replayed
+    /// with [`replay_session`](Self::replay_session).
+    ///
+    /// The file is written when the event loop exits.
+    /// This can be called from [`AppDriver::on_start`].
+    /// See [`Session`] for details.
+    pub fn record_session(&mut self, path: impl Into<PathBuf>) {
+        self.recorder = Some(Recorder::new(path.into()));
+    }
 
-    pub fn handle_memory_warning(&mut self, _: &ActiveEventLoop) {}
+    /// Feed the events of `session` to the app, instead of the input of the window.
+    ///
+    /// This can be called from [`AppDriver::on_start`].
+    /// See [`Session`] for details.
+    pub fn replay_session(&mut self, session: Session) {
+        self.render_root.global_state.clock.freeze();
+        self.replayer = Some(Replayer::new(session));
+    }
+
+    /// Feed the replayed events which are due to the app.
+    ///
+    /// If you run Masonry from an external event loop, call this after
+    /// [`handle_new_events`](Self::handle_new_events).
+    pub fn handle_replay(&mut self, event_loop: &ActiveEventLoop, app_driver: &mut dyn AppDriver) {
+        if !matches!(self.window, WindowState::Rendering { .. }) {
+            return;
+        }
+        while let Some((elapsed, event)) = self
+            .replayer
+            .as_mut()
+            .and_then(|replayer| replayer.next_due(Instant::now()))
+        {
+            self.render_root.global_state.clock.advance(elapsed);
+            self.replay_event(event);
+            self.handle_signals(event_loop, app_driver);
+        }
+        if self.replayer.as_ref().is_some_and(Replayer::is_finished) {
+            info!("Finished replaying input session");
+            self.replayer = None;
+            self.render_root.global_state.clock.unfreeze();
+        }
+    }
+
+    fn replay_event(&mut self, event: SessionEvent) {
+        match event {
+            SessionEvent::Resize { width, height } => {
+                let size = PhysicalSize::new(width, height);
+                if let WindowState::Rendering { window, .. } = &self.window {
+                    // TODO - Handle return value?
+                    let _ = window.request_inner_size(size);
+                }
+                self.render_root
+                    .handle_window_event(WindowEvent::Resize(size));
+            }
+            SessionEvent::Rescale { scale_factor } => {
+                self.render_root
+                    .handle_window_event(WindowEvent::Rescale(scale_factor));
+            }
+            SessionEvent::Focus { focused } => {
+                self.render_root
+                    .handle_text_event(TextEvent::FocusChange(focused));
+            }
+            SessionEvent::AnimFrame => {
+                self.render_root.handle_window_event(WindowEvent::AnimFrame);
+            }
+            SessionEvent::Modifiers { bits } => {
+                let modifiers = ModifiersState::from_bits_truncate(bits);
+                self.pointer_state.mods = modifiers.into();
+                self.render_root
+                    .handle_text_event(TextEvent::ModifierChange(modifiers));
+            }
+            SessionEvent::Key {
+                code,
+                text,
+                pressed: true,
+                ..
+            } => {
+                let modifiers = self.pointer_state.mods.state();
+                if let Some(code) = code.as_deref().and_then(SessionEvent::navigation_key) {
+                    run_focus_navigation(&mut self.render_root, PhysicalKey::Code(code), modifiers);
+                    self.render_root.run_rewrite_passes();
+                } else if let Some(text) = text {
+                    let is_shortcut =
+                        modifiers.control_key() || modifiers.alt_key() || modifiers.super_key();
+                    if !is_shortcut {
+                        self.render_root
+                            .handle_text_event(TextEvent::Ime(winit::event::Ime::Commit(text)));
+                    }
+                }
+            }
+            SessionEvent::Key { pressed: false, .. } => {}
+            SessionEvent::Ime(ime) => {
+                let ime = match ime {
+                    ImeEvent::Enabled => winit::event::Ime::Enabled,
+                    ImeEvent::Preedit { text, cursor } => winit::event::Ime::Preedit(text, cursor),
+                    ImeEvent::Commit { text } => winit::event::Ime::Commit(text),
+                    ImeEvent::Disabled => winit::event::Ime::Disabled,
+                };
+                self.render_root.handle_text_event(TextEvent::Ime(ime));
+            }
+            SessionEvent::PointerEnter => {
+                self.render_root
+                    .handle_pointer_event(PointerEvent::PointerEnter(self.pointer_state.clone()));
+            }
+            SessionEvent::PointerMove { x, y } => {
+                self.set_pointer_position(PhysicalPosition::new(x, y));
+                self.render_root
+                    .handle_pointer_event(PointerEvent::PointerMove(self.pointer_state.clone()));
+            }
+            SessionEvent::PointerLeave => {
+                self.render_root
+                    .handle_pointer_event(PointerEvent::PointerLeave(self.pointer_state.clone()));
+            }
+            SessionEvent::PointerDown { button } => {
+                self.render_root
+                    .handle_pointer_event(PointerEvent::PointerDown(
+                        button,
+                        self.pointer_state.clone(),
+                    ));
+            }
+            SessionEvent::PointerUp { button } => {
+                self.render_root
+                    .handle_pointer_event(PointerEvent::PointerUp(
+                        button,
+                        self.pointer_state.clone(),
+                    ));
+            }
+            SessionEvent::Wheel { x, y, pixels } => {
+                let delta = if pixels {
+                    ScrollDelta::Pixels(Vec2::new(x, y))
+                } else {
+                    ScrollDelta::Lines(Vec2::new(x, y))
+                };
+                self.render_root
+                    .handle_pointer_event(PointerEvent::MouseWheel(
+                        delta,
+                        self.pointer_state.clone(),
+                    ));
+            }
+            SessionEvent::Touch { x, y, phase } => {
+                if phase == TouchPhase::Started {
+                    self.render_root.handle_touch();
+                }
+                self.set_pointer_position(PhysicalPosition::new(x, y));
+                let state = self.pointer_state.clone();
+                let events = match phase {
+                    TouchPhase::Started => vec![
+                        PointerEvent::PointerMove(state.clone()),
+                        PointerEvent::PointerDown(PointerButton::Primary, state),
+                    ],
+                    TouchPhase::Moved => vec![PointerEvent::PointerMove(state)],
+                    TouchPhase::Ended => {
+                        vec![PointerEvent::PointerUp(PointerButton::Primary, state)]
+                    }
+                    TouchPhase::Cancelled => vec![PointerEvent::PointerLeave(state)],
+                };
+                for event in events {
+                    self.render_root.handle_pointer_event(event);
+                }
+            }
+            SessionEvent::Pinch { delta } => {
+                self.render_root
+                    .handle_pointer_event(PointerEvent::Pinch(delta, self.pointer_state.clone()));
+            }
+        }
+    }
+
+    fn set_pointer_position(&mut self, position: PhysicalPosition<f64>) {
+        self.pointer_state.physical_position = position;
+        self.pointer_state.position = position.to_logical(self.render_root.scale_factor);
+    }
+
+    pub fn handle_memory_warning(&mut self, _: &ActiveEventLoop) {
+        // Decoded resources can be loaded again, so they are the first thing to let go of.
+        self.render_root.resource_cache().clear();
+    }
+
+    // --- MARK: EMPTY WINIT HANDLERS ---
+
+    pub fn handle_exiting(&mut self, _: &ActiveEventLoop) {}
 
     // --- MARK: SIGNALS ---
     fn handle_signals(&mut self, event_loop: &ActiveEventLoop, app_driver: &mut dyn AppDriver) {
+        // These are reported even if the window couldn't be rendered
+        for report in std::mem::take(&mut self.gpu_reports) {
+            self.render_root.edit_root_widget(|root| {
+                let mut driver_ctx = DriverCtx {
+                    main_root_widget: root,
+                };
+                match &report {
+                    GpuReport::Adapter(info) => app_driver.on_gpu_adapter(&mut driver_ctx, info),
+                    GpuReport::Error(err) => app_driver.on_render_error(&mut driver_ctx, err),
+                }
+            });
+            if matches!(&report, GpuReport::Error(err) if err.is_fatal()) {
+                event_loop.exit();
+            }
+        }
+
         let WindowState::Rendering { window, .. } = &mut self.window else {
             tracing::warn!("Tried to handle a signal whilst suspended or before window created");
             return;
@@ -688,9 +1591,20 @@ impl MasonryState<'_> {
                 }
                 render_root::RenderRootSignal::StartIme => {
                     window.set_ime_allowed(true);
+                    // Winit doesn't show the soft keyboard on Android
+                    #[cfg(target_os = "android")]
+                    {
+                        use winit::platform::android::ActiveEventLoopExtAndroid;
+                        event_loop.android_app().show_soft_input(true);
+                    }
                 }
                 render_root::RenderRootSignal::EndIme => {
                     window.set_ime_allowed(false);
+                    #[cfg(target_os = "android")]
+                    {
+                        use winit::platform::android::ActiveEventLoopExtAndroid;
+                        event_loop.android_app().hide_soft_input(true);
+                    }
                 }
                 render_root::RenderRootSignal::ImeMoved(position, size) => {
                     window.set_ime_cursor_area(position, size);
@@ -706,7 +1620,10 @@ impl MasonryState<'_> {
                     window.focus_window();
                 }
                 render_root::RenderRootSignal::SetCursor(cursor) => {
-                    window.set_cursor(cursor);
+                    // The resize cursor is shown over the edges of undecorated windows
+                    if self.resize_edge.is_none() {
+                        window.set_cursor(cursor);
+                    }
                 }
                 render_root::RenderRootSignal::SetSize(size) => {
                     // TODO - Handle return value?
@@ -735,13 +1652,41 @@ impl MasonryState<'_> {
                 render_root::RenderRootSignal::ShowWindowMenu(position) => {
                     window.show_window_menu(position);
                 }
+                render_root::RenderRootSignal::SetWindowPlacement(placement) => {
+                    placement.apply(window);
+                }
+                render_root::RenderRootSignal::ShowPopup(popup, request) => {
+                    let cache = self.render_root.resource_cache().clone();
+                    self.popups.open(
+                        event_loop,
+                        &mut self.render_cx,
+                        window,
+                        cache,
+                        popup,
+                        request,
+                    );
+                }
+                render_root::RenderRootSignal::ClosePopup(popup) => {
+                    self.popups.close(popup);
+                }
+                #[cfg(feature = "webview")]
+                render_root::RenderRootSignal::WebView(widget_id, request) => {
+                    self.web_views
+                        .handle_request(window, &self.proxy, widget_id, request);
+                }
+                #[cfg(feature = "menu")]
+                render_root::RenderRootSignal::SetMenu(entries) => {
+                    self.menu.set(window, entries, &self.proxy);
+                }
             }
         }
 
         // If we're processing a lot of actions, we may have a lot of pending redraws.
-        // We batch them up to avoid redundant requests.
+        // We batch them up to avoid redundant requests, and let the frame scheduler
+        // decide when to render.
         if needs_redraw {
-            window.request_redraw();
+            self.frame_scheduler.request_frame();
+            self.request_redraw_if_due();
         }
     }
 
@@ -758,4 +1703,325 @@ impl MasonryState<'_> {
             self.render_cx.set_present_mode(surface, present_mode);
         }
     }
+
+    /// Set how rendered frames are paced.
+    ///
+    /// This also sets the present mode of the window surface accordingly.
+    /// It can be called from [`AppDriver::on_start`].
+    pub fn set_frame_pacing(&mut self, pacing: FramePacing) {
+        self.frame_scheduler.set_pacing(pacing);
+        self.set_present_mode(pacing.present_mode());
+    }
+
+    /// The monitors of the system.
+    ///
+    /// This is empty until the window has been created.
+    pub fn monitors(&self) -> &[MonitorInfo] {
+        self.render_root.monitors()
+    }
+
+    /// Where the window is on the desktop, if it has been created.
+    pub fn window_placement(&self) -> Option<&WindowPlacement> {
+        self.render_root.window_placement()
+    }
+
+    /// Move and resize the window.
+    ///
+    /// This can be called from [`AppDriver::on_start`], e.g. to restore the placement the
+    /// window had when the app last exited. In that case, the placement is checked with
+    /// [`WindowPlacement::fit_to`] when the window is created, so that the window is shown on
+    /// a connected monitor.
+    pub fn set_window_placement(&mut self, placement: WindowPlacement) {
+        match &self.window {
+            WindowState::Uninitialized(_) => self.pending_placement = Some(placement),
+            WindowState::Rendering { window, .. } | WindowState::Suspended { window, .. } => {
+                placement.apply(window);
+            }
+            #[cfg(target_arch = "wasm32")]
+            WindowState::CreatingSurface { window, .. } => placement.apply(window),
+        }
+    }
+
+    /// Choose how the GPU adapter which renders the window is picked.
+    ///
+    /// This has to be called from [`AppDriver::on_start`], as the adapter is chosen when the
+    /// window is created. See the [`gpu`](crate::gpu) module for details.
+    pub fn set_gpu_options(&mut self, options: GpuOptions) {
+        if !matches!(self.window, WindowState::Uninitialized(_)) {
+            warn!("The GPU options can only be set before the window is created");
+            return;
+        }
+        self.software_rendering = options.software_rendering();
+        self.render_cx = RenderContext::new(&options, self.software_rendering);
+        self.gpu_options = options;
+    }
+
+    /// The adapter the window is rendered with, once it has been created.
+    pub fn gpu_info(&self) -> Option<&GpuInfo> {
+        self.gpu_info.as_ref()
+    }
+
+    /// Show an icon with a menu in the system tray, replacing the current one.
+    ///
+    /// This can be called from [`AppDriver::on_start`]; the icon is created when the event loop
+    /// has started. See the [`tray`](crate::tray) module for details.
+    #[cfg(feature = "tray")]
+    pub fn set_tray(&mut self, options: crate::tray::TrayOptions) {
+        self.tray = TrayState::Pending(options);
+        if !matches!(self.window, WindowState::Uninitialized(_)) {
+            self.create_pending_tray();
+        }
+    }
+
+    /// Create the tray icon set by [`Self::set_tray`], which can only be done once the event
+    /// loop has started.
+    #[cfg(feature = "tray")]
+    fn create_pending_tray(&mut self) {
+        let TrayState::Pending(options) = &self.tray else {
+            return;
+        };
+        self.tray = match crate::tray::create_tray(options, self.proxy.clone()) {
+            Ok(tray) => TrayState::Created(tray),
+            Err(err) => {
+                warn!("Failed to create the tray icon: {err}");
+                TrayState::None
+            }
+        };
+    }
+
+    /// Tell the app driver that an item of the tray menu was selected.
+    #[cfg(feature = "tray")]
+    fn handle_tray_menu_event(
+        &mut self,
+        app_driver: &mut dyn AppDriver,
+        event: crate::tray::TrayMenuEvent,
+    ) {
+        // The menus share the handler of menu events, see `handle_menu_event`
+        #[cfg(feature = "menu")]
+        if self.menu.contains(&event.id) {
+            let event = crate::menu::MenuEvent { id: event.id };
+            self.handle_menu_event(app_driver, event);
+            return;
+        }
+        self.render_root.edit_root_widget(|root| {
+            let mut driver_ctx = DriverCtx {
+                main_root_widget: root,
+            };
+            app_driver.on_tray_menu(&mut driver_ctx, event);
+        });
+    }
+
+    /// Tell the app driver that an item of the menu bar was selected.
+    #[cfg(feature = "menu")]
+    fn handle_menu_event(&mut self, app_driver: &mut dyn AppDriver, event: crate::menu::MenuEvent) {
+        // Muda only calls the first handler of menu events it's given, whether by the tray
+        // or by the menu bar, so the selections of either menu can arrive as the other's.
+        #[cfg(feature = "tray")]
+        if !self.menu.contains(&event.id) {
+            let event = crate::tray::TrayMenuEvent { id: event.id };
+            self.handle_tray_menu_event(app_driver, event);
+            return;
+        }
+        self.render_root.edit_root_widget(|root| {
+            let mut driver_ctx = DriverCtx {
+                main_root_widget: root,
+            };
+            app_driver.on_menu(&mut driver_ctx, event);
+        });
+    }
+
+    /// Timing statistics about the frames rendered so far.
+    pub fn frame_stats(&self) -> &FrameStats {
+        self.frame_scheduler.stats()
+    }
+}
+
+/// Start the inspector server on the address in [`INSPECTOR_ADDR_VAR`].
+#[cfg(feature = "inspector")]
+fn start_inspector() -> Option<InspectorServer> {
+    let addr = std::env::var(INSPECTOR_ADDR_VAR)
+        .ok()
+        .filter(|addr| !addr.is_empty())
+        .unwrap_or_else(|| DEFAULT_INSPECTOR_ADDR.to_string());
+    match InspectorServer::bind(&*addr) {
+        Ok(server) => {
+            info!("Inspector server listening on {}", server.local_addr());
+            Some(server)
+        }
+        Err(err) => {
+            warn!("Failed to start the inspector server on {addr}: {err}");
+            None
+        }
+    }
+}
+
+/// The parts of `window` covered by system UI, in logical pixels.
+///
+/// On Android, this is the area outside the content rect, which excludes the soft keyboard.
+/// Returns `None` if it isn't known yet.
+#[cfg(target_os = "android")]
+fn system_ui_insets(window: &Window) -> Option<vello::kurbo::Insets> {
+    use winit::platform::android::WindowExtAndroid;
+
+    let size = window.inner_size();
+    let content = window.content_rect();
+    if content.right <= content.left || content.bottom <= content.top {
+        return None;
+    }
+    let scale_factor = window.scale_factor();
+    Some(vello::kurbo::Insets::new(
+        f64::from(content.left).max(0.) / scale_factor,
+        f64::from(content.top).max(0.) / scale_factor,
+        (f64::from(size.width) - f64::from(content.right)).max(0.) / scale_factor,
+        (f64::from(size.height) - f64::from(content.bottom)).max(0.) / scale_factor,
+    ))
+}
+
+/// The parts of `window` covered by system UI, in logical pixels.
+///
+/// On iOS, this is the area outside the safe area, which winit reports as the inner
+/// position and size of the window.
+#[cfg(target_os = "ios")]
+fn system_ui_insets(window: &Window) -> Option<vello::kurbo::Insets> {
+    let scale_factor = window.scale_factor();
+    let outer = window
+        .outer_position()
+        .ok()?
+        .to_logical::<f64>(scale_factor);
+    let inner = window
+        .inner_position()
+        .ok()?
+        .to_logical::<f64>(scale_factor);
+    let outer_size = window.outer_size().to_logical::<f64>(scale_factor);
+    let inner_size = window.inner_size().to_logical::<f64>(scale_factor);
+    let left = (inner.x - outer.x).max(0.);
+    let top = (inner.y - outer.y).max(0.);
+    Some(vello::kurbo::Insets::new(
+        left,
+        top,
+        (outer_size.width - inner_size.width - left).max(0.),
+        (outer_size.height - inner_size.height - top).max(0.),
+    ))
+}
+
+/// The size of the surface `window` is rendered to.
+fn surface_size(window: &Window) -> PhysicalSize<u32> {
+    // https://github.com/rust-windowing/winit/issues/2308
+    if cfg!(target_os = "ios") {
+        window.outer_size()
+    } else {
+        window.inner_size()
+    }
+}
+
+/// Tell `app_driver` if the placement of `window` changed.
+fn update_window_placement(
+    render_root: &mut RenderRoot,
+    window: &Window,
+    app_driver: &mut dyn AppDriver,
+) {
+    let placement = WindowPlacement::of_window(window, render_root.monitors());
+    if render_root.window_placement() == Some(&placement) {
+        return;
+    }
+    render_root.set_window_placement(placement.clone());
+    let monitors = render_root.monitors().to_vec();
+    render_root.edit_root_widget(|root| {
+        let mut driver_ctx = DriverCtx {
+            main_root_widget: root,
+        };
+        app_driver.on_window_placement(&mut driver_ctx, &placement, &monitors);
+    });
+}
+
+/// How long idle work may run each time the event loop becomes idle, so that events
+/// arriving in the meantime aren't delayed for long.
+const IDLE_BUDGET: Duration = Duration::from_millis(4);
+
+/// The width of the band along the edges of an undecorated window from which it can be
+/// resized, in logical pixels.
+const RESIZE_BORDER: f64 = 5.;
+
+/// The direction in which `window` is resized when dragged from `position`, if the window is
+/// undecorated and `position` is close to its edges.
+fn resize_direction(window: &Window, position: PhysicalPosition<f64>) -> Option<ResizeDirection> {
+    if window.is_decorated() || !window.is_resizable() || window.is_maximized() {
+        return None;
+    }
+    let size = window.inner_size();
+    let border = RESIZE_BORDER * window.scale_factor();
+    let left = position.x < border;
+    let right = position.x > size.width as f64 - border;
+    let top = position.y < border;
+    let bottom = position.y > size.height as f64 - border;
+    match (left, right, top, bottom) {
+        (true, _, true, _) => Some(ResizeDirection::NorthWest),
+        (_, true, true, _) => Some(ResizeDirection::NorthEast),
+        (true, _, _, true) => Some(ResizeDirection::SouthWest),
+        (_, true, _, true) => Some(ResizeDirection::SouthEast),
+        (true, _, _, _) => Some(ResizeDirection::West),
+        (_, true, _, _) => Some(ResizeDirection::East),
+        (_, _, true, _) => Some(ResizeDirection::North),
+        (_, _, _, true) => Some(ResizeDirection::South),
+        _ => None,
+    }
+}
+
+fn resize_cursor(direction: ResizeDirection) -> CursorIcon {
+    match direction {
+        ResizeDirection::East => CursorIcon::EResize,
+        ResizeDirection::North => CursorIcon::NResize,
+        ResizeDirection::NorthEast => CursorIcon::NeResize,
+        ResizeDirection::NorthWest => CursorIcon::NwResize,
+        ResizeDirection::South => CursorIcon::SResize,
+        ResizeDirection::SouthEast => CursorIcon::SeResize,
+        ResizeDirection::SouthWest => CursorIcon::SwResize,
+        ResizeDirection::West => CursorIcon::WResize,
+    }
+}
+
+/// Create the renderer of the device `dev_id`.
+fn new_renderer(
+    render_cx: &RenderContext,
+    dev_id: usize,
+    options: RendererOptions,
+) -> Result<Renderer, vello::Error> {
+    let device_handle = &render_cx.devices[dev_id];
+    #[cfg_attr(not(feature = "tracy"), allow(unused_mut))]
+    let mut renderer = Renderer::new(&device_handle.device, options)?;
+    #[cfg(feature = "tracy")]
+    {
+        let new_profiler = wgpu_profiler::GpuProfiler::new_with_tracy_client(
+            wgpu_profiler::GpuProfilerSettings::default(),
+            device_handle.adapter().get_info().backend,
+            &device_handle.device,
+            &device_handle.queue,
+        )
+        .unwrap_or(renderer.profiler);
+        renderer.profiler = new_profiler;
+    }
+    Ok(renderer)
+}
+
+/// Let the compositor blend the frames rendered to `surface` with what's behind the window.
+fn enable_surface_transparency(render_cx: &RenderContext, surface: &mut RenderSurface<'_>) {
+    let device_handle = &render_cx.devices[surface.dev_id];
+    let alpha_modes = surface
+        .surface
+        .get_capabilities(device_handle.adapter())
+        .alpha_modes;
+    // The frames Vello renders aren't premultiplied
+    let Some(alpha_mode) = [
+        wgpu::CompositeAlphaMode::PostMultiplied,
+        wgpu::CompositeAlphaMode::PreMultiplied,
+    ]
+    .into_iter()
+    .find(|mode| alpha_modes.contains(mode)) else {
+        warn!("The window is transparent, but its surface can only be composited as opaque");
+        return;
+    };
+    surface.config.alpha_mode = alpha_mode;
+    surface
+        .surface
+        .configure(&device_handle.device, &surface.config);
 }
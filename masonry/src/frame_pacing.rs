@@ -0,0 +1,310 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scheduling of rendered frames in the event loop runner.
+
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use wgpu::PresentMode;
+
+/// How many frame durations are averaged in [`FrameStats::average_frame_time`].
+#[cfg_attr(
+    all(not(feature = "event_loop_runner"), not(test)),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+const FRAME_TIME_SMOOTHING: u32 = 16;
+
+/// The refresh interval assumed when the platform doesn't report one (60Hz).
+#[cfg_attr(
+    all(not(feature = "event_loop_runner"), not(test)),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_nanos(16_666_667);
+
+/// Strategies for pacing the frames rendered by the event loop runner.
+///
+/// Whatever the strategy, requests for a redraw or an animation frame which arrive
+/// before the next frame is rendered are coalesced into a single frame.
+/// If rendering a frame takes longer than the frame budget, the runner doesn't try to
+/// catch up: the frames which would have been rendered in the meantime are skipped,
+/// and animations see a larger interval in [`Widget::on_anim_frame`](crate::Widget::on_anim_frame).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FramePacing {
+    /// Render whenever the platform is ready for a new frame, and let presentation
+    /// wait for vertical sync.
+    ///
+    /// This is the default; it never renders more frames than the monitor can display.
+    #[default]
+    Vsync,
+    /// Render at most the given number of frames per second, without waiting for vertical sync.
+    ///
+    /// This can be used to cap the CPU and GPU usage of animation-heavy apps below the
+    /// refresh rate of the monitor. A value of zero is treated as one.
+    MaxFps(u32),
+}
+
+impl FramePacing {
+    /// The present mode the window surface should use with this pacing.
+    pub fn present_mode(self) -> PresentMode {
+        match self {
+            Self::Vsync => PresentMode::AutoVsync,
+            Self::MaxFps(_) => PresentMode::AutoNoVsync,
+        }
+    }
+
+    /// The minimum time between the start of two frames, if any.
+    #[cfg_attr(
+        all(not(feature = "event_loop_runner"), not(test)),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    fn min_frame_interval(self) -> Option<Duration> {
+        match self {
+            Self::Vsync => None,
+            Self::MaxFps(fps) => Some(Duration::from_secs(1) / fps.max(1)),
+        }
+    }
+}
+
+/// Timing statistics about the frames rendered by the event loop runner.
+///
+/// These can be read through [`MasonryState::frame_stats`](crate::event_loop_runner::MasonryState::frame_stats).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    /// The number of frames rendered so far.
+    pub frame_count: u64,
+    /// The number of frames which were skipped because rendering took longer than the frame budget.
+    pub skipped_frames: u64,
+    /// The number of frames which weren't sent to the GPU, because nothing changed since
+    /// the previous frame, which the window still shows.
+    ///
+    /// These are included in [`frame_count`](Self::frame_count).
+    pub unchanged_frames: u64,
+    /// How long it took to produce the most recent frame, from the start of the
+    /// animation pass to the presentation of the frame.
+    pub last_frame_time: Duration,
+    /// A moving average of recent frame times.
+    pub average_frame_time: Duration,
+    /// The time between the start of the two most recent frames.
+    ///
+    /// This is zero for the first frame after the app was idle.
+    pub last_frame_interval: Duration,
+}
+
+/// Decides when the event loop runner should render frames.
+#[cfg_attr(
+    all(not(feature = "event_loop_runner"), not(test)),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+pub(crate) struct FrameScheduler {
+    pacing: FramePacing,
+    /// The refresh interval of the monitor the window is on.
+    refresh_interval: Duration,
+    /// Whether a frame was requested and hasn't been rendered yet.
+    frame_requested: bool,
+    /// Whether the window was asked to redraw, and hasn't done so yet.
+    redraw_requested: bool,
+    /// When the most recent frame started, if the app hasn't been idle since.
+    last_frame_start: Option<Instant>,
+    stats: FrameStats,
+}
+
+#[cfg_attr(
+    all(not(feature = "event_loop_runner"), not(test)),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+impl FrameScheduler {
+    pub(crate) fn new(pacing: FramePacing) -> Self {
+        Self {
+            pacing,
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            frame_requested: false,
+            redraw_requested: false,
+            last_frame_start: None,
+            stats: FrameStats::default(),
+        }
+    }
+
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn pacing(&self) -> FramePacing {
+        self.pacing
+    }
+
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn set_pacing(&mut self, pacing: FramePacing) {
+        self.pacing = pacing;
+    }
+
+    pub(crate) fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Set the refresh rate of the monitor, as reported by the platform.
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn set_refresh_rate_millihertz(&mut self, millihertz: Option<u32>) {
+        self.refresh_interval = match millihertz {
+            Some(millihertz) if millihertz > 0 => Duration::from_secs(1000) / millihertz,
+            _ => DEFAULT_REFRESH_INTERVAL,
+        };
+    }
+
+    /// The time budget for a single frame.
+    fn frame_budget(&self) -> Duration {
+        self.pacing
+            .min_frame_interval()
+            .unwrap_or(self.refresh_interval)
+    }
+
+    /// Record that a new frame is wanted.
+    pub(crate) fn request_frame(&mut self) {
+        self.frame_requested = true;
+    }
+
+    /// Returns whether the window should be asked to redraw now.
+    ///
+    /// If this returns `false` while a frame is requested, the runner should wake up at
+    /// [`next_frame_deadline`](Self::next_frame_deadline) and call this again.
+    pub(crate) fn should_request_redraw(&mut self, now: Instant) -> bool {
+        if !self.frame_requested || self.redraw_requested {
+            return false;
+        }
+        if self
+            .next_frame_deadline()
+            .is_some_and(|deadline| now < deadline)
+        {
+            return false;
+        }
+        self.redraw_requested = true;
+        true
+    }
+
+    /// When the next frame may start, if a frame is waiting on the frame rate cap.
+    pub(crate) fn next_frame_deadline(&self) -> Option<Instant> {
+        if !self.frame_requested || self.redraw_requested {
+            return None;
+        }
+        let interval = self.pacing.min_frame_interval()?;
+        Some(self.last_frame_start? + interval)
+    }
+
+    /// Record the start of a frame.
+    ///
+    /// Returns the time elapsed since the previous frame, or zero if the app was idle.
+    pub(crate) fn begin_frame(&mut self, now: Instant) -> Duration {
+        self.frame_requested = false;
+        self.redraw_requested = false;
+        let interval = self
+            .last_frame_start
+            .map(|last| now.saturating_duration_since(last))
+            .unwrap_or_default();
+        self.last_frame_start = Some(now);
+        self.stats.last_frame_interval = interval;
+        interval
+    }
+
+    /// Record that the current frame wasn't rendered, as it's the same as the previous one.
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn record_unchanged_frame(&mut self) {
+        self.stats.unchanged_frames += 1;
+    }
+
+    /// Record the end of a frame which started at `start`.
+    ///
+    /// `animating` is whether another frame has already been requested by an ongoing animation.
+    pub(crate) fn end_frame(&mut self, start: Instant, now: Instant, animating: bool) {
+        let frame_time = now.saturating_duration_since(start);
+        let budget = self.frame_budget();
+        let stats = &mut self.stats;
+        stats.frame_count += 1;
+        stats.last_frame_time = frame_time;
+        stats.average_frame_time = if stats.frame_count == 1 {
+            frame_time
+        } else {
+            (stats.average_frame_time * (FRAME_TIME_SMOOTHING - 1) + frame_time)
+                / FRAME_TIME_SMOOTHING
+        };
+
+        if animating && !budget.is_zero() && frame_time > budget {
+            let missed = (frame_time.as_nanos() / budget.as_nanos()) as u64;
+            stats.skipped_frames += missed;
+        }
+
+        if !animating {
+            // The next frame won't be part of a continuous animation.
+            self.last_frame_start = None;
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesces_requests() {
+        let mut scheduler = FrameScheduler::new(FramePacing::Vsync);
+        let now = Instant::now();
+
+        assert!(!scheduler.should_request_redraw(now));
+        scheduler.request_frame();
+        scheduler.request_frame();
+        assert!(scheduler.should_request_redraw(now));
+        // The redraw is already pending.
+        scheduler.request_frame();
+        assert!(!scheduler.should_request_redraw(now));
+
+        assert_eq!(scheduler.begin_frame(now), Duration::ZERO);
+        scheduler.end_frame(now, now, false);
+        assert!(!scheduler.should_request_redraw(now));
+        assert_eq!(scheduler.stats().frame_count, 1);
+    }
+
+    #[test]
+    fn max_fps_delays_frames() {
+        let mut scheduler = FrameScheduler::new(FramePacing::MaxFps(10));
+        let start = Instant::now();
+
+        scheduler.request_frame();
+        assert!(scheduler.should_request_redraw(start));
+        scheduler.begin_frame(start);
+        scheduler.end_frame(start, start, true);
+
+        scheduler.request_frame();
+        let deadline = start + Duration::from_millis(100);
+        assert_eq!(scheduler.next_frame_deadline(), Some(deadline));
+        assert!(!scheduler.should_request_redraw(start + Duration::from_millis(50)));
+        assert!(scheduler.should_request_redraw(deadline));
+        assert_eq!(scheduler.begin_frame(deadline), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn slow_frames_are_skipped() {
+        let mut scheduler = FrameScheduler::new(FramePacing::MaxFps(100));
+        let start = Instant::now();
+
+        scheduler.request_frame();
+        scheduler.begin_frame(start);
+        scheduler.end_frame(start, start + Duration::from_millis(35), true);
+
+        let stats = scheduler.stats();
+        assert_eq!(stats.skipped_frames, 3);
+        assert_eq!(stats.last_frame_time, Duration::from_millis(35));
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Choosing the GPU adapter windows are rendered with, and reporting rendering problems.
+//!
+//! The adapter is chosen when the window is first shown, following the [`GpuOptions`] set with
+//! [`MasonryState::set_gpu_options`]. The app is then told which adapter was picked through
+//! [`AppDriver::on_gpu_adapter`], and about the frames which couldn't be rendered through
+//! [`AppDriver::on_render_error`], so that it can show them in a diagnostics view or a bug
+//! report.
+//!
+//! The wgpu environment variables, such as `WGPU_BACKEND`, `WGPU_POWER_PREF` and
+//! `WGPU_ADAPTER_NAME`, take precedence over the options, so that users can work around a
+//! broken driver without rebuilding the app.
+//!
+//! [`MasonryState::set_gpu_options`]: crate::event_loop_runner::MasonryState::set_gpu_options
+//! [`AppDriver::on_gpu_adapter`]: crate::AppDriver::on_gpu_adapter
+//! [`AppDriver::on_render_error`]: crate::AppDriver::on_render_error
+
+use std::fmt;
+
+use wgpu::{AdapterInfo, Backends, Limits, PowerPreference, SurfaceError};
+
+use crate::software_render;
+
+/// How the GPU adapter which renders the window is chosen.
+#[derive(Clone, Debug, Default)]
+pub struct GpuOptions {
+    /// Whether to prefer the integrated or the discrete GPU, on systems which have both.
+    pub power_preference: PowerPreference,
+    /// The graphics APIs the adapter can use, e.g. [`Backends::VULKAN`] to avoid a broken
+    /// OpenGL driver.
+    ///
+    /// By default, the primary backends of the platform are used.
+    pub backends: Option<Backends>,
+    /// Use the first adapter whose name contains this, ignoring case, if there is one.
+    pub adapter_name: Option<String>,
+    /// The limits the adapter has to support.
+    ///
+    /// If it doesn't, Masonry falls back to software rendering, and reports
+    /// [`RenderError::InsufficientLimits`] if the software adapter doesn't either.
+    /// Note that the device is created with the default limits of wgpu in any case.
+    pub required_limits: Option<Limits>,
+    /// Render with a software adapter, even if a GPU is available.
+    ///
+    /// This can also be forced by setting the `MASONRY_SOFTWARE_RENDERING` environment
+    /// variable to `1`.
+    pub force_software: bool,
+}
+
+impl GpuOptions {
+    /// Whether the window is rendered with a software adapter from the start.
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn software_rendering(&self) -> bool {
+        self.force_software || software_render::is_forced()
+    }
+
+    /// Check that an adapter with `limits` supports the [required limits](Self::required_limits).
+    #[cfg_attr(
+        all(not(feature = "event_loop_runner"), not(test)),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn supports(&self, limits: &Limits) -> bool {
+        self.required_limits
+            .as_ref()
+            .map_or(true, |required| required.check_limits(limits))
+    }
+}
+
+/// The adapter a window is rendered with.
+#[derive(Clone, Debug)]
+pub struct GpuInfo {
+    /// The adapter, as described by its driver.
+    pub adapter: AdapterInfo,
+    /// The limits of the adapter.
+    pub limits: Limits,
+    /// Whether Vello runs its compute stages on the CPU, as it does when falling back to
+    /// software rendering.
+    pub software_rendering: bool,
+}
+
+/// A problem which prevented the window from being rendered.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RenderError {
+    /// No adapter could render to the window, even after falling back to software rendering.
+    CreateSurface(vello::Error),
+    /// None of the adapters which could render to the window support the
+    /// [required limits](GpuOptions::required_limits).
+    InsufficientLimits(AdapterInfo),
+    /// Vello couldn't be set up on the device, e.g. because its shaders didn't compile.
+    CreateRenderer(vello::Error),
+    /// A frame couldn't be rendered, and was skipped.
+    Render(vello::Error),
+    /// The texture of the next frame couldn't be acquired from the surface, and the frame was
+    /// skipped.
+    Surface(SurfaceError),
+}
+
+impl RenderError {
+    /// Whether the window can't be rendered at all.
+    ///
+    /// The event loop exits after fatal errors are reported.
+    pub fn is_fatal(&self) -> bool {
+        match self {
+            Self::CreateSurface(_) | Self::InsufficientLimits(_) | Self::CreateRenderer(_) => true,
+            Self::Surface(err) => matches!(err, SurfaceError::OutOfMemory),
+            Self::Render(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateSurface(err) => write!(f, "couldn't create a render surface: {err}"),
+            Self::InsufficientLimits(adapter) => write!(
+                f,
+                "the adapter {} ({:?}) doesn't support the required limits",
+                adapter.name, adapter.backend
+            ),
+            Self::CreateRenderer(err) => write!(f, "couldn't create the renderer: {err}"),
+            Self::Render(err) => write!(f, "couldn't render a frame: {err}"),
+            Self::Surface(err) => write!(f, "couldn't acquire a surface texture: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CreateSurface(err) | Self::CreateRenderer(err) | Self::Render(err) => Some(err),
+            Self::Surface(err) => Some(err),
+            Self::InsufficientLimits(_) => None,
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_limits() {
+        let options = GpuOptions::default();
+        assert!(options.supports(&Limits::downlevel_webgl2_defaults()));
+
+        let options = GpuOptions {
+            required_limits: Some(Limits {
+                max_texture_dimension_2d: 16384,
+                ..Limits::default()
+            }),
+            ..GpuOptions::default()
+        };
+        assert!(!options.supports(&Limits::default()));
+        assert!(options.supports(&Limits {
+            max_texture_dimension_2d: 32768,
+            ..Limits::default()
+        }));
+    }
+
+    #[test]
+    fn fatal_errors() {
+        assert!(RenderError::Surface(SurfaceError::OutOfMemory).is_fatal());
+        assert!(!RenderError::Surface(SurfaceError::Timeout).is_fatal());
+    }
+}
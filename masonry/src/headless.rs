@@ -0,0 +1,291 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rendering widgets to images, without a window.
+//!
+//! This is useful for generating thumbnails on a server, or screenshots for documentation.
+//! [`TestHarness`](crate::testing::TestHarness) renders its snapshots the same way.
+//!
+//! ```no_run
+//! use masonry::headless::{HeadlessOptions, HeadlessRenderer};
+//! use masonry::widget::Label;
+//!
+//! let mut renderer = HeadlessRenderer::new().unwrap();
+//! let image = renderer
+//!     .render_widget(Label::new("Hello"), &HeadlessOptions::default())
+//!     .unwrap();
+//! image.save("hello.png").unwrap();
+//! ```
+
+use std::num::NonZeroUsize;
+
+use vello::kurbo::Affine;
+use vello::util::{block_on_wgpu, RenderContext};
+use vello::{RendererOptions, Scene};
+use wgpu::{
+    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, Extent3d, ImageCopyBuffer,
+    Queue, TextureDescriptor, TextureFormat, TextureUsages,
+};
+
+pub use image::RgbaImage;
+
+use crate::dpi::PhysicalSize;
+use crate::event::WindowEvent;
+use crate::render_root::{RenderRoot, RenderRootOptions, WindowSizePolicy};
+use crate::software_render;
+use crate::{Color, Size, Widget};
+
+/// The settings of a headless render.
+#[derive(Clone, Debug)]
+pub struct HeadlessOptions {
+    /// The size of the area the widgets are laid out in, in logical pixels.
+    pub size: Size,
+    /// The number of image pixels per logical pixel.
+    pub scale_factor: f64,
+    /// The color drawn behind the widgets.
+    pub background_color: Color,
+    /// Whether fonts installed on the system can be used, as well as the registered fonts.
+    ///
+    /// Disable this to get the same output on every machine.
+    pub use_system_fonts: bool,
+    /// The fonts to register before laying out the widgets, as raw font file data.
+    pub fonts: Vec<Vec<u8>>,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            size: Size::new(400., 400.),
+            scale_factor: 1.0,
+            background_color: Color::BLACK,
+            use_system_fonts: true,
+            fonts: Vec::new(),
+        }
+    }
+}
+
+/// An error from a [`HeadlessRenderer`].
+#[derive(Debug)]
+pub enum HeadlessError {
+    /// No GPU device compatible with Vello could be found.
+    NoCompatibleDevice,
+    /// Vello failed to create its renderer or to render the scene.
+    Render(vello::Error),
+}
+
+impl std::fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoCompatibleDevice => f.write_str("no compatible GPU device found"),
+            Self::Render(err) => write!(f, "rendering failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for HeadlessError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NoCompatibleDevice => None,
+            Self::Render(err) => Some(err),
+        }
+    }
+}
+
+impl From<vello::Error> for HeadlessError {
+    fn from(err: vello::Error) -> Self {
+        Self::Render(err)
+    }
+}
+
+/// Renders widgets to images without a window.
+///
+/// Creating the renderer initializes the GPU device, so it should be reused for several images.
+pub struct HeadlessRenderer {
+    context: RenderContext,
+    device_id: usize,
+    renderer: vello::Renderer,
+}
+
+impl HeadlessRenderer {
+    /// Create a renderer using the first compatible GPU device.
+    ///
+    /// If there is none, this falls back to a software adapter, if one is installed.
+    pub fn new() -> Result<Self, HeadlessError> {
+        let mut context = if software_render::is_forced() {
+            software_render::render_context()
+        } else {
+            RenderContext::new()
+        };
+        let mut device_id = pollster::block_on(context.device(None));
+        if device_id.is_none() && !software_render::is_forced() {
+            context = software_render::render_context();
+            device_id = pollster::block_on(context.device(None));
+        }
+        let device_id = device_id.ok_or(HeadlessError::NoCompatibleDevice)?;
+        let renderer = vello::Renderer::new(
+            &context.devices[device_id].device,
+            RendererOptions {
+                surface_format: None,
+                // TODO - Examine this value
+                use_cpu: true,
+                num_init_threads: NonZeroUsize::new(1),
+                // TODO - Examine this value
+                antialiasing_support: vello::AaSupport::area_only(),
+            },
+        )?;
+        Ok(Self {
+            context,
+            device_id,
+            renderer,
+        })
+    }
+
+    /// Lay out `root_widget` and render it to an image.
+    ///
+    /// The image is `options.size` multiplied by `options.scale_factor`, rounded to whole pixels.
+    pub fn render_widget(
+        &mut self,
+        root_widget: impl Widget,
+        options: &HeadlessOptions,
+    ) -> Result<RgbaImage, HeadlessError> {
+        let mut render_root = RenderRoot::new(
+            root_widget,
+            RenderRootOptions {
+                use_system_fonts: options.use_system_fonts,
+                size_policy: WindowSizePolicy::User,
+                scale_factor: options.scale_factor,
+                test_font: None,
+            },
+        );
+        for font in &options.fonts {
+            drop(render_root.register_fonts(font.clone()));
+        }
+        let size = PhysicalSize::new(
+            (options.size.width * options.scale_factor).round() as u32,
+            (options.size.height * options.scale_factor).round() as u32,
+        );
+        render_root.handle_window_event(WindowEvent::Resize(size));
+        self.render_root(&mut render_root, options.background_color)
+    }
+
+    /// Paint the widgets of `render_root` and render them to an image the size of its window.
+    pub fn render_root(
+        &mut self,
+        render_root: &mut RenderRoot,
+        background_color: Color,
+    ) -> Result<RgbaImage, HeadlessError> {
+        let (scene, _tree_update) = render_root.redraw();
+        let scale_factor = render_root.scale_factor;
+        let PhysicalSize { width, height } = render_root.size;
+        if scale_factor == 1.0 {
+            self.render_scene(&scene, width, height, background_color)
+        } else {
+            let mut scaled = Scene::new();
+            scaled.append(&scene, Some(Affine::scale(scale_factor)));
+            self.render_scene(&scaled, width, height, background_color)
+        }
+    }
+
+    /// Render `scene` to an image of `width` by `height` pixels.
+    pub fn render_scene(
+        &mut self,
+        scene: &Scene,
+        width: u32,
+        height: u32,
+        background_color: Color,
+    ) -> Result<RgbaImage, HeadlessError> {
+        let device_handle = &self.context.devices[self.device_id];
+        let render_params = vello::RenderParams {
+            base_color: background_color,
+            width,
+            height,
+            antialiasing_method: vello::AaConfig::Area,
+        };
+        let image = render_to_image(
+            &device_handle.device,
+            &device_handle.queue,
+            &mut self.renderer,
+            scene,
+            &render_params,
+        )?;
+        Ok(image)
+    }
+}
+
+/// Render `scene` with `renderer` to an image, and read it back from the GPU.
+pub(crate) fn render_to_image(
+    device: &Device,
+    queue: &Queue,
+    renderer: &mut vello::Renderer,
+    scene: &Scene,
+    render_params: &vello::RenderParams,
+) -> Result<RgbaImage, vello::Error> {
+    let (width, height) = (render_params.width, render_params.height);
+    let size = Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let target = device.create_texture(&TextureDescriptor {
+        label: Some("Target texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: TextureFormat::Rgba8Unorm,
+        usage: TextureUsages::STORAGE_BINDING | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+    renderer.render_to_texture(device, queue, scene, &view, render_params)?;
+    let padded_byte_width = (width * 4).next_multiple_of(256);
+    let buffer_size = padded_byte_width as u64 * height as u64;
+    let buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("val"),
+        size: buffer_size,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("Copy out buffer"),
+    });
+    encoder.copy_texture_to_buffer(
+        target.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_byte_width),
+                rows_per_image: None,
+            },
+        },
+        size,
+    );
+
+    queue.submit([encoder.finish()]);
+    let buf_slice = buffer.slice(..);
+
+    let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
+    buf_slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
+    let recv_result = block_on_wgpu(device, receiver.receive()).expect("channel was closed");
+    recv_result.expect("failed to map buffer");
+
+    let data = buf_slice.get_mapped_range();
+    let mut result_unpadded = Vec::<u8>::with_capacity((width * height * 4).try_into().unwrap());
+    for row in 0..height {
+        let start = (row * padded_byte_width).try_into().unwrap();
+        result_unpadded.extend(&data[start..start + (width * 4) as usize]);
+    }
+
+    Ok(RgbaImage::from_vec(width, height, result_unpadded).expect("failed to create image"))
+}
+
+/// Encode `image` as a PNG file.
+#[cfg(feature = "png")]
+pub fn encode_png(image: &RgbaImage) -> Vec<u8> {
+    let mut png = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageFormat::Png)
+        .expect("encoding to memory can't fail");
+    png.into_inner()
+}
@@ -0,0 +1,274 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Running Masonry apps in event loops other than the one of winit.
+//!
+//! An [`AppHost`] owns the loop which drives an app: it feeds input to the widgets, routes
+//! their actions to the [`AppDriver`], and renders them when they change. The default host is
+//! the winit event loop runner, [`WinitHost`](crate::event_loop_runner::WinitHost), which is
+//! enabled by the `event_loop_runner` feature.
+//!
+//! Other hosts, such as the event loop of a game engine or the frame loop of a game,
+//! usually wrap an [`EmbeddedUi`](crate::embed::EmbeddedUi), which renders the widgets to a
+//! texture the host owns. Such a host:
+//!
+//! - Calls [`AppDriver::on_host_start`] once it has created the widgets.
+//! - Delivers the actions sent through its [`HostProxy`] to the driver, e.g. with
+//!   [`EmbeddedUi::handle_action`](crate::embed::EmbeddedUi::handle_action), along with the
+//!   actions emitted by the widgets.
+//!
+//! Hosts which check for work on each frame can use an [`ActionQueue`] as their proxy.
+//!
+//! # Examples
+//!
+//! A host which runs the app for a number of frames, without a window:
+//!
+//! ```
+//! use std::convert::Infallible;
+//! use std::sync::Arc;
+//!
+//! use masonry::host::{ActionQueue, AppHost, HostProxy};
+//! use masonry::{AppDriver, Color, DriverCtx, RenderRoot, RenderRootOptions, Widget, WindowSizePolicy};
+//!
+//! struct FrameLoop {
+//!     actions: Arc<ActionQueue>,
+//!     frames: usize,
+//! }
+//!
+//! impl AppHost for FrameLoop {
+//!     type Error = Infallible;
+//!
+//!     fn proxy(&self) -> Arc<dyn HostProxy> {
+//!         self.actions.clone()
+//!     }
+//!
+//!     fn run(
+//!         self,
+//!         root_widget: impl Widget,
+//!         mut app_driver: impl AppDriver + 'static,
+//!         _background_color: Color,
+//!     ) -> Result<(), Infallible> {
+//!         let mut render_root = RenderRoot::new(
+//!             root_widget,
+//!             RenderRootOptions {
+//!                 use_system_fonts: false,
+//!                 size_policy: WindowSizePolicy::User,
+//!                 scale_factor: 1.0,
+//!                 test_font: None,
+//!             },
+//!         );
+//!         app_driver.on_host_start(&mut render_root);
+//!         for _ in 0..self.frames {
+//!             while let Some((action, widget_id)) = self.actions.pop() {
+//!                 render_root.edit_root_widget(|root| {
+//!                     let mut ctx = DriverCtx { main_root_widget: root };
+//!                     app_driver.on_action(&mut ctx, widget_id, action);
+//!                 });
+//!             }
+//!             // Input would be given to the widgets here.
+//!             let (_scene, _tree_update) = render_root.redraw();
+//!             // And the scene would be rendered here.
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use vello::peniko::Color;
+
+use crate::{Action, AppDriver, Widget, WidgetId};
+
+/// The loop which runs a Masonry app.
+///
+/// See the [module docs](self) for details.
+pub trait AppHost {
+    /// The error returned if the app can't be run.
+    type Error;
+
+    /// A proxy which can be used from other threads to send actions to the app, e.g. once
+    /// async work completes.
+    fn proxy(&self) -> Arc<dyn HostProxy>;
+
+    /// Run `root_widget` until the app exits, routing its actions to `app_driver`.
+    ///
+    /// Implementations call [`AppDriver::on_host_start`] before the first frame, unless they
+    /// call [`AppDriver::on_start`] instead, as the winit event loop runner does.
+    ///
+    /// Some hosts, such as the web browser, run their loop after this returns.
+    fn run(
+        self,
+        root_widget: impl Widget,
+        app_driver: impl AppDriver + 'static,
+        background_color: Color,
+    ) -> Result<(), Self::Error>;
+}
+
+/// A handle to send actions to an app run by an [`AppHost`], from any thread.
+pub trait HostProxy: Send + Sync + 'static {
+    /// Send `action` to the [`AppDriver`] of the app, as if it had been emitted by the
+    /// widget `widget_id`, and wake the host up if it's waiting for events.
+    ///
+    /// # Errors
+    ///
+    /// Returns the action if the app has exited.
+    fn send_action(&self, action: Action, widget_id: WidgetId) -> Result<(), Action>;
+}
+
+/// A [`HostProxy`] which queues the actions, for hosts which check for them on each frame.
+#[derive(Default)]
+pub struct ActionQueue {
+    actions: Mutex<VecDeque<(Action, WidgetId)>>,
+    closed: AtomicBool,
+    wake: Option<Box<dyn Fn() + Send + Sync>>,
+}
+
+impl ActionQueue {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty queue which calls `wake` after each action is queued, e.g. to request
+    /// a frame from a host which only runs frames when needed.
+    pub fn with_waker(wake: impl Fn() + Send + Sync + 'static) -> Self {
+        Self {
+            wake: Some(Box::new(wake)),
+            ..Self::default()
+        }
+    }
+
+    /// Take the oldest action in the queue, with the id of the widget it was sent as.
+    pub fn pop(&self) -> Option<(Action, WidgetId)> {
+        self.actions.lock().unwrap().pop_front()
+    }
+
+    /// Reject the actions sent from now on, and drop the queued ones, e.g. once the app has exited.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.actions.lock().unwrap().clear();
+    }
+}
+
+impl HostProxy for ActionQueue {
+    fn send_action(&self, action: Action, widget_id: WidgetId) -> Result<(), Action> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(action);
+        }
+        self.actions.lock().unwrap().push_back((action, widget_id));
+        if let Some(wake) = &self.wake {
+            wake();
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ActionQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActionQueue")
+            .field("actions", &self.actions.lock().unwrap().len())
+            .field("closed", &self.closed.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::convert::Infallible;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::widget::SizedBox;
+    use crate::{DriverCtx, RenderRoot, RenderRootOptions, WindowSizePolicy};
+
+    /// A host which runs a fixed number of frames.
+    struct FrameLoop {
+        actions: Arc<ActionQueue>,
+        frames: usize,
+    }
+
+    impl AppHost for FrameLoop {
+        type Error = Infallible;
+
+        fn proxy(&self) -> Arc<dyn HostProxy> {
+            self.actions.clone()
+        }
+
+        fn run(
+            self,
+            root_widget: impl Widget,
+            mut app_driver: impl AppDriver + 'static,
+            _background_color: Color,
+        ) -> Result<(), Infallible> {
+            let mut render_root = RenderRoot::new(
+                root_widget,
+                RenderRootOptions {
+                    use_system_fonts: false,
+                    size_policy: WindowSizePolicy::User,
+                    scale_factor: 1.0,
+                    test_font: None,
+                },
+            );
+            app_driver.on_host_start(&mut render_root);
+            for _ in 0..self.frames {
+                while let Some((action, widget_id)) = self.actions.pop() {
+                    render_root.edit_root_widget(|root| {
+                        let mut ctx = DriverCtx {
+                            main_root_widget: root,
+                        };
+                        app_driver.on_action(&mut ctx, widget_id, action);
+                    });
+                }
+                let _ = render_root.redraw();
+            }
+            self.actions.close();
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Driver {
+        started: Rc<Cell<bool>>,
+        received: Rc<Cell<Option<u32>>>,
+    }
+
+    impl AppDriver for Driver {
+        fn on_action(&mut self, _ctx: &mut DriverCtx<'_>, _widget_id: WidgetId, action: Action) {
+            self.received.set(action.downcast::<u32>().ok());
+        }
+
+        fn on_host_start(&mut self, _render_root: &mut RenderRoot) {
+            self.started.set(true);
+        }
+    }
+
+    #[test]
+    fn actions_sent_from_other_threads() {
+        let host = FrameLoop {
+            actions: Arc::new(ActionQueue::new()),
+            frames: 2,
+        };
+        let proxy = host.proxy();
+        std::thread::spawn(move || proxy.send_action(Action::typed(42_u32), WidgetId::next()))
+            .join()
+            .unwrap()
+            .unwrap();
+
+        let driver = Driver::default();
+        let (started, received) = (driver.started.clone(), driver.received.clone());
+        let proxy = host.proxy();
+        host.run(SizedBox::empty(), driver, Color::BLACK).unwrap();
+        assert!(started.get());
+        assert_eq!(received.get(), Some(42));
+
+        // The app has exited
+        assert!(proxy
+            .send_action(Action::typed(0_u32), WidgetId::next())
+            .is_err());
+    }
+}
@@ -0,0 +1,217 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured dumps of the widget tree, for debugging tools.
+//!
+//! [`WidgetNode`] describes a widget and its descendants, and can be serialized as JSON.
+//!
+//! With the `inspector` feature, the event loop runner also runs an [`InspectorServer`],
+//! which external tools can connect to in order to browse the live widget tree.
+//! It listens on the address in the `MASONRY_INSPECTOR_ADDR` environment variable,
+//! or on [`DEFAULT_INSPECTOR_ADDR`] if it isn't set.
+//!
+//! The protocol is plain TCP: the server sends each client one [`InspectorMessage`] per line,
+//! as JSON. Clients are sent the current tree when they connect, and a new one each time a
+//! frame with a different tree is rendered. A tool such as
+//! [`websocat`](https://github.com/vi/websocat) can forward the messages to a WebSocket, for
+//! inspectors running in a browser.
+
+use serde::{Deserialize, Serialize};
+
+use crate::widget::WidgetRef;
+use crate::Widget;
+
+/// A widget and its descendants, as shown to debugging tools.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WidgetNode {
+    /// The id of the widget.
+    pub id: u64,
+    /// The name of the widget's type, without its module path.
+    pub type_name: String,
+    /// The widget's [debug tag](crate::WidgetPod::set_debug_tag), if any.
+    pub debug_tag: Option<String>,
+    /// The widget's [debug text](Widget::get_debug_text), if any.
+    pub debug_text: Option<String>,
+    /// The layout rectangle of the widget in window coordinates, as `[x0, y0, x1, y1]`.
+    pub layout_rect: [f64; 4],
+    /// The offset of the baseline relative to the bottom of the widget.
+    pub baseline_offset: f64,
+    pub is_hovered: bool,
+    pub is_focused: bool,
+    pub is_disabled: bool,
+    pub is_stashed: bool,
+    pub accepts_pointer_interaction: bool,
+    pub accepts_focus: bool,
+    pub accepts_text_input: bool,
+    /// The children of the widget, in order.
+    pub children: Vec<WidgetNode>,
+}
+
+impl WidgetNode {
+    /// Describe `widget` and its descendants.
+    pub fn new(widget: WidgetRef<'_, dyn Widget>) -> Self {
+        let ctx = widget.ctx();
+        let rect = ctx.window_layout_rect();
+        Self {
+            id: widget.id().into(),
+            type_name: widget.short_type_name().to_string(),
+            debug_tag: ctx.debug_tag().map(str::to_string),
+            debug_text: widget.get_debug_text(),
+            layout_rect: [rect.x0, rect.y0, rect.x1, rect.y1],
+            baseline_offset: ctx.baseline_offset(),
+            is_hovered: ctx.is_hovered(),
+            is_focused: ctx.is_focused(),
+            is_disabled: ctx.is_disabled(),
+            is_stashed: ctx.is_stashed(),
+            accepts_pointer_interaction: ctx.accepts_pointer_interaction(),
+            accepts_focus: ctx.accepts_focus(),
+            accepts_text_input: ctx.accepts_text_input(),
+            children: widget.children().into_iter().map(Self::new).collect(),
+        }
+    }
+
+    /// Serialize the tree as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("widget trees can always be serialized")
+    }
+}
+
+/// The messages sent by an [`InspectorServer`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InspectorMessage {
+    /// The current widget tree.
+    Tree { root: WidgetNode },
+}
+
+#[cfg(feature = "inspector")]
+pub use server::{InspectorServer, DEFAULT_INSPECTOR_ADDR, INSPECTOR_ADDR_VAR};
+
+#[cfg(feature = "inspector")]
+mod server {
+    use std::io::Write;
+    use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+    use std::sync::{Arc, Mutex};
+
+    use super::{InspectorMessage, WidgetNode};
+
+    /// The environment variable with the address the inspector server listens on.
+    pub const INSPECTOR_ADDR_VAR: &str = "MASONRY_INSPECTOR_ADDR";
+
+    /// The address the inspector server listens on by default.
+    pub const DEFAULT_INSPECTOR_ADDR: &str = "127.0.0.1:9470";
+
+    #[derive(Default)]
+    struct Shared {
+        clients: Vec<TcpStream>,
+        /// The last message sent, for new clients.
+        last_message: Option<String>,
+    }
+
+    /// Streams the widget tree to the inspectors connected to it.
+    ///
+    /// See the [module docs](super) for the protocol.
+    pub struct InspectorServer {
+        local_addr: SocketAddr,
+        shared: Arc<Mutex<Shared>>,
+    }
+
+    impl InspectorServer {
+        /// Listen for inspectors on `addr`.
+        ///
+        /// Connections are accepted on a background thread.
+        pub fn bind(addr: impl ToSocketAddrs) -> std::io::Result<Self> {
+            let listener = TcpListener::bind(addr)?;
+            let local_addr = listener.local_addr()?;
+            let shared = Arc::new(Mutex::new(Shared::default()));
+            let accept_shared = shared.clone();
+            std::thread::Builder::new()
+                .name("masonry-inspector".into())
+                .spawn(move || {
+                    for stream in listener.incoming() {
+                        let mut stream = match stream {
+                            Ok(stream) => stream,
+                            Err(err) => {
+                                tracing::warn!("Inspector connection failed: {err}");
+                                continue;
+                            }
+                        };
+                        let mut shared = accept_shared.lock().unwrap();
+                        if let Some(message) = &shared.last_message {
+                            if stream.write_all(message.as_bytes()).is_err() {
+                                continue;
+                            }
+                        }
+                        shared.clients.push(stream);
+                    }
+                })?;
+            Ok(Self { local_addr, shared })
+        }
+
+        /// The address the server listens on.
+        pub fn local_addr(&self) -> SocketAddr {
+            self.local_addr
+        }
+
+        /// Send the widget tree `root` to the connected inspectors, if it changed since
+        /// the last call.
+        pub fn publish(&self, root: WidgetNode) {
+            let message = InspectorMessage::Tree { root };
+            let mut message = serde_json::to_string(&message).unwrap();
+            message.push('\n');
+
+            let mut shared = self.shared.lock().unwrap();
+            if shared.last_message.as_ref() == Some(&message) {
+                return;
+            }
+            // Inspectors which disconnected are dropped
+            shared
+                .clients
+                .retain_mut(|client| client.write_all(message.as_bytes()).is_ok());
+            shared.last_message = Some(message);
+        }
+    }
+
+    impl std::fmt::Debug for InspectorServer {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("InspectorServer")
+                .field("local_addr", &self.local_addr)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Button, Flex, Label};
+
+    #[test]
+    fn dump_widget_tree() {
+        let widget = Flex::column()
+            .with_child(Label::new("Hello"))
+            .with_child(Button::new("Click"));
+        let harness = TestHarness::create(widget);
+        let root = WidgetNode::new(harness.root_widget());
+
+        assert_eq!(root.type_name, "Flex");
+        let [label, button] = &root.children[..] else {
+            panic!("expected two children, got {:?}", root.children);
+        };
+        assert_eq!(label.type_name, "Label");
+        assert_eq!(label.debug_text.as_deref(), Some("Hello"));
+        assert!(!label.accepts_focus);
+        assert_eq!(button.type_name, "Button");
+        assert!(button.accepts_focus);
+        assert!(label.layout_rect[3] <= button.layout_rect[1]);
+
+        let message = InspectorMessage::Tree { root };
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(json.starts_with(r#"{"type":"tree","root":{"id":"#));
+        assert_eq!(
+            serde_json::from_str::<InspectorMessage>(&json).unwrap(),
+            message
+        );
+    }
+}
@@ -81,8 +81,27 @@
 //!
 //! The following feature flags are available:
 //!
+//! - `event_loop_runner` (enabled by default): Adds the [`event_loop_runner`] module, which runs apps in a
+//!   [winit] event loop. Without it, apps are run by the hosts of the [`host`] module, such as the event loop of a game engine.
 //! - `tracy`: Enables creating output for the [Tracy](https://github.com/wolfpld/tracy) profiler using [`tracing-tracy`][tracing_tracy].
 //!   This can be used by installing Tracy and connecting to a Masonry with this feature enabled.
+//! - `parallel_text`: Shapes the text of widgets which are about to be laid out on a [rayon](https://crates.io/crates/rayon) thread pool.
+//!   This speeds up layout of text-heavy apps; see [`Widget::take_text_layout_job`].
+//!   [`Label`](widget::Label) and [`TextArea`](widget::TextArea), and so [`Prose`](widget::Prose) and
+//!   [`Textbox`](widget::Textbox), take part; other widgets still shape their text during layout.
+//! - `profiling`: Emits a zone for each pass and marks the end of each frame using the [`profiling`](https://crates.io/crates/profiling) crate.
+//!   The profiler (e.g. Tracy or puffin) is chosen by enabling the matching `profile-with-*` feature of `profiling` in your app.
+//!   GPU timestamps from Vello are only reported to Tracy, using the `tracy` feature.
+//! - `png`: Adds [`headless::encode_png`], to encode the images rendered without a window as PNG files.
+//! - `capture`: Adds the [`capture`] module, to capture frames to disk with a keyboard shortcut, to debug rendering issues.
+//! - `inspector`: Runs an [`inspector::InspectorServer`] alongside the event loop, which streams the widget tree
+//!   as JSON to external inspector tools.
+//! - `webview`: Adds the [`widget::WebView`] widget, which shows web content in a native webview
+//!   using [wry](https://crates.io/crates/wry).
+//! - `tray`: Adds the [`tray`] module, to show an icon with a menu in the system tray.
+//! - `menu`: Adds the [`menu`] module, to show a menu bar in the window on Windows and macOS.
+//! - `notifications`: Adds the [`notification`] module, to post desktop notifications.
+//! - `regex`: Lets a [`text::FindPattern`] be a regular expression, using [regex](https://crates.io/crates/regex).
 //!
 //! [winit]: https://crates.io/crates/winit
 //! [Druid]: https://crates.io/crates/druid
@@ -158,15 +177,49 @@ mod app_driver;
 mod box_constraints;
 mod contexts;
 mod event;
+mod frame_pacing;
 mod paint_scene_helpers;
 mod passes;
+#[cfg(feature = "event_loop_runner")]
+mod popup_windows;
+mod profiler;
+#[cfg(feature = "event_loop_runner")]
+mod render_context;
 mod render_root;
+mod replay;
+mod software_render;
 mod tracing_backend;
+#[cfg_attr(
+    not(feature = "event_loop_runner"),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+mod viewport_compositor;
+#[cfg(feature = "webview")]
+mod web_views;
 
+#[cfg(feature = "capture")]
+pub mod capture;
+pub mod command;
+pub mod embed;
+#[cfg(feature = "event_loop_runner")]
 pub mod event_loop_runner;
+pub mod gpu;
+pub mod headless;
+pub mod host;
+pub mod inspector;
+#[cfg(feature = "menu")]
+pub mod menu;
+pub mod monitor;
+#[cfg(feature = "notifications")]
+pub mod notification;
+pub mod popup;
+pub mod resource_cache;
+pub mod selection;
 pub mod testing;
 pub mod text;
 pub mod theme;
+#[cfg(feature = "tray")]
+pub mod tray;
 pub mod widget;
 
 pub use cursor_icon;
@@ -181,21 +234,29 @@ pub use parley::layout::Alignment as TextAlignment;
 pub use parley::style::FontWeight;
 pub use vello::peniko::{Color, Gradient};
 
-pub use action::Action;
-pub use app_driver::{AppDriver, DriverCtx};
+pub use action::{Action, ActionSource};
+pub use app_driver::{ActionRouter, AppDriver, DriverCtx, RouteKey};
 pub use box_constraints::BoxConstraints;
 pub use contexts::{
     AccessCtx, ComposeCtx, EventCtx, IsContext, LayoutCtx, MutateCtx, PaintCtx, QueryCtx,
     RawWrapper, RawWrapperMut, RegisterCtx, UpdateCtx,
 };
 pub use event::{
-    AccessEvent, PointerButton, PointerEvent, PointerState, TextEvent, Update, WindowEvent,
-    WindowTheme,
+    AccessEvent, PointerButton, PointerEvent, PointerState, ScrollDelta, TextEvent, TimerToken,
+    Update, WindowEvent, WindowTheme,
 };
+pub use frame_pacing::{FramePacing, FrameStats};
 pub use paint_scene_helpers::UnitPoint;
-pub use render_root::{RenderRoot, RenderRootOptions, RenderRootSignal, WindowSizePolicy};
+pub use profiler::{FrameProfile, PassTimings, WidgetTypeTimings};
+pub use render_root::{
+    RenderCacheStats, RenderRoot, RenderRootOptions, RenderRootSignal, WindowSizePolicy,
+};
+pub use replay::{
+    ImeEvent, RecordedEvent, Session, SessionEvent, TouchPhase, RECORD_SESSION_VAR,
+    REPLAY_SESSION_VAR,
+};
 pub use util::{AsAny, Handled};
 pub use widget::widget::{AllowRawMut, Widget, WidgetId};
-pub use widget::WidgetPod;
+pub use widget::{HitTest, Visibility, WidgetPod};
 
 pub(crate) use widget::WidgetState;
@@ -0,0 +1,394 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The menu bar of the window.
+//!
+//! The menu is set with [`MutateCtx::set_menu`](crate::MutateCtx::set_menu), and can be set
+//! again whenever its entries change, e.g. to disable an item. Entries which keep their place
+//! are updated in the native menu, rather than the whole menu being recreated. When the user
+//! selects an item, [`AppDriver::on_menu`](crate::AppDriver::on_menu) is called with the
+//! item's id.
+//!
+//! The menu is shown in the window on Windows, and as the menu bar of the app on macOS, where
+//! its first submenu is the application menu. On other platforms, setting the menu does
+//! nothing: on Linux, native menus can only be added to GTK windows, which winit doesn't
+//! create. Apps should make the commands of their menu reachable some other way too, such as
+//! with keyboard shortcuts.
+
+use tracing::warn;
+use winit::window::Window;
+
+use crate::event_loop_runner::EventLoopProxy;
+
+/// An entry of the menu bar, or of one of its submenus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MenuEntry {
+    /// An item which can be selected.
+    Item {
+        /// The id given to [`AppDriver::on_menu`](crate::AppDriver::on_menu).
+        id: String,
+        /// The text of the item.
+        text: String,
+        /// Whether the item can be selected.
+        enabled: bool,
+    },
+    /// A submenu, shown when its title is selected.
+    Submenu {
+        /// The title of the submenu.
+        text: String,
+        /// Whether the submenu can be opened.
+        enabled: bool,
+        /// The entries of the submenu.
+        entries: Vec<MenuEntry>,
+    },
+    /// A line between groups of items.
+    Separator,
+}
+
+impl MenuEntry {
+    /// An enabled item.
+    pub fn item(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::Item {
+            id: id.into(),
+            text: text.into(),
+            enabled: true,
+        }
+    }
+
+    /// An enabled submenu.
+    pub fn submenu(text: impl Into<String>, entries: impl IntoIterator<Item = Self>) -> Self {
+        Self::Submenu {
+            text: text.into(),
+            enabled: true,
+            entries: entries.into_iter().collect(),
+        }
+    }
+
+    /// Builder-style method to set whether this item or submenu can be selected.
+    ///
+    /// This has no effect on separators.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        match &mut self {
+            Self::Item { enabled: value, .. } | Self::Submenu { enabled: value, .. } => {
+                *value = enabled;
+            }
+            Self::Separator => (),
+        }
+        self
+    }
+}
+
+/// The selection of an item of the menu bar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MenuEvent {
+    /// The id of the selected [item](MenuEntry::Item).
+    pub id: String,
+}
+
+/// The menu bar of the window, as last set.
+#[derive(Default)]
+pub(crate) struct MenuBar {
+    entries: Vec<MenuEntry>,
+    native: Option<native::NativeMenu>,
+}
+
+impl MenuBar {
+    /// Show `entries` in the menu bar of `window`, whose selections are sent to `proxy`.
+    pub(crate) fn set(&mut self, window: &Window, entries: Vec<MenuEntry>, proxy: &EventLoopProxy) {
+        if entries == self.entries {
+            return;
+        }
+        match &self.native {
+            // Items keep their state, e.g. whether a submenu is open, when only their text
+            // and whether they're enabled change.
+            Some(native) if same_shape(&self.entries, &entries) => {
+                native.update(&self.entries, &entries);
+            }
+            _ => {
+                if let Some(native) = self.native.take() {
+                    native.remove(window);
+                }
+                if !entries.is_empty() {
+                    match native::NativeMenu::new(window, &entries, proxy) {
+                        Ok(native) => self.native = Some(native),
+                        Err(err) => warn!("Failed to set the menu of the window: {err}"),
+                    }
+                }
+            }
+        }
+        self.entries = entries;
+    }
+
+    /// Whether the menu has an item with this id.
+    pub(crate) fn contains(&self, id: &str) -> bool {
+        contains(&self.entries, id)
+    }
+}
+
+/// Whether the entries only differ by their text and whether they're enabled.
+fn same_shape(a: &[MenuEntry], b: &[MenuEntry]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| match (a, b) {
+            (MenuEntry::Item { id: a, .. }, MenuEntry::Item { id: b, .. }) => a == b,
+            (MenuEntry::Submenu { entries: a, .. }, MenuEntry::Submenu { entries: b, .. }) => {
+                same_shape(a, b)
+            }
+            (MenuEntry::Separator, MenuEntry::Separator) => true,
+            _ => false,
+        })
+}
+
+fn contains(entries: &[MenuEntry], id: &str) -> bool {
+    entries.iter().any(|entry| match entry {
+        MenuEntry::Item { id: item_id, .. } => item_id == id,
+        MenuEntry::Submenu { entries, .. } => contains(entries, id),
+        MenuEntry::Separator => false,
+    })
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+#[allow(
+    unsafe_code,
+    reason = "Muda can only add menus to Win32 windows through their raw handle"
+)]
+mod native {
+    use muda::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+    use winit::window::Window;
+
+    use super::{MenuEntry, MenuEvent};
+    use crate::event_loop_runner::{EventLoopProxy, MasonryUserEvent};
+
+    /// The menu shown by the platform, along with its entries.
+    pub(super) struct NativeMenu {
+        menu: Menu,
+        entries: Vec<NativeEntry>,
+        /// The window the menu was added to.
+        #[cfg(target_os = "windows")]
+        hwnd: isize,
+    }
+
+    enum NativeEntry {
+        Item(MenuItem),
+        Submenu(Submenu, Vec<NativeEntry>),
+        Separator,
+    }
+
+    impl NativeMenu {
+        /// Add a menu with `entries` to `window`.
+        pub(super) fn new(
+            window: &Window,
+            entries: &[MenuEntry],
+            proxy: &EventLoopProxy,
+        ) -> Result<Self, Box<dyn std::error::Error>> {
+            let menu = Menu::new();
+            let entries = build(entries, &|item| menu.append(item))?;
+
+            // Muda keeps the first handler it's given, which the tray menu may have set.
+            // The event loop runner tells the selections of both menus apart by their id.
+            let proxy = proxy.clone();
+            muda::MenuEvent::set_event_handler(Some(move |event: muda::MenuEvent| {
+                let event = MenuEvent { id: event.id.0 };
+                let _ = proxy.send_event(MasonryUserEvent::Menu(event));
+            }));
+
+            #[cfg(target_os = "windows")]
+            {
+                use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+                let RawWindowHandle::Win32(handle) = window.window_handle()?.as_raw() else {
+                    return Err("the window isn't a Win32 window".into());
+                };
+                let hwnd = handle.hwnd.get();
+                // SAFETY: The handle is that of `window`, which is alive.
+                unsafe { menu.init_for_hwnd(hwnd)? };
+                Ok(Self {
+                    menu,
+                    entries,
+                    hwnd,
+                })
+            }
+            #[cfg(target_os = "macos")]
+            {
+                // The menu bar belongs to the app rather than to the window
+                let _ = window;
+                menu.init_for_nsapp();
+                Ok(Self { menu, entries })
+            }
+        }
+
+        /// Change the text of the entries, and whether they're enabled, from `old` to `new`.
+        ///
+        /// The entries must have the same shape.
+        pub(super) fn update(&self, old: &[MenuEntry], new: &[MenuEntry]) {
+            update(&self.entries, old, new);
+        }
+
+        /// Remove the menu from the window it was added to.
+        pub(super) fn remove(self, _window: &Window) {
+            #[cfg(target_os = "windows")]
+            // SAFETY: The menu was added to this window, which is still alive.
+            if let Err(err) = unsafe { self.menu.remove_for_hwnd(self.hwnd) } {
+                tracing::warn!("Failed to remove the menu of the window: {err}");
+            }
+            #[cfg(target_os = "macos")]
+            self.menu.remove_for_nsapp();
+        }
+    }
+
+    /// Create the native entries for `entries`, and append them with `append`.
+    fn build(
+        entries: &[MenuEntry],
+        append: &dyn Fn(&dyn IsMenuItem) -> muda::Result<()>,
+    ) -> muda::Result<Vec<NativeEntry>> {
+        entries
+            .iter()
+            .map(|entry| {
+                let native = match entry {
+                    MenuEntry::Item { id, text, enabled } => {
+                        let item = MenuItem::with_id(id.as_str(), text, *enabled, None);
+                        append(&item)?;
+                        NativeEntry::Item(item)
+                    }
+                    MenuEntry::Submenu {
+                        text,
+                        enabled,
+                        entries,
+                    } => {
+                        let submenu = Submenu::new(text, *enabled);
+                        let children = build(entries, &|item| submenu.append(item))?;
+                        append(&submenu)?;
+                        NativeEntry::Submenu(submenu, children)
+                    }
+                    MenuEntry::Separator => {
+                        append(&PredefinedMenuItem::separator())?;
+                        NativeEntry::Separator
+                    }
+                };
+                Ok(native)
+            })
+            .collect()
+    }
+
+    fn update(native: &[NativeEntry], old: &[MenuEntry], new: &[MenuEntry]) {
+        for ((native, old), new) in native.iter().zip(old).zip(new) {
+            match (native, old, new) {
+                (
+                    NativeEntry::Item(item),
+                    MenuEntry::Item {
+                        text: old_text,
+                        enabled: old_enabled,
+                        ..
+                    },
+                    MenuEntry::Item { text, enabled, .. },
+                ) => {
+                    if text != old_text {
+                        item.set_text(text);
+                    }
+                    if enabled != old_enabled {
+                        item.set_enabled(*enabled);
+                    }
+                }
+                (
+                    NativeEntry::Submenu(submenu, children),
+                    MenuEntry::Submenu {
+                        text: old_text,
+                        enabled: old_enabled,
+                        entries: old_entries,
+                    },
+                    MenuEntry::Submenu {
+                        text,
+                        enabled,
+                        entries,
+                    },
+                ) => {
+                    if text != old_text {
+                        submenu.set_text(text);
+                    }
+                    if enabled != old_enabled {
+                        submenu.set_enabled(*enabled);
+                    }
+                    update(children, old_entries, entries);
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+/// The platform has no native menus which can be added to winit windows.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod native {
+    use winit::window::Window;
+
+    use super::MenuEntry;
+    use crate::event_loop_runner::EventLoopProxy;
+
+    pub(super) struct NativeMenu;
+
+    impl NativeMenu {
+        pub(super) fn new(
+            _window: &Window,
+            _entries: &[MenuEntry],
+            _proxy: &EventLoopProxy,
+        ) -> Result<Self, Box<dyn std::error::Error>> {
+            tracing::debug!("The menu of the window isn't shown on this platform");
+            Ok(Self)
+        }
+
+        pub(super) fn update(&self, _old: &[MenuEntry], _new: &[MenuEntry]) {}
+
+        pub(super) fn remove(self, _window: &Window) {}
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_menu(save_enabled: bool) -> Vec<MenuEntry> {
+        vec![MenuEntry::submenu(
+            "File",
+            [
+                MenuEntry::item("save", "Save").with_enabled(save_enabled),
+                MenuEntry::Separator,
+                MenuEntry::item("quit", "Quit"),
+            ],
+        )]
+    }
+
+    #[test]
+    fn only_text_and_enabled_changes_keep_the_shape() {
+        assert!(same_shape(&file_menu(true), &file_menu(false)));
+
+        let mut renamed = file_menu(true);
+        let MenuEntry::Submenu { text, .. } = &mut renamed[0] else {
+            unreachable!()
+        };
+        *text = "Fichier".into();
+        assert!(same_shape(&file_menu(true), &renamed));
+
+        let mut extended = file_menu(true);
+        extended.push(MenuEntry::item("help", "Help"));
+        assert!(!same_shape(&file_menu(true), &extended));
+
+        let other_id = vec![MenuEntry::submenu(
+            "File",
+            [
+                MenuEntry::item("save_as", "Save"),
+                MenuEntry::Separator,
+                MenuEntry::item("quit", "Quit"),
+            ],
+        )];
+        assert!(!same_shape(&file_menu(true), &other_id));
+    }
+
+    #[test]
+    fn items_are_found_in_submenus() {
+        let entries = file_menu(true);
+        assert!(contains(&entries, "save"));
+        assert!(contains(&entries, "quit"));
+        assert!(!contains(&entries, "File"));
+        assert!(!contains(&entries, "help"));
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The monitors of the system, and the placement of the window on them.
+//!
+//! The event loop runner keeps these up to date: widgets read them with
+//! [`EventCtx::monitors`] and [`EventCtx::window_placement`] (and the same methods of the other
+//! contexts), and app drivers are told when the window moves with
+//! [`AppDriver::on_window_placement`].
+//!
+//! Winit doesn't report the work area of monitors (the part which isn't covered by task bars
+//! and docks), so placements are computed from the whole area of the monitors.
+//!
+//! [`EventCtx::monitors`]: crate::EventCtx::monitors
+//! [`EventCtx::window_placement`]: crate::EventCtx::window_placement
+//! [`AppDriver::on_window_placement`]: crate::AppDriver::on_window_placement
+
+use winit::monitor::MonitorHandle;
+use winit::window::Window;
+
+use crate::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+use crate::Rect;
+
+/// A monitor connected to the system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorInfo {
+    /// A human-readable name of the monitor, if the platform provides one.
+    pub name: Option<String>,
+    /// The position of the top-left corner of the monitor on the desktop, in physical pixels.
+    pub position: PhysicalPosition<i32>,
+    /// The resolution of the monitor.
+    pub size: PhysicalSize<u32>,
+    /// The number of physical pixels per logical pixel of windows shown on the monitor.
+    pub scale_factor: f64,
+    /// The refresh rate of the monitor, in millihertz, if the platform reports it.
+    pub refresh_rate_millihertz: Option<u32>,
+    /// Whether this is the primary monitor of the system.
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn from_handle(handle: &MonitorHandle, primary: Option<&MonitorHandle>) -> Self {
+        Self {
+            name: handle.name(),
+            position: handle.position(),
+            size: handle.size(),
+            scale_factor: handle.scale_factor(),
+            refresh_rate_millihertz: handle.refresh_rate_millihertz(),
+            is_primary: primary == Some(handle),
+        }
+    }
+
+    /// The area of the monitor on the desktop, in physical pixels.
+    pub fn rect(&self) -> Rect {
+        Rect::new(
+            self.position.x as f64,
+            self.position.y as f64,
+            self.position.x as f64 + self.size.width as f64,
+            self.position.y as f64 + self.size.height as f64,
+        )
+    }
+}
+
+/// List `monitors`, marking `primary`.
+#[cfg_attr(
+    not(feature = "event_loop_runner"),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+pub(crate) fn monitor_infos(
+    monitors: impl Iterator<Item = MonitorHandle>,
+    primary: Option<MonitorHandle>,
+) -> Vec<MonitorInfo> {
+    monitors
+        .map(|monitor| MonitorInfo::from_handle(&monitor, primary.as_ref()))
+        .collect()
+}
+
+/// Where the window is on the desktop.
+///
+/// Apps can save the placement when they exit, and pass it to
+/// [`MasonryState::set_window_placement`] when they next start, after checking that it still
+/// fits on the monitors with [`fit_to`](Self::fit_to).
+///
+/// [`MasonryState::set_window_placement`]: crate::event_loop_runner::MasonryState::set_window_placement
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowPlacement {
+    /// The position of the top-left corner of the window, including its decorations, in
+    /// physical pixels on the desktop.
+    pub position: PhysicalPosition<i32>,
+    /// The size of the content area of the window, in physical pixels.
+    pub size: PhysicalSize<u32>,
+    /// Whether the window is maximized.
+    pub maximized: bool,
+    /// The index of the monitor showing the window in the list of monitors, if known.
+    pub monitor: Option<usize>,
+}
+
+impl WindowPlacement {
+    /// The placement of a window of `size` logical pixels in the center of `monitor`.
+    pub fn centered_on(
+        monitor: &MonitorInfo,
+        monitor_index: usize,
+        size: LogicalSize<f64>,
+    ) -> Self {
+        let size: PhysicalSize<u32> = size.to_physical(monitor.scale_factor);
+        let size = PhysicalSize::new(
+            size.width.min(monitor.size.width),
+            size.height.min(monitor.size.height),
+        );
+        let x = monitor.position.x + (monitor.size.width - size.width) as i32 / 2;
+        let y = monitor.position.y + (monitor.size.height - size.height) as i32 / 2;
+        Self {
+            position: PhysicalPosition::new(x, y),
+            size,
+            maximized: false,
+            monitor: Some(monitor_index),
+        }
+    }
+
+    /// This placement if its top-left corner is on one of `monitors`, otherwise the same size
+    /// centered on the primary monitor.
+    ///
+    /// This is used to restore a saved placement, as the monitor it was on may have been
+    /// disconnected since.
+    pub fn fit_to(&self, monitors: &[MonitorInfo]) -> Self {
+        let corner = (self.position.x as f64, self.position.y as f64).into();
+        if let Some(index) = monitors
+            .iter()
+            .position(|monitor| monitor.rect().contains(corner))
+        {
+            return Self {
+                monitor: Some(index),
+                ..self.clone()
+            };
+        }
+        let Some((index, monitor)) = monitors
+            .iter()
+            .enumerate()
+            .find(|(_, monitor)| monitor.is_primary)
+            .or_else(|| monitors.iter().enumerate().next())
+        else {
+            return self.clone();
+        };
+        let size = self.size.to_logical(monitor.scale_factor);
+        Self {
+            maximized: self.maximized,
+            ..Self::centered_on(monitor, index, size)
+        }
+    }
+
+    /// The current placement of `window`, which is shown on one of `monitors`.
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn of_window(window: &Window, monitors: &[MonitorInfo]) -> Self {
+        let current_monitor = window
+            .current_monitor()
+            .map(|monitor| (monitor.name(), monitor.position()));
+        Self {
+            position: window.outer_position().unwrap_or_default(),
+            size: window.inner_size(),
+            maximized: window.is_maximized(),
+            monitor: current_monitor.and_then(|(name, position)| {
+                monitors
+                    .iter()
+                    .position(|monitor| monitor.name == name && monitor.position == position)
+            }),
+        }
+    }
+
+    /// Move and resize `window` to this placement.
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn apply(&self, window: &Window) {
+        // The position and size are those of the restored window
+        window.set_maximized(false);
+        window.set_outer_position(self.position);
+        // TODO - Handle return value?
+        let _ = window.request_inner_size(self.size);
+        window.set_maximized(self.maximized);
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, width: u32, is_primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            name: None,
+            position: PhysicalPosition::new(x, 0),
+            size: PhysicalSize::new(width, 1000),
+            scale_factor: 2.,
+            refresh_rate_millihertz: None,
+            is_primary,
+        }
+    }
+
+    #[test]
+    fn fit_to_monitors() {
+        let monitors = [monitor(0, 2000, true), monitor(2000, 1000, false)];
+        let placement = WindowPlacement {
+            position: PhysicalPosition::new(2100, 100),
+            size: PhysicalSize::new(400, 300),
+            maximized: true,
+            monitor: None,
+        };
+
+        // The placement is kept if it's on a monitor
+        let fitted = placement.fit_to(&monitors);
+        assert_eq!(fitted.position, placement.position);
+        assert_eq!(fitted.monitor, Some(1));
+
+        // Otherwise, it's centered on the primary monitor
+        let fitted = placement.fit_to(&monitors[..1]);
+        assert_eq!(fitted.position, PhysicalPosition::new(800, 350));
+        assert_eq!(fitted.size, placement.size);
+        assert!(fitted.maximized);
+        assert_eq!(fitted.monitor, Some(0));
+    }
+
+    #[test]
+    fn centered_placement_fits_monitor() {
+        let placement =
+            WindowPlacement::centered_on(&monitor(0, 2000, true), 0, LogicalSize::new(400., 800.));
+        assert_eq!(placement.size, PhysicalSize::new(800, 1000));
+        assert_eq!(placement.position, PhysicalPosition::new(600, 0));
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Posting desktop notifications.
+//!
+//! ```no_run
+//! use masonry::notification::Notification;
+//!
+//! Notification::new("Download complete", "report.pdf was saved to Downloads")
+//!     .show()
+//!     .unwrap();
+//! ```
+
+pub use notify_rust::error::Error as NotificationError;
+
+/// A notification shown by the desktop, outside of the app's windows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    /// The title of the notification.
+    pub summary: String,
+    /// The text of the notification.
+    pub body: String,
+    /// The name of the app shown with the notification, if it isn't the name of the executable.
+    pub app_name: Option<String>,
+}
+
+impl Notification {
+    /// Create a notification with a `summary` line and a `body` text.
+    pub fn new(summary: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            summary: summary.into(),
+            body: body.into(),
+            app_name: None,
+        }
+    }
+
+    /// Builder-style method to set the name of the app shown with the notification.
+    pub fn with_app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = Some(app_name.into());
+        self
+    }
+
+    /// Ask the desktop to show the notification.
+    pub fn show(&self) -> Result<(), NotificationError> {
+        let mut notification = notify_rust::Notification::new();
+        notification.summary(&self.summary).body(&self.body);
+        if let Some(app_name) = &self.app_name {
+            notification.appname(app_name);
+        }
+        notification.show()?;
+        Ok(())
+    }
+}
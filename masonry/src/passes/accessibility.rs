@@ -8,6 +8,7 @@ use vello::kurbo::Rect;
 
 use crate::passes::recurse_on_children;
 use crate::render_root::{RenderRoot, RenderRootState};
+use crate::widget::AccessOverrides;
 use crate::{AccessCtx, Widget, WidgetState};
 
 use super::enter_span_if;
@@ -52,6 +53,7 @@ fn build_accessibility_tree(
         };
         let mut node = build_access_node(widget.item, &mut ctx);
         widget.item.accessibility(&mut ctx, &mut node);
+        apply_access_overrides(&ctx.widget_state.access_overrides, &mut node);
         if let Some(scale_factor) = scale_factor {
             node.set_transform(accesskit::Affine::scale(scale_factor));
         }
@@ -107,27 +109,70 @@ fn build_access_node(widget: &mut dyn Widget, ctx: &mut AccessCtx) -> Node {
     if ctx.is_disabled() {
         node.set_disabled();
     }
-    if ctx.is_stashed() {
+    if ctx.is_stashed() || ctx.is_hidden() {
         node.set_hidden();
     }
     if ctx.widget_state.clip_path.is_some() {
         node.set_clips_children();
     }
-    if ctx.accepts_focus() && !ctx.is_disabled() && !ctx.is_stashed() {
+    if ctx.accepts_focus() && !ctx.is_disabled() && !ctx.is_stashed() && !ctx.is_hidden() {
         node.add_action(accesskit::Action::Focus);
     }
     if ctx.is_focused() {
         node.add_action(accesskit::Action::Blur);
     }
+    if !ctx.is_stashed() {
+        node.add_action(accesskit::Action::ScrollIntoView);
+    }
+    let is_focus_group = ctx
+        .widget_state
+        .is_focus_group
+        .unwrap_or_else(|| widget.is_focus_group());
+    if is_focus_group && ctx.widget_state.has_focus {
+        if let Some(focused) = ctx.global_state.focused_widget {
+            if focused != ctx.widget_state.id {
+                node.set_active_descendant(focused.into());
+            }
+        }
+    }
 
     node
 }
 
+/// Apply the properties set from outside the widget, which take precedence over its own.
+fn apply_access_overrides(overrides: &AccessOverrides, node: &mut Node) {
+    if let Some(name) = &overrides.name {
+        node.set_label(name.clone());
+    }
+    if let Some(description) = &overrides.description {
+        node.set_description(description.clone());
+    }
+    if !overrides.labelled_by.is_empty() {
+        node.set_labelled_by(
+            overrides
+                .labelled_by
+                .iter()
+                .map(|&id| id.into())
+                .collect::<Vec<NodeId>>(),
+        );
+    }
+    if !overrides.described_by.is_empty() {
+        node.set_described_by(
+            overrides
+                .described_by
+                .iter()
+                .map(|&id| id.into())
+                .collect::<Vec<NodeId>>(),
+        );
+    }
+}
+
 fn to_accesskit_rect(r: Rect) -> accesskit::Rect {
     accesskit::Rect::new(r.x0, r.y0, r.x1, r.y1)
 }
 
 // --- MARK: ROOT ---
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_accessibility_pass(root: &mut RenderRoot, scale_factor: f64) -> TreeUpdate {
     let _span = info_span!("accessibility").entered();
 
@@ -176,3 +221,49 @@ pub(crate) fn run_accessibility_pass(root: &mut RenderRoot, scale_factor: f64) -
 
     tree_update
 }
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Button, Flex, Label};
+    use crate::WidgetPod;
+
+    #[test]
+    fn access_overrides_are_applied() {
+        let [label_id, button_id, hint_id] = widget_ids();
+        let mut button = WidgetPod::new_with_id(Button::new("🔍"), button_id);
+        let overrides = button.access_overrides_mut().unwrap();
+        overrides.name = Some("Search".to_string());
+        overrides.description = Some("Searches the whole project".to_string());
+        overrides.labelled_by = vec![label_id];
+        overrides.described_by = vec![hint_id];
+
+        let widget = Flex::column()
+            .with_child_id(Label::new("Find"), label_id)
+            .with_child_pod(button.boxed())
+            .with_child_id(Label::new("Press Enter to search"), hint_id);
+        let mut harness = TestHarness::create(widget);
+
+        let tree = harness.access_tree();
+        let node = tree.node(button_id.into()).unwrap();
+        assert_eq!(node.label(), Some("Search"));
+        assert_eq!(node.description(), Some("Searches the whole project"));
+        assert_eq!(node.labelled_by(), [NodeId::from(label_id)]);
+        assert_eq!(node.described_by(), [NodeId::from(hint_id)]);
+
+        harness.edit_widget(button_id, |mut button| {
+            let overrides = button.ctx.access_overrides_mut();
+            overrides.name = Some("Find in project".to_string());
+            overrides.described_by.clear();
+        });
+
+        let tree = harness.access_tree();
+        let node = tree.node(button_id.into()).unwrap();
+        assert_eq!(node.label(), Some("Find in project"));
+        assert!(node.described_by().is_empty());
+        // The overrides which weren't changed are kept
+        assert_eq!(node.labelled_by(), [NodeId::from(label_id)]);
+    }
+}
@@ -54,6 +54,7 @@ fn update_anim_for_widget(
 }
 
 /// Run the animation pass.
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_update_anim_pass(root: &mut RenderRoot, elapsed_ns: u64) {
     let _span = info_span!("update_anim").entered();
 
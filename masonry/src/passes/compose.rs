@@ -3,7 +3,7 @@
 
 use tracing::info_span;
 use tree_arena::ArenaMut;
-use vello::kurbo::Vec2;
+use vello::kurbo::{Rect, Vec2};
 
 use crate::passes::{enter_span_if, recurse_on_children};
 use crate::render_root::{RenderRoot, RenderRootState};
@@ -16,6 +16,7 @@ fn compose_widget(
     mut state: ArenaMut<'_, WidgetState>,
     parent_moved: bool,
     parent_translation: Vec2,
+    parent_clip: Option<Rect>,
 ) {
     let _span = enter_span_if(
         global_state.trace.compose,
@@ -27,6 +28,7 @@ fn compose_widget(
     let moved = parent_moved || state.item.translation_changed;
     let translation = parent_translation + state.item.translation + state.item.origin.to_vec2();
     state.item.window_origin = translation.to_point();
+    state.item.ancestor_clip = parent_clip;
 
     if !parent_moved && !state.item.translation_changed && !state.item.needs_compose {
         return;
@@ -38,19 +40,29 @@ fn compose_widget(
         widget_state_children: state.children.reborrow_mut(),
         widget_children: widget.children.reborrow_mut(),
     };
-    if ctx.widget_state.request_compose {
+    if ctx.widget_state.request_compose || (moved && ctx.widget_state.compose_on_move) {
         widget.item.compose(&mut ctx);
     }
 
     // We need to update the accessibility node's coordinates and repaint it at the new position.
-    state.item.request_accessibility = true;
-    state.item.needs_accessibility = true;
-    state.item.needs_paint = true;
+    // Widgets which didn't move, e.g. a portal whose content is scrolled, are left as they are.
+    // Moved widgets aren't painted again either: their previous scene is reused at the new position.
+    if moved {
+        state.item.request_accessibility = true;
+        state.item.needs_accessibility = true;
+        state.item.needs_paint = true;
+    }
 
     state.item.needs_compose = false;
     state.item.request_compose = false;
     state.item.translation_changed = false;
 
+    let clip = match (state.item.clip_path, parent_clip) {
+        (Some(clip), Some(parent_clip)) => Some((clip + translation).intersect(parent_clip)),
+        (Some(clip), None) => Some(clip + translation),
+        (None, parent_clip) => parent_clip,
+    };
+
     let id = state.item.id;
     let parent_state = state.item;
     recurse_on_children(
@@ -64,6 +76,7 @@ fn compose_widget(
                 state.reborrow_mut(),
                 moved,
                 translation,
+                clip,
             );
             parent_state.merge_up(state.item);
         },
@@ -71,6 +84,7 @@ fn compose_widget(
 }
 
 // --- MARK: ROOT ---
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_compose_pass(root: &mut RenderRoot) {
     let _span = info_span!("compose").entered();
 
@@ -87,5 +101,6 @@ pub(crate) fn run_compose_pass(root: &mut RenderRoot) {
         root_state,
         false,
         Vec2::ZERO,
+        None,
     );
 }
@@ -4,10 +4,10 @@
 use dpi::LogicalPosition;
 use tracing::{debug, info_span, trace};
 use winit::event::ElementState;
-use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
 
 use crate::passes::{enter_span, merge_state_up};
-use crate::render_root::RenderRoot;
+use crate::render_root::{GroupMotion, RenderRoot};
 use crate::{AccessEvent, EventCtx, Handled, PointerEvent, TextEvent, Widget, WidgetId};
 
 // --- MARK: HELPERS ---
@@ -84,6 +84,7 @@ fn run_event_pass<E>(
 }
 
 // --- MARK: POINTER_EVENT ---
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_on_pointer_event_pass(root: &mut RenderRoot, event: &PointerEvent) -> Handled {
     let _span = info_span!("dispatch_pointer_event").entered();
 
@@ -141,6 +142,7 @@ pub(crate) fn run_on_pointer_event_pass(root: &mut RenderRoot, event: &PointerEv
 // - If a Widget has focus, then none of its parents is hidden
 
 // --- MARK: TEXT EVENT ---
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_on_text_event_pass(root: &mut RenderRoot, event: &TextEvent) -> Handled {
     if matches!(event, TextEvent::FocusChange(false)) {
         run_on_pointer_event_pass(root, &PointerEvent::new_pointer_leave());
@@ -170,18 +172,16 @@ pub(crate) fn run_on_text_event_pass(root: &mut RenderRoot, event: &TextEvent) -
         !event.is_high_density(),
     );
 
-    // Handle Tab focus
+    // Handle Tab focus, and arrow keys and type-ahead in focus groups
     if let TextEvent::KeyboardKey(key, mods) = event {
-        if key.physical_key == PhysicalKey::Code(KeyCode::Tab)
-            && key.state == ElementState::Pressed
-            && handled == Handled::No
-        {
-            if !mods.shift_key() {
-                root.global_state.next_focused_widget = root.widget_from_focus_chain(true);
-            } else {
-                root.global_state.next_focused_widget = root.widget_from_focus_chain(false);
+        if key.state == ElementState::Pressed && handled == Handled::No {
+            handled = run_focus_navigation(root, key.physical_key, *mods);
+        }
+        let is_shortcut = mods.control_key() || mods.alt_key() || mods.super_key();
+        if key.state == ElementState::Pressed && handled == Handled::No && !is_shortcut {
+            if let Some(text) = &key.text {
+                handled = run_type_ahead(root, text);
             }
-            handled = Handled::Yes;
         }
     }
 
@@ -196,7 +196,61 @@ pub(crate) fn run_on_text_event_pass(root: &mut RenderRoot, event: &TextEvent) -
     handled
 }
 
+/// Move the focus in response to a key the focused widget didn't handle.
+///
+/// Tab and Shift+Tab move through the focus chain, skipping over the rest of a focus group.
+/// Inside the focus group of the focused widget, the arrow keys move to the next or previous
+/// widget (or to the nearest widget in that direction if the group is a [`Role::Grid`]),
+/// and Home and End move to the first and last widget.
+///
+/// [`Role::Grid`]: accesskit::Role::Grid
+pub(crate) fn run_focus_navigation(
+    root: &mut RenderRoot,
+    key: PhysicalKey,
+    mods: ModifiersState,
+) -> Handled {
+    let PhysicalKey::Code(code) = key else {
+        return Handled::No;
+    };
+    if code == KeyCode::Tab {
+        root.global_state.next_focused_widget = root.widget_from_focus_chain(!mods.shift_key());
+        return Handled::Yes;
+    }
+    if !mods.is_empty() {
+        return Handled::No;
+    }
+    let motion = match code {
+        KeyCode::ArrowUp => GroupMotion::Up,
+        KeyCode::ArrowDown => GroupMotion::Down,
+        KeyCode::ArrowLeft => GroupMotion::Left,
+        KeyCode::ArrowRight => GroupMotion::Right,
+        KeyCode::Home => GroupMotion::First,
+        KeyCode::End => GroupMotion::Last,
+        _ => return Handled::No,
+    };
+    let target = root.widget_from_focus_group(motion);
+    move_focus_to(root, target)
+}
+
+/// Move the focus to the next widget in the focused widget's focus group whose
+/// name starts with `text`.
+pub(crate) fn run_type_ahead(root: &mut RenderRoot, text: &str) -> Handled {
+    let target = root.widget_from_type_ahead(text);
+    move_focus_to(root, target)
+}
+
+fn move_focus_to(root: &mut RenderRoot, target: Option<WidgetId>) -> Handled {
+    match target {
+        Some(target) => {
+            root.global_state.next_focused_widget = Some(target);
+            Handled::Yes
+        }
+        None => Handled::No,
+    }
+}
+
 // --- MARK: ACCESS EVENT ---
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_on_access_event_pass(
     root: &mut RenderRoot,
     event: &AccessEvent,
@@ -216,7 +270,7 @@ pub(crate) fn run_on_access_event_pass(
         true,
     );
 
-    // Handle focus events
+    // Handle focus and scroll events
     match event.action {
         accesskit::Action::Focus if !handled.is_handled() => {
             if root.is_still_interactive(target) {
@@ -230,6 +284,19 @@ pub(crate) fn run_on_access_event_pass(
                 handled = Handled::Yes;
             }
         }
+        // Scroll containers pan to show the target in the update_scroll pass
+        accesskit::Action::ScrollIntoView if !handled.is_handled() => {
+            if let Some(state) = root.widget_arena.widget_states.find(target) {
+                let rect = state.item.layout_rect();
+                root.global_state
+                    .scroll_request_targets
+                    .push((target, rect));
+                handled = Handled::Yes;
+            }
+        }
+        // Increment and Decrement are out of scope for now: they are left to widgets with a
+        // value to handle in `on_access_event`, and Masonry doesn't have a slider or spin button
+        // yet (`Spinner` is a loading indicator, and has no value).
         _ => {}
     }
 
@@ -9,10 +9,15 @@ use dpi::LogicalSize;
 use smallvec::SmallVec;
 use tracing::{info_span, trace};
 use vello::kurbo::{Point, Rect, Size};
+#[cfg(feature = "parallel_text")]
+use {parley::LayoutContext, rayon::prelude::*, tree_arena::ArenaMut};
 
 use crate::passes::{enter_span_if, recurse_on_children};
+use crate::profiler::ProfiledPass;
 use crate::render_root::{RenderRoot, RenderRootSignal, WindowSizePolicy};
-use crate::widget::WidgetState;
+use crate::widget::{Visibility, WidgetState};
+#[cfg(feature = "parallel_text")]
+use crate::{text::TextLayoutJob, WidgetId};
 use crate::{BoxConstraints, LayoutCtx, Widget, WidgetPod};
 
 // --- MARK: RUN LAYOUT ---
@@ -65,6 +70,20 @@ pub(crate) fn run_layout_on<W: Widget>(
         return Size::ZERO;
     }
 
+    // Collapsed widgets take no space, and their subtree isn't laid out until they are shown
+    // again, which requests a layout.
+    if state.item.visibility == Visibility::Collapsed {
+        if trace {
+            trace!("Skipping layout of collapsed widget");
+        }
+        state.item.size = Size::ZERO;
+        state.item.local_paint_rect = Rect::ZERO;
+        state.item.needs_layout = false;
+        state.item.request_layout = false;
+        state.item.is_expecting_place_child_call = true;
+        return Size::ZERO;
+    }
+
     // TODO - Not everything that has been re-laid out needs to be repainted.
     state.item.needs_paint = true;
     state.item.needs_compose = true;
@@ -95,6 +114,8 @@ pub(crate) fn run_layout_on<W: Widget>(
         },
     );
 
+    let type_name = widget.item.short_type_name();
+    let profile_start = parent_ctx.global_state.profiler.start_widget();
     let new_size = {
         let mut inner_ctx = LayoutCtx {
             widget_state: state.item,
@@ -108,6 +129,14 @@ pub(crate) fn run_layout_on<W: Widget>(
         inner_ctx.widget_state.request_layout = false;
         widget.item.layout(&mut inner_ctx, bc)
     };
+    if let Some(layout_time) =
+        parent_ctx
+            .global_state
+            .profiler
+            .end_widget(ProfiledPass::Layout, type_name, profile_start)
+    {
+        state.item.layout_time = layout_time;
+    }
     if state.item.request_layout {
         debug_panic!(
             "Error in '{}' {}: layout request flag was set during layout pass",
@@ -214,7 +243,80 @@ pub(crate) fn run_layout_on<W: Widget>(
     new_size
 }
 
+// --- MARK: TEXT PREPASS ---
+/// Take the [`TextLayoutJob`]s of all widgets which may be laid out in the upcoming pass.
+#[cfg(feature = "parallel_text")]
+fn collect_text_layout_jobs(
+    mut widget: ArenaMut<'_, Box<dyn Widget>>,
+    state: ArenaMut<'_, WidgetState>,
+    jobs: &mut Vec<(WidgetId, TextLayoutJob)>,
+) {
+    // Widgets which don't need layout keep their current text layout.
+    // Stashed and collapsed widgets are never laid out, so we leave their text alone.
+    if !state.item.needs_layout
+        || state.item.is_stashed
+        || state.item.visibility == Visibility::Collapsed
+    {
+        return;
+    }
+
+    let id = state.item.id;
+    if let Some(job) = widget.item.take_text_layout_job() {
+        jobs.push((id, job));
+    }
+    recurse_on_children(
+        id,
+        widget.reborrow_mut(),
+        state.children,
+        |widget, state| {
+            collect_text_layout_jobs(widget, state, jobs);
+        },
+    );
+}
+
+/// Shape the text of all widgets about to be laid out on the rayon thread pool.
+///
+/// Widgets only ever see this through [`Widget::take_text_layout_job`] and
+/// [`Widget::finish_text_layout_job`]; all widget methods are still called on the main thread.
+#[cfg(feature = "parallel_text")]
+fn run_text_prepass(root: &mut RenderRoot) {
+    let _span = info_span!("text_prepass").entered();
+
+    let mut jobs = Vec::new();
+    let (root_widget, root_state) = root.widget_arena.get_pair_mut(root.root.id());
+    collect_text_layout_jobs(root_widget, root_state, &mut jobs);
+    if jobs.is_empty() {
+        return;
+    }
+
+    // Font and layout contexts aren't shareable between threads, so each chunk of jobs
+    // gets its own pair. These are kept around between passes, as creating them isn't free.
+    let global_state = &mut root.global_state;
+    let threads = rayon::current_num_threads().min(jobs.len());
+    while global_state.text_shaping_contexts.len() < threads {
+        global_state
+            .text_shaping_contexts
+            .push((global_state.font_context.clone(), LayoutContext::new()));
+    }
+    let chunk_size = jobs.len().div_ceil(threads);
+    jobs.par_chunks_mut(chunk_size)
+        .zip(global_state.text_shaping_contexts.par_iter_mut())
+        .for_each(|(jobs, (font_ctx, layout_ctx))| {
+            for (_, job) in jobs {
+                job.run(font_ctx, layout_ctx);
+            }
+        });
+
+    for (id, job) in jobs {
+        root.widget_arena
+            .get_widget_mut(id)
+            .item
+            .finish_text_layout_job(job);
+    }
+}
+
 // --- MARK: ROOT ---
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_layout_pass(root: &mut RenderRoot) {
     if !root.root_state().needs_layout {
         return;
@@ -223,10 +325,24 @@ pub(crate) fn run_layout_pass(root: &mut RenderRoot) {
     let _span = info_span!("layout").entered();
     root.global_state.needs_pointer_pass = true;
 
+    #[cfg(feature = "parallel_text")]
+    run_text_prepass(root);
+
     let window_size = root.get_kurbo_size();
-    let bc = match root.size_policy {
-        WindowSizePolicy::User => BoxConstraints::tight(window_size),
-        WindowSizePolicy::Content => BoxConstraints::UNBOUNDED,
+    let (bc, origin) = match root.size_policy {
+        WindowSizePolicy::User => {
+            // The root widget is laid out around the system UI covering the window
+            let insets = root.insets;
+            let size = Size::new(
+                (window_size.width - insets.x_value()).max(0.),
+                (window_size.height - insets.y_value()).max(0.),
+            );
+            (
+                BoxConstraints::tight(size),
+                Point::new(insets.x0, insets.y0),
+            )
+        }
+        WindowSizePolicy::Content => (BoxConstraints::UNBOUNDED, Point::ORIGIN),
     };
 
     let mut dummy_state = WidgetState::synthetic(root.root.id(), root.get_kurbo_size());
@@ -240,7 +356,7 @@ pub(crate) fn run_layout_pass(root: &mut RenderRoot) {
     };
 
     let size = run_layout_on(&mut ctx, &mut root.root, &bc);
-    ctx.place_child(&mut root.root, Point::ORIGIN);
+    ctx.place_child(&mut root.root, origin);
 
     if let WindowSizePolicy::Content = root.size_policy {
         let new_size = LogicalSize::new(size.width, size.height).to_physical(root.scale_factor);
@@ -251,3 +367,97 @@ pub(crate) fn run_layout_pass(root: &mut RenderRoot) {
         }
     }
 }
+
+// --- MARK: TESTS ---
+#[cfg(all(test, feature = "parallel_text"))]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use accesskit::{Node, Role};
+    use parley::Layout;
+    use vello::Scene;
+
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::text::{default_style_properties, ArcStr, BrushIndex};
+    use crate::widget::Flex;
+    use crate::{AccessCtx, PaintCtx, RegisterCtx};
+
+    /// A widget which hands out the shaping of its text, and records whether it was shaped
+    /// by the prepass.
+    struct PreparedText {
+        text: ArcStr,
+        layout: Layout<BrushIndex>,
+        needs_shaping: bool,
+        shaped_ahead: Rc<Cell<bool>>,
+    }
+
+    impl PreparedText {
+        fn new(text: &str, shaped_ahead: Rc<Cell<bool>>) -> Self {
+            Self {
+                text: text.into(),
+                layout: Layout::new(),
+                needs_shaping: true,
+                shaped_ahead,
+            }
+        }
+    }
+
+    impl Widget for PreparedText {
+        fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+        fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+            assert!(!self.needs_shaping, "text wasn't shaped before layout");
+            self.layout.break_all_lines(None);
+            bc.constrain(Size::new(
+                self.layout.width().into(),
+                self.layout.height().into(),
+            ))
+        }
+
+        fn take_text_layout_job(&mut self) -> Option<TextLayoutJob> {
+            std::mem::take(&mut self.needs_shaping).then(|| {
+                TextLayoutJob::new(
+                    self.text.clone(),
+                    default_style_properties(),
+                    std::mem::replace(&mut self.layout, Layout::new()),
+                )
+            })
+        }
+
+        fn finish_text_layout_job(&mut self, job: TextLayoutJob) {
+            self.layout = job.layout;
+            self.shaped_ahead.set(true);
+        }
+
+        fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+        fn accessibility_role(&self) -> Role {
+            Role::GenericContainer
+        }
+
+        fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+        fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+            SmallVec::new()
+        }
+    }
+
+    #[test]
+    fn text_is_shaped_before_layout() {
+        let shaped_ahead: [_; 3] = std::array::from_fn(|_| Rc::new(Cell::new(false)));
+        let widget = Flex::column()
+            .with_child(PreparedText::new("Hello", shaped_ahead[0].clone()))
+            .with_child(PreparedText::new("world", shaped_ahead[1].clone()))
+            .with_child(
+                Flex::row().with_child(PreparedText::new("nested", shaped_ahead[2].clone())),
+            );
+
+        let harness = TestHarness::create(widget);
+
+        assert!(shaped_ahead.iter().all(|shaped| shaped.get()));
+        let root = harness.root_widget();
+        assert!(root.children()[0].ctx().size().width > 0.);
+    }
+}
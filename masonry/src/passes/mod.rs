@@ -47,15 +47,44 @@ pub(crate) fn enter_span(
 }
 
 pub(crate) fn recurse_on_children(
+    id: WidgetId,
+    widget: ArenaMut<'_, Box<dyn Widget>>,
+    state: ArenaMutChildren<'_, WidgetState>,
+    callback: impl FnMut(ArenaMut<'_, Box<dyn Widget>>, ArenaMut<'_, WidgetState>),
+) {
+    let children_ids = widget.item.children_ids();
+    recurse_on_children_in_order(id, widget, state, children_ids, callback);
+}
+
+/// Like [`recurse_on_children`], but from the bottom child to the top one, as ordered by
+/// their [z-index](crate::WidgetPod::set_z_index).
+pub(crate) fn recurse_on_children_in_z_order(
+    id: WidgetId,
+    widget: ArenaMut<'_, Box<dyn Widget>>,
+    state: ArenaMutChildren<'_, WidgetState>,
+    callback: impl FnMut(ArenaMut<'_, Box<dyn Widget>>, ArenaMut<'_, WidgetState>),
+) {
+    let mut children_ids = widget.item.children_ids();
+    // The sort is stable, so children with the same z-index stay in tree order.
+    children_ids.sort_by_key(|child_id| {
+        state
+            .get_child(*child_id)
+            .map_or(0, |child_state| child_state.item.z_index)
+    });
+    recurse_on_children_in_order(id, widget, state, children_ids, callback);
+}
+
+fn recurse_on_children_in_order(
     id: WidgetId,
     mut widget: ArenaMut<'_, Box<dyn Widget>>,
     mut state: ArenaMutChildren<'_, WidgetState>,
+    children_ids: impl IntoIterator<Item = WidgetId>,
     mut callback: impl FnMut(ArenaMut<'_, Box<dyn Widget>>, ArenaMut<'_, WidgetState>),
 ) {
     let parent_name = widget.item.short_type_name();
     let parent_id = id;
 
-    for child_id in widget.item.children_ids() {
+    for child_id in children_ids {
         let widget = widget.children.get_child_mut(child_id).unwrap_or_else(|| {
             panic!(
                 "Error in '{}' #{}: cannot find child #{} returned by children_ids()",
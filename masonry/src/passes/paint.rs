@@ -2,18 +2,34 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use tracing::{info_span, trace};
 use tree_arena::ArenaMut;
-use vello::kurbo::{Affine, Stroke};
-use vello::peniko::Mix;
+use vello::kurbo::{Affine, Rect, Stroke};
+use vello::peniko::{BlendMode, Color, Fill, Mix};
 use vello::Scene;
 
-use crate::passes::{enter_span_if, recurse_on_children};
+use crate::passes::{enter_span_if, recurse_on_children_in_z_order};
+use crate::profiler::ProfiledPass;
 use crate::render_root::{RenderRoot, RenderRootState};
-use crate::theme::get_debug_color;
+use crate::text::{default_style_properties, render_text, StyleProperty};
+use crate::theme::{self, get_debug_color};
 use crate::{PaintCtx, Widget, WidgetId, WidgetState};
 
+/// Below this fraction of the time of the slowest widget, widgets aren't tinted by the heat overlay.
+const MIN_HEAT: f64 = 0.02;
+/// The opacity of the tint of the slowest widget.
+const MAX_HEAT_ALPHA: f64 = 0.6;
+
+/// The visible part of a widget, in window coordinates, and the time it took to lay out and
+/// paint, for the heat overlay.
+struct HeatSpot {
+    rect: Rect,
+    time: Duration,
+}
+
 // --- MARK: PAINT WIDGET ---
 fn paint_widget(
     global_state: &mut RenderRootState,
@@ -22,11 +38,28 @@ fn paint_widget(
     mut widget: ArenaMut<'_, Box<dyn Widget>>,
     mut state: ArenaMut<'_, WidgetState>,
     debug_paint: bool,
+    mut heat_spots: Option<&mut Vec<HeatSpot>>,
 ) {
     let trace = global_state.trace.paint;
     let _span = enter_span_if(trace, global_state, widget.reborrow(), state.reborrow());
 
     let id = state.item.id;
+    let window_origin = state.item.window_origin;
+
+    // The subtrees mirrored by other widgets are painted into a scene of their own,
+    // which is then kept for the mirrors.
+    let mut subtree_scene = global_state
+        .mirrored_scenes
+        .get(&id)
+        .is_some_and(|mirrored| state.item.needs_paint || mirrored.origin != Some(window_origin))
+        .then(Scene::new);
+    let scene_out = match &mut subtree_scene {
+        Some(scene) => scene,
+        None => &mut *complete_scene,
+    };
+
+    // Mirrors are painted again whenever the scene they mirror changes.
+    let request_paint = state.item.request_paint || global_state.mirrors_to_repaint.remove(&id);
 
     // TODO - Handle invalidation regions
     let mut ctx = PaintCtx {
@@ -36,7 +69,7 @@ fn paint_widget(
         widget_children: widget.children.reborrow_mut(),
         debug_paint,
     };
-    if ctx.widget_state.request_paint {
+    if request_paint {
         if trace {
             trace!("Painting widget '{}' {}", widget.item.short_type_name(), id);
         }
@@ -45,7 +78,18 @@ fn paint_widget(
         // https://github.com/linebender/xilem/issues/524
         let scene = scenes.entry(id).or_default();
         scene.reset();
+        let profile_start = ctx.global_state.profiler.start_widget();
         widget.item.paint(&mut ctx, scene);
+        if let Some(paint_time) = ctx.global_state.profiler.end_widget(
+            ProfiledPass::Paint,
+            widget.item.short_type_name(),
+            profile_start,
+        ) {
+            ctx.widget_state.paint_time = paint_time;
+        }
+        ctx.global_state.render_cache_stats.widgets_painted += 1;
+    } else {
+        ctx.global_state.render_cache_stats.widget_scenes_reused += 1;
     }
 
     state.item.request_paint = false;
@@ -56,23 +100,49 @@ fn paint_widget(
     let transform = Affine::translate(state.item.window_origin.to_vec2());
     let scene = scenes.get(&id).unwrap();
 
+    // The widget and its descendants are composed into a layer of their own to be faded.
+    let is_translucent = state.item.opacity < 1.0;
+    if is_translucent {
+        scene_out.push_layer(
+            BlendMode::default(),
+            state.item.opacity as f32,
+            transform,
+            &state.item.local_paint_rect,
+        );
+    }
+
     if let Some(clip) = clip {
-        complete_scene.push_layer(Mix::Clip, 1., transform, &clip);
+        scene_out.push_layer(Mix::Clip, 1., transform, &clip);
     }
 
-    complete_scene.append(scene, Some(transform));
+    scene_out.append(scene, Some(transform));
+
+    if let Some(heat_spots) = heat_spots.as_deref_mut() {
+        // Widgets whose layout or paint was skipped keep the time they took when last measured.
+        let time = state.item.layout_time + state.item.paint_time;
+        let mut rect = Rect::from_origin_size(window_origin, state.item.size);
+        if let Some(clip) = clip {
+            rect = rect.intersect(clip + window_origin.to_vec2());
+        }
+        if let Some(ancestor_clip) = state.item.ancestor_clip {
+            rect = rect.intersect(ancestor_clip);
+        }
+        if rect.area() > 0. {
+            heat_spots.push(HeatSpot { rect, time });
+        }
+    }
 
     let id = state.item.id;
     let size = state.item.size;
     let parent_state = state.item;
-    recurse_on_children(
+    recurse_on_children_in_z_order(
         id,
         widget.reborrow_mut(),
         state.children,
         |widget, mut state| {
             // TODO - We skip painting stashed items.
             // This may have knock-on effects we'd need to document.
-            if state.item.is_stashed {
+            if state.item.is_stashed || state.item.is_hidden {
                 return;
             }
             // TODO: We could skip painting children outside the parent clip path.
@@ -81,11 +151,12 @@ fn paint_widget(
             // - Once we implement compositor layers, we may want to paint outside of the clip path anyway in anticipation of user scrolling.
             paint_widget(
                 global_state,
-                complete_scene,
+                scene_out,
                 scenes,
                 widget,
                 state.reborrow_mut(),
                 debug_paint,
+                heat_spots.as_deref_mut(),
             );
             parent_state.merge_up(state.item);
         },
@@ -95,19 +166,108 @@ fn paint_widget(
         const BORDER_WIDTH: f64 = 1.0;
         let rect = size.to_rect().inset(BORDER_WIDTH / -2.0);
         let color = get_debug_color(id.to_raw());
-        complete_scene.stroke(&Stroke::new(BORDER_WIDTH), transform, color, None, &rect);
+        scene_out.stroke(&Stroke::new(BORDER_WIDTH), transform, color, None, &rect);
     }
 
     if has_clip {
-        complete_scene.pop_layer();
+        scene_out.pop_layer();
+    }
+    if is_translucent {
+        scene_out.pop_layer();
+    }
+
+    if let Some(subtree_scene) = subtree_scene {
+        complete_scene.append(&subtree_scene, None);
+        let mirrored = global_state.mirrored_scenes.get_mut(&id).unwrap();
+        mirrored.scene = subtree_scene;
+        mirrored.origin = Some(window_origin);
+        global_state
+            .mirrors_to_repaint
+            .extend(mirrored.mirrors.iter().copied());
     }
 }
 
+// --- MARK: HEAT OVERLAY ---
+/// Tint each widget in red, by the time spent in its `layout` and `paint` methods relative to
+/// the slowest widget, and outline the slowest widget.
+///
+/// The times exclude children, but the tints of nested widgets add up, so that a slow
+/// subtree stands out as a whole.
+fn paint_heat_overlay(scene: &mut Scene, heat_spots: &[HeatSpot]) {
+    let Some(hottest) = heat_spots.iter().max_by_key(|spot| spot.time) else {
+        return;
+    };
+    if hottest.time.is_zero() {
+        return;
+    }
+    let max_time = hottest.time.as_secs_f64();
+    for spot in heat_spots {
+        let heat = spot.time.as_secs_f64() / max_time;
+        if heat < MIN_HEAT {
+            continue;
+        }
+        let alpha = (heat * MAX_HEAT_ALPHA * 255.).round() as u8;
+        let color = Color::rgba8(255, 0, 0, alpha);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &spot.rect);
+    }
+
+    const BORDER_WIDTH: f64 = 2.0;
+    let rect = hottest.rect.inset(BORDER_WIDTH / -2.0);
+    scene.stroke(
+        &Stroke::new(BORDER_WIDTH),
+        Affine::IDENTITY,
+        Color::RED,
+        None,
+        &rect,
+    );
+}
+
+// --- MARK: PROFILE OVERLAY ---
+/// Show the pass timings of the last frame and the slowest widget types in the top left
+/// corner of the window, if profiling is enabled.
+fn paint_profile_overlay(global_state: &mut RenderRootState, scene: &mut Scene) {
+    const PADDING: f64 = 4.0;
+
+    let Some(frame) = global_state.profiler.frames().next_back() else {
+        return;
+    };
+    let text = frame.overlay_text();
+
+    let mut builder =
+        global_state
+            .text_layout_context
+            .ranged_builder(&mut global_state.font_context, &text, 1.0);
+    builder.push_default(StyleProperty::FontSize(theme::TEXT_SIZE_NORMAL));
+    for prop in default_style_properties() {
+        builder.push_default(prop);
+    }
+    let mut layout = builder.build(&text);
+    layout.break_all_lines(None);
+
+    let panel = Rect::new(
+        0.,
+        0.,
+        f64::from(layout.width()) + 2. * PADDING,
+        f64::from(layout.height()) + 2. * PADDING,
+    );
+    let background = Color::rgba8(0, 0, 0, 0xc0);
+    scene.fill(Fill::NonZero, Affine::IDENTITY, background, None, &panel);
+    render_text(
+        scene,
+        Affine::translate((PADDING, PADDING)),
+        &layout,
+        &[Color::WHITE.into()],
+        false,
+    );
+}
+
 // --- MARK: ROOT ---
-pub(crate) fn run_paint_pass(root: &mut RenderRoot) -> Scene {
+#[cfg_attr(feature = "profiling", profiling::function)]
+pub(crate) fn run_paint_pass(root: &mut RenderRoot) -> Arc<Scene> {
     let _span = info_span!("paint").entered();
 
     let debug_paint = std::env::var("MASONRY_DEBUG_PAINT").is_ok_and(|it| !it.is_empty());
+    let heat_overlay = root.global_state.profiler.is_heat_overlay_enabled();
 
     // TODO - Reserve scene
     // https://github.com/linebender/xilem/issues/524
@@ -128,9 +288,22 @@ pub(crate) fn run_paint_pass(root: &mut RenderRoot) -> Scene {
         (widget, state)
     };
 
+    // When no widget needs to be painted, nothing moved either, so the last frame is
+    // still accurate, including the glyph runs and images encoded in it.
+    if !root_state.item.needs_paint && !debug_paint && !heat_overlay {
+        if let Some(last_scene) = &root.global_state.last_scene {
+            let stats = &mut root.global_state.render_cache_stats;
+            stats.frames += 1;
+            stats.reused_frames += 1;
+            root.global_state.scene_reused = true;
+            return Arc::clone(last_scene);
+        }
+    }
+
     // TODO - This is a bit of a hack until we refactor widget tree mutation.
     // This should be removed once remove_child is exclusive to MutateCtx.
     let mut scenes = std::mem::take(&mut root.global_state.scenes);
+    let mut heat_spots = heat_overlay.then(Vec::new);
 
     paint_widget(
         &mut root.global_state,
@@ -139,8 +312,101 @@ pub(crate) fn run_paint_pass(root: &mut RenderRoot) -> Scene {
         root_widget,
         root_state,
         debug_paint,
+        heat_spots.as_mut(),
     );
     root.global_state.scenes = scenes;
 
+    if let Some(heat_spots) = heat_spots {
+        paint_heat_overlay(&mut complete_scene, &heat_spots);
+    }
+    if debug_paint {
+        paint_profile_overlay(&mut root.global_state, &mut complete_scene);
+    }
+
+    root.global_state.render_cache_stats.frames += 1;
+    root.global_state.scene_reused = false;
+    let complete_scene = Arc::new(complete_scene);
+    root.global_state.last_scene = Some(Arc::clone(&complete_scene));
     complete_scene
 }
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use vello::kurbo::Size;
+
+    use super::*;
+    use crate::render_root::{RenderRootOptions, WindowSizePolicy};
+    use crate::testing::{widget_ids, ModularWidget};
+    use crate::widget::Flex;
+
+    #[test]
+    fn heat_overlay_measures_paint() {
+        let [slow, fast] = widget_ids();
+        let slow_widget = ModularWidget::new(()).paint_fn(|_, _, _| {
+            std::thread::sleep(Duration::from_millis(5));
+        });
+        let root = Flex::column()
+            .with_child_id(slow_widget, slow)
+            .with_child_id(ModularWidget::new(()), fast);
+        let mut render_root = RenderRoot::new(
+            root,
+            RenderRootOptions {
+                use_system_fonts: false,
+                size_policy: WindowSizePolicy::User,
+                scale_factor: 1.0,
+                test_font: None,
+            },
+        );
+        let _ = render_root.redraw();
+
+        render_root.set_heat_overlay(true);
+        let _ = render_root.redraw();
+        let slow_time = render_root.widget_arena.get_state(slow).item.paint_time;
+        let fast_time = render_root.widget_arena.get_state(fast).item.paint_time;
+        assert!(slow_time >= Duration::from_millis(5));
+        assert!(fast_time < slow_time);
+        assert!(!render_root.global_state.scene_reused);
+
+        // The overlay is painted on each frame, even when no widget has to be.
+        let _ = render_root.redraw();
+        assert!(!render_root.global_state.scene_reused);
+        assert_eq!(
+            render_root.widget_arena.get_state(slow).item.paint_time,
+            slow_time
+        );
+
+        render_root.set_heat_overlay(false);
+        let _ = render_root.redraw();
+        let _ = render_root.redraw();
+        assert!(render_root.global_state.scene_reused);
+    }
+
+    #[test]
+    fn reused_frames_share_the_scene() {
+        let mut render_root = RenderRoot::new(
+            Flex::column().with_child(ModularWidget::new(())),
+            RenderRootOptions {
+                use_system_fonts: false,
+                size_policy: WindowSizePolicy::User,
+                scale_factor: 1.0,
+                test_font: None,
+            },
+        );
+        let (first, _) = render_root.redraw();
+        let (second, _) = render_root.redraw();
+        assert!(render_root.global_state.scene_reused);
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn heat_overlay_skips_idle_widgets() {
+        let spots = [HeatSpot {
+            rect: Rect::from_origin_size((0., 0.), Size::new(10., 10.)),
+            time: Duration::ZERO,
+        }];
+        let mut scene = Scene::new();
+        paint_heat_overlay(&mut scene, &spots);
+        assert!(scene.encoding().is_empty());
+    }
+}
@@ -7,9 +7,11 @@ use cursor_icon::CursorIcon;
 use tracing::{info_span, trace};
 use tree_arena::ArenaMut;
 
+use crate::command::{Command, CommandTarget};
 use crate::passes::event::{run_on_pointer_event_pass, run_on_text_event_pass};
 use crate::passes::{enter_span, enter_span_if, merge_state_up, recurse_on_children};
 use crate::render_root::{RenderRoot, RenderRootSignal, RenderRootState};
+use crate::widget::Visibility;
 use crate::{
     PointerEvent, QueryCtx, RegisterCtx, TextEvent, Update, UpdateCtx, Widget, WidgetId,
     WidgetState,
@@ -165,6 +167,7 @@ fn update_widget_tree(
     );
 }
 
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_update_widget_tree_pass(root: &mut RenderRoot) {
     let _span = info_span!("update_new_widgets").entered();
 
@@ -233,6 +236,7 @@ fn update_disabled_for_widget(
     );
 }
 
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_update_disabled_pass(root: &mut RenderRoot) {
     let _span = info_span!("update_disabled").entered();
 
@@ -258,12 +262,17 @@ fn update_stashed_for_widget(
     mut widget: ArenaMut<'_, Box<dyn Widget>>,
     mut state: ArenaMut<'_, WidgetState>,
     parent_stashed: bool,
+    parent_hidden: bool,
 ) {
     let _span = enter_span(global_state, widget.reborrow(), state.reborrow());
     let id = state.item.id;
 
     let stashed = state.item.is_explicitly_stashed || parent_stashed;
-    if !state.item.needs_update_stashed && stashed == state.item.is_stashed {
+    let hidden = state.item.visibility != Visibility::Visible || parent_hidden;
+    if !state.item.needs_update_stashed
+        && stashed == state.item.is_stashed
+        && hidden == state.item.is_hidden
+    {
         return;
     }
 
@@ -292,6 +301,15 @@ fn update_stashed_for_widget(
         state.item.request_layout = !stashed;
     }
 
+    // Hidden widgets are laid out as usual, but leave the focus chain and aren't painted.
+    if hidden != state.item.is_hidden {
+        state.item.is_hidden = hidden;
+        state.item.update_focus_chain = true;
+        state.item.needs_paint = true;
+        state.item.needs_accessibility = true;
+        state.item.request_accessibility = true;
+    }
+
     state.item.needs_update_stashed = false;
 
     let parent_state = state.item;
@@ -300,17 +318,24 @@ fn update_stashed_for_widget(
         widget.reborrow_mut(),
         state.children,
         |widget, mut state| {
-            update_stashed_for_widget(global_state, widget, state.reborrow_mut(), stashed);
+            update_stashed_for_widget(global_state, widget, state.reborrow_mut(), stashed, hidden);
             parent_state.merge_up(state.item);
         },
     );
 }
 
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_update_stashed_pass(root: &mut RenderRoot) {
     let _span = info_span!("update_stashed").entered();
 
     let (root_widget, root_state) = root.widget_arena.get_pair_mut(root.root.id());
-    update_stashed_for_widget(&mut root.global_state, root_widget, root_state, false);
+    update_stashed_for_widget(
+        &mut root.global_state,
+        root_widget,
+        root_state,
+        false,
+        false,
+    );
 }
 
 // ----------------
@@ -362,7 +387,7 @@ fn update_focus_chain_for_widget(
         },
     );
 
-    if !state.item.is_disabled {
+    if !state.item.is_disabled && !state.item.is_hidden {
         parent_focus_chain.extend(&state.item.focus_chain);
     }
 
@@ -376,6 +401,7 @@ fn update_focus_chain_for_widget(
     state.item.has_focus = had_focus;
 }
 
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_update_focus_chain_pass(root: &mut RenderRoot) {
     let _span = info_span!("update_focus_chain").entered();
     let mut dummy_focus_chain = Vec::new();
@@ -392,6 +418,7 @@ pub(crate) fn run_update_focus_chain_pass(root: &mut RenderRoot) {
 // ----------------
 
 // --- MARK: UPDATE FOCUS ---
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_update_focus_pass(root: &mut RenderRoot) {
     let _span = info_span!("update_focus").entered();
     // If the next-focused widget is disabled, stashed or removed, we set
@@ -526,6 +553,20 @@ pub(crate) fn run_update_focus_pass(root: &mut RenderRoot) {
         } else {
             root.global_state.is_ime_active = false;
         }
+
+        // The focus groups containing either widget expose it as their active descendant.
+        let groups = [prev_focused, next_focused]
+            .into_iter()
+            .flatten()
+            .filter(|&id| root.widget_arena.has(id))
+            .filter_map(|id| root.focus_group_of(id))
+            .collect::<HashSet<_>>();
+        for group in groups {
+            run_single_update_pass(root, group, |_, ctx| {
+                ctx.widget_state.request_accessibility = true;
+                ctx.widget_state.needs_accessibility = true;
+            });
+        }
     }
 
     root.global_state.focused_widget = next_focused;
@@ -540,6 +581,7 @@ pub(crate) fn run_update_focus_pass(root: &mut RenderRoot) {
 // Each parent that implements scrolling will update its scroll position to ensure the
 // child is visible. (If the target area is larger than the parent, the parent will try
 // to show the top left of that area.)
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_update_scroll_pass(root: &mut RenderRoot) {
     let _span = info_span!("update_scroll").entered();
 
@@ -563,7 +605,109 @@ pub(crate) fn run_update_scroll_pass(root: &mut RenderRoot) {
 
 // ----------------
 
+// --- MARK: UPDATE TIMERS ---
+// This pass sends `Update::Timer` to the widgets whose timers have expired, in the order
+// of their deadlines.
+#[cfg_attr(feature = "profiling", profiling::function)]
+pub(crate) fn run_update_timers_pass(root: &mut RenderRoot) {
+    let _span = info_span!("update_timers").entered();
+
+    let now = root.global_state.clock.now();
+    let (mut expired, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut root.global_state.timers)
+        .into_iter()
+        .partition(|timer| timer.deadline <= now);
+    // Timers requested while handling these events are added after the pending ones
+    root.global_state.timers = pending;
+    expired.sort_by_key(|timer| timer.deadline);
+
+    for timer in expired {
+        // The widget may have been removed since it requested the timer
+        if !root.widget_arena.has(timer.widget) {
+            continue;
+        }
+        run_single_update_pass(root, timer.widget, |widget, ctx| {
+            widget.update(ctx, &Update::Timer(timer.token));
+        });
+    }
+}
+
+// ----------------
+
+// --- MARK: UPDATE COMMANDS ---
+fn update_commands_for_widget(
+    global_state: &mut RenderRootState,
+    mut widget: ArenaMut<'_, Box<dyn Widget>>,
+    mut state: ArenaMut<'_, WidgetState>,
+    command: &Command,
+) {
+    let _span = enter_span(global_state, widget.reborrow(), state.reborrow());
+    let id = state.item.id;
+
+    let subscriptions = &state.item.command_subscriptions;
+    if subscriptions.contains(&command.type_id()) {
+        let mut ctx = UpdateCtx {
+            global_state,
+            widget_state: state.item,
+            widget_state_children: state.children.reborrow_mut(),
+            widget_children: widget.children.reborrow_mut(),
+        };
+        widget
+            .item
+            .update(&mut ctx, &Update::Command(command.clone()));
+    }
+
+    let parent_state = state.item;
+    recurse_on_children(
+        id,
+        widget.reborrow_mut(),
+        state.children,
+        |widget, mut state| {
+            update_commands_for_widget(global_state, widget, state.reborrow_mut(), command);
+            parent_state.merge_up(state.item);
+        },
+    );
+}
+
+/// Deliver the commands submitted with [`EventCtx::submit_command`](crate::EventCtx::submit_command).
+#[cfg_attr(feature = "profiling", profiling::function)]
+pub(crate) fn run_update_commands_pass(root: &mut RenderRoot) {
+    if root.global_state.commands.is_empty() {
+        return;
+    }
+    let _span = info_span!("update_commands").entered();
+
+    // Commands submitted whilst these are delivered are left for the next rewrite pass
+    let commands = std::mem::take(&mut root.global_state.commands);
+    for (target, command) in commands {
+        let target = match target {
+            CommandTarget::Widget(id) => Some(id),
+            CommandTarget::Focused => root.global_state.focused_widget,
+            CommandTarget::Subscribers => {
+                let (root_widget, root_state) = root.widget_arena.get_pair_mut(root.root.id());
+                update_commands_for_widget(
+                    &mut root.global_state,
+                    root_widget,
+                    root_state,
+                    &command,
+                );
+                continue;
+            }
+        };
+        // The widget may have been removed since the command was submitted
+        let Some(target) = target.filter(|id| root.widget_arena.has(*id)) else {
+            trace!("Discarding {command:?}, as its target isn't in the tree");
+            continue;
+        };
+        run_single_update_pass(root, target, |widget, ctx| {
+            widget.update(ctx, &Update::Command(command.clone()));
+        });
+    }
+}
+
+// ----------------
+
 // --- MARK: UPDATE POINTER ---
+#[cfg_attr(feature = "profiling", profiling::function)]
 pub(crate) fn run_update_pointer_pass(root: &mut RenderRoot) {
     if !root.global_state.needs_pointer_pass {
         return;
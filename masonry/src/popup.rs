@@ -0,0 +1,60 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Popups, for content which may extend outside of the window, such as menus and tooltips.
+//!
+//! A widget opens a popup with [`EventCtx::show_popup`], giving it the widget to show.
+//! The event loop runner shows that widget in a borderless window of its own, placed over the
+//! window of the widget which opened it, and which can extend past the window's edges.
+//!
+//! The actions submitted by widgets of the popup are submitted as
+//! [`Action::Popup`], on behalf of the widget which opened it. Popups are closed when the
+//! widget which opened them calls [`EventCtx::close_popup`] or is removed, and when the
+//! window is clicked, in which case [`Action::PopupClosed`] is submitted on behalf of that
+//! widget.
+//!
+//! Popup windows receive pointer events, but not keyboard focus, and only have the system
+//! fonts, not those registered with [`RenderRoot::register_fonts`].
+//! Wayland doesn't let apps position their windows, so popups may be placed elsewhere there.
+//! On the web, Android and iOS, which only have one window, popups aren't shown.
+//!
+//! Apps embedding Masonry receive popups as [`RenderRootSignal::ShowPopup`] signals.
+//!
+//! [`EventCtx::show_popup`]: crate::EventCtx::show_popup
+//! [`EventCtx::close_popup`]: crate::EventCtx::close_popup
+//! [`Action::Popup`]: crate::Action::Popup
+//! [`Action::PopupClosed`]: crate::Action::PopupClosed
+//! [`RenderRoot::register_fonts`]: crate::RenderRoot::register_fonts
+//! [`RenderRootSignal::ShowPopup`]: crate::RenderRootSignal::ShowPopup
+
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::dpi::{LogicalPosition, LogicalSize};
+use crate::{Widget, WidgetId};
+
+/// The identity of a popup, returned when it's opened.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct PopupId(NonZeroU64);
+
+impl PopupId {
+    /// Allocate a new, unique `PopupId`.
+    pub(crate) fn next() -> Self {
+        static POPUP_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
+        let id = POPUP_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self(id.try_into().unwrap())
+    }
+}
+
+/// A popup to show, as sent by the widget which opened it.
+pub struct PopupRequest {
+    /// The root widget of the popup.
+    pub content: Box<dyn Widget>,
+    /// The position of the top-left corner of the popup, in the coordinates of the window of
+    /// the widget which opened it.
+    pub position: LogicalPosition<f64>,
+    /// The size of the popup.
+    pub size: LogicalSize<f64>,
+    /// The widget which opened the popup.
+    pub owner: WidgetId,
+}
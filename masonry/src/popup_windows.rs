@@ -0,0 +1,331 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The windows of the [popups](crate::popup) opened by widgets.
+
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use tracing::warn;
+use vello::kurbo::{Affine, Vec2};
+use vello::{AaSupport, RenderParams, Renderer, RendererOptions, Scene};
+use wgpu::PresentMode;
+use winit::event::{ElementState, MouseScrollDelta, WindowEvent as WinitWindowEvent};
+use winit::event_loop::ActiveEventLoop;
+use winit::window::{Window, WindowAttributes, WindowId, WindowLevel};
+
+use crate::dpi::{LogicalPosition, PhysicalPosition};
+use crate::event::{PointerState, ScrollDelta, WindowEvent};
+use crate::popup::{PopupId, PopupRequest};
+use crate::render_context::{RenderContext, RenderSurface};
+use crate::render_root::{RenderRootOptions, RenderRootSignal, WindowSizePolicy};
+use crate::resource_cache::ResourceCache;
+use crate::{theme, Action, PointerEvent, RenderRoot, WidgetId};
+
+struct PopupWindow {
+    window: Arc<Window>,
+    surface: RenderSurface<'static>,
+    render_root: RenderRoot,
+    owner: WidgetId,
+    pointer_state: PointerState,
+}
+
+/// The popup windows of a window.
+#[derive(Default)]
+pub(crate) struct PopupWindows {
+    popups: HashMap<PopupId, PopupWindow>,
+    /// The renderer of the popups, and the index of the device it belongs to.
+    renderer: Option<(usize, Renderer)>,
+}
+
+impl PopupWindows {
+    /// Handle a [`RenderRootSignal::ShowPopup`], opening the popup over `parent`.
+    pub(crate) fn open(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        render_cx: &mut RenderContext,
+        parent: &Window,
+        resource_cache: ResourceCache,
+        id: PopupId,
+        request: PopupRequest,
+    ) {
+        if cfg!(any(
+            target_arch = "wasm32",
+            target_os = "android",
+            target_os = "ios"
+        )) {
+            warn!("Popups aren't supported on this platform");
+            return;
+        }
+        let origin = parent.inner_position().unwrap_or_default();
+        let offset: PhysicalPosition<i32> = request.position.to_physical(parent.scale_factor());
+        let attributes = popup_attributes()
+            .with_position(PhysicalPosition::new(
+                origin.x + offset.x,
+                origin.y + offset.y,
+            ))
+            .with_inner_size(request.size);
+        let window = match event_loop.create_window(attributes) {
+            Ok(window) => Arc::new(window),
+            Err(err) => {
+                warn!("Failed to create a popup window: {err}");
+                return;
+            }
+        };
+        let size = window.inner_size();
+        let surface = pollster::block_on(render_cx.create_surface(
+            window.clone(),
+            size.width,
+            size.height,
+            PresentMode::AutoVsync,
+        ));
+        let surface = match surface {
+            Ok(surface) => surface,
+            Err(err) => {
+                warn!("Failed to create the render surface of a popup: {err}");
+                return;
+            }
+        };
+        let mut render_root = RenderRoot::new(
+            request.content,
+            RenderRootOptions {
+                use_system_fonts: true,
+                size_policy: WindowSizePolicy::User,
+                scale_factor: window.scale_factor(),
+                test_font: None,
+            },
+        );
+        // Popups show the resources of their window, so they share its cache.
+        render_root.set_resource_cache(resource_cache);
+        render_root.handle_window_event(WindowEvent::Resize(size));
+        window.set_visible(true);
+        window.request_redraw();
+        self.popups.insert(
+            id,
+            PopupWindow {
+                window,
+                surface,
+                render_root,
+                owner: request.owner,
+                pointer_state: PointerState::empty(),
+            },
+        );
+    }
+
+    /// Handle a [`RenderRootSignal::ClosePopup`].
+    pub(crate) fn close(&mut self, id: PopupId) {
+        self.popups.remove(&id);
+    }
+
+    /// Close all popups, as the window was clicked, and return them with the widgets which
+    /// opened them.
+    pub(crate) fn dismiss_all(&mut self) -> Vec<(PopupId, WidgetId)> {
+        self.popups
+            .drain()
+            .map(|(id, popup)| (id, popup.owner))
+            .collect()
+    }
+
+    /// Close the popups whose owner was removed from `render_root`.
+    pub(crate) fn sync(&mut self, render_root: &RenderRoot) {
+        self.popups
+            .retain(|_, popup| render_root.get_widget(popup.owner).is_some());
+    }
+
+    /// The popup shown in `window_id`, if it's a popup window.
+    pub(crate) fn popup_of(&self, window_id: WindowId) -> Option<PopupId> {
+        self.popups
+            .iter()
+            .find(|(_, popup)| popup.window.id() == window_id)
+            .map(|(&id, _)| id)
+    }
+
+    /// Handle an event of the window of popup `id`.
+    ///
+    /// The actions submitted by the popup's widgets are emitted as signals of `parent`.
+    pub(crate) fn handle_window_event(
+        &mut self,
+        id: PopupId,
+        event: WinitWindowEvent,
+        render_cx: &mut RenderContext,
+        use_cpu: bool,
+        parent: &mut RenderRoot,
+    ) {
+        let Some(popup) = self.popups.get_mut(&id) else {
+            return;
+        };
+        let scale_factor = popup.window.scale_factor();
+        let mut scene = None;
+        match event {
+            WinitWindowEvent::RedrawRequested => {
+                popup
+                    .render_root
+                    .handle_window_event(WindowEvent::AnimFrame);
+                scene = Some(popup.render_root.redraw().0);
+            }
+            WinitWindowEvent::Resized(size) => {
+                popup
+                    .render_root
+                    .handle_window_event(WindowEvent::Resize(size));
+            }
+            WinitWindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                popup
+                    .render_root
+                    .handle_window_event(WindowEvent::Rescale(scale_factor));
+            }
+            WinitWindowEvent::CloseRequested => {
+                self.popups.remove(&id);
+                return;
+            }
+            WinitWindowEvent::CursorEntered { .. } => {
+                popup
+                    .render_root
+                    .handle_pointer_event(PointerEvent::PointerEnter(popup.pointer_state.clone()));
+            }
+            WinitWindowEvent::CursorMoved { position, .. } => {
+                popup.pointer_state.physical_position = position;
+                popup.pointer_state.position = position.to_logical(scale_factor);
+                popup
+                    .render_root
+                    .handle_pointer_event(PointerEvent::PointerMove(popup.pointer_state.clone()));
+            }
+            WinitWindowEvent::CursorLeft { .. } => {
+                popup
+                    .render_root
+                    .handle_pointer_event(PointerEvent::PointerLeave(popup.pointer_state.clone()));
+            }
+            WinitWindowEvent::MouseInput { state, button, .. } => {
+                let event = match state {
+                    ElementState::Pressed => {
+                        PointerEvent::PointerDown(button.into(), popup.pointer_state.clone())
+                    }
+                    ElementState::Released => {
+                        PointerEvent::PointerUp(button.into(), popup.pointer_state.clone())
+                    }
+                };
+                popup.render_root.handle_pointer_event(event);
+            }
+            WinitWindowEvent::MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        ScrollDelta::Lines(Vec2::new(x as f64, y as f64))
+                    }
+                    MouseScrollDelta::PixelDelta(delta) => {
+                        let delta: LogicalPosition<f64> = delta.to_logical(scale_factor);
+                        ScrollDelta::Pixels(Vec2::new(delta.x, delta.y))
+                    }
+                };
+                popup
+                    .render_root
+                    .handle_pointer_event(PointerEvent::MouseWheel(
+                        delta,
+                        popup.pointer_state.clone(),
+                    ));
+            }
+            _ => (),
+        }
+
+        while let Some(signal) = popup.render_root.pop_signal() {
+            match signal {
+                RenderRootSignal::Action(action, widget) => {
+                    let action = Action::Popup {
+                        popup: id,
+                        widget,
+                        action: Box::new(action),
+                    };
+                    parent
+                        .global_state
+                        .emit_signal(RenderRootSignal::Action(action, popup.owner));
+                }
+                RenderRootSignal::RequestRedraw | RenderRootSignal::RequestAnimFrame => {
+                    popup.window.request_redraw();
+                }
+                RenderRootSignal::SetCursor(cursor) => popup.window.set_cursor(cursor),
+                // Popups can't take focus, and don't own a window the user manages
+                _ => (),
+            }
+        }
+        if let Some(scene) = scene {
+            self.render(id, &scene, render_cx, use_cpu);
+        }
+    }
+
+    fn render(&mut self, id: PopupId, scene: &Scene, render_cx: &mut RenderContext, use_cpu: bool) {
+        let Some(popup) = self.popups.get_mut(&id) else {
+            return;
+        };
+        let size = popup.window.inner_size();
+        let surface = &mut popup.surface;
+        if surface.config.width != size.width || surface.config.height != size.height {
+            render_cx.resize_surface(surface, size.width, size.height);
+        }
+        let mut scaled_scene = Scene::new();
+        scaled_scene.append(scene, Some(Affine::scale(popup.window.scale_factor())));
+
+        let Ok(surface_texture) = surface.surface.get_current_texture() else {
+            warn!("failed to acquire next swapchain texture of a popup");
+            return;
+        };
+        let device_handle = &render_cx.devices[surface.dev_id];
+        if self
+            .renderer
+            .as_ref()
+            .is_some_and(|(dev_id, _)| *dev_id != surface.dev_id)
+        {
+            self.renderer = None;
+        }
+        let (_, renderer) = self.renderer.get_or_insert_with(|| {
+            let options = RendererOptions {
+                surface_format: Some(surface.format),
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                num_init_threads: NonZeroUsize::new(1),
+            };
+            let renderer = Renderer::new(&device_handle.device, options).unwrap();
+            (surface.dev_id, renderer)
+        });
+        let render_params = RenderParams {
+            base_color: theme::WINDOW_BACKGROUND_COLOR,
+            width: size.width,
+            height: size.height,
+            antialiasing_method: vello::AaConfig::Area,
+        };
+        popup.window.pre_present_notify();
+        if let Err(err) = renderer.render_to_surface(
+            &device_handle.device,
+            &device_handle.queue,
+            &scaled_scene,
+            &surface_texture,
+            &render_params,
+        ) {
+            warn!("Failed to render a popup: {err}");
+            return;
+        }
+        surface_texture.present();
+    }
+}
+
+/// The attributes of popup windows, which are undecorated, always on top, and don't take focus.
+fn popup_attributes() -> WindowAttributes {
+    let attributes = Window::default_attributes()
+        .with_decorations(false)
+        .with_resizable(false)
+        .with_active(false)
+        .with_window_level(WindowLevel::AlwaysOnTop)
+        .with_visible(false);
+    // Let the popup be placed anywhere, without being managed by the window manager
+    #[cfg(all(unix, not(target_vendor = "apple"), not(target_os = "android")))]
+    let attributes = {
+        use winit::platform::x11::{WindowAttributesExtX11, WindowType};
+        attributes
+            .with_override_redirect(true)
+            .with_x11_window_type(vec![WindowType::PopupMenu])
+    };
+    #[cfg(target_os = "windows")]
+    let attributes = {
+        use winit::platform::windows::WindowAttributesExtWindows;
+        attributes.with_skip_taskbar(true)
+    };
+    attributes
+}
@@ -0,0 +1,358 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in timing of passes and widgets, to find performance hotspots.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use serde::Serialize;
+
+/// How many frames of timings are kept by the profiler.
+const MAX_RECORDED_FRAMES: usize = 120;
+/// How many widget types are listed in the debug overlay.
+const OVERLAY_HOTSPOTS: usize = 3;
+
+/// The passes whose duration is recorded by the profiler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ProfiledPass {
+    Event,
+    Update,
+    Layout,
+    Compose,
+    Paint,
+    Accessibility,
+}
+
+/// Time spent in each group of passes during a frame.
+///
+/// "Update" covers the mutate, animation and `update_xxx` passes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PassTimings {
+    #[serde(serialize_with = "serialize_micros")]
+    pub event: Duration,
+    #[serde(serialize_with = "serialize_micros")]
+    pub update: Duration,
+    #[serde(serialize_with = "serialize_micros")]
+    pub layout: Duration,
+    #[serde(serialize_with = "serialize_micros")]
+    pub compose: Duration,
+    #[serde(serialize_with = "serialize_micros")]
+    pub paint: Duration,
+    #[serde(serialize_with = "serialize_micros")]
+    pub accessibility: Duration,
+}
+
+/// Time spent in the methods of all the widgets of a given type during a frame.
+///
+/// Durations exclude the time spent in children of these widgets.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct WidgetTypeTimings {
+    /// How many times [`Widget::layout`](crate::Widget::layout) was called.
+    pub layout_calls: u32,
+    #[serde(serialize_with = "serialize_micros")]
+    pub layout: Duration,
+    /// How many times [`Widget::paint`](crate::Widget::paint) was called.
+    pub paint_calls: u32,
+    #[serde(serialize_with = "serialize_micros")]
+    pub paint: Duration,
+}
+
+/// The timings recorded during a single frame.
+///
+/// A frame ends when [`RenderRoot::redraw`](crate::RenderRoot::redraw) is called.
+/// Durations are serialized as microseconds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct FrameProfile {
+    pub passes: PassTimings,
+    /// Timings of each widget type, keyed by [`Widget::short_type_name`](crate::Widget::short_type_name).
+    pub widget_types: BTreeMap<&'static str, WidgetTypeTimings>,
+}
+
+impl FrameProfile {
+    /// The widget types which spent the most time in layout, slowest first.
+    pub fn layout_hotspots(&self) -> Vec<(&'static str, &WidgetTypeTimings)> {
+        let mut hotspots: Vec<_> = self
+            .widget_types
+            .iter()
+            .map(|(name, timings)| (*name, timings))
+            .collect();
+        hotspots.sort_by(|a, b| b.1.layout.cmp(&a.1.layout));
+        hotspots
+    }
+
+    /// Serialize these timings as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// The summary shown in the debug overlay: the time spent in each pass, then the
+    /// slowest widget types in layout.
+    pub(crate) fn overlay_text(&self) -> String {
+        fn millis(duration: Duration) -> f64 {
+            duration.as_secs_f64() * 1000.0
+        }
+
+        let passes = &self.passes;
+        let mut text = String::new();
+        for (name, duration) in [
+            ("event", passes.event),
+            ("update", passes.update),
+            ("layout", passes.layout),
+            ("compose", passes.compose),
+            ("paint", passes.paint),
+            ("accessibility", passes.accessibility),
+        ] {
+            text += &format!("{name}: {:.2} ms\n", millis(duration));
+        }
+        for (name, timings) in self.layout_hotspots().into_iter().take(OVERLAY_HOTSPOTS) {
+            if timings.layout_calls == 0 {
+                break;
+            }
+            text += &format!(
+                "{name}: {:.2} ms in {} layouts\n",
+                millis(timings.layout),
+                timings.layout_calls
+            );
+        }
+        text.truncate(text.trim_end().len());
+        text
+    }
+}
+
+/// Records pass and widget timings while profiling is enabled.
+///
+/// Profiling is disabled by default. It can be enabled with
+/// [`RenderRoot::set_profiling`](crate::RenderRoot::set_profiling), or by setting
+/// the `MASONRY_PROFILE_PASSES` environment variable.
+///
+/// Widgets are also measured while the [heat overlay](crate::RenderRoot::set_heat_overlay)
+/// is shown, which doesn't record the timings of the frames.
+pub(crate) struct PassProfiler {
+    enabled: bool,
+    heat_overlay: bool,
+    current: FrameProfile,
+    frames: VecDeque<FrameProfile>,
+    /// For each widget currently being measured, the time spent in its children so far.
+    children_time: Vec<Duration>,
+}
+
+impl PassProfiler {
+    pub(crate) fn from_env() -> Self {
+        let enabled = std::env::var("MASONRY_PROFILE_PASSES").is_ok_and(|it| !it.is_empty());
+        let mut profiler = Self::new(enabled);
+        profiler.heat_overlay =
+            std::env::var("MASONRY_HEAT_OVERLAY").is_ok_and(|it| !it.is_empty());
+        profiler
+    }
+
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            heat_overlay: false,
+            current: FrameProfile::default(),
+            frames: VecDeque::new(),
+            children_time: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.current = FrameProfile::default();
+            self.frames.clear();
+        }
+    }
+
+    pub(crate) fn is_heat_overlay_enabled(&self) -> bool {
+        self.heat_overlay
+    }
+
+    pub(crate) fn set_heat_overlay(&mut self, enabled: bool) {
+        self.heat_overlay = enabled;
+    }
+
+    /// Returns the current time if profiling is enabled.
+    pub(crate) fn start(&self) -> Option<Instant> {
+        self.enabled.then(Instant::now)
+    }
+
+    pub(crate) fn record_pass(&mut self, pass: ProfiledPass, start: Option<Instant>) {
+        let Some(start) = start else {
+            return;
+        };
+        let elapsed = start.elapsed();
+        let passes = &mut self.current.passes;
+        let total = match pass {
+            ProfiledPass::Event => &mut passes.event,
+            ProfiledPass::Update => &mut passes.update,
+            ProfiledPass::Layout => &mut passes.layout,
+            ProfiledPass::Compose => &mut passes.compose,
+            ProfiledPass::Paint => &mut passes.paint,
+            ProfiledPass::Accessibility => &mut passes.accessibility,
+        };
+        *total += elapsed;
+    }
+
+    /// Start measuring a widget method. Must be paired with [`end_widget`](Self::end_widget).
+    pub(crate) fn start_widget(&mut self) -> Option<Instant> {
+        if !self.enabled && !self.heat_overlay {
+            return None;
+        }
+        self.children_time.push(Duration::ZERO);
+        Some(Instant::now())
+    }
+
+    /// Returns the time spent in the widget method, excluding its children, if it was measured.
+    pub(crate) fn end_widget(
+        &mut self,
+        pass: ProfiledPass,
+        type_name: &'static str,
+        start: Option<Instant>,
+    ) -> Option<Duration> {
+        let start = start?;
+        let elapsed = start.elapsed();
+        let children_time = self.children_time.pop().unwrap_or_default();
+        if let Some(parent_children_time) = self.children_time.last_mut() {
+            *parent_children_time += elapsed;
+        }
+
+        let self_time = elapsed.saturating_sub(children_time);
+        if !self.enabled {
+            return Some(self_time);
+        }
+        let timings = self.current.widget_types.entry(type_name).or_default();
+        match pass {
+            ProfiledPass::Layout => {
+                timings.layout_calls += 1;
+                timings.layout += self_time;
+            }
+            ProfiledPass::Paint => {
+                timings.paint_calls += 1;
+                timings.paint += self_time;
+            }
+            _ => debug_panic!("Widget timings are only recorded for layout and paint"),
+        }
+        Some(self_time)
+    }
+
+    /// Store the timings of the current frame, and start a new one.
+    pub(crate) fn end_frame(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        if self.frames.len() == MAX_RECORDED_FRAMES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(std::mem::take(&mut self.current));
+    }
+
+    pub(crate) fn frames(&self) -> impl DoubleEndedIterator<Item = &FrameProfile> {
+        self.frames.iter()
+    }
+}
+
+fn serialize_micros<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1_000_000.0)
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_profiler_records_nothing() {
+        let mut profiler = PassProfiler::new(false);
+        let start = profiler.start_widget();
+        assert!(start.is_none());
+        assert!(profiler
+            .end_widget(ProfiledPass::Layout, "Label", start)
+            .is_none());
+        profiler.record_pass(ProfiledPass::Layout, profiler.start());
+        profiler.end_frame();
+        assert_eq!(profiler.frames().count(), 0);
+    }
+
+    #[test]
+    fn widget_timings_exclude_children() {
+        let mut profiler = PassProfiler::new(true);
+
+        let flex_start = profiler.start_widget();
+        let label_start = profiler.start_widget();
+        std::thread::sleep(Duration::from_millis(5));
+        profiler.end_widget(ProfiledPass::Layout, "Label", label_start);
+        profiler.end_widget(ProfiledPass::Layout, "Flex", flex_start);
+        let paint_start = profiler.start_widget();
+        profiler.end_widget(ProfiledPass::Paint, "Label", paint_start);
+        profiler.end_frame();
+
+        let frame = profiler.frames().next_back().unwrap();
+        let label = &frame.widget_types["Label"];
+        let flex = &frame.widget_types["Flex"];
+        assert_eq!(label.layout_calls, 1);
+        assert_eq!(label.paint_calls, 1);
+        assert_eq!(flex.layout_calls, 1);
+        assert!(label.layout >= Duration::from_millis(5));
+        assert!(flex.layout < label.layout);
+        assert_eq!(frame.layout_hotspots()[0].0, "Label");
+
+        let json = frame.to_json();
+        assert!(json.contains("\"widget_types\""));
+        assert!(json.contains("\"layout_calls\":1"));
+    }
+
+    #[test]
+    fn heat_overlay_measures_widgets_without_recording_frames() {
+        let mut profiler = PassProfiler::new(false);
+        profiler.set_heat_overlay(true);
+
+        let start = profiler.start_widget();
+        std::thread::sleep(Duration::from_millis(5));
+        let self_time = profiler.end_widget(ProfiledPass::Paint, "Label", start);
+        assert!(self_time.unwrap() >= Duration::from_millis(5));
+
+        profiler.end_frame();
+        assert_eq!(profiler.frames().count(), 0);
+    }
+
+    #[test]
+    fn overlay_lists_passes_and_hotspots() {
+        let mut frame = FrameProfile::default();
+        frame.passes.layout = Duration::from_micros(1500);
+        for (name, layout) in [
+            ("Label", 900),
+            ("Flex", 300),
+            ("Button", 200),
+            ("Image", 100),
+        ] {
+            frame.widget_types.insert(
+                name,
+                WidgetTypeTimings {
+                    layout_calls: 2,
+                    layout: Duration::from_micros(layout),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let text = frame.overlay_text();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[2], "layout: 1.50 ms");
+        assert_eq!(lines[6], "Label: 0.90 ms in 2 layouts");
+        assert_eq!(lines.len(), 6 + OVERLAY_HOTSPOTS);
+        assert!(!text.contains("Image"));
+    }
+}
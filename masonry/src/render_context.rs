@@ -0,0 +1,211 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The GPU devices which the windows of the event loop runner are rendered with.
+//!
+//! This mirrors Vello's [`RenderContext`](vello::util::RenderContext), which always picks the
+//! adapter of new devices from the wgpu environment variables. Its devices can't be created
+//! outside of Vello, so Masonry keeps its own, and picks their adapter following the
+//! [`GpuOptions`] instead.
+
+use tracing::warn;
+use wgpu::{
+    Adapter, CompositeAlphaMode, Device, DeviceDescriptor, Features, Instance, InstanceDescriptor,
+    InstanceFlags, Limits, PowerPreference, PresentMode, Queue, RequestAdapterOptions, Surface,
+    SurfaceConfiguration, SurfaceTarget, TextureFormat, TextureUsages,
+};
+
+use crate::gpu::GpuOptions;
+use crate::software_render;
+
+/// The environment variable read by wgpu to choose the adapter by name.
+#[cfg(not(target_arch = "wasm32"))]
+const ADAPTER_NAME_VAR: &str = "WGPU_ADAPTER_NAME";
+
+/// A device, along with the adapter it was created from and its queue.
+pub(crate) struct DeviceHandle {
+    adapter: Adapter,
+    pub(crate) device: Device,
+    pub(crate) queue: Queue,
+}
+
+impl DeviceHandle {
+    pub(crate) fn adapter(&self) -> &Adapter {
+        &self.adapter
+    }
+}
+
+/// The surface of a window, configured for a device of the [`RenderContext`].
+pub(crate) struct RenderSurface<'s> {
+    pub(crate) surface: Surface<'s>,
+    pub(crate) config: SurfaceConfiguration,
+    /// The index of the device in [`RenderContext::devices`].
+    pub(crate) dev_id: usize,
+    pub(crate) format: TextureFormat,
+}
+
+/// The wgpu instance, and the devices created from it.
+pub(crate) struct RenderContext {
+    pub(crate) instance: Instance,
+    pub(crate) devices: Vec<DeviceHandle>,
+    power_preference: PowerPreference,
+    adapter_name: Option<String>,
+}
+
+impl RenderContext {
+    /// Create a context whose devices are created following `options`.
+    ///
+    /// If `software_rendering`, the adapters of the secondary backends, such as OpenGL, can
+    /// also be picked, which is where software adapters are usually found.
+    pub(crate) fn new(options: &GpuOptions, software_rendering: bool) -> Self {
+        let instance = if software_rendering {
+            software_render::instance()
+        } else {
+            let backends = wgpu::util::backend_bits_from_env()
+                .or(options.backends)
+                .unwrap_or_default();
+            Instance::new(InstanceDescriptor {
+                backends,
+                flags: InstanceFlags::from_build_config().with_env(),
+                ..Default::default()
+            })
+        };
+        Self {
+            instance,
+            devices: Vec::new(),
+            power_preference: options.power_preference,
+            adapter_name: options.adapter_name.clone(),
+        }
+    }
+
+    /// Create a surface for `window`, configured for a device which can present to it.
+    pub(crate) async fn create_surface<'w>(
+        &mut self,
+        window: impl Into<SurfaceTarget<'w>>,
+        width: u32,
+        height: u32,
+        present_mode: PresentMode,
+    ) -> Result<RenderSurface<'w>, vello::Error> {
+        let surface = self.instance.create_surface(window.into())?;
+        let dev_id = self
+            .device(&surface)
+            .await
+            .ok_or(vello::Error::NoCompatibleDevice)?;
+        let capabilities = surface.get_capabilities(self.devices[dev_id].adapter());
+        // These are the formats Vello can render to
+        let format = capabilities
+            .formats
+            .into_iter()
+            .find(|format| {
+                matches!(
+                    format,
+                    TextureFormat::Rgba8Unorm | TextureFormat::Bgra8Unorm
+                )
+            })
+            .ok_or(vello::Error::UnsupportedSurfaceFormat)?;
+        let surface = RenderSurface {
+            surface,
+            config: SurfaceConfiguration {
+                usage: TextureUsages::RENDER_ATTACHMENT,
+                format,
+                width,
+                height,
+                present_mode,
+                desired_maximum_frame_latency: 2,
+                alpha_mode: CompositeAlphaMode::Auto,
+                view_formats: vec![],
+            },
+            dev_id,
+            format,
+        };
+        self.configure_surface(&surface);
+        Ok(surface)
+    }
+
+    /// Resize `surface` to `width` by `height` pixels.
+    pub(crate) fn resize_surface(&self, surface: &mut RenderSurface<'_>, width: u32, height: u32) {
+        surface.config.width = width;
+        surface.config.height = height;
+        self.configure_surface(surface);
+    }
+
+    pub(crate) fn set_present_mode(
+        &self,
+        surface: &mut RenderSurface<'_>,
+        present_mode: PresentMode,
+    ) {
+        surface.config.present_mode = present_mode;
+        self.configure_surface(surface);
+    }
+
+    fn configure_surface(&self, surface: &RenderSurface<'_>) {
+        let device = &self.devices[surface.dev_id].device;
+        surface.surface.configure(device, &surface.config);
+    }
+
+    /// The index of a device which can present to `surface`, which is created if there is none.
+    async fn device(&mut self, surface: &Surface<'_>) -> Option<usize> {
+        if let Some(dev_id) = self
+            .devices
+            .iter()
+            .position(|device| device.adapter.is_surface_supported(surface))
+        {
+            return Some(dev_id);
+        }
+        let adapter = self.adapter(surface).await?;
+        // Vello doesn't need more than this, see `vello::util::RenderContext`
+        let descriptor = DeviceDescriptor {
+            label: None,
+            required_features: adapter.features() & Features::CLEAR_TEXTURE,
+            required_limits: Limits::default(),
+            ..Default::default()
+        };
+        let (device, queue) = adapter.request_device(&descriptor, None).await.ok()?;
+        self.devices.push(DeviceHandle {
+            adapter,
+            device,
+            queue,
+        });
+        Some(self.devices.len() - 1)
+    }
+
+    /// Pick the adapter of a new device which can present to `surface`.
+    ///
+    /// The wgpu environment variables take precedence over the options.
+    async fn adapter(&self, surface: &Surface<'_>) -> Option<Adapter> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let adapter_name = std::env::var(ADAPTER_NAME_VAR)
+                .ok()
+                .or_else(|| self.adapter_name.clone());
+            if let Some(name) = adapter_name {
+                let name = name.to_lowercase();
+                let adapter = self
+                    .instance
+                    .enumerate_adapters(wgpu::Backends::all())
+                    .into_iter()
+                    .find(|adapter| {
+                        adapter.get_info().name.to_lowercase().contains(&name)
+                            && adapter.is_surface_supported(surface)
+                    });
+                if adapter.is_some() {
+                    return adapter;
+                }
+                warn!("No adapter named {name:?} can render the window, using the default one");
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        if self.adapter_name.is_some() {
+            warn!("The browser doesn't let the adapter be chosen by name");
+        }
+        let power_preference =
+            wgpu::util::power_preference_from_env().unwrap_or(self.power_preference);
+        self.instance
+            .request_adapter(&RequestAdapterOptions {
+                power_preference,
+                force_fallback_adapter: false,
+                compatible_surface: Some(surface),
+            })
+            .await
+    }
+}
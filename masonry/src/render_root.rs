@@ -1,14 +1,16 @@
 // Copyright 2019 the Xilem Authors and the Druid Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
 
-use accesskit::{ActionRequest, TreeUpdate};
+use accesskit::{ActionRequest, Role, TreeUpdate};
 use parley::fontique::{self, Collection, CollectionOptions};
 use parley::{FontContext, LayoutContext};
 use tracing::{info_span, warn};
 use tree_arena::{ArenaMut, TreeArena};
-use vello::kurbo::{self, Rect};
+use vello::kurbo::{self, Insets, Point, Rect};
 use vello::Scene;
 use winit::window::ResizeDirection;
 
@@ -17,9 +19,11 @@ use std::time::Instant;
 #[cfg(target_arch = "wasm32")]
 use web_time::Instant;
 
+use crate::command::{Command, CommandTarget};
 use crate::debug_logger::DebugLogger;
 use crate::dpi::{LogicalPosition, LogicalSize, PhysicalSize};
 use crate::event::{PointerEvent, TextEvent, WindowEvent};
+use crate::monitor::{MonitorInfo, WindowPlacement};
 use crate::passes::accessibility::run_accessibility_pass;
 use crate::passes::anim::run_update_anim_pass;
 use crate::passes::compose::run_compose_pass;
@@ -30,14 +34,20 @@ use crate::passes::layout::run_layout_pass;
 use crate::passes::mutate::{mutate_widget, run_mutate_pass};
 use crate::passes::paint::run_paint_pass;
 use crate::passes::update::{
-    run_update_disabled_pass, run_update_focus_chain_pass, run_update_focus_pass,
-    run_update_pointer_pass, run_update_scroll_pass, run_update_stashed_pass,
-    run_update_widget_tree_pass,
+    run_update_commands_pass, run_update_disabled_pass, run_update_focus_chain_pass,
+    run_update_focus_pass, run_update_pointer_pass, run_update_scroll_pass,
+    run_update_stashed_pass, run_update_timers_pass, run_update_widget_tree_pass,
 };
 use crate::passes::{recurse_on_children, PassTracing};
-use crate::text::BrushIndex;
-use crate::widget::{WidgetArena, WidgetMut, WidgetRef, WidgetState};
-use crate::{AccessEvent, Action, CursorIcon, Handled, QueryCtx, Widget, WidgetId, WidgetPod};
+use crate::popup::{PopupId, PopupRequest};
+use crate::profiler::{FrameProfile, PassProfiler, ProfiledPass};
+use crate::resource_cache::ResourceCache;
+use crate::text::{measure_text, BrushIndex, StyleProperty, TextMetrics};
+use crate::theme::Density;
+use crate::widget::{ViewportCallback, WidgetArena, WidgetMut, WidgetPool, WidgetRef, WidgetState};
+use crate::{
+    AccessEvent, Action, CursorIcon, Handled, QueryCtx, TimerToken, Widget, WidgetId, WidgetPod,
+};
 
 /// We ensure that any valid initial IME area is sent to the platform by storing an invalid initial
 /// IME area as the `last_sent_ime_area`.
@@ -52,6 +62,8 @@ pub struct RenderRoot {
     // TODO - Currently this is always 1.0
     // kurbo coordinates are assumed to be in logical pixels
     pub(crate) scale_factor: f64,
+    /// The parts of the window covered by system UI, which the root widget is laid out around.
+    pub(crate) insets: Insets,
     /// Is `Some` if the most recently displayed frame was an animation frame.
     pub(crate) last_anim: Option<Instant>,
     pub(crate) last_mouse_pos: Option<LogicalPosition<f64>>,
@@ -76,16 +88,112 @@ pub(crate) struct RenderRootState {
     pub(crate) cursor_icon: CursorIcon,
     pub(crate) font_context: FontContext,
     pub(crate) text_layout_context: LayoutContext<BrushIndex>,
+    /// Font and layout contexts for the worker threads of the parallel text prepass.
+    #[cfg(feature = "parallel_text")]
+    pub(crate) text_shaping_contexts: Vec<(FontContext, LayoutContext<BrushIndex>)>,
+    /// The decoded images and fonts shared by the widgets, see [`RenderRoot::resource_cache`].
+    pub(crate) resource_cache: ResourceCache,
     pub(crate) mutate_callbacks: Vec<MutateCallback>,
+    /// The callbacks queued with [`EventCtx::schedule_idle`](crate::EventCtx::schedule_idle).
+    pub(crate) idle_callbacks: VecDeque<MutateCallback>,
+    /// The timers requested with [`EventCtx::request_timer`](crate::EventCtx::request_timer).
+    pub(crate) timers: Vec<Timer>,
+    /// The commands submitted with [`EventCtx::submit_command`](crate::EventCtx::submit_command),
+    /// which haven't been delivered yet.
+    pub(crate) commands: Vec<(CommandTarget, Command)>,
+    /// The events queued with [`EventCtx::submit_text_event`](crate::EventCtx::submit_text_event).
+    pub(crate) synthesized_text_events: VecDeque<TextEvent>,
     pub(crate) is_ime_active: bool,
     /// The IME area last sent to the platform.
     ///
     /// This allows only sending the area to the platform when the area has changed.
     pub(crate) last_sent_ime_area: Rect,
     pub(crate) scenes: HashMap<WidgetId, Scene>,
+    /// The scene of the last frame, which is reused as long as no widget needs to be painted.
+    ///
+    /// It's shared with the host which renders it, so that reusing it doesn't copy it.
+    pub(crate) last_scene: Option<Arc<Scene>>,
+    /// Whether the last frame reused the scene of the frame before it.
+    pub(crate) scene_reused: bool,
+    pub(crate) render_cache_stats: RenderCacheStats,
+    /// The scenes of the subtrees mirrored with [`LayoutCtx::mirror_scene`](crate::LayoutCtx::mirror_scene),
+    /// by the id of their root.
+    pub(crate) mirrored_scenes: HashMap<WidgetId, MirroredScene>,
+    /// The mirrors to paint again because the scene they mirror changed in the current paint pass.
+    pub(crate) mirrors_to_repaint: HashSet<WidgetId>,
+    /// The callbacks of the [`RenderViewport`](crate::widget::RenderViewport) widgets.
+    pub(crate) viewport_callbacks: HashMap<WidgetId, ViewportCallback>,
+    pub(crate) widget_pool: WidgetPool,
     /// Whether data set in the pointer pass has been invalidated.
     pub(crate) needs_pointer_pass: bool,
     pub(crate) trace: PassTracing,
+    pub(crate) profiler: PassProfiler,
+    pub(crate) clock: Clock,
+    /// The monitors of the system, as reported by the event loop runner.
+    pub(crate) monitors: Vec<MonitorInfo>,
+    /// Where the window is on the desktop, as reported by the event loop runner.
+    pub(crate) window_placement: Option<WindowPlacement>,
+    /// How large the built-in widgets are.
+    pub(crate) density: Density,
+    /// Whether the density switches to [`Density::Touch`] when the window is next touched.
+    pub(crate) switch_to_touch_density: bool,
+}
+
+/// The source of the current time for widgets and animations.
+///
+/// The clock follows the system time, unless it is frozen. A frozen clock only moves forward
+/// when it is advanced, which lets tests control time deterministically.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Clock {
+    frozen_at: Option<Instant>,
+}
+
+impl Clock {
+    /// The current time.
+    pub(crate) fn now(&self) -> Instant {
+        self.frozen_at.unwrap_or_else(Instant::now)
+    }
+
+    /// Stop following the system time.
+    pub(crate) fn freeze(&mut self) {
+        self.frozen_at = Some(self.now());
+    }
+
+    /// Move a frozen clock forward by `duration`.
+    pub(crate) fn advance(&mut self, duration: Duration) {
+        debug_assert!(
+            self.frozen_at.is_some(),
+            "only a frozen clock can be advanced"
+        );
+        self.frozen_at = Some(self.now() + duration);
+    }
+
+    /// Follow the system time again.
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn unfreeze(&mut self) {
+        self.frozen_at = None;
+    }
+}
+
+/// A timer requested by a widget.
+pub(crate) struct Timer {
+    pub(crate) deadline: Instant,
+    pub(crate) token: TimerToken,
+    pub(crate) widget: WidgetId,
+}
+
+/// The scene of a widget and its descendants, kept for the widgets mirroring it.
+#[derive(Default)]
+pub(crate) struct MirroredScene {
+    pub(crate) scene: Scene,
+    /// The window origin of the root of the subtree when it was painted.
+    ///
+    /// This is `None` until the subtree is first painted.
+    pub(crate) origin: Option<Point>,
+    pub(crate) mirrors: Vec<WidgetId>,
 }
 
 pub(crate) struct MutateCallback {
@@ -121,6 +229,41 @@ pub struct RenderRootOptions {
     pub test_font: Option<Vec<u8>>,
 }
 
+/// How much of the painted frames was reused from previous frames.
+///
+/// Widgets are only painted again when they request it, and their scenes, with the glyph
+/// runs and images encoded in them, are reused otherwise. When no widget needs to be
+/// painted, the whole frame is the same as the previous one, and hosts which still show
+/// it don't send it to the GPU again.
+///
+/// Vello keeps the glyphs it has already rasterized between renders, but uploads the images
+/// of a scene each time it renders it. So frames which did change still upload all their
+/// images.
+///
+/// These can be read through [`RenderRoot::render_cache_stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderCacheStats {
+    /// The number of frames painted.
+    pub frames: u64,
+    /// The number of frames which reused the scene of the previous frame.
+    pub reused_frames: u64,
+    /// The number of times a widget was painted.
+    pub widgets_painted: u64,
+    /// The number of times the scene of a widget was reused instead of painting it again.
+    pub widget_scenes_reused: u64,
+}
+
+/// A movement of the focus between the members of a focus group.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum GroupMotion {
+    Up,
+    Down,
+    Left,
+    Right,
+    First,
+    Last,
+}
+
 pub enum RenderRootSignal {
     Action(Action, WidgetId),
     StartIme,
@@ -138,6 +281,18 @@ pub enum RenderRootSignal {
     Minimize,
     Exit,
     ShowWindowMenu(LogicalPosition<f64>),
+    /// Move and resize the window.
+    SetWindowPlacement(WindowPlacement),
+    /// Show a popup window. See the [`popup`](crate::popup) module for details.
+    ShowPopup(PopupId, PopupRequest),
+    /// Close a popup window opened with [`ShowPopup`](Self::ShowPopup).
+    ClosePopup(PopupId),
+    /// A request to the native webview of a [`WebView`](crate::widget::WebView) widget.
+    #[cfg(feature = "webview")]
+    WebView(WidgetId, crate::widget::WebViewRequest),
+    /// Show these entries in the menu bar of the window. See the [`menu`](crate::menu) module.
+    #[cfg(feature = "menu")]
+    SetMenu(Vec<crate::menu::MenuEntry>),
 }
 
 impl RenderRoot {
@@ -155,6 +310,7 @@ impl RenderRoot {
             size_policy,
             size: PhysicalSize::new(0, 0),
             scale_factor,
+            insets: Insets::ZERO,
             last_anim: None,
             last_mouse_pos: None,
             cursor_icon: CursorIcon::Default,
@@ -176,12 +332,32 @@ impl RenderRoot {
                     source_cache: Default::default(),
                 },
                 text_layout_context: LayoutContext::new(),
+                #[cfg(feature = "parallel_text")]
+                text_shaping_contexts: Vec::new(),
+                resource_cache: ResourceCache::default(),
                 mutate_callbacks: Vec::new(),
+                idle_callbacks: VecDeque::new(),
+                timers: Vec::new(),
+                commands: Vec::new(),
+                synthesized_text_events: VecDeque::new(),
                 is_ime_active: false,
                 last_sent_ime_area: INVALID_IME_AREA,
                 scenes: HashMap::new(),
+                last_scene: None,
+                scene_reused: false,
+                render_cache_stats: RenderCacheStats::default(),
+                mirrored_scenes: HashMap::new(),
+                mirrors_to_repaint: HashSet::new(),
+                viewport_callbacks: HashMap::new(),
+                widget_pool: WidgetPool::default(),
                 needs_pointer_pass: false,
                 trace: PassTracing::from_env(),
+                profiler: PassProfiler::from_env(),
+                clock: Clock::default(),
+                monitors: Vec::new(),
+                window_placement: None,
+                density: Density::Normal,
+                switch_to_touch_density: true,
             },
             widget_arena: WidgetArena {
                 widgets: TreeArena::new(),
@@ -230,8 +406,17 @@ impl RenderRoot {
                 self.run_rewrite_passes();
                 Handled::Yes
             }
+            WindowEvent::SetInsets(insets) => {
+                if self.insets != insets {
+                    self.insets = insets;
+                    self.root_state().request_layout = true;
+                    self.root_state().needs_layout = true;
+                    self.run_rewrite_passes();
+                }
+                Handled::Yes
+            }
             WindowEvent::AnimFrame => {
-                let now = Instant::now();
+                let now = self.global_state.clock.now();
                 // TODO: this calculation uses wall-clock time of the paint call, which
                 // potentially has jitter.
                 //
@@ -239,7 +424,9 @@ impl RenderRoot {
                 let last = self.last_anim.take();
                 let elapsed_ns = last.map(|t| now.duration_since(t).as_nanos()).unwrap_or(0) as u64;
 
-                run_update_anim_pass(self, elapsed_ns);
+                self.profile_pass(ProfiledPass::Update, |root| {
+                    run_update_anim_pass(root, elapsed_ns);
+                });
                 self.run_rewrite_passes();
 
                 // If this animation will continue, store the time.
@@ -261,17 +448,22 @@ impl RenderRoot {
     // --- MARK: PUB FUNCTIONS ---
     pub fn handle_pointer_event(&mut self, event: PointerEvent) -> Handled {
         let _span = info_span!("pointer_event");
-        let handled = run_on_pointer_event_pass(self, &event);
-        run_update_pointer_pass(self);
+        let handled = self.profile_pass(ProfiledPass::Event, |root| {
+            run_on_pointer_event_pass(root, &event)
+        });
+        self.profile_pass(ProfiledPass::Update, run_update_pointer_pass);
         self.run_rewrite_passes();
+        self.run_synthesized_text_events();
 
         handled
     }
 
     pub fn handle_text_event(&mut self, event: TextEvent) -> Handled {
         let _span = info_span!("text_event");
-        let handled = run_on_text_event_pass(self, &event);
-        run_update_focus_pass(self);
+        let handled = self.profile_pass(ProfiledPass::Event, |root| {
+            run_on_text_event_pass(root, &event)
+        });
+        self.profile_pass(ProfiledPass::Update, run_update_focus_pass);
 
         if matches!(event, TextEvent::Ime(winit::event::Ime::Enabled)) {
             // Reset the last sent IME area, as the platform reset the IME state and may have
@@ -294,8 +486,18 @@ impl RenderRoot {
             data: event.data,
         };
 
-        run_on_access_event_pass(self, &event, WidgetId(id));
+        self.profile_pass(ProfiledPass::Event, |root| {
+            run_on_access_event_pass(root, &event, WidgetId(id));
+        });
         self.run_rewrite_passes();
+        self.run_synthesized_text_events();
+    }
+
+    /// Send the text events queued by widgets during the last event to the focused widget.
+    fn run_synthesized_text_events(&mut self) {
+        while let Some(event) = self.global_state.synthesized_text_events.pop_front() {
+            self.handle_text_event(event);
+        }
     }
 
     /// Registers all fonts that exist in the given data.
@@ -306,13 +508,68 @@ impl RenderRoot {
         &mut self,
         data: Vec<u8>,
     ) -> Vec<(fontique::FamilyId, Vec<fontique::FontInfo>)> {
+        // The contexts used for parallel shaping are copies of the main font context,
+        // so they need to be recreated to see the new fonts.
+        #[cfg(feature = "parallel_text")]
+        self.global_state.text_shaping_contexts.clear();
         self.global_state
             .font_context
             .collection
             .register_fonts(data)
     }
 
-    pub fn redraw(&mut self) -> (Scene, TreeUpdate) {
+    /// Whether the scene returned by the last call to [`redraw`](Self::redraw) differs from
+    /// the one before it.
+    ///
+    /// If it doesn't, a window which still shows the previous frame doesn't need to render it.
+    pub fn scene_changed(&self) -> bool {
+        !self.global_state.scene_reused
+    }
+
+    /// How much of the painted frames was reused from previous frames.
+    pub fn render_cache_stats(&self) -> RenderCacheStats {
+        self.global_state.render_cache_stats
+    }
+
+    /// The cache of decoded images and fonts shared by the widgets of this window.
+    ///
+    /// The cache is a handle, so it can be cloned to share it with other windows or
+    /// with the app.
+    pub fn resource_cache(&self) -> &ResourceCache {
+        &self.global_state.resource_cache
+    }
+
+    /// Replace the resource cache of this window, e.g. with a clone of the cache of
+    /// another window, so that they share their resources.
+    pub fn set_resource_cache(&mut self, cache: ResourceCache) {
+        self.global_state.resource_cache = cache;
+    }
+
+    /// Measure `text` as the widgets of this window would render it, with the fonts registered
+    /// with [`register_fonts`](Self::register_fonts).
+    ///
+    /// See [`measure_text`] for details.
+    pub fn measure_text(
+        &mut self,
+        text: &str,
+        styles: impl IntoIterator<Item = StyleProperty>,
+        max_width: Option<f32>,
+    ) -> TextMetrics {
+        measure_text(
+            &mut self.global_state.font_context,
+            &mut self.global_state.text_layout_context,
+            text,
+            styles,
+            max_width,
+        )
+    }
+
+    /// Run the paint and accessibility passes, and return the scene of the frame along with
+    /// the changes to the accessibility tree.
+    ///
+    /// The scene is shared with the render root, which returns it again as long as
+    /// [`scene_changed`](Self::scene_changed) is false.
+    pub fn redraw(&mut self) -> (Arc<Scene>, TreeUpdate) {
         if self.root_state().needs_layout {
             // TODO - Rewrite more clearly after run_rewrite_passes is rewritten
             self.run_rewrite_passes();
@@ -325,10 +582,22 @@ impl RenderRoot {
 
         // TODO - Handle invalidation regions
         // TODO - Improve caching of scenes.
-        (
-            run_paint_pass(self),
-            run_accessibility_pass(self, self.scale_factor),
-        )
+        let scale_factor = self.scale_factor;
+        let frame = (
+            self.profile_pass(ProfiledPass::Paint, run_paint_pass),
+            self.profile_pass(ProfiledPass::Accessibility, |root| {
+                run_accessibility_pass(root, scale_factor)
+            }),
+        );
+
+        // Every pass has now seen the widgets removed since the last frame.
+        let freed_ids = self.global_state.widget_pool.free_released_ids();
+        // The timers of these widgets mustn't be delivered to the new widgets reusing their ids.
+        self.global_state
+            .timers
+            .retain(|timer| !freed_ids.contains(&timer.widget));
+        self.global_state.profiler.end_frame();
+        frame
     }
 
     pub fn pop_signal(&mut self) -> Option<RenderRootSignal> {
@@ -347,6 +616,171 @@ impl RenderRoot {
         self.cursor_icon
     }
 
+    // --- MARK: TIMERS ---
+    /// When the next timer requested with [`EventCtx::request_timer`] expires, if any.
+    ///
+    /// The event loop should wake up at that time and call [`Self::handle_timers`].
+    ///
+    /// [`EventCtx::request_timer`]: crate::EventCtx::request_timer
+    pub fn next_timer_deadline(&self) -> Option<Instant> {
+        self.global_state
+            .timers
+            .iter()
+            .map(|timer| timer.deadline)
+            .min()
+    }
+
+    /// Send [`Update::Timer`](crate::Update::Timer) to the widgets whose timers have expired.
+    pub fn handle_timers(&mut self) {
+        let now = self.global_state.clock.now();
+        if !self
+            .next_timer_deadline()
+            .is_some_and(|deadline| deadline <= now)
+        {
+            return;
+        }
+        self.profile_pass(ProfiledPass::Update, run_update_timers_pass);
+        self.run_rewrite_passes();
+    }
+
+    // --- MARK: IDLE ---
+    /// Whether widgets have queued work to run when the event loop is idle.
+    pub fn has_idle_work(&self) -> bool {
+        !self.global_state.idle_callbacks.is_empty()
+    }
+
+    /// Run the callbacks queued with [`EventCtx::schedule_idle`], in order, until they have
+    /// all run or `budget` has elapsed.
+    ///
+    /// Returns whether callbacks are still queued, including those queued by the callbacks
+    /// which ran.
+    ///
+    /// [`EventCtx::schedule_idle`]: crate::EventCtx::schedule_idle
+    pub fn run_idle_work(&mut self, budget: Duration) -> bool {
+        let start = Instant::now();
+        while let Some(callback) = self.global_state.idle_callbacks.pop_front() {
+            // The widget may have been removed since it queued the callback
+            if self.widget_arena.has(callback.id) {
+                mutate_widget(self, callback.id, callback.callback);
+            }
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+        self.run_rewrite_passes();
+        self.has_idle_work()
+    }
+
+    // --- MARK: MONITORS ---
+    /// The monitors of the system.
+    pub fn monitors(&self) -> &[MonitorInfo] {
+        &self.global_state.monitors
+    }
+
+    /// Set the monitors of the system, which widgets can read.
+    ///
+    /// This is called by the event loop runner when the monitors may have changed.
+    pub fn set_monitors(&mut self, monitors: Vec<MonitorInfo>) {
+        self.global_state.monitors = monitors;
+    }
+
+    /// Where the window is on the desktop, if known.
+    pub fn window_placement(&self) -> Option<&WindowPlacement> {
+        self.global_state.window_placement.as_ref()
+    }
+
+    /// Set where the window is on the desktop, which widgets can read.
+    ///
+    /// This is called by the event loop runner when the window is moved or resized.
+    pub fn set_window_placement(&mut self, placement: WindowPlacement) {
+        self.global_state.window_placement = Some(placement);
+    }
+
+    // --- MARK: DENSITY ---
+    /// How large the built-in widgets and the paddings around them are.
+    pub fn density(&self) -> Density {
+        self.global_state.density
+    }
+
+    /// Set how large the built-in widgets and the paddings around them are, and lay out
+    /// every widget again.
+    pub fn set_density(&mut self, density: Density) {
+        if self.global_state.density == density {
+            return;
+        }
+        self.global_state.density = density;
+        self.request_layout_all();
+        self.run_rewrite_passes();
+    }
+
+    /// Set whether the density switches to [`Density::Touch`] the first time the window
+    /// is touched, which it does by default.
+    pub fn set_switch_to_touch_density(&mut self, switch: bool) {
+        self.global_state.switch_to_touch_density = switch;
+    }
+
+    /// Tell the render root that the window was touched, rather than clicked.
+    ///
+    /// This is called by the event loop runner before the pointer events of the touch.
+    pub fn handle_touch(&mut self) {
+        if std::mem::take(&mut self.global_state.switch_to_touch_density) {
+            self.set_density(Density::Touch);
+        }
+    }
+
+    // --- MARK: PROFILING ---
+    /// Enable or disable recording of pass and widget timings.
+    ///
+    /// Profiling can also be enabled by setting the `MASONRY_PROFILE_PASSES` environment variable.
+    /// Disabling profiling discards the timings recorded so far.
+    ///
+    /// While the `MASONRY_DEBUG_PAINT` environment variable is set, the timings of the last
+    /// frame are shown in the top left corner of the window.
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.global_state.profiler.set_enabled(enabled);
+    }
+
+    /// Whether pass and widget timings are being recorded.
+    pub fn is_profiling(&self) -> bool {
+        self.global_state.profiler.is_enabled()
+    }
+
+    /// The timings of the most recent frames, oldest first.
+    ///
+    /// Only the last 120 frames are kept.
+    pub fn frame_profiles(&self) -> impl DoubleEndedIterator<Item = &FrameProfile> {
+        self.global_state.profiler.frames()
+    }
+
+    /// The timings of the last rendered frame, if profiling is enabled.
+    pub fn last_frame_profile(&self) -> Option<&FrameProfile> {
+        self.frame_profiles().next_back()
+    }
+
+    /// Show or hide the heat overlay, which tints each widget in red by how long its `layout`
+    /// and `paint` methods took the last time they ran, relative to the slowest widget.
+    ///
+    /// The slowest widget is also outlined. Showing the overlay lays out and paints every
+    /// widget again, so that they are all measured. It doesn't require profiling to be enabled.
+    ///
+    /// The overlay can also be shown by setting the `MASONRY_HEAT_OVERLAY` environment variable.
+    pub fn set_heat_overlay(&mut self, enabled: bool) {
+        if self.global_state.profiler.is_heat_overlay_enabled() == enabled {
+            return;
+        }
+        self.global_state.profiler.set_heat_overlay(enabled);
+        if enabled {
+            self.request_layout_all();
+            self.run_rewrite_passes();
+        }
+        self.request_render_all();
+    }
+
+    /// Whether the heat overlay is shown.
+    pub fn is_heat_overlay_enabled(&self) -> bool {
+        self.global_state.profiler.is_heat_overlay_enabled()
+    }
+
     // --- MARK: ACCESS WIDGETS---
     /// Get a [`WidgetRef`] to the root widget.
     pub fn get_root_widget(&self) -> WidgetRef<dyn Widget> {
@@ -450,6 +884,14 @@ impl RenderRoot {
         kurbo::Size::new(size.width, size.height)
     }
 
+    /// Run `pass`, recording its duration if profiling is enabled.
+    fn profile_pass<R>(&mut self, pass: ProfiledPass, run: impl FnOnce(&mut Self) -> R) -> R {
+        let start = self.global_state.profiler.start();
+        let result = run(self);
+        self.global_state.profiler.record_pass(pass, start);
+        result
+    }
+
     // --- MARK: REWRITE PASSES ---
     /// Run all rewrite passes on widget tree.
     ///
@@ -466,16 +908,19 @@ impl RenderRoot {
             // Calling a run_xxx_pass (or root_xxx) should always be very fast if
             // the pass doesn't need to do anything.
 
-            run_mutate_pass(self);
-            run_update_widget_tree_pass(self);
-            run_update_disabled_pass(self);
-            run_update_stashed_pass(self);
-            run_update_focus_chain_pass(self);
-            run_update_focus_pass(self);
-            run_layout_pass(self);
-            run_update_scroll_pass(self);
-            run_compose_pass(self);
-            run_update_pointer_pass(self);
+            self.profile_pass(ProfiledPass::Update, |root| {
+                run_mutate_pass(root);
+                run_update_widget_tree_pass(root);
+                run_update_disabled_pass(root);
+                run_update_stashed_pass(root);
+                run_update_focus_chain_pass(root);
+                run_update_focus_pass(root);
+                run_update_commands_pass(root);
+            });
+            self.profile_pass(ProfiledPass::Layout, run_layout_pass);
+            self.profile_pass(ProfiledPass::Update, run_update_scroll_pass);
+            self.profile_pass(ProfiledPass::Compose, run_compose_pass);
+            self.profile_pass(ProfiledPass::Update, run_update_pointer_pass);
 
             if !self.root_state().needs_rewrite_passes()
                 && !self.global_state.needs_rewrite_passes()
@@ -546,43 +991,228 @@ impl RenderRoot {
             .emit_signal(RenderRootSignal::RequestRedraw);
     }
 
+    /// Lay out every widget again, e.g. because the density changed.
+    pub(crate) fn request_layout_all(&mut self) {
+        fn request_layout_all_in(
+            mut widget: ArenaMut<'_, Box<dyn Widget>>,
+            state: ArenaMut<'_, WidgetState>,
+        ) {
+            state.item.request_layout = true;
+            state.item.needs_layout = true;
+
+            let id = state.item.id;
+            recurse_on_children(
+                id,
+                widget.reborrow_mut(),
+                state.children,
+                |widget, mut state| {
+                    request_layout_all_in(widget, state.reborrow_mut());
+                },
+            );
+        }
+
+        let (root_widget, mut root_state) = self.widget_arena.get_pair_mut(self.root.id());
+        request_layout_all_in(root_widget, root_state.reborrow_mut());
+    }
+
     // Checks whether the given id points to a widget that is "interactive".
-    // i.e. not disabled or stashed.
+    // i.e. not disabled, stashed or hidden.
     // Only interactive widgets can have text focus or pointer capture.
     pub(crate) fn is_still_interactive(&self, id: WidgetId) -> bool {
         let Some(state) = self.widget_arena.widget_states.find(id) else {
             return false;
         };
 
-        !state.item.is_stashed && !state.item.is_disabled
+        !state.item.is_stashed && !state.item.is_disabled && !state.item.is_hidden
     }
 
+    /// The widget which Tab (or Shift+Tab if not `forward`) should move the focus to.
+    ///
+    /// Focus groups are treated as a single step, which focuses their first member in Tab order.
     pub(crate) fn widget_from_focus_chain(&mut self, forward: bool) -> Option<WidgetId> {
+        let chain = self.tab_order();
+        let groups: Vec<_> = chain.iter().map(|&id| self.focus_group_of(id)).collect();
+        let len = chain.len();
+        // The first member of the focus group at `idx`, or the widget itself if it isn't in one
+        let group_entry = |idx: usize| match groups[idx] {
+            Some(group) => chain[groups.iter().position(|&g| g == Some(group)).unwrap()],
+            None => chain[idx],
+        };
+
         let focused_widget = self.global_state.focused_widget;
         let focused_idx = focused_widget.and_then(|focused_widget| {
-            self.focus_chain()
+            chain
                 .iter()
                 // Find where the focused widget is in the focus chain
                 .position(|id| id == &focused_widget)
         });
 
-        if let Some(idx) = focused_idx {
-            // Return the id that's next to it in the focus chain
-            let len = self.focus_chain().len();
-            let new_idx = if forward {
-                (idx + 1) % len
-            } else {
-                (idx + len - 1) % len
-            };
-            Some(self.focus_chain()[new_idx])
-        } else {
+        let Some(idx) = focused_idx else {
             // If no widget is currently focused or the
             // currently focused widget isn't in the focus chain,
             // then we'll just return the first/last entry of the chain, if any.
-            if forward {
-                self.focus_chain().first().copied()
+            if len == 0 {
+                return None;
+            }
+            return Some(group_entry(if forward { 0 } else { len - 1 }));
+        };
+
+        // Return the id that's next to it in the focus chain, skipping the rest of its group
+        let mut new_idx = idx;
+        loop {
+            new_idx = if forward {
+                (new_idx + 1) % len
             } else {
-                self.focus_chain().last().copied()
+                (new_idx + len - 1) % len
+            };
+            if new_idx == idx || groups[new_idx].is_none() || groups[new_idx] != groups[idx] {
+                break;
+            }
+        }
+        Some(group_entry(new_idx))
+    }
+
+    /// The widget which `motion` should move the focus to, inside the focus group of
+    /// the focused widget.
+    ///
+    /// Returns `None` if the focused widget isn't in a focus group, or if there is no
+    /// widget in that direction of a grid.
+    pub(crate) fn widget_from_focus_group(&mut self, motion: GroupMotion) -> Option<WidgetId> {
+        let focused_widget = self.global_state.focused_widget?;
+        let group = self.focus_group_of(focused_widget)?;
+        let members = self.focus_group_members(group);
+
+        let idx = members.iter().position(|&id| id == focused_widget)?;
+        let len = members.len();
+        let role = self
+            .widget_arena
+            .get_widget(group)
+            .item
+            .accessibility_role();
+        let is_grid = role == Role::Grid;
+        let new_idx = match motion {
+            GroupMotion::First => 0,
+            GroupMotion::Last => len - 1,
+            GroupMotion::Up | GroupMotion::Down | GroupMotion::Left | GroupMotion::Right
+                if is_grid =>
+            {
+                return self.nearest_in_direction(focused_widget, &members, motion);
+            }
+            GroupMotion::Down | GroupMotion::Right => (idx + 1) % len,
+            GroupMotion::Up | GroupMotion::Left => (idx + len - 1) % len,
+        };
+        Some(members[new_idx])
+    }
+
+    /// The next widget after the focused one in its focus group whose name starts with `text`,
+    /// wrapping around to the start of the group.
+    ///
+    /// This implements type-ahead for lists and menus. Each key press is matched on its own and
+    /// case-insensitively, so typing the same letter again cycles through the matching widgets.
+    pub(crate) fn widget_from_type_ahead(&mut self, text: &str) -> Option<WidgetId> {
+        let focused_widget = self.global_state.focused_widget?;
+        let group = self.focus_group_of(focused_widget)?;
+        let members = self.focus_group_members(group);
+        let text = text.to_lowercase();
+        if text.trim().is_empty() {
+            return None;
+        }
+
+        let idx = members.iter().position(|&id| id == focused_widget)?;
+        let len = members.len();
+        (1..=len)
+            .map(|offset| members[(idx + offset) % len])
+            .find(|&id| {
+                self.accessible_name_of(id)
+                    .is_some_and(|name| name.to_lowercase().starts_with(&text))
+            })
+    }
+
+    /// The members of `group` which accept focus, in Tab order.
+    fn focus_group_members(&mut self, group: WidgetId) -> Vec<WidgetId> {
+        self.tab_order()
+            .into_iter()
+            .filter(|&id| self.focus_group_of(id) == Some(group))
+            .collect()
+    }
+
+    /// The member of a grid closest to `from` in the direction of `motion`, by layout position.
+    fn nearest_in_direction(
+        &self,
+        from: WidgetId,
+        members: &[WidgetId],
+        motion: GroupMotion,
+    ) -> Option<WidgetId> {
+        let center = |id: WidgetId| {
+            let (_, state) = self.widget_arena.get_pair(id);
+            state.item.window_layout_rect().center()
+        };
+        let origin = center(from);
+        members
+            .iter()
+            .filter(|&&id| id != from)
+            .filter_map(|&id| {
+                let offset = center(id) - origin;
+                // The distance along the direction of motion, and the distance across it
+                let (along, across) = match motion {
+                    GroupMotion::Up => (-offset.y, offset.x),
+                    GroupMotion::Down => (offset.y, offset.x),
+                    GroupMotion::Left => (-offset.x, offset.y),
+                    GroupMotion::Right => (offset.x, offset.y),
+                    GroupMotion::First | GroupMotion::Last => unreachable!(),
+                };
+                // Prefer staying in the same row or column over the closest widget
+                (along > 0.5).then_some((id, along + 2. * across.abs()))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id)
+    }
+
+    /// The name of a widget for type-ahead: its accessible name if it was overridden,
+    /// or else the first text found in its subtree.
+    fn accessible_name_of(&self, id: WidgetId) -> Option<String> {
+        let (widget, state) = self.widget_arena.get_pair(id);
+        if let Some(name) = &state.item.access_overrides.name {
+            return Some(name.clone());
+        }
+        if let Some(text) = widget.item.get_debug_text() {
+            return Some(text);
+        }
+        widget
+            .item
+            .children_ids()
+            .into_iter()
+            .find_map(|child| self.accessible_name_of(child))
+    }
+
+    /// The focus chain, sorted by tab index.
+    fn tab_order(&mut self) -> Vec<WidgetId> {
+        let mut chain = self.focus_chain().to_vec();
+        // The sort is stable, so widgets with the same tab index stay in tree order
+        chain.sort_by_key(|&id| {
+            let tab_index = self
+                .widget_arena
+                .widget_states
+                .find(id)
+                .and_then(|state| state.item.tab_index);
+            (tab_index.is_none(), tab_index)
+        });
+        chain
+    }
+
+    /// The closest ancestor of `id` which is a focus group.
+    pub(crate) fn focus_group_of(&self, id: WidgetId) -> Option<WidgetId> {
+        let mut current = id;
+        loop {
+            let parent_id = self.widget_arena.widgets.find(current)?.parent_id?;
+            current = WidgetId(parent_id.try_into().unwrap());
+            let (widget, state) = self.widget_arena.get_pair(current);
+            if state
+                .item
+                .is_focus_group
+                .unwrap_or_else(|| widget.item.is_focus_group())
+            {
+                return Some(current);
             }
         }
     }
@@ -617,7 +1247,9 @@ impl RenderRootState {
     }
 
     pub(crate) fn needs_rewrite_passes(&self) -> bool {
-        self.needs_pointer_pass || self.focused_widget != self.next_focused_widget
+        self.needs_pointer_pass
+            || self.focused_widget != self.next_focused_widget
+            || !self.commands.is_empty()
     }
 }
 
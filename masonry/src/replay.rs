@@ -0,0 +1,402 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording the input of the event loop runner to a file, and replaying it.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use serde::{Deserialize, Serialize};
+use winit::event::{
+    ElementState, Ime, MouseScrollDelta, TouchPhase as WinitTouchPhase,
+    WindowEvent as WinitWindowEvent,
+};
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+use crate::PointerButton;
+
+/// The environment variable which makes the event loop runner record its input to a file.
+pub const RECORD_SESSION_VAR: &str = "MASONRY_RECORD_SESSION";
+
+/// The environment variable which makes the event loop runner replay the input recorded in a file.
+pub const REPLAY_SESSION_VAR: &str = "MASONRY_REPLAY_SESSION";
+
+/// The input received by an app's window, with the time it was received.
+///
+/// Sessions are recorded by the event loop runner when the [`MASONRY_RECORD_SESSION`] environment
+/// variable is set to a file path, or after [`MasonryState::record_session`] is called.
+/// They are replayed when [`MASONRY_REPLAY_SESSION`] is set to the path of a recording, or with
+/// [`MasonryState::replay_session`].
+///
+/// Whilst a session is replayed, the input from the window is ignored, and the time seen by
+/// widgets (for instance in [`Widget::on_anim_frame`]) is the time at which each event was
+/// recorded, so that the app goes through the same states as when it was recorded.
+///
+/// Winit doesn't allow creating keyboard events, so key presses are replayed as the text they
+/// produce, except for Tab, the arrow keys, Home and End, which move the focus as usual.
+/// Other keys, such as Backspace, aren't replayed.
+///
+/// [`MASONRY_RECORD_SESSION`]: RECORD_SESSION_VAR
+/// [`MASONRY_REPLAY_SESSION`]: REPLAY_SESSION_VAR
+/// [`MasonryState::record_session`]: crate::event_loop_runner::MasonryState::record_session
+/// [`MasonryState::replay_session`]: crate::event_loop_runner::MasonryState::replay_session
+/// [`Widget::on_anim_frame`]: crate::Widget::on_anim_frame
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// The events, in the order they were received.
+    pub events: Vec<RecordedEvent>,
+}
+
+/// An event of a [`Session`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// The time since the start of the session.
+    pub time: Duration,
+    /// The event.
+    pub event: SessionEvent,
+}
+
+/// The input events which can be recorded in a [`Session`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SessionEvent {
+    /// The window was resized, in physical pixels.
+    Resize { width: u32, height: u32 },
+    /// The scale factor of the window changed.
+    Rescale { scale_factor: f64 },
+    /// The window was given or lost the focus.
+    Focus { focused: bool },
+    /// An animation frame was rendered.
+    AnimFrame,
+    /// The modifier keys changed, as the bits of a [`ModifiersState`](winit::keyboard::ModifiersState).
+    Modifiers { bits: u32 },
+    /// A key was pressed or released.
+    Key {
+        /// The `Debug` representation of the [`KeyCode`] of the physical key, if known.
+        code: Option<String>,
+        /// The text produced by the key press.
+        text: Option<String>,
+        pressed: bool,
+        repeat: bool,
+    },
+    /// An IME event.
+    Ime(ImeEvent),
+    /// The pointer entered the window.
+    PointerEnter,
+    /// The pointer moved, in physical pixels.
+    PointerMove { x: f64, y: f64 },
+    /// The pointer left the window.
+    PointerLeave,
+    /// A pointer button was pressed.
+    PointerDown { button: PointerButton },
+    /// A pointer button was released.
+    PointerUp { button: PointerButton },
+    /// The mouse wheel or touchpad was scrolled, in lines, or in logical pixels if `pixels` is
+    /// true.
+    Wheel {
+        x: f64,
+        y: f64,
+        #[serde(default)]
+        pixels: bool,
+    },
+    /// A touch event, in physical pixels.
+    Touch { x: f64, y: f64, phase: TouchPhase },
+    /// A pinch gesture.
+    Pinch { delta: f64 },
+}
+
+/// The IME events of a [`Session`], mirroring [`winit::event::Ime`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ImeEvent {
+    Enabled,
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    Commit {
+        text: String,
+    },
+    Disabled,
+}
+
+/// The phase of a [`SessionEvent::Touch`], mirroring [`winit::event::TouchPhase`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+impl Session {
+    /// Read a session from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Write the session to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+}
+
+impl SessionEvent {
+    /// Convert an input event of the window, if it should be recorded.
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn from_winit(event: &WinitWindowEvent, scale_factor: f64) -> Option<Self> {
+        let event = match event {
+            WinitWindowEvent::Resized(size) => Self::Resize {
+                width: size.width,
+                height: size.height,
+            },
+            WinitWindowEvent::ScaleFactorChanged { scale_factor, .. } => Self::Rescale {
+                scale_factor: *scale_factor,
+            },
+            WinitWindowEvent::Focused(focused) => Self::Focus { focused: *focused },
+            WinitWindowEvent::ModifiersChanged(modifiers) => Self::Modifiers {
+                bits: modifiers.state().bits(),
+            },
+            WinitWindowEvent::KeyboardInput {
+                event,
+                is_synthetic: false,
+                ..
+            } => Self::Key {
+                code: match event.physical_key {
+                    PhysicalKey::Code(code) => Some(format!("{code:?}")),
+                    PhysicalKey::Unidentified(_) => None,
+                },
+                text: event.text.as_ref().map(ToString::to_string),
+                pressed: event.state == ElementState::Pressed,
+                repeat: event.repeat,
+            },
+            WinitWindowEvent::Ime(ime) => Self::Ime(match ime {
+                Ime::Enabled => ImeEvent::Enabled,
+                Ime::Preedit(text, cursor) => ImeEvent::Preedit {
+                    text: text.clone(),
+                    cursor: *cursor,
+                },
+                Ime::Commit(text) => ImeEvent::Commit { text: text.clone() },
+                Ime::Disabled => ImeEvent::Disabled,
+            }),
+            WinitWindowEvent::CursorEntered { .. } => Self::PointerEnter,
+            WinitWindowEvent::CursorMoved { position, .. } => Self::PointerMove {
+                x: position.x,
+                y: position.y,
+            },
+            WinitWindowEvent::CursorLeft { .. } => Self::PointerLeave,
+            WinitWindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed => Self::PointerDown {
+                    button: (*button).into(),
+                },
+                ElementState::Released => Self::PointerUp {
+                    button: (*button).into(),
+                },
+            },
+            WinitWindowEvent::MouseWheel { delta, .. } => {
+                let (x, y, pixels) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (*x as f64, *y as f64, false),
+                    MouseScrollDelta::PixelDelta(delta) => {
+                        let delta = delta.to_logical::<f64>(scale_factor);
+                        (delta.x, delta.y, true)
+                    }
+                };
+                Self::Wheel { x, y, pixels }
+            }
+            WinitWindowEvent::Touch(touch) => Self::Touch {
+                x: touch.location.x,
+                y: touch.location.y,
+                phase: match touch.phase {
+                    WinitTouchPhase::Started => TouchPhase::Started,
+                    WinitTouchPhase::Moved => TouchPhase::Moved,
+                    WinitTouchPhase::Ended => TouchPhase::Ended,
+                    WinitTouchPhase::Cancelled => TouchPhase::Cancelled,
+                },
+            },
+            WinitWindowEvent::PinchGesture { delta, .. } => Self::Pinch { delta: *delta },
+            _ => return None,
+        };
+        Some(event)
+    }
+
+    /// The key code of a [`SessionEvent::Key`], if it's one which can be replayed.
+    #[cfg_attr(
+        not(feature = "event_loop_runner"),
+        expect(dead_code, reason = "Only used by the winit event loop runner")
+    )]
+    pub(crate) fn navigation_key(code: &str) -> Option<KeyCode> {
+        let code = match code {
+            "Tab" => KeyCode::Tab,
+            "ArrowUp" => KeyCode::ArrowUp,
+            "ArrowDown" => KeyCode::ArrowDown,
+            "ArrowLeft" => KeyCode::ArrowLeft,
+            "ArrowRight" => KeyCode::ArrowRight,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            _ => return None,
+        };
+        Some(code)
+    }
+}
+
+/// Records the events of a [`Session`], and saves it when dropped.
+#[cfg_attr(
+    not(feature = "event_loop_runner"),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+pub(crate) struct Recorder {
+    path: PathBuf,
+    start: Instant,
+    session: Session,
+}
+
+#[cfg_attr(
+    not(feature = "event_loop_runner"),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+impl Recorder {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            start: Instant::now(),
+            session: Session::default(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, event: SessionEvent) {
+        self.session.events.push(RecordedEvent {
+            time: self.start.elapsed(),
+            event,
+        });
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        // The recorder is dropped when the event loop exits, including if the app panics,
+        // which is when recordings are most useful.
+        match self.session.save(&self.path) {
+            Ok(()) => tracing::info!("Saved input session to {}", self.path.display()),
+            Err(err) => tracing::error!(
+                "Couldn't save input session to {}: {err}",
+                self.path.display()
+            ),
+        }
+    }
+}
+
+/// Hands out the events of a [`Session`] as their time comes.
+#[cfg_attr(
+    all(not(feature = "event_loop_runner"), not(test)),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+pub(crate) struct Replayer {
+    /// When the replay started; `None` until the first event is requested.
+    start: Option<Instant>,
+    /// The time of the last event handed out.
+    time: Duration,
+    events: VecDeque<RecordedEvent>,
+}
+
+#[cfg_attr(
+    all(not(feature = "event_loop_runner"), not(test)),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
+impl Replayer {
+    pub(crate) fn new(session: Session) -> Self {
+        Self {
+            start: None,
+            time: Duration::ZERO,
+            events: session.events.into(),
+        }
+    }
+
+    /// The next event which is due at `now`, with the time elapsed in the session since
+    /// the previous event.
+    pub(crate) fn next_due(&mut self, now: Instant) -> Option<(Duration, SessionEvent)> {
+        let start = *self.start.get_or_insert(now);
+        let next = self.events.front()?;
+        if start + next.time > now {
+            return None;
+        }
+        let next = self.events.pop_front()?;
+        let elapsed = next.time.saturating_sub(self.time);
+        self.time = next.time;
+        Some((elapsed, next.event))
+    }
+
+    /// When the next event is due, if the replay has started and isn't finished.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        Some(self.start? + self.events.front()?.time)
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session {
+            events: vec![
+                RecordedEvent {
+                    time: Duration::ZERO,
+                    event: SessionEvent::PointerMove { x: 10., y: 20. },
+                },
+                RecordedEvent {
+                    time: Duration::from_millis(100),
+                    event: SessionEvent::PointerDown {
+                        button: PointerButton::Primary,
+                    },
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn session_round_trip() {
+        let json = serde_json::to_string(&session()).unwrap();
+        let session_from_json: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(session_from_json, session());
+    }
+
+    #[test]
+    fn replay_timing() {
+        let mut replayer = Replayer::new(session());
+        let start = Instant::now();
+
+        assert_eq!(
+            replayer.next_due(start),
+            Some((Duration::ZERO, SessionEvent::PointerMove { x: 10., y: 20. }))
+        );
+        assert_eq!(replayer.next_due(start), None);
+        assert_eq!(
+            replayer.next_deadline(),
+            Some(start + Duration::from_millis(100))
+        );
+
+        assert_eq!(
+            replayer.next_due(start + Duration::from_millis(150)),
+            Some((
+                Duration::from_millis(100),
+                SessionEvent::PointerDown {
+                    button: PointerButton::Primary
+                }
+            ))
+        );
+        assert!(replayer.is_finished());
+    }
+}
@@ -0,0 +1,444 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cache of decoded resources, such as images and fonts, shared by a whole app.
+//!
+//! Decoding the same bytes again for each widget which shows them is wasteful, e.g. in a
+//! list of messages showing the avatar of their sender. Instead, resources are decoded once,
+//! stored in the [`ResourceCache`] under a [`ResourceKey`], and given out as [`Resource`]
+//! handles which are cheap to clone and compare.
+//!
+//! The cache of a window is available from [`RenderRoot::resource_cache`](crate::RenderRoot::resource_cache)
+//! and from the contexts of its widgets. As the cache is itself a handle, it can also be cloned
+//! into the app state, so that views can load resources from it.
+
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use vello::peniko::{Format, Image as ImageBuf};
+
+/// The key under which a resource is stored in a [`ResourceCache`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceKey {
+    /// The URL or path the resource was loaded from.
+    Url(Arc<str>),
+    /// A hash of the encoded bytes of the resource, see [`ResourceKey::hash_of`].
+    Hash(u64),
+}
+
+impl ResourceKey {
+    /// The key of a resource loaded from `url`.
+    pub fn url(url: impl Into<Arc<str>>) -> Self {
+        Self::Url(url.into())
+    }
+
+    /// The key of a resource encoded as `bytes`, for resources which don't have a URL,
+    /// e.g. because they are embedded in the app.
+    ///
+    /// The hash is only stable within a run of the app.
+    pub fn hash_of(bytes: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        Self::Hash(hasher.finish())
+    }
+}
+
+impl From<&str> for ResourceKey {
+    fn from(url: &str) -> Self {
+        Self::url(url)
+    }
+}
+
+impl From<String> for ResourceKey {
+    fn from(url: String) -> Self {
+        Self::url(url)
+    }
+}
+
+impl From<Arc<str>> for ResourceKey {
+    fn from(url: Arc<str>) -> Self {
+        Self::Url(url)
+    }
+}
+
+/// A handle to a resource stored in a [`ResourceCache`].
+///
+/// Cloning a handle doesn't clone the resource, and the resource stays alive as long as
+/// one of its handles does, even once it has been evicted from the cache.
+pub struct Resource<T: ?Sized> {
+    value: Arc<T>,
+}
+
+impl<T: ?Sized> Resource<T> {
+    /// Create a handle to a resource which isn't stored in a cache.
+    pub fn new(value: impl Into<Arc<T>>) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for Resource<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for Resource<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for Resource<T> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// Two `Resource`s are equal if they are handles to the same resource.
+///
+/// This makes comparing resources cheap, e.g. when views are rebuilt.
+impl<T: ?Sized> PartialEq for Resource<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.value, &other.value)
+    }
+}
+
+impl<T: ?Sized> Eq for Resource<T> {}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Resource<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Resource").field(&&*self.value).finish()
+    }
+}
+
+/// How a [`ResourceCache`] has been used, e.g. to tune its capacity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceCacheStats {
+    /// The number of resources in the cache.
+    pub entries: usize,
+    /// The sum of the sizes of the resources in the cache, in bytes.
+    pub size_bytes: usize,
+    /// The number of lookups which found their resource in the cache.
+    pub hits: u64,
+    /// The number of lookups which had to load their resource.
+    pub misses: u64,
+    /// The number of resources removed to stay under the capacity.
+    pub evictions: u64,
+}
+
+/// A cache of decoded resources, keyed by [`ResourceKey`].
+///
+/// When the resources take more bytes than the capacity of the cache, the least recently
+/// used ones are evicted. The handles to evicted resources stay valid, but the next lookup
+/// of their key loads them again.
+///
+/// Any type can be cached with [`get_or_insert_with`](Self::get_or_insert_with), e.g.
+/// SVG documents parsed by the app. Images and font data have dedicated methods.
+///
+/// A `ResourceCache` is a handle: its clones share the same resources.
+#[derive(Clone)]
+pub struct ResourceCache {
+    inner: Arc<Mutex<CacheInner>>,
+}
+
+struct CacheInner {
+    entries: HashMap<ResourceKey, CacheEntry>,
+    capacity_bytes: usize,
+    size_bytes: usize,
+    /// Incremented on each lookup, to find the least recently used entries.
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+struct CacheEntry {
+    value: Arc<dyn Any + Send + Sync>,
+    size_bytes: usize,
+    last_used: u64,
+}
+
+impl ResourceCache {
+    /// The capacity of the caches created by [`RenderRoot`](crate::RenderRoot), 256 MiB.
+    pub const DEFAULT_CAPACITY: usize = 256 * 1024 * 1024;
+
+    /// Create an empty cache which holds resources taking up to `capacity_bytes` bytes.
+    pub fn new(capacity_bytes: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(CacheInner {
+                entries: HashMap::new(),
+                capacity_bytes,
+                size_bytes: 0,
+                clock: 0,
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+            })),
+        }
+    }
+
+    /// The number of bytes of resources this cache holds before evicting some.
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().unwrap().capacity_bytes
+    }
+
+    /// Set the number of bytes of resources this cache holds, evicting resources if needed.
+    pub fn set_capacity(&self, capacity_bytes: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.capacity_bytes = capacity_bytes;
+        inner.evict();
+    }
+
+    /// Get the resource stored under `key`, if there is one of type `T`.
+    pub fn get<T: Any + Send + Sync>(&self, key: &ResourceKey) -> Option<Resource<T>> {
+        let mut inner = self.inner.lock().unwrap();
+        let value = inner.lookup(key)?.downcast::<T>().ok()?;
+        inner.hits += 1;
+        Some(Resource { value })
+    }
+
+    /// Store `value`, which takes `size_bytes` bytes, under `key`, replacing the resource
+    /// previously stored there.
+    pub fn insert<T: Any + Send + Sync>(
+        &self,
+        key: ResourceKey,
+        value: T,
+        size_bytes: usize,
+    ) -> Resource<T> {
+        let value = Arc::new(value);
+        self.inner
+            .lock()
+            .unwrap()
+            .insert(key, value.clone(), size_bytes);
+        Resource { value }
+    }
+
+    /// Get the resource stored under `key`, or load it with `load` and store it.
+    ///
+    /// `load` returns the resource and the number of bytes it takes. If it fails, nothing
+    /// is stored, and its error is returned.
+    ///
+    /// The cache isn't locked while `load` runs, so several threads may load the same
+    /// resource at once, in which case the last one is kept.
+    pub fn get_or_insert_with<T: Any + Send + Sync, E>(
+        &self,
+        key: ResourceKey,
+        load: impl FnOnce() -> Result<(T, usize), E>,
+    ) -> Result<Resource<T>, E> {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some(value) = inner.lookup(&key) {
+                if let Ok(value) = value.downcast::<T>() {
+                    inner.hits += 1;
+                    return Ok(Resource { value });
+                }
+            }
+            inner.misses += 1;
+        }
+        let (value, size_bytes) = load()?;
+        Ok(self.insert(key, value, size_bytes))
+    }
+
+    /// Get the image stored under `key`, or decode it from `bytes` and store it.
+    ///
+    /// Only the image formats enabled in the features of the `image` crate can be decoded,
+    /// e.g. PNG with the `png` feature of Masonry.
+    pub fn image(
+        &self,
+        key: impl Into<ResourceKey>,
+        bytes: &[u8],
+    ) -> Result<Resource<ImageBuf>, image::ImageError> {
+        self.get_or_insert_with(key.into(), || {
+            let image = image::load_from_memory(bytes)?.into_rgba8();
+            let (width, height) = image.dimensions();
+            let data = image.into_raw();
+            let size_bytes = data.len();
+            Ok((
+                ImageBuf::new(data.into(), Format::Rgba8, width, height),
+                size_bytes,
+            ))
+        })
+    }
+
+    /// Get the font data stored under `key`, or load it with `load` and store it.
+    ///
+    /// The data can then be given to [`RenderRoot::register_fonts`](crate::RenderRoot::register_fonts).
+    pub fn font_data(
+        &self,
+        key: impl Into<ResourceKey>,
+        load: impl FnOnce() -> Vec<u8>,
+    ) -> Resource<Vec<u8>> {
+        self.get_or_insert_with(key.into(), || {
+            let data = load();
+            let size_bytes = data.len();
+            Ok::<_, Infallible>((data, size_bytes))
+        })
+        .unwrap_or_else(|never| match never {})
+    }
+
+    /// Remove the resource stored under `key`, returning whether there was one.
+    pub fn remove(&self, key: &ResourceKey) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(entry) = inner.entries.remove(key) else {
+            return false;
+        };
+        inner.size_bytes -= entry.size_bytes;
+        true
+    }
+
+    /// Remove all resources, e.g. when the system is low on memory.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.entries.clear();
+        inner.size_bytes = 0;
+    }
+
+    /// How this cache has been used.
+    pub fn stats(&self) -> ResourceCacheStats {
+        let inner = self.inner.lock().unwrap();
+        ResourceCacheStats {
+            entries: inner.entries.len(),
+            size_bytes: inner.size_bytes,
+            hits: inner.hits,
+            misses: inner.misses,
+            evictions: inner.evictions,
+        }
+    }
+}
+
+impl Default for ResourceCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl fmt::Debug for ResourceCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceCache")
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Two `ResourceCache`s are equal if they are handles to the same cache.
+impl PartialEq for ResourceCache {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl CacheInner {
+    /// Find the entry stored under `key`, marking it as used.
+    fn lookup(&mut self, key: &ResourceKey) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.clock;
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: ResourceKey, value: Arc<dyn Any + Send + Sync>, size_bytes: usize) {
+        self.clock += 1;
+        let entry = CacheEntry {
+            value,
+            size_bytes,
+            last_used: self.clock,
+        };
+        if let Some(previous) = self.entries.insert(key, entry) {
+            self.size_bytes -= previous.size_bytes;
+        }
+        self.size_bytes += size_bytes;
+        self.evict();
+    }
+
+    /// Evict the least recently used entries until the cache is under its capacity.
+    fn evict(&mut self) {
+        while self.size_bytes > self.capacity_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            let entry = self.entries.remove(&oldest).unwrap();
+            self.size_bytes -= entry.size_bytes;
+            self.evictions += 1;
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(value: u32, size_bytes: usize) -> Result<(u32, usize), ()> {
+        Ok((value, size_bytes))
+    }
+
+    #[test]
+    fn hits_share_the_resource() {
+        let cache = ResourceCache::new(100);
+        let first = cache
+            .get_or_insert_with(ResourceKey::url("avatar.png"), || load(1, 10))
+            .unwrap();
+        let second = cache
+            .get_or_insert_with(
+                ResourceKey::url("avatar.png"),
+                || -> Result<(u32, usize), ()> { panic!("the resource should be cached") },
+            )
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(*second, 1);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!((stats.entries, stats.size_bytes), (1, 10));
+    }
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let cache = ResourceCache::new(30);
+        let keys = ["a", "b", "c"].map(ResourceKey::url);
+        for (value, key) in keys.iter().enumerate() {
+            cache
+                .get_or_insert_with(key.clone(), || load(value as u32, 10))
+                .unwrap();
+        }
+        // Using "a" and "c" after "b" makes "b" the least recently used.
+        let handle = cache.get::<u32>(&keys[1]).unwrap();
+        cache.get::<u32>(&keys[0]).unwrap();
+        cache.get::<u32>(&keys[2]).unwrap();
+        cache.insert(ResourceKey::url("d"), 3_u32, 10);
+
+        assert!(cache.get::<u32>(&keys[1]).is_none());
+        assert!(cache.get::<u32>(&keys[0]).is_some());
+        assert!(cache.get::<u32>(&keys[2]).is_some());
+        // Evicted resources stay alive while they have handles.
+        assert_eq!(*handle, 1);
+
+        let stats = cache.stats();
+        assert_eq!(
+            (stats.entries, stats.size_bytes, stats.evictions),
+            (3, 30, 1)
+        );
+
+        // Only "c", which was used last, fits in the new capacity.
+        cache.set_capacity(10);
+        assert_eq!(cache.stats().entries, 1);
+        assert!(cache.get::<u32>(&keys[2]).is_some());
+    }
+}
@@ -0,0 +1,321 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A selection model for lists, tables and trees.
+//!
+//! [`Selection`] tracks which items of a collection are selected, by index, and updates
+//! itself from the [`SelectionInput`]s submitted by [`Selectable`](crate::widget::Selectable)
+//! items.
+//! Trees use the indices of their visible rows.
+//!
+//! The items render their selected state themselves, usually by checking
+//! [`Selection::is_selected`] when they're built.
+
+use std::collections::BTreeSet;
+
+/// Which items of a [`Selection`] can be selected together.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// At most one item is selected.
+    #[default]
+    Single,
+    /// Any number of items is selected.
+    ///
+    /// Items are added to the selection with <kbd>Ctrl</kbd>+click (<kbd>Cmd</kbd>+click on
+    /// macOS), and ranges of items are selected with <kbd>Shift</kbd>+click.
+    Multiple,
+}
+
+/// An input from the user changing a [`Selection`], relative to the item it targets.
+///
+/// This is the [`ActionSource::Action`](crate::ActionSource::Action) of
+/// [`Selectable`](crate::widget::Selectable).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionInput {
+    /// The item was clicked.
+    Click {
+        /// Whether the item is toggled, rather than selected alone.
+        toggle: bool,
+        /// Whether the selection is extended to the item.
+        extend: bool,
+    },
+    /// The selection focus is moved from the item, e.g. with the arrow keys.
+    Move {
+        /// Where the focus is moved.
+        movement: SelectionMove,
+        /// Whether the selection is extended to the new focus.
+        extend: bool,
+    },
+    /// The item is toggled, e.g. with <kbd>Space</kbd>.
+    Toggle,
+    /// All the items are selected.
+    SelectAll,
+}
+
+/// A movement of the focus of a [`Selection`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionMove {
+    /// To the previous item.
+    Previous,
+    /// To the next item.
+    Next,
+    /// To the first item.
+    First,
+    /// To the last item.
+    Last,
+}
+
+/// The selected items of a collection, by index.
+///
+/// Besides the selected items, this tracks the *focus*, the item last clicked or moved to,
+/// and the *anchor*, the item from which ranges are extended.
+///
+/// The indices must be kept in sync with the collection, with [`insert_item`](Self::insert_item),
+/// [`remove_item`](Self::remove_item) and [`set_item_count`](Self::set_item_count).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    mode: SelectionMode,
+    item_count: usize,
+    selected: BTreeSet<usize>,
+    anchor: Option<usize>,
+    focus: Option<usize>,
+}
+
+impl Selection {
+    /// Create an empty selection of a collection of `item_count` items.
+    pub fn new(mode: SelectionMode, item_count: usize) -> Self {
+        Self {
+            mode,
+            item_count,
+            ..Self::default()
+        }
+    }
+
+    /// Which items can be selected together.
+    pub fn mode(&self) -> SelectionMode {
+        self.mode
+    }
+
+    /// The number of items of the collection.
+    pub fn item_count(&self) -> usize {
+        self.item_count
+    }
+
+    /// Whether the item at `index` is selected.
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    /// The indices of the selected items, in increasing order.
+    pub fn selected(&self) -> impl Iterator<Item = usize> + '_ {
+        self.selected.iter().copied()
+    }
+
+    /// The item last clicked or moved to, if any.
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    /// Deselect all the items.
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.anchor = None;
+    }
+
+    /// Select the item at `index` alone.
+    pub fn select(&mut self, index: usize) {
+        if index >= self.item_count {
+            return;
+        }
+        self.selected.clear();
+        self.selected.insert(index);
+        self.anchor = Some(index);
+        self.focus = Some(index);
+    }
+
+    /// Toggle whether the item at `index` is selected.
+    ///
+    /// In [`SelectionMode::Single`], selecting an item deselects the others.
+    pub fn toggle(&mut self, index: usize) {
+        if index >= self.item_count {
+            return;
+        }
+        if self.selected.remove(&index) {
+            self.anchor = Some(index);
+            self.focus = Some(index);
+        } else if self.mode == SelectionMode::Single {
+            self.select(index);
+        } else {
+            self.selected.insert(index);
+            self.anchor = Some(index);
+            self.focus = Some(index);
+        }
+    }
+
+    /// Select the items from the anchor to the item at `index`.
+    ///
+    /// In [`SelectionMode::Single`], this selects the item at `index` alone.
+    pub fn extend_to(&mut self, index: usize) {
+        if index >= self.item_count {
+            return;
+        }
+        let anchor = match (self.mode, self.anchor) {
+            (SelectionMode::Multiple, Some(anchor)) => anchor,
+            _ => return self.select(index),
+        };
+        self.selected = (anchor.min(index)..=anchor.max(index)).collect();
+        self.focus = Some(index);
+    }
+
+    /// Select all the items, in [`SelectionMode::Multiple`].
+    pub fn select_all(&mut self) {
+        if self.mode == SelectionMode::Multiple {
+            self.selected = (0..self.item_count).collect();
+        }
+    }
+
+    /// Apply an `input` targeting the item at `index`.
+    pub fn apply(&mut self, index: usize, input: SelectionInput) {
+        match input {
+            SelectionInput::Click { extend: true, .. } => self.extend_to(index),
+            SelectionInput::Click { toggle: true, .. } => self.toggle(index),
+            SelectionInput::Click { .. } => self.select(index),
+            SelectionInput::Move { movement, extend } => {
+                let Some(last) = self.item_count.checked_sub(1) else {
+                    return;
+                };
+                let target = match movement {
+                    SelectionMove::Previous => index.saturating_sub(1),
+                    SelectionMove::Next => (index + 1).min(last),
+                    SelectionMove::First => 0,
+                    SelectionMove::Last => last,
+                };
+                if extend {
+                    self.extend_to(target);
+                } else {
+                    self.select(target);
+                }
+            }
+            SelectionInput::Toggle => self.toggle(index),
+            SelectionInput::SelectAll => self.select_all(),
+        }
+    }
+
+    /// Update the indices after an item was inserted at `index`.
+    pub fn insert_item(&mut self, index: usize) {
+        let shift = |i: usize| if i >= index { i + 1 } else { i };
+        self.selected = self.selected.iter().map(|&i| shift(i)).collect();
+        self.anchor = self.anchor.map(shift);
+        self.focus = self.focus.map(shift);
+        self.item_count += 1;
+    }
+
+    /// Update the indices after the item at `index` was removed.
+    pub fn remove_item(&mut self, index: usize) {
+        let shift = |i: usize| match i.cmp(&index) {
+            std::cmp::Ordering::Less => Some(i),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(i - 1),
+        };
+        self.selected = self.selected.iter().filter_map(|&i| shift(i)).collect();
+        self.anchor = self.anchor.and_then(shift);
+        self.focus = self.focus.and_then(shift);
+        self.item_count = self.item_count.saturating_sub(1);
+    }
+
+    /// Set the number of items, e.g. after the collection was replaced, deselecting the
+    /// items past the end.
+    pub fn set_item_count(&mut self, item_count: usize) {
+        self.item_count = item_count;
+        self.selected.retain(|&i| i < item_count);
+        self.anchor = self.anchor.filter(|&i| i < item_count);
+        self.focus = self.focus.filter(|&i| i < item_count);
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn click(toggle: bool, extend: bool) -> SelectionInput {
+        SelectionInput::Click { toggle, extend }
+    }
+
+    fn selected(selection: &Selection) -> Vec<usize> {
+        selection.selected().collect()
+    }
+
+    #[test]
+    fn single_selection() {
+        let mut selection = Selection::new(SelectionMode::Single, 5);
+        selection.apply(1, click(false, false));
+        assert_eq!(selected(&selection), [1]);
+        selection.apply(3, click(true, false));
+        assert_eq!(selected(&selection), [3]);
+        selection.apply(0, click(false, true));
+        assert_eq!(selected(&selection), [0]);
+        selection.apply(0, SelectionInput::SelectAll);
+        assert_eq!(selected(&selection), [0]);
+        selection.apply(0, SelectionInput::Toggle);
+        assert!(selected(&selection).is_empty());
+    }
+
+    #[test]
+    fn multiple_selection() {
+        let mut selection = Selection::new(SelectionMode::Multiple, 10);
+        selection.apply(2, click(false, false));
+        selection.apply(5, click(true, false));
+        assert_eq!(selected(&selection), [2, 5]);
+
+        // Ranges extend from the last toggled item
+        selection.apply(7, click(false, true));
+        assert_eq!(selected(&selection), [5, 6, 7]);
+        selection.apply(3, click(false, true));
+        assert_eq!(selected(&selection), [3, 4, 5]);
+        assert_eq!(selection.focus(), Some(3));
+
+        selection.apply(4, click(true, false));
+        assert_eq!(selected(&selection), [3, 5]);
+
+        selection.apply(0, SelectionInput::SelectAll);
+        assert_eq!(selected(&selection).len(), 10);
+    }
+
+    #[test]
+    fn keyboard_movement() {
+        let mut selection = Selection::new(SelectionMode::Multiple, 4);
+        let movement = |movement, extend| SelectionInput::Move { movement, extend };
+
+        selection.apply(1, movement(SelectionMove::Next, false));
+        assert_eq!(selected(&selection), [2]);
+        selection.apply(2, movement(SelectionMove::Next, true));
+        assert_eq!(selected(&selection), [2, 3]);
+        // The focus doesn't go past the last item
+        selection.apply(3, movement(SelectionMove::Next, true));
+        assert_eq!(selected(&selection), [2, 3]);
+        selection.apply(3, movement(SelectionMove::First, true));
+        assert_eq!(selected(&selection), [0, 1, 2]);
+        selection.apply(0, movement(SelectionMove::Previous, false));
+        assert_eq!(selected(&selection), [0]);
+    }
+
+    #[test]
+    fn indices_follow_the_items() {
+        let mut selection = Selection::new(SelectionMode::Multiple, 5);
+        selection.apply(1, click(false, false));
+        selection.apply(3, click(true, false));
+
+        selection.insert_item(2);
+        assert_eq!(selected(&selection), [1, 4]);
+        assert_eq!(selection.focus(), Some(4));
+
+        selection.remove_item(1);
+        assert_eq!(selected(&selection), [3]);
+        assert_eq!(selection.item_count(), 5);
+
+        selection.set_item_count(2);
+        assert!(selected(&selection).is_empty());
+        assert_eq!(selection.focus(), None);
+    }
+}
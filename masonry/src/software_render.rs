@@ -0,0 +1,40 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Falling back to software rendering when no GPU adapter is usable.
+//!
+//! Vello only renders through wgpu, so "software rendering" means using a software adapter,
+//! such as Mesa's llvmpipe or Windows' WARP, with Vello's CPU implementation of its compute
+//! stages. This is much slower than a GPU, but lets apps run in VMs, in CI, and on old
+//! hardware instead of failing to create their surface.
+
+use vello::util::RenderContext;
+use wgpu::{Backends, Instance, InstanceDescriptor, InstanceFlags};
+
+/// The environment variable which forces software rendering when set to `1`.
+pub(crate) const SOFTWARE_RENDERING_VAR: &str = "MASONRY_SOFTWARE_RENDERING";
+
+/// Whether the user asked for software rendering with [`SOFTWARE_RENDERING_VAR`].
+pub(crate) fn is_forced() -> bool {
+    std::env::var_os(SOFTWARE_RENDERING_VAR).is_some_and(|value| value == "1")
+}
+
+/// A wgpu instance which can pick an adapter from any backend.
+///
+/// Unlike the default instance, this includes the secondary backends (such as OpenGL),
+/// which is where software adapters are usually found.
+pub(crate) fn instance() -> Instance {
+    Instance::new(InstanceDescriptor {
+        backends: Backends::all(),
+        flags: InstanceFlags::from_build_config().with_env(),
+        ..Default::default()
+    })
+}
+
+/// A render context which can pick an adapter from any wgpu backend, see [`instance`].
+pub(crate) fn render_context() -> RenderContext {
+    RenderContext {
+        instance: instance(),
+        devices: Vec::new(),
+    }
+}
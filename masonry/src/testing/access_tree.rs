@@ -0,0 +1,170 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A stable text representation of the accessibility tree, for snapshot tests.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use accesskit::{Node, NodeId, TreeUpdate};
+
+/// The accessibility tree of a [`TestHarness`](super::TestHarness), as seen by assistive technologies.
+///
+/// Its `Debug` output lists one node per line, indented under its parent, with the node's role
+/// followed by its name, description, value and states.
+/// Nodes are numbered in tree order, and relations to other nodes (such as `labelled_by` or
+/// `active_descendant`) refer to those numbers, so the output doesn't depend on widget ids.
+/// This makes it suitable for snapshot tests with [`insta::assert_debug_snapshot`].
+///
+/// ```text
+/// #0 ProgressIndicator value="25%" numeric_value=25
+///   #1 Label
+///     #2 TextRun value="25%"
+/// ```
+///
+/// [`insta::assert_debug_snapshot`]: https://docs.rs/insta/latest/insta/macro.assert_debug_snapshot.html
+pub struct AccessTree {
+    root: NodeId,
+    focus: NodeId,
+    nodes: HashMap<NodeId, Node>,
+    /// The ids of the nodes reachable from the root, in tree order.
+    order: Vec<NodeId>,
+    /// The nodes which are the child of more than one node, or of one of their descendants.
+    repeated: Vec<NodeId>,
+}
+
+impl AccessTree {
+    /// Create the representation of a full tree update.
+    ///
+    /// # Panics
+    ///
+    /// If `update` doesn't contain the whole tree.
+    pub(crate) fn new(update: TreeUpdate) -> Self {
+        let root = update
+            .tree
+            .expect("A full tree update should contain the tree")
+            .root;
+        let nodes: HashMap<NodeId, Node> = update.nodes.into_iter().collect();
+
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        let mut repeated = Vec::new();
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                repeated.push(id);
+                continue;
+            }
+            let node = nodes
+                .get(&id)
+                .unwrap_or_else(|| panic!("Node {id:?} is missing from the tree update"));
+            order.push(id);
+            stack.extend(node.children().iter().rev());
+        }
+
+        Self {
+            root,
+            focus: update.focus,
+            nodes,
+            order,
+            repeated,
+        }
+    }
+
+    /// The node with the given id, if it's in the tree.
+    pub(crate) fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(&id)
+    }
+
+    /// Check that each node is reachable from the root exactly once.
+    pub(crate) fn check_consistency(&self) -> Result<(), String> {
+        if let Some(id) = self.repeated.first() {
+            return Err(format!(
+                "Accessibility node {id:?} is reachable from the root more than once"
+            ));
+        }
+        if let Some(id) = self.nodes.keys().find(|id| !self.order.contains(id)) {
+            return Err(format!(
+                "Accessibility node {id:?} isn't reachable from the root"
+            ));
+        }
+        Ok(())
+    }
+
+    fn fmt_node(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        id: NodeId,
+        depth: usize,
+        indices: &HashMap<NodeId, usize>,
+    ) -> fmt::Result {
+        let node = &self.nodes[&id];
+        let index = indices[&id];
+        if index != 0 {
+            writeln!(f)?;
+        }
+        let indent = depth * 2;
+        write!(f, "{:indent$}#{index} {:?}", "", node.role())?;
+
+        if let Some(name) = node.label() {
+            write!(f, " name={name:?}")?;
+        }
+        if let Some(description) = node.description() {
+            write!(f, " description={description:?}")?;
+        }
+        if let Some(value) = node.value() {
+            write!(f, " value={value:?}")?;
+        }
+        if let Some(numeric_value) = node.numeric_value() {
+            write!(f, " numeric_value={numeric_value}")?;
+        }
+        if let Some(toggled) = node.toggled() {
+            write!(f, " toggled={toggled:?}")?;
+        }
+        if node.is_disabled() {
+            write!(f, " disabled")?;
+        }
+        if node.is_hidden() {
+            write!(f, " hidden")?;
+        }
+        if id == self.focus && id != self.root {
+            write!(f, " focused")?;
+        }
+
+        let refer = |id: &NodeId| match indices.get(id) {
+            Some(index) => format!("#{index}"),
+            None => "<missing>".to_string(),
+        };
+        let relations = [
+            ("labelled_by", node.labelled_by()),
+            ("described_by", node.described_by()),
+            ("controls", node.controls()),
+        ];
+        for (name, targets) in relations {
+            if !targets.is_empty() {
+                let targets: Vec<String> = targets.iter().map(refer).collect();
+                write!(f, " {name}=[{}]", targets.join(", "))?;
+            }
+        }
+        if let Some(active_descendant) = node.active_descendant() {
+            write!(f, " active_descendant={}", refer(&active_descendant))?;
+        }
+
+        for &child in node.children() {
+            self.fmt_node(f, child, depth + 1, indices)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for AccessTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indices: HashMap<NodeId, usize> = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(index, &id)| (id, index))
+            .collect();
+        self.fmt_node(f, self.root, 0, &indices)
+    }
+}
@@ -0,0 +1,459 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Random widget trees and event sequences, to find bugs in the layout protocol and passes.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use accesskit::NodeId;
+use winit::keyboard::{KeyCode, ModifiersState};
+
+use crate::dpi::PhysicalSize;
+use crate::event::{PointerButton, WindowEvent};
+use crate::testing::TestHarness;
+use crate::widget::{
+    Align, Button, Checkbox, CrossAxisAlignment, Flex, Grid, GridParams, Label, LineBreaking,
+    MainAxisAlignment, ProgressBar, SizedBox, Spinner, Split, Textbox, WidgetRef,
+};
+use crate::{Color, Point, Vec2, Widget, WidgetId};
+
+/// A small pseudo-random number generator, so that fuzzing runs can be reproduced from their seed.
+#[derive(Clone, Debug)]
+pub struct FuzzRng {
+    state: u64,
+}
+
+impl FuzzRng {
+    /// Create a generator which always produces the same numbers for the same `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// The next number, using the SplitMix64 algorithm.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "FuzzRng::below called with an empty range");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `min..max`.
+    pub fn range(&mut self, min: f64, max: f64) -> f64 {
+        let unit = (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64;
+        min + unit * (max - min)
+    }
+
+    /// `true` with the probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.range(0., 1.) < p
+    }
+
+    /// A random element of `items`.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// The parameters of [`fuzz_widget_tree`].
+#[derive(Clone, Debug)]
+pub struct FuzzOptions {
+    /// The maximum depth of the generated widget trees.
+    pub max_depth: usize,
+    /// The maximum number of children of each generated container.
+    pub max_children: usize,
+    /// The number of events sent to each tree.
+    pub steps: usize,
+}
+
+impl Default for FuzzOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            max_children: 4,
+            steps: 50,
+        }
+    }
+}
+
+/// Generate a random tree of Masonry's widgets.
+///
+/// Widgets which need bounded constraints, like [`Grid`] or expanded [`SizedBox`]es, are only
+/// generated where their parent gives them bounded constraints, as the layout protocol
+/// doesn't define what they should do otherwise.
+/// [`Portal`](crate::widget::Portal) isn't generated, because it gives its content
+/// unbounded constraints on both axes.
+pub fn random_widget(rng: &mut FuzzRng, options: &FuzzOptions) -> Box<dyn Widget> {
+    random_widget_inner(rng, options, options.max_depth, true)
+}
+
+fn random_widget_inner(
+    rng: &mut FuzzRng,
+    options: &FuzzOptions,
+    depth: usize,
+    bounded: bool,
+) -> Box<dyn Widget> {
+    if depth == 0 || rng.chance(0.3) {
+        return random_leaf(rng);
+    }
+    let depth = depth - 1;
+    let kinds: &[u8] = if bounded {
+        &[0, 1, 2, 3, 4, 5]
+    } else {
+        &[0, 1]
+    };
+    match *rng.pick(kinds) {
+        // Flex
+        0 => {
+            let mut flex = if rng.chance(0.5) {
+                Flex::row()
+            } else {
+                Flex::column()
+            };
+            flex = flex
+                .cross_axis_alignment(*rng.pick(&[
+                    CrossAxisAlignment::Start,
+                    CrossAxisAlignment::Center,
+                    CrossAxisAlignment::End,
+                    CrossAxisAlignment::Baseline,
+                    CrossAxisAlignment::Fill,
+                ]))
+                .main_axis_alignment(*rng.pick(&[
+                    MainAxisAlignment::Start,
+                    MainAxisAlignment::Center,
+                    MainAxisAlignment::End,
+                    MainAxisAlignment::SpaceBetween,
+                    MainAxisAlignment::SpaceEvenly,
+                    MainAxisAlignment::SpaceAround,
+                ]))
+                .must_fill_main_axis(bounded && rng.chance(0.5));
+            if rng.chance(0.3) {
+                flex = flex.gap(rng.range(0., 20.));
+            }
+            for _ in 0..rng.below(options.max_children + 1) {
+                flex = match rng.below(5) {
+                    0 => flex.with_spacer(rng.range(0., 20.)),
+                    1 if bounded => flex.with_flex_spacer(rng.range(0.5, 3.)),
+                    2 | 3 if bounded => flex.with_flex_child(
+                        random_widget_inner(rng, options, depth, true),
+                        rng.range(0.5, 3.),
+                    ),
+                    _ => flex.with_child(random_widget_inner(rng, options, depth, false)),
+                };
+            }
+            Box::new(flex)
+        }
+        // SizedBox with a fixed size, which gives its child bounded constraints
+        1 => {
+            let child = random_widget_inner(rng, options, depth, true);
+            let sized_box = SizedBox::new(child)
+                .width(rng.range(20., 300.))
+                .height(rng.range(20., 300.))
+                .padding(rng.range(0., 10.));
+            Box::new(sized_box)
+        }
+        // Expanded SizedBox
+        2 => {
+            let child = random_widget_inner(rng, options, depth, true);
+            Box::new(SizedBox::new(child).expand().border(Color::WHITE, 1.))
+        }
+        3 => {
+            let child = random_widget_inner(rng, options, depth, true);
+            let align = match rng.below(3) {
+                0 => Align::centered(child),
+                1 => Align::left(child),
+                _ => Align::right(child),
+            };
+            Box::new(align)
+        }
+        4 => {
+            let first = random_widget_inner(rng, options, depth, true);
+            let second = random_widget_inner(rng, options, depth, true);
+            let split = if rng.chance(0.5) {
+                Split::columns(first, second)
+            } else {
+                Split::rows(first, second)
+            };
+            Box::new(split.split_point(rng.range(0.1, 0.9)).draggable(true))
+        }
+        _ => {
+            let width = 1 + rng.below(3) as i32;
+            let height = 1 + rng.below(3) as i32;
+            let mut grid = Grid::with_dimensions(width, height).with_spacing(rng.range(0., 10.));
+            for _ in 0..rng.below(options.max_children + 1) {
+                let x = rng.below(width as usize) as i32;
+                let y = rng.below(height as usize) as i32;
+                let params = GridParams::new(
+                    x,
+                    y,
+                    1 + rng.below((width - x) as usize) as i32,
+                    1 + rng.below((height - y) as usize) as i32,
+                );
+                grid = grid.with_child(random_widget_inner(rng, options, depth, true), params);
+            }
+            Box::new(grid)
+        }
+    }
+}
+
+fn random_leaf(rng: &mut FuzzRng) -> Box<dyn Widget> {
+    const TEXTS: &[&str] = &[
+        "",
+        "Hello",
+        "A longer text which might need to be wrapped over several lines",
+        "Ünïcödé and emoji 🦀",
+    ];
+    let text = *rng.pick(TEXTS);
+    match rng.below(7) {
+        0 => {
+            let line_break = *rng.pick(&[
+                LineBreaking::WordWrap,
+                LineBreaking::Clip,
+                LineBreaking::Overflow,
+            ]);
+            Box::new(Label::new(text).with_line_break_mode(line_break))
+        }
+        1 => Box::new(Button::new(text)),
+        2 => Box::new(Checkbox::new(rng.chance(0.5), text)),
+        3 => Box::new(Textbox::new(text)),
+        4 => {
+            let progress = rng.chance(0.8).then(|| rng.range(0., 1.));
+            Box::new(ProgressBar::new(progress))
+        }
+        5 => Box::new(Spinner::new()),
+        _ => Box::new(
+            SizedBox::empty()
+                .width(rng.range(0., 100.))
+                .height(rng.range(0., 100.)),
+        ),
+    }
+}
+
+/// One step of a fuzzing run.
+#[derive(Clone, Debug)]
+enum FuzzStep {
+    Resize(PhysicalSize<u32>),
+    MouseMove(Point),
+    Click(Point),
+    Drag(Point, Point),
+    Scroll(Point, Vec2),
+    TypeChars(String),
+    FocusNext(bool),
+    RequestLayout(WidgetId),
+    SetDisabled(WidgetId, bool),
+}
+
+impl FuzzStep {
+    fn random(rng: &mut FuzzRng, harness: &TestHarness) -> Self {
+        let size = harness.window_size();
+        let mut point = || {
+            Point::new(
+                rng.range(-10., size.width as f64 + 10.),
+                rng.range(-10., size.height as f64 + 10.),
+            )
+        };
+        let (first, second) = (point(), point());
+        match rng.below(9) {
+            0 => Self::Resize(PhysicalSize::new(
+                rng.range(1., 1000.) as u32,
+                rng.range(1., 1000.) as u32,
+            )),
+            1 => Self::MouseMove(first),
+            2 => Self::Click(first),
+            3 => Self::Drag(first, second),
+            4 => Self::Scroll(first, Vec2::new(rng.range(-50., 50.), rng.range(-50., 50.))),
+            5 => Self::TypeChars(rng.pick(&["a", "Hello ", "🦀"]).to_string()),
+            6 => Self::FocusNext(rng.chance(0.8)),
+            7 => Self::RequestLayout(*rng.pick(&widget_ids_of(harness.root_widget()))),
+            _ => Self::SetDisabled(
+                *rng.pick(&widget_ids_of(harness.root_widget())),
+                rng.chance(0.5),
+            ),
+        }
+    }
+
+    fn run(&self, harness: &mut TestHarness) {
+        match self {
+            Self::Resize(size) => {
+                harness.process_window_event(WindowEvent::Resize(*size));
+            }
+            Self::MouseMove(pos) => harness.mouse_move(*pos),
+            Self::Click(pos) => {
+                harness.mouse_move(*pos);
+                harness.mouse_button_press(PointerButton::Primary);
+                harness.mouse_button_release(PointerButton::Primary);
+            }
+            Self::Drag(from, to) => harness.drag_from_to(*from, *to),
+            Self::Scroll(pos, delta) => harness.scroll_at(*pos, *delta),
+            Self::TypeChars(text) => harness.keyboard_type_chars(text),
+            Self::FocusNext(forward) => {
+                let modifiers = if *forward {
+                    ModifiersState::empty()
+                } else {
+                    ModifiersState::SHIFT
+                };
+                harness.press_focus_navigation_key(KeyCode::Tab, modifiers);
+            }
+            Self::RequestLayout(id) => {
+                if harness.try_get_widget(*id).is_some() {
+                    harness.edit_widget(*id, |mut widget| widget.ctx.request_layout());
+                }
+            }
+            Self::SetDisabled(id, disabled) => {
+                if harness.try_get_widget(*id).is_some() {
+                    harness.edit_widget(*id, |mut widget| widget.ctx.set_disabled(*disabled));
+                }
+            }
+        }
+    }
+}
+
+fn widget_ids_of(widget: WidgetRef<'_, dyn Widget>) -> Vec<WidgetId> {
+    let mut ids = vec![widget.id()];
+    for child in widget.children() {
+        ids.extend(widget_ids_of(child));
+    }
+    ids
+}
+
+/// A broken invariant of the widget tree, found by [`check_invariants`].
+#[derive(Clone, Debug)]
+pub struct InvariantError(String);
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+/// Check the invariants which should hold for any widget tree after the passes have run.
+///
+/// - Widget ids are unique.
+/// - Layout sizes and positions are finite, and sizes aren't negative.
+/// - The paint rect of each widget contains those of its children, unless it clips them.
+/// - The accessibility tree has a node for each widget, whose children include the nodes of
+///   the widget's children, and each node is reachable from the root exactly once.
+pub fn check_invariants(harness: &mut TestHarness) -> Result<(), InvariantError> {
+    let mut ids = HashSet::new();
+    check_widget(harness.root_widget(), &mut ids)?;
+
+    let access_tree = harness.access_tree();
+    access_tree.check_consistency().map_err(InvariantError)?;
+    for id in ids {
+        let Some(widget) = harness.try_get_widget(id) else {
+            continue;
+        };
+        let Some(node) = access_tree.node(id.into()) else {
+            return Err(InvariantError(format!(
+                "Widget {} has no accessibility node",
+                describe(widget)
+            )));
+        };
+        for child in widget.children() {
+            if !node.children().contains(&NodeId::from(child.id())) {
+                return Err(InvariantError(format!(
+                    "The accessibility node of {} doesn't contain the node of its child {}",
+                    describe(widget),
+                    describe(child)
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_widget(
+    widget: WidgetRef<'_, dyn Widget>,
+    ids: &mut HashSet<WidgetId>,
+) -> Result<(), InvariantError> {
+    if !ids.insert(widget.id()) {
+        return Err(InvariantError(format!(
+            "Widget id of {} appears twice in the tree",
+            describe(widget)
+        )));
+    }
+    if widget.ctx().is_stashed() {
+        return Ok(());
+    }
+
+    let layout_rect = widget.ctx().layout_rect();
+    if !layout_rect.is_finite() || layout_rect.width() < 0. || layout_rect.height() < 0. {
+        return Err(InvariantError(format!(
+            "{} has an invalid layout rect {layout_rect:?}",
+            describe(widget)
+        )));
+    }
+
+    let clips = widget.ctx().clip_path().is_some();
+    let local_paint_rect = widget.ctx().paint_rect() - layout_rect.origin().to_vec2();
+    for child in widget.children() {
+        if !clips && !child.ctx().is_stashed() {
+            let child_paint_rect = child.ctx().paint_rect();
+            if !local_paint_rect.contains_rect(child_paint_rect) {
+                return Err(InvariantError(format!(
+                    "The paint rect {local_paint_rect:?} of {} doesn't contain \
+                    the paint rect {child_paint_rect:?} of its child {}",
+                    describe(widget),
+                    describe(child)
+                )));
+            }
+        }
+        check_widget(child, ids)?;
+    }
+    Ok(())
+}
+
+fn describe(widget: WidgetRef<'_, dyn Widget>) -> String {
+    format!("'{}' {}", widget.short_type_name(), widget.id())
+}
+
+/// Send random events to a random widget tree generated from `seed`, and check
+/// [the invariants](check_invariants) after each of them.
+///
+/// # Panics
+///
+/// If an invariant is broken or a pass panics.
+/// The message includes the seed and the events sent so far, to reproduce the failure.
+pub fn fuzz_widget_tree(seed: u64, options: &FuzzOptions) {
+    let mut steps = Vec::new();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut rng = FuzzRng::new(seed);
+        let mut harness = TestHarness::create(random_widget(&mut rng, options));
+        check_invariants(&mut harness)?;
+        for _ in 0..options.steps {
+            let step = FuzzStep::random(&mut rng, &harness);
+            steps.push(step.clone());
+            step.run(&mut harness);
+            check_invariants(&mut harness)?;
+        }
+        Ok::<(), InvariantError>(())
+    }));
+    let error = match result {
+        Ok(Ok(())) => return,
+        Ok(Err(error)) => error.to_string(),
+        Err(payload) => {
+            if let Some(message) = payload.downcast_ref::<&str>() {
+                format!("panic: {message}")
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                format!("panic: {message}")
+            } else {
+                "panic".to_string()
+            }
+        }
+    };
+    panic!("Fuzzing with seed {seed} failed after steps {steps:#?}\n{error}");
+}
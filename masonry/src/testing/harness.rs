@@ -4,27 +4,32 @@
 //! Tools and infrastructure for testing widgets.
 
 use std::collections::VecDeque;
-use std::num::NonZeroUsize;
+use std::time::Duration;
 
+use accesskit::ActionRequest;
 use cursor_icon::CursorIcon;
 use dpi::LogicalSize;
 use image::{DynamicImage, ImageReader, Rgba, RgbaImage};
 use tracing::debug;
-use vello::util::{block_on_wgpu, RenderContext};
-use vello::RendererOptions;
-use wgpu::{
-    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
-    TextureDescriptor, TextureFormat, TextureUsages,
-};
 use winit::event::Ime;
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
 
 use crate::action::Action;
 use crate::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
-use crate::event::{PointerButton, PointerEvent, PointerState, TextEvent, WindowEvent};
+use crate::event::{
+    PointerButton, PointerEvent, PointerState, ScrollDelta, TextEvent, WindowEvent,
+};
+use crate::headless::HeadlessRenderer;
+use crate::passes::accessibility::run_accessibility_pass;
 use crate::passes::anim::run_update_anim_pass;
-use crate::render_root::{RenderRoot, RenderRootOptions, RenderRootSignal, WindowSizePolicy};
-use crate::testing::screenshots::get_image_diff;
+use crate::passes::event::{run_focus_navigation, run_type_ahead};
+use crate::render_root::{
+    RenderCacheStats, RenderRoot, RenderRootOptions, RenderRootSignal, WindowSizePolicy,
+};
+use crate::testing::screenshots::{get_image_diff, SnapshotOptions};
 use crate::testing::snapshot_utils::get_cargo_workspace;
+use crate::testing::AccessTree;
+use crate::theme::Density;
 use crate::tracing_backend::try_init_test_tracing;
 use crate::widget::{WidgetMut, WidgetRef};
 use crate::{Color, Handled, Point, Size, Vec2, Widget, WidgetId};
@@ -62,7 +67,8 @@ pub const HARNESS_DEFAULT_BACKGROUND_COLOR: Color = Color::rgb8(0x29, 0x29, 0x29
 ///
 /// `TestHarness` tries to act like the normal masonry environment. It will run the same passes as the normal app after every user event and animation.
 ///
-/// Animations can be simulated with the [`animate_ms`](Self::animate_ms) method.
+/// Time is frozen in the harness. Animations and the passage of time can be simulated with
+/// the [`advance_time`](Self::advance_time) method.
 ///
 /// One minor difference is that paint only happens when the user explicitly calls rendering
 /// methods, whereas in a normal applications you could reasonably expect multiple paint calls
@@ -116,6 +122,11 @@ pub struct TestHarness {
     has_ime_session: bool,
     ime_rect: (LogicalPosition<f64>, LogicalSize<f64>),
     title: String,
+    #[cfg(feature = "menu")]
+    menu: Vec<crate::menu::MenuEntry>,
+    /// Created the first time the harness renders, as it's slow to set up.
+    renderer: Option<HeadlessRenderer>,
+    snapshot_options: SnapshotOptions,
 }
 
 /// Assert a snapshot of a rendered frame of your app.
@@ -129,6 +140,11 @@ pub struct TestHarness {
 ///
 /// If a screenshot doesn't exist, the assert will fail; the new screenshot is stored as
 /// `./screenshots/<test_name>.new.png`, and must be renamed before the assert will pass.
+///
+/// If the `MASONRY_TEST_BLESS` environment variable is set, the new render is stored
+/// as the screenshot instead of failing the assert.
+///
+/// Renders don't need to match exactly; see [`TestHarness::set_snapshot_options`].
 #[macro_export]
 macro_rules! assert_render_snapshot {
     ($test_harness:expr, $name:expr) => {
@@ -199,7 +215,13 @@ impl TestHarness {
             has_ime_session: false,
             ime_rect: Default::default(),
             title: String::new(),
+            #[cfg(feature = "menu")]
+            menu: Vec::new(),
+            renderer: None,
+            snapshot_options: SnapshotOptions::default(),
         };
+        // Time only passes when the test advances it
+        harness.render_root.global_state.clock.freeze();
         harness.process_window_event(WindowEvent::Resize(window_size));
 
         harness
@@ -238,6 +260,14 @@ impl TestHarness {
         handled
     }
 
+    /// Send an [`ActionRequest`] from assistive technology to the simulated window.
+    ///
+    /// If this event triggers rewrite passes, they will also run as normal.
+    pub fn process_access_event(&mut self, event: ActionRequest) {
+        self.render_root.handle_access_event(event);
+        self.process_signals();
+    }
+
     fn process_signals(&mut self) {
         while let Some(signal) = self.render_root.pop_signal() {
             match signal {
@@ -270,6 +300,15 @@ impl TestHarness {
                 RenderRootSignal::Minimize => (),
                 RenderRootSignal::Exit => (),
                 RenderRootSignal::ShowWindowMenu(_) => (),
+                RenderRootSignal::SetWindowPlacement(_) => (),
+                RenderRootSignal::ShowPopup(..) => (),
+                RenderRootSignal::ClosePopup(_) => (),
+                #[cfg(feature = "webview")]
+                RenderRootSignal::WebView(..) => (),
+                #[cfg(feature = "menu")]
+                RenderRootSignal::SetMenu(entries) => {
+                    self.menu = entries;
+                }
             }
         }
     }
@@ -283,96 +322,15 @@ impl TestHarness {
         if std::env::var("SKIP_RENDER_TESTS").is_ok_and(|it| !it.is_empty()) {
             return RgbaImage::from_pixel(1, 1, Rgba([255, 255, 255, 255]));
         }
-        // TODO: Cache/share the context
-        let mut context = RenderContext::new();
-        let device_id =
-            pollster::block_on(context.device(None)).expect("No compatible device found");
-        let device_handle = &mut context.devices[device_id];
-        let device = &device_handle.device;
-        let queue = &device_handle.queue;
-        let mut renderer = vello::Renderer::new(
-            device,
-            RendererOptions {
-                surface_format: None,
-                // TODO - Examine this value
-                use_cpu: true,
-                num_init_threads: NonZeroUsize::new(1),
-                // TODO - Examine this value
-                antialiasing_support: vello::AaSupport::area_only(),
-            },
-        )
-        .expect("Got non-Send/Sync error from creating renderer");
+        let renderer = self
+            .renderer
+            .get_or_insert_with(|| HeadlessRenderer::new().expect("No compatible device found"));
 
         // TODO - fix window_size
         let (width, height) = (self.window_size.width, self.window_size.height);
-        let render_params = vello::RenderParams {
-            // TODO - Parameterize
-            base_color: self.background_color,
-            width,
-            height,
-            antialiasing_method: vello::AaConfig::Area,
-        };
-
-        let size = Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        };
-        let target = device.create_texture(&TextureDescriptor {
-            label: Some("Target texture"),
-            size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: TextureFormat::Rgba8Unorm,
-            usage: TextureUsages::STORAGE_BINDING | TextureUsages::COPY_SRC,
-            view_formats: &[],
-        });
-        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
         renderer
-            .render_to_texture(device, queue, &scene, &view, &render_params)
-            .expect("Got non-Send/Sync error from rendering");
-        let padded_byte_width = (width * 4).next_multiple_of(256);
-        let buffer_size = padded_byte_width as u64 * height as u64;
-        let buffer = device.create_buffer(&BufferDescriptor {
-            label: Some("val"),
-            size: buffer_size,
-            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
-            label: Some("Copy out buffer"),
-        });
-        encoder.copy_texture_to_buffer(
-            target.as_image_copy(),
-            ImageCopyBuffer {
-                buffer: &buffer,
-                layout: wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(padded_byte_width),
-                    rows_per_image: None,
-                },
-            },
-            size,
-        );
-
-        queue.submit([encoder.finish()]);
-        let buf_slice = buffer.slice(..);
-
-        let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
-        buf_slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
-        let recv_result = block_on_wgpu(device, receiver.receive()).expect("channel was closed");
-        recv_result.expect("failed to map buffer");
-
-        let data = buf_slice.get_mapped_range();
-        let mut result_unpadded =
-            Vec::<u8>::with_capacity((width * height * 4).try_into().unwrap());
-        for row in 0..height {
-            let start = (row * padded_byte_width).try_into().unwrap();
-            result_unpadded.extend(&data[start..start + (width * 4) as usize]);
-        }
-
-        RgbaImage::from_vec(width, height, result_unpadded).expect("failed to create image")
+            .render_scene(&scene, width, height, self.background_color)
+            .expect("Got non-Send/Sync error from rendering")
     }
 
     // --- MARK: EVENT HELPERS ---
@@ -405,13 +363,17 @@ impl TestHarness {
         self.process_pointer_event(PointerEvent::PointerUp(button, self.mouse_state.clone()));
     }
 
-    /// Send a [`MouseWheel`](PointerEvent::MouseWheel) event to the window.
+    /// Send a [`MouseWheel`](PointerEvent::MouseWheel) event to the window, scrolling by
+    /// `wheel_delta` lines, as a mouse wheel with ticks does.
     pub fn mouse_wheel(&mut self, wheel_delta: Vec2) {
-        let pixel_delta = LogicalPosition::new(wheel_delta.x, wheel_delta.y);
-        self.process_pointer_event(PointerEvent::MouseWheel(
-            pixel_delta,
-            self.mouse_state.clone(),
-        ));
+        self.mouse_scroll(ScrollDelta::Lines(wheel_delta));
+    }
+
+    /// Send a [`MouseWheel`](PointerEvent::MouseWheel) event to the window.
+    ///
+    /// Use [`ScrollDelta::Pixels`] to scroll as a trackpad does.
+    pub fn mouse_scroll(&mut self, delta: ScrollDelta) {
+        self.process_pointer_event(PointerEvent::MouseWheel(delta, self.mouse_state.clone()));
     }
 
     /// Send events that lead to a given widget being clicked.
@@ -426,6 +388,69 @@ impl TestHarness {
         self.mouse_button_release(PointerButton::Primary);
     }
 
+    /// Send the events of dragging with the primary button from `from` to `to`.
+    ///
+    /// The pointer is pressed at `from`, then moved to `to` in several intermediate steps
+    /// before being released, as it would be by a user.
+    pub fn drag_from_to(&mut self, from: impl Into<Point>, to: impl Into<Point>) {
+        const STEPS: u32 = 8;
+        let (from, to) = (from.into(), to.into());
+
+        self.mouse_move(from);
+        self.mouse_button_press(PointerButton::Primary);
+        for step in 1..=STEPS {
+            self.mouse_move(from.lerp(to, f64::from(step) / f64::from(STEPS)));
+        }
+        self.mouse_button_release(PointerButton::Primary);
+    }
+
+    /// Move the pointer to `pos` and send a [`MouseWheel`](PointerEvent::MouseWheel) event there.
+    pub fn scroll_at(&mut self, pos: impl Into<Point>, wheel_delta: Vec2) {
+        self.mouse_move(pos);
+        self.mouse_wheel(wheel_delta);
+    }
+
+    /// Send the events of a double click with the primary button at `pos`.
+    ///
+    /// The second press has a click [`count`](PointerState::count) of 2.
+    pub fn double_click_at(&mut self, pos: impl Into<Point>) {
+        self.mouse_move(pos);
+        for count in 1..=2 {
+            self.mouse_state.count = count;
+            self.mouse_button_press(PointerButton::Primary);
+            self.mouse_button_release(PointerButton::Primary);
+        }
+        self.mouse_state.count = 0;
+    }
+
+    /// Send the events of a two-finger pinch gesture centered on `pos`.
+    ///
+    /// The total `scale_delta` (positive to zoom in) is split over several
+    /// [`Pinch`](PointerEvent::Pinch) events.
+    pub fn pinch_at(&mut self, pos: impl Into<Point>, scale_delta: f64) {
+        const STEPS: u32 = 4;
+
+        self.mouse_move(pos);
+        for _ in 0..STEPS {
+            self.process_pointer_event(PointerEvent::Pinch(
+                scale_delta / f64::from(STEPS),
+                self.mouse_state.clone(),
+            ));
+        }
+    }
+
+    /// Send the events of tapping `pos` with a finger.
+    ///
+    /// The window is told it was touched first, as the event loop runner does, which may
+    /// switch its [`Density`] to [`Touch`](Density::Touch).
+    pub fn tap_at(&mut self, pos: impl Into<Point>) {
+        self.render_root.handle_touch();
+        self.process_signals();
+        self.mouse_move(pos);
+        self.mouse_button_press(PointerButton::Primary);
+        self.mouse_button_release(PointerButton::Primary);
+    }
+
     /// Use [`mouse_move`](Self::mouse_move) to set the internal mouse pos to the center of the given widget.
     pub fn mouse_move_to(&mut self, id: WidgetId) {
         // FIXME - handle case where the widget isn't visible
@@ -447,6 +472,75 @@ impl TestHarness {
         }
     }
 
+    /// Send the sequence of IME events for composing `commit` through `preedits`, e.g. with
+    /// a Pinyin or Kana input method.
+    ///
+    /// Each of `preedits` is sent in turn as the text being composed, with the cursor at its end.
+    /// The composition is then cleared and `commit` is inserted, in the same order as winit.
+    pub fn ime_compose(&mut self, preedits: &[&str], commit: &str) {
+        self.process_text_event(TextEvent::Ime(Ime::Enabled));
+        for preedit in preedits {
+            self.ime_preedit(preedit, Some((preedit.len(), preedit.len())));
+        }
+        self.ime_preedit("", None);
+        self.ime_commit(commit);
+    }
+
+    /// Send an [`Ime::Preedit`] event, which replaces the text being composed.
+    ///
+    /// `cursor` is the byte range of the cursor in `text`, if it should be shown.
+    /// An empty `text` ends the composition without inserting anything.
+    pub fn ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) -> Handled {
+        self.process_text_event(TextEvent::Ime(Ime::Preedit(text.to_string(), cursor)))
+    }
+
+    /// Send an [`Ime::Commit`] event, which inserts `text` in place of the text being composed.
+    pub fn ime_commit(&mut self, text: &str) -> Handled {
+        self.process_text_event(TextEvent::Ime(Ime::Commit(text.to_string())))
+    }
+
+    /// Send an [`Ime::Disabled`] event, which abandons any composition.
+    pub fn ime_disable(&mut self) -> Handled {
+        self.process_text_event(TextEvent::Ime(Ime::Disabled))
+    }
+
+    /// Set the keyboard modifiers which are held down, e.g. for a shortcut.
+    ///
+    /// The focused widget receives a [`TextEvent::ModifierChange`], and the modifiers are
+    /// held for the next pointer events, as with the event loop runner.
+    pub fn keyboard_set_modifiers(&mut self, modifiers: ModifiersState) -> Handled {
+        self.mouse_state.mods = modifiers.into();
+        self.process_text_event(TextEvent::ModifierChange(modifiers))
+    }
+
+    // TODO - Replace with a full keyboard event once winit key events can be mocked
+    /// Simulate pressing `key` with `modifiers` when the focused widget doesn't handle it.
+    ///
+    /// This moves the focus for Tab, the arrow keys, Home and End, as described in
+    /// [`Widget::is_focus_group`].
+    pub fn press_focus_navigation_key(
+        &mut self,
+        key: KeyCode,
+        modifiers: ModifiersState,
+    ) -> Handled {
+        let handled =
+            run_focus_navigation(&mut self.render_root, PhysicalKey::Code(key), modifiers);
+        self.render_root.run_rewrite_passes();
+        self.process_signals();
+        handled
+    }
+
+    // TODO - Replace with a full keyboard event once winit key events can be mocked
+    /// Simulate typing `text` when the focused widget doesn't handle it.
+    ///
+    /// In a focus group, this moves the focus to the next widget whose name starts with `text`.
+    pub fn type_ahead(&mut self, text: &str) -> Handled {
+        let handled = run_type_ahead(&mut self.render_root, text);
+        self.render_root.run_rewrite_passes();
+        self.process_signals();
+        handled
+    }
+
     /// Sets the focused widget.
     ///
     /// ## Panics
@@ -463,6 +557,9 @@ impl TestHarness {
             if state.item.is_stashed {
                 panic!("Cannot focus widget {id}: widget is stashed");
             }
+            if state.item.is_hidden {
+                panic!("Cannot focus widget {id}: widget is hidden");
+            }
             if state.item.is_disabled {
                 panic!("Cannot focus widget {id}: widget is disabled");
             }
@@ -472,34 +569,53 @@ impl TestHarness {
         self.process_signals();
     }
 
-    // TODO - Fold into move_timers_forward
+    /// Move the clock forward by `duration`, and run an animation frame of that length.
+    ///
+    /// Time is frozen in the harness, so widgets see it pass (through [`EventCtx::now`] and
+    /// the other contexts) only when this is called. Widgets which requested an animation
+    /// frame are animated by `duration` in a single step, and the timers which expire
+    /// during `duration` (see [`EventCtx::request_timer`]) are then triggered.
+    ///
+    /// [`EventCtx::now`]: crate::EventCtx::now
+    /// [`EventCtx::request_timer`]: crate::EventCtx::request_timer
+    pub fn advance_time(&mut self, duration: Duration) {
+        self.render_root.global_state.clock.advance(duration);
+        let elapsed_ns = duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        run_update_anim_pass(&mut self.render_root, elapsed_ns);
+        self.render_root.run_rewrite_passes();
+        self.render_root.handle_timers();
+        self.process_signals();
+    }
+
     /// Run an animation pass on the widget tree.
+    ///
+    /// This is the same as [`advance_time`](Self::advance_time) by `ms` milliseconds.
     pub fn animate_ms(&mut self, ms: u64) {
-        run_update_anim_pass(&mut self.render_root, ms * 1_000_000);
-        self.render_root.run_rewrite_passes();
+        self.advance_time(Duration::from_millis(ms));
+    }
+
+    /// How much of the frames rendered so far was reused from previous frames.
+    ///
+    /// See [`RenderRoot::render_cache_stats`].
+    pub fn render_cache_stats(&self) -> RenderCacheStats {
+        self.render_root.render_cache_stats()
+    }
+
+    /// Set how large the built-in widgets are, and lay them out again.
+    ///
+    /// See [`RenderRoot::set_density`].
+    pub fn set_density(&mut self, density: Density) {
+        self.render_root.set_density(density);
         self.process_signals();
     }
 
-    #[cfg(FALSE)]
-    /// Simulate the passage of time.
-    ///
-    /// If you create any timer in a widget, this method is the only way to trigger
-    /// them in unit tests. The testing model assumes that everything else executes
-    /// instantly, and timers are never triggered "spontaneously".
-    ///
-    /// **(TODO - Doesn't move animations forward.)**
-    pub fn move_timers_forward(&mut self, duration: Duration) {
-        // TODO - handle animations
-        let tokens = self
-            .mock_app
-            .window
-            .mock_timer_queue
-            .as_mut()
-            .unwrap()
-            .move_forward(duration);
-        for token in tokens {
-            self.process_event(Event::Timer(token));
-        }
+    /// Run all the work queued with [`EventCtx::schedule_idle`], as if the event loop were
+    /// idle for as long as it takes.
+    ///
+    /// [`EventCtx::schedule_idle`]: crate::EventCtx::schedule_idle
+    pub fn run_idle_work(&mut self) {
+        self.render_root.run_idle_work(Duration::MAX);
+        self.process_signals();
     }
 
     // --- MARK: GETTERS ---
@@ -526,6 +642,24 @@ impl TestHarness {
         self.render_root.get_widget(id)
     }
 
+    /// Return a [`WidgetRef`] to the first widget with the given [debug tag](crate::WidgetPod::set_debug_tag),
+    /// in depth-first order.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if no Widget with this tag can be found.
+    #[track_caller]
+    pub fn get_widget_by_tag(&self, tag: &str) -> WidgetRef<'_, dyn Widget> {
+        self.try_get_widget_by_tag(tag)
+            .unwrap_or_else(|| panic!("could not find widget with tag {tag:?}"))
+    }
+
+    /// Try to return a [`WidgetRef`] to the first widget with the given
+    /// [debug tag](crate::WidgetPod::set_debug_tag), in depth-first order.
+    pub fn try_get_widget_by_tag(&self, tag: &str) -> Option<WidgetRef<'_, dyn Widget>> {
+        self.root_widget().find_widget_by_tag(tag)
+    }
+
     // TODO - Link to focus definition in tutorial
     /// Return a [`WidgetRef`] to the widget that receives keyboard events.
     pub fn focused_widget(&self) -> Option<WidgetRef<'_, dyn Widget>> {
@@ -546,6 +680,19 @@ impl TestHarness {
         self.render_root.global_state.pointer_capture_target
     }
 
+    /// Return the whole accessibility tree, as it would be sent to assistive technologies.
+    ///
+    /// This can be used with [`insta::assert_debug_snapshot`] to catch regressions in the
+    /// accessibility of widgets, the same way [`assert_render_snapshot`] is used for their
+    /// appearance.
+    ///
+    /// [`insta::assert_debug_snapshot`]: https://docs.rs/insta/latest/insta/macro.assert_debug_snapshot.html
+    pub fn access_tree(&mut self) -> AccessTree {
+        self.render_root.rebuild_access_tree = true;
+        let scale_factor = self.render_root.scale_factor;
+        AccessTree::new(run_accessibility_pass(&mut self.render_root, scale_factor))
+    }
+
     /// Call the provided visitor on every widget in the widget tree.
     pub fn inspect_widgets(&mut self, f: impl Fn(WidgetRef<'_, dyn Widget>) + 'static) {
         fn inspect(
@@ -568,7 +715,9 @@ impl TestHarness {
         &mut self,
         f: impl FnOnce(WidgetMut<'_, Box<dyn Widget>>) -> R,
     ) -> R {
-        self.render_root.edit_root_widget(f)
+        let res = self.render_root.edit_root_widget(f);
+        self.process_signals();
+        res
     }
 
     /// Get a [`WidgetMut`] to a specific widget.
@@ -579,7 +728,9 @@ impl TestHarness {
         id: WidgetId,
         f: impl FnOnce(WidgetMut<'_, Box<dyn Widget>>) -> R,
     ) -> R {
-        self.render_root.edit_widget(id, f)
+        let res = self.render_root.edit_widget(id, f);
+        self.process_signals();
+        res
     }
 
     /// Pop the next action from the queue.
@@ -621,6 +772,12 @@ impl TestHarness {
         self.title.clone()
     }
 
+    /// Return the entries of the menu bar of the simulated window.
+    #[cfg(feature = "menu")]
+    pub fn menu(&self) -> &[crate::menu::MenuEntry] {
+        &self.menu
+    }
+
     // --- MARK: SNAPSHOT ---
 
     /// Method used by [`assert_render_snapshot`]. Use the macro instead.
@@ -669,7 +826,9 @@ impl TestHarness {
         if let Ok(reference_file) = ImageReader::open(&reference_path) {
             let ref_image = reference_file.decode().unwrap().to_rgb8();
 
-            if let Some(diff_image) = get_image_diff(&ref_image, &new_image.to_rgb8()) {
+            if let Some(diff_image) =
+                get_image_diff(&ref_image, &new_image.to_rgb8(), &self.snapshot_options)
+            {
                 if std::env::var_os("MASONRY_TEST_BLESS").is_some_and(|it| !it.is_empty()) {
                     let _ = std::fs::remove_file(&new_path);
                     let _ = std::fs::remove_file(&diff_path);
@@ -687,11 +846,21 @@ impl TestHarness {
         } else {
             // Remove '<test_name>.new.png' file if it exists
             let _ = std::fs::remove_file(&new_path);
+            if std::env::var_os("MASONRY_TEST_BLESS").is_some_and(|it| !it.is_empty()) {
+                new_image.save(&reference_path).unwrap();
+                return;
+            }
             new_image.save(&new_path).unwrap();
             panic!("Snapshot test '{test_name}' failed: No reference file");
         }
     }
 
+    /// Set how [`assert_render_snapshot`](crate::assert_render_snapshot) compares renders with their reference images,
+    /// e.g. to tolerate larger differences or ignore part of the window.
+    pub fn set_snapshot_options(&mut self, options: SnapshotOptions) {
+        self.snapshot_options = options;
+    }
+
     // --- Debug logger ---
 
     // ex: harness.write_debug_logs("test_log.json");
@@ -20,6 +20,7 @@ use widget::widget::get_child_at_pos;
 use widget::WidgetRef;
 
 use crate::event::{PointerEvent, TextEvent};
+use crate::text::TextLayoutJob;
 use crate::widget::SizedBox;
 
 // TODO: Expect doesn't work here
@@ -543,6 +544,14 @@ impl<W: Widget> Widget for Recorder<W> {
         size
     }
 
+    fn take_text_layout_job(&mut self) -> Option<TextLayoutJob> {
+        self.child.take_text_layout_job()
+    }
+
+    fn finish_text_layout_job(&mut self, job: TextLayoutJob) {
+        self.child.finish_text_layout_job(job);
+    }
+
     fn compose(&mut self, ctx: &mut ComposeCtx) {
         self.recording.push(Record::Compose);
         self.child.compose(ctx);
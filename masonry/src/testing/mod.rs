@@ -5,6 +5,10 @@
 
 #![cfg(not(tarpaulin_include))]
 
+#[cfg(not(tarpaulin_include))]
+mod access_tree;
+#[cfg(not(tarpaulin_include))]
+mod fuzz;
 #[cfg(not(tarpaulin_include))]
 mod harness;
 #[cfg(not(tarpaulin_include))]
@@ -14,8 +18,13 @@ mod screenshots;
 #[cfg(not(tarpaulin_include))]
 mod snapshot_utils;
 
+pub use access_tree::AccessTree;
+pub use fuzz::{
+    check_invariants, fuzz_widget_tree, random_widget, FuzzOptions, FuzzRng, InvariantError,
+};
 pub use harness::{TestHarness, HARNESS_DEFAULT_BACKGROUND_COLOR, HARNESS_DEFAULT_SIZE};
 pub use helper_widgets::{ModularWidget, Record, Recorder, Recording, ReplaceChild, TestWidgetExt};
+pub use screenshots::SnapshotOptions;
 
 use crate::WidgetId;
 
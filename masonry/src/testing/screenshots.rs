@@ -6,23 +6,87 @@
 use image::{GenericImageView as _, RgbImage};
 use nv_flip::{FlipImageRgb8, DEFAULT_PIXELS_PER_DEGREE};
 
-pub(crate) fn get_image_diff(ref_image: &RgbImage, new_image: &RgbImage) -> Option<RgbImage> {
-    assert_eq!(
-        (ref_image.width(), ref_image.height()),
-        (new_image.width(), new_image.height()),
-        "New image (right) has different size from old image (left)."
-    );
+use crate::Rect;
 
-    let ref_image_flip = FlipImageRgb8::with_data(ref_image.width(), ref_image.height(), ref_image);
-    let new_image_flip = FlipImageRgb8::with_data(new_image.width(), new_image.height(), new_image);
-    let error_map = nv_flip::flip(ref_image_flip, new_image_flip, DEFAULT_PIXELS_PER_DEGREE);
-    let pool = nv_flip::FlipPool::from_image(&error_map);
-    let mean = pool.mean();
+/// How [`assert_render_snapshot`](crate::assert_render_snapshot) compares a render
+/// with its reference image.
+///
+/// Renders are compared with the [FLIP](https://github.com/NVlabs/flip) perceptual metric,
+/// so that the small differences between GPUs and drivers don't fail tests.
+/// Set them with [`TestHarness::set_snapshot_options`](super::TestHarness::set_snapshot_options).
+#[derive(Clone, Debug)]
+pub struct SnapshotOptions {
+    tolerance: f32,
+    masks: Vec<Rect>,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.01,
+            masks: Vec::new(),
+        }
+    }
+}
+
+impl SnapshotOptions {
+    /// Set the mean perceptual error above which the images are different. The default is `0.01`.
+    ///
+    /// The error of each pixel is between 0 (identical) and 1 (as different as possible).
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Ignore the content of `rect`, in window coordinates.
+    ///
+    /// This is useful for areas which aren't deterministic, such as a clock.
+    pub fn mask(mut self, rect: Rect) -> Self {
+        self.masks.push(rect);
+        self
+    }
+
+    /// Paint the masked areas of `image` black.
+    fn apply_masks(&self, image: &mut RgbImage) {
+        let (width, height) = image.dimensions();
+        for mask in &self.masks {
+            let mask = mask.expand();
+            let x_range = (mask.x0.max(0.) as u32)..(mask.x1.max(0.) as u32).min(width);
+            let y_range = (mask.y0.max(0.) as u32)..(mask.y1.max(0.) as u32).min(height);
+            for y in y_range {
+                for x in x_range.clone() {
+                    image.put_pixel(x, y, [0, 0, 0].into());
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn get_image_diff(
+    ref_image: &RgbImage,
+    new_image: &RgbImage,
+    options: &SnapshotOptions,
+) -> Option<RgbImage> {
+    let (mut ref_image, mut new_image) = (ref_image.clone(), new_image.clone());
+    options.apply_masks(&mut ref_image);
+    options.apply_masks(&mut new_image);
+    let (ref_image, new_image) = (&ref_image, &new_image);
 
-    let is_changed = mean.abs() > 0.01;
+    // Images of different sizes are always different, e.g. if the window size of the test changed
+    if ref_image.dimensions() == new_image.dimensions() {
+        let ref_image_flip =
+            FlipImageRgb8::with_data(ref_image.width(), ref_image.height(), ref_image);
+        let new_image_flip =
+            FlipImageRgb8::with_data(new_image.width(), new_image.height(), new_image);
+        let error_map = nv_flip::flip(ref_image_flip, new_image_flip, DEFAULT_PIXELS_PER_DEGREE);
+        let pool = nv_flip::FlipPool::from_image(&error_map);
+        let mean = pool.mean();
 
-    if !is_changed {
-        return None;
+        let is_changed = mean.abs() > options.tolerance;
+
+        if !is_changed {
+            return None;
+        }
     }
 
     let width = std::cmp::max(ref_image.width(), new_image.width());
@@ -49,3 +113,54 @@ pub(crate) fn get_image_diff(ref_image: &RgbImage, new_image: &RgbImage) -> Opti
 
     Some(diff_image)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image_with_square(color: [u8; 3]) -> RgbImage {
+        RgbImage::from_fn(40, 40, |x, y| {
+            if (10..20).contains(&x) && (10..20).contains(&y) {
+                color.into()
+            } else {
+                [255, 255, 255].into()
+            }
+        })
+    }
+
+    #[test]
+    fn identical_images() {
+        let image = image_with_square([0, 0, 255]);
+        assert!(get_image_diff(&image, &image, &SnapshotOptions::default()).is_none());
+    }
+
+    #[test]
+    fn tolerance() {
+        let ref_image = image_with_square([0, 0, 255]);
+        let new_image = image_with_square([255, 0, 0]);
+        assert!(get_image_diff(&ref_image, &new_image, &SnapshotOptions::default()).is_some());
+
+        let options = SnapshotOptions::default().tolerance(1.0);
+        assert!(get_image_diff(&ref_image, &new_image, &options).is_none());
+    }
+
+    #[test]
+    fn masks() {
+        let ref_image = image_with_square([0, 0, 255]);
+        let new_image = image_with_square([255, 0, 0]);
+
+        let options = SnapshotOptions::default().mask(Rect::new(10., 10., 15., 20.));
+        assert!(get_image_diff(&ref_image, &new_image, &options).is_some());
+
+        let options = options.mask(Rect::new(15., 10., 20., 20.));
+        assert!(get_image_diff(&ref_image, &new_image, &options).is_none());
+    }
+
+    #[test]
+    fn different_sizes() {
+        let ref_image = RgbImage::new(40, 40);
+        let new_image = RgbImage::new(40, 50);
+        let diff = get_image_diff(&ref_image, &new_image, &SnapshotOptions::default()).unwrap();
+        assert_eq!(diff.dimensions(), (40, 50));
+    }
+}
@@ -0,0 +1,292 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keyboard cursor movement and deletion, shared by Masonry's text editing widgets.
+
+use parley::{FontContext, LayoutContext, PlainEditor};
+use unicode_segmentation::UnicodeSegmentation;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use super::BrushIndex;
+
+/// Whether moving forward by a word stops at the start of the next word, as on Windows,
+/// rather than at the end of the current word, as on macOS and Linux.
+const WORD_RIGHT_TO_NEXT_START: bool = cfg!(target_os = "windows");
+
+/// A movement of the cursor in a text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movement {
+    /// To the previous grapheme cluster, in visual order.
+    Left,
+    /// To the next grapheme cluster, in visual order.
+    Right,
+    /// To the start of the previous word.
+    WordLeft,
+    /// To the next word boundary, following the platform convention.
+    ///
+    /// See [`next_word_boundary`].
+    WordRight,
+    /// To the line above.
+    Up,
+    /// To the line below.
+    Down,
+    /// To the start of the line.
+    LineStart,
+    /// To the end of the line.
+    LineEnd,
+    /// To the start of the text.
+    TextStart,
+    /// To the end of the text.
+    TextEnd,
+}
+
+/// How much text is deleted around the cursor, when the selection is empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deletion {
+    /// The grapheme cluster before the cursor.
+    Backward,
+    /// The grapheme cluster after the cursor.
+    Forward,
+    /// Up to the start of the previous word.
+    WordBackward,
+    /// Up to the next word boundary, following the platform convention.
+    WordForward,
+    /// Up to the start of the line.
+    LineBackward,
+}
+
+/// An editing command bound to a key, see [`edit_command`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditCommand {
+    /// Move the cursor, collapsing the selection.
+    Move(Movement),
+    /// Move the focus of the selection, keeping its anchor.
+    Select(Movement),
+    /// Select the whole text.
+    SelectAll,
+    /// Collapse the selection to its focus.
+    CollapseSelection,
+    /// Delete the selection, or the text around the cursor if the selection is empty.
+    Delete(Deletion),
+}
+
+impl EditCommand {
+    /// Whether this command changes the text, rather than only the selection.
+    pub fn edits(self) -> bool {
+        matches!(self, Self::Delete(_))
+    }
+}
+
+/// The editing command bound to `key` with `modifiers` on the current platform, if any.
+///
+/// Words are navigated with <kbd>Ctrl</kbd>, or <kbd>Option</kbd> on macOS, and
+/// <kbd>Shift</kbd> extends the selection.
+/// On macOS, <kbd>Cmd</kbd> with the arrow keys moves to the start and end of lines and
+/// of the text, and <kbd>Cmd</kbd>+<kbd>Backspace</kbd> deletes to the start of the line.
+pub fn edit_command(key: &Key, modifiers: ModifiersState) -> Option<EditCommand> {
+    let macos = cfg!(target_os = "macos");
+    let shift = modifiers.shift_key();
+    let word_mod = if macos {
+        modifiers.alt_key()
+    } else {
+        modifiers.control_key()
+    };
+    let action_mod = if macos {
+        modifiers.super_key()
+    } else {
+        modifiers.control_key()
+    };
+
+    let movement = match key {
+        Key::Character(a) if action_mod && a.as_str().eq_ignore_ascii_case("a") => {
+            return Some(if shift {
+                EditCommand::CollapseSelection
+            } else {
+                EditCommand::SelectAll
+            });
+        }
+        Key::Named(NamedKey::Backspace) => {
+            return Some(EditCommand::Delete(if macos && action_mod {
+                Deletion::LineBackward
+            } else if word_mod {
+                Deletion::WordBackward
+            } else {
+                Deletion::Backward
+            }));
+        }
+        Key::Named(NamedKey::Delete) => {
+            return Some(EditCommand::Delete(if word_mod {
+                Deletion::WordForward
+            } else {
+                Deletion::Forward
+            }));
+        }
+        Key::Named(NamedKey::ArrowLeft) if macos && action_mod => Movement::LineStart,
+        Key::Named(NamedKey::ArrowRight) if macos && action_mod => Movement::LineEnd,
+        Key::Named(NamedKey::ArrowUp) if macos && action_mod => Movement::TextStart,
+        Key::Named(NamedKey::ArrowDown) if macos && action_mod => Movement::TextEnd,
+        Key::Named(NamedKey::ArrowLeft) if word_mod => Movement::WordLeft,
+        Key::Named(NamedKey::ArrowRight) if word_mod => Movement::WordRight,
+        Key::Named(NamedKey::ArrowLeft) => Movement::Left,
+        Key::Named(NamedKey::ArrowRight) => Movement::Right,
+        Key::Named(NamedKey::ArrowUp) => Movement::Up,
+        Key::Named(NamedKey::ArrowDown) => Movement::Down,
+        Key::Named(NamedKey::Home) if action_mod => Movement::TextStart,
+        Key::Named(NamedKey::End) if action_mod => Movement::TextEnd,
+        Key::Named(NamedKey::Home) => Movement::LineStart,
+        Key::Named(NamedKey::End) => Movement::LineEnd,
+        _ => return None,
+    };
+    Some(if shift {
+        EditCommand::Select(movement)
+    } else {
+        EditCommand::Move(movement)
+    })
+}
+
+/// Apply `command` to `editor`.
+///
+/// Grapheme clusters and lines follow the layout of the text, and words follow the
+/// Unicode word boundaries of the text.
+pub fn apply_edit_command(
+    editor: &mut PlainEditor<BrushIndex>,
+    font_ctx: &mut FontContext,
+    layout_ctx: &mut LayoutContext<BrushIndex>,
+    command: EditCommand,
+) {
+    let selection = editor.raw_selection();
+    let (anchor, focus) = (selection.anchor().index(), selection.focus().index());
+    let is_collapsed = selection.is_collapsed();
+    // The text is only needed for words, which parley's editor doesn't segment with
+    // the Unicode word boundaries.
+    let text = editor.text().to_string();
+    let mut driver = editor.driver(font_ctx, layout_ctx);
+
+    match command {
+        EditCommand::Move(movement) => match movement {
+            Movement::Left => driver.move_left(),
+            Movement::Right => driver.move_right(),
+            Movement::WordLeft => {
+                let target = prev_word_boundary(&text, focus);
+                driver.select_byte_range(target, target);
+            }
+            Movement::WordRight => {
+                let target = next_word_boundary(&text, focus);
+                driver.select_byte_range(target, target);
+            }
+            Movement::Up => driver.move_up(),
+            Movement::Down => driver.move_down(),
+            Movement::LineStart => driver.move_to_line_start(),
+            Movement::LineEnd => driver.move_to_line_end(),
+            Movement::TextStart => driver.move_to_text_start(),
+            Movement::TextEnd => driver.move_to_text_end(),
+        },
+        EditCommand::Select(movement) => match movement {
+            Movement::Left => driver.select_left(),
+            Movement::Right => driver.select_right(),
+            Movement::WordLeft => {
+                driver.select_byte_range(anchor, prev_word_boundary(&text, focus));
+            }
+            Movement::WordRight => {
+                driver.select_byte_range(anchor, next_word_boundary(&text, focus));
+            }
+            Movement::Up => driver.select_up(),
+            Movement::Down => driver.select_down(),
+            Movement::LineStart => driver.select_to_line_start(),
+            Movement::LineEnd => driver.select_to_line_end(),
+            Movement::TextStart => driver.select_to_text_start(),
+            Movement::TextEnd => driver.select_to_text_end(),
+        },
+        EditCommand::SelectAll => driver.select_all(),
+        EditCommand::CollapseSelection => driver.collapse_selection(),
+        EditCommand::Delete(_) if !is_collapsed => driver.delete_selection(),
+        EditCommand::Delete(deletion) => match deletion {
+            Deletion::Backward => driver.backdelete(),
+            Deletion::Forward => driver.delete(),
+            Deletion::WordBackward => {
+                driver.select_byte_range(prev_word_boundary(&text, focus), focus);
+                driver.delete_selection();
+            }
+            Deletion::WordForward => {
+                driver.select_byte_range(focus, next_word_boundary(&text, focus));
+                driver.delete_selection();
+            }
+            Deletion::LineBackward => {
+                driver.select_to_line_start();
+                driver.delete_selection();
+            }
+        },
+    }
+}
+
+/// Whether the word segment `segment` is a word, rather than whitespace or punctuation.
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+/// The start of the word before the byte `index` of `text`, or of the word containing it.
+///
+/// Whitespace and punctuation before the cursor are skipped.
+pub fn prev_word_boundary(text: &str, index: usize) -> usize {
+    text[..index]
+        .split_word_bound_indices()
+        .rev()
+        .find(|(_, segment)| is_word(segment))
+        .map_or(0, |(start, _)| start)
+}
+
+/// The next word boundary after the byte `index` of `text`.
+///
+/// On Windows, this is the start of the next word. On other platforms, this is the
+/// end of the word containing `index`, or of the next word.
+pub fn next_word_boundary(text: &str, index: usize) -> usize {
+    next_word_boundary_with(text, index, WORD_RIGHT_TO_NEXT_START)
+}
+
+fn next_word_boundary_with(text: &str, index: usize, to_next_start: bool) -> usize {
+    let mut segments = text[index..].split_word_bound_indices();
+    let boundary = if to_next_start {
+        segments
+            .find(|(start, segment)| *start > 0 && is_word(segment))
+            .map(|(start, _)| start)
+    } else {
+        segments
+            .find(|(_, segment)| is_word(segment))
+            .map(|(start, segment)| start + segment.len())
+    };
+    boundary.map_or(text.len(), |offset| index + offset)
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_boundaries() {
+        let text = "Hello, wörld! Foo";
+        assert_eq!(prev_word_boundary(text, text.len()), 15);
+        assert_eq!(prev_word_boundary(text, 15), 7);
+        // From inside a word, go to its start
+        assert_eq!(prev_word_boundary(text, 10), 7);
+        assert_eq!(prev_word_boundary(text, 7), 0);
+        assert_eq!(prev_word_boundary(text, 0), 0);
+
+        assert_eq!(next_word_boundary_with(text, 0, false), 5);
+        assert_eq!(next_word_boundary_with(text, 5, false), 13);
+        assert_eq!(next_word_boundary_with(text, 10, false), 13);
+        assert_eq!(next_word_boundary_with(text, 15, false), text.len());
+
+        assert_eq!(next_word_boundary_with(text, 0, true), 7);
+        assert_eq!(next_word_boundary_with(text, 10, true), 15);
+        assert_eq!(next_word_boundary_with(text, 15, true), text.len());
+    }
+
+    #[test]
+    fn words_are_unicode_aware() {
+        // Apostrophes don't split words, and ideographs are separate words
+        let text = "can't 漢字";
+        assert_eq!(next_word_boundary_with(text, 0, false), 5);
+        assert_eq!(prev_word_boundary(text, text.len()), 9);
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+
+/// What to search for in a text, with [`TextArea::set_find_pattern`].
+///
+/// [`TextArea::set_find_pattern`]: crate::widget::TextArea::set_find_pattern
+#[derive(Clone, Debug)]
+pub enum FindPattern {
+    /// A literal string.
+    Text {
+        /// The string to search for.
+        text: String,
+        /// Whether the case of letters must match.
+        case_sensitive: bool,
+    },
+    /// A regular expression.
+    ///
+    /// Empty matches are ignored.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl FindPattern {
+    /// A pattern matching `text`, ignoring the case of letters.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text {
+            text: text.into(),
+            case_sensitive: false,
+        }
+    }
+
+    /// The byte ranges of the non-overlapping matches of this pattern in `haystack`, in order.
+    pub fn find_in(&self, haystack: &str) -> Vec<Range<usize>> {
+        match self {
+            Self::Text { text, .. } if text.is_empty() => Vec::new(),
+            Self::Text {
+                text,
+                case_sensitive: true,
+            } => haystack
+                .match_indices(text.as_str())
+                .map(|(start, matched)| start..start + matched.len())
+                .collect(),
+            Self::Text {
+                text,
+                case_sensitive: false,
+            } => find_ignoring_case(haystack, text),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex
+                .find_iter(haystack)
+                .filter(|matched| !matched.is_empty())
+                .map(|matched| matched.range())
+                .collect(),
+        }
+    }
+}
+
+impl PartialEq for FindPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Text {
+                    text: l_text,
+                    case_sensitive: l_case_sensitive,
+                },
+                Self::Text {
+                    text: r_text,
+                    case_sensitive: r_case_sensitive,
+                },
+            ) => l_text == r_text && l_case_sensitive == r_case_sensitive,
+            #[cfg(feature = "regex")]
+            (Self::Regex(l_regex), Self::Regex(r_regex)) => l_regex.as_str() == r_regex.as_str(),
+            #[cfg(feature = "regex")]
+            _ => false,
+        }
+    }
+}
+
+/// The matches of `needle` in `haystack`, comparing the lowercase forms of their characters.
+///
+/// This works on characters rather than lowercasing the whole strings, as the lowercase
+/// form of a character can have a different length, which would shift the byte ranges.
+fn find_ignoring_case(haystack: &str, needle: &str) -> Vec<Range<usize>> {
+    let mut matches = Vec::new();
+    let mut search_start = 0;
+    'starts: for (start, _) in haystack.char_indices() {
+        if start < search_start {
+            continue;
+        }
+        let mut haystack_chars = haystack[start..].char_indices();
+        for needle_char in needle.chars() {
+            let Some((_, haystack_char)) = haystack_chars.next() else {
+                break 'starts;
+            };
+            if !haystack_char.to_lowercase().eq(needle_char.to_lowercase()) {
+                continue 'starts;
+            }
+        }
+        let end = haystack_chars
+            .next()
+            .map_or(haystack.len(), |(offset, _)| start + offset);
+        matches.push(start..end);
+        search_start = end;
+    }
+    matches
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_text() {
+        let haystack = "Straße, STRASSE, strasse";
+        assert_eq!(
+            FindPattern::text("strasse").find_in(haystack),
+            [9..16, 18..25]
+        );
+
+        let case_sensitive = FindPattern::Text {
+            text: "strasse".into(),
+            case_sensitive: true,
+        };
+        assert_eq!(case_sensitive.find_in(haystack), [18..25]);
+
+        // The matches are byte ranges of the haystack
+        assert_eq!(FindPattern::text("é").find_in("aÉé"), [1..3, 3..5]);
+        assert_eq!(FindPattern::text("").find_in(haystack), []);
+    }
+
+    #[test]
+    fn matches_dont_overlap() {
+        assert_eq!(FindPattern::text("aa").find_in("aaaaa"), [0..2, 2..4]);
+    }
+}
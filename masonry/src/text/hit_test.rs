@@ -0,0 +1,37 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mapping between positions in a text layout and indices in its text.
+
+use std::ops::Range;
+
+use parley::layout::{Affinity, Cursor, Selection};
+use parley::Layout;
+use vello::kurbo::{Point, Rect};
+
+use super::BrushIndex;
+
+/// The byte index of the text nearest to `point` in `layout`.
+///
+/// This is the boundary between the glyph clusters closest to `point`, i.e. where a
+/// cursor would be placed by clicking at `point`.
+/// The `point` is relative to the origin of the layout.
+pub fn index_at_point(layout: &Layout<BrushIndex>, point: Point) -> usize {
+    Cursor::from_point(layout, point.x as f32, point.y as f32).index()
+}
+
+/// The rect of a cursor of the given `width` placed before the byte `index` of the text in
+/// `layout`, relative to the origin of the layout.
+///
+/// This spans the height of the line containing `index`.
+pub fn caret_rect(layout: &Layout<BrushIndex>, index: usize, width: f32) -> Rect {
+    Cursor::from_byte_index(layout, index, Affinity::Downstream).geometry(layout, width)
+}
+
+/// The rects covering the text in the byte `range` of `layout`, one per line, relative to
+/// the origin of the layout.
+pub fn range_rects(layout: &Layout<BrushIndex>, range: Range<usize>) -> Vec<Rect> {
+    let start = Cursor::from_byte_index(layout, range.start, Affinity::Downstream);
+    let end = Cursor::from_byte_index(layout, range.end, Affinity::Upstream);
+    Selection::new(start, end).geometry(layout)
+}
@@ -0,0 +1,127 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Measuring text outside of widgets.
+
+use std::ops::Range;
+
+use parley::{FontContext, LayoutContext};
+use vello::kurbo::Size;
+
+use super::{default_style_properties, BrushIndex, StyleProperty};
+use crate::theme;
+
+/// The size of a measured text, and the metrics of its lines.
+///
+/// See [`measure_text`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextMetrics {
+    /// The size of the text.
+    ///
+    /// When the text is wrapped, this is the width of its longest line, not the maximum width.
+    pub size: Size,
+    /// The metrics of each line of the text, from top to bottom.
+    pub lines: Vec<LineMetrics>,
+}
+
+/// The metrics of one line of a measured text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineMetrics {
+    /// The byte range of the text on this line.
+    pub text_range: Range<usize>,
+    /// The distance from the top of the text to the baseline of this line.
+    pub baseline: f64,
+    /// The distance from the baseline to the top of the tallest glyph of this line.
+    pub ascent: f64,
+    /// The distance from the baseline to the bottom of the lowest glyph of this line.
+    pub descent: f64,
+    /// The height of this line, including the line spacing.
+    pub line_height: f64,
+    /// The width of this line.
+    pub advance: f64,
+}
+
+/// Measure `text` as a [`Label`](crate::widget::Label) or [`TextArea`](crate::widget::TextArea)
+/// would render it.
+///
+/// The `styles` apply to the whole text, on top of the default styles of Masonry's widgets.
+/// If `max_width` is set, the text is wrapped at that width, as with word wrapping.
+///
+/// This lets app code make layout decisions, such as how many columns fit, which agree with
+/// what the widgets will render.
+/// The contexts can be those of the window, from [`RenderRoot::measure_text`](crate::RenderRoot::measure_text)
+/// or [`DriverCtx::measure_text`](crate::DriverCtx::measure_text), or standalone ones, in which
+/// case the fonts registered with the window aren't available.
+pub fn measure_text(
+    font_ctx: &mut FontContext,
+    layout_ctx: &mut LayoutContext<BrushIndex>,
+    text: &str,
+    styles: impl IntoIterator<Item = StyleProperty>,
+    max_width: Option<f32>,
+) -> TextMetrics {
+    // TODO: Should we use a different scale?
+    let mut builder = layout_ctx.ranged_builder(font_ctx, text, 1.0);
+    builder.push_default(StyleProperty::FontSize(theme::TEXT_SIZE_NORMAL));
+    for prop in default_style_properties() {
+        builder.push_default(prop);
+    }
+    for prop in styles {
+        builder.push_default(prop);
+    }
+    let mut layout = builder.build(text);
+    layout.break_all_lines(max_width);
+
+    let lines = layout
+        .lines()
+        .map(|line| {
+            let metrics = line.metrics();
+            LineMetrics {
+                text_range: line.text_range(),
+                baseline: metrics.baseline.into(),
+                ascent: metrics.ascent.into(),
+                descent: metrics.descent.into(),
+                line_height: metrics.line_height.into(),
+                advance: metrics.advance.into(),
+            }
+        })
+        .collect();
+    TextMetrics {
+        size: Size::new(layout.width().into(), layout.height().into()),
+        lines,
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_text_has_more_lines() {
+        const ROBOTO: &[u8] = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/resources/fonts/roboto/Roboto-Regular.ttf"
+        ));
+        let mut font_ctx = FontContext::default();
+        font_ctx.collection.register_fonts(ROBOTO.to_vec());
+        let mut layout_ctx = LayoutContext::new();
+        let text = "Some words to wrap";
+        let styles = || [StyleProperty::FontStack("Roboto".into())];
+
+        let unwrapped = measure_text(&mut font_ctx, &mut layout_ctx, text, styles(), None);
+        assert_eq!(unwrapped.lines.len(), 1);
+        assert_eq!(unwrapped.lines[0].text_range, 0..text.len());
+
+        let max_width = unwrapped.size.width as f32 / 2.;
+        let wrapped = measure_text(
+            &mut font_ctx,
+            &mut layout_ctx,
+            text,
+            styles(),
+            Some(max_width),
+        );
+        assert!(wrapped.lines.len() > 1);
+        assert!(wrapped.size.width <= max_width.into());
+        assert!(wrapped.size.height > unwrapped.size.height);
+    }
+}
@@ -11,10 +11,25 @@
 //! All of these have the same set of global styling options, and can contain rich text
 
 #![warn(missing_docs)]
+mod editing;
+mod find;
+mod hit_test;
+mod measure;
 mod render_text;
+mod spell_check;
+mod text_layout_job;
 
+pub use editing::{
+    apply_edit_command, edit_command, next_word_boundary, prev_word_boundary, Deletion,
+    EditCommand, Movement,
+};
+pub use find::FindPattern;
+pub use hit_test::{caret_rect, index_at_point, range_rects};
+pub use measure::{measure_text, LineMetrics, TextMetrics};
 use parley::GenericFamily;
 pub use render_text::render_text;
+pub use spell_check::{SpellChecker, SpellingSuggestions};
+pub use text_layout_job::TextLayoutJob;
 
 /// A reference counted string slice.
 ///
@@ -37,6 +52,15 @@ pub type StyleSet = parley::StyleSet<BrushIndex>;
 
 /// Applies the default text styles for Masonry into `styles`.
 pub(crate) fn default_styles(styles: &mut StyleSet) {
-    styles.insert(StyleProperty::LineHeight(1.2));
-    styles.insert(GenericFamily::SystemUi.into());
+    for prop in default_style_properties() {
+        styles.insert(prop);
+    }
+}
+
+/// The default text styles for Masonry.
+pub(crate) fn default_style_properties() -> [StyleProperty; 2] {
+    [
+        StyleProperty::LineHeight(1.2),
+        GenericFamily::SystemUi.into(),
+    ]
 }
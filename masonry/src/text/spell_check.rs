@@ -0,0 +1,50 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+
+use vello::kurbo::Point;
+
+/// A spell checker, consulted by text areas to flag misspelled words.
+///
+/// Set one with [`TextArea::with_spell_checker`]. The text is checked when the event loop is
+/// idle after it changes (see [`EventCtx::schedule_idle`]), so checking doesn't slow down
+/// typing, and the flagged words are underlined with a squiggly line.
+/// Right-clicking a flagged word submits an [`Action::SpellingSuggestions`].
+///
+/// Both methods do nothing by default, so implementations can provide only one of them.
+///
+/// [`TextArea::with_spell_checker`]: crate::widget::TextArea::with_spell_checker
+/// [`EventCtx::schedule_idle`]: crate::EventCtx::schedule_idle
+/// [`Action::SpellingSuggestions`]: crate::Action::SpellingSuggestions
+pub trait SpellChecker: Send + Sync {
+    #[allow(unused_variables)]
+    // reason: otherwise `text` would need to be named `_text` which behaves badly when using rust-analyzer to implement the trait
+    /// The byte ranges of the misspelled words of `text`, in order.
+    fn check(&self, text: &str) -> Vec<Range<usize>> {
+        Vec::new()
+    }
+
+    #[allow(unused_variables)]
+    // reason: otherwise `word` would need to be named `_word` which behaves badly when using rust-analyzer to implement the trait
+    /// The replacements to suggest for the misspelled `word`, best first.
+    fn suggestions(&self, word: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The replacements suggested for a misspelled word, which the user right-clicked.
+///
+/// Apps typically show these in a context menu, and replace the word with the chosen one
+/// using [`TextArea::replace_range`](crate::widget::TextArea::replace_range).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpellingSuggestions {
+    /// The byte range of the word in the text.
+    pub range: Range<usize>,
+    /// The misspelled word.
+    pub word: String,
+    /// The suggested replacements, best first.
+    pub suggestions: Vec<String>,
+    /// Where the word was clicked, in window coordinates.
+    pub position: Point,
+}
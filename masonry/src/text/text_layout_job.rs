@@ -0,0 +1,79 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Text shaping which can be run independently of the widget which owns the text.
+
+use parley::{FontContext, Layout, LayoutContext, PlainEditor};
+
+use super::{ArcStr, BrushIndex, StyleProperty};
+
+/// An independent unit of text shaping work.
+///
+/// Shaping is usually the most expensive part of laying out text, and doesn't depend
+/// on the constraints passed to [`Widget::layout`](crate::Widget::layout).
+/// Widgets can hand this work out through [`Widget::take_text_layout_job`](crate::Widget::take_text_layout_job),
+/// so that Masonry can shape the text of many widgets at once before the layout pass.
+/// The job is then handed back through [`Widget::finish_text_layout_job`](crate::Widget::finish_text_layout_job).
+///
+/// Line breaking and alignment are left to the widget, as they depend on layout constraints.
+/// The exception is the editor of a [`TextArea`](crate::widget::TextArea), which breaks its
+/// lines as it shapes them, at the width of its previous layout.
+pub struct TextLayoutJob {
+    /// The text to shape.
+    pub text: ArcStr,
+    /// The default styles applied to the whole text.
+    pub styles: Vec<StyleProperty>,
+    /// The layout the shaped text is written into.
+    ///
+    /// Widgets should move their existing layout in here, so that its allocations can be reused.
+    pub layout: Layout<BrushIndex>,
+    /// The editor whose layout is refreshed instead of shaping `text`.
+    editor: Option<PlainEditor<BrushIndex>>,
+}
+
+impl TextLayoutJob {
+    /// Create a new job which will shape `text` with the given default `styles` into `layout`.
+    pub fn new(
+        text: ArcStr,
+        styles: impl IntoIterator<Item = StyleProperty>,
+        layout: Layout<BrushIndex>,
+    ) -> Self {
+        Self {
+            text,
+            styles: styles.into_iter().collect(),
+            layout,
+            editor: None,
+        }
+    }
+
+    /// Create a new job which will refresh the layout of `editor`, using its own text and styles.
+    ///
+    /// The editor is given back by [`into_editor`](Self::into_editor).
+    pub(crate) fn for_editor(editor: PlainEditor<BrushIndex>) -> Self {
+        Self {
+            text: "".into(),
+            styles: Vec::new(),
+            layout: Layout::new(),
+            editor: Some(editor),
+        }
+    }
+
+    /// The editor this job was created [for](Self::for_editor), if any.
+    pub(crate) fn into_editor(self) -> Option<PlainEditor<BrushIndex>> {
+        self.editor
+    }
+
+    /// Shape the text into [`layout`](Self::layout).
+    pub fn run(&mut self, font_ctx: &mut FontContext, layout_ctx: &mut LayoutContext<BrushIndex>) {
+        if let Some(editor) = &mut self.editor {
+            editor.refresh_layout(font_ctx, layout_ctx);
+            return;
+        }
+        // TODO: Should we use a different scale?
+        let mut builder = layout_ctx.ranged_builder(font_ctx, &self.text, 1.0);
+        for prop in &self.styles {
+            builder.push_default(prop.clone());
+        }
+        builder.build_into(&mut self.layout, &self.text);
+    }
+}
@@ -5,6 +5,8 @@
 
 #![allow(missing_docs)]
 
+use std::time::Duration;
+
 use vello::peniko::Color;
 
 use crate::Insets;
@@ -37,6 +39,19 @@ pub const SELECTED_TEXT_BACKGROUND_COLOR: Color = Color::rgb8(0x43, 0x70, 0xA8);
 pub const SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR: Color = Color::rgb8(0x74, 0x74, 0x74);
 pub const SELECTION_TEXT_COLOR: Color = Color::rgb8(0x00, 0x00, 0x00);
 pub const CURSOR_COLOR: Color = Color::WHITE;
+pub const FIND_MATCH_COLOR: Color = Color::rgb8(0x6e, 0x5a, 0x1e);
+pub const FIND_ACTIVE_MATCH_COLOR: Color = Color::rgb8(0xc0, 0x8a, 0x10);
+pub const SPELLING_ERROR_COLOR: Color = Color::rgb8(0xe8, 0x3b, 0x3b);
+pub const ERROR_TEXT_COLOR: Color = Color::rgb8(0xff, 0x6b, 0x6b);
+pub const METER_OPTIMAL_COLOR: Color = Color::rgb8(0x4c, 0xc0, 0x5a);
+pub const METER_SUBOPTIMAL_COLOR: Color = Color::rgb8(0xe8, 0xb9, 0x2e);
+pub const METER_CRITICAL_COLOR: Color = Color::rgb8(0xe8, 0x3b, 0x3b);
+pub const CURSOR_WIDTH: f32 = 1.5;
+/// The time the text cursor stays shown, then hidden, when it blinks.
+///
+/// Winit doesn't report the platform's blink rate, so this is the default rate of Windows,
+/// which is close to that of the other platforms.
+pub const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(530);
 pub const TEXT_SIZE_NORMAL: f32 = 15.0;
 pub const TEXT_SIZE_LARGE: f32 = 24.0;
 pub const BASIC_WIDGET_HEIGHT: f64 = 18.0;
@@ -58,6 +73,72 @@ pub const WIDGET_PADDING_VERTICAL: f64 = 10.0;
 pub const WIDGET_PADDING_HORIZONTAL: f64 = 8.0;
 pub const WIDGET_CONTROL_COMPONENT_PADDING: f64 = 4.0;
 
+/// How large widgets and the spaces around them are.
+///
+/// The sizes of this module are those of [`Density::Normal`]. Built-in widgets scale them
+/// with the density of their window, which they read with
+/// [`LayoutCtx::density`](crate::LayoutCtx::density), so that they can be made larger
+/// to be touched, or smaller to fit more of them on screen.
+///
+/// The density is set with [`RenderRoot::set_density`](crate::RenderRoot::set_density),
+/// and switches to [`Touch`](Density::Touch) when the window is first touched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Density {
+    /// Smaller widgets and paddings, for dense desktop UIs.
+    Compact,
+    /// The sizes of this module.
+    #[default]
+    Normal,
+    /// Larger widgets and paddings, with hit targets large enough to be touched.
+    Touch,
+}
+
+impl Density {
+    /// The factor by which the sizes of this module are scaled.
+    pub fn scale(self) -> f64 {
+        match self {
+            Self::Compact => 0.75,
+            Self::Normal => 1.0,
+            Self::Touch => 1.5,
+        }
+    }
+
+    /// [`BASIC_WIDGET_HEIGHT`], scaled for this density.
+    pub fn basic_widget_height(self) -> f64 {
+        BASIC_WIDGET_HEIGHT * self.scale()
+    }
+
+    /// [`BORDERED_WIDGET_HEIGHT`], scaled for this density.
+    pub fn bordered_widget_height(self) -> f64 {
+        BORDERED_WIDGET_HEIGHT * self.scale()
+    }
+
+    /// [`WIDGET_PADDING_VERTICAL`], scaled for this density.
+    pub fn widget_padding_vertical(self) -> f64 {
+        WIDGET_PADDING_VERTICAL * self.scale()
+    }
+
+    /// [`WIDGET_PADDING_HORIZONTAL`], scaled for this density.
+    pub fn widget_padding_horizontal(self) -> f64 {
+        WIDGET_PADDING_HORIZONTAL * self.scale()
+    }
+
+    /// [`WIDGET_CONTROL_COMPONENT_PADDING`], scaled for this density.
+    pub fn widget_control_component_padding(self) -> f64 {
+        WIDGET_CONTROL_COMPONENT_PADDING * self.scale()
+    }
+
+    /// [`SCROLLBAR_WIDTH`], scaled for this density.
+    pub fn scrollbar_width(self) -> f64 {
+        SCROLLBAR_WIDTH * self.scale()
+    }
+
+    /// [`SCROLLBAR_MIN_SIZE`], scaled for this density.
+    pub fn scrollbar_min_size(self) -> f64 {
+        SCROLLBAR_MIN_SIZE * self.scale()
+    }
+}
+
 static DEBUG_COLOR: &[Color] = &[
     Color::rgb8(230, 25, 75),
     Color::rgb8(60, 180, 75),
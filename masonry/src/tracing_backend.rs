@@ -141,6 +141,10 @@ pub(crate) fn try_init_test_tracing() -> Result<(), SetGlobalDefaultError> {
 }
 
 /// Initialise tracing for an end-user application.
+#[cfg_attr(
+    not(feature = "event_loop_runner"),
+    expect(dead_code, reason = "Only used by the winit event loop runner")
+)]
 pub(crate) fn try_init_tracing() -> Result<(), SetGlobalDefaultError> {
     // Default level is DEBUG in --dev, INFO in --release, unless a level is passed.
     // DEBUG should print a few logs per low-density event.
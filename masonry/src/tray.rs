@@ -0,0 +1,94 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An icon in the system tray, with a menu.
+//!
+//! The tray is set up with [`MasonryState::set_tray`], and created by the event loop runner
+//! once the event loop has started. When the user selects an item of its menu,
+//! [`AppDriver::on_tray_menu`](crate::AppDriver::on_tray_menu) is called with the item's id.
+//!
+//! On Linux, the tray needs the GTK event loop, which Masonry doesn't run, so the tray is
+//! currently only shown on Windows and macOS.
+//!
+//! [`MasonryState::set_tray`]: crate::event_loop_runner::MasonryState::set_tray
+
+use image::RgbaImage;
+use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+use crate::event_loop_runner::{EventLoopProxy, MasonryUserEvent};
+
+/// An entry of the tray menu.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrayMenuEntry {
+    /// An item which can be selected.
+    Item {
+        /// The id given to [`AppDriver::on_tray_menu`](crate::AppDriver::on_tray_menu).
+        id: String,
+        /// The text of the item.
+        text: String,
+        /// Whether the item can be selected.
+        enabled: bool,
+    },
+    /// A line between groups of items.
+    Separator,
+}
+
+impl TrayMenuEntry {
+    /// An enabled item.
+    pub fn item(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::Item {
+            id: id.into(),
+            text: text.into(),
+            enabled: true,
+        }
+    }
+}
+
+/// The settings of a tray icon.
+#[derive(Clone, Debug, Default)]
+pub struct TrayOptions {
+    /// The image of the icon.
+    pub icon: Option<RgbaImage>,
+    /// The text shown when the pointer hovers the icon.
+    pub tooltip: Option<String>,
+    /// The entries of the menu shown when the icon is clicked.
+    pub menu: Vec<TrayMenuEntry>,
+}
+
+/// The selection of an item of the tray menu.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrayMenuEvent {
+    /// The id of the selected [item](TrayMenuEntry::Item).
+    pub id: String,
+}
+
+/// Create the tray icon described by `options`, whose menu events are sent to `proxy`.
+pub(crate) fn create_tray(
+    options: &TrayOptions,
+    proxy: EventLoopProxy,
+) -> Result<TrayIcon, Box<dyn std::error::Error>> {
+    let menu = Menu::new();
+    for entry in &options.menu {
+        match entry {
+            TrayMenuEntry::Item { id, text, enabled } => {
+                menu.append(&MenuItem::with_id(id.as_str(), text, *enabled, None))?;
+            }
+            TrayMenuEntry::Separator => menu.append(&PredefinedMenuItem::separator())?,
+        }
+    }
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+        let event = TrayMenuEvent { id: event.id.0 };
+        let _ = proxy.send_event(MasonryUserEvent::TrayMenu(event));
+    }));
+
+    let mut builder = TrayIconBuilder::new().with_menu(Box::new(menu));
+    if let Some(icon) = &options.icon {
+        let (width, height) = icon.dimensions();
+        builder = builder.with_icon(Icon::from_rgba(icon.as_raw().clone(), width, height)?);
+    }
+    if let Some(tooltip) = &options.tooltip {
+        builder = builder.with_tooltip(tooltip);
+    }
+    Ok(builder.build()?)
+}
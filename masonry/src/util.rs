@@ -70,6 +70,8 @@ pub trait AsAny: Any {
     fn as_dyn_any(&self) -> &dyn Any;
     /// Return self.
     fn as_mut_dyn_any(&mut self) -> &mut dyn Any;
+    /// Return self.
+    fn into_dyn_any(self: Box<Self>) -> Box<dyn Any>;
 }
 
 impl<T: Any> AsAny for T {
@@ -80,4 +82,8 @@ impl<T: Any> AsAny for T {
     fn as_mut_dyn_any(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn into_dyn_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
 }
@@ -0,0 +1,262 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compositing the content of [`RenderViewport`] widgets over the rendered scene.
+//!
+//! [`RenderViewport`]: crate::widget::RenderViewport
+
+use std::collections::HashMap;
+
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, BlendState, ColorTargetState, ColorWrites,
+    CommandEncoderDescriptor, Device, Extent3d, FragmentState, LoadOp, Operations,
+    PipelineLayoutDescriptor, Queue, RenderPassColorAttachment, RenderPassDescriptor,
+    RenderPipeline, RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor,
+    ShaderModuleDescriptor, ShaderSource, ShaderStages, StoreOp, Texture, TextureDescriptor,
+    TextureFormat, TextureSampleType, TextureUsages, TextureView, TextureViewDescriptor,
+    TextureViewDimension, VertexState,
+};
+
+use crate::dpi::PhysicalSize;
+use crate::widget::{ViewportTarget, VIEWPORT_FORMAT};
+use crate::{Rect, RenderRoot, WidgetId};
+
+const SHADER: &str = r"
+@group(0) @binding(0) var viewport_texture: texture_2d<f32>;
+@group(0) @binding(1) var viewport_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// A triangle covering the whole render pass viewport.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(viewport_texture, viewport_sampler, in.uv);
+}
+";
+
+/// Renders the viewports of a window and draws them over its surface.
+pub(crate) struct ViewportCompositor {
+    pipeline: RenderPipeline,
+    bind_group_layout: BindGroupLayout,
+    sampler: Sampler,
+    /// The texture each viewport renders to, kept whilst its size doesn't change.
+    textures: HashMap<WidgetId, Texture>,
+}
+
+impl ViewportCompositor {
+    /// Create a compositor drawing to surfaces of `format`.
+    pub(crate) fn new(device: &Device, format: TextureFormat) -> Self {
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("viewport_compositor"),
+            source: ShaderSource::Wgsl(SHADER.into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("viewport_compositor"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("viewport_compositor"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("viewport_compositor"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                compilation_options: Default::default(),
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: Default::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+            multiview: None,
+            cache: None,
+        });
+        // The textures are drawn at their size, so no filtering is needed
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("viewport_compositor"),
+            ..Default::default()
+        });
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            textures: HashMap::new(),
+        }
+    }
+
+    /// Call the callbacks of the viewports of `render_root`, then draw their textures over
+    /// `target`, which is `target_size` pixels large.
+    pub(crate) fn render(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        render_root: &mut RenderRoot,
+        target: &TextureView,
+        target_size: PhysicalSize<u32>,
+    ) {
+        let scale_factor = render_root.scale_factor;
+        // The rects of the viewports in physical pixels, clipped to the target
+        let mut rects = Vec::new();
+        let mut removed = Vec::new();
+        for &id in render_root.global_state.viewport_callbacks.keys() {
+            let Some(widget) = render_root.get_widget(id) else {
+                removed.push(id);
+                continue;
+            };
+            if widget.ctx().is_stashed() || widget.ctx().is_hidden() {
+                continue;
+            }
+            let rect = widget
+                .ctx()
+                .window_layout_rect()
+                .scale_from_origin(scale_factor)
+                .round();
+            let rect = rect.intersect(Rect::new(
+                0.,
+                0.,
+                target_size.width as f64,
+                target_size.height as f64,
+            ));
+            if rect.width() >= 1. && rect.height() >= 1. {
+                rects.push((id, rect));
+            }
+        }
+        for id in removed {
+            render_root.global_state.viewport_callbacks.remove(&id);
+        }
+        self.textures
+            .retain(|id, _| render_root.global_state.viewport_callbacks.contains_key(id));
+        if rects.is_empty() {
+            return;
+        }
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("viewport_compositor"),
+        });
+        for (id, rect) in rects {
+            let size = PhysicalSize::new(rect.width() as u32, rect.height() as u32);
+            let texture = self
+                .textures
+                .entry(id)
+                .or_insert_with(|| Self::create_texture(device, size));
+            if texture.width() != size.width || texture.height() != size.height {
+                *texture = Self::create_texture(device, size);
+            }
+            let view = texture.create_view(&TextureViewDescriptor::default());
+
+            let callback = render_root
+                .global_state
+                .viewport_callbacks
+                .get_mut(&id)
+                .unwrap();
+            callback(&ViewportTarget {
+                device,
+                queue,
+                view: &view,
+                size,
+                scale_factor,
+            });
+
+            let bind_group = device.create_bind_group(&BindGroupDescriptor {
+                label: Some("viewport_compositor"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+            let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("viewport_compositor"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Load,
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_viewport(
+                rect.x0 as f32,
+                rect.y0 as f32,
+                rect.width() as f32,
+                rect.height() as f32,
+                0.,
+                1.,
+            );
+            pass.draw(0..3, 0..1);
+        }
+        queue.submit([encoder.finish()]);
+    }
+
+    fn create_texture(device: &Device, size: PhysicalSize<u32>) -> Texture {
+        device.create_texture(&TextureDescriptor {
+            label: Some("render_viewport"),
+            size: Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: VIEWPORT_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+    }
+}
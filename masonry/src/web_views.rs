@@ -0,0 +1,112 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The native webviews of the [`WebView`](crate::widget::WebView) widgets, created with wry.
+
+use std::collections::HashMap;
+
+use tracing::warn;
+use winit::window::Window;
+use wry::dpi::{LogicalPosition, LogicalSize};
+use wry::{PageLoadEvent, WebViewBuilder};
+
+use crate::event_loop_runner::{EventLoopProxy, MasonryUserEvent};
+use crate::widget::WebViewRequest;
+use crate::{Action, Rect, RenderRoot, WidgetId};
+
+struct NativeWebView {
+    web_view: wry::WebView,
+    bounds: Rect,
+    visible: bool,
+}
+
+/// The webviews of a window, by the id of their widget.
+#[derive(Default)]
+pub(crate) struct WebViews {
+    web_views: HashMap<WidgetId, NativeWebView>,
+}
+
+impl WebViews {
+    /// Handle a [`RenderRootSignal::WebView`](crate::RenderRootSignal::WebView).
+    pub(crate) fn handle_request(
+        &mut self,
+        window: &Window,
+        proxy: &EventLoopProxy,
+        widget_id: WidgetId,
+        request: WebViewRequest,
+    ) {
+        let result = match request {
+            WebViewRequest::Create { url } => {
+                let message_proxy = proxy.clone();
+                let load_proxy = proxy.clone();
+                let web_view = WebViewBuilder::new()
+                    .with_url(url)
+                    // The bounds are set once the widget has been laid out
+                    .with_visible(false)
+                    .with_ipc_handler(move |request| {
+                        let action = Action::WebViewMessage(request.into_body());
+                        let _ =
+                            message_proxy.send_event(MasonryUserEvent::Action(action, widget_id));
+                    })
+                    .with_on_page_load_handler(move |event, url| {
+                        if matches!(event, PageLoadEvent::Finished) {
+                            let action = Action::WebViewLoaded(url);
+                            let _ =
+                                load_proxy.send_event(MasonryUserEvent::Action(action, widget_id));
+                        }
+                    })
+                    .build_as_child(window);
+                web_view.map(|web_view| {
+                    self.web_views.insert(
+                        widget_id,
+                        NativeWebView {
+                            web_view,
+                            bounds: Rect::ZERO,
+                            visible: false,
+                        },
+                    );
+                })
+            }
+            WebViewRequest::LoadUrl(url) => match self.web_views.get(&widget_id) {
+                Some(native) => native.web_view.load_url(&url),
+                None => Ok(()),
+            },
+            WebViewRequest::EvaluateScript(script) => match self.web_views.get(&widget_id) {
+                Some(native) => native.web_view.evaluate_script(&script),
+                None => Ok(()),
+            },
+        };
+        if let Err(err) = result {
+            warn!("WebView request failed for {widget_id:?}: {err}");
+        }
+    }
+
+    /// Move the webviews over the layout rects of their widgets, hide those whose widget is
+    /// stashed, and destroy those whose widget was removed.
+    pub(crate) fn sync(&mut self, render_root: &RenderRoot) {
+        self.web_views.retain(|&widget_id, native| {
+            let Some(widget) = render_root.get_widget(widget_id) else {
+                return false;
+            };
+            let rect = widget.ctx().window_layout_rect();
+            if rect != native.bounds {
+                let bounds = wry::Rect {
+                    position: LogicalPosition::new(rect.x0, rect.y0).into(),
+                    size: LogicalSize::new(rect.width(), rect.height()).into(),
+                };
+                if let Err(err) = native.web_view.set_bounds(bounds) {
+                    warn!("Failed to move the webview of {widget_id:?}: {err}");
+                }
+                native.bounds = rect;
+            }
+            let visible = !widget.ctx().is_stashed() && !widget.ctx().is_hidden();
+            if visible != native.visible {
+                if let Err(err) = native.web_view.set_visible(visible) {
+                    warn!("Failed to show or hide the webview of {widget_id:?}: {err}");
+                }
+                native.visible = visible;
+            }
+            true
+        });
+    }
+}
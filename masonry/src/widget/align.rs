@@ -15,7 +15,7 @@ use vello::Scene;
 
 use crate::contexts::AccessCtx;
 use crate::paint_scene_helpers::UnitPoint;
-use crate::widget::WidgetPod;
+use crate::widget::{WidgetMut, WidgetPod};
 use crate::{
     AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent, QueryCtx, Rect,
     RegisterCtx, Size, TextEvent, Widget, WidgetId,
@@ -83,6 +83,38 @@ impl Align {
     }
 }
 
+// --- MARK: WIDGETMUT ---
+impl Align {
+    /// Set the alignment of the child.
+    pub fn set_alignment(this: &mut WidgetMut<'_, Self>, align: UnitPoint) {
+        this.widget.align = align;
+        this.ctx.request_layout();
+    }
+
+    /// Set the width of this widget as a multiple of the child's width, or `None` to take
+    /// all the available width.
+    ///
+    /// [`Align::vertical`] sets this to 1, so that the child is only aligned vertically.
+    pub fn set_width_factor(this: &mut WidgetMut<'_, Self>, width_factor: Option<f64>) {
+        this.widget.width_factor = width_factor;
+        this.ctx.request_layout();
+    }
+
+    /// Set the height of this widget as a multiple of the child's height, or `None` to take
+    /// all the available height.
+    ///
+    /// [`Align::horizontal`] sets this to 1, so that the child is only aligned horizontally.
+    pub fn set_height_factor(this: &mut WidgetMut<'_, Self>, height_factor: Option<f64>) {
+        this.widget.height_factor = height_factor;
+        this.ctx.request_layout();
+    }
+
+    /// Get mutable access to the child.
+    pub fn child_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.child)
+    }
+}
+
 // --- MARK: IMPL WIDGET ---
 impl Widget for Align {
     fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
@@ -196,6 +228,22 @@ mod tests {
         assert_render_snapshot!(harness, "right");
     }
 
+    #[test]
+    fn set_alignment() {
+        let widget = Align::left(Label::new("hello"));
+        let mut harness = TestHarness::create(widget);
+        let label_id = harness.root_widget().children()[0].id();
+        let label_x =
+            |harness: &TestHarness| harness.get_widget(label_id).ctx().window_layout_rect().x0;
+        assert_eq!(label_x(&harness), 0.);
+
+        harness.edit_root_widget(|mut align| {
+            let mut align = align.downcast::<Align>();
+            Align::set_alignment(&mut align, UnitPoint::RIGHT);
+        });
+        assert!(label_x(&harness) > 0.);
+    }
+
     #[test]
     fn left() {
         let widget = Align::left(Label::new("hello"));
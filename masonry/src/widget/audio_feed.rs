@@ -0,0 +1,53 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The channel through which audio samples are sent to the audio widgets.
+
+use std::sync::{Arc, Mutex};
+
+/// The channel through which a producer, such as an audio callback or a decoder, sends
+/// samples to a [`Waveform`](crate::widget::Waveform) or a
+/// [`LevelMeter`](crate::widget::LevelMeter).
+///
+/// This is cheap to clone, and can be sent to the thread producing the samples.
+/// The samples are buffered until the widget reading them takes them, on its next animation
+/// frame, so a feed should only be read by one widget.
+#[derive(Clone, Debug)]
+pub struct AudioFeed {
+    channels: usize,
+    pending: Arc<Mutex<Vec<f32>>>,
+}
+
+impl AudioFeed {
+    /// Create a feed of samples with `channels` interleaved channels.
+    pub fn new(channels: usize) -> Self {
+        Self {
+            channels: channels.max(1),
+            pending: Arc::default(),
+        }
+    }
+
+    /// The number of interleaved channels of the samples.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Send samples, with one sample of each channel per frame, between -1 and 1.
+    pub fn push(&self, samples: &[f32]) {
+        self.pending.lock().unwrap().extend_from_slice(samples);
+    }
+
+    /// Take the samples sent since the last call.
+    pub(crate) fn take(&self) -> Vec<f32> {
+        std::mem::take(&mut *self.pending.lock().unwrap())
+    }
+}
+
+/// Two `AudioFeed`s are equal if they are handles to the same channel.
+impl PartialEq for AudioFeed {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.pending, &other.pending)
+    }
+}
+
+impl Eq for AudioFeed {}
@@ -3,6 +3,8 @@
 
 //! A button widget.
 
+use std::time::Duration;
+
 use accesskit::{Node, Role};
 use smallvec::{smallvec, SmallVec};
 use tracing::{trace, trace_span, Span};
@@ -15,7 +17,7 @@ use crate::text::ArcStr;
 use crate::widget::{Label, WidgetMut, WidgetPod};
 use crate::{
     theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, Insets, LayoutCtx, PaintCtx,
-    PointerEvent, QueryCtx, Size, TextEvent, Update, UpdateCtx, Widget, WidgetId,
+    PointerEvent, QueryCtx, Size, TextEvent, TimerToken, Update, UpdateCtx, Widget, WidgetId,
 };
 
 // the minimum padding added to a button.
@@ -23,11 +25,36 @@ use crate::{
 // should be reevaluated at some point.
 const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
 
+/// How a [`Button`] repeats its action while it's held down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AutoRepeat {
+    /// The time between the press and the first repetition.
+    pub delay: Duration,
+    /// The time between the following repetitions.
+    pub interval: Duration,
+}
+
+impl Default for AutoRepeat {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(500),
+            interval: Duration::from_millis(50),
+        }
+    }
+}
+
 /// A button with a text label.
 ///
 /// Emits [`Action::ButtonPressed`] when pressed.
+///
+/// With [auto-repeat](Self::with_auto_repeat), the action is instead emitted as soon as the
+/// primary button is pressed, then repeatedly while the button is held down and the
+/// pointer is over it, which suits buttons such as the arrows of a spinner.
 pub struct Button {
     label: WidgetPod<Label>,
+    auto_repeat: Option<AutoRepeat>,
+    /// The timer of the next repetition, while the button is held down.
+    repeat_timer: Option<TimerToken>,
 }
 
 // --- MARK: BUILDERS ---
@@ -59,8 +86,24 @@ impl Button {
     pub fn from_label(label: Label) -> Button {
         Button {
             label: WidgetPod::new(label),
+            auto_repeat: None,
+            repeat_timer: None,
         }
     }
+
+    /// Builder-style method to repeat the button's action while it's held down.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use masonry::widget::{AutoRepeat, Button};
+    ///
+    /// let button = Button::new("+").with_auto_repeat(AutoRepeat::default());
+    /// ```
+    pub fn with_auto_repeat(mut self, auto_repeat: AutoRepeat) -> Self {
+        self.auto_repeat = Some(auto_repeat);
+        self
+    }
 }
 
 // --- MARK: WIDGETMUT ---
@@ -73,22 +116,42 @@ impl Button {
     pub fn label_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Label> {
         this.ctx.get_mut(&mut this.widget.label)
     }
+
+    /// Set whether and how the button's action is repeated while it's held down.
+    ///
+    /// See [`Button::with_auto_repeat`].
+    pub fn set_auto_repeat(this: &mut WidgetMut<'_, Self>, auto_repeat: Option<AutoRepeat>) {
+        this.widget.auto_repeat = auto_repeat;
+        if auto_repeat.is_none() {
+            this.widget.repeat_timer = None;
+        }
+    }
 }
 
 // --- MARK: IMPL WIDGET ---
 impl Widget for Button {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
         match event {
-            PointerEvent::PointerDown(_, _) => {
+            PointerEvent::PointerDown(button, _) => {
                 if !ctx.is_disabled() {
                     ctx.capture_pointer();
                     // Changes in pointer capture impact appearance, but not accessibility node
                     ctx.request_paint_only();
                     trace!("Button {:?} pressed", ctx.widget_id());
+                    if let Some(auto_repeat) = self.auto_repeat {
+                        if *button == PointerButton::Primary {
+                            ctx.submit_action(Action::ButtonPressed(*button));
+                            self.repeat_timer = Some(ctx.request_timer(auto_repeat.delay));
+                        }
+                    }
                 }
             }
             PointerEvent::PointerUp(button, _) => {
-                if ctx.has_pointer_capture() && ctx.is_hovered() && !ctx.is_disabled() {
+                // With auto-repeat, the primary button's action was emitted when it was pressed
+                let repeated = self.auto_repeat.is_some() && *button == PointerButton::Primary;
+                self.repeat_timer = None;
+                if ctx.has_pointer_capture() && ctx.is_hovered() && !ctx.is_disabled() && !repeated
+                {
                     ctx.submit_action(Action::ButtonPressed(*button));
                     trace!("Button {:?} released", ctx.widget_id());
                 }
@@ -117,6 +180,22 @@ impl Widget for Button {
             Update::HoveredChanged(_) | Update::FocusChanged(_) | Update::DisabledChanged(_) => {
                 ctx.request_paint_only();
             }
+            Update::Timer(token) if self.repeat_timer == Some(*token) => {
+                let Some(auto_repeat) = self.auto_repeat else {
+                    return;
+                };
+                if !ctx.has_pointer_capture() || ctx.is_disabled() {
+                    self.repeat_timer = None;
+                    return;
+                }
+                // Keep the timer running while the pointer is outside the button,
+                // so that repetitions resume when it comes back
+                if ctx.is_hovered() {
+                    ctx.submit_action(Action::ButtonPressed(PointerButton::Primary));
+                    trace!("Button {:?} repeated", ctx.widget_id());
+                }
+                self.repeat_timer = Some(ctx.request_timer(auto_repeat.interval));
+            }
             _ => {}
         }
     }
@@ -136,7 +215,7 @@ impl Widget for Button {
 
         // HACK: to make sure we look okay at default sizes when beside a textbox,
         // we make sure we will have at least the same height as the default textbox.
-        let min_height = theme::BORDERED_WIDGET_HEIGHT;
+        let min_height = ctx.density().bordered_widget_height();
 
         let button_size = bc.constrain(Size::new(
             label_size.width + padding.width,
@@ -224,7 +303,8 @@ mod tests {
     use crate::assert_render_snapshot;
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
     use crate::text::StyleProperty;
-    use crate::theme::PRIMARY_LIGHT;
+    use crate::theme::{Density, PRIMARY_LIGHT};
+    use crate::widget::Flex;
 
     #[test]
     fn simple_button() {
@@ -245,6 +325,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn touch_density() {
+        let [button_id] = widget_ids();
+        let widget = Flex::column().with_child_id(Button::new("Hello"), button_id);
+
+        let mut harness = TestHarness::create(widget);
+        let height = |harness: &TestHarness| harness.get_widget(button_id).ctx().size().height;
+        assert_eq!(height(&harness), theme::BORDERED_WIDGET_HEIGHT);
+
+        // The first touch makes the button large enough to be touched, and still presses it.
+        let center = harness
+            .get_widget(button_id)
+            .ctx()
+            .window_layout_rect()
+            .center();
+        harness.tap_at(center);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed(PointerButton::Primary), button_id))
+        );
+        assert_eq!(height(&harness), Density::Touch.bordered_widget_height());
+
+        // The density set by the app isn't overridden by later touches.
+        harness.set_density(Density::Compact);
+        harness.tap_at(center);
+        let button = harness.get_widget(button_id);
+        assert_eq!(button.ctx().density(), Density::Compact);
+        assert!(button.ctx().size().height < theme::BORDERED_WIDGET_HEIGHT);
+    }
+
+    #[test]
+    fn auto_repeat_button() {
+        let [button_id] = widget_ids();
+        let auto_repeat = AutoRepeat {
+            delay: Duration::from_millis(500),
+            interval: Duration::from_millis(100),
+        };
+        let widget = Button::new("+")
+            .with_auto_repeat(auto_repeat)
+            .with_id(button_id);
+
+        let mut harness = TestHarness::create(widget);
+        let pressed = Some((Action::ButtonPressed(PointerButton::Primary), button_id));
+
+        harness.mouse_move_to(button_id);
+        harness.mouse_button_press(PointerButton::Primary);
+        assert_eq!(harness.pop_action(), pressed);
+
+        harness.advance_time(Duration::from_millis(400));
+        assert_eq!(harness.pop_action(), None);
+        harness.advance_time(Duration::from_millis(100));
+        assert_eq!(harness.pop_action(), pressed);
+        harness.advance_time(Duration::from_millis(100));
+        assert_eq!(harness.pop_action(), pressed);
+
+        // Releasing the button stops the repetitions, without emitting another action
+        harness.mouse_button_release(PointerButton::Primary);
+        harness.advance_time(Duration::from_millis(500));
+        assert_eq!(harness.pop_action(), None);
+    }
+
     #[test]
     fn edit_button() {
         let image_1 = {
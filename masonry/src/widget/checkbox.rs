@@ -44,19 +44,19 @@ impl Checkbox {
 
 // --- MARK: WIDGETMUT ---
 impl Checkbox {
+    /// Set whether the checkbox is checked.
     pub fn set_checked(this: &mut WidgetMut<'_, Self>, checked: bool) {
         this.widget.checked = checked;
         // Checked state impacts appearance and accessibility node
         this.ctx.request_render();
     }
 
-    /// Set the text.
-    ///
-    /// We enforce this to be an `ArcStr` to make the allocation explicit.
-    pub fn set_text(this: &mut WidgetMut<'_, Self>, new_text: ArcStr) {
+    /// Set the text of the label.
+    pub fn set_text(this: &mut WidgetMut<'_, Self>, new_text: impl Into<ArcStr>) {
         Label::set_text(&mut Checkbox::label_mut(this), new_text);
     }
 
+    /// Get mutable access to the label, e.g. to change its style.
     pub fn label_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Label> {
         this.ctx.get_mut(&mut this.widget.label)
     }
@@ -118,8 +118,8 @@ impl Widget for Checkbox {
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
-        let x_padding = theme::WIDGET_CONTROL_COMPONENT_PADDING;
-        let check_size = theme::BASIC_WIDGET_HEIGHT;
+        let x_padding = ctx.density().widget_control_component_padding();
+        let check_size = ctx.density().basic_widget_height();
 
         let label_size = ctx.run_layout(&mut self.label, bc);
         ctx.place_child(&mut self.label, (check_size + x_padding, 0.0).into());
@@ -136,7 +136,7 @@ impl Widget for Checkbox {
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
-        let check_size = theme::BASIC_WIDGET_HEIGHT;
+        let check_size = ctx.density().basic_widget_height();
         let border_width = 1.;
 
         let rect = Size::new(check_size, check_size)
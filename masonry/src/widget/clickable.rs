@@ -0,0 +1,155 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which makes its child clickable.
+
+use accesskit::{Node, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point, PointerEvent,
+    QueryCtx, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+type ClickHandler = Box<dyn FnMut(&mut EventCtx, PointerButton)>;
+
+/// A widget which calls a handler when its child is clicked.
+///
+/// Unlike [`Button`](super::Button), this doesn't draw anything around its child.
+/// The handler can submit an [`Action`](crate::Action), or a [typed action](crate::Action::typed),
+/// with the given [`EventCtx`].
+///
+/// This is usually created with [`WidgetExt::on_click`](super::WidgetExt::on_click).
+pub struct Clickable {
+    child: WidgetPod<Box<dyn Widget>>,
+    on_click: ClickHandler,
+}
+
+// --- MARK: BUILDERS ---
+impl Clickable {
+    /// Create a new widget calling `on_click` when `child` is clicked.
+    ///
+    /// A click is a press and a release of a pointer button over the widget.
+    pub fn new(
+        child: impl Widget,
+        on_click: impl FnMut(&mut EventCtx, PointerButton) + 'static,
+    ) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed(), on_click)
+    }
+
+    /// Create a new widget calling `on_click` when the child `child` is clicked.
+    pub fn new_pod(
+        child: WidgetPod<Box<dyn Widget>>,
+        on_click: impl FnMut(&mut EventCtx, PointerButton) + 'static,
+    ) -> Self {
+        Self {
+            child,
+            on_click: Box::new(on_click),
+        }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Clickable {
+    /// Replace the handler called when the child is clicked.
+    pub fn set_on_click(
+        this: &mut WidgetMut<'_, Self>,
+        on_click: impl FnMut(&mut EventCtx, PointerButton) + 'static,
+    ) {
+        this.widget.on_click = Box::new(on_click);
+    }
+
+    /// Get mutable access to the child.
+    pub fn child_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.child)
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Clickable {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(_, _) => {
+                if !ctx.is_disabled() {
+                    ctx.capture_pointer();
+                }
+            }
+            PointerEvent::PointerUp(button, _) => {
+                if ctx.has_pointer_capture() && ctx.is_hovered() && !ctx.is_disabled() {
+                    (self.on_click)(ctx, *button);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if ctx.target() == ctx.widget_id() && event.action == accesskit::Action::Click {
+            (self.on_click)(ctx, PointerButton::Primary);
+        }
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.child);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = ctx.run_layout(&mut self.child, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+
+        let insets = ctx.compute_insets_from_child(&self.child, size);
+        ctx.set_paint_insets(insets);
+        let baseline_offset = ctx.child_baseline_offset(&self.child);
+        ctx.set_baseline_offset(baseline_offset);
+        size
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        node.add_action(accesskit::Action::Click);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Clickable", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Label, WidgetExt};
+    use crate::Action;
+
+    #[test]
+    fn click_wrapped_label() {
+        let widget = Label::new("Click me")
+            .padded(4.)
+            .on_click(|ctx, button| ctx.submit_action(Action::ButtonPressed(button)));
+        let mut harness = TestHarness::create(widget);
+        let clickable_id = harness.root_widget().id();
+
+        assert_eq!(harness.pop_action(), None);
+        harness.mouse_click_on(clickable_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed(PointerButton::Primary), clickable_id))
+        );
+    }
+}
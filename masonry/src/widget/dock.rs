@@ -0,0 +1,981 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Panels docked to the edges of a central widget, tabbed together, or floating over it.
+
+use accesskit::{Node, Role};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{Line, Vec2};
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text::ArcStr;
+use crate::widget::{Label, WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, ActionSource, BoxConstraints, CursorIcon, EventCtx, LayoutCtx,
+    PaintCtx, Point, PointerEvent, QueryCtx, Rect, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The height of the tab strips, and of the title bars of floating panels.
+const TAB_HEIGHT: f64 = 28.0;
+/// The space between the edges of a tab and its title.
+const TAB_PADDING: f64 = 10.0;
+/// The thickness of the splitters between the docked areas and the rest of the dock.
+const SPLITTER_SIZE: f64 = 6.0;
+/// The smallest size of a docked area, and of the center widget, along the splitter's axis.
+const MIN_AREA_SIZE: f64 = 40.0;
+/// The width of the drop zones along the edges of the dock, when dragging a panel.
+const DROP_ZONE_SIZE: f64 = 48.0;
+/// How far the pointer must move before a pressed tab is dragged.
+const DRAG_THRESHOLD: f64 = 6.0;
+/// The size of a docked area created for a panel.
+const DEFAULT_AREA_SIZE: f64 = 240.0;
+/// The size of a panel when it's floated from a docked area.
+const DEFAULT_FLOATING_SIZE: Size = Size::new(320.0, 240.0);
+
+// --- MARK: LAYOUT MODEL ---
+
+/// An edge of a [`Dock`], which panels can be docked to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DockEdge {
+    /// The left edge.
+    Left,
+    /// The right edge.
+    Right,
+    /// The top edge.
+    Top,
+    /// The bottom edge.
+    Bottom,
+}
+
+/// A group of panels docked to an edge of a [`Dock`], shown as tabs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DockArea {
+    /// The edge the panels are docked to.
+    pub edge: DockEdge,
+    /// The keys of the panels, in the order of their tabs.
+    pub panels: Vec<String>,
+    /// The index in `panels` of the panel which is shown.
+    pub active: usize,
+    /// The width of the area for the left and right edges, or its height for the top
+    /// and bottom edges.
+    pub size: f64,
+}
+
+/// A panel floating over a [`Dock`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FloatingPanel {
+    /// The key of the panel.
+    pub panel: String,
+    /// The horizontal position of the panel's title bar, in the coordinates of the dock.
+    pub x: f64,
+    /// The vertical position of the panel's title bar, in the coordinates of the dock.
+    pub y: f64,
+    /// The width of the panel.
+    pub width: f64,
+    /// The height of the panel, including its title bar.
+    pub height: f64,
+}
+
+impl FloatingPanel {
+    /// The rectangle covered by the panel, including its title bar.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.x + self.width, self.y + self.height)
+    }
+}
+
+/// The arrangement of the panels of a [`Dock`].
+///
+/// Panels are identified by their key, which should stay the same between runs of the app,
+/// so that the layout can be saved, e.g. with `serde_json`, and restored.
+/// Panels which aren't in the layout are hidden.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DockLayout {
+    /// The docked areas. Each takes its space from what the previous ones left.
+    pub areas: Vec<DockArea>,
+    /// The floating panels, from back to front.
+    pub floating: Vec<FloatingPanel>,
+}
+
+impl DockLayout {
+    /// Create a layout without any panels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method to dock the panel `key` to `edge`, after the other panels there.
+    ///
+    /// The first panel docked to an edge is the one shown.
+    pub fn with_panel(mut self, edge: DockEdge, key: impl Into<String>) -> Self {
+        let key = key.into();
+        self.remove(&key);
+        match self.areas.iter_mut().find(|area| area.edge == edge) {
+            Some(area) => area.panels.push(key),
+            None => self.areas.push(DockArea {
+                edge,
+                panels: vec![key],
+                active: 0,
+                size: DEFAULT_AREA_SIZE,
+            }),
+        }
+        self
+    }
+
+    /// Whether the panel `key` is docked or floating.
+    pub fn contains(&self, key: &str) -> bool {
+        self.edge_of(key).is_some() || self.floating.iter().any(|panel| panel.panel == key)
+    }
+
+    /// The edge the panel `key` is docked to, if it's docked.
+    pub fn edge_of(&self, key: &str) -> Option<DockEdge> {
+        self.areas
+            .iter()
+            .find(|area| area.panels.iter().any(|panel| panel == key))
+            .map(|area| area.edge)
+    }
+
+    /// Whether the panel `key` is visible, rather than in a tab behind another panel.
+    pub fn is_visible(&self, key: &str) -> bool {
+        self.areas.iter().any(|area| {
+            area.panels
+                .get(area.active)
+                .is_some_and(|panel| panel == key)
+        }) || self.floating.iter().any(|panel| panel.panel == key)
+    }
+
+    /// Dock the panel `key` to `edge`, after the other panels there, and show it.
+    pub fn dock(&mut self, key: &str, edge: DockEdge) {
+        self.remove(key);
+        match self.areas.iter_mut().find(|area| area.edge == edge) {
+            Some(area) => {
+                area.panels.push(key.to_string());
+                area.active = area.panels.len() - 1;
+            }
+            None => self.areas.push(DockArea {
+                edge,
+                panels: vec![key.to_string()],
+                active: 0,
+                size: DEFAULT_AREA_SIZE,
+            }),
+        }
+    }
+
+    /// Float the panel `key` over the dock, covering `rect`, in front of the other panels.
+    pub fn float(&mut self, key: &str, rect: Rect) {
+        self.remove(key);
+        self.floating.push(FloatingPanel {
+            panel: key.to_string(),
+            x: rect.x0,
+            y: rect.y0,
+            width: rect.width(),
+            height: rect.height(),
+        });
+    }
+
+    /// Show the panel `key`: bring its tab to the front if it's docked, or bring it in front
+    /// of the other panels if it's floating.
+    ///
+    /// Returns whether the layout changed.
+    pub fn activate(&mut self, key: &str) -> bool {
+        for area in &mut self.areas {
+            if let Some(idx) = area.panels.iter().position(|panel| panel == key) {
+                let changed = area.active != idx;
+                area.active = idx;
+                return changed;
+            }
+        }
+        match self.floating.iter().position(|panel| panel.panel == key) {
+            Some(idx) if idx + 1 != self.floating.len() => {
+                let panel = self.floating.remove(idx);
+                self.floating.push(panel);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Remove the panel `key` from the layout, which hides it.
+    ///
+    /// Areas left without panels are removed. Returns whether the panel was in the layout.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let docked = self.areas.iter_mut().any(|area| {
+            let Some(idx) = area.panels.iter().position(|panel| panel == key) else {
+                return false;
+            };
+            area.panels.remove(idx);
+            if area.active > idx || area.active == area.panels.len() {
+                area.active = area.active.saturating_sub(1);
+            }
+            true
+        });
+        if docked {
+            self.areas.retain(|area| !area.panels.is_empty());
+            return true;
+        }
+        let floating_len = self.floating.len();
+        self.floating.retain(|panel| panel.panel != key);
+        self.floating.len() != floating_len
+    }
+}
+
+/// The layout of a [`Dock`] was changed by the user.
+///
+/// This is the [`ActionSource::Action`] of [`Dock`]. The dock already shows the new layout;
+/// apps usually store it, so that it can be restored the next time they run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DockLayoutChanged(pub DockLayout);
+
+// --- MARK: WIDGET ---
+
+/// A panel of a [`Dock`].
+struct Panel {
+    key: String,
+    title: WidgetPod<Label>,
+    content: WidgetPod<Box<dyn Widget>>,
+    /// The frame painted behind the panel while it's floating.
+    backdrop: WidgetPod<FloatingBackdrop>,
+    /// The tab or title bar of the panel, from the last layout.
+    handle_rect: Rect,
+}
+
+/// The rectangles of a docked area, from the last layout.
+#[derive(Clone, Copy)]
+struct AreaGeometry {
+    tabs: Rect,
+    splitter: Rect,
+}
+
+enum Drag {
+    /// The splitter of the area at this index is being moved.
+    Splitter {
+        area: usize,
+        start: Point,
+        start_size: f64,
+    },
+    /// A panel is being dragged by its tab or title bar.
+    Panel {
+        key: String,
+        start: Point,
+        /// The position of the pointer relative to the handle of the panel.
+        grab: Vec2,
+        /// Whether the pointer moved past [`DRAG_THRESHOLD`].
+        moved: bool,
+        pointer: Point,
+    },
+}
+
+/// Panels docked to the edges of a central widget, like the sidebars of an IDE.
+///
+/// The panels docked to the same edge are shown as tabs. Dragging a tab to another edge of
+/// the dock docks the panel there, and dropping it anywhere else floats the panel over the
+/// dock, where it can be moved by its title bar, or dragged back to an edge.
+/// The areas are resized by dragging their splitters.
+///
+/// The arrangement of the panels is a [`DockLayout`], which is serializable. Whenever the
+/// user changes it, the dock submits a [`DockLayoutChanged`] [typed action](crate::Action::typed).
+///
+/// Floating panels are shown over the dock, inside its window. Floating them into windows of
+/// their own isn't supported: the [popup](crate::popup) windows, which are the only other windows
+/// Masonry can show, don't get keyboard focus, are closed when the main window is clicked, and
+/// take their content out of the widget tree of the window.
+// TODO - Float panels into their own windows, once Masonry supports secondary windows.
+pub struct Dock {
+    center: WidgetPod<Box<dyn Widget>>,
+    panels: Vec<Panel>,
+    layout: DockLayout,
+    areas: Vec<AreaGeometry>,
+    drag: Option<Drag>,
+}
+
+// --- MARK: BUILDERS ---
+impl Dock {
+    /// Create a new dock around `center`, without panels.
+    pub fn new(center: impl Widget) -> Self {
+        Self::new_pod(WidgetPod::new(center).boxed())
+    }
+
+    /// Create a new dock around the widget `center`, without panels.
+    pub fn new_pod(center: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            center,
+            panels: Vec::new(),
+            layout: DockLayout::default(),
+            areas: Vec::new(),
+            drag: None,
+        }
+    }
+
+    /// Builder-style method to add a panel, identified by `key` in the layout.
+    pub fn with_panel(
+        self,
+        key: impl Into<String>,
+        title: impl Into<ArcStr>,
+        content: impl Widget,
+    ) -> Self {
+        self.with_panel_pod(key, title, WidgetPod::new(content).boxed())
+    }
+
+    /// Builder-style method to add a panel, identified by `key` in the layout.
+    pub fn with_panel_pod(
+        mut self,
+        key: impl Into<String>,
+        title: impl Into<ArcStr>,
+        content: WidgetPod<Box<dyn Widget>>,
+    ) -> Self {
+        self.panels
+            .push(Panel::new(key.into(), title.into(), content));
+        self
+    }
+
+    /// Builder-style method to set the arrangement of the panels.
+    pub fn with_layout(mut self, layout: DockLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// The arrangement of the panels.
+    pub fn layout(&self) -> &DockLayout {
+        &self.layout
+    }
+
+    /// The number of panels.
+    pub fn len(&self) -> usize {
+        self.panels.len()
+    }
+
+    /// Whether the dock has no panels.
+    pub fn is_empty(&self) -> bool {
+        self.panels.is_empty()
+    }
+}
+
+impl Panel {
+    fn new(key: String, title: ArcStr, content: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            key,
+            title: WidgetPod::new(Label::new(title)),
+            content,
+            backdrop: WidgetPod::new(FloatingBackdrop),
+            handle_rect: Rect::ZERO,
+        }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Dock {
+    /// Set the arrangement of the panels.
+    ///
+    /// This cancels the current drag, if any.
+    pub fn set_layout(this: &mut WidgetMut<'_, Self>, layout: DockLayout) {
+        if this.widget.layout == layout {
+            return;
+        }
+        this.widget.layout = layout;
+        this.widget.drag = None;
+        this.ctx.children_changed();
+    }
+
+    /// Add a panel, identified by `key` in the layout.
+    pub fn add_panel(
+        this: &mut WidgetMut<'_, Self>,
+        key: impl Into<String>,
+        title: impl Into<ArcStr>,
+        content: impl Widget,
+    ) {
+        let idx = this.widget.panels.len();
+        Self::insert_panel_pod(this, idx, key, title, WidgetPod::new(content).boxed());
+    }
+
+    /// Insert a panel at `idx`, identified by `key` in the layout.
+    pub fn insert_panel_pod(
+        this: &mut WidgetMut<'_, Self>,
+        idx: usize,
+        key: impl Into<String>,
+        title: impl Into<ArcStr>,
+        content: WidgetPod<Box<dyn Widget>>,
+    ) {
+        let panel = Panel::new(key.into(), title.into(), content);
+        this.widget.panels.insert(idx, panel);
+        this.ctx.children_changed();
+    }
+
+    /// Remove the panel at `idx`.
+    ///
+    /// The panel stays in the layout, so that it's shown at the same place if it's added again.
+    pub fn remove_panel(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        let panel = this.widget.panels.remove(idx);
+        this.ctx.remove_child(panel.title);
+        this.ctx.remove_child(panel.content);
+        this.ctx.remove_child(panel.backdrop);
+        this.widget.drag = None;
+    }
+
+    /// Set the key identifying the panel at `idx` in the layout.
+    pub fn set_panel_key(this: &mut WidgetMut<'_, Self>, idx: usize, key: impl Into<String>) {
+        this.widget.panels[idx].key = key.into();
+        this.ctx.children_changed();
+    }
+
+    /// Set the title of the panel at `idx`.
+    pub fn set_panel_title(this: &mut WidgetMut<'_, Self>, idx: usize, title: impl Into<ArcStr>) {
+        Label::set_text(
+            &mut this.ctx.get_mut(&mut this.widget.panels[idx].title),
+            title,
+        );
+    }
+
+    /// Get mutable access to the content of the panel at `idx`.
+    pub fn panel_mut<'t>(
+        this: &'t mut WidgetMut<'_, Self>,
+        idx: usize,
+    ) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.panels[idx].content)
+    }
+
+    /// Get mutable access to the center widget.
+    pub fn center_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.center)
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl Dock {
+    fn panel_index(&self, key: &str) -> Option<usize> {
+        self.panels.iter().position(|panel| panel.key == key)
+    }
+
+    /// The indices of the panels, with the docked panels first, then the floating panels
+    /// from back to front, then the hidden panels. This is the order they're painted in.
+    fn paint_order(&self) -> Vec<usize> {
+        let docked = self.layout.areas.iter().flat_map(|area| &area.panels);
+        let floating = self.layout.floating.iter().map(|panel| &panel.panel);
+        let mut order: Vec<usize> = docked
+            .chain(floating)
+            .filter_map(|key| self.panel_index(key))
+            .collect();
+        order.dedup();
+        let hidden = (0..self.panels.len()).filter(|idx| !order.contains(idx));
+        order.extend(hidden.collect::<Vec<_>>());
+        order
+    }
+
+    /// The edge whose drop zone contains `pos`, if any.
+    fn drop_edge(size: Size, pos: Point) -> Option<DockEdge> {
+        if pos.x < DROP_ZONE_SIZE {
+            Some(DockEdge::Left)
+        } else if pos.x > size.width - DROP_ZONE_SIZE {
+            Some(DockEdge::Right)
+        } else if pos.y < DROP_ZONE_SIZE {
+            Some(DockEdge::Top)
+        } else if pos.y > size.height - DROP_ZONE_SIZE {
+            Some(DockEdge::Bottom)
+        } else {
+            None
+        }
+    }
+
+    /// The area which is highlighted while a panel is dragged to `pos`.
+    fn drop_indicator(size: Size, pos: Point) -> Option<Rect> {
+        let extent = DEFAULT_AREA_SIZE
+            .min(size.width / 3.0)
+            .min(size.height / 3.0);
+        Some(match Self::drop_edge(size, pos)? {
+            DockEdge::Left => Rect::new(0.0, 0.0, extent, size.height),
+            DockEdge::Right => Rect::new(size.width - extent, 0.0, size.width, size.height),
+            DockEdge::Top => Rect::new(0.0, 0.0, size.width, extent),
+            DockEdge::Bottom => Rect::new(0.0, size.height - extent, size.width, size.height),
+        })
+    }
+
+    fn layout_changed(&mut self, ctx: &mut EventCtx) {
+        ctx.children_changed();
+        ctx.submit_typed_action(DockLayoutChanged(self.layout.clone()));
+    }
+
+    fn on_pointer_down(&mut self, ctx: &mut EventCtx, pos: Point) {
+        // Floating panels are in front of everything else, so they're hit first.
+        let floating = self
+            .layout
+            .floating
+            .iter()
+            .rev()
+            .find(|panel| panel.rect().contains(pos));
+        let hit = match floating {
+            Some(floating) => {
+                let key = floating.panel.clone();
+                if self.layout.activate(&key) {
+                    self.layout_changed(ctx);
+                }
+                self.panel_index(&key)
+                    .filter(|&idx| self.panels[idx].handle_rect.contains(pos))
+            }
+            None => {
+                let splitter = self
+                    .areas
+                    .iter()
+                    .position(|area| area.splitter.contains(pos));
+                if let Some(area) = splitter {
+                    self.drag = Some(Drag::Splitter {
+                        area,
+                        start: pos,
+                        start_size: self.layout.areas[area].size,
+                    });
+                    ctx.capture_pointer();
+                    return;
+                }
+                self.panels.iter().position(|panel| {
+                    self.layout.edge_of(&panel.key).is_some() && panel.handle_rect.contains(pos)
+                })
+            }
+        };
+        let Some(idx) = hit else {
+            return;
+        };
+        let panel = &self.panels[idx];
+        let key = panel.key.clone();
+        self.drag = Some(Drag::Panel {
+            start: pos,
+            grab: pos - panel.handle_rect.origin(),
+            moved: false,
+            pointer: pos,
+            key: key.clone(),
+        });
+        ctx.capture_pointer();
+        if self.layout.activate(&key) {
+            self.layout_changed(ctx);
+        }
+    }
+
+    fn on_pointer_move(&mut self, ctx: &mut EventCtx, pos: Point) {
+        match &mut self.drag {
+            Some(Drag::Splitter {
+                area,
+                start,
+                start_size,
+            }) => {
+                let delta = pos - *start;
+                let area = &mut self.layout.areas[*area];
+                area.size = *start_size
+                    + match area.edge {
+                        DockEdge::Left => delta.x,
+                        DockEdge::Right => -delta.x,
+                        DockEdge::Top => delta.y,
+                        DockEdge::Bottom => -delta.y,
+                    };
+                area.size = area.size.max(MIN_AREA_SIZE);
+                ctx.request_layout();
+            }
+            Some(Drag::Panel {
+                key,
+                start,
+                grab,
+                moved,
+                pointer,
+            }) => {
+                *moved |= (pos - *start).hypot() > DRAG_THRESHOLD;
+                *pointer = pos;
+                if !*moved {
+                    return;
+                }
+                if let Some(panel) = self.layout.floating.iter_mut().find(|p| p.panel == *key) {
+                    panel.x = pos.x - grab.x;
+                    panel.y = pos.y - grab.y;
+                    ctx.request_layout();
+                } else {
+                    ctx.request_paint_only();
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn on_pointer_up(&mut self, ctx: &mut EventCtx, pos: Point) {
+        match self.drag.take() {
+            Some(Drag::Splitter { .. }) => {
+                self.layout_changed(ctx);
+            }
+            Some(Drag::Panel {
+                key, grab, moved, ..
+            }) if moved => {
+                let size = ctx.size();
+                if let Some(edge) = Self::drop_edge(size, pos) {
+                    self.layout.dock(&key, edge);
+                } else if self.layout.edge_of(&key).is_some() {
+                    let rect = Rect::from_origin_size(pos - grab, DEFAULT_FLOATING_SIZE);
+                    self.layout.float(&key, rect);
+                }
+                self.layout_changed(ctx);
+            }
+            _ => (),
+        }
+        ctx.request_paint_only();
+    }
+
+    /// Lay out the title of `panel` in its tab or title bar, starting at `origin`.
+    fn layout_title(ctx: &mut LayoutCtx, panel: &mut Panel, origin: Point) -> f64 {
+        ctx.set_stashed(&mut panel.title, false);
+        let title_size = ctx.run_layout(&mut panel.title, &BoxConstraints::UNBOUNDED);
+        let title_y = origin.y + (TAB_HEIGHT - title_size.height) / 2.0;
+        ctx.place_child(
+            &mut panel.title,
+            Point::new(origin.x + TAB_PADDING, title_y),
+        );
+        title_size.width + 2.0 * TAB_PADDING
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Dock {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                self.on_pointer_down(ctx, pos);
+            }
+            PointerEvent::PointerMove(state) if ctx.has_pointer_capture() => {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                self.on_pointer_move(ctx, pos);
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) if ctx.has_pointer_capture() => {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                self.on_pointer_up(ctx, pos);
+            }
+            PointerEvent::PointerLeave(_) if self.drag.is_some() => {
+                // The pointer capture is lost, so the drag is cancelled.
+                self.drag = None;
+                ctx.request_paint_only();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.center);
+        for panel in &mut self.panels {
+            ctx.register_child(&mut panel.backdrop);
+            ctx.register_child(&mut panel.content);
+            ctx.register_child(&mut panel.title);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = bc.max();
+        let mut rest = size.to_rect();
+        let mut laid_out = vec![false; self.panels.len()];
+        self.areas.clear();
+
+        for area in &mut self.layout.areas {
+            let available = match area.edge {
+                DockEdge::Left | DockEdge::Right => rest.width(),
+                DockEdge::Top | DockEdge::Bottom => rest.height(),
+            };
+            let extent = area
+                .size
+                .min(available - SPLITTER_SIZE - MIN_AREA_SIZE)
+                .max(0.0);
+            let (area_rect, splitter) = match area.edge {
+                DockEdge::Left => {
+                    let area_rect = rest.with_size((extent, rest.height()));
+                    rest.x0 += extent;
+                    let splitter = rest.with_size((SPLITTER_SIZE, rest.height()));
+                    rest.x0 += SPLITTER_SIZE;
+                    (area_rect, splitter)
+                }
+                DockEdge::Right => {
+                    rest.x1 -= extent;
+                    let area_rect = Rect::new(rest.x1, rest.y0, rest.x1 + extent, rest.y1);
+                    rest.x1 -= SPLITTER_SIZE;
+                    let splitter = Rect::new(rest.x1, rest.y0, rest.x1 + SPLITTER_SIZE, rest.y1);
+                    (area_rect, splitter)
+                }
+                DockEdge::Top => {
+                    let area_rect = rest.with_size((rest.width(), extent));
+                    rest.y0 += extent;
+                    let splitter = rest.with_size((rest.width(), SPLITTER_SIZE));
+                    rest.y0 += SPLITTER_SIZE;
+                    (area_rect, splitter)
+                }
+                DockEdge::Bottom => {
+                    rest.y1 -= extent;
+                    let area_rect = Rect::new(rest.x0, rest.y1, rest.x1, rest.y1 + extent);
+                    rest.y1 -= SPLITTER_SIZE;
+                    let splitter = Rect::new(rest.x0, rest.y1, rest.x1, rest.y1 + SPLITTER_SIZE);
+                    (area_rect, splitter)
+                }
+            };
+            let tabs = area_rect.with_size((area_rect.width(), TAB_HEIGHT.min(extent)));
+            let content_rect = Rect::new(area_rect.x0, tabs.y1, area_rect.x1, area_rect.y1);
+            self.areas.push(AreaGeometry { tabs, splitter });
+
+            let mut tab_x = tabs.x0;
+            for (tab, key) in area.panels.iter().enumerate() {
+                let Some(idx) = self.panels.iter().position(|panel| panel.key == *key) else {
+                    continue;
+                };
+                if std::mem::replace(&mut laid_out[idx], true) {
+                    continue;
+                }
+                let panel = &mut self.panels[idx];
+                let tab_width = Self::layout_title(ctx, panel, Point::new(tab_x, tabs.y0));
+                panel.handle_rect = Rect::new(tab_x, tabs.y0, tab_x + tab_width, tabs.y1);
+                tab_x += tab_width;
+
+                let shown = tab == area.active;
+                ctx.set_stashed(&mut panel.content, !shown);
+                if shown {
+                    let content_bc = BoxConstraints::tight(content_rect.size());
+                    ctx.run_layout(&mut panel.content, &content_bc);
+                    ctx.place_child(&mut panel.content, content_rect.origin());
+                } else {
+                    ctx.skip_layout(&mut panel.content);
+                }
+                ctx.set_stashed(&mut panel.backdrop, true);
+                ctx.skip_layout(&mut panel.backdrop);
+            }
+        }
+
+        ctx.run_layout(&mut self.center, &BoxConstraints::tight(rest.size()));
+        ctx.place_child(&mut self.center, rest.origin());
+
+        for floating in &self.layout.floating {
+            let Some(idx) = self.panel_index(&floating.panel) else {
+                continue;
+            };
+            if std::mem::replace(&mut laid_out[idx], true) {
+                continue;
+            }
+            let rect = floating.rect();
+            let panel = &mut self.panels[idx];
+            ctx.set_stashed(&mut panel.backdrop, false);
+            ctx.run_layout(&mut panel.backdrop, &BoxConstraints::tight(rect.size()));
+            ctx.place_child(&mut panel.backdrop, rect.origin());
+
+            Self::layout_title(ctx, panel, rect.origin());
+            panel.handle_rect = rect.with_size((rect.width(), TAB_HEIGHT));
+
+            let content_size = Size::new(rect.width(), (rect.height() - TAB_HEIGHT).max(0.0));
+            ctx.set_stashed(&mut panel.content, false);
+            ctx.run_layout(&mut panel.content, &BoxConstraints::tight(content_size));
+            ctx.place_child(
+                &mut panel.content,
+                Point::new(rect.x0, rect.y0 + TAB_HEIGHT),
+            );
+        }
+
+        // The panels which aren't in the layout are hidden.
+        for (panel, laid_out) in self.panels.iter_mut().zip(laid_out) {
+            if laid_out {
+                continue;
+            }
+            ctx.set_stashed(&mut panel.title, true);
+            ctx.skip_layout(&mut panel.title);
+            ctx.set_stashed(&mut panel.content, true);
+            ctx.skip_layout(&mut panel.content);
+            ctx.set_stashed(&mut panel.backdrop, true);
+            ctx.skip_layout(&mut panel.backdrop);
+            panel.handle_rect = Rect::ZERO;
+        }
+
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        for (area, geometry) in self.layout.areas.iter().zip(&self.areas) {
+            fill_color(scene, &geometry.tabs, theme::BACKGROUND_DARK);
+            if let Some(panel) = area
+                .panels
+                .get(area.active)
+                .and_then(|key| self.panel_index(key))
+            {
+                fill_color(
+                    scene,
+                    &self.panels[panel].handle_rect,
+                    theme::BACKGROUND_LIGHT,
+                );
+            }
+            let splitter = geometry.splitter;
+            let (from, to) = match area.edge {
+                DockEdge::Left | DockEdge::Right => (
+                    Point::new(splitter.center().x, splitter.y0),
+                    Point::new(splitter.center().x, splitter.y1),
+                ),
+                DockEdge::Top | DockEdge::Bottom => (
+                    Point::new(splitter.x0, splitter.center().y),
+                    Point::new(splitter.x1, splitter.center().y),
+                ),
+            };
+            stroke(scene, &Line::new(from, to), theme::BORDER_DARK, 1.0);
+        }
+
+        if let Some(Drag::Panel {
+            moved: true,
+            pointer,
+            ..
+        }) = &self.drag
+        {
+            if let Some(indicator) = Self::drop_indicator(ctx.size(), *pointer) {
+                fill_color(scene, &indicator, theme::PRIMARY_DARK.multiply_alpha(0.3));
+            }
+        }
+    }
+
+    fn get_cursor(&self, ctx: &QueryCtx, pos: Point) -> CursorIcon {
+        let local_pos = pos - ctx.window_origin().to_vec2();
+        let splitter = match &self.drag {
+            Some(Drag::Splitter { area, .. }) => Some(*area),
+            Some(Drag::Panel { moved: true, .. }) => return CursorIcon::Grabbing,
+            _ => self
+                .areas
+                .iter()
+                .position(|area| area.splitter.contains(local_pos)),
+        };
+        match splitter.map(|area| self.layout.areas[area].edge) {
+            Some(DockEdge::Left | DockEdge::Right) => CursorIcon::EwResize,
+            Some(DockEdge::Top | DockEdge::Bottom) => CursorIcon::NsResize,
+            None => CursorIcon::Default,
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut ids = SmallVec::new();
+        ids.push(self.center.id());
+        for idx in self.paint_order() {
+            let panel = &self.panels[idx];
+            ids.extend([panel.backdrop.id(), panel.content.id(), panel.title.id()]);
+        }
+        ids
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Dock", id = ctx.widget_id().trace())
+    }
+}
+
+impl ActionSource for Dock {
+    type Action = DockLayoutChanged;
+}
+
+// --- MARK: FLOATING BACKDROP ---
+
+/// The frame and title bar painted behind a floating panel, over the rest of the dock.
+struct FloatingBackdrop;
+
+impl Widget for FloatingBackdrop {
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let rect = ctx.size().to_rect();
+        fill_color(scene, &rect, theme::BACKGROUND_DARK);
+        fill_color(
+            scene,
+            &rect.with_size((rect.width(), TAB_HEIGHT)),
+            theme::BACKGROUND_LIGHT,
+        );
+        stroke(scene, &rect.inset(-0.5), theme::BORDER_LIGHT, 1.0);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::SizedBox;
+
+    #[test]
+    fn layout_model() {
+        let mut layout = DockLayout::new()
+            .with_panel(DockEdge::Left, "files")
+            .with_panel(DockEdge::Left, "search")
+            .with_panel(DockEdge::Bottom, "terminal");
+        assert_eq!(layout.edge_of("search"), Some(DockEdge::Left));
+        assert!(layout.is_visible("files"));
+        assert!(!layout.is_visible("search"));
+
+        assert!(layout.activate("search"));
+        assert!(!layout.activate("search"));
+        assert!(layout.is_visible("search"));
+
+        // Removing the active tab shows the previous one
+        layout.dock("search", DockEdge::Right);
+        assert!(layout.is_visible("files"));
+        assert_eq!(layout.areas.len(), 3);
+
+        layout.float("terminal", Rect::new(10., 20., 110., 220.));
+        assert_eq!(layout.areas.len(), 2);
+        assert_eq!(layout.floating[0].rect(), Rect::new(10., 20., 110., 220.));
+        assert!(layout.remove("terminal"));
+        assert!(!layout.contains("terminal"));
+
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(serde_json::from_str::<DockLayout>(&json).unwrap(), layout);
+    }
+
+    #[test]
+    fn drag_tab_to_other_edge() {
+        let [files_id, center_id] = widget_ids();
+        let widget = Dock::new_pod(WidgetPod::new_with_id(SizedBox::empty(), center_id).boxed())
+            .with_panel_pod(
+                "files",
+                "Files",
+                WidgetPod::new_with_id(SizedBox::empty(), files_id).boxed(),
+            )
+            .with_layout(DockLayout::new().with_panel(DockEdge::Left, "files"));
+        let mut harness = TestHarness::create_with_size(widget, Size::new(800., 600.));
+        let files_rect = harness.get_widget(files_id).ctx().layout_rect();
+        assert_eq!(
+            files_rect,
+            Rect::new(0., TAB_HEIGHT, DEFAULT_AREA_SIZE, 600.)
+        );
+        assert_eq!(
+            harness.get_widget(center_id).ctx().layout_rect().x0,
+            DEFAULT_AREA_SIZE + SPLITTER_SIZE
+        );
+
+        // Drag the tab to the right edge
+        harness.drag_from_to((20., TAB_HEIGHT / 2.), (790., 300.));
+        let expected = DockLayout::new().with_panel(DockEdge::Right, "files");
+        let (action, _) = harness.pop_action().unwrap();
+        assert_eq!(
+            action.downcast::<DockLayoutChanged>().ok(),
+            Some(DockLayoutChanged(expected))
+        );
+        let files_rect = harness.get_widget(files_id).ctx().layout_rect();
+        assert_eq!(files_rect.x1, 800.);
+        assert_eq!(harness.get_widget(center_id).ctx().layout_rect().x0, 0.);
+
+        // Drag the splitter to make the area wider
+        let splitter_x = 800. - DEFAULT_AREA_SIZE - SPLITTER_SIZE / 2.;
+        harness.drag_from_to((splitter_x, 300.), (splitter_x - 60., 300.));
+        assert!(harness.pop_action().is_some());
+        let files_rect = harness.get_widget(files_id).ctx().layout_rect();
+        assert_eq!(files_rect.width(), DEFAULT_AREA_SIZE + 60.);
+    }
+}
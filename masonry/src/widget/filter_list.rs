@@ -0,0 +1,505 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A search box over a list of items, filtered as the user types.
+
+use std::ops::Range;
+
+use accesskit::{Node, Role};
+use parley::layout::Alignment;
+use parley::Layout;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::kurbo::{Affine, Rect, RoundedRect, Stroke, Vec2};
+use vello::peniko::{BlendMode, Fill};
+use vello::Scene;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::event::PointerButton;
+use crate::text::{
+    default_style_properties, range_rects, render_text, ArcStr, BrushIndex, FindPattern,
+    StyleProperty, TextLayoutJob,
+};
+use crate::widget::{Padding, TextArea, WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, ActionSource, BoxConstraints, EventCtx, LayoutCtx, PaintCtx,
+    Point, PointerEvent, QueryCtx, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The height of a row of the result list.
+const ROW_HEIGHT: f64 = 24.0;
+/// The space between the edges of the rows and their text.
+const ROW_PADDING: f64 = 8.0;
+/// The space between the search box and the result list.
+const LIST_SPACING: f64 = 4.0;
+/// The padding of the search box, around its text.
+const SEARCH_PADDING: Padding = Padding::all(5.0);
+
+/// The actions of a [`FilterList`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterListAction {
+    /// The item at this index was chosen, by clicking it or by pressing <kbd>Enter</kbd>
+    /// while it's selected.
+    Chosen(usize),
+    /// <kbd>Escape</kbd> was pressed, e.g. to close a command palette.
+    Dismissed,
+}
+
+/// An item matching the query.
+struct FilterMatch {
+    /// The index of the item.
+    item: usize,
+    /// The byte ranges of the matches of the query in the item.
+    ranges: Vec<Range<usize>>,
+}
+
+/// A search box over a list of text items, which only shows the items containing the query.
+///
+/// The matching substrings are highlighted. The arrow keys move the selection through the
+/// results while the search box is focused, and <kbd>Enter</kbd> chooses the selected one,
+/// which submits [`FilterListAction::Chosen`] as a [typed action](crate::Action::typed).
+/// This makes it usable as a command palette.
+///
+/// The list is virtualized: only the visible rows are laid out and painted, so it can hold
+/// many thousands of items. It scrolls by whole rows.
+pub struct FilterList {
+    search: WidgetPod<TextArea<true>>,
+    items: Vec<ArcStr>,
+    /// The query which `results` were filtered with.
+    query: String,
+    results: Vec<FilterMatch>,
+    /// The index in `results` of the selected result.
+    selected: Option<usize>,
+    /// The index in `results` of the first visible row.
+    first_visible: usize,
+    /// The maximum number of rows shown at once.
+    visible_rows: usize,
+    /// The text of the visible rows, in order.
+    row_layouts: Vec<Layout<BrushIndex>>,
+    /// Whether `row_layouts` need to be recreated, because the rows or their text changed.
+    rows_changed: bool,
+    /// The vertical position of the first row.
+    list_y: f64,
+    /// The row which was pressed, while the pointer is captured.
+    pressed_row: Option<usize>,
+}
+
+// --- MARK: BUILDERS ---
+impl FilterList {
+    /// Create a new filter list over `items`, with an empty query.
+    pub fn new(items: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        let search = TextArea::new_editable("")
+            .with_word_wrap(false)
+            .with_padding_if_default(SEARCH_PADDING)
+            .with_ignored_keys([
+                Key::Named(NamedKey::ArrowUp),
+                Key::Named(NamedKey::ArrowDown),
+                Key::Named(NamedKey::Enter),
+                Key::Named(NamedKey::Escape),
+            ]);
+        let mut list = Self {
+            search: WidgetPod::new(search),
+            items: items.into_iter().map(Into::into).collect(),
+            query: String::new(),
+            results: Vec::new(),
+            selected: None,
+            first_visible: 0,
+            visible_rows: 8,
+            row_layouts: Vec::new(),
+            rows_changed: true,
+            list_y: 0.0,
+            pressed_row: None,
+        };
+        list.refilter();
+        list
+    }
+
+    /// Builder-style method to set the maximum number of rows shown at once.
+    ///
+    /// The default is 8.
+    pub fn with_visible_rows(mut self, visible_rows: usize) -> Self {
+        self.visible_rows = visible_rows;
+        self
+    }
+
+    /// The id of the search box.
+    pub fn search_id(&self) -> WidgetId {
+        self.search.id()
+    }
+
+    /// The query which the items are currently filtered with.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// The indices of the items matching the query, in order.
+    pub fn matching_items(&self) -> impl Iterator<Item = usize> + '_ {
+        self.results.iter().map(|result| result.item)
+    }
+
+    /// The index of the selected item, if any item matches the query.
+    pub fn selected_item(&self) -> Option<usize> {
+        self.selected.map(|selected| self.results[selected].item)
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl FilterList {
+    /// Replace the items of the list.
+    pub fn set_items(
+        this: &mut WidgetMut<'_, Self>,
+        items: impl IntoIterator<Item = impl Into<ArcStr>>,
+    ) {
+        this.widget.items = items.into_iter().map(Into::into).collect();
+        this.widget.refilter();
+        this.ctx.request_layout();
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Set the maximum number of rows shown at once.
+    pub fn set_visible_rows(this: &mut WidgetMut<'_, Self>, visible_rows: usize) {
+        this.widget.visible_rows = visible_rows;
+        this.widget.scroll_to_selected();
+        this.widget.rows_changed = true;
+        this.ctx.request_layout();
+    }
+
+    /// Clear the query, showing all the items again.
+    pub fn clear_query(this: &mut WidgetMut<'_, Self>) {
+        TextArea::reset_text(&mut Self::search_mut(this), "");
+    }
+
+    /// Give keyboard focus to the search box.
+    pub fn focus_search(this: &mut WidgetMut<'_, Self>) {
+        let id = this.widget.search.id();
+        this.ctx.set_focus(id);
+    }
+
+    /// Get mutable access to the search box.
+    pub fn search_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, TextArea<true>> {
+        this.ctx.get_mut(&mut this.widget.search)
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl FilterList {
+    /// Find the items matching `query`, and select the first one.
+    fn refilter(&mut self) {
+        let pattern = FindPattern::text(self.query.as_str());
+        self.results = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(item, text)| {
+                let ranges = pattern.find_in(text);
+                (self.query.is_empty() || !ranges.is_empty())
+                    .then_some(FilterMatch { item, ranges })
+            })
+            .collect();
+        self.selected = (!self.results.is_empty()).then_some(0);
+        self.first_visible = 0;
+        self.rows_changed = true;
+    }
+
+    /// The number of rows shown.
+    fn row_count(&self) -> usize {
+        (self.results.len() - self.first_visible).min(self.visible_rows)
+    }
+
+    /// The index in `results` of the row at `pos`, in local coordinates.
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        let row = (pos.y - self.list_y) / ROW_HEIGHT;
+        if row < 0.0 {
+            return None;
+        }
+        let row = row as usize;
+        (row < self.row_count()).then_some(self.first_visible + row)
+    }
+
+    /// Scroll the list by whole rows so that the selected row is visible.
+    fn scroll_to_selected(&mut self) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        if selected < self.first_visible {
+            self.first_visible = selected;
+        } else if selected >= self.first_visible + self.visible_rows {
+            self.first_visible = selected + 1 - self.visible_rows.max(1);
+        }
+    }
+
+    /// Move the selection by `delta` results, stopping at either end of the list.
+    fn move_selection(&mut self, ctx: &mut EventCtx, delta: isize) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let last = self.results.len() - 1;
+        let selected = selected.saturating_add_signed(delta).min(last);
+        self.select(ctx, selected);
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, selected: usize) {
+        if self.selected == Some(selected) {
+            return;
+        }
+        self.selected = Some(selected);
+        let first_visible = self.first_visible;
+        self.scroll_to_selected();
+        if self.first_visible != first_visible {
+            self.rows_changed = true;
+            ctx.request_layout();
+        } else {
+            ctx.request_paint_only();
+        }
+        ctx.request_accessibility_update();
+    }
+
+    fn choose_selected(&mut self, ctx: &mut EventCtx) {
+        if let Some(item) = self.selected_item() {
+            ctx.submit_typed_action(FilterListAction::Chosen(item));
+        }
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for FilterList {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if let Some(row) = self.row_at(local_pos) {
+                    ctx.capture_pointer();
+                    self.pressed_row = Some(row);
+                    self.select(ctx, row);
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if let Some(row) = self.pressed_row.take() {
+                    if ctx.has_pointer_capture() && self.row_at(local_pos) == Some(row) {
+                        self.choose_selected(ctx);
+                    }
+                }
+            }
+            PointerEvent::MouseWheel(delta, _) => {
+                // A line of the wheel scrolls by a row.
+                let delta = delta.to_pixels(ROW_HEIGHT);
+                if delta.y == 0.0 {
+                    return;
+                }
+                let max_first = self.results.len().saturating_sub(self.visible_rows);
+                let rows = (delta.y / ROW_HEIGHT).round() as isize;
+                let rows = if rows == 0 {
+                    -delta.y.signum() as isize
+                } else {
+                    -rows
+                };
+                let first_visible = self
+                    .first_visible
+                    .saturating_add_signed(rows)
+                    .min(max_first);
+                if first_visible != self.first_visible {
+                    self.first_visible = first_visible;
+                    self.rows_changed = true;
+                    ctx.request_layout();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        // The search box lets these keys bubble up to us.
+        let Some((key, _, _)) = event.pressed_key() else {
+            return;
+        };
+        match key {
+            Key::Named(NamedKey::ArrowUp) => self.move_selection(ctx, -1),
+            Key::Named(NamedKey::ArrowDown) => self.move_selection(ctx, 1),
+            Key::Named(NamedKey::Enter) => self.choose_selected(ctx),
+            Key::Named(NamedKey::Escape) => {
+                ctx.submit_typed_action(FilterListAction::Dismissed);
+            }
+            _ => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.search);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // The search box asks for a layout when its text is edited.
+        let query: String = ctx
+            .get_raw_ref(&mut self.search)
+            .widget()
+            .text()
+            .into_iter()
+            .collect();
+        if query != self.query {
+            self.query = query;
+            self.refilter();
+            ctx.request_accessibility_update();
+        }
+
+        let width = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            theme::WIDE_WIDGET_WIDTH * 3.0
+        };
+        let search_bc = BoxConstraints::new(Size::new(width, 0.0), Size::new(width, f64::INFINITY));
+        let search_size = ctx.run_layout(&mut self.search, &search_bc);
+        ctx.place_child(&mut self.search, Point::ORIGIN);
+        self.list_y = search_size.height + LIST_SPACING;
+
+        // Only the visible rows are shaped.
+        if std::mem::take(&mut self.rows_changed) {
+            let mut old_layouts = std::mem::take(&mut self.row_layouts);
+            let (font_ctx, layout_ctx) = ctx.text_contexts();
+            let visible = &self.results[self.first_visible..][..self.row_count()];
+            for result in visible {
+                let mut job = TextLayoutJob::new(
+                    self.items[result.item].clone(),
+                    default_style_properties()
+                        .into_iter()
+                        .chain([StyleProperty::FontSize(theme::TEXT_SIZE_NORMAL)]),
+                    old_layouts.pop().unwrap_or_else(Layout::new),
+                );
+                job.run(font_ctx, layout_ctx);
+                job.layout.break_all_lines(None);
+                job.layout.align(None, Alignment::Start);
+                self.row_layouts.push(job.layout);
+            }
+        }
+
+        let height = self.list_y + self.row_count() as f64 * ROW_HEIGHT;
+        bc.constrain(Size::new(width, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+
+        let search_rect = Rect::new(0.0, 0.0, size.width, self.list_y - LIST_SPACING);
+        scene.stroke(
+            &Stroke::new(theme::TEXTBOX_BORDER_WIDTH),
+            Affine::IDENTITY,
+            theme::BORDER_LIGHT,
+            None,
+            &RoundedRect::from_rect(search_rect, theme::TEXTBOX_BORDER_RADIUS),
+        );
+
+        let list_rect = Rect::new(0.0, self.list_y, size.width, size.height);
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &list_rect);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            theme::BACKGROUND_DARK,
+            None,
+            &list_rect,
+        );
+        for (row, layout) in self.row_layouts.iter().enumerate() {
+            let index = self.first_visible + row;
+            let row_y = self.list_y + row as f64 * ROW_HEIGHT;
+            if self.selected == Some(index) {
+                let row_rect = Rect::new(0.0, row_y, size.width, row_y + ROW_HEIGHT);
+                scene.fill(
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    theme::SELECTED_TEXT_BACKGROUND_COLOR,
+                    None,
+                    &row_rect,
+                );
+            }
+            let text_origin = Vec2::new(
+                ROW_PADDING,
+                row_y + (ROW_HEIGHT - f64::from(layout.height())) / 2.0,
+            );
+            let transform = Affine::translate(text_origin);
+            for range in &self.results[index].ranges {
+                for rect in range_rects(layout, range.clone()) {
+                    scene.fill(
+                        Fill::NonZero,
+                        transform,
+                        theme::FIND_MATCH_COLOR,
+                        None,
+                        &rect,
+                    );
+                }
+            }
+            render_text(scene, transform, layout, &[theme::TEXT_COLOR.into()], true);
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ComboBox
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        if let Some(item) = self.selected_item() {
+            node.set_value(self.items[item].to_string());
+        }
+        node.set_description(format!("{} results", self.results.len()));
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.search.id()]
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("FilterList", id = ctx.widget_id().trace())
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.query.clone())
+    }
+}
+
+impl ActionSource for FilterList {
+    type Action = FilterListAction;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn typing_filters_items() {
+        let widget = FilterList::new(["Open file", "Close window", "Save file as"]);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(300., 300.));
+        let list_id = harness.root_widget().id();
+        let search_id = harness
+            .root_widget()
+            .downcast::<FilterList>()
+            .unwrap()
+            .search_id();
+
+        harness.focus_on(Some(search_id));
+        harness.keyboard_type_chars("FILE");
+        let list = harness.root_widget().downcast::<FilterList>().unwrap();
+        assert_eq!(list.query(), "FILE");
+        assert_eq!(list.matching_items().collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(list.selected_item(), Some(0));
+        let ranges: Vec<_> = list.results.iter().map(|r| r.ranges.clone()).collect();
+        assert_eq!(ranges, [vec![5..9], vec![5..9]]);
+
+        // Click the second result, "Save file as"
+        let list_y = list.list_y;
+        harness.mouse_move((50.0, list_y + ROW_HEIGHT * 1.5));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_button_release(PointerButton::Primary);
+        // The search box also submits an action for each edit
+        let (action, _) = std::iter::from_fn(|| harness.pop_action())
+            .find(|(_, id)| *id == list_id)
+            .unwrap();
+        assert_eq!(
+            action.downcast::<FilterListAction>().ok(),
+            Some(FilterListAction::Chosen(2))
+        );
+    }
+}
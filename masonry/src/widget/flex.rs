@@ -10,6 +10,7 @@ use vello::kurbo::common::FloatExt;
 use vello::kurbo::{Affine, Line, Stroke, Vec2};
 use vello::Scene;
 
+use crate::theme::Density;
 use crate::widget::WidgetMut;
 use crate::{
     AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point, PointerEvent,
@@ -113,6 +114,8 @@ enum Child {
         flex: f64,
     },
     FixedSpacer(f64, f64),
+    /// A spacer whose length is the default spacing for the density of the window.
+    DefaultSpacer(f64),
     FlexedSpacer(f64, f64),
 }
 
@@ -191,7 +194,8 @@ impl Flex {
     /// Builder-style method to use the default gap value.
     ///
     /// This is [`WIDGET_PADDING_VERTICAL`] for a flex column and
-    /// [`WIDGET_PADDING_HORIZONTAL`] for flex row.
+    /// [`WIDGET_PADDING_HORIZONTAL`] for flex row, scaled with the
+    /// [`Density`](crate::theme::Density) of the window.
     ///
     /// See also [`gap`](Self::gap)
     ///
@@ -257,9 +261,9 @@ impl Flex {
     ///
     /// The actual value of this spacer depends on whether this container is
     /// a row or column, as well as theme settings.
-    pub fn with_default_spacer(self) -> Self {
-        let key = axis_default_spacer(self.direction);
-        self.with_spacer(key)
+    pub fn with_default_spacer(mut self) -> Self {
+        self.children.push(Child::DefaultSpacer(0.0));
+        self
     }
 
     /// Builder-style method for adding a fixed-size spacer to the container.
@@ -352,7 +356,8 @@ impl Flex {
     /// Use the default gap value.
     ///
     /// This is [`WIDGET_PADDING_VERTICAL`] for a flex column and
-    /// [`WIDGET_PADDING_HORIZONTAL`] for flex row.
+    /// [`WIDGET_PADDING_HORIZONTAL`] for flex row, scaled with the
+    /// [`Density`](crate::theme::Density) of the window.
     ///
     /// See also [`set_gap`](Self::set_gap)
     ///
@@ -413,8 +418,7 @@ impl Flex {
     /// The actual value of this spacer depends on whether this container is
     /// a row or column, as well as theme settings.
     pub fn add_default_spacer(this: &mut WidgetMut<'_, Self>) {
-        let key = axis_default_spacer(this.widget.direction);
-        Flex::add_spacer(this, key);
+        this.widget.children.push(Child::DefaultSpacer(0.0));
         this.ctx.request_layout();
     }
 
@@ -497,8 +501,7 @@ impl Flex {
     /// The actual value of this spacer depends on whether this container is
     /// a row or column, as well as theme settings.
     pub fn insert_default_spacer(this: &mut WidgetMut<'_, Self>, idx: usize) {
-        let key = axis_default_spacer(this.widget.direction);
-        Self::insert_spacer(this, idx, key);
+        this.widget.children.insert(idx, Child::DefaultSpacer(0.0));
         this.ctx.request_layout();
     }
 
@@ -540,6 +543,48 @@ impl Flex {
         this.ctx.request_layout();
     }
 
+    /// Remove the child at `idx`, keeping its allocation and id for reuse.
+    ///
+    /// See [`MutateCtx::recycle_child`](crate::MutateCtx::recycle_child) for details.
+    pub fn recycle_child(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        let child = this.widget.children.remove(idx);
+        if let Child::Fixed { widget, .. } | Child::Flex { widget, .. } = child {
+            this.ctx.recycle_child(widget);
+        }
+        this.ctx.request_layout();
+    }
+
+    /// Swap the children, or spacers, at `a` and `b`.
+    ///
+    /// Unlike removing and inserting them again, this keeps the state of the child widgets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is out of bounds.
+    pub fn swap_children(this: &mut WidgetMut<'_, Self>, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        this.widget.children.swap(a, b);
+        this.ctx.children_changed();
+    }
+
+    /// Move the child, or spacer, at `from` to `to`, shifting the elements in between.
+    ///
+    /// Unlike removing and inserting it again, this keeps the state of the child widget.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_child(this: &mut WidgetMut<'_, Self>, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let child = this.widget.children.remove(from);
+        this.widget.children.insert(to, child);
+        this.ctx.children_changed();
+    }
+
     // FIXME - Remove Box
     pub fn child_mut<'t>(
         this: &'t mut WidgetMut<'_, Self>,
@@ -548,6 +593,7 @@ impl Flex {
         let child = match &mut this.widget.children[idx] {
             Child::Fixed { widget, .. } | Child::Flex { widget, .. } => widget,
             Child::FixedSpacer(..) => return None,
+            Child::DefaultSpacer(..) => return None,
             Child::FlexedSpacer(..) => return None,
         };
 
@@ -586,7 +632,7 @@ impl Flex {
         let child = &mut this.widget.children[idx];
 
         match *child {
-            Child::FixedSpacer(_, _) | Child::FlexedSpacer(_, _) => {
+            Child::FixedSpacer(_, _) | Child::DefaultSpacer(_) | Child::FlexedSpacer(_, _) => {
                 *child = Child::FlexedSpacer(flex, 0.0);
             }
             _ => {
@@ -605,7 +651,7 @@ impl Flex {
         let child = &mut this.widget.children[idx];
 
         match *child {
-            Child::FixedSpacer(_, _) | Child::FlexedSpacer(_, _) => {
+            Child::FixedSpacer(_, _) | Child::DefaultSpacer(_) | Child::FlexedSpacer(_, _) => {
                 *child = Child::FixedSpacer(len, 0.0);
             }
             _ => {
@@ -877,10 +923,10 @@ impl Child {
 }
 
 /// The size in logical pixels of the default spacer for an axis.
-fn axis_default_spacer(axis: Axis) -> f64 {
+fn axis_default_spacer(axis: Axis, density: Density) -> f64 {
     match axis {
-        Axis::Vertical => crate::theme::WIDGET_PADDING_VERTICAL,
-        Axis::Horizontal => crate::theme::WIDGET_PADDING_HORIZONTAL,
+        Axis::Vertical => density.widget_padding_vertical(),
+        Axis::Horizontal => density.widget_padding_horizontal(),
     }
 }
 
@@ -938,7 +984,8 @@ impl Widget for Flex {
         let mut any_changed = bc_changed;
         self.old_bc = *bc;
 
-        let gap = self.gap.unwrap_or(axis_default_spacer(self.direction));
+        let default_spacer = axis_default_spacer(self.direction, ctx.density());
+        let gap = self.gap.unwrap_or(default_spacer);
         // The gaps are only between the items, so 2 children means 1 gap.
         let total_gap = self.children.len().saturating_sub(1) as f64 * gap;
         // Measure non-flex children.
@@ -992,6 +1039,10 @@ impl Widget for Flex {
                     *calculated_size = calculated_size.max(0.0);
                     major_non_flex += *calculated_size;
                 }
+                Child::DefaultSpacer(calculated_size) => {
+                    *calculated_size = default_spacer;
+                    major_non_flex += *calculated_size;
+                }
                 Child::Flex { flex, .. } | Child::FlexedSpacer(flex, _) => flex_sum += *flex,
             }
         }
@@ -1121,7 +1172,8 @@ impl Widget for Flex {
                     major += gap;
                 }
                 Child::FlexedSpacer(_, calculated_size)
-                | Child::FixedSpacer(_, calculated_size) => {
+                | Child::FixedSpacer(_, calculated_size)
+                | Child::DefaultSpacer(calculated_size) => {
                     major += *calculated_size;
                     major += gap;
                 }
@@ -1612,4 +1664,34 @@ mod tests {
         let mut harness = TestHarness::create(widget);
         harness.render();
     }
+
+    #[test]
+    fn reorder_children() {
+        let [a, b, c] = [WidgetId::next(), WidgetId::next(), WidgetId::next()];
+        let widget = Flex::column()
+            .with_child_id(Label::new("a"), a)
+            .with_child_id(Label::new("b"), b)
+            .with_child_id(Label::new("c"), c);
+        let mut harness = TestHarness::create(widget);
+        let order = |harness: &TestHarness| {
+            let mut ids = [a, b, c];
+            ids.sort_by(|l, r| {
+                let y = |id| harness.get_widget(id).ctx().window_layout_rect().y0;
+                y(*l).total_cmp(&y(*r))
+            });
+            ids
+        };
+
+        harness.edit_root_widget(|mut flex| {
+            let mut flex = flex.downcast::<Flex>();
+            Flex::swap_children(&mut flex, 0, 2);
+        });
+        assert_eq!(order(&harness), [c, b, a]);
+
+        harness.edit_root_widget(|mut flex| {
+            let mut flex = flex.downcast::<Flex>();
+            Flex::move_child(&mut flex, 0, 2);
+        });
+        assert_eq!(order(&harness), [b, a, c]);
+    }
 }
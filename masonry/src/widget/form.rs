@@ -0,0 +1,267 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A column of labelled fields with a submit button.
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::Rect;
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::text::ArcStr;
+use crate::widget::{Button, FormField, WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point, PointerEvent,
+    QueryCtx, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The space between two fields, and between the last field and the submit button.
+const FIELD_SPACING: f64 = 12.0;
+
+/// A column of [`FormField`]s followed by a submit button.
+///
+/// The submit button is disabled while any field has an error message. Clicking it anyway
+/// gives the focus to the first invalid field, so that the user can fix it.
+/// Otherwise, it submits [`Action::ButtonPressed`](crate::Action::ButtonPressed) as usual,
+/// with the id of the button.
+pub struct Form {
+    fields: Vec<WidgetPod<FormField>>,
+    submit: WidgetPod<Button>,
+    /// Whether all the fields were valid on the last layout.
+    valid: bool,
+    submit_rect: Rect,
+}
+
+// --- MARK: BUILDERS ---
+impl Form {
+    /// Create a new form without fields, with a submit button showing `submit_label`.
+    pub fn new(submit_label: impl Into<ArcStr>) -> Self {
+        Self::new_pod(WidgetPod::new(Button::new(submit_label)))
+    }
+
+    /// Create a new form without fields, with the given submit button.
+    ///
+    /// This can be used to know the id of the button.
+    pub fn new_pod(submit: WidgetPod<Button>) -> Self {
+        Self {
+            fields: Vec::new(),
+            submit,
+            valid: true,
+            submit_rect: Rect::ZERO,
+        }
+    }
+
+    /// Builder-style method to add a field.
+    pub fn with_field(self, field: FormField) -> Self {
+        self.with_field_pod(WidgetPod::new(field))
+    }
+
+    /// Builder-style method to add a field.
+    pub fn with_field_pod(mut self, field: WidgetPod<FormField>) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// The id of the submit button, which submits the actions of the form.
+    pub fn submit_id(&self) -> WidgetId {
+        self.submit.id()
+    }
+
+    /// The number of fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Whether the form has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Form {
+    /// Add a field at the end of the form.
+    pub fn add_field(this: &mut WidgetMut<'_, Self>, field: FormField) {
+        let len = this.widget.fields.len();
+        Self::insert_field_pod(this, len, WidgetPod::new(field));
+    }
+
+    /// Insert a field at `idx`.
+    pub fn insert_field_pod(
+        this: &mut WidgetMut<'_, Self>,
+        idx: usize,
+        field: WidgetPod<FormField>,
+    ) {
+        this.widget.fields.insert(idx, field);
+        this.ctx.children_changed();
+    }
+
+    /// Remove the field at `idx`.
+    pub fn remove_field(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        let field = this.widget.fields.remove(idx);
+        this.ctx.remove_child(field);
+    }
+
+    /// Get mutable access to the field at `idx`.
+    ///
+    /// The submit button is updated when the error messages of the fields change.
+    pub fn field_mut<'t>(
+        this: &'t mut WidgetMut<'_, Self>,
+        idx: usize,
+    ) -> WidgetMut<'t, FormField> {
+        this.ctx.get_mut(&mut this.widget.fields[idx])
+    }
+
+    /// Set the label of the submit button.
+    pub fn set_submit_label(this: &mut WidgetMut<'_, Self>, label: impl Into<ArcStr>) {
+        Button::set_text(&mut Self::submit_mut(this), label);
+    }
+
+    /// Get mutable access to the submit button.
+    pub fn submit_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Button> {
+        this.ctx.get_mut(&mut this.widget.submit)
+    }
+
+    /// Give keyboard focus to the first field with an error message, if any.
+    pub fn focus_first_invalid(this: &mut WidgetMut<'_, Self>) {
+        for field in &mut this.widget.fields {
+            let mut field = this.ctx.get_mut(field);
+            if !field.widget.is_valid() {
+                FormField::focus_input(&mut field);
+                return;
+            }
+        }
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Form {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        // The submit button doesn't handle clicks while it's disabled, so they reach the form.
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if !self.valid && !ctx.is_disabled() && self.submit_rect.contains(local_pos) {
+                    ctx.capture_pointer();
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if ctx.has_pointer_capture() && self.submit_rect.contains(local_pos) {
+                    let target = self.fields.iter_mut().find_map(|field| {
+                        let field = ctx.get_raw_ref(field);
+                        (!field.widget().is_valid())
+                            .then(|| field.ctx().first_focusable())
+                            .flatten()
+                    });
+                    if let Some(target) = target {
+                        ctx.set_focus(target);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        for field in &mut self.fields {
+            ctx.register_child(field);
+        }
+        ctx.register_child(&mut self.submit);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let field_bc = BoxConstraints::new(
+            Size::new(bc.min().width, 0.0),
+            Size::new(bc.max().width, f64::INFINITY),
+        );
+
+        let mut width: f64 = 0.0;
+        let mut y = 0.0;
+        let mut valid = true;
+        for field in &mut self.fields {
+            let size = ctx.run_layout(field, &field_bc);
+            ctx.place_child(field, Point::new(0.0, y));
+            width = width.max(size.width);
+            y += size.height + FIELD_SPACING;
+            valid &= ctx.get_raw_ref(field).widget().is_valid();
+        }
+
+        // Fields call for a layout when their error message changes.
+        self.valid = valid;
+        ctx.set_child_disabled(&mut self.submit, !valid);
+
+        let submit_size = ctx.run_layout(&mut self.submit, &field_bc.loosen());
+        let submit_origin = Point::new(0.0, y);
+        ctx.place_child(&mut self.submit, submit_origin);
+        self.submit_rect = Rect::from_origin_size(submit_origin, submit_size);
+        width = width.max(submit_size.width);
+
+        bc.constrain(Size::new(width, y + submit_size.height))
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::Form
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut ids: SmallVec<_> = self.fields.iter().map(|field| field.id()).collect();
+        ids.push(self.submit.id());
+        ids
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Form", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::Textbox;
+    use crate::Action;
+
+    #[test]
+    fn submit_needs_valid_fields() {
+        let [submit_id, email_id] = widget_ids();
+        let widget = Form::new_pod(WidgetPod::new_with_id(Button::new("Save"), submit_id))
+            .with_field(FormField::new("Name", Textbox::new("Alice")))
+            .with_field(
+                FormField::new_pod(
+                    "Email",
+                    WidgetPod::new_with_id(Textbox::new(""), email_id).boxed(),
+                )
+                .with_error(Some("Enter an email address".into())),
+            );
+        let mut harness = TestHarness::create_with_size(widget, Size::new(300., 300.));
+        assert!(harness.get_widget(submit_id).ctx().is_disabled());
+
+        // Clicking the disabled button focuses the invalid field
+        harness.mouse_click_on(submit_id);
+        assert_eq!(harness.pop_action(), None);
+        assert!(harness.get_widget(email_id).ctx().has_focus());
+
+        harness.edit_root_widget(|mut form| {
+            let mut form = form.downcast::<Form>();
+            FormField::set_error(&mut Form::field_mut(&mut form, 1), None);
+        });
+        assert!(!harness.get_widget(submit_id).ctx().is_disabled());
+        harness.mouse_click_on(submit_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed(PointerButton::Primary), submit_id))
+        );
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A labelled input of a form, with an error message.
+
+use accesskit::{Invalid, Node, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::Scene;
+
+use crate::text::ArcStr;
+use crate::widget::{Label, WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point,
+    PointerEvent, QueryCtx, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The space between the label, the input and the error message.
+const LABEL_SPACING: f64 = 4.0;
+
+/// A field of a [`Form`](super::Form): a label, an input widget and an error message
+/// shown under the input while the value is invalid.
+///
+/// The field doesn't validate anything itself: its owner sets the error message with
+/// [`set_error`](Self::set_error) when the value of the input changes.
+pub struct FormField {
+    label: WidgetPod<Label>,
+    input: WidgetPod<Box<dyn Widget>>,
+    error_label: WidgetPod<Label>,
+    error: Option<ArcStr>,
+}
+
+// --- MARK: BUILDERS ---
+impl FormField {
+    /// Create a new field showing `label` above `input`.
+    pub fn new(label: impl Into<ArcStr>, input: impl Widget) -> Self {
+        Self::new_pod(label, WidgetPod::new(input).boxed())
+    }
+
+    /// Create a new field showing `label` above the widget `input`.
+    pub fn new_pod(label: impl Into<ArcStr>, input: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            label: WidgetPod::new(Label::new(label)),
+            input,
+            error_label: WidgetPod::new(Label::new("").with_brush(theme::ERROR_TEXT_COLOR)),
+            error: None,
+        }
+    }
+
+    /// Builder-style method to set the error message of the field.
+    pub fn with_error(mut self, error: Option<ArcStr>) -> Self {
+        if let Some(error) = &error {
+            self.error_label =
+                WidgetPod::new(Label::new(error.clone()).with_brush(theme::ERROR_TEXT_COLOR));
+        }
+        self.error = error;
+        self
+    }
+
+    /// Whether the value of the field is valid, i.e. it has no error message.
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// The error message of the field, if any.
+    pub fn error(&self) -> Option<&ArcStr> {
+        self.error.as_ref()
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl FormField {
+    /// Set the text of the label.
+    pub fn set_label(this: &mut WidgetMut<'_, Self>, label: impl Into<ArcStr>) {
+        Label::set_text(&mut this.ctx.get_mut(&mut this.widget.label), label);
+    }
+
+    /// Set the error message of the field, or `None` if its value is valid.
+    pub fn set_error(this: &mut WidgetMut<'_, Self>, error: Option<ArcStr>) {
+        if this.widget.error == error {
+            return;
+        }
+        if let Some(error) = &error {
+            Label::set_text(
+                &mut this.ctx.get_mut(&mut this.widget.error_label),
+                error.clone(),
+            );
+        }
+        this.widget.error = error;
+        this.ctx.request_layout();
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Give keyboard focus to the input, or to its first descendant which accepts it.
+    pub fn focus_input(this: &mut WidgetMut<'_, Self>) {
+        let target = this
+            .ctx
+            .get_mut(&mut this.widget.input)
+            .ctx
+            .first_focusable();
+        if let Some(id) = target {
+            this.ctx.set_focus(id);
+        }
+    }
+
+    /// Get mutable access to the input.
+    pub fn input_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.input)
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for FormField {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.label);
+        ctx.register_child(&mut self.input);
+        ctx.register_child(&mut self.error_label);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let child_bc = BoxConstraints::new(
+            Size::new(bc.min().width, 0.0),
+            Size::new(bc.max().width, f64::INFINITY),
+        );
+
+        let label_size = ctx.run_layout(&mut self.label, &child_bc.loosen());
+        ctx.place_child(&mut self.label, Point::ORIGIN);
+        let mut y = label_size.height + LABEL_SPACING;
+
+        let input_size = ctx.run_layout(&mut self.input, &child_bc);
+        ctx.place_child(&mut self.input, Point::new(0.0, y));
+        y += input_size.height;
+        let mut width = label_size.width.max(input_size.width);
+
+        ctx.set_stashed(&mut self.error_label, self.error.is_none());
+        if self.error.is_some() {
+            y += LABEL_SPACING;
+            let error_size = ctx.run_layout(&mut self.error_label, &child_bc.loosen());
+            ctx.place_child(&mut self.error_label, Point::new(0.0, y));
+            y += error_size.height;
+            width = width.max(error_size.width);
+        } else {
+            ctx.skip_layout(&mut self.error_label);
+        }
+
+        bc.constrain(Size::new(width, y))
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        if let Some(error) = &self.error {
+            node.set_invalid(Invalid::True);
+            node.set_description(error.to_string());
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.label.id(), self.input.id(), self.error_label.id()]
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("FormField", id = ctx.widget_id().trace())
+    }
+}
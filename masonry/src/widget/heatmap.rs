@@ -0,0 +1,620 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A matrix of values shown as colored cells.
+
+use std::ops::Range;
+
+use accesskit::{Node, Role};
+use parley::layout::Alignment;
+use parley::{FontContext, Layout, LayoutContext};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{Affine, Vec2};
+use vello::peniko::{Color, Mix};
+use vello::Scene;
+
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text::{
+    default_style_properties, render_text, ArcStr, BrushIndex, StyleProperty, TextLayoutJob,
+};
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, ComposeCtx, EventCtx, LayoutCtx, PaintCtx,
+    Point, PointerEvent, QueryCtx, Rect, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The default size of a cell.
+const DEFAULT_CELL_SIZE: Size = Size::new(16.0, 16.0);
+/// The space between the labels and the cells.
+const LABEL_PADDING: f64 = 4.0;
+/// The offset of the tooltip from the pointer.
+const TOOLTIP_OFFSET: f64 = 12.0;
+/// The space between the edges of the tooltip and its text.
+const TOOLTIP_PADDING: f64 = 4.0;
+
+/// The colors of the values of a [`Heatmap`], from the lowest to the highest.
+///
+/// Values between two stops are interpolated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorScale {
+    /// The stops, as positions from 0 to 1 and their colors, in order.
+    stops: Vec<(f64, Color)>,
+}
+
+impl ColorScale {
+    /// Create a scale going through `colors`, evenly spaced.
+    ///
+    /// ## Panics
+    ///
+    /// If `colors` is empty.
+    pub fn new(colors: impl IntoIterator<Item = Color>) -> Self {
+        let colors: Vec<_> = colors.into_iter().collect();
+        assert!(!colors.is_empty(), "a color scale needs at least one color");
+        let last = (colors.len() - 1).max(1) as f64;
+        let stops = (0..).map(|idx| idx as f64 / last).zip(colors).collect();
+        Self { stops }
+    }
+
+    /// A scale from `low` to `high`.
+    pub fn sequential(low: Color, high: Color) -> Self {
+        Self::new([low, high])
+    }
+
+    /// A scale from `low` to `high` going through `mid`, for values around a midpoint.
+    pub fn diverging(low: Color, mid: Color, high: Color) -> Self {
+        Self::new([low, mid, high])
+    }
+
+    /// The color at `position`, from 0 for the lowest values to 1 for the highest.
+    pub fn color_at(&self, position: f64) -> Color {
+        let position = position.clamp(0.0, 1.0);
+        let next = self
+            .stops
+            .iter()
+            .position(|(stop, _)| *stop >= position)
+            .unwrap_or(self.stops.len() - 1);
+        let (end, end_color) = self.stops[next];
+        let Some(&(start, start_color)) = next.checked_sub(1).map(|prev| &self.stops[prev]) else {
+            return end_color;
+        };
+        let t = ((position - start) / (end - start)) as f32;
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color::rgba8(
+            lerp(start_color.r, end_color.r),
+            lerp(start_color.g, end_color.g),
+            lerp(start_color.b, end_color.b),
+            lerp(start_color.a, end_color.a),
+        )
+    }
+}
+
+impl Default for ColorScale {
+    /// A scale from dark blue to yellow, through teal and green.
+    fn default() -> Self {
+        Self::new([
+            Color::rgb8(0x44, 0x01, 0x54),
+            Color::rgb8(0x21, 0x91, 0x8c),
+            Color::rgb8(0x5e, 0xc9, 0x62),
+            Color::rgb8(0xfd, 0xe7, 0x25),
+        ])
+    }
+}
+
+/// A matrix of values, shown as cells colored with a [`ColorScale`].
+///
+/// Rows and columns can have labels, on the left and the top of the cells. Hovering a cell
+/// shows its value in a tooltip. Cells whose value is NaN are left empty.
+///
+/// The heatmap is as large as all its cells, and is meant to be in a
+/// [`Portal`](crate::widget::Portal) when they don't fit. Only the cells in view are painted,
+/// so it can show large matrices.
+pub struct Heatmap {
+    rows: usize,
+    columns: usize,
+    /// The values, row by row.
+    values: Vec<f64>,
+    /// The values at the ends of the color scale, if set.
+    range: Option<(f64, f64)>,
+    scale: ColorScale,
+    cell_size: Size,
+    row_labels: Vec<ArcStr>,
+    column_labels: Vec<ArcStr>,
+    /// The shaped labels, in the same order.
+    row_layouts: Vec<Layout<BrushIndex>>,
+    column_layouts: Vec<Layout<BrushIndex>>,
+    /// Whether the layouts need to be recreated, because the labels changed.
+    labels_changed: bool,
+    /// The position of the first cell, after the labels.
+    cells_origin: Point,
+    /// The part of the heatmap which is in view, from the last compose.
+    visible: Rect,
+    /// The rows and columns of the cells painted on the last paint.
+    painted_cells: (Range<usize>, Range<usize>),
+    /// The row and column of the hovered cell.
+    hovered: Option<(usize, usize)>,
+    /// The position of the pointer, where the tooltip is shown.
+    pointer_pos: Point,
+}
+
+// --- MARK: BUILDERS ---
+impl Heatmap {
+    /// Create a new heatmap of `rows` by `columns` values, given row by row.
+    ///
+    /// Missing values are NaN, and extra values are ignored.
+    pub fn new(rows: usize, columns: usize, values: impl IntoIterator<Item = f64>) -> Self {
+        Self {
+            rows,
+            columns,
+            values: Self::collect_values(rows, columns, values),
+            range: None,
+            scale: ColorScale::default(),
+            cell_size: DEFAULT_CELL_SIZE,
+            row_labels: Vec::new(),
+            column_labels: Vec::new(),
+            row_layouts: Vec::new(),
+            column_layouts: Vec::new(),
+            labels_changed: true,
+            cells_origin: Point::ORIGIN,
+            visible: Rect::ZERO,
+            painted_cells: (0..0, 0..0),
+            hovered: None,
+            pointer_pos: Point::ORIGIN,
+        }
+    }
+
+    /// Builder-style method to set the values at the ends of the color scale.
+    ///
+    /// By default, these are the lowest and highest values.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Builder-style method to set the colors of the values.
+    pub fn with_color_scale(mut self, scale: ColorScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Builder-style method to set the size of a cell.
+    pub fn with_cell_size(mut self, size: Size) -> Self {
+        self.cell_size = size;
+        self
+    }
+
+    /// Builder-style method to set the labels of the rows, from the top.
+    pub fn with_row_labels(mut self, labels: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        self.row_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Builder-style method to set the labels of the columns, from the left.
+    pub fn with_column_labels(
+        mut self,
+        labels: impl IntoIterator<Item = impl Into<ArcStr>>,
+    ) -> Self {
+        self.column_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The value at `row` and `column`, if they're in the matrix.
+    pub fn value(&self, row: usize, column: usize) -> Option<f64> {
+        if row >= self.rows || column >= self.columns {
+            return None;
+        }
+        Some(self.values[row * self.columns + column])
+    }
+
+    /// The row and column of the cell under the pointer, if any.
+    pub fn hovered_cell(&self) -> Option<(usize, usize)> {
+        self.hovered
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Heatmap {
+    /// Replace the values, as in [`Heatmap::new`].
+    pub fn set_values(
+        this: &mut WidgetMut<'_, Self>,
+        rows: usize,
+        columns: usize,
+        values: impl IntoIterator<Item = f64>,
+    ) {
+        let resized = (rows, columns) != (this.widget.rows, this.widget.columns);
+        this.widget.values = Self::collect_values(rows, columns, values);
+        this.widget.rows = rows;
+        this.widget.columns = columns;
+        if resized {
+            this.widget.hovered = None;
+            this.ctx.request_layout();
+        }
+        this.ctx.request_render();
+    }
+
+    /// Set the values at the ends of the color scale, or use the lowest and highest values.
+    pub fn set_range(this: &mut WidgetMut<'_, Self>, range: Option<(f64, f64)>) {
+        this.widget.range = range;
+        this.ctx.request_paint_only();
+    }
+
+    /// Set the colors of the values.
+    pub fn set_color_scale(this: &mut WidgetMut<'_, Self>, scale: ColorScale) {
+        this.widget.scale = scale;
+        this.ctx.request_paint_only();
+    }
+
+    /// Set the size of a cell.
+    pub fn set_cell_size(this: &mut WidgetMut<'_, Self>, size: Size) {
+        this.widget.cell_size = size;
+        this.ctx.request_layout();
+    }
+
+    /// Set the labels of the rows, from the top.
+    pub fn set_row_labels(
+        this: &mut WidgetMut<'_, Self>,
+        labels: impl IntoIterator<Item = impl Into<ArcStr>>,
+    ) {
+        this.widget.row_labels = labels.into_iter().map(Into::into).collect();
+        this.widget.labels_changed = true;
+        this.ctx.request_layout();
+    }
+
+    /// Set the labels of the columns, from the left.
+    pub fn set_column_labels(
+        this: &mut WidgetMut<'_, Self>,
+        labels: impl IntoIterator<Item = impl Into<ArcStr>>,
+    ) {
+        this.widget.column_labels = labels.into_iter().map(Into::into).collect();
+        this.widget.labels_changed = true;
+        this.ctx.request_layout();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl Heatmap {
+    fn collect_values(
+        rows: usize,
+        columns: usize,
+        values: impl IntoIterator<Item = f64>,
+    ) -> Vec<f64> {
+        let len = rows * columns;
+        let mut values: Vec<_> = values.into_iter().take(len).collect();
+        values.resize(len, f64::NAN);
+        values
+    }
+
+    /// The values at the ends of the color scale.
+    fn value_range(&self) -> (f64, f64) {
+        self.range.unwrap_or_else(|| {
+            let finite = self
+                .values
+                .iter()
+                .copied()
+                .filter(|value| value.is_finite());
+            let min = finite.clone().reduce(f64::min).unwrap_or(0.0);
+            let max = finite.reduce(f64::max).unwrap_or(1.0);
+            (min, max)
+        })
+    }
+
+    /// The rows and columns of the cells overlapping `rect`.
+    fn cells_in(&self, rect: Rect) -> (Range<usize>, Range<usize>) {
+        let cells = rect - self.cells_origin.to_vec2();
+        let range = |start: f64, end: f64, cell: f64, count: usize| {
+            if cell <= 0.0 {
+                return 0..0;
+            }
+            let first = (start / cell).floor().clamp(0.0, count as f64) as usize;
+            let last = (end / cell).ceil().clamp(0.0, count as f64) as usize;
+            first..last.max(first)
+        };
+        (
+            range(cells.y0, cells.y1, self.cell_size.height, self.rows),
+            range(cells.x0, cells.x1, self.cell_size.width, self.columns),
+        )
+    }
+
+    /// The row and column of the cell under `pos`.
+    fn cell_at(&self, pos: Point) -> Option<(usize, usize)> {
+        let pos = pos - self.cells_origin;
+        if pos.x < 0.0 || pos.y < 0.0 {
+            return None;
+        }
+        let row = (pos.y / self.cell_size.height) as usize;
+        let column = (pos.x / self.cell_size.width) as usize;
+        (row < self.rows && column < self.columns).then_some((row, column))
+    }
+
+    fn hover(&mut self, ctx: &mut EventCtx, pos: Option<Point>) {
+        let hovered = pos.and_then(|pos| self.cell_at(pos));
+        if let Some(pos) = pos {
+            self.pointer_pos = pos;
+        }
+        // The tooltip follows the pointer.
+        if hovered.is_some() || hovered != self.hovered {
+            self.hovered = hovered;
+            ctx.request_paint_only();
+            ctx.request_accessibility_update();
+        }
+    }
+
+    /// The text of the tooltip of the cell at `row` and `column`.
+    fn tooltip(&self, row: usize, column: usize) -> String {
+        let value = self.values[row * self.columns + column];
+        match (self.row_labels.get(row), self.column_labels.get(column)) {
+            (Some(row), Some(column)) => format!("{row}, {column}: {value}"),
+            _ => format!("Row {}, column {}: {value}", row + 1, column + 1),
+        }
+    }
+
+    fn shape(
+        font_ctx: &mut FontContext,
+        layout_ctx: &mut LayoutContext<BrushIndex>,
+        text: ArcStr,
+        layout: &mut Layout<BrushIndex>,
+    ) {
+        let mut job = TextLayoutJob::new(
+            text,
+            default_style_properties()
+                .into_iter()
+                .chain([StyleProperty::FontSize(theme::TEXT_SIZE_NORMAL)]),
+            std::mem::replace(layout, Layout::new()),
+        );
+        job.run(font_ctx, layout_ctx);
+        job.layout.break_all_lines(None);
+        job.layout.align(None, Alignment::Start);
+        *layout = job.layout;
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Heatmap {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerMove(state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                self.hover(ctx, Some(local_pos));
+            }
+            PointerEvent::PointerLeave(_) => self.hover(ctx, None),
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // The visible part is updated in compose whenever the heatmap moves.
+        ctx.set_compose_on_move(true);
+
+        if std::mem::take(&mut self.labels_changed) {
+            let (font_ctx, layout_ctx) = ctx.text_contexts();
+            for (labels, layouts) in [
+                (&self.row_labels, &mut self.row_layouts),
+                (&self.column_labels, &mut self.column_layouts),
+            ] {
+                layouts.resize_with(labels.len(), Layout::new);
+                for (label, layout) in labels.iter().zip(layouts) {
+                    Self::shape(font_ctx, layout_ctx, label.clone(), layout);
+                }
+            }
+        }
+
+        let labels_width = self
+            .row_layouts
+            .iter()
+            .map(|layout| f64::from(layout.width()) + LABEL_PADDING)
+            .reduce(f64::max)
+            .unwrap_or(0.0);
+        let labels_height = self
+            .column_layouts
+            .iter()
+            .map(|layout| f64::from(layout.height()) + LABEL_PADDING)
+            .reduce(f64::max)
+            .unwrap_or(0.0);
+        self.cells_origin = Point::new(labels_width, labels_height);
+
+        // The heatmap takes the size of all its cells, even past the max constraints, so that
+        // a portal can scroll through it.
+        let size = Size::new(
+            labels_width + self.columns as f64 * self.cell_size.width,
+            labels_height + self.rows as f64 * self.cell_size.height,
+        );
+        let size = Size::new(
+            size.width.max(bc.min().width),
+            size.height.max(bc.min().height),
+        );
+        self.visible = size.to_rect();
+        size
+    }
+
+    fn compose(&mut self, ctx: &mut ComposeCtx) {
+        let bounds = ctx.size().to_rect();
+        self.visible = match ctx.ancestor_clip_rect() {
+            Some(clip) => (clip - ctx.window_origin().to_vec2()).intersect(bounds),
+            None => bounds,
+        };
+        if self.cells_in(self.visible) != self.painted_cells {
+            ctx.request_paint_only();
+        }
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let (rows, columns) = self.cells_in(self.visible);
+        let (min, max) = self.value_range();
+        let cell = self.cell_size;
+        for row in rows.clone() {
+            for column in columns.clone() {
+                let value = self.values[row * self.columns + column];
+                if value.is_nan() {
+                    continue;
+                }
+                let position = if max > min {
+                    (value - min) / (max - min)
+                } else {
+                    0.5
+                };
+                let origin = self.cells_origin
+                    + Vec2::new(column as f64 * cell.width, row as f64 * cell.height);
+                let rect = Rect::from_origin_size(origin, cell);
+                fill_color(scene, &rect, self.scale.color_at(position));
+            }
+        }
+
+        // The labels are cut at the edges of their cell.
+        for row in rows.clone() {
+            let Some(label) = self.row_layouts.get(row) else {
+                break;
+            };
+            let y = self.cells_origin.y + row as f64 * cell.height;
+            let clip = Rect::new(0.0, y, self.cells_origin.x, y + cell.height);
+            let label_y = y + (cell.height - f64::from(label.height())) / 2.0;
+            scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &clip);
+            let transform = Affine::translate(Vec2::new(0.0, label_y));
+            render_text(scene, transform, label, &[theme::TEXT_COLOR.into()], true);
+            scene.pop_layer();
+        }
+        for column in columns.clone() {
+            let Some(label) = self.column_layouts.get(column) else {
+                break;
+            };
+            let x = self.cells_origin.x + column as f64 * cell.width;
+            let clip = Rect::new(x, 0.0, x + cell.width, self.cells_origin.y);
+            scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &clip);
+            let transform = Affine::translate(Vec2::new(x, 0.0));
+            render_text(scene, transform, label, &[theme::TEXT_COLOR.into()], true);
+            scene.pop_layer();
+        }
+
+        if let Some((row, column)) = self.hovered {
+            let origin =
+                self.cells_origin + Vec2::new(column as f64 * cell.width, row as f64 * cell.height);
+            stroke(
+                scene,
+                &Rect::from_origin_size(origin, cell).inset(-0.5),
+                theme::FOREGROUND_LIGHT,
+                1.0,
+            );
+
+            let mut tooltip = Layout::new();
+            let (font_ctx, layout_ctx) = ctx.text_contexts();
+            Self::shape(
+                font_ctx,
+                layout_ctx,
+                self.tooltip(row, column).into(),
+                &mut tooltip,
+            );
+            let tooltip_size = Size::new(
+                f64::from(tooltip.width()) + 2.0 * TOOLTIP_PADDING,
+                f64::from(tooltip.height()) + 2.0 * TOOLTIP_PADDING,
+            );
+            // The tooltip is kept within the part of the heatmap in view.
+            let origin = Point::new(
+                (self.pointer_pos.x + TOOLTIP_OFFSET)
+                    .min(self.visible.x1 - tooltip_size.width)
+                    .max(self.visible.x0),
+                (self.pointer_pos.y + TOOLTIP_OFFSET)
+                    .min(self.visible.y1 - tooltip_size.height)
+                    .max(self.visible.y0),
+            );
+            let rect = Rect::from_origin_size(origin, tooltip_size).to_rounded_rect(2.0);
+            fill_color(scene, &rect, theme::BACKGROUND_LIGHT);
+            stroke(scene, &rect, theme::BORDER_LIGHT, 1.0);
+            let transform =
+                Affine::translate(origin.to_vec2() + Vec2::new(TOOLTIP_PADDING, TOOLTIP_PADDING));
+            render_text(
+                scene,
+                transform,
+                &tooltip,
+                &[theme::TEXT_COLOR.into()],
+                true,
+            );
+        }
+
+        self.painted_cells = (rows, columns);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Figure
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        node.set_description(format!("{} rows by {} columns", self.rows, self.columns));
+        if let Some((row, column)) = self.hovered {
+            node.set_value(self.tooltip(row, column));
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Heatmap", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::Portal;
+    use crate::WidgetPod;
+
+    #[test]
+    fn color_scale() {
+        let scale = ColorScale::sequential(Color::BLACK, Color::WHITE);
+        assert_eq!(scale.color_at(0.0), Color::BLACK);
+        assert_eq!(scale.color_at(0.5), Color::rgb8(0x80, 0x80, 0x80));
+        assert_eq!(scale.color_at(2.0), Color::WHITE);
+
+        let scale = ColorScale::diverging(Color::BLACK, Color::WHITE, Color::BLACK);
+        assert_eq!(scale.color_at(0.5), Color::WHITE);
+        assert_eq!(scale.color_at(1.0), Color::BLACK);
+    }
+
+    #[test]
+    fn only_visible_cells_are_painted() {
+        let heatmap_id = WidgetId::next();
+        let values = (0..1000 * 1000).map(|value| value as f64);
+        let heatmap = WidgetPod::new_with_id(Heatmap::new(1000, 1000, values), heatmap_id);
+        let widget = Portal::new_pod(heatmap);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        harness.render();
+
+        let heatmap = harness.get_widget(heatmap_id);
+        let heatmap = heatmap.downcast::<Heatmap>().unwrap();
+        assert_eq!(heatmap.painted_cells, (0..25, 0..25));
+        assert_eq!(heatmap.value(2, 3), Some(2003.0));
+
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Heatmap>>();
+            Portal::set_viewport_pos(&mut portal, Point::new(1608., 800.));
+        });
+        harness.render();
+        let heatmap = harness.get_widget(heatmap_id);
+        let heatmap = heatmap.downcast::<Heatmap>().unwrap();
+        assert_eq!(heatmap.painted_cells, (50..75, 100..126));
+
+        harness.mouse_move((2.5 * 16., 1.5 * 16.));
+        let heatmap = harness.get_widget(heatmap_id);
+        let heatmap = heatmap.downcast::<Heatmap>().unwrap();
+        assert_eq!(heatmap.hovered_cell(), Some((51, 103)));
+        assert_eq!(heatmap.tooltip(1, 2), "Row 2, column 3: 1002");
+    }
+}
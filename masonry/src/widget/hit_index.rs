@@ -0,0 +1,130 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An index of the layout rects of the children of a widget, used for hit-testing.
+
+use vello::kurbo::{Point, Rect};
+
+use crate::widget::Axis;
+use crate::WidgetId;
+
+/// The layout rects of the children of a widget, sorted along one axis.
+///
+/// Hit-testing a widget with thousands of children, such as a long list, would otherwise
+/// check every child on each pointer move. With the rects sorted by their start along the
+/// axis the children are spread on, only the children starting within the largest child
+/// extent before the pointer are checked.
+///
+/// The index is built when the widget is first hit-tested, and discarded when its children
+/// change or move.
+#[derive(Clone, Debug)]
+pub(crate) struct ChildHitIndex {
+    axis: Axis,
+    /// The children, sorted by the start of their rect along `axis`.
+    entries: Vec<HitEntry>,
+    /// The largest extent of a child rect along `axis`.
+    max_extent: f64,
+}
+
+#[derive(Clone, Debug)]
+struct HitEntry {
+    id: WidgetId,
+    /// The position of the child in [`Widget::children_ids`](crate::Widget::children_ids).
+    order: usize,
+    /// The layout rect of the child, relative to the window origin of the parent.
+    rect: Rect,
+}
+
+impl ChildHitIndex {
+    /// Index the given children, in the order of [`Widget::children_ids`](crate::Widget::children_ids),
+    /// with their layout rects relative to the window origin of their parent.
+    pub(crate) fn new(children: impl IntoIterator<Item = (WidgetId, Rect)>) -> Self {
+        let mut entries: Vec<_> = children
+            .into_iter()
+            .enumerate()
+            .map(|(order, (id, rect))| HitEntry { id, order, rect })
+            .collect();
+
+        // Sort along the axis on which the children overlap the least, e.g. the vertical
+        // axis for a column.
+        let spread = |axis: Axis| {
+            let mut start = f64::INFINITY;
+            let mut end = f64::NEG_INFINITY;
+            let mut max_extent: f64 = 0.;
+            for entry in &entries {
+                let (x0, x1) = axis.major_span(entry.rect);
+                start = start.min(x0);
+                end = end.max(x1);
+                max_extent = max_extent.max(x1 - x0);
+            }
+            (max_extent, max_extent / (end - start).max(f64::EPSILON))
+        };
+        let (horizontal_extent, horizontal_ratio) = spread(Axis::Horizontal);
+        let (vertical_extent, vertical_ratio) = spread(Axis::Vertical);
+        let (axis, max_extent) = if horizontal_ratio < vertical_ratio {
+            (Axis::Horizontal, horizontal_extent)
+        } else {
+            (Axis::Vertical, vertical_extent)
+        };
+
+        entries.sort_by(|a, b| {
+            let a = axis.major_span(a.rect).0;
+            let b = axis.major_span(b.rect).0;
+            a.total_cmp(&b)
+        });
+        Self {
+            axis,
+            entries,
+            max_extent,
+        }
+    }
+
+    fn start(&self, entry: &HitEntry) -> f64 {
+        self.axis.major_span(entry.rect).0
+    }
+
+    /// The children whose rect contains `pos`, relative to the window origin of the parent,
+    /// with their position in [`Widget::children_ids`](crate::Widget::children_ids).
+    ///
+    /// The children are in no particular order.
+    pub(crate) fn children_at(&self, pos: Point) -> impl Iterator<Item = (WidgetId, usize)> + '_ {
+        let key = self.axis.major_pos(pos);
+        let first = self
+            .entries
+            .partition_point(|entry| self.start(entry) < key - self.max_extent);
+        let end = self
+            .entries
+            .partition_point(|entry| self.start(entry) <= key);
+        self.entries[first..end.max(first)]
+            .iter()
+            .filter(move |entry| entry.rect.contains(pos))
+            .map(|entry| (entry.id, entry.order))
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::widget_ids;
+
+    #[test]
+    fn finds_overlapping_children() {
+        let [a, b, c, d] = widget_ids();
+        // A column of rows, with a tall child overlapping the two others.
+        let index = ChildHitIndex::new([
+            (a, Rect::new(0., 0., 100., 10.)),
+            (b, Rect::new(0., 10., 100., 20.)),
+            (c, Rect::new(0., 20., 100., 30.)),
+            (d, Rect::new(50., 0., 60., 30.)),
+        ]);
+
+        let mut hits: Vec<_> = index.children_at(Point::new(55., 15.)).collect();
+        hits.sort_by_key(|(_, order)| *order);
+        assert_eq!(hits, [(b, 1), (d, 3)]);
+
+        let hits: Vec<_> = index.children_at(Point::new(10., 25.)).collect();
+        assert_eq!(hits, [(c, 2)]);
+        assert_eq!(index.children_at(Point::new(10., 30.)).count(), 0);
+    }
+}
@@ -6,17 +6,21 @@
 //! A label widget.
 
 use std::mem::Discriminant;
+use std::ops::Range;
 
 use accesskit::{Node, NodeId, Role};
 use parley::layout::Alignment;
 use parley::{Layout, LayoutAccessibility};
 use smallvec::SmallVec;
 use tracing::{trace_span, Span};
-use vello::kurbo::{Affine, Size};
+use vello::kurbo::{Affine, Point, Rect, Size, Vec2};
 use vello::peniko::{BlendMode, Brush};
 use vello::Scene;
 
-use crate::text::{default_styles, render_text, ArcStr, BrushIndex, StyleProperty, StyleSet};
+use crate::text::{
+    caret_rect, default_styles, index_at_point, range_rects, render_text, ArcStr, BrushIndex,
+    StyleProperty, StyleSet, TextLayoutJob,
+};
 use crate::widget::WidgetMut;
 use crate::{
     theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent,
@@ -27,6 +31,9 @@ use crate::{
 /// and the text in logical pixels.
 const LABEL_X_PADDING: f64 = 2.0;
 
+/// The offset of the text from the origin of the widget.
+const TEXT_ORIGIN: Vec2 = Vec2::new(LABEL_X_PADDING, 0.);
+
 /// Options for handling lines that are too wide for the label.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum LineBreaking {
@@ -52,6 +59,10 @@ pub struct Label {
     ///
     /// If they have, the layout needs to be recreated.
     styles_changed: bool,
+    /// Whether `text_layout` was recreated ahead of layout, through a [`TextLayoutJob`].
+    ///
+    /// If it was, line breaking still needs to be performed.
+    prepared_ahead: bool,
 
     line_break_mode: LineBreaking,
     alignment: Alignment,
@@ -93,6 +104,7 @@ impl Label {
             text: text.into(),
             styles,
             styles_changed: true,
+            prepared_ahead: false,
             line_break_mode: LineBreaking::Overflow,
             alignment: Alignment::Start,
             alignment_changed: true,
@@ -110,6 +122,43 @@ impl Label {
         &self.text
     }
 
+    /// The byte index of the text nearest to `point`, which is in this widget's coordinate space.
+    ///
+    /// This lets custom widgets position overlays relative to the rendered text.
+    /// Returns `None` if the text hasn't been laid out since it last changed.
+    pub fn index_at_point(&self, point: Point) -> Option<usize> {
+        let layout = self.laid_out_text()?;
+        Some(index_at_point(layout, point - TEXT_ORIGIN))
+    }
+
+    /// The rect of a cursor of the given `width` placed before the byte `index` of the text,
+    /// in this widget's coordinate space.
+    ///
+    /// Returns `None` if the text hasn't been laid out since it last changed.
+    pub fn caret_rect(&self, index: usize, width: f32) -> Option<Rect> {
+        let layout = self.laid_out_text()?;
+        Some(caret_rect(layout, index, width) + TEXT_ORIGIN)
+    }
+
+    /// The rects covering the text in the byte `range`, one per line, in this widget's
+    /// coordinate space.
+    ///
+    /// This is empty if the text hasn't been laid out since it last changed.
+    pub fn range_rects(&self, range: Range<usize>) -> Vec<Rect> {
+        let Some(layout) = self.laid_out_text() else {
+            return Vec::new();
+        };
+        range_rects(layout, range)
+            .into_iter()
+            .map(|rect| rect + TEXT_ORIGIN)
+            .collect()
+    }
+
+    /// The layout of the text, if it matches the text and styles.
+    fn laid_out_text(&self) -> Option<&Layout<BrushIndex>> {
+        (!self.styles_changed && !self.prepared_ahead).then_some(&self.text_layout)
+    }
+
     /// Set a style property for the new label.
     ///
     /// Setting [`StyleProperty::Brush`](parley::StyleProperty::Brush) is not supported.
@@ -344,16 +393,11 @@ impl Widget for Label {
         } else {
             None
         };
-        let styles_changed = self.styles_changed;
-        if self.styles_changed {
+        let styles_changed = self.styles_changed || std::mem::take(&mut self.prepared_ahead);
+        if let Some(mut job) = self.take_text_layout_job() {
             let (font_ctx, layout_ctx) = ctx.text_contexts();
-            // TODO: Should we use a different scale?
-            let mut builder = layout_ctx.ranged_builder(font_ctx, &self.text, 1.0);
-            for prop in self.styles.inner().values() {
-                builder.push_default(prop.to_owned());
-            }
-            builder.build_into(&mut self.text_layout, &self.text);
-            self.styles_changed = false;
+            job.run(font_ctx, layout_ctx);
+            self.text_layout = job.layout;
         }
 
         if max_advance != self.last_max_advance || styles_changed {
@@ -395,12 +439,29 @@ impl Widget for Label {
         bc.constrain(label_size)
     }
 
+    fn take_text_layout_job(&mut self) -> Option<TextLayoutJob> {
+        if !self.styles_changed {
+            return None;
+        }
+        self.styles_changed = false;
+        Some(TextLayoutJob::new(
+            self.text.clone(),
+            self.styles.inner().values().cloned(),
+            std::mem::replace(&mut self.text_layout, Layout::new()),
+        ))
+    }
+
+    fn finish_text_layout_job(&mut self, job: TextLayoutJob) {
+        self.text_layout = job.layout;
+        self.prepared_ahead = true;
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
         if self.line_break_mode == LineBreaking::Clip {
             let clip_rect = ctx.size().to_rect();
             scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
         }
-        let transform = Affine::translate((LABEL_X_PADDING, 0.));
+        let transform = Affine::translate(TEXT_ORIGIN);
 
         let brush = if ctx.is_disabled() {
             self.disabled_brush
@@ -459,6 +520,22 @@ mod tests {
     use crate::theme::{PRIMARY_DARK, PRIMARY_LIGHT};
     use crate::widget::{CrossAxisAlignment, Flex, SizedBox};
 
+    #[test]
+    fn hit_test_label() {
+        let label = Label::new("Hello world");
+        let harness = TestHarness::create(label);
+        let root = harness.root_widget();
+        let label = root.downcast::<Label>().unwrap();
+
+        let caret = label.caret_rect(6, 1.).unwrap();
+        assert!(caret.x0 > LABEL_X_PADDING);
+        assert_eq!(label.index_at_point(caret.center()), Some(6));
+
+        let rects = label.range_rects(0..5);
+        assert_eq!(rects.len(), 1);
+        assert!(rects[0].x1 < caret.x0);
+    }
+
     #[test]
     fn simple_label() {
         let label = Label::new("Hello");
@@ -469,6 +546,20 @@ mod tests {
         assert_render_snapshot!(harness, "hello");
     }
 
+    #[test]
+    fn text_layout_job() {
+        let mut label = Label::new("Hello");
+
+        let job = label.take_text_layout_job().unwrap();
+        assert_eq!(job.text.as_ref(), "Hello");
+        // The text has been handed out, so there is nothing left to shape until it changes.
+        assert!(label.take_text_layout_job().is_none());
+
+        label.finish_text_layout_job(job);
+        assert!(label.prepared_ahead);
+        assert!(label.take_text_layout_job().is_none());
+    }
+
     #[test]
     fn styled_label() {
         let label = Label::new("The quick brown fox jumps over the lazy dog")
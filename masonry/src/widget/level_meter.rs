@@ -0,0 +1,380 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bars showing the level of audio channels in decibels, as in a mixer.
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::Line;
+use vello::peniko::Color;
+use vello::Scene;
+
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{AudioFeed, WidgetMut};
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent,
+    QueryCtx, Rect, RegisterCtx, Size, TextEvent, Update, UpdateCtx, Widget, WidgetId,
+};
+
+/// The width of the bar of a channel.
+const BAR_WIDTH: f64 = 8.0;
+/// The space between the bars of two channels.
+const BAR_GAP: f64 = 2.0;
+/// The height of a level meter without height constraints.
+const DEFAULT_HEIGHT: f64 = 120.0;
+/// The level above which the bars are drawn as suboptimal, in decibels.
+const WARNING_LEVEL: f64 = -18.0;
+/// The level above which the bars are drawn as critical, in decibels.
+const CRITICAL_LEVEL: f64 = -6.0;
+/// How fast the bars fall when the level drops, in decibels per second.
+const FALL_RATE: f64 = 24.0;
+/// How long the highest level is held before it falls, in seconds.
+const PEAK_HOLD: f64 = 1.5;
+/// The thickness of the line showing the held peak.
+const PEAK_LINE_WIDTH: f64 = 2.0;
+
+/// The level of a channel, in decibels.
+#[derive(Clone, Copy, Debug)]
+struct ChannelLevel {
+    level: f64,
+    peak: f64,
+    /// How long the peak was held for, in seconds.
+    peak_age: f64,
+}
+
+/// Vertical bars showing the level of audio channels in decibels, with the highest recent
+/// level held above them.
+///
+/// Samples can be sent from another thread through an [`AudioFeed`], which is read on every
+/// animation frame, or the app can give levels directly with [`push_levels`](Self::push_levels).
+/// Bars rise instantly and fall smoothly, and peaks are held for a moment before falling.
+pub struct LevelMeter {
+    channels: Vec<ChannelLevel>,
+    feed: Option<AudioFeed>,
+    /// The levels at the bottom and the top of the bars, in decibels.
+    min_db: f64,
+    max_db: f64,
+}
+
+// --- MARK: BUILDERS ---
+impl LevelMeter {
+    /// Create a level meter with a bar for each of `channels`, from -60 to 0 decibels.
+    pub fn new(channels: usize) -> Self {
+        let mut meter = Self {
+            channels: Vec::new(),
+            feed: None,
+            min_db: -60.0,
+            max_db: 0.0,
+        };
+        meter.resize_channels(channels);
+        meter
+    }
+
+    /// Builder-style method to show the levels of the samples sent to `feed`.
+    ///
+    /// The meter shows as many channels as the feed has.
+    pub fn with_feed(mut self, feed: AudioFeed) -> Self {
+        self.resize_channels(feed.channels());
+        self.feed = Some(feed);
+        self
+    }
+
+    /// Builder-style method to set the levels at the bottom and the top of the bars,
+    /// in decibels.
+    pub fn with_range(mut self, min_db: f64, max_db: f64) -> Self {
+        self.set_range_raw(min_db, max_db);
+        self
+    }
+
+    /// The number of channels.
+    pub fn channel_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// The level shown for `channel`, in decibels.
+    pub fn level(&self, channel: usize) -> f64 {
+        self.channels[channel].level
+    }
+
+    /// The peak held for `channel`, in decibels.
+    pub fn peak(&self, channel: usize) -> f64 {
+        self.channels[channel].peak
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl LevelMeter {
+    /// Show the levels of the samples sent to another feed, or stop reading them.
+    pub fn set_feed(this: &mut WidgetMut<'_, Self>, feed: Option<AudioFeed>) {
+        if let Some(feed) = &feed {
+            this.widget.resize_channels(feed.channels());
+        }
+        this.widget.feed = feed;
+        this.ctx.request_layout();
+        this.ctx.request_anim_frame();
+    }
+
+    /// Set the number of channels.
+    pub fn set_channel_count(this: &mut WidgetMut<'_, Self>, channels: usize) {
+        this.widget.resize_channels(channels);
+        this.ctx.request_layout();
+    }
+
+    /// Set the levels at the bottom and the top of the bars, in decibels.
+    pub fn set_range(this: &mut WidgetMut<'_, Self>, min_db: f64, max_db: f64) {
+        this.widget.set_range_raw(min_db, max_db);
+        this.ctx.request_paint_only();
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Show the peak amplitude of each channel, between 0 and 1, in the samples played since
+    /// the last call.
+    pub fn push_levels(this: &mut WidgetMut<'_, Self>, amplitudes: &[f32]) {
+        this.widget.apply(amplitudes, 0.0);
+        this.ctx.request_paint_only();
+        this.ctx.request_accessibility_update();
+        // The bars fall on the next frames.
+        this.ctx.request_anim_frame();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl LevelMeter {
+    fn resize_channels(&mut self, channels: usize) {
+        let silent = ChannelLevel {
+            level: self.min_db,
+            peak: self.min_db,
+            peak_age: 0.0,
+        };
+        self.channels.resize(channels.max(1), silent);
+    }
+
+    fn set_range_raw(&mut self, min_db: f64, max_db: f64) {
+        if max_db > min_db {
+            self.min_db = min_db;
+            self.max_db = max_db;
+            for channel in &mut self.channels {
+                channel.level = channel.level.max(min_db);
+                channel.peak = channel.peak.max(min_db);
+            }
+        }
+    }
+
+    /// Whether every bar is at the bottom.
+    fn is_silent(&self) -> bool {
+        self.channels
+            .iter()
+            .all(|channel| channel.level <= self.min_db && channel.peak <= self.min_db)
+    }
+
+    /// Let `elapsed` seconds pass, then show the peak `amplitudes` of the channels.
+    fn apply(&mut self, amplitudes: &[f32], elapsed: f64) {
+        for (idx, channel) in self.channels.iter_mut().enumerate() {
+            let amplitude = amplitudes.get(idx).copied().unwrap_or(0.0).abs() as f64;
+            let db = (20.0 * amplitude.log10()).clamp(self.min_db, self.max_db);
+            channel.level = (channel.level - FALL_RATE * elapsed)
+                .max(db)
+                .max(self.min_db);
+            channel.peak_age += elapsed;
+            if channel.peak_age > PEAK_HOLD {
+                channel.peak = (channel.peak - FALL_RATE * elapsed).max(self.min_db);
+            }
+            if db >= channel.peak {
+                channel.peak = db;
+                channel.peak_age = 0.0;
+            }
+        }
+    }
+
+    /// The peak amplitude of each channel in the samples sent to the feed.
+    fn read_feed(&self) -> Option<Vec<f32>> {
+        let feed = self.feed.as_ref()?;
+        let samples = feed.take();
+        let mut amplitudes = vec![0.0_f32; feed.channels()];
+        for frame in samples.chunks(feed.channels()) {
+            for (amplitude, sample) in amplitudes.iter_mut().zip(frame) {
+                *amplitude = amplitude.max(sample.abs());
+            }
+        }
+        Some(amplitudes)
+    }
+
+    fn y_for(&self, db: f64, height: f64) -> f64 {
+        let fraction = (db - self.min_db) / (self.max_db - self.min_db);
+        height * (1.0 - fraction.clamp(0.0, 1.0))
+    }
+
+    fn color_for(db: f64) -> Color {
+        if db > CRITICAL_LEVEL {
+            theme::METER_CRITICAL_COLOR
+        } else if db > WARNING_LEVEL {
+            theme::METER_SUBOPTIMAL_COLOR
+        } else {
+            theme::METER_OPTIMAL_COLOR
+        }
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for LevelMeter {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_anim_frame(&mut self, ctx: &mut UpdateCtx, interval: u64) {
+        let was_silent = self.is_silent();
+        let amplitudes = self.read_feed().unwrap_or_default();
+        self.apply(&amplitudes, interval as f64 * 1e-9);
+        if !(was_silent && self.is_silent()) {
+            ctx.request_paint_only();
+            ctx.request_accessibility_update();
+        }
+        if self.feed.is_some() || !self.is_silent() {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, event: &Update) {
+        match event {
+            Update::WidgetAdded => {
+                ctx.request_anim_frame();
+            }
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let count = self.channels.len() as f64;
+        let width = count * BAR_WIDTH + (count - 1.0) * BAR_GAP;
+        let height = if bc.max().height.is_finite() {
+            bc.max().height
+        } else {
+            DEFAULT_HEIGHT
+        };
+        bc.constrain(Size::new(width, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let count = self.channels.len() as f64;
+        let bar_width = (size.width - (count - 1.0) * BAR_GAP) / count;
+        let zones = [
+            (self.min_db, WARNING_LEVEL),
+            (WARNING_LEVEL, CRITICAL_LEVEL),
+            (CRITICAL_LEVEL, self.max_db),
+        ];
+        for (idx, channel) in self.channels.iter().enumerate() {
+            let x0 = idx as f64 * (bar_width + BAR_GAP);
+            let track = Rect::new(x0, 0.0, x0 + bar_width, size.height);
+            fill_color(scene, &track, theme::BACKGROUND_DARK);
+
+            // Each zone of the bar has its own color.
+            for (low, high) in zones {
+                let low = low.max(self.min_db);
+                let high = high.min(channel.level);
+                if high > low {
+                    let zone = Rect::new(
+                        x0,
+                        self.y_for(high, size.height),
+                        x0 + bar_width,
+                        self.y_for(low, size.height),
+                    );
+                    fill_color(scene, &zone, Self::color_for(high));
+                }
+            }
+
+            if channel.peak > self.min_db {
+                let y = self.y_for(channel.peak, size.height) + PEAK_LINE_WIDTH / 2.0;
+                stroke(
+                    scene,
+                    &Line::new((x0, y), (x0 + bar_width, y)),
+                    Self::color_for(channel.peak),
+                    PEAK_LINE_WIDTH,
+                );
+            }
+            stroke(scene, &track, theme::BORDER_DARK, 1.0);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Meter
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        let level = self
+            .channels
+            .iter()
+            .map(|channel| channel.level)
+            .fold(self.min_db, f64::max);
+        node.set_numeric_value(level);
+        node.set_min_numeric_value(self.min_db);
+        node.set_max_numeric_value(self.max_db);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("LevelMeter", id = ctx.widget_id().trace())
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        let levels: Vec<_> = self
+            .channels
+            .iter()
+            .map(|channel| format!("{:.1} dB", channel.level))
+            .collect();
+        Some(levels.join(", "))
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn levels_fall_and_peaks_hold() {
+        let feed = AudioFeed::new(2);
+        let mut harness = TestHarness::create(LevelMeter::new(1).with_feed(feed.clone()));
+
+        // The left channel is at half amplitude, the right one is silent.
+        feed.push(&[0.5, 0.0, -0.5, 0.0]);
+        harness.animate_ms(16);
+        let meter = harness.root_widget().downcast::<LevelMeter>().unwrap();
+        assert_eq!(meter.channel_count(), 2);
+        assert!((meter.level(0) + 6.02).abs() < 0.01);
+        assert_eq!(meter.level(1), -60.0);
+        assert_eq!(meter.peak(0), meter.level(0));
+
+        // The bar falls at once, the peak only after it was held.
+        harness.animate_ms(1000);
+        let meter = harness.root_widget().downcast::<LevelMeter>().unwrap();
+        assert!((meter.level(0) + 30.02).abs() < 0.01);
+        assert!((meter.peak(0) + 6.02).abs() < 0.01);
+
+        harness.animate_ms(1000);
+        let meter = harness.root_widget().downcast::<LevelMeter>().unwrap();
+        assert!((meter.level(0) + 54.02).abs() < 0.01);
+        assert!((meter.peak(0) + 30.02).abs() < 0.01);
+    }
+
+    #[test]
+    fn push_levels() {
+        let mut harness = TestHarness::create(LevelMeter::new(2).with_range(-40.0, 6.0));
+        harness.edit_root_widget(|mut meter| {
+            LevelMeter::push_levels(&mut meter.downcast::<LevelMeter>(), &[1.0, 4.0]);
+        });
+        let meter = harness.root_widget().downcast::<LevelMeter>().unwrap();
+        assert_eq!(meter.level(0), 0.0);
+        // Levels above the range are clamped.
+        assert_eq!(meter.level(1), 6.0);
+    }
+}
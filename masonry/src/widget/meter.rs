@@ -0,0 +1,310 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A gauge showing a level within a range.
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::Rect;
+use vello::peniko::Color;
+use vello::Scene;
+
+use crate::kurbo::Size;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent,
+    QueryCtx, RegisterCtx, TextEvent, Widget, WidgetId,
+};
+
+/// How good the value of a [`Meter`] is, compared to its optimum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeterLevel {
+    /// The value is in the same band as the optimum.
+    Optimal,
+    /// The value is in a band next to that of the optimum.
+    Suboptimal,
+    /// The value is in the band at the other end from that of the optimum.
+    Critical,
+}
+
+/// A gauge showing a value within a known range, like the HTML `<meter>` element.
+///
+/// Unlike a [`ProgressBar`](crate::widget::ProgressBar), this shows a level, e.g. of a
+/// battery, of the volume or of the disk usage.
+///
+/// The range can be split into low and high bands with [`with_low`](Self::with_low) and
+/// [`with_high`](Self::with_high). The bar is colored depending on how far the band of the
+/// value is from the band of the [optimum](Self::with_optimum).
+pub struct Meter {
+    value: f64,
+    min: f64,
+    max: f64,
+    low: Option<f64>,
+    high: Option<f64>,
+    optimum: Option<f64>,
+}
+
+// --- MARK: BUILDERS ---
+impl Meter {
+    /// Create a new meter showing `value`, in the range from 0 to 1.
+    pub fn new(value: f64) -> Self {
+        Self {
+            value,
+            min: 0.0,
+            max: 1.0,
+            low: None,
+            high: None,
+            optimum: None,
+        }
+    }
+
+    /// Builder-style method to set the range of the value.
+    ///
+    /// If `max` is less than `min`, the range is empty.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Builder-style method to set the value under which the value is in the low band.
+    pub fn with_low(mut self, low: f64) -> Self {
+        self.low = Some(low);
+        self
+    }
+
+    /// Builder-style method to set the value over which the value is in the high band.
+    pub fn with_high(mut self, high: f64) -> Self {
+        self.high = Some(high);
+        self
+    }
+
+    /// Builder-style method to set the best value.
+    ///
+    /// By default, this is the middle of the range, so the low and high bands are both worse
+    /// than the band between them.
+    pub fn with_optimum(mut self, optimum: f64) -> Self {
+        self.optimum = Some(optimum);
+        self
+    }
+
+    /// The value, clamped to the range.
+    pub fn value(&self) -> f64 {
+        self.value.clamp(self.min(), self.max())
+    }
+
+    /// The start of the range.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// The end of the range, which is never less than its start.
+    pub fn max(&self) -> f64 {
+        self.max.max(self.min)
+    }
+
+    /// The end of the low band, within the range.
+    pub fn low(&self) -> f64 {
+        self.low
+            .map_or(self.min(), |low| low.clamp(self.min(), self.max()))
+    }
+
+    /// The start of the high band, within the range and after the low band.
+    pub fn high(&self) -> f64 {
+        self.high
+            .map_or(self.max(), |high| high.clamp(self.low(), self.max()))
+    }
+
+    /// The best value, within the range.
+    pub fn optimum(&self) -> f64 {
+        self.optimum
+            .map_or((self.min() + self.max()) / 2.0, |optimum| {
+                optimum.clamp(self.min(), self.max())
+            })
+    }
+
+    /// How good the current value is.
+    pub fn level(&self) -> MeterLevel {
+        match self.band(self.value()).abs_diff(self.band(self.optimum())) {
+            0 => MeterLevel::Optimal,
+            1 => MeterLevel::Suboptimal,
+            _ => MeterLevel::Critical,
+        }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Meter {
+    /// Set the value shown.
+    pub fn set_value(this: &mut WidgetMut<'_, Self>, value: f64) {
+        this.widget.value = value;
+        this.ctx.request_render();
+    }
+
+    /// Set the range of the value, as in [`with_range`](Self::with_range).
+    pub fn set_range(this: &mut WidgetMut<'_, Self>, min: f64, max: f64) {
+        this.widget.min = min;
+        this.widget.max = max;
+        this.ctx.request_render();
+    }
+
+    /// Set or remove the end of the low band.
+    pub fn set_low(this: &mut WidgetMut<'_, Self>, low: Option<f64>) {
+        this.widget.low = low;
+        this.ctx.request_render();
+    }
+
+    /// Set or remove the start of the high band.
+    pub fn set_high(this: &mut WidgetMut<'_, Self>, high: Option<f64>) {
+        this.widget.high = high;
+        this.ctx.request_render();
+    }
+
+    /// Set the best value, or go back to the middle of the range.
+    pub fn set_optimum(this: &mut WidgetMut<'_, Self>, optimum: Option<f64>) {
+        this.widget.optimum = optimum;
+        this.ctx.request_render();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl Meter {
+    /// The band of `value`: 0 for the low band, 1 for the middle one and 2 for the high one.
+    fn band(&self, value: f64) -> u8 {
+        if value < self.low() {
+            0
+        } else if value > self.high() {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn band_color(&self, band: u8) -> Color {
+        match band.abs_diff(self.band(self.optimum())) {
+            0 => theme::METER_OPTIMAL_COLOR,
+            1 => theme::METER_SUBOPTIMAL_COLOR,
+            _ => theme::METER_CRITICAL_COLOR,
+        }
+    }
+
+    /// The position of `value` from the left of a meter of `width`.
+    fn position(&self, value: f64, width: f64) -> f64 {
+        let range = self.max() - self.min();
+        if range <= 0.0 {
+            return width;
+        }
+        (value - self.min()) / range * width
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Meter {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        bc.constrain(Size::new(
+            theme::WIDE_WIDGET_WIDTH,
+            ctx.density().basic_widget_height(),
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let border_width = 1.;
+        let size = ctx.size();
+        let track = size.to_rect().inset(-border_width / 2.).to_rounded_rect(2.);
+        fill_color(scene, &track, theme::BACKGROUND_DARK);
+
+        // The bands are tinted with the color the bar has when the value is in them.
+        let low = self.position(self.low(), size.width);
+        let high = self.position(self.high(), size.width);
+        let bands = [(0.0, low), (low, high), (high, size.width)];
+        for (band, (start, end)) in (0..).zip(bands) {
+            if end > start {
+                let rect = Rect::new(start, 0.0, end, size.height).inset(-border_width);
+                fill_color(scene, &rect, self.band_color(band).multiply_alpha(0.2));
+            }
+        }
+
+        let value = self.position(self.value(), size.width);
+        if value > 0.0 {
+            let bar = Rect::new(0.0, 0.0, value, size.height)
+                .inset(-border_width)
+                .to_rounded_rect(2.);
+            fill_color(scene, &bar, self.band_color(self.band(self.value())));
+        }
+
+        stroke(scene, &track, theme::BORDER_DARK, border_width);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Meter
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        node.set_numeric_value(self.value());
+        node.set_min_numeric_value(self.min());
+        node.set_max_numeric_value(self.max());
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Meter", id = ctx.widget_id().trace())
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("{}", self.value()))
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn levels() {
+        // A battery, which should be full.
+        let battery = |value| {
+            Meter::new(value)
+                .with_range(0.0, 100.0)
+                .with_low(20.0)
+                .with_high(80.0)
+                .with_optimum(100.0)
+        };
+        assert_eq!(battery(90.0).level(), MeterLevel::Optimal);
+        assert_eq!(battery(50.0).level(), MeterLevel::Suboptimal);
+        assert_eq!(battery(10.0).level(), MeterLevel::Critical);
+        assert_eq!(battery(150.0).value(), 100.0);
+
+        // Without bands, the whole range is optimal.
+        assert_eq!(Meter::new(0.0).level(), MeterLevel::Optimal);
+
+        // The bands stay within the range, in order.
+        let meter = Meter::new(0.5).with_low(0.8).with_high(0.2);
+        assert_eq!((meter.low(), meter.high()), (0.8, 0.8));
+    }
+
+    #[test]
+    fn set_value() {
+        let mut harness = TestHarness::create(Meter::new(0.5).with_high(0.75));
+        harness.edit_root_widget(|mut meter| {
+            Meter::set_value(&mut meter.downcast::<Meter>(), 0.9);
+        });
+        let meter = harness.root_widget().downcast::<Meter>().unwrap();
+        assert_eq!(meter.value(), 0.9);
+        assert_eq!(meter.level(), MeterLevel::Suboptimal);
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A scrollable widget with a thumbnail of its content, as seen in code editors.
+
+use accesskit::{Node, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::kurbo::{Affine, Vec2};
+use vello::peniko::Mix;
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{Portal, WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, AllowRawMut, BoxConstraints, CursorIcon, EventCtx, LayoutCtx,
+    PaintCtx, Point, PointerEvent, QueryCtx, Rect, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// A [`Portal`] scrolling over `child`, with a map on its right showing the whole child
+/// scaled down.
+///
+/// The map shows which part of the child is in the viewport. Clicking on the map, or
+/// dragging on it, centers the viewport on that point.
+///
+/// The map reuses the scene painted by the child, so the child isn't painted twice.
+pub struct Minimap<W: Widget> {
+    portal: WidgetPod<Portal<W>>,
+    map: WidgetPod<MapView>,
+    map_width: f64,
+}
+
+/// The thumbnail of the content of the portal.
+///
+/// This is a separate widget painted after the portal, so that the scene of the content
+/// is up to date when the map is painted.
+struct MapView {
+    content: WidgetId,
+    /// The position of the portal relative to the map.
+    portal_offset: Vec2,
+    portal_size: Size,
+    content_size: Size,
+}
+
+// --- MARK: BUILDERS ---
+impl<W: Widget> Minimap<W> {
+    /// The width of the map, unless set with [`with_map_width`](Self::with_map_width).
+    pub const DEFAULT_MAP_WIDTH: f64 = 100.0;
+
+    /// Create a new minimap scrolling over `child`.
+    pub fn new(child: W) -> Self {
+        Self::new_pod(WidgetPod::new(child))
+    }
+
+    /// Create a new minimap scrolling over the widget `child`.
+    pub fn new_pod(child: WidgetPod<W>) -> Self {
+        let map = MapView {
+            content: child.id(),
+            portal_offset: Vec2::ZERO,
+            portal_size: Size::ZERO,
+            content_size: Size::ZERO,
+        };
+        Self {
+            portal: WidgetPod::new(Portal::new_pod(child)),
+            map: WidgetPod::new(map),
+            map_width: Self::DEFAULT_MAP_WIDTH,
+        }
+    }
+
+    /// Builder-style method to set the width of the map.
+    pub fn with_map_width(mut self, width: f64) -> Self {
+        self.map_width = width;
+        self
+    }
+
+    /// The width of the map.
+    pub fn map_width(&self) -> f64 {
+        self.map_width
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl<W: Widget> Minimap<W> {
+    /// Set the width of the map.
+    pub fn set_map_width(this: &mut WidgetMut<'_, Self>, width: f64) {
+        this.widget.map_width = width;
+        this.ctx.request_layout();
+    }
+
+    /// Get mutable access to the portal scrolling over the child.
+    ///
+    /// The child itself is reached with [`Portal::child_mut`].
+    pub fn portal_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Portal<W>> {
+        this.ctx.get_mut(&mut this.widget.portal)
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl<W: Widget> Minimap<W> {
+    /// Center the viewport of the portal on the point of the content under `pos`.
+    fn navigate_to(&mut self, ctx: &mut EventCtx, pos: Point) {
+        let local_pos = pos - ctx.window_origin().to_vec2();
+        let map = ctx.get_raw_ref(&mut self.map);
+        let map_rect = map.ctx().layout_rect();
+        let portal_size = map.widget().portal_size;
+        let Some(content_to_map) = content_to_map(map.widget().content_size, map_rect.size())
+        else {
+            return;
+        };
+        let center = content_to_map.inverse() * (local_pos - map_rect.origin().to_vec2());
+        let viewport_pos = center - portal_size.to_vec2() / 2.0;
+        ctx.mutate_later(&mut self.portal, move |mut portal| {
+            Portal::set_viewport_pos(&mut portal, viewport_pos);
+        });
+    }
+}
+
+/// The transform from the coordinates of content of `content_size` to the coordinates of
+/// a map of `map_size` showing all of it, or `None` if the content is empty.
+fn content_to_map(content_size: Size, map_size: Size) -> Option<Affine> {
+    if content_size.is_zero_area() {
+        return None;
+    }
+    let scale = (map_size.width / content_size.width).min(map_size.height / content_size.height);
+    let x = (map_size.width - content_size.width * scale) / 2.0;
+    Some(Affine::translate((x, 0.0)) * Affine::scale(scale))
+}
+
+// --- MARK: IMPL WIDGET ---
+impl<W: Widget> Widget for Minimap<W> {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state)
+                if ctx.target() == self.map.id() =>
+            {
+                ctx.capture_pointer();
+                self.navigate_to(ctx, state.position);
+            }
+            PointerEvent::PointerMove(state) if ctx.has_pointer_capture() => {
+                self.navigate_to(ctx, state.position);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.portal);
+        ctx.register_child(&mut self.map);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let map_width = self.map_width.min(bc.max().width);
+        let portal_size = ctx.run_layout(&mut self.portal, &bc.shrink((map_width, 0.0)));
+        ctx.place_child(&mut self.portal, Point::ORIGIN);
+        let content_size = ctx
+            .get_raw_ref(&mut self.portal)
+            .widget()
+            .get_content_size();
+
+        let mut map = ctx.get_raw_mut(&mut self.map);
+        map.widget().portal_offset = Vec2::new(-portal_size.width, 0.0);
+        map.widget().portal_size = portal_size;
+        map.widget().content_size = content_size;
+        std::mem::drop(map);
+
+        let map_size = Size::new(map_width, portal_size.height);
+        ctx.run_layout(&mut self.map, &BoxConstraints::tight(map_size));
+        ctx.place_child(&mut self.map, Point::new(portal_size.width, 0.0));
+
+        Size::new(portal_size.width + map_width, portal_size.height)
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        // The map is painted after the portal.
+        smallvec![self.portal.id(), self.map.id()]
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Minimap", id = ctx.widget_id().trace())
+    }
+}
+
+impl Widget for MapView {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        ctx.mirror_scene(self.content);
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        fill_color(scene, &size.to_rect(), theme::BACKGROUND_DARK);
+        let Some(content_to_map) = content_to_map(self.content_size, size) else {
+            return;
+        };
+        let Some((content_scene, content_origin)) = ctx.mirrored_scene(self.content) else {
+            return;
+        };
+
+        // The mirrored scene is in window coordinates.
+        let transform = content_to_map * Affine::translate(-content_origin.to_vec2());
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &size.to_rect());
+        scene.append(content_scene, Some(transform));
+        scene.pop_layer();
+
+        // The content moves in the portal when it's scrolled, so the viewport is where the
+        // portal is relative to the content.
+        let portal_origin = ctx.window_origin() + self.portal_offset;
+        let viewport = Rect::from_origin_size(
+            (portal_origin - content_origin).to_point(),
+            self.portal_size,
+        );
+        let viewport = content_to_map.transform_rect_bbox(viewport);
+        fill_color(scene, &viewport, theme::PRIMARY_LIGHT.multiply_alpha(0.2));
+        stroke(scene, &viewport.inset(-0.5), theme::PRIMARY_LIGHT, 1.0);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        // The map only repeats the content of the portal.
+        node.set_hidden();
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn get_cursor(&self, _ctx: &QueryCtx, _pos: Point) -> CursorIcon {
+        CursorIcon::Pointer
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("MapView", id = ctx.widget_id().trace())
+    }
+}
+
+impl AllowRawMut for MapView {}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Flex, SizedBox};
+
+    #[test]
+    fn click_and_drag_on_map() {
+        let mut column = Flex::column();
+        for _ in 0..20 {
+            column = column.with_child(SizedBox::empty().width(100.).height(100.));
+        }
+        let widget = Minimap::new(column);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(300., 200.));
+
+        let viewport_pos = |harness: &TestHarness| {
+            let minimap = harness.root_widget();
+            let minimap = minimap.downcast::<Minimap<Flex>>().unwrap();
+            let portal = harness.get_widget(minimap.portal.id());
+            portal
+                .downcast::<Portal<Flex>>()
+                .unwrap()
+                .get_viewport_pos()
+        };
+
+        // The map is 100 by 200 and shows the 2000 high content at a tenth of its size.
+        // Clicking at 150 centers the viewport on 1500.
+        harness.mouse_move((250., 150.));
+        harness.mouse_button_press(PointerButton::Primary);
+        assert_eq!(viewport_pos(&harness).y, 1400.);
+
+        // Dragging keeps moving the viewport, until the top of the content.
+        harness.mouse_move((250., 5.));
+        assert_eq!(viewport_pos(&harness).y, 0.);
+        harness.mouse_button_release(PointerButton::Primary);
+    }
+}
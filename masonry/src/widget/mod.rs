@@ -14,48 +14,109 @@ mod widget_state;
 mod tests;
 
 mod align;
+mod audio_feed;
 mod button;
 mod checkbox;
+mod clickable;
+mod dock;
+mod filter_list;
 mod flex;
+mod form;
+mod form_field;
 mod grid;
+mod heatmap;
+mod hit_index;
 mod image;
 mod label;
+mod level_meter;
+mod meter;
+mod minimap;
+mod node_editor;
+mod page_viewer;
 mod portal;
 mod progress_bar;
 mod prose;
+mod render_viewport;
+mod reorderable_list;
 mod root_widget;
 mod scroll_bar;
+mod selectable;
 mod sized_box;
 mod spinner;
 mod split;
+mod status_bar;
+mod stepper;
+mod sticky_section;
+mod suspense;
 mod text_area;
 mod textbox;
+mod timeline;
+mod timer;
+mod title_bar;
 mod variable_label;
+mod video;
+mod virtual_keyboard;
+mod waveform;
+#[cfg(feature = "webview")]
+mod web_view;
 mod widget_arena;
+mod widget_ext;
+mod widget_pool;
 
 pub use self::image::Image;
 pub use align::Align;
-pub use button::Button;
+pub use audio_feed::AudioFeed;
+pub use button::{AutoRepeat, Button};
 pub use checkbox::Checkbox;
+pub use clickable::Clickable;
+pub use dock::{Dock, DockArea, DockEdge, DockLayout, DockLayoutChanged, FloatingPanel};
+pub use filter_list::{FilterList, FilterListAction};
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use form::Form;
+pub use form_field::FormField;
 pub use grid::{Grid, GridParams};
+pub use heatmap::{ColorScale, Heatmap};
 pub use label::{Label, LineBreaking};
-pub use portal::Portal;
+pub use level_meter::LevelMeter;
+pub use meter::{Meter, MeterLevel};
+pub use minimap::Minimap;
+pub use node_editor::{Edge, NodeEditor, NodeEditorAction, Port};
+pub use page_viewer::{PageSource, PageViewer, PageViewerAction, PageWord};
+pub use portal::{OverscrollBehavior, Portal, PortalAction, ScrollConfig, ScrollPosition};
 pub use progress_bar::ProgressBar;
 pub use prose::Prose;
+pub use render_viewport::{RenderViewport, ViewportTarget, VIEWPORT_FORMAT};
+pub use reorderable_list::{ReorderableList, RowMoved};
 pub use root_widget::RootWidget;
 pub use scroll_bar::ScrollBar;
+pub use selectable::Selectable;
 pub use sized_box::{Padding, SizedBox};
 pub use spinner::Spinner;
 pub use split::Split;
+pub use status_bar::StatusBar;
+pub use stepper::{Stepper, StepperAction};
+pub use sticky_section::StickySection;
+pub use suspense::Suspense;
 pub use text_area::TextArea;
 pub use textbox::Textbox;
+pub use timeline::{Timeline, TimelineItem, TimelineItemClicked};
+pub use timer::{Timer, TimerFired};
+pub use title_bar::TitleBar;
 pub use variable_label::VariableLabel;
+pub use video::{Video, VideoFrames, VideoStats};
+pub use virtual_keyboard::{KeyAction, KeyCap, KeyboardLayout, VirtualKeyboard};
+pub use waveform::{Waveform, WaveformSeek};
+#[cfg(feature = "webview")]
+pub use web_view::{WebView, WebViewRequest};
+pub use widget_ext::WidgetExt;
 pub use widget_mut::WidgetMut;
-pub use widget_pod::WidgetPod;
+pub use widget_pod::{HitTest, Visibility, WidgetPod};
 pub use widget_ref::WidgetRef;
+pub use widget_state::AccessOverrides;
 
+pub(crate) use render_viewport::ViewportCallback;
 pub(crate) use widget_arena::WidgetArena;
+pub(crate) use widget_pool::WidgetPool;
 pub(crate) use widget_state::WidgetState;
 
 use crate::{Affine, Size};
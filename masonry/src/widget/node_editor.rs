@@ -0,0 +1,914 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A canvas of nodes linked by edges, for visual tools such as shader or audio graphs.
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{Circle, CubicBez, Line, RoundedRect, RoundedRectRadii, Vec2};
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, ActionSource, BoxConstraints, CursorIcon, EventCtx, LayoutCtx,
+    PaintCtx, Point, PointerEvent, QueryCtx, Rect, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The size of the editor when its constraints are unbounded.
+const DEFAULT_SIZE: Size = Size::new(600.0, 400.0);
+/// The height of the bar at the top of a node, by which it's dragged.
+const HEADER_HEIGHT: f64 = 12.0;
+/// The space between the frame of a node and its content.
+const NODE_PADDING: f64 = 6.0;
+/// The radius of the corners of a node.
+const NODE_RADIUS: f64 = 4.0;
+/// The smallest vertical space between two ports on the same side of a node.
+const PORT_SPACING: f64 = 20.0;
+/// The radius of the circle drawn for a port.
+const PORT_RADIUS: f64 = 5.0;
+/// How far from the center of a port the pointer can be to grab it.
+const PORT_HIT_RADIUS: f64 = 9.0;
+/// The smallest horizontal distance between the ends of an edge and their control points.
+const EDGE_MIN_REACH: f64 = 40.0;
+/// The space between the lines of the background grid, at a zoom of 1.
+const GRID_SPACING: f64 = 24.0;
+/// The smallest space between the lines of the background grid, under which it isn't drawn.
+const MIN_GRID_SPACING: f64 = 8.0;
+/// The zoom range.
+const MIN_ZOOM: f64 = 0.25;
+const MAX_ZOOM: f64 = 4.0;
+/// How much the view is zoomed by a step of the mouse wheel.
+const ZOOM_STEP: f64 = 1.25;
+/// How far the view is panned by a line of horizontal wheel delta.
+const SCROLLING_SPEED: f64 = 10.0;
+/// How far the pointer must move before a pressed node is dragged.
+const DRAG_THRESHOLD: f64 = 3.0;
+
+/// A port of a node of a [`NodeEditor`], which is an input or an output depending on where
+/// it's used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Port {
+    /// The index of the node.
+    pub node: usize,
+    /// The index of the port among the inputs or outputs of the node, from the top.
+    pub index: usize,
+}
+
+impl Port {
+    /// The port `index` of the node `node`.
+    pub fn new(node: usize, index: usize) -> Self {
+        Self { node, index }
+    }
+}
+
+/// A link from an output port of a node of a [`NodeEditor`] to an input port.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Edge {
+    /// The output port, on the right of its node.
+    pub output: Port,
+    /// The input port, on the left of its node.
+    pub input: Port,
+}
+
+impl Edge {
+    /// An edge from `output` to `input`.
+    pub fn new(output: Port, input: Port) -> Self {
+        Self { output, input }
+    }
+}
+
+/// What the user did in a [`NodeEditor`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeEditorAction {
+    /// An edge was dragged between two ports.
+    ///
+    /// The editor doesn't add it itself, so that the app can check it first.
+    EdgeCreated(Edge),
+    /// Nodes were dragged to the given positions, which the editor already moved them to.
+    NodesMoved(Vec<(usize, Point)>),
+    /// The selected nodes changed to the given ones.
+    SelectionChanged(Vec<usize>),
+}
+
+struct GraphNode {
+    widget: WidgetPod<Box<dyn Widget>>,
+    /// The position of the top-left corner of the node, in the coordinates of the graph.
+    position: Point,
+    inputs: usize,
+    outputs: usize,
+    selected: bool,
+    /// The rectangle covered by the node, in the coordinates of the editor.
+    rect: Rect,
+}
+
+/// What the user is dragging.
+enum Drag {
+    /// The selected nodes, which were at `origins` when the drag started at `start`.
+    Nodes {
+        start: Point,
+        origins: Vec<(usize, Point)>,
+        moved: bool,
+    },
+    /// A new edge, from `port`.
+    Edge {
+        port: Port,
+        output: bool,
+        pointer: Point,
+    },
+    /// A selection rectangle, adding to the nodes which were selected when it started.
+    Marquee {
+        start: Point,
+        pointer: Point,
+        base: Vec<bool>,
+    },
+    /// The view, with the middle button.
+    Pan { last: Point },
+}
+
+/// A canvas of nodes hosting arbitrary widgets, linked by edges between their ports.
+///
+/// Each node has inputs on its left and outputs on its right. The user can:
+/// - Drag nodes by their header. Clicking a node selects it, and Shift toggles its selection.
+/// - Drag from a port to a port on the other side of another node, to create an edge.
+/// - Drag on the background to select the nodes within a rectangle.
+/// - Zoom with the mouse wheel, and pan with the middle button or a horizontal wheel.
+///
+/// Zooming scales the distances between nodes, but the nodes keep the size of their content.
+///
+/// Nodes and edges are identified by the index of their node, so removing a node changes
+/// the indices of the following ones.
+pub struct NodeEditor {
+    nodes: Vec<GraphNode>,
+    edges: Vec<Edge>,
+    /// The position of the origin of the graph, in the coordinates of the editor.
+    pan: Vec2,
+    zoom: f64,
+    drag: Option<Drag>,
+    /// The port under the pointer, and whether it's an output.
+    hovered_port: Option<(Port, bool)>,
+}
+
+// --- MARK: BUILDERS ---
+impl NodeEditor {
+    /// Create a new editor without nodes.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            pan: Vec2::ZERO,
+            zoom: 1.0,
+            drag: None,
+            hovered_port: None,
+        }
+    }
+
+    /// Builder-style method to add a node showing `widget` at `position`, with `inputs`
+    /// input ports and `outputs` output ports.
+    pub fn with_node(
+        self,
+        widget: impl Widget,
+        position: Point,
+        inputs: usize,
+        outputs: usize,
+    ) -> Self {
+        self.with_node_pod(WidgetPod::new(Box::new(widget)), position, inputs, outputs)
+    }
+
+    /// Builder-style method to add a node, as in [`with_node`](Self::with_node).
+    pub fn with_node_pod(
+        mut self,
+        widget: WidgetPod<Box<dyn Widget>>,
+        position: Point,
+        inputs: usize,
+        outputs: usize,
+    ) -> Self {
+        self.nodes
+            .push(GraphNode::new(widget, position, inputs, outputs));
+        self
+    }
+
+    /// Builder-style method to add an edge.
+    pub fn with_edge(mut self, edge: Edge) -> Self {
+        self.edges.push(edge);
+        self
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The position of the top-left corner of the node `idx`, in the coordinates of the graph.
+    pub fn node_position(&self, idx: usize) -> Point {
+        self.nodes[idx].position
+    }
+
+    /// The edges.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// The indices of the selected nodes, in order.
+    pub fn selection(&self) -> Vec<usize> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.selected)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// How much the graph is scaled.
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// The position of the origin of the graph, in the coordinates of the editor.
+    pub fn pan(&self) -> Vec2 {
+        self.pan
+    }
+}
+
+impl Default for NodeEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl NodeEditor {
+    /// Add a node after the others, as in [`with_node`](Self::with_node).
+    pub fn add_node(
+        this: &mut WidgetMut<'_, Self>,
+        widget: impl Widget,
+        position: Point,
+        inputs: usize,
+        outputs: usize,
+    ) {
+        Self::add_node_pod(
+            this,
+            WidgetPod::new(Box::new(widget)),
+            position,
+            inputs,
+            outputs,
+        );
+    }
+
+    /// Add a node after the others, as in [`with_node`](Self::with_node).
+    pub fn add_node_pod(
+        this: &mut WidgetMut<'_, Self>,
+        widget: WidgetPod<Box<dyn Widget>>,
+        position: Point,
+        inputs: usize,
+        outputs: usize,
+    ) {
+        let idx = this.widget.nodes.len();
+        Self::insert_node_pod(this, idx, widget, position, inputs, outputs);
+    }
+
+    /// Insert a node at `idx`, as in [`with_node`](Self::with_node).
+    ///
+    /// The following nodes move up by one index, and so do the ports of their edges.
+    pub fn insert_node_pod(
+        this: &mut WidgetMut<'_, Self>,
+        idx: usize,
+        widget: WidgetPod<Box<dyn Widget>>,
+        position: Point,
+        inputs: usize,
+        outputs: usize,
+    ) {
+        this.widget
+            .nodes
+            .insert(idx, GraphNode::new(widget, position, inputs, outputs));
+        for edge in &mut this.widget.edges {
+            for port in [&mut edge.output, &mut edge.input] {
+                if port.node >= idx {
+                    port.node += 1;
+                }
+            }
+        }
+        this.widget.drag = None;
+        this.widget.hovered_port = None;
+        this.ctx.children_changed();
+        this.ctx.request_layout();
+    }
+
+    /// Remove the node `idx` and its edges.
+    ///
+    /// The following nodes move down by one index, and so do the ports of their edges.
+    pub fn remove_node(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        let node = this.widget.nodes.remove(idx);
+        this.ctx.remove_child(node.widget);
+        this.widget
+            .edges
+            .retain(|edge| edge.output.node != idx && edge.input.node != idx);
+        for edge in &mut this.widget.edges {
+            for port in [&mut edge.output, &mut edge.input] {
+                if port.node > idx {
+                    port.node -= 1;
+                }
+            }
+        }
+        this.widget.drag = None;
+        this.widget.hovered_port = None;
+        this.ctx.request_layout();
+    }
+
+    /// Get a mutable reference to the widget of the node `idx`.
+    pub fn node_mut<'t>(
+        this: &'t mut WidgetMut<'_, Self>,
+        idx: usize,
+    ) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.nodes[idx].widget)
+    }
+
+    /// Move the node `idx` to `position`, in the coordinates of the graph.
+    pub fn set_node_position(this: &mut WidgetMut<'_, Self>, idx: usize, position: Point) {
+        this.widget.nodes[idx].position = position;
+        this.ctx.request_layout();
+    }
+
+    /// Set the number of input and output ports of the node `idx`.
+    ///
+    /// Edges to ports which no longer exist aren't drawn.
+    pub fn set_node_ports(
+        this: &mut WidgetMut<'_, Self>,
+        idx: usize,
+        inputs: usize,
+        outputs: usize,
+    ) {
+        let node = &mut this.widget.nodes[idx];
+        node.inputs = inputs;
+        node.outputs = outputs;
+        this.ctx.request_layout();
+    }
+
+    /// Replace the edges.
+    pub fn set_edges(this: &mut WidgetMut<'_, Self>, edges: impl IntoIterator<Item = Edge>) {
+        this.widget.edges = edges.into_iter().collect();
+        this.ctx.request_paint_only();
+    }
+
+    /// Select the nodes in `selection`, and deselect the others.
+    pub fn set_selection(this: &mut WidgetMut<'_, Self>, selection: &[usize]) {
+        for (idx, node) in this.widget.nodes.iter_mut().enumerate() {
+            node.selected = selection.contains(&idx);
+        }
+        this.ctx.request_paint_only();
+    }
+
+    /// Set how much the graph is scaled, and where its origin is in the editor.
+    pub fn set_view(this: &mut WidgetMut<'_, Self>, pan: Vec2, zoom: f64) {
+        this.widget.pan = pan;
+        this.widget.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        this.ctx.request_layout();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl GraphNode {
+    fn new(
+        widget: WidgetPod<Box<dyn Widget>>,
+        position: Point,
+        inputs: usize,
+        outputs: usize,
+    ) -> Self {
+        Self {
+            widget,
+            position,
+            inputs,
+            outputs,
+            selected: false,
+            rect: Rect::ZERO,
+        }
+    }
+
+    fn header_rect(&self) -> Rect {
+        Rect::new(
+            self.rect.x0,
+            self.rect.y0,
+            self.rect.x1,
+            self.rect.y0 + HEADER_HEIGHT,
+        )
+    }
+
+    /// The center of the port `index`, on the right for outputs and the left for inputs.
+    fn port_pos(&self, index: usize, output: bool) -> Option<Point> {
+        let count = if output { self.outputs } else { self.inputs };
+        if index >= count {
+            return None;
+        }
+        let body_height = self.rect.height() - HEADER_HEIGHT;
+        let x = if output { self.rect.x1 } else { self.rect.x0 };
+        let y = self.rect.y0 + HEADER_HEIGHT + (index as f64 + 0.5) * body_height / count as f64;
+        Some(Point::new(x, y))
+    }
+}
+
+impl NodeEditor {
+    /// The position in the editor of `pos`, in the coordinates of the graph.
+    fn to_local(&self, pos: Point) -> Point {
+        Point::ORIGIN + pos.to_vec2() * self.zoom + self.pan
+    }
+
+    /// The position in the graph of `pos`, in the coordinates of the editor.
+    fn to_graph(&self, pos: Point) -> Point {
+        Point::ORIGIN + (pos.to_vec2() - self.pan) / self.zoom
+    }
+
+    fn port_pos(&self, port: Port, output: bool) -> Option<Point> {
+        self.nodes.get(port.node)?.port_pos(port.index, output)
+    }
+
+    /// The port under `pos`, and whether it's an output, with the front nodes first.
+    fn port_at(&self, pos: Point) -> Option<(Port, bool)> {
+        self.nodes.iter().enumerate().rev().find_map(|(idx, node)| {
+            let inputs = (0..node.inputs).map(|index| (index, false));
+            let outputs = (0..node.outputs).map(|index| (index, true));
+            inputs.chain(outputs).find_map(|(index, output)| {
+                let center = node.port_pos(index, output)?;
+                ((center - pos).hypot() <= PORT_HIT_RADIUS)
+                    .then_some((Port::new(idx, index), output))
+            })
+        })
+    }
+
+    /// The front node under `pos`.
+    fn node_at(&self, pos: Point) -> Option<usize> {
+        self.nodes.iter().rposition(|node| node.rect.contains(pos))
+    }
+
+    /// The path of an edge from an output at `from` to an input at `to`.
+    fn edge_path(from: Point, to: Point) -> CubicBez {
+        let reach = Vec2::new(((to.x - from.x).abs() / 2.0).max(EDGE_MIN_REACH), 0.0);
+        CubicBez::new(from, from + reach, to - reach, to)
+    }
+
+    fn zoom_around(&mut self, pos: Point, factor: f64) {
+        let graph_pos = self.to_graph(pos);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan = pos.to_vec2() - graph_pos.to_vec2() * self.zoom;
+    }
+
+    fn submit_selection(&mut self, ctx: &mut EventCtx, old_selection: Vec<usize>) {
+        let selection = self.selection();
+        if selection != old_selection {
+            ctx.submit_typed_action(NodeEditorAction::SelectionChanged(selection));
+            ctx.request_paint_only();
+        }
+    }
+
+    fn on_pointer_down(&mut self, ctx: &mut EventCtx, pos: Point, toggle: bool) {
+        ctx.capture_pointer();
+        if let Some((port, output)) = self.port_at(pos) {
+            self.drag = Some(Drag::Edge {
+                port,
+                output,
+                pointer: pos,
+            });
+            return;
+        }
+
+        let old_selection = self.selection();
+        match self.node_at(pos) {
+            Some(hit) => {
+                if toggle {
+                    self.nodes[hit].selected ^= true;
+                } else if !self.nodes[hit].selected {
+                    for (idx, node) in self.nodes.iter_mut().enumerate() {
+                        node.selected = idx == hit;
+                    }
+                }
+                let origins = self
+                    .nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, node)| node.selected)
+                    .map(|(idx, node)| (idx, node.position))
+                    .collect();
+                self.drag = Some(Drag::Nodes {
+                    start: pos,
+                    origins,
+                    moved: false,
+                });
+            }
+            None => {
+                if !toggle {
+                    for node in &mut self.nodes {
+                        node.selected = false;
+                    }
+                }
+                self.drag = Some(Drag::Marquee {
+                    start: pos,
+                    pointer: pos,
+                    base: self.nodes.iter().map(|node| node.selected).collect(),
+                });
+            }
+        }
+        self.submit_selection(ctx, old_selection);
+    }
+
+    fn on_pointer_move(&mut self, ctx: &mut EventCtx, pos: Point) {
+        let old_selection = self.selection();
+        match &mut self.drag {
+            Some(Drag::Nodes {
+                start,
+                origins,
+                moved,
+            }) => {
+                *moved |= (pos - *start).hypot() > DRAG_THRESHOLD;
+                if *moved {
+                    let delta = (pos - *start) / self.zoom;
+                    for &(idx, origin) in origins.iter() {
+                        self.nodes[idx].position = origin + delta;
+                    }
+                    ctx.request_layout();
+                }
+            }
+            Some(Drag::Edge { pointer, .. }) => {
+                *pointer = pos;
+                ctx.request_paint_only();
+            }
+            Some(Drag::Marquee {
+                start,
+                pointer,
+                base,
+            }) => {
+                *pointer = pos;
+                let marquee = Rect::from_points(*start, pos);
+                for (node, &selected) in self.nodes.iter_mut().zip(base.iter()) {
+                    node.selected = selected || node.rect.intersect(marquee).area() > 0.0;
+                }
+                ctx.request_paint_only();
+            }
+            Some(Drag::Pan { last }) => {
+                self.pan += pos - *last;
+                *last = pos;
+                ctx.request_layout();
+            }
+            None => (),
+        }
+        self.submit_selection(ctx, old_selection);
+    }
+
+    fn on_pointer_up(&mut self, ctx: &mut EventCtx, pos: Point) {
+        match self.drag.take() {
+            Some(Drag::Nodes {
+                origins,
+                moved: true,
+                ..
+            }) => {
+                let moves = origins
+                    .iter()
+                    .map(|&(idx, _)| (idx, self.nodes[idx].position))
+                    .collect();
+                ctx.submit_typed_action(NodeEditorAction::NodesMoved(moves));
+            }
+            Some(Drag::Edge { port, output, .. }) => {
+                let target = self.port_at(pos);
+                if let Some((target, target_output)) = target {
+                    if target_output != output && target.node != port.node {
+                        let edge = if output {
+                            Edge::new(port, target)
+                        } else {
+                            Edge::new(target, port)
+                        };
+                        ctx.submit_typed_action(NodeEditorAction::EdgeCreated(edge));
+                    }
+                }
+            }
+            _ => (),
+        }
+        ctx.request_paint_only();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for NodeEditor {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            // Presses on the content of nodes are left to it.
+            PointerEvent::PointerDown(PointerButton::Primary, state)
+                if ctx.target() == ctx.widget_id() =>
+            {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                let toggle = state.mods.state().shift_key();
+                self.on_pointer_down(ctx, pos, toggle);
+            }
+            PointerEvent::PointerDown(PointerButton::Auxiliary, state) => {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                self.drag = Some(Drag::Pan { last: pos });
+                ctx.capture_pointer();
+            }
+            PointerEvent::PointerMove(state) => {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                if ctx.has_pointer_capture() {
+                    self.on_pointer_move(ctx, pos);
+                }
+                let hovered_port = self.port_at(pos);
+                if hovered_port != self.hovered_port {
+                    self.hovered_port = hovered_port;
+                    ctx.request_paint_only();
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary | PointerButton::Auxiliary, state)
+                if ctx.has_pointer_capture() =>
+            {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                self.on_pointer_up(ctx, pos);
+            }
+            PointerEvent::PointerLeave(_) => {
+                if self.hovered_port.take().is_some() {
+                    ctx.request_paint_only();
+                }
+            }
+            PointerEvent::MouseWheel(delta, state) => {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                let delta = delta.to_pixels(SCROLLING_SPEED);
+                if delta.y != 0.0 {
+                    let factor = if delta.y > 0.0 {
+                        1.0 / ZOOM_STEP
+                    } else {
+                        ZOOM_STEP
+                    };
+                    self.zoom_around(pos, factor);
+                }
+                self.pan.x -= delta.x;
+                ctx.request_layout();
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        for node in &mut self.nodes {
+            ctx.register_child(&mut node.widget);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let max = bc.max();
+        let size = bc.constrain(Size::new(
+            if max.width.is_finite() {
+                max.width
+            } else {
+                DEFAULT_SIZE.width
+            },
+            if max.height.is_finite() {
+                max.height
+            } else {
+                DEFAULT_SIZE.height
+            },
+        ));
+
+        for idx in 0..self.nodes.len() {
+            let origin = self.to_local(self.nodes[idx].position);
+            let node = &mut self.nodes[idx];
+            let content_size = ctx.run_layout(&mut node.widget, &BoxConstraints::UNBOUNDED);
+            let ports = node.inputs.max(node.outputs) as f64 * PORT_SPACING;
+            let body_height = (content_size.height + 2.0 * NODE_PADDING).max(ports);
+            node.rect = Rect::from_origin_size(
+                origin,
+                Size::new(
+                    content_size.width + 2.0 * NODE_PADDING,
+                    HEADER_HEIGHT + body_height,
+                ),
+            );
+            let content_y = HEADER_HEIGHT + (body_height - content_size.height) / 2.0;
+            ctx.place_child(
+                &mut node.widget,
+                origin + Vec2::new(NODE_PADDING, content_y),
+            );
+        }
+
+        // Nodes can be dragged past the edges of the editor.
+        ctx.set_clip_path(size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        fill_color(scene, &size.to_rect(), theme::BACKGROUND_DARK);
+
+        let spacing = GRID_SPACING * self.zoom;
+        if spacing >= MIN_GRID_SPACING {
+            let mut x = self.pan.x.rem_euclid(spacing);
+            while x < size.width {
+                let line = Line::new((x, 0.0), (x, size.height));
+                stroke(scene, &line, theme::BACKGROUND_LIGHT, 1.0);
+                x += spacing;
+            }
+            let mut y = self.pan.y.rem_euclid(spacing);
+            while y < size.height {
+                let line = Line::new((0.0, y), (size.width, y));
+                stroke(scene, &line, theme::BACKGROUND_LIGHT, 1.0);
+                y += spacing;
+            }
+        }
+
+        for edge in &self.edges {
+            let (Some(from), Some(to)) = (
+                self.port_pos(edge.output, true),
+                self.port_pos(edge.input, false),
+            ) else {
+                continue;
+            };
+            let selected =
+                self.nodes[edge.output.node].selected || self.nodes[edge.input.node].selected;
+            let color = if selected {
+                theme::PRIMARY_LIGHT
+            } else {
+                theme::FOREGROUND_DARK
+            };
+            stroke(scene, &Self::edge_path(from, to), color, 2.0);
+        }
+        if let Some(Drag::Edge {
+            port,
+            output,
+            pointer,
+        }) = &self.drag
+        {
+            if let Some(port_pos) = self.port_pos(*port, *output) {
+                let (from, to) = if *output {
+                    (port_pos, *pointer)
+                } else {
+                    (*pointer, port_pos)
+                };
+                stroke(scene, &Self::edge_path(from, to), theme::PRIMARY_LIGHT, 2.0);
+            }
+        }
+
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let frame = node.rect.to_rounded_rect(NODE_RADIUS);
+            fill_color(scene, &frame, theme::BACKGROUND_LIGHT);
+            let header = RoundedRect::from_rect(
+                node.header_rect(),
+                RoundedRectRadii::new(NODE_RADIUS, NODE_RADIUS, 0.0, 0.0),
+            );
+            fill_color(scene, &header, theme::BUTTON_LIGHT);
+            if node.selected {
+                stroke(scene, &frame, theme::PRIMARY_LIGHT, 2.0);
+            } else {
+                stroke(scene, &frame, theme::BORDER_LIGHT, 1.0);
+            }
+
+            let inputs = (0..node.inputs).map(|index| (index, false));
+            let outputs = (0..node.outputs).map(|index| (index, true));
+            for (index, output) in inputs.chain(outputs) {
+                let Some(center) = node.port_pos(index, output) else {
+                    continue;
+                };
+                let hovered = self.hovered_port == Some((Port::new(idx, index), output));
+                let color = if hovered {
+                    theme::PRIMARY_LIGHT
+                } else {
+                    theme::FOREGROUND_DARK
+                };
+                fill_color(scene, &Circle::new(center, PORT_RADIUS), color);
+            }
+        }
+
+        if let Some(Drag::Marquee { start, pointer, .. }) = &self.drag {
+            let marquee = Rect::from_points(*start, *pointer);
+            fill_color(scene, &marquee, theme::PRIMARY_DARK.multiply_alpha(0.2));
+            stroke(scene, &marquee, theme::PRIMARY_LIGHT, 1.0);
+        }
+    }
+
+    fn get_cursor(&self, ctx: &QueryCtx, pos: Point) -> CursorIcon {
+        let local_pos = pos - ctx.window_origin().to_vec2();
+        match &self.drag {
+            Some(Drag::Nodes { moved: true, .. } | Drag::Pan { .. }) => CursorIcon::Grabbing,
+            Some(Drag::Edge { .. }) => CursorIcon::Crosshair,
+            _ if self.port_at(local_pos).is_some() => CursorIcon::Crosshair,
+            _ if self
+                .nodes
+                .iter()
+                .any(|node| node.header_rect().contains(local_pos)) =>
+            {
+                CursorIcon::Grab
+            }
+            _ => CursorIcon::Default,
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.nodes.iter().map(|node| node.widget.id()).collect()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("NodeEditor", id = ctx.widget_id().trace())
+    }
+}
+
+impl ActionSource for NodeEditor {
+    type Action = NodeEditorAction;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::SizedBox;
+
+    /// Two nodes with 40 by 20 content, whose output and input ports face each other at
+    /// (52, 28) and (200, 28).
+    fn editor() -> NodeEditor {
+        let content = || SizedBox::empty().width(40.).height(20.);
+        NodeEditor::new()
+            .with_node(content(), Point::new(0., 0.), 0, 1)
+            .with_node(content(), Point::new(200., 0.), 1, 0)
+    }
+
+    fn actions(harness: &mut TestHarness) -> Vec<NodeEditorAction> {
+        std::iter::from_fn(|| harness.pop_action())
+            .filter_map(|(action, _)| action.downcast::<NodeEditorAction>().ok())
+            .collect()
+    }
+
+    #[test]
+    fn create_edges() {
+        let mut harness = TestHarness::create_with_size(editor(), Size::new(400., 400.));
+        let edge = Edge::new(Port::new(0, 0), Port::new(1, 0));
+
+        harness.drag_from_to((52., 28.), (200., 28.));
+        assert_eq!(actions(&mut harness), [NodeEditorAction::EdgeCreated(edge)]);
+
+        // Edges can be dragged from inputs too, but not between two inputs.
+        harness.drag_from_to((200., 28.), (52., 28.));
+        assert_eq!(actions(&mut harness), [NodeEditorAction::EdgeCreated(edge)]);
+        harness.drag_from_to((200., 28.), (100., 100.));
+        assert!(actions(&mut harness).is_empty());
+
+        harness.edit_root_widget(|mut editor| {
+            NodeEditor::set_edges(&mut editor.downcast::<NodeEditor>(), [edge]);
+        });
+        harness.edit_root_widget(|mut editor| {
+            NodeEditor::remove_node(&mut editor.downcast::<NodeEditor>(), 0);
+        });
+        let editor = harness.root_widget().downcast::<NodeEditor>().unwrap();
+        assert_eq!(editor.len(), 1);
+        assert!(editor.edges().is_empty());
+    }
+
+    #[test]
+    fn drag_and_select() {
+        let mut harness = TestHarness::create_with_size(editor(), Size::new(400., 400.));
+
+        harness.drag_from_to((20., 4.), (70., 54.));
+        assert_eq!(
+            actions(&mut harness),
+            [
+                NodeEditorAction::SelectionChanged(vec![0]),
+                NodeEditorAction::NodesMoved(vec![(0, Point::new(50., 50.))]),
+            ]
+        );
+
+        harness.drag_from_to((300., 300.), (1., 1.));
+        let editor = harness.root_widget().downcast::<NodeEditor>().unwrap();
+        assert_eq!(editor.selection(), [0, 1]);
+
+        // Clicking the background clears the selection.
+        harness.drag_from_to((300., 300.), (300., 300.));
+        let editor = harness.root_widget().downcast::<NodeEditor>().unwrap();
+        assert!(editor.selection().is_empty());
+    }
+
+    #[test]
+    fn zoom_around_pointer() {
+        let mut harness = TestHarness::create_with_size(editor(), Size::new(400., 400.));
+        harness.scroll_at((100., 100.), Vec2::new(0., -1.));
+
+        let editor = harness.root_widget().downcast::<NodeEditor>().unwrap();
+        assert_eq!(editor.zoom(), ZOOM_STEP);
+        // The point under the pointer stays in place.
+        assert_eq!(
+            editor.to_local(Point::new(100., 100.)),
+            Point::new(100., 100.)
+        );
+        assert_eq!(
+            editor.to_local(Point::new(200., 0.)),
+            Point::new(225., -25.)
+        );
+    }
+}
@@ -0,0 +1,785 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A viewer for paginated documents, such as PDF files.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{Affine, Vec2};
+use vello::peniko::{Color, Image as ImageBuf, Mix};
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{Axis, ScrollBar, ScrollConfig, WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, ActionSource, BoxConstraints, EventCtx, LayoutCtx, PaintCtx,
+    Point, PointerEvent, QueryCtx, Rect, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The size of the viewer when its constraints are unbounded.
+const DEFAULT_SIZE: Size = Size::new(600.0, 600.0);
+/// The space around the pages.
+const PAGE_GAP: f64 = 12.0;
+/// The width of the thumbnails in the sidebar.
+const THUMBNAIL_WIDTH: f64 = 96.0;
+/// The space around the thumbnails.
+const THUMBNAIL_GAP: f64 = 8.0;
+/// The width of the sidebar showing the thumbnails.
+const SIDEBAR_WIDTH: f64 = THUMBNAIL_WIDTH + 2.0 * THUMBNAIL_GAP;
+/// The zoom range.
+const MIN_ZOOM: f64 = 0.1;
+const MAX_ZOOM: f64 = 8.0;
+/// How much the pages are zoomed by a step of the mouse wheel.
+const ZOOM_STEP: f64 = 1.25;
+/// How far the pointer must move before a press on the text starts selecting it.
+const DRAG_THRESHOLD: f64 = 3.0;
+
+/// A run of text on a page, usually a word, which the user can select in a [`PageViewer`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageWord {
+    /// The bounds of the text, in the coordinates of its page at a zoom of 1.
+    pub rect: Rect,
+    /// The text.
+    pub text: String,
+}
+
+impl PageWord {
+    /// A run of `text` within `rect`.
+    pub fn new(rect: Rect, text: impl Into<String>) -> Self {
+        Self {
+            rect,
+            text: text.into(),
+        }
+    }
+}
+
+/// The pages shown by a [`PageViewer`], such as the output of a PDF renderer.
+///
+/// Pages are only rendered when they come into view, during the paint pass, so rendering
+/// should be fast, or cached by the source.
+pub trait PageSource: Send + Sync + 'static {
+    /// The number of pages.
+    fn page_count(&self) -> usize;
+
+    /// The size of the page `page`, in logical pixels at a zoom of 1.
+    fn page_size(&self, page: usize) -> Size;
+
+    /// An image of the page `page`, rendered at `scale` times its size.
+    ///
+    /// An image of another size is stretched to the size of the page.
+    fn render_page(&self, page: usize, scale: f64) -> ImageBuf;
+
+    /// The text of the page `page`, in reading order, which the user can select.
+    ///
+    /// By default, pages have no selectable text.
+    fn page_text(&self, page: usize) -> Vec<PageWord> {
+        let _ = page;
+        Vec::new()
+    }
+}
+
+/// Pages given as images, e.g. rendered ahead of time by the app, which are one logical pixel
+/// per pixel at a zoom of 1.
+impl PageSource for Vec<ImageBuf> {
+    fn page_count(&self) -> usize {
+        self.len()
+    }
+
+    fn page_size(&self, page: usize) -> Size {
+        Size::new(self[page].width as f64, self[page].height as f64)
+    }
+
+    fn render_page(&self, page: usize, _scale: f64) -> ImageBuf {
+        self[page].clone()
+    }
+}
+
+/// What the user did in a [`PageViewer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PageViewerAction {
+    /// The page at the top of the view changed to the given one.
+    PageChanged(usize),
+    /// The user selected the given text, with words separated by spaces and pages by new lines.
+    TextSelected(String),
+}
+
+/// A word of a page, as its page and its index in the page's text.
+type WordPos = (usize, usize);
+
+/// A press on the text of a page.
+struct Press {
+    start: Point,
+    moved: bool,
+}
+
+/// A viewer for paginated documents, showing the pages of a [`PageSource`] one below
+/// the other.
+///
+/// The user scrolls through the pages with the mouse wheel or the scrollbar, zooms with
+/// Ctrl and the mouse wheel, and goes to a page by clicking its thumbnail in the sidebar.
+/// Dragging over the [text](PageSource::page_text) of the pages selects it.
+///
+/// Only the pages and thumbnails in view are rendered.
+pub struct PageViewer {
+    source: Arc<dyn PageSource>,
+    zoom: f64,
+    show_thumbnails: bool,
+    /// The position of the view in the pages.
+    scroll: Vec2,
+    /// The position of the view in the thumbnails.
+    thumbnail_scroll: f64,
+    current_page: usize,
+    /// The page to scroll to on the next layout.
+    go_to_page: Option<usize>,
+    /// The rectangles of the pages from the top-left of the pages, from the last layout.
+    page_rects: Vec<Rect>,
+    /// The size of the pages with the space around them.
+    content_size: Size,
+    /// The rectangles of the thumbnails from the top of the sidebar, from the last layout.
+    thumbnail_rects: Vec<Rect>,
+    thumbnails_height: f64,
+    /// The pages in view on the last paint, with the zoom they were rendered at.
+    rendered: HashMap<usize, (f64, ImageBuf)>,
+    /// The thumbnails in view on the last paint.
+    thumbnails: HashMap<usize, ImageBuf>,
+    /// The text of the pages which were selected, by page.
+    words: HashMap<usize, Vec<PageWord>>,
+    /// The word where the selection started, and the one where it ends.
+    selection: Option<(WordPos, WordPos)>,
+    press: Option<Press>,
+    scrollbar: WidgetPod<ScrollBar>,
+}
+
+// --- MARK: BUILDERS ---
+impl PageViewer {
+    /// Create a new viewer showing the pages of `source`.
+    pub fn new(source: Arc<dyn PageSource>) -> Self {
+        Self {
+            source,
+            zoom: 1.0,
+            show_thumbnails: true,
+            scroll: Vec2::ZERO,
+            thumbnail_scroll: 0.0,
+            current_page: 0,
+            go_to_page: None,
+            page_rects: Vec::new(),
+            content_size: Size::ZERO,
+            thumbnail_rects: Vec::new(),
+            thumbnails_height: 0.0,
+            rendered: HashMap::new(),
+            thumbnails: HashMap::new(),
+            words: HashMap::new(),
+            selection: None,
+            press: None,
+            scrollbar: WidgetPod::new(ScrollBar::new(Axis::Vertical, 1.0, 1.0)),
+        }
+    }
+
+    /// Builder-style method to set how much the pages are scaled.
+    pub fn with_zoom(mut self, zoom: f64) -> Self {
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        self
+    }
+
+    /// Builder-style method to show `page` at the top of the view.
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.go_to_page = Some(page);
+        self
+    }
+
+    /// Builder-style method to show or hide the sidebar with the thumbnails of the pages.
+    ///
+    /// It's shown by default.
+    pub fn with_thumbnails(mut self, show_thumbnails: bool) -> Self {
+        self.show_thumbnails = show_thumbnails;
+        self
+    }
+
+    /// How much the pages are scaled.
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// The number of pages.
+    pub fn page_count(&self) -> usize {
+        self.source.page_count()
+    }
+
+    /// The page at the top of the view.
+    pub fn current_page(&self) -> usize {
+        self.current_page
+    }
+
+    /// The selected text, with words separated by spaces and pages by new lines.
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection_range()?;
+        let pages = (start.0..=end.0).map(|page| {
+            let words = match self.words.get(&page) {
+                Some(words) => Cow::Borrowed(words.as_slice()),
+                None => Cow::Owned(self.source.page_text(page)),
+            };
+            let first = if page == start.0 { start.1 } else { 0 };
+            let last = if page == end.0 {
+                end.1 + 1
+            } else {
+                words.len()
+            };
+            words[first..last.min(words.len())]
+                .iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        Some(pages.collect::<Vec<_>>().join("\n"))
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl PageViewer {
+    /// Show the pages of another source, from the first page.
+    pub fn set_source(this: &mut WidgetMut<'_, Self>, source: Arc<dyn PageSource>) {
+        this.widget.source = source;
+        this.widget.scroll = Vec2::ZERO;
+        this.widget.thumbnail_scroll = 0.0;
+        Self::clear_caches(this);
+    }
+
+    /// Render the pages again, e.g. because the source changed them.
+    pub fn reload(this: &mut WidgetMut<'_, Self>) {
+        Self::clear_caches(this);
+    }
+
+    /// Set how much the pages are scaled.
+    pub fn set_zoom(this: &mut WidgetMut<'_, Self>, zoom: f64) {
+        this.widget.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        this.ctx.request_layout();
+    }
+
+    /// Show or hide the sidebar with the thumbnails of the pages.
+    pub fn set_thumbnails(this: &mut WidgetMut<'_, Self>, show_thumbnails: bool) {
+        this.widget.show_thumbnails = show_thumbnails;
+        this.ctx.request_layout();
+    }
+
+    /// Scroll to the top of `page`.
+    pub fn go_to_page(this: &mut WidgetMut<'_, Self>, page: usize) {
+        // The pages may have changed size since the last layout.
+        this.widget.go_to_page = Some(page);
+        this.ctx.request_layout();
+    }
+
+    /// Clear the selection.
+    pub fn clear_selection(this: &mut WidgetMut<'_, Self>) {
+        this.widget.selection = None;
+        this.ctx.request_paint_only();
+    }
+
+    fn clear_caches(this: &mut WidgetMut<'_, Self>) {
+        this.widget.rendered.clear();
+        this.widget.thumbnails.clear();
+        this.widget.words.clear();
+        this.widget.selection = None;
+        this.widget.press = None;
+        this.ctx.request_layout();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl PageViewer {
+    fn sidebar_width(&self) -> f64 {
+        if self.show_thumbnails {
+            SIDEBAR_WIDTH
+        } else {
+            0.0
+        }
+    }
+
+    /// The part of the viewer showing the pages.
+    fn pages_area(&self, size: Size) -> Rect {
+        Rect::new(self.sidebar_width(), 0.0, size.width, size.height)
+    }
+
+    /// The offset from the coordinates of the pages to those of the viewer.
+    fn pages_offset(&self, size: Size) -> Vec2 {
+        self.pages_area(size).origin().to_vec2() - self.scroll
+    }
+
+    /// The items of `rects`, which are sorted vertically, overlapping `top..bottom`.
+    fn visible(rects: &[Rect], top: f64, bottom: f64) -> Range<usize> {
+        let start = rects.partition_point(|rect| rect.y1 <= top);
+        let end = rects.partition_point(|rect| rect.y0 < bottom);
+        start..end.max(start)
+    }
+
+    fn scroll_to_page(&mut self, page: usize) {
+        if let Some(rect) = self.page_rects.get(page) {
+            self.scroll.y = rect.y0 - PAGE_GAP;
+        }
+    }
+
+    fn zoom_around(&mut self, size: Size, pos: Point, factor: f64) {
+        let old_zoom = self.zoom;
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let pointer = pos - self.pages_area(size).origin();
+        self.scroll = (self.scroll + pointer) * (self.zoom / old_zoom) - pointer;
+    }
+
+    fn load_words(&mut self, page: usize) -> &[PageWord] {
+        let source = &self.source;
+        self.words
+            .entry(page)
+            .or_insert_with(|| source.page_text(page))
+    }
+
+    /// The word nearest to `pos`, if it's on a page with text.
+    fn word_at(&mut self, size: Size, pos: Point) -> Option<WordPos> {
+        let pos = pos - self.pages_offset(size);
+        let page = self.page_rects.iter().position(|rect| rect.contains(pos))?;
+        let local_pos = Point::ORIGIN + (pos - self.page_rects[page].origin()) / self.zoom;
+        let distance = |rect: Rect| {
+            let nearest = Point::new(
+                local_pos.x.clamp(rect.x0, rect.x1),
+                local_pos.y.clamp(rect.y0, rect.y1),
+            );
+            (nearest - local_pos).hypot2()
+        };
+        let words = self.load_words(page);
+        let word = (0..words.len())
+            .min_by(|&a, &b| distance(words[a].rect).total_cmp(&distance(words[b].rect)))?;
+        Some((page, word))
+    }
+
+    /// The first and last selected words, in reading order.
+    fn selection_range(&self) -> Option<(WordPos, WordPos)> {
+        let (anchor, focus) = self.selection?;
+        Some((anchor.min(focus), anchor.max(focus)))
+    }
+
+    /// Draw `image` stretched over `rect`.
+    fn draw_image(scene: &mut Scene, image: &ImageBuf, rect: Rect) {
+        if image.width == 0 || image.height == 0 {
+            return;
+        }
+        let transform = Affine::translate(rect.origin().to_vec2())
+            * Affine::scale_non_uniform(
+                rect.width() / image.width as f64,
+                rect.height() / image.height as f64,
+            );
+        scene.draw_image(image, transform);
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for PageViewer {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let size = ctx.size();
+        match event {
+            // Presses on the scrollbar are left to it.
+            PointerEvent::PointerDown(PointerButton::Primary, state)
+                if ctx.target() == ctx.widget_id() =>
+            {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                if pos.x < self.sidebar_width() {
+                    let pos = pos + Vec2::new(0.0, self.thumbnail_scroll);
+                    if let Some(page) = self.thumbnail_rects.iter().position(|r| r.contains(pos)) {
+                        self.scroll_to_page(page);
+                        ctx.request_layout();
+                    }
+                } else if let Some(word) = self.word_at(size, pos) {
+                    self.selection = Some((word, word));
+                    self.press = Some(Press {
+                        start: pos,
+                        moved: false,
+                    });
+                    ctx.capture_pointer();
+                } else if self.selection.take().is_some() {
+                    ctx.request_paint_only();
+                }
+            }
+            PointerEvent::PointerMove(state) if ctx.has_pointer_capture() => {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                if let Some(press) = &mut self.press {
+                    press.moved |= (pos - press.start).hypot() > DRAG_THRESHOLD;
+                    if press.moved {
+                        if let (Some(word), Some((_, focus))) =
+                            (self.word_at(size, pos), &mut self.selection)
+                        {
+                            *focus = word;
+                        }
+                        ctx.request_paint_only();
+                    }
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, _) => {
+                if let Some(press) = self.press.take() {
+                    if press.moved {
+                        if let Some(text) = self.selected_text() {
+                            ctx.submit_typed_action(PageViewerAction::TextSelected(text));
+                        }
+                    } else {
+                        // A click on the text clears the selection.
+                        self.selection = None;
+                    }
+                    ctx.request_paint_only();
+                }
+            }
+            PointerEvent::MouseWheel(delta, state) => {
+                let pos = state.position - ctx.window_origin().to_vec2();
+                let mods = state.mods.state();
+                let scrolled = ScrollConfig::default().viewport_delta(*delta, mods.shift_key());
+                if mods.control_key() {
+                    if scrolled.y != 0.0 {
+                        // Scrolling up zooms out.
+                        let factor = if scrolled.y < 0.0 {
+                            1.0 / ZOOM_STEP
+                        } else {
+                            ZOOM_STEP
+                        };
+                        self.zoom_around(size, pos, factor);
+                    }
+                } else if pos.x < self.sidebar_width() {
+                    self.thumbnail_scroll += scrolled.y;
+                } else {
+                    self.scroll += scrolled;
+                }
+                ctx.request_layout();
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+
+        // As in `Portal`, the scrollbar has already handled the event when it bubbles here.
+        let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar);
+        if scrollbar.widget().moved {
+            scrollbar.widget().moved = false;
+            let progress = scrollbar.widget().cursor_progress;
+            self.scroll.y = progress * (self.content_size.height - size.height);
+            std::mem::drop(scrollbar);
+            ctx.request_layout();
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.scrollbar);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let max = bc.max();
+        let size = bc.constrain(Size::new(
+            if max.width.is_finite() {
+                max.width
+            } else {
+                DEFAULT_SIZE.width
+            },
+            if max.height.is_finite() {
+                max.height
+            } else {
+                DEFAULT_SIZE.height
+            },
+        ));
+        let area = self.pages_area(size);
+
+        let page_sizes: Vec<_> = (0..self.source.page_count())
+            .map(|page| self.source.page_size(page))
+            .collect();
+        let widest = page_sizes.iter().map(|size| size.width).fold(0.0, f64::max);
+        let width = (widest * self.zoom + 2.0 * PAGE_GAP).max(area.width());
+        let mut y = PAGE_GAP;
+        self.page_rects = page_sizes
+            .iter()
+            .map(|page_size| {
+                let page_size = *page_size * self.zoom;
+                let origin = Point::new((width - page_size.width) / 2.0, y);
+                y += page_size.height + PAGE_GAP;
+                Rect::from_origin_size(origin, page_size)
+            })
+            .collect();
+        self.content_size = Size::new(width, y);
+        if let Some(page) = self.go_to_page.take() {
+            self.scroll_to_page(page);
+        }
+
+        let mut y = THUMBNAIL_GAP;
+        self.thumbnail_rects = page_sizes
+            .iter()
+            .map(|page_size| {
+                let height = if page_size.width > 0.0 {
+                    THUMBNAIL_WIDTH * page_size.height / page_size.width
+                } else {
+                    0.0
+                };
+                let rect = Rect::new(
+                    THUMBNAIL_GAP,
+                    y,
+                    THUMBNAIL_GAP + THUMBNAIL_WIDTH,
+                    y + height,
+                );
+                y += height + THUMBNAIL_GAP;
+                rect
+            })
+            .collect();
+        self.thumbnails_height = y;
+
+        let max_scroll = self.content_size - area.size();
+        self.scroll = Vec2::new(
+            self.scroll.x.clamp(0.0, max_scroll.width.max(0.0)),
+            self.scroll.y.clamp(0.0, max_scroll.height.max(0.0)),
+        );
+
+        let current_page = self
+            .page_rects
+            .partition_point(|rect| rect.y0 <= self.scroll.y + PAGE_GAP)
+            .saturating_sub(1);
+        if current_page != self.current_page {
+            self.current_page = current_page;
+            ctx.submit_typed_action(PageViewerAction::PageChanged(current_page));
+            // The sidebar follows the current page.
+            if let Some(thumbnail) = self.thumbnail_rects.get(current_page) {
+                if thumbnail.y0 < self.thumbnail_scroll {
+                    self.thumbnail_scroll = thumbnail.y0 - THUMBNAIL_GAP;
+                } else if thumbnail.y1 > self.thumbnail_scroll + size.height {
+                    self.thumbnail_scroll = thumbnail.y1 + THUMBNAIL_GAP - size.height;
+                }
+            }
+        }
+        self.thumbnail_scroll = self
+            .thumbnail_scroll
+            .clamp(0.0, (self.thumbnails_height - size.height).max(0.0));
+
+        let show_scrollbar = self.content_size.height > size.height;
+        ctx.set_stashed(&mut self.scrollbar, !show_scrollbar);
+        if show_scrollbar {
+            let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar);
+            scrollbar.widget().portal_size = size.height;
+            scrollbar.widget().content_size = self.content_size.height;
+            scrollbar.widget().cursor_progress =
+                self.scroll.y / (self.content_size.height - size.height);
+            std::mem::drop(scrollbar);
+
+            let scrollbar_size =
+                ctx.run_layout(&mut self.scrollbar, &BoxConstraints::new(Size::ZERO, size));
+            ctx.place_child(
+                &mut self.scrollbar,
+                Point::new(size.width - scrollbar_size.width, 0.0),
+            );
+        } else {
+            ctx.skip_layout(&mut self.scrollbar);
+        }
+
+        ctx.set_clip_path(size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let area = self.pages_area(size);
+        fill_color(scene, &area, theme::BACKGROUND_DARK);
+
+        let offset = self.pages_offset(size);
+        let visible = Self::visible(&self.page_rects, self.scroll.y, self.scroll.y + size.height);
+        self.rendered.retain(|page, _| visible.contains(page));
+        let selection = self.selection_range();
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &area);
+        for page in visible {
+            let rect = self.page_rects[page] + offset;
+            let zoom = self.zoom;
+            let source = &self.source;
+            let (_, image) = self
+                .rendered
+                .entry(page)
+                .and_modify(|(scale, image)| {
+                    if *scale != zoom {
+                        *scale = zoom;
+                        *image = source.render_page(page, zoom);
+                    }
+                })
+                .or_insert_with(|| (zoom, source.render_page(page, zoom)));
+            fill_color(scene, &rect, Color::WHITE);
+            Self::draw_image(scene, image, rect);
+            stroke(scene, &rect, theme::BORDER_DARK, 1.0);
+
+            let Some((start, end)) =
+                selection.filter(|(start, end)| (start.0..=end.0).contains(&page))
+            else {
+                continue;
+            };
+            let Some(words) = self.words.get(&page) else {
+                continue;
+            };
+            let first = if page == start.0 { start.1 } else { 0 };
+            let last = if page == end.0 {
+                end.1 + 1
+            } else {
+                words.len()
+            };
+            for word in &words[first..last.min(words.len())] {
+                let word_rect = word.rect.scale_from_origin(self.zoom) + rect.origin().to_vec2();
+                fill_color(
+                    scene,
+                    &word_rect,
+                    theme::SELECTED_TEXT_BACKGROUND_COLOR.multiply_alpha(0.5),
+                );
+            }
+        }
+        scene.pop_layer();
+
+        if !self.show_thumbnails {
+            return;
+        }
+        let sidebar = Rect::new(0.0, 0.0, SIDEBAR_WIDTH, size.height);
+        fill_color(scene, &sidebar, theme::BACKGROUND_LIGHT);
+        let visible = Self::visible(
+            &self.thumbnail_rects,
+            self.thumbnail_scroll,
+            self.thumbnail_scroll + size.height,
+        );
+        self.thumbnails.retain(|page, _| visible.contains(page));
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &sidebar);
+        for page in visible {
+            let rect = self.thumbnail_rects[page] - Vec2::new(0.0, self.thumbnail_scroll);
+            let page_width = self.page_rects[page].width() / self.zoom;
+            let source = &self.source;
+            let image = self.thumbnails.entry(page).or_insert_with(|| {
+                let scale = if page_width > 0.0 {
+                    THUMBNAIL_WIDTH / page_width
+                } else {
+                    1.0
+                };
+                source.render_page(page, scale)
+            });
+            fill_color(scene, &rect, Color::WHITE);
+            Self::draw_image(scene, image, rect);
+            if page == self.current_page {
+                stroke(scene, &rect.inset(1.0), theme::PRIMARY_LIGHT, 2.0);
+            }
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Document
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        node.set_description(format!(
+            "Page {} of {}",
+            self.current_page + 1,
+            self.page_count()
+        ));
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec::smallvec![self.scrollbar.id()]
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("PageViewer", id = ctx.widget_id().trace())
+    }
+}
+
+impl ActionSource for PageViewer {
+    type Action = PageViewerAction;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use vello::peniko::Format;
+
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::ScrollDelta;
+
+    /// Ten 100 by 100 pages, with the words "hello world" at the top.
+    #[derive(Default)]
+    struct TestPages {
+        /// The pages rendered at full size.
+        rendered: Mutex<Vec<usize>>,
+    }
+
+    impl PageSource for TestPages {
+        fn page_count(&self) -> usize {
+            10
+        }
+
+        fn page_size(&self, _page: usize) -> Size {
+            Size::new(100., 100.)
+        }
+
+        fn render_page(&self, page: usize, scale: f64) -> ImageBuf {
+            if scale == 1.0 {
+                self.rendered.lock().unwrap().push(page);
+            }
+            ImageBuf::new(vec![255; 4].into(), Format::Rgba8, 1, 1)
+        }
+
+        fn page_text(&self, _page: usize) -> Vec<PageWord> {
+            vec![
+                PageWord::new(Rect::new(10., 10., 40., 20.), "hello"),
+                PageWord::new(Rect::new(50., 10., 90., 20.), "world"),
+            ]
+        }
+    }
+
+    fn actions(harness: &mut TestHarness) -> Vec<PageViewerAction> {
+        std::iter::from_fn(|| harness.pop_action())
+            .filter_map(|(action, _)| action.downcast::<PageViewerAction>().ok())
+            .collect()
+    }
+
+    #[test]
+    fn only_visible_pages_are_rendered() {
+        let pages = Arc::new(TestPages::default());
+        let widget = PageViewer::new(pages.clone());
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 300.));
+        harness.render();
+        assert_eq!(*pages.rendered.lock().unwrap(), [0, 1, 2]);
+
+        // The pages are 112 pixels apart.
+        harness.mouse_move((250., 150.));
+        harness.mouse_scroll(ScrollDelta::Pixels(Vec2::new(0., -300.)));
+        harness.render();
+        assert_eq!(actions(&mut harness), [PageViewerAction::PageChanged(2)]);
+        assert_eq!(
+            *pages.rendered.lock().unwrap(),
+            [0, 1, 2, 3, 4, 5],
+            "pages already rendered at this zoom are kept"
+        );
+
+        // Clicking the second thumbnail goes to the second page.
+        harness.drag_from_to((50., 160.), (50., 160.));
+        let viewer = harness.root_widget().downcast::<PageViewer>().unwrap();
+        assert_eq!(viewer.current_page(), 1);
+    }
+
+    #[test]
+    fn select_text() {
+        let widget = PageViewer::new(Arc::new(TestPages::default()));
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 300.));
+
+        // The first page is centered in the 288 pixels right of the sidebar.
+        harness.drag_from_to((220., 27.), (290., 27.));
+        assert_eq!(
+            actions(&mut harness),
+            [PageViewerAction::TextSelected("hello world".into())]
+        );
+
+        // Clicking the text clears the selection.
+        harness.drag_from_to((220., 27.), (220., 27.));
+        let viewer = harness.root_widget().downcast::<PageViewer>().unwrap();
+        assert_eq!(viewer.selected_text(), None);
+    }
+}
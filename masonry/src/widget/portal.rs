@@ -9,14 +9,103 @@ use accesskit::{Node, Role};
 use smallvec::{smallvec, SmallVec};
 use tracing::{trace_span, Span};
 use vello::kurbo::{Point, Rect, Size, Vec2};
+use vello::peniko::Color;
 use vello::Scene;
 
-use crate::widget::{Axis, ScrollBar, WidgetMut};
+use crate::event::{PointerButton, ScrollDelta};
+use crate::paint_scene_helpers::{fill_lin_gradient, UnitPoint};
+use crate::widget::{Axis, ScrollBar, Spinner, WidgetMut};
 use crate::{
-    AccessCtx, AccessEvent, BoxConstraints, ComposeCtx, EventCtx, LayoutCtx, PaintCtx,
-    PointerEvent, QueryCtx, RegisterCtx, TextEvent, Update, UpdateCtx, Widget, WidgetId, WidgetPod,
+    theme, AccessCtx, AccessEvent, ActionSource, AllowRawMut, BoxConstraints, ComposeCtx, EventCtx,
+    LayoutCtx, PaintCtx, PointerEvent, QueryCtx, RegisterCtx, TextEvent, Update, UpdateCtx, Widget,
+    WidgetId, WidgetPod,
 };
 
+/// How far, after resistance, the content must be pulled down to refresh.
+const PULL_THRESHOLD: f64 = 64.0;
+/// The ratio of the distance the content is pulled to the distance the pointer moves.
+const PULL_RESISTANCE: f64 = 0.5;
+const MAX_PULL: f64 = 2.0 * PULL_THRESHOLD;
+const REFRESH_INDICATOR_SIZE: f64 = 24.0;
+const REFRESH_INDICATOR_MARGIN: f64 = 8.0;
+/// The furthest the content can be scrolled past its edges.
+const MAX_OVERSCROLL: f64 = 120.0;
+/// The ratio of the overscroll to the scrolled distance, with [`OverscrollBehavior::Bounce`].
+const BOUNCE_RESISTANCE: f64 = 0.4;
+/// The time it takes for the overscroll to decrease by a factor of e, in seconds.
+const OVERSCROLL_DECAY_TIME: f64 = 0.08;
+/// The default distance scrolled per line of a [`ScrollDelta::Lines`].
+///
+/// A wheel tick is reported as one line. On macOS, the system accelerates the ticks and
+/// scrolls by about ten pixels per line. On the other platforms, a tick scrolls by three
+/// lines of text.
+#[cfg(target_os = "macos")]
+const DEFAULT_LINE_SCALE: f64 = 10.0;
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_LINE_SCALE: f64 = 48.0;
+
+/// What a [`Portal`] shows when it's scrolled past the edges of its content.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverscrollBehavior {
+    /// The viewport stops at the edges, with no indication.
+    #[default]
+    Clamp,
+    /// The viewport stops at the edges, and a glow is painted on the edge which was
+    /// scrolled against, as on Android.
+    Glow,
+    /// The content follows the scrolling past the edges, with some resistance, and springs
+    /// back when the scrolling stops, as on iOS and macOS.
+    Bounce,
+}
+
+/// How a [`Portal`] moves its viewport for the [`MouseWheel`](PointerEvent::MouseWheel)
+/// events it receives.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollConfig {
+    /// The distance scrolled per line of a [`ScrollDelta::Lines`], in logical pixels.
+    ///
+    /// This is what a tick of a mouse wheel scrolls. The default follows the platform.
+    pub line_scale: f64,
+    /// The factor applied to the distances of [`ScrollDelta::Pixels`], as reported by
+    /// trackpads.
+    ///
+    /// The default is `1.0`, so that the content follows the fingers.
+    pub pixel_scale: f64,
+    /// Whether scrolling vertically with <kbd>Shift</kbd> held scrolls horizontally, for
+    /// mice without a horizontal wheel.
+    ///
+    /// The default is `true`. This has no effect where the platform already does it, as
+    /// on macOS.
+    pub shift_scrolls_horizontally: bool,
+}
+
+impl ScrollConfig {
+    /// The distance by which the viewport moves for a scroll of `delta`, with <kbd>Shift</kbd>
+    /// held if `shift` is true.
+    pub fn viewport_delta(&self, delta: ScrollDelta, shift: bool) -> Vec2 {
+        let delta = match delta {
+            ScrollDelta::Lines(lines) => lines * self.line_scale,
+            ScrollDelta::Pixels(pixels) => pixels * self.pixel_scale,
+        };
+        // Scrolling up moves the content down, so the viewport up.
+        if shift && self.shift_scrolls_horizontally && delta.x == 0.0 {
+            Vec2::new(-delta.y, 0.0)
+        } else {
+            -delta
+        }
+    }
+}
+
+impl Default for ScrollConfig {
+    fn default() -> Self {
+        Self {
+            line_scale: DEFAULT_LINE_SCALE,
+            pixel_scale: 1.0,
+            shift_scrolls_horizontally: true,
+        }
+    }
+}
+
 // TODO - refactor - see https://github.com/linebender/xilem/issues/366
 // TODO - rename "Portal" to "ScrollPortal"?
 // TODO - Document which cases need request_layout, request_compose and request_render
@@ -29,6 +118,8 @@ pub struct Portal<W: Widget> {
     // on re-layouts
     // TODO - rename
     viewport_pos: Point,
+    /// The size of the child, from the last layout.
+    content_size: Size,
     // TODO - test how it looks like
     constrain_horizontal: bool,
     constrain_vertical: bool,
@@ -37,6 +128,53 @@ pub struct Portal<W: Widget> {
     scrollbar_horizontal_visible: bool,
     scrollbar_vertical: WidgetPod<ScrollBar>,
     scrollbar_vertical_visible: bool,
+    pull_to_refresh: bool,
+    /// The vertical position of the pointer when a pull started, if one is in progress.
+    pull_start: Option<f64>,
+    pull_distance: f64,
+    refreshing: bool,
+    refresh_indicator: WidgetPod<Spinner>,
+    overscroll_behavior: OverscrollBehavior,
+    /// How far the content was scrolled past its top (when negative) or bottom edge.
+    overscroll: f64,
+    overscroll_glow: WidgetPod<OverscrollGlow>,
+    scroll_config: ScrollConfig,
+}
+
+/// The scroll position of a [`Portal`].
+///
+/// This is submitted as [`PortalAction::Scrolled`] when the viewport of the portal is moved,
+/// except by its [`WidgetMut`] methods.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollPosition {
+    /// The position of the top left of the viewport in the content.
+    pub offset: Point,
+    /// The largest offset, where the viewport shows the bottom right of the content.
+    pub max_offset: Point,
+}
+
+impl ScrollPosition {
+    /// Whether the viewport shows the top of the content.
+    pub fn at_top(&self) -> bool {
+        self.offset.y <= 0.5
+    }
+
+    /// Whether the viewport shows the bottom of the content.
+    pub fn at_bottom(&self) -> bool {
+        self.offset.y >= self.max_offset.y - 0.5
+    }
+}
+
+/// Something happened to the viewport of a [`Portal`].
+///
+/// This is the [`ActionSource::Action`] of [`Portal`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PortalAction {
+    /// The viewport was moved by the user, or to show one of the children of the portal.
+    Scrolled(ScrollPosition),
+    /// The content of a portal with pull-to-refresh enabled was pulled down far enough
+    /// to refresh it.
+    RefreshRequested,
 }
 
 // --- MARK: BUILDERS ---
@@ -49,6 +187,7 @@ impl<W: Widget> Portal<W> {
         Portal {
             child,
             viewport_pos: Point::ORIGIN,
+            content_size: Size::ZERO,
             constrain_horizontal: false,
             constrain_vertical: false,
             must_fill: false,
@@ -57,13 +196,67 @@ impl<W: Widget> Portal<W> {
             scrollbar_horizontal_visible: false,
             scrollbar_vertical: WidgetPod::new(ScrollBar::new(Axis::Vertical, 1.0, 1.0)),
             scrollbar_vertical_visible: false,
+            pull_to_refresh: false,
+            pull_start: None,
+            pull_distance: 0.0,
+            refreshing: false,
+            refresh_indicator: WidgetPod::new(Spinner::new()),
+            overscroll_behavior: OverscrollBehavior::default(),
+            overscroll: 0.0,
+            overscroll_glow: WidgetPod::new(OverscrollGlow { overscroll: 0.0 }),
+            scroll_config: ScrollConfig::default(),
         }
     }
 
+    /// Builder-style method to set the initial position of the viewport in the content.
+    ///
+    /// The position is clamped when the portal is laid out, so that the viewport stays
+    /// within the content.
+    pub fn with_viewport_pos(mut self, position: Point) -> Self {
+        self.viewport_pos = position;
+        self
+    }
+
     pub fn get_viewport_pos(&self) -> Point {
         self.viewport_pos
     }
 
+    /// The size of the content, from the last layout.
+    pub fn get_content_size(&self) -> Size {
+        self.content_size
+    }
+
+    /// Builder-style method to enable pulling the content down to refresh it.
+    ///
+    /// When the content is dragged down past the top with the pointer, a spinner is shown
+    /// above it. If the content is pulled far enough before the pointer is released,
+    /// [`PortalAction::RefreshRequested`] is submitted and the spinner keeps spinning until
+    /// [`set_refreshing(false)`](Self::set_refreshing) is called.
+    pub fn with_pull_to_refresh(mut self, pull_to_refresh: bool) -> Self {
+        self.pull_to_refresh = pull_to_refresh;
+        self
+    }
+
+    /// Builder-style method to set what is shown when the content is scrolled past its edges.
+    ///
+    /// The default is [`OverscrollBehavior::Clamp`].
+    pub fn with_overscroll_behavior(mut self, behavior: OverscrollBehavior) -> Self {
+        self.overscroll_behavior = behavior;
+        self
+    }
+
+    /// Builder-style method to set how the viewport moves when the mouse wheel or the
+    /// trackpad is scrolled.
+    pub fn with_scroll_config(mut self, config: ScrollConfig) -> Self {
+        self.scroll_config = config;
+        self
+    }
+
+    /// Whether a refresh started by pulling the content down is in progress.
+    pub fn is_refreshing(&self) -> bool {
+        self.refreshing
+    }
+
     // TODO - rewrite doc
     /// Builder-style method for deciding whether to constrain the child vertically.
     ///
@@ -164,6 +357,38 @@ impl<W: Widget> Portal<W> {
 
         self.set_viewport_pos_raw(portal_size, content_size, Point::new(new_pos_x, new_pos_y))
     }
+
+    /// How far the content is moved down, to show the refresh indicator above it.
+    fn refresh_offset(&self) -> f64 {
+        if self.refreshing {
+            REFRESH_INDICATOR_SIZE + 2.0 * REFRESH_INDICATOR_MARGIN
+        } else {
+            self.pull_distance
+        }
+    }
+
+    /// Add the `excess` of a scroll past the edges of the content to the overscroll,
+    /// returning whether the overscroll changed.
+    fn add_overscroll(&mut self, excess: f64) -> bool {
+        let excess = match self.overscroll_behavior {
+            OverscrollBehavior::Clamp => return false,
+            OverscrollBehavior::Glow => excess,
+            OverscrollBehavior::Bounce => excess * BOUNCE_RESISTANCE,
+        };
+        if excess == 0.0 {
+            return false;
+        }
+        self.overscroll = (self.overscroll + excess).clamp(-MAX_OVERSCROLL, MAX_OVERSCROLL);
+        true
+    }
+
+    fn scroll_position(&self, portal_size: Size, content_size: Size) -> ScrollPosition {
+        let max_offset = (content_size - portal_size).clamp(Size::ZERO, content_size);
+        ScrollPosition {
+            offset: self.viewport_pos,
+            max_offset: max_offset.to_vec2().to_point(),
+        }
+    }
 }
 
 // --- MARK: WIDGETMUT ---
@@ -197,6 +422,11 @@ impl<W: Widget> Portal<W> {
         this.ctx.request_layout();
     }
 
+    /// Set how the viewport moves when the mouse wheel or the trackpad is scrolled.
+    pub fn set_scroll_config(this: &mut WidgetMut<'_, Self>, config: ScrollConfig) {
+        this.widget.scroll_config = config;
+    }
+
     /// Set whether the child's size must be greater than or equal the size of
     /// the `ClipBox`.
     ///
@@ -208,6 +438,39 @@ impl<W: Widget> Portal<W> {
         this.ctx.request_layout();
     }
 
+    /// Set whether pulling the content down refreshes it.
+    ///
+    /// See [`with_pull_to_refresh`](Self::with_pull_to_refresh) for details.
+    pub fn set_pull_to_refresh(this: &mut WidgetMut<'_, Self>, pull_to_refresh: bool) {
+        this.widget.pull_to_refresh = pull_to_refresh;
+        if !pull_to_refresh {
+            this.widget.pull_start = None;
+            this.widget.pull_distance = 0.0;
+        }
+        this.ctx.request_layout();
+    }
+
+    /// Set whether a refresh is in progress, showing a spinner above the content.
+    ///
+    /// This is set when the content is pulled down to refresh it, and should be unset when
+    /// the refresh is complete.
+    pub fn set_refreshing(this: &mut WidgetMut<'_, Self>, refreshing: bool) {
+        if this.widget.refreshing != refreshing {
+            this.widget.refreshing = refreshing;
+            this.ctx.request_layout();
+        }
+    }
+
+    /// Set what is shown when the content is scrolled past its edges.
+    pub fn set_overscroll_behavior(this: &mut WidgetMut<'_, Self>, behavior: OverscrollBehavior) {
+        this.widget.overscroll_behavior = behavior;
+        this.widget.overscroll = 0.0;
+        let mut glow = this.ctx.get_mut(&mut this.widget.overscroll_glow);
+        glow.widget.overscroll = 0.0;
+        glow.ctx.request_render();
+        this.ctx.request_compose();
+    }
+
     pub fn set_viewport_pos(this: &mut WidgetMut<'_, Self>, position: Point) -> bool {
         let portal_size = this.ctx.layout_rect().size();
         let content_size = this
@@ -227,7 +490,9 @@ impl<W: Widget> Portal<W> {
             let progress_y = this.widget.viewport_pos.y / (content_size - portal_size).height;
             Self::vertical_scrollbar_mut(this).widget.cursor_progress = progress_y;
             Self::vertical_scrollbar_mut(this).ctx.request_render();
-            this.ctx.request_layout();
+            // Only the child moves, so there's no need to lay it out again.
+            this.ctx.request_compose();
+            this.ctx.request_accessibility_update();
         }
         pos_changed
     }
@@ -258,22 +523,69 @@ impl<W: Widget> Portal<W> {
 // --- MARK: IMPL WIDGET ---
 impl<W: Widget> Widget for Portal<W> {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
-        const SCROLLING_SPEED: f64 = 10.0;
-
         let portal_size = ctx.size();
         let content_size = ctx.get_raw_ref(&mut self.child).ctx().layout_rect().size();
 
         match event {
-            PointerEvent::MouseWheel(delta, _) => {
-                let delta = Vec2::new(delta.x * -SCROLLING_SPEED, delta.y * -SCROLLING_SPEED);
-                self.set_viewport_pos_raw(portal_size, content_size, self.viewport_pos + delta);
+            PointerEvent::MouseWheel(delta, state) => {
+                let shift = state.mods.state().shift_key();
+                let delta = self.scroll_config.viewport_delta(*delta, shift);
+                let target = self.viewport_pos + delta;
+                let pos_changed = self.set_viewport_pos_raw(portal_size, content_size, target);
+                if self.add_overscroll(target.y - self.viewport_pos.y) {
+                    ctx.request_anim_frame();
+                    let mut glow = ctx.get_raw_mut(&mut self.overscroll_glow);
+                    glow.widget().overscroll = self.overscroll;
+                    glow.ctx().request_render();
+                }
+                if pos_changed {
+                    ctx.submit_typed_action(PortalAction::Scrolled(
+                        self.scroll_position(portal_size, content_size),
+                    ));
+                }
                 ctx.request_compose();
+                ctx.request_accessibility_update();
 
-                // TODO - horizontal scrolling?
                 let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_vertical);
                 scrollbar.widget().cursor_progress =
                     self.viewport_pos.y / (content_size - portal_size).height;
                 scrollbar.ctx().request_render();
+
+                std::mem::drop(scrollbar);
+
+                let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_horizontal);
+                scrollbar.widget().cursor_progress =
+                    self.viewport_pos.x / (content_size - portal_size).width;
+                scrollbar.ctx().request_render();
+            }
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                if self.pull_to_refresh && !self.refreshing && self.viewport_pos.y <= 0.0 {
+                    self.pull_start = Some(state.position.y);
+                }
+            }
+            PointerEvent::PointerMove(state) => {
+                if let Some(pull_start) = self.pull_start {
+                    let was_pulled = self.pull_distance > 0.0;
+                    self.pull_distance =
+                        ((state.position.y - pull_start) * PULL_RESISTANCE).clamp(0.0, MAX_PULL);
+                    if was_pulled != (self.pull_distance > 0.0) {
+                        // Show or hide the indicator
+                        ctx.request_layout();
+                    }
+                    ctx.request_compose();
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, _) | PointerEvent::PointerLeave(_) => {
+                if self.pull_start.take().is_some() {
+                    if self.pull_distance >= PULL_THRESHOLD
+                        && matches!(event, PointerEvent::PointerUp(..))
+                    {
+                        self.refreshing = true;
+                        ctx.submit_typed_action(PortalAction::RefreshRequested);
+                    }
+                    self.pull_distance = 0.0;
+                    ctx.request_layout();
+                }
             }
             _ => (),
         }
@@ -313,20 +625,87 @@ impl<W: Widget> Widget for Portal<W> {
         }
 
         if scrollbar_moved {
+            ctx.submit_typed_action(PortalAction::Scrolled(
+                self.scroll_position(portal_size, content_size),
+            ));
             ctx.request_compose();
+            ctx.request_accessibility_update();
         }
     }
 
+    fn on_anim_frame(&mut self, ctx: &mut UpdateCtx, interval: u64) {
+        if self.overscroll == 0.0 {
+            return;
+        }
+        // The overscroll decreases exponentially, which makes the content spring back.
+        let elapsed = interval as f64 * 1e-9;
+        self.overscroll *= (-elapsed / OVERSCROLL_DECAY_TIME).exp();
+        if self.overscroll.abs() < 0.5 {
+            self.overscroll = 0.0;
+        } else {
+            ctx.request_anim_frame();
+        }
+        ctx.request_compose();
+
+        let mut glow = ctx.get_raw_mut(&mut self.overscroll_glow);
+        glow.widget().overscroll = self.overscroll;
+        glow.ctx().request_render();
+    }
+
     // TODO - handle Home/End keys, etc
     fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
 
-    // TODO - Handle scroll-related events?
-    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        let portal_size = ctx.size();
+        let content_size = ctx.get_raw_ref(&mut self.child).ctx().layout_rect().size();
+
+        // Scrolling by a page leaves no overlap, which matches what platforms do for these actions.
+        let target = match (event.action, &event.data) {
+            (
+                accesskit::Action::SetScrollOffset,
+                Some(accesskit::ActionData::SetScrollOffset(offset)),
+            ) => Point::new(offset.x, offset.y),
+            (accesskit::Action::ScrollUp, _) => {
+                self.viewport_pos - Vec2::new(0.0, portal_size.height)
+            }
+            (accesskit::Action::ScrollDown, _) => {
+                self.viewport_pos + Vec2::new(0.0, portal_size.height)
+            }
+            (accesskit::Action::ScrollLeft, _) => {
+                self.viewport_pos - Vec2::new(portal_size.width, 0.0)
+            }
+            (accesskit::Action::ScrollRight, _) => {
+                self.viewport_pos + Vec2::new(portal_size.width, 0.0)
+            }
+            _ => return,
+        };
+        ctx.set_handled();
+
+        if self.set_viewport_pos_raw(portal_size, content_size, target) {
+            ctx.submit_typed_action(PortalAction::Scrolled(
+                self.scroll_position(portal_size, content_size),
+            ));
+            ctx.request_compose();
+            ctx.request_accessibility_update();
+
+            let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_horizontal);
+            scrollbar.widget().cursor_progress =
+                self.viewport_pos.x / (content_size - portal_size).width;
+            scrollbar.ctx().request_render();
+
+            let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_vertical);
+            scrollbar.widget().cursor_progress =
+                self.viewport_pos.y / (content_size - portal_size).height;
+            scrollbar.ctx().request_render();
+        }
+    }
 
     fn register_children(&mut self, ctx: &mut RegisterCtx) {
         ctx.register_child(&mut self.child);
         ctx.register_child(&mut self.scrollbar_horizontal);
         ctx.register_child(&mut self.scrollbar_vertical);
+        ctx.register_child(&mut self.refresh_indicator);
+        ctx.register_child(&mut self.overscroll_glow);
     }
 
     fn update(&mut self, ctx: &mut UpdateCtx, event: &Update) {
@@ -335,8 +714,13 @@ impl<W: Widget> Widget for Portal<W> {
                 let portal_size = ctx.size();
                 let content_size = ctx.get_raw_ref(&mut self.child).ctx().layout_rect().size();
 
-                self.pan_viewport_to_raw(portal_size, content_size, *target);
+                if self.pan_viewport_to_raw(portal_size, content_size, *target) {
+                    ctx.submit_typed_action(PortalAction::Scrolled(
+                        self.scroll_position(portal_size, content_size),
+                    ));
+                }
                 ctx.request_compose();
+                ctx.request_accessibility_update();
 
                 // TODO - There's a lot of code here that's duplicated from the `MouseWheel`
                 // event in `on_pointer_event`.
@@ -367,11 +751,16 @@ impl<W: Widget> Widget for Portal<W> {
 
         let content_size = ctx.run_layout(&mut self.child, &child_bc);
         let portal_size = bc.constrain(content_size);
+        self.content_size = content_size;
 
         // TODO - document better
         // Recompute the portal offset for the new layout
-        self.set_viewport_pos_raw(portal_size, content_size, self.viewport_pos);
-        // TODO - recompute portal progress
+        if self.set_viewport_pos_raw(portal_size, content_size, self.viewport_pos) {
+            // The viewport was clamped to the content, e.g. because the content shrank.
+            ctx.submit_typed_action(PortalAction::Scrolled(
+                self.scroll_position(portal_size, content_size),
+            ));
+        }
 
         ctx.set_clip_path(portal_size.to_rect());
 
@@ -395,6 +784,8 @@ impl<W: Widget> Widget for Portal<W> {
             let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_horizontal);
             scrollbar.widget().portal_size = portal_size.width;
             scrollbar.widget().content_size = content_size.width;
+            scrollbar.widget().cursor_progress =
+                self.viewport_pos.x / (content_size.width - portal_size.width);
             // TODO - request paint for scrollbar?
             std::mem::drop(scrollbar);
 
@@ -410,6 +801,8 @@ impl<W: Widget> Widget for Portal<W> {
             let mut scrollbar = ctx.get_raw_mut(&mut self.scrollbar_vertical);
             scrollbar.widget().portal_size = portal_size.height;
             scrollbar.widget().content_size = content_size.height;
+            scrollbar.widget().cursor_progress =
+                self.viewport_pos.y / (content_size.height - portal_size.height);
             // TODO - request paint for scrollbar?
             std::mem::drop(scrollbar);
 
@@ -422,11 +815,46 @@ impl<W: Widget> Widget for Portal<W> {
             ctx.skip_layout(&mut self.scrollbar_vertical);
         }
 
+        // The indicator is placed right above the content, and moved down with it.
+        let show_refresh_indicator = self.refreshing || self.pull_distance > 0.0;
+        ctx.set_stashed(&mut self.refresh_indicator, !show_refresh_indicator);
+        if show_refresh_indicator {
+            let indicator_size = Size::new(REFRESH_INDICATOR_SIZE, REFRESH_INDICATOR_SIZE);
+            ctx.run_layout(
+                &mut self.refresh_indicator,
+                &BoxConstraints::tight(indicator_size),
+            );
+            ctx.place_child(
+                &mut self.refresh_indicator,
+                Point::new(
+                    (portal_size.width - REFRESH_INDICATOR_SIZE) / 2.0,
+                    -REFRESH_INDICATOR_SIZE - REFRESH_INDICATOR_MARGIN,
+                ),
+            );
+        } else {
+            ctx.skip_layout(&mut self.refresh_indicator);
+        }
+
+        ctx.run_layout(
+            &mut self.overscroll_glow,
+            &BoxConstraints::tight(portal_size),
+        );
+        ctx.place_child(&mut self.overscroll_glow, Point::ORIGIN);
+
         portal_size
     }
 
     fn compose(&mut self, ctx: &mut ComposeCtx) {
-        ctx.set_child_translation(&mut self.child, Vec2::new(0.0, -self.viewport_pos.y));
+        let refresh_offset = self.refresh_offset();
+        let bounce_offset = match self.overscroll_behavior {
+            OverscrollBehavior::Bounce => -self.overscroll,
+            OverscrollBehavior::Clamp | OverscrollBehavior::Glow => 0.0,
+        };
+        ctx.set_child_translation(
+            &mut self.child,
+            Vec2::new(0.0, refresh_offset + bounce_offset - self.viewport_pos.y),
+        );
+        ctx.set_child_translation(&mut self.refresh_indicator, Vec2::new(0.0, refresh_offset));
     }
 
     fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
@@ -436,24 +864,32 @@ impl<W: Widget> Widget for Portal<W> {
     }
 
     fn accessibility(&mut self, ctx: &mut AccessCtx, node: &mut Node) {
-        // TODO - Double check this code
-        // Not sure about these values
-        if false {
-            node.set_scroll_x(self.viewport_pos.x);
-            node.set_scroll_y(self.viewport_pos.y);
-            node.set_scroll_x_min(0.0);
-
-            let x_max = ctx
-                .get_raw_ref(&self.scrollbar_horizontal)
-                .widget()
-                .portal_size;
-            let y_max = ctx
-                .get_raw_ref(&self.scrollbar_vertical)
-                .widget()
-                .portal_size;
-            node.set_scroll_x_max(x_max);
-            node.set_scroll_y_min(0.0);
-            node.set_scroll_y_max(y_max);
+        let portal_size = ctx.size();
+        let content_size = ctx.get_raw_ref(&self.child).ctx().layout_rect().size();
+        let max_x = (content_size.width - portal_size.width).max(0.0);
+        let max_y = (content_size.height - portal_size.height).max(0.0);
+
+        node.set_scroll_x(self.viewport_pos.x);
+        node.set_scroll_x_min(0.0);
+        node.set_scroll_x_max(max_x);
+        node.set_scroll_y(self.viewport_pos.y);
+        node.set_scroll_y_min(0.0);
+        node.set_scroll_y_max(max_y);
+
+        if max_x > 0.0 || max_y > 0.0 {
+            node.add_action(accesskit::Action::SetScrollOffset);
+        }
+        if self.viewport_pos.y > 0.0 {
+            node.add_action(accesskit::Action::ScrollUp);
+        }
+        if self.viewport_pos.y < max_y {
+            node.add_action(accesskit::Action::ScrollDown);
+        }
+        if self.viewport_pos.x > 0.0 {
+            node.add_action(accesskit::Action::ScrollLeft);
+        }
+        if self.viewport_pos.x < max_x {
+            node.add_action(accesskit::Action::ScrollRight);
         }
 
         node.set_clips_children();
@@ -464,6 +900,8 @@ impl<W: Widget> Widget for Portal<W> {
             self.child.id(),
             self.scrollbar_vertical.id(),
             self.scrollbar_horizontal.id(),
+            self.refresh_indicator.id(),
+            self.overscroll_glow.id(),
         ]
     }
 
@@ -472,14 +910,83 @@ impl<W: Widget> Widget for Portal<W> {
     }
 }
 
+impl<W: Widget> ActionSource for Portal<W> {
+    type Action = PortalAction;
+}
+
+// --- MARK: OVERSCROLL GLOW ---
+const GLOW_HEIGHT: f64 = 48.0;
+
+/// The glow painted over a [`Portal`] with [`OverscrollBehavior::Glow`] on the edge it was
+/// scrolled against.
+struct OverscrollGlow {
+    overscroll: f64,
+}
+
+impl Widget for OverscrollGlow {
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        if self.overscroll == 0.0 {
+            return;
+        }
+        let size = ctx.size();
+        let strength = (self.overscroll.abs() / MAX_OVERSCROLL).min(1.0);
+        let height = GLOW_HEIGHT * strength.sqrt();
+        let (rect, start, end) = if self.overscroll < 0.0 {
+            (
+                Rect::new(0.0, 0.0, size.width, height),
+                UnitPoint::TOP,
+                UnitPoint::BOTTOM,
+            )
+        } else {
+            (
+                Rect::new(0.0, size.height - height, size.width, size.height),
+                UnitPoint::BOTTOM,
+                UnitPoint::TOP,
+            )
+        };
+        let color = theme::PRIMARY_LIGHT.multiply_alpha(0.6 * strength as f32);
+        fill_lin_gradient(scene, &rect, [color, Color::TRANSPARENT], start, end);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("OverscrollGlow", id = ctx.widget_id().trace())
+    }
+}
+
+impl AllowRawMut for OverscrollGlow {}
+
 // --- MARK: TESTS ---
 #[cfg(test)]
 mod tests {
+    use accesskit::{ActionData, ActionRequest};
     use insta::assert_debug_snapshot;
+    use winit::keyboard::ModifiersState;
 
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::{widget_ids, TestHarness};
+    use crate::testing::{
+        widget_ids, Record, Recorder, Recording, TestHarness, TestWidgetExt as _,
+    };
     use crate::widget::{Button, Flex, SizedBox};
 
     fn button(text: &'static str) -> impl Widget {
@@ -553,6 +1060,213 @@ mod tests {
         assert_render_snapshot!(harness, "button_list_scroll_to_item_13");
     }
 
+    #[test]
+    fn access_actions_scroll() {
+        let [last_id] = widget_ids();
+
+        let mut column = Flex::column();
+        for _ in 0..12 {
+            column = column.with_child(button("Item")).with_spacer(10.0);
+        }
+        let widget = Portal::new(column.with_child_id(button("Last"), last_id));
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        let portal_id = harness.root_widget().id();
+        let viewport_y = |harness: &TestHarness| {
+            let portal = harness.root_widget();
+            portal.downcast::<Portal<Flex>>().unwrap().viewport_pos.y
+        };
+        assert_eq!(viewport_y(&harness), 0.0);
+
+        harness.process_access_event(ActionRequest {
+            action: accesskit::Action::ScrollIntoView,
+            target: last_id.into(),
+            data: None,
+        });
+        assert!(viewport_y(&harness) > 0.0);
+
+        let offset = accesskit::Point::new(0.0, 50.0);
+        harness.process_access_event(ActionRequest {
+            action: accesskit::Action::SetScrollOffset,
+            target: portal_id.into(),
+            data: Some(ActionData::SetScrollOffset(offset)),
+        });
+        assert_eq!(viewport_y(&harness), 50.0);
+
+        harness.process_access_event(ActionRequest {
+            action: accesskit::Action::ScrollUp,
+            target: portal_id.into(),
+            data: None,
+        });
+        assert_eq!(viewport_y(&harness), 0.0);
+    }
+
+    #[test]
+    fn scrolling_submits_position() {
+        let mut column = Flex::column();
+        for _ in 0..12 {
+            column = column.with_child(button("Item")).with_spacer(10.0);
+        }
+        let widget = Portal::new(column);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        let portal_id = harness.root_widget().id();
+
+        harness.process_access_event(ActionRequest {
+            action: accesskit::Action::ScrollDown,
+            target: portal_id.into(),
+            data: None,
+        });
+        let (action, id) = harness
+            .pop_action()
+            .expect("scrolling should submit the scroll position");
+        let Ok(PortalAction::Scrolled(position)) = action.downcast::<PortalAction>() else {
+            panic!("scrolling should submit the scroll position");
+        };
+        assert_eq!(id, portal_id);
+        assert_eq!(position.offset, Point::new(0.0, 200.0));
+        assert_eq!(position.max_offset, Point::new(0.0, 200.0));
+        assert!(!position.at_top());
+        assert!(position.at_bottom());
+
+        // Moving the viewport from a `WidgetMut` doesn't submit an action
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Flex>>();
+            Portal::set_viewport_pos(&mut portal, Point::ORIGIN)
+        });
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn scrolling_only_moves_content() {
+        let recording = Recording::default();
+        let widget = Portal::new(
+            SizedBox::empty()
+                .width(100.0)
+                .height(1000.0)
+                .record(&recording),
+        );
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(100., 100.));
+        let _ = harness.render();
+        let content_origin =
+            |harness: &TestHarness| harness.root_widget().children()[0].ctx().window_origin();
+        assert_eq!(content_origin(&harness), Point::ORIGIN);
+        recording.clear();
+
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Recorder<SizedBox>>>();
+            Portal::set_viewport_pos(&mut portal, Point::new(0.0, 300.0))
+        });
+        let _ = harness.render();
+
+        // The content is translated, but neither laid out nor painted again.
+        assert_eq!(content_origin(&harness), Point::new(0.0, -300.0));
+        let records = recording.drain();
+        assert!(!records
+            .iter()
+            .any(|record| matches!(record, Record::Layout(_) | Record::Paint)));
+    }
+
+    #[test]
+    fn wheel_and_trackpad_scrolling() {
+        let widget = Portal::new(SizedBox::empty().width(1000.0).height(1000.0))
+            .with_scroll_config(ScrollConfig {
+                line_scale: 20.0,
+                pixel_scale: 2.0,
+                shift_scrolls_horizontally: true,
+            });
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        let viewport_pos = |harness: &TestHarness| {
+            let portal = harness.root_widget();
+            portal
+                .downcast::<Portal<SizedBox>>()
+                .unwrap()
+                .get_viewport_pos()
+        };
+        harness.mouse_move((100.0, 100.0));
+
+        // Scrolling down moves the viewport down.
+        harness.mouse_wheel(Vec2::new(0.0, -2.0));
+        assert_eq!(viewport_pos(&harness), Point::new(0.0, 40.0));
+        harness.mouse_scroll(ScrollDelta::Pixels(Vec2::new(0.0, -5.0)));
+        assert_eq!(viewport_pos(&harness), Point::new(0.0, 50.0));
+
+        // Horizontal wheels scroll horizontally, as does Shift with a vertical wheel.
+        harness.mouse_wheel(Vec2::new(-1.0, 0.0));
+        assert_eq!(viewport_pos(&harness), Point::new(20.0, 50.0));
+        harness.keyboard_set_modifiers(ModifiersState::SHIFT);
+        harness.mouse_wheel(Vec2::new(0.0, -1.0));
+        assert_eq!(viewport_pos(&harness), Point::new(40.0, 50.0));
+    }
+
+    #[test]
+    fn pull_to_refresh() {
+        let mut column = Flex::column();
+        for _ in 0..12 {
+            column = column.with_child(SizedBox::empty().width(70.0).height(40.0));
+        }
+        let widget = Portal::new(column).with_pull_to_refresh(true);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        let portal_id = harness.root_widget().id();
+        let is_refreshing = |harness: &TestHarness| {
+            let portal = harness.root_widget();
+            portal.downcast::<Portal<Flex>>().unwrap().is_refreshing()
+        };
+
+        // A short pull doesn't refresh
+        harness.mouse_move((10.0, 10.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((10.0, 50.0));
+        harness.mouse_button_release(PointerButton::Primary);
+        assert_eq!(harness.pop_action(), None);
+        assert!(!is_refreshing(&harness));
+
+        harness.mouse_move((10.0, 10.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((10.0, 200.0));
+        harness.mouse_button_release(PointerButton::Primary);
+        let (action, id) = harness.pop_action().expect("a long pull should refresh");
+        assert_eq!(id, portal_id);
+        assert_eq!(
+            action.downcast::<PortalAction>().ok(),
+            Some(PortalAction::RefreshRequested)
+        );
+        assert!(is_refreshing(&harness));
+
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Flex>>();
+            Portal::set_refreshing(&mut portal, false);
+        });
+        assert!(!is_refreshing(&harness));
+    }
+
+    #[test]
+    fn overscroll_bounces_back() {
+        let mut column = Flex::column();
+        for _ in 0..12 {
+            column = column.with_child(SizedBox::empty().width(70.0).height(40.0));
+        }
+        let widget = Portal::new(column).with_overscroll_behavior(OverscrollBehavior::Bounce);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        let content_y = |harness: &TestHarness| {
+            let content_id = harness.root_widget().children()[0].id();
+            harness.get_widget(content_id).ctx().window_origin().y
+        };
+        assert_eq!(content_y(&harness), 0.0);
+
+        // Scrolling up at the top moves the content down
+        harness.mouse_move((10.0, 10.0));
+        harness.mouse_wheel(Vec2::new(0.0, 5.0));
+        assert!(content_y(&harness) > 0.0);
+
+        harness.animate_ms(1000);
+        assert_eq!(content_y(&harness), 0.0);
+    }
+
     // Helper function for panning tests
     fn make_range(repr: &str) -> Range<f64> {
         let repr = &repr[repr.find('_').unwrap()..];
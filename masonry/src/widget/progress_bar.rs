@@ -62,6 +62,7 @@ impl ProgressBar {
 
 // --- MARK: WIDGETMUT ---
 impl ProgressBar {
+    /// Set the progress, as in [`ProgressBar::new`].
     pub fn set_progress(this: &mut WidgetMut<'_, Self>, mut progress: Option<f64>) {
         clamp_progress(&mut progress);
         let progress_changed = this.widget.progress != progress;
@@ -73,6 +74,11 @@ impl ProgressBar {
         this.ctx.request_layout();
         this.ctx.request_render();
     }
+
+    /// Get mutable access to the label showing the percentage, e.g. to change its style.
+    pub fn label_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Label> {
+        this.ctx.get_mut(&mut this.widget.label)
+    }
 }
 
 /// Helper to ensure progress is either a number between [0, 1] inclusive, or `None`.
@@ -108,7 +114,7 @@ impl Widget for ProgressBar {
         let label_size = ctx.run_layout(&mut self.label, &bc.loosen());
         let desired_size = Size::new(
             DEFAULT_WIDTH.max(label_size.width),
-            crate::theme::BASIC_WIDGET_HEIGHT.max(label_size.height),
+            ctx.density().basic_widget_height().max(label_size.height),
         );
         let final_size = bc.constrain(desired_size);
 
@@ -223,6 +229,13 @@ mod tests {
         assert_render_snapshot!(harness, "25_percent_progressbar");
     }
 
+    #[test]
+    fn progressbar_access_tree() {
+        let widget = ProgressBar::new(Some(0.25));
+        let mut harness = TestHarness::create(widget);
+        assert_debug_snapshot!(harness.access_tree());
+    }
+
     #[test]
     fn _50_percent_progressbar() {
         let [_50percent] = widget_ids();
@@ -0,0 +1,168 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget whose content is rendered directly with wgpu, such as a 3D scene.
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::Scene;
+use wgpu::{Device, Queue, TextureFormat, TextureView};
+
+use crate::dpi::PhysicalSize;
+use crate::widget::WidgetMut;
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent, QueryCtx,
+    RegisterCtx, Size, TextEvent, Update, UpdateCtx, Widget, WidgetId,
+};
+
+/// The size of a viewport which isn't constrained by its parent.
+const DEFAULT_SIZE: Size = Size::new(300., 300.);
+
+/// The format of the textures [`RenderViewport`] callbacks render to.
+pub const VIEWPORT_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+/// What a [`RenderViewport`] callback renders to.
+#[derive(Debug)]
+pub struct ViewportTarget<'a> {
+    /// The device of the window.
+    pub device: &'a Device,
+    /// The queue of the window's device.
+    pub queue: &'a Queue,
+    /// The texture to render to, which has the [`VIEWPORT_FORMAT`] format.
+    ///
+    /// Its content is kept between frames, as long as the size doesn't change.
+    pub view: &'a TextureView,
+    /// The size of the texture, which is the size of the visible part of the widget
+    /// in physical pixels.
+    pub size: PhysicalSize<u32>,
+    /// The number of physical pixels per logical pixel.
+    pub scale_factor: f64,
+}
+
+/// The callback of a [`RenderViewport`].
+pub(crate) type ViewportCallback = Box<dyn FnMut(&ViewportTarget<'_>)>;
+
+/// A widget whose content is rendered by user code with wgpu, such as a 3D model viewer.
+///
+/// Each frame, the callback is given a texture the size of the widget, which is then
+/// composited into the window. The texture's content is premultiplied by alpha, so
+/// transparent parts show the background color of the window.
+///
+/// The viewport is drawn over the Vello scene, so other widgets can't be drawn on top of it.
+/// It is only shown by the event loop runner, not in the [`TestHarness`] or headless renders.
+///
+/// [`TestHarness`]: crate::testing::TestHarness
+pub struct RenderViewport {
+    /// The callback, until it's handed to the render root when the widget is added.
+    callback: Option<ViewportCallback>,
+    animated: bool,
+}
+
+// --- MARK: BUILDERS ---
+impl RenderViewport {
+    /// Create a viewport rendered by `callback`.
+    pub fn new(callback: impl FnMut(&ViewportTarget<'_>) + 'static) -> Self {
+        Self {
+            callback: Some(Box::new(callback)),
+            animated: false,
+        }
+    }
+
+    /// Builder-style method to redraw the window on every frame, e.g. for a spinning model.
+    ///
+    /// Otherwise, the viewport is only rendered when the window is redrawn for another reason,
+    /// or after [`request_render`](Self::request_render).
+    pub fn animated(mut self, animated: bool) -> Self {
+        self.animated = animated;
+        self
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl RenderViewport {
+    /// Replace the callback rendering the viewport.
+    pub fn set_callback(
+        this: &mut WidgetMut<'_, Self>,
+        callback: impl FnMut(&ViewportTarget<'_>) + 'static,
+    ) {
+        this.ctx.set_viewport_callback(Box::new(callback));
+        this.ctx.request_paint_only();
+    }
+
+    /// Set whether the window is redrawn on every frame.
+    pub fn set_animated(this: &mut WidgetMut<'_, Self>, animated: bool) {
+        this.widget.animated = animated;
+        if animated {
+            this.ctx.request_anim_frame();
+        }
+    }
+
+    /// Render the viewport again, e.g. because the model it shows changed.
+    pub fn request_render(this: &mut WidgetMut<'_, Self>) {
+        this.ctx.request_paint_only();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for RenderViewport {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_anim_frame(&mut self, ctx: &mut UpdateCtx, _interval: u64) {
+        if self.animated {
+            ctx.request_anim_frame();
+            ctx.request_paint_only();
+        }
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, event: &Update) {
+        match event {
+            Update::WidgetAdded => {
+                if let Some(callback) = self.callback.take() {
+                    ctx.set_viewport_callback(callback);
+                }
+                if self.animated {
+                    ctx.request_anim_frame();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            DEFAULT_SIZE.width
+        };
+        let height = if bc.is_height_bounded() {
+            bc.max().height
+        } else {
+            DEFAULT_SIZE.height
+        };
+        bc.constrain(Size::new(width, height))
+    }
+
+    // The content is composited after the scene is rendered.
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::Canvas
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("RenderViewport", id = ctx.widget_id().trace())
+    }
+}
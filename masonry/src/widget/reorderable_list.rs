@@ -0,0 +1,475 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A vertical list whose rows can be dragged to new positions.
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{Line, Rect, Vec2};
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, ActionSource, BoxConstraints, ComposeCtx, CursorIcon, EventCtx,
+    LayoutCtx, PaintCtx, Point, PointerEvent, QueryCtx, RegisterCtx, Size, TextEvent, UpdateCtx,
+    Widget, WidgetId,
+};
+
+/// The width of the drag handles, to the left of the rows.
+const HANDLE_WIDTH: f64 = 24.0;
+/// The time constant, in seconds, of the animation of the rows making room for a dragged row.
+const GAP_ANIMATION_TIME: f64 = 0.05;
+
+/// A row was dragged from index `from` to index `to` of a [`ReorderableList`].
+///
+/// The row is moved as by `vec.insert(to, vec.remove(from))`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RowMoved {
+    /// The index of the row before the move.
+    pub from: usize,
+    /// The index of the row after the move.
+    pub to: usize,
+}
+
+/// A vertical list whose rows can be dragged to new positions by their handles.
+///
+/// While a row is dragged, the other rows move aside to show where it will be dropped.
+/// Dropping it submits a [`RowMoved`] [typed action](crate::Action::typed), but doesn't
+/// reorder the rows: the owner of the list should move its data, and the row with
+/// [`move_child`](Self::move_child) if it keeps the widgets around.
+pub struct ReorderableList {
+    rows: Vec<Row>,
+    drag: Option<Drag>,
+    /// The position of the gap indicator, which follows the dropping position.
+    gap_y: f64,
+}
+
+struct Row {
+    widget: WidgetPod<Box<dyn Widget>>,
+    y: f64,
+    height: f64,
+    /// The animated offset of the row, to make room for the dragged row.
+    shift: f64,
+}
+
+struct Drag {
+    from: usize,
+    to: usize,
+    /// The vertical position of the pointer when the drag started.
+    grab_y: f64,
+    pointer_y: f64,
+}
+
+// --- MARK: BUILDERS ---
+impl ReorderableList {
+    /// Create a new empty list.
+    pub fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            drag: None,
+            gap_y: 0.0,
+        }
+    }
+
+    /// Builder-style method to add a row.
+    pub fn with_child(self, child: impl Widget) -> Self {
+        self.with_child_pod(WidgetPod::new(child).boxed())
+    }
+
+    /// Builder-style method to add a row.
+    pub fn with_child_pod(mut self, child: WidgetPod<Box<dyn Widget>>) -> Self {
+        self.rows.push(Row::new(child));
+        self
+    }
+
+    /// The number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the list has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl Default for ReorderableList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Row {
+    fn new(widget: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            widget,
+            y: 0.0,
+            height: 0.0,
+            shift: 0.0,
+        }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl ReorderableList {
+    /// Add a row at the end of the list.
+    pub fn add_child(this: &mut WidgetMut<'_, Self>, child: impl Widget) {
+        let len = this.widget.rows.len();
+        Self::insert_child_pod(this, len, WidgetPod::new(child).boxed());
+    }
+
+    /// Insert a row at `idx`.
+    ///
+    /// This cancels the current drag, if any.
+    pub fn insert_child_pod(
+        this: &mut WidgetMut<'_, Self>,
+        idx: usize,
+        child: WidgetPod<Box<dyn Widget>>,
+    ) {
+        this.widget.rows.insert(idx, Row::new(child));
+        Self::cancel_drag(this);
+        this.ctx.children_changed();
+    }
+
+    /// Remove the row at `idx`.
+    ///
+    /// This cancels the current drag, if any.
+    pub fn remove_child(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        let row = this.widget.rows.remove(idx);
+        this.ctx.remove_child(row.widget);
+        Self::cancel_drag(this);
+        this.ctx.request_layout();
+    }
+
+    /// Move the row at `from` to `to`, shifting the rows in between.
+    ///
+    /// Unlike removing and inserting it again, this keeps the state of the row widget.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_child(this: &mut WidgetMut<'_, Self>, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let row = this.widget.rows.remove(from);
+        this.widget.rows.insert(to, row);
+        Self::cancel_drag(this);
+        this.ctx.children_changed();
+    }
+
+    /// Get mutable access to the row at `idx`.
+    pub fn child_mut<'t>(
+        this: &'t mut WidgetMut<'_, Self>,
+        idx: usize,
+    ) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.rows[idx].widget)
+    }
+
+    fn cancel_drag(this: &mut WidgetMut<'_, Self>) {
+        if this.widget.drag.take().is_some() {
+            for row in &mut this.widget.rows {
+                row.shift = 0.0;
+            }
+            this.ctx.request_compose();
+            this.ctx.request_paint_only();
+        }
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl ReorderableList {
+    fn row_at(&self, y: f64) -> Option<usize> {
+        self.rows
+            .iter()
+            .position(|row| row.y <= y && y < row.y + row.height)
+    }
+
+    /// The offset of the dragged row from its position, kept within the list.
+    fn drag_offset(&self, drag: &Drag, height: f64) -> f64 {
+        let row = &self.rows[drag.from];
+        let max_offset = (height - row.y - row.height).max(-row.y);
+        (drag.pointer_y - drag.grab_y).clamp(-row.y, max_offset)
+    }
+
+    /// The index at which the dragged row would be dropped, from the position of its center.
+    fn drop_index(&self, drag: &Drag, height: f64) -> usize {
+        let row = &self.rows[drag.from];
+        let center = row.y + self.drag_offset(drag, height) + row.height / 2.0;
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(idx, other)| *idx != drag.from && other.y + other.height / 2.0 < center)
+            .count()
+    }
+
+    /// The offset of the row at `idx` once it has made room for the dragged row.
+    fn target_shift(&self, idx: usize) -> f64 {
+        match &self.drag {
+            Some(drag) if drag.from < idx && idx <= drag.to => -self.rows[drag.from].height,
+            Some(drag) if drag.to <= idx && idx < drag.from => self.rows[drag.from].height,
+            _ => 0.0,
+        }
+    }
+
+    /// The position at which the dragged row would be dropped.
+    fn target_gap_y(&self) -> f64 {
+        let Some(drag) = &self.drag else {
+            return 0.0;
+        };
+        let target = &self.rows[drag.to];
+        if drag.to <= drag.from {
+            target.y
+        } else {
+            target.y + target.height - self.rows[drag.from].height
+        }
+    }
+
+    fn end_drag(&mut self, ctx: &mut EventCtx, submit: bool) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+        if submit && drag.from != drag.to {
+            ctx.submit_typed_action(RowMoved {
+                from: drag.from,
+                to: drag.to,
+            });
+        }
+        // The rows snap back, as the owner of the list is expected to reorder them.
+        for row in &mut self.rows {
+            row.shift = 0.0;
+        }
+        ctx.request_compose();
+        ctx.request_paint_only();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for ReorderableList {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if ctx.is_disabled() || local_pos.x >= HANDLE_WIDTH {
+                    return;
+                }
+                if let Some(from) = self.row_at(local_pos.y) {
+                    ctx.capture_pointer();
+                    self.drag = Some(Drag {
+                        from,
+                        to: from,
+                        grab_y: local_pos.y,
+                        pointer_y: local_pos.y,
+                    });
+                    self.gap_y = self.rows[from].y;
+                    ctx.request_compose();
+                    ctx.request_paint_only();
+                }
+            }
+            PointerEvent::PointerMove(state) => {
+                let height = ctx.size().height;
+                let Some(mut drag) = self.drag.take() else {
+                    return;
+                };
+                drag.pointer_y = state.position.y - ctx.window_origin().y;
+                drag.to = self.drop_index(&drag, height);
+                self.drag = Some(drag);
+                ctx.request_anim_frame();
+                ctx.request_compose();
+                ctx.request_paint_only();
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, _) => {
+                self.end_drag(ctx, true);
+            }
+            PointerEvent::PointerLeave(_) => {
+                self.end_drag(ctx, false);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_anim_frame(&mut self, ctx: &mut UpdateCtx, interval: u64) {
+        if self.drag.is_none() {
+            return;
+        }
+        let elapsed = interval as f64 * 1e-9;
+        let progress = 1.0 - (-elapsed / GAP_ANIMATION_TIME).exp();
+        let approach = |value: &mut f64, target: f64| {
+            *value += (target - *value) * progress;
+            if (target - *value).abs() < 0.5 {
+                *value = target;
+            }
+            *value != target
+        };
+
+        let mut animating = false;
+        for idx in 0..self.rows.len() {
+            let target = self.target_shift(idx);
+            animating |= approach(&mut self.rows[idx].shift, target);
+        }
+        let target_gap_y = self.target_gap_y();
+        animating |= approach(&mut self.gap_y, target_gap_y);
+        if animating {
+            ctx.request_anim_frame();
+        }
+        ctx.request_compose();
+        ctx.request_paint_only();
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        for row in &mut self.rows {
+            ctx.register_child(&mut row.widget);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let row_bc = bc.shrink((HANDLE_WIDTH, 0.0));
+        let row_bc = BoxConstraints::new(
+            Size::new(row_bc.min().width, 0.0),
+            Size::new(row_bc.max().width, f64::INFINITY),
+        );
+
+        let mut width: f64 = 0.0;
+        let mut y = 0.0;
+        for row in &mut self.rows {
+            let size = ctx.run_layout(&mut row.widget, &row_bc);
+            ctx.place_child(&mut row.widget, Point::new(HANDLE_WIDTH, y));
+            row.y = y;
+            row.height = size.height;
+            width = width.max(size.width);
+            y += size.height;
+        }
+        bc.constrain(Size::new(width + HANDLE_WIDTH, y))
+    }
+
+    fn compose(&mut self, ctx: &mut ComposeCtx) {
+        let height = ctx.size().height;
+        let drag_offset = self
+            .drag
+            .as_ref()
+            .map(|drag| (drag.from, self.drag_offset(drag, height)));
+        for (idx, row) in self.rows.iter_mut().enumerate() {
+            let offset = match drag_offset {
+                Some((from, offset)) if from == idx => offset,
+                _ => row.shift,
+            };
+            ctx.set_child_translation(&mut row.widget, Vec2::new(0.0, offset));
+        }
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        if let Some(drag) = &self.drag {
+            let gap = Rect::new(
+                HANDLE_WIDTH,
+                self.gap_y,
+                size.width,
+                self.gap_y + self.rows[drag.from].height,
+            );
+            fill_color(scene, &gap, theme::PRIMARY_DARK.multiply_alpha(0.3));
+        }
+
+        for (idx, row) in self.rows.iter().enumerate() {
+            let offset = match &self.drag {
+                Some(drag) if drag.from == idx => self.drag_offset(drag, size.height),
+                _ => row.shift,
+            };
+            let center_y = row.y + offset + row.height / 2.0;
+            for line_y in [center_y - 4.0, center_y, center_y + 4.0] {
+                let line = Line::new((6.0, line_y), (HANDLE_WIDTH - 6.0, line_y));
+                stroke(scene, &line, theme::BORDER_LIGHT, 1.5);
+            }
+        }
+    }
+
+    fn get_cursor(&self, ctx: &QueryCtx, pos: Point) -> CursorIcon {
+        let local_pos = pos - ctx.window_origin().to_vec2();
+        if self.drag.is_some() {
+            CursorIcon::Grabbing
+        } else if local_pos.x < HANDLE_WIDTH {
+            CursorIcon::Grab
+        } else {
+            CursorIcon::Default
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::List
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut ids: SmallVec<_> = self.rows.iter().map(|row| row.widget.id()).collect();
+        // The dragged row is painted over the others.
+        if let Some(drag) = &self.drag {
+            let dragged = ids.remove(drag.from);
+            ids.push(dragged);
+        }
+        ids
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("ReorderableList", id = ctx.widget_id().trace())
+    }
+}
+
+impl ActionSource for ReorderableList {
+    type Action = RowMoved;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::SizedBox;
+
+    #[test]
+    fn drag_row_to_new_position() {
+        let [first, second, third] = widget_ids();
+        let row = |id| WidgetPod::new_with_id(SizedBox::empty().width(100.).height(20.), id);
+        let widget = ReorderableList::new()
+            .with_child_pod(row(first).boxed())
+            .with_child_pod(row(second).boxed())
+            .with_child_pod(row(third).boxed());
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 100.));
+        let list_id = harness.root_widget().id();
+        let row_y = |harness: &TestHarness, id| harness.get_widget(id).ctx().window_origin().y;
+
+        // Grab the first row by its handle, and drag it below the third one
+        harness.mouse_move((5.0, 10.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((5.0, 55.0));
+        harness.animate_ms(500);
+        assert_eq!(row_y(&harness, first), 45.0);
+        assert_eq!(row_y(&harness, second), 0.0);
+        assert_eq!(row_y(&harness, third), 20.0);
+
+        harness.mouse_button_release(PointerButton::Primary);
+        let (action, id) = harness.pop_action().unwrap();
+        assert_eq!(id, list_id);
+        assert_eq!(
+            action.downcast::<RowMoved>().unwrap(),
+            RowMoved { from: 0, to: 2 }
+        );
+        // The rows are only reordered by the owner of the list
+        assert_eq!(row_y(&harness, first), 0.0);
+        assert_eq!(row_y(&harness, third), 40.0);
+
+        // Clicking outside of the handles doesn't drag rows
+        harness.mouse_move((50.0, 10.0));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_move((50.0, 55.0));
+        harness.mouse_button_release(PointerButton::Primary);
+        assert_eq!(harness.pop_action(), None);
+    }
+}
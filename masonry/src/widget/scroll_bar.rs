@@ -127,7 +127,7 @@ impl Widget for ScrollBar {
             PointerEvent::PointerDown(_, state) => {
                 ctx.capture_pointer();
 
-                let cursor_min_length = theme::SCROLLBAR_MIN_SIZE;
+                let cursor_min_length = ctx.density().scrollbar_min_size();
                 let cursor_rect = self.get_cursor_rect(ctx.size(), cursor_min_length);
 
                 let mouse_pos =
@@ -148,7 +148,7 @@ impl Widget for ScrollBar {
                 let mouse_pos =
                     Point::new(state.position.x, state.position.y) - ctx.window_origin().to_vec2();
                 if let Some(grab_anchor) = self.grab_anchor {
-                    let cursor_min_length = theme::SCROLLBAR_MIN_SIZE;
+                    let cursor_min_length = ctx.density().scrollbar_min_size();
                     self.cursor_progress = self.progress_from_mouse_pos(
                         ctx.size(),
                         cursor_min_length,
@@ -177,10 +177,10 @@ impl Widget for ScrollBar {
 
     fn update(&mut self, _ctx: &mut UpdateCtx, _event: &Update) {}
 
-    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
         // TODO - handle resize
 
-        let scrollbar_width = theme::SCROLLBAR_WIDTH;
+        let scrollbar_width = ctx.density().scrollbar_width();
         let cursor_padding = theme::SCROLLBAR_PAD;
         self.axis
             .pack(
@@ -194,7 +194,7 @@ impl Widget for ScrollBar {
         let radius = theme::SCROLLBAR_RADIUS;
         let edge_width = theme::SCROLLBAR_EDGE_WIDTH;
         let cursor_padding = theme::SCROLLBAR_PAD;
-        let cursor_min_length = theme::SCROLLBAR_MIN_SIZE;
+        let cursor_min_length = ctx.density().scrollbar_min_size();
 
         let (inset_x, inset_y) = self.axis.pack(0.0, cursor_padding);
         let cursor_rect = self
@@ -0,0 +1,228 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An item of a list, table or tree which can be selected.
+
+use accesskit::{Node, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::Scene;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::event::PointerButton;
+use crate::selection::{SelectionInput, SelectionMove};
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, ActionSource, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point,
+    PointerEvent, QueryCtx, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// An item of a list, table or tree, which turns clicks and keys into [`SelectionInput`]s.
+///
+/// The inputs are submitted as [typed actions](crate::Action::typed), to be applied to a
+/// [`Selection`](crate::selection::Selection) with the index of the item.
+/// This doesn't paint anything: the child should show whether it's selected.
+///
+/// When focused, the arrow keys and <kbd>Home</kbd>/<kbd>End</kbd> move the selection,
+/// extending it with <kbd>Shift</kbd>, <kbd>Space</kbd> toggles the item and
+/// <kbd>Ctrl</kbd>+<kbd>A</kbd> (<kbd>Cmd</kbd>+<kbd>A</kbd> on macOS) selects all the items.
+pub struct Selectable {
+    child: WidgetPod<Box<dyn Widget>>,
+    selected: bool,
+}
+
+// --- MARK: BUILDERS ---
+impl Selectable {
+    /// Create a new selectable item showing `child`.
+    pub fn new(child: impl Widget) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed())
+    }
+
+    /// Create a new selectable item showing the child `child`.
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            child,
+            selected: false,
+        }
+    }
+
+    /// Builder-style method to set whether the item is selected.
+    ///
+    /// This is reported to assistive technologies.
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Selectable {
+    /// Set whether the item is selected.
+    pub fn set_selected(this: &mut WidgetMut<'_, Self>, selected: bool) {
+        this.widget.selected = selected;
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Give keyboard focus to the item, e.g. when the selection was moved to it.
+    pub fn focus(this: &mut WidgetMut<'_, Self>) {
+        this.ctx.request_focus();
+    }
+
+    /// Get mutable access to the child.
+    pub fn child_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.child)
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Selectable {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, _) => {
+                if !ctx.is_disabled() {
+                    ctx.capture_pointer();
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) => {
+                if ctx.has_pointer_capture() && ctx.is_hovered() && !ctx.is_disabled() {
+                    let mods = state.mods.state();
+                    let toggle = if cfg!(target_os = "macos") {
+                        mods.super_key()
+                    } else {
+                        mods.control_key()
+                    };
+                    ctx.submit_typed_action(SelectionInput::Click {
+                        toggle,
+                        extend: mods.shift_key(),
+                    });
+                    ctx.request_focus();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let Some((key, _, mods)) = event.pressed_key() else {
+            return;
+        };
+        if ctx.is_disabled() {
+            return;
+        }
+        let action_mod = if cfg!(target_os = "macos") {
+            mods.super_key()
+        } else {
+            mods.control_key()
+        };
+        let movement = |movement| SelectionInput::Move {
+            movement,
+            extend: mods.shift_key(),
+        };
+        let input = match key {
+            Key::Named(NamedKey::ArrowUp) => movement(SelectionMove::Previous),
+            Key::Named(NamedKey::ArrowDown) => movement(SelectionMove::Next),
+            Key::Named(NamedKey::Home) => movement(SelectionMove::First),
+            Key::Named(NamedKey::End) => movement(SelectionMove::Last),
+            Key::Named(NamedKey::Space) => SelectionInput::Toggle,
+            Key::Character(c) if action_mod && c.as_str().eq_ignore_ascii_case("a") => {
+                SelectionInput::SelectAll
+            }
+            _ => return,
+        };
+        ctx.submit_typed_action(input);
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if ctx.target() == ctx.widget_id() && event.action == accesskit::Action::Click {
+            ctx.submit_typed_action(SelectionInput::Click {
+                toggle: false,
+                extend: false,
+            });
+        }
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.child);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = ctx.run_layout(&mut self.child, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+
+        let insets = ctx.compute_insets_from_child(&self.child, size);
+        ctx.set_paint_insets(insets);
+        let baseline_offset = ctx.child_baseline_offset(&self.child);
+        ctx.set_baseline_offset(baseline_offset);
+        size
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::ListItem
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        node.set_selected(self.selected);
+        node.add_action(accesskit::Action::Click);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Selectable", id = ctx.widget_id().trace())
+    }
+}
+
+impl ActionSource for Selectable {
+    type Action = SelectionInput;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::selection::{Selection, SelectionMode};
+    use crate::testing::TestHarness;
+    use crate::widget::{Flex, Label};
+
+    #[test]
+    fn click_selects_item() {
+        let widget = Flex::column()
+            .with_child(Selectable::new(Label::new("First")))
+            .with_child(Selectable::new(Label::new("Second")));
+        let mut harness = TestHarness::create(widget);
+        let items: Vec<_> = harness
+            .root_widget()
+            .children()
+            .iter()
+            .map(|child| child.id())
+            .collect();
+        let mut selection = Selection::new(SelectionMode::Multiple, items.len());
+
+        harness.mouse_click_on(items[1]);
+        let (action, id) = harness.pop_action().unwrap();
+        assert_eq!(id, items[1]);
+        let input = action.downcast::<SelectionInput>().unwrap();
+        assert_eq!(
+            input,
+            SelectionInput::Click {
+                toggle: false,
+                extend: false
+            }
+        );
+        assert_eq!(harness.focused_widget().map(|widget| widget.id()), Some(id));
+
+        let index = items.iter().position(|item| *item == id).unwrap();
+        selection.apply(index, input);
+        assert!(selection.is_selected(1));
+        assert!(!selection.is_selected(0));
+    }
+}
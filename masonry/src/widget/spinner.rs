@@ -96,14 +96,12 @@ impl Widget for Spinner {
         }
     }
 
-    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
         if bc.is_width_bounded() && bc.is_height_bounded() {
             bc.max()
         } else {
-            bc.constrain(Size::new(
-                theme::BASIC_WIDGET_HEIGHT,
-                theme::BASIC_WIDGET_HEIGHT,
-            ))
+            let size = ctx.density().basic_widget_height();
+            bc.constrain(Size::new(size, size))
         }
     }
 
@@ -583,8 +583,25 @@ mod tests {
         assert_render_snapshot!(harness, "rows");
     }
 
-    // FIXME - test moving the split point by mouse
-    // test draggable and min_bar_area
+    #[test]
+    fn drag_split_point() {
+        let widget = Split::columns(Label::new("Hello"), Label::new("World")).draggable(true);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 100.));
+        let split_point = |harness: &TestHarness| {
+            let split = harness.root_widget().downcast::<Split>().unwrap();
+            split.split_point_chosen
+        };
+
+        // The bar is centered on x = 200
+        harness.drag_from_to((200., 50.), (100., 50.));
+        assert_eq!(split_point(&harness), 0.25);
+
+        // Dragging outside the bar does nothing
+        harness.drag_from_to((300., 50.), (350., 50.));
+        assert_eq!(split_point(&harness), 0.25);
+    }
+
+    // FIXME - test min_bar_area
 
     #[test]
     fn edit_splitter() {
@@ -0,0 +1,396 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A status bar pinned under the content of the window.
+
+use accesskit::{Node, Role};
+use parley::layout::Alignment;
+use parley::{FontContext, Layout, LayoutContext};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::kurbo::{Affine, Line, Point, Vec2};
+use vello::Scene;
+
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text::{
+    default_style_properties, render_text, ArcStr, BrushIndex, StyleProperty, TextLayoutJob,
+};
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent,
+    QueryCtx, Rect, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The height of the bar.
+const BAR_HEIGHT: f64 = 24.0;
+/// The space between the edges of the bar and the first and last items.
+const BAR_PADDING: f64 = 8.0;
+/// The space between two items of the bar.
+const ITEM_SPACING: f64 = 16.0;
+/// Appended to the center text when it's too long for the space left by the other items.
+const ELLIPSIS: &str = "…";
+
+/// A text shown in the bar.
+struct StatusText {
+    text: ArcStr,
+    layout: Layout<BrushIndex>,
+    /// Whether `layout` needs to be recreated, because the text or the space for it changed.
+    needs_shaping: bool,
+    /// The position of the text from the left of the bar, from the last layout.
+    x: f64,
+}
+
+/// A bar pinned under `content`, with items of text on its left and right, and a message
+/// in its center, like the status bar of a code editor.
+///
+/// The left and right items always have room. When they leave too little for the center
+/// message, it's ellipsized.
+pub struct StatusBar {
+    content: WidgetPod<Box<dyn Widget>>,
+    left: Vec<StatusText>,
+    center: StatusText,
+    right: Vec<StatusText>,
+    /// The width available to the center message on the last layout.
+    center_width: f64,
+    /// The length of the part of the center message which is shown, without the ellipsis.
+    center_len: usize,
+}
+
+// --- MARK: BUILDERS ---
+impl StatusBar {
+    /// Create a new status bar under `content`, without items.
+    pub fn new(content: impl Widget) -> Self {
+        Self::new_pod(WidgetPod::new(content).boxed())
+    }
+
+    /// Create a new status bar under the widget `content`, without items.
+    pub fn new_pod(content: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            content,
+            left: Vec::new(),
+            center: StatusText::new("".into()),
+            right: Vec::new(),
+            center_width: 0.0,
+            center_len: 0,
+        }
+    }
+
+    /// Builder-style method to set the items on the left of the bar, from left to right.
+    pub fn with_left(mut self, items: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        self.left = StatusText::from_items(items);
+        self
+    }
+
+    /// Builder-style method to set the message in the center of the bar.
+    pub fn with_center(mut self, text: impl Into<ArcStr>) -> Self {
+        self.center = StatusText::new(text.into());
+        self
+    }
+
+    /// Builder-style method to set the items on the right of the bar, from left to right.
+    pub fn with_right(mut self, items: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        self.right = StatusText::from_items(items);
+        self
+    }
+
+    /// The items on the left of the bar.
+    pub fn left(&self) -> impl Iterator<Item = &ArcStr> {
+        self.left.iter().map(|item| &item.text)
+    }
+
+    /// The message in the center of the bar, including the part hidden by the ellipsis.
+    pub fn center(&self) -> &ArcStr {
+        &self.center.text
+    }
+
+    /// The items on the right of the bar.
+    pub fn right(&self) -> impl Iterator<Item = &ArcStr> {
+        self.right.iter().map(|item| &item.text)
+    }
+
+    /// Whether the center message was too long to be shown whole on the last layout.
+    pub fn is_center_ellipsized(&self) -> bool {
+        self.center_len < self.center.text.len()
+    }
+}
+
+impl StatusText {
+    fn new(text: ArcStr) -> Self {
+        Self {
+            text,
+            layout: Layout::new(),
+            needs_shaping: true,
+            x: 0.0,
+        }
+    }
+
+    fn from_items(items: impl IntoIterator<Item = impl Into<ArcStr>>) -> Vec<Self> {
+        items
+            .into_iter()
+            .map(|item| Self::new(item.into()))
+            .collect()
+    }
+
+    fn width(&self) -> f64 {
+        self.layout.width().into()
+    }
+
+    /// Shape `text` into `layout`.
+    fn shape(
+        font_ctx: &mut FontContext,
+        layout_ctx: &mut LayoutContext<BrushIndex>,
+        text: ArcStr,
+        layout: &mut Layout<BrushIndex>,
+    ) {
+        let mut job = TextLayoutJob::new(
+            text,
+            default_style_properties()
+                .into_iter()
+                .chain([StyleProperty::FontSize(theme::TEXT_SIZE_NORMAL)]),
+            std::mem::replace(layout, Layout::new()),
+        );
+        job.run(font_ctx, layout_ctx);
+        job.layout.break_all_lines(None);
+        job.layout.align(None, Alignment::Start);
+        *layout = job.layout;
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl StatusBar {
+    /// Set the items on the left of the bar, from left to right.
+    pub fn set_left(
+        this: &mut WidgetMut<'_, Self>,
+        items: impl IntoIterator<Item = impl Into<ArcStr>>,
+    ) {
+        Self::set_items(this, items, |bar| &mut bar.left);
+    }
+
+    /// Set the message in the center of the bar.
+    pub fn set_center(this: &mut WidgetMut<'_, Self>, text: impl Into<ArcStr>) {
+        let text = text.into();
+        if text == this.widget.center.text {
+            return;
+        }
+        this.widget.center = StatusText::new(text);
+        this.ctx.request_layout();
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Set the items on the right of the bar, from left to right.
+    pub fn set_right(
+        this: &mut WidgetMut<'_, Self>,
+        items: impl IntoIterator<Item = impl Into<ArcStr>>,
+    ) {
+        Self::set_items(this, items, |bar| &mut bar.right);
+    }
+
+    fn set_items(
+        this: &mut WidgetMut<'_, Self>,
+        items: impl IntoIterator<Item = impl Into<ArcStr>>,
+        section: impl Fn(&mut Self) -> &mut Vec<StatusText>,
+    ) {
+        let items = StatusText::from_items(items);
+        let old_items = section(this.widget);
+        if old_items
+            .iter()
+            .map(|item| &item.text)
+            .eq(items.iter().map(|item| &item.text))
+        {
+            return;
+        }
+        *old_items = items;
+        // The space left for the center message changes.
+        this.widget.center.needs_shaping = true;
+        this.ctx.request_layout();
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Get mutable access to the content above the bar.
+    pub fn content_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.content)
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl StatusBar {
+    /// Shape the center message, ellipsized to fit in `max_width` if needed.
+    fn shape_center(
+        &mut self,
+        font_ctx: &mut FontContext,
+        layout_ctx: &mut LayoutContext<BrushIndex>,
+        max_width: f64,
+    ) {
+        let center = &mut self.center;
+        StatusText::shape(
+            font_ctx,
+            layout_ctx,
+            center.text.clone(),
+            &mut center.layout,
+        );
+        self.center_len = center.text.len();
+        if center.width() <= max_width {
+            return;
+        }
+
+        // Find the longest prefix which fits with the ellipsis.
+        let boundaries: Vec<usize> = center.text.char_indices().map(|(idx, _)| idx).collect();
+        let mut shape_prefix = |chars: usize, layout: &mut Layout<BrushIndex>| {
+            let prefix = center.text[..boundaries[chars]].trim_end();
+            let text = format!("{prefix}{ELLIPSIS}");
+            StatusText::shape(font_ctx, layout_ctx, text.into(), layout);
+            prefix.len()
+        };
+        let (mut fits, mut too_long) = (0, boundaries.len());
+        while too_long - fits > 1 {
+            let mid = (fits + too_long) / 2;
+            shape_prefix(mid, &mut center.layout);
+            if center.layout.width() as f64 <= max_width {
+                fits = mid;
+            } else {
+                too_long = mid;
+            }
+        }
+        self.center_len = shape_prefix(fits, &mut center.layout);
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for StatusBar {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.content);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = bc.max().width;
+        let content_bc = if bc.is_height_bounded() {
+            BoxConstraints::tight(Size::new(width, (bc.max().height - BAR_HEIGHT).max(0.0)))
+        } else {
+            bc.shrink((0.0, BAR_HEIGHT))
+        };
+        let content_size = ctx.run_layout(&mut self.content, &content_bc);
+        ctx.place_child(&mut self.content, Point::ORIGIN);
+        let width = if width.is_finite() {
+            width
+        } else {
+            content_size.width
+        };
+
+        let (font_ctx, layout_ctx) = ctx.text_contexts();
+        for item in self.left.iter_mut().chain(&mut self.right) {
+            if std::mem::take(&mut item.needs_shaping) {
+                StatusText::shape(font_ctx, layout_ctx, item.text.clone(), &mut item.layout);
+            }
+        }
+
+        let mut left_end = BAR_PADDING;
+        for item in &mut self.left {
+            item.x = left_end;
+            left_end += item.width() + ITEM_SPACING;
+        }
+        let mut right_start = width - BAR_PADDING;
+        for item in self.right.iter_mut().rev() {
+            right_start -= item.width();
+            item.x = right_start;
+            right_start -= ITEM_SPACING;
+        }
+
+        let center_width = (right_start - left_end).max(0.0);
+        if std::mem::take(&mut self.center.needs_shaping) || center_width != self.center_width {
+            self.center_width = center_width;
+            self.shape_center(font_ctx, layout_ctx, center_width);
+        }
+        // The message is centered in the bar, unless this would overlap the other items.
+        let centered = (width - self.center.width()) / 2.0;
+        self.center.x = centered.clamp(left_end, (right_start - self.center.width()).max(left_end));
+
+        let size = Size::new(width, content_size.height + BAR_HEIGHT);
+        ctx.set_clip_path(size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let bar_y = size.height - BAR_HEIGHT;
+        fill_color(
+            scene,
+            &Rect::new(0.0, bar_y, size.width, size.height),
+            theme::BACKGROUND_DARK,
+        );
+        let border = Line::new((0.0, bar_y + 0.5), (size.width, bar_y + 0.5));
+        stroke(scene, &border, theme::BORDER_DARK, 1.0);
+
+        for item in self.left.iter().chain([&self.center]).chain(&self.right) {
+            let text_y = bar_y + (BAR_HEIGHT - f64::from(item.layout.height())) / 2.0;
+            let transform = Affine::translate(Vec2::new(item.x, text_y));
+            render_text(
+                scene,
+                transform,
+                &item.layout,
+                &[theme::TEXT_COLOR.into()],
+                true,
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        let texts = self.left.iter().chain([&self.center]).chain(&self.right);
+        let status: Vec<&str> = texts
+            .map(|item| &*item.text)
+            .filter(|text| !text.is_empty())
+            .collect();
+        node.set_description(status.join(", "));
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.content.id()]
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("StatusBar", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::SizedBox;
+
+    #[test]
+    fn long_center_is_ellipsized() {
+        let [content_id] = widget_ids();
+        let widget =
+            StatusBar::new_pod(WidgetPod::new_with_id(SizedBox::empty(), content_id).boxed())
+                .with_left(["main"])
+                .with_center("Indexing the workspace, this may take a while for large projects")
+                .with_right(["Ln 1, Col 1", "UTF-8"]);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(300., 200.));
+        let content_rect = harness.get_widget(content_id).ctx().layout_rect();
+        assert_eq!(content_rect, Rect::new(0., 0., 300., 200. - BAR_HEIGHT));
+
+        let bar = harness.root_widget().downcast::<StatusBar>().unwrap();
+        assert!(bar.is_center_ellipsized());
+        assert!(bar.center.width() <= bar.center_width);
+        assert!(bar.center.x >= bar.left[0].x + bar.left[0].width());
+
+        // The whole message is shown when there is room for it
+        harness.edit_root_widget(|mut bar| {
+            StatusBar::set_center(&mut bar.downcast::<StatusBar>(), "Ready");
+        });
+        let bar = harness.root_widget().downcast::<StatusBar>().unwrap();
+        assert!(!bar.is_center_ellipsized());
+        assert_eq!(&**bar.center(), "Ready");
+    }
+}
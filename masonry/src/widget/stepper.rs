@@ -0,0 +1,585 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A wizard showing one step at a time, with back and next buttons.
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{Circle, Line, Vec2};
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text::ArcStr;
+use crate::widget::{Button, Label, WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, ActionSource, BoxConstraints, ComposeCtx, EventCtx, LayoutCtx,
+    PaintCtx, Point, PointerEvent, QueryCtx, RegisterCtx, Size, TextEvent, UpdateCtx, Widget,
+    WidgetId,
+};
+
+/// The diameter of the circles of the progress header.
+const CIRCLE_SIZE: f64 = 20.0;
+/// The space between a circle of the progress header and the title of its step.
+const TITLE_SPACING: f64 = 4.0;
+/// The space between the progress header, the content of the step and the buttons.
+const SECTION_SPACING: f64 = 12.0;
+/// The width of a stepper without width constraints.
+const DEFAULT_WIDTH: f64 = 400.0;
+/// The duration of the slide between two steps, in seconds.
+const TRANSITION_TIME: f64 = 0.25;
+
+/// The current step of a [`Stepper`] changed, or its last step was completed.
+///
+/// This is the [`ActionSource::Action`] of [`Stepper`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepperAction {
+    /// The user went to the step at this index, with the back or next button.
+    StepChanged(usize),
+    /// The user pressed the finish button of the last step.
+    Finished,
+}
+
+struct Step {
+    title: WidgetPod<Label>,
+    content: WidgetPod<Box<dyn Widget>>,
+    /// Whether the user can go past this step.
+    valid: bool,
+}
+
+/// The slide from one step to the current one.
+struct Transition {
+    from: usize,
+    forward: bool,
+    /// From 0 to 1.
+    progress: f64,
+}
+
+/// A wizard, which shows the content of one step at a time.
+///
+/// A header shows the title of every step and how far the user got. Under the content of the
+/// current step, the back and next buttons move between the steps, with a slide animation.
+/// The next button is disabled while the current step is invalid, so that the user has to
+/// complete each step before going further. On the last step, it's replaced by a finish button.
+///
+/// The stepper submits a [`StepperAction`] [typed action](crate::Action::typed) for each
+/// navigation. Its buttons also submit their own
+/// [`Action::ButtonPressed`](crate::Action::ButtonPressed), which can be ignored.
+pub struct Stepper {
+    steps: Vec<Step>,
+    current: usize,
+    back: WidgetPod<Button>,
+    next: WidgetPod<Button>,
+    finish: WidgetPod<Button>,
+    transition: Option<Transition>,
+    /// The width of the column of each step in the header, from the last layout.
+    column_width: f64,
+}
+
+// --- MARK: BUILDERS ---
+impl Stepper {
+    /// Create a new stepper without steps, with "Back", "Next" and "Finish" buttons.
+    pub fn new() -> Self {
+        Self::new_pods(
+            WidgetPod::new(Button::new("Back")),
+            WidgetPod::new(Button::new("Next")),
+            WidgetPod::new(Button::new("Finish")),
+        )
+    }
+
+    /// Create a new stepper without steps, with the given buttons.
+    ///
+    /// This can be used to know the ids of the buttons.
+    pub fn new_pods(
+        back: WidgetPod<Button>,
+        next: WidgetPod<Button>,
+        finish: WidgetPod<Button>,
+    ) -> Self {
+        Self {
+            steps: Vec::new(),
+            current: 0,
+            back,
+            next,
+            finish,
+            transition: None,
+            column_width: 0.0,
+        }
+    }
+
+    /// Builder-style method to add a step.
+    pub fn with_step(self, title: impl Into<ArcStr>, content: impl Widget) -> Self {
+        self.with_step_pod(title, WidgetPod::new(content).boxed())
+    }
+
+    /// Builder-style method to add a step.
+    pub fn with_step_pod(
+        mut self,
+        title: impl Into<ArcStr>,
+        content: WidgetPod<Box<dyn Widget>>,
+    ) -> Self {
+        self.steps.push(Step::new(title.into(), content));
+        self
+    }
+
+    /// Builder-style method to set whether the user can go past the last step added.
+    pub fn valid(mut self, valid: bool) -> Self {
+        if let Some(step) = self.steps.last_mut() {
+            step.valid = valid;
+        }
+        self
+    }
+
+    /// Builder-style method to set the step shown first.
+    pub fn with_current(mut self, current: usize) -> Self {
+        self.current = current;
+        self
+    }
+
+    /// The index of the current step.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Whether the user can go past the step at `idx`.
+    pub fn is_step_valid(&self, idx: usize) -> bool {
+        self.steps[idx].valid
+    }
+
+    /// The id of the back button.
+    pub fn back_id(&self) -> WidgetId {
+        self.back.id()
+    }
+
+    /// The id of the next button.
+    pub fn next_id(&self) -> WidgetId {
+        self.next.id()
+    }
+
+    /// The id of the finish button.
+    pub fn finish_id(&self) -> WidgetId {
+        self.finish.id()
+    }
+
+    /// The number of steps.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether the stepper has no steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl Default for Stepper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Step {
+    fn new(title: ArcStr, content: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            title: WidgetPod::new(Label::new(title)),
+            content,
+            valid: true,
+        }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Stepper {
+    /// Add a step at the end.
+    pub fn add_step(
+        this: &mut WidgetMut<'_, Self>,
+        title: impl Into<ArcStr>,
+        content: impl Widget,
+    ) {
+        let len = this.widget.steps.len();
+        Self::insert_step_pod(this, len, title, WidgetPod::new(content).boxed());
+    }
+
+    /// Insert a step at `idx`.
+    ///
+    /// The current step stays the same, even if its index changes.
+    pub fn insert_step_pod(
+        this: &mut WidgetMut<'_, Self>,
+        idx: usize,
+        title: impl Into<ArcStr>,
+        content: WidgetPod<Box<dyn Widget>>,
+    ) {
+        if idx <= this.widget.current && !this.widget.steps.is_empty() {
+            this.widget.current += 1;
+        }
+        this.widget
+            .steps
+            .insert(idx, Step::new(title.into(), content));
+        this.widget.transition = None;
+        this.ctx.children_changed();
+    }
+
+    /// Remove the step at `idx`.
+    ///
+    /// If it's the current step, the next step becomes current, or the previous one if there
+    /// is no next step.
+    pub fn remove_step(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        let step = this.widget.steps.remove(idx);
+        this.ctx.remove_child(step.title);
+        this.ctx.remove_child(step.content);
+        let current = &mut this.widget.current;
+        if idx < *current || *current == this.widget.steps.len() {
+            *current = current.saturating_sub(1);
+        }
+        this.widget.transition = None;
+        this.ctx.request_layout();
+    }
+
+    /// Get mutable access to the content of the step at `idx`.
+    pub fn step_mut<'t>(
+        this: &'t mut WidgetMut<'_, Self>,
+        idx: usize,
+    ) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.steps[idx].content)
+    }
+
+    /// Set the title of the step at `idx`.
+    pub fn set_step_title(this: &mut WidgetMut<'_, Self>, idx: usize, title: impl Into<ArcStr>) {
+        Label::set_text(
+            &mut this.ctx.get_mut(&mut this.widget.steps[idx].title),
+            title,
+        );
+    }
+
+    /// Set whether the user can go past the step at `idx`.
+    pub fn set_step_valid(this: &mut WidgetMut<'_, Self>, idx: usize, valid: bool) {
+        this.widget.steps[idx].valid = valid;
+        this.ctx.request_layout();
+    }
+
+    /// Go to the step at `idx`, with the same animation as the buttons.
+    ///
+    /// This doesn't submit a [`StepperAction`].
+    pub fn set_current(this: &mut WidgetMut<'_, Self>, idx: usize) {
+        if this.widget.current == idx {
+            return;
+        }
+        this.widget.go_to(idx);
+        this.ctx.request_anim_frame();
+        this.ctx.request_layout();
+    }
+
+    /// Get mutable access to the back button.
+    pub fn back_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Button> {
+        this.ctx.get_mut(&mut this.widget.back)
+    }
+
+    /// Get mutable access to the next button.
+    pub fn next_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Button> {
+        this.ctx.get_mut(&mut this.widget.next)
+    }
+
+    /// Get mutable access to the finish button.
+    pub fn finish_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Button> {
+        this.ctx.get_mut(&mut this.widget.finish)
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl Stepper {
+    fn go_to(&mut self, idx: usize) {
+        self.transition = Some(Transition {
+            from: self.current,
+            forward: idx > self.current,
+            progress: 0.0,
+        });
+        self.current = idx;
+    }
+
+    fn is_last_step(&self) -> bool {
+        self.current + 1 >= self.steps.len()
+    }
+
+    fn can_advance(&self) -> bool {
+        self.steps.get(self.current).is_some_and(|step| step.valid)
+    }
+
+    /// Whether the current pointer event completes a click on `button`.
+    ///
+    /// The buttons don't handle their clicks, so they bubble up to the stepper.
+    fn is_clicked(ctx: &mut EventCtx, button: &mut WidgetPod<Button>) -> bool {
+        let button = ctx.get_raw_ref(button);
+        let button_ctx = button.ctx();
+        button_ctx.has_pointer_capture() && button_ctx.is_hovered() && !button_ctx.is_disabled()
+    }
+
+    fn on_button(&mut self, ctx: &mut EventCtx, button: WidgetId) {
+        if button == self.back.id() && self.current > 0 {
+            self.go_to(self.current - 1);
+            ctx.submit_typed_action(StepperAction::StepChanged(self.current));
+        } else if button == self.next.id() && !self.is_last_step() && self.can_advance() {
+            self.go_to(self.current + 1);
+            ctx.submit_typed_action(StepperAction::StepChanged(self.current));
+        } else if button == self.finish.id() && self.is_last_step() && self.can_advance() {
+            ctx.submit_typed_action(StepperAction::Finished);
+            return;
+        } else {
+            return;
+        }
+        ctx.request_anim_frame();
+        ctx.request_layout();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Stepper {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        if let PointerEvent::PointerUp(PointerButton::Primary, _) = event {
+            let target = ctx.target();
+            let clicked = if target == self.back.id() {
+                Self::is_clicked(ctx, &mut self.back)
+            } else if target == self.next.id() {
+                Self::is_clicked(ctx, &mut self.next)
+            } else if target == self.finish.id() {
+                Self::is_clicked(ctx, &mut self.finish)
+            } else {
+                false
+            };
+            if clicked {
+                self.on_button(ctx, target);
+            }
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.action == accesskit::Action::Click {
+            self.on_button(ctx, ctx.target());
+        }
+    }
+
+    fn on_anim_frame(&mut self, ctx: &mut UpdateCtx, interval: u64) {
+        let Some(transition) = &mut self.transition else {
+            return;
+        };
+        transition.progress += interval as f64 * 1e-9 / TRANSITION_TIME;
+        if transition.progress >= 1.0 {
+            // The previous step is stashed again.
+            self.transition = None;
+            ctx.request_layout();
+        } else {
+            ctx.request_anim_frame();
+        }
+        ctx.request_compose();
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        for step in &mut self.steps {
+            ctx.register_child(&mut step.title);
+            ctx.register_child(&mut step.content);
+        }
+        ctx.register_child(&mut self.back);
+        ctx.register_child(&mut self.next);
+        ctx.register_child(&mut self.finish);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            DEFAULT_WIDTH.max(bc.min().width)
+        };
+
+        // The header has a column for each step, with a circle above its title.
+        self.column_width = width / self.steps.len().max(1) as f64;
+        let title_y = CIRCLE_SIZE + TITLE_SPACING;
+        let title_bc = BoxConstraints::new(Size::ZERO, Size::new(self.column_width, f64::INFINITY));
+        let mut header_height = CIRCLE_SIZE;
+        for (idx, step) in self.steps.iter_mut().enumerate() {
+            let title_size = ctx.run_layout(&mut step.title, &title_bc);
+            let column_center = self.column_width * (idx as f64 + 0.5);
+            let title_origin = Point::new(column_center - title_size.width / 2.0, title_y);
+            ctx.place_child(&mut step.title, title_origin);
+            header_height = header_height.max(title_y + title_size.height);
+        }
+
+        // Only the last step has a finish button.
+        let is_last_step = self.is_last_step();
+        let can_advance = self.can_advance();
+        ctx.set_child_disabled(&mut self.back, self.current == 0);
+        ctx.set_child_disabled(&mut self.next, !can_advance);
+        ctx.set_child_disabled(&mut self.finish, !can_advance);
+        ctx.set_stashed(&mut self.next, is_last_step);
+        ctx.set_stashed(&mut self.finish, !is_last_step);
+        let (forward, hidden) = if is_last_step {
+            (&mut self.finish, &mut self.next)
+        } else {
+            (&mut self.next, &mut self.finish)
+        };
+        ctx.skip_layout(hidden);
+        let forward_size = ctx.run_layout(forward, &bc.loosen());
+        let back_size = ctx.run_layout(&mut self.back, &bc.loosen());
+        let buttons_height = forward_size.height.max(back_size.height);
+
+        let content_y = header_height + SECTION_SPACING;
+        let content_bc = if bc.is_height_bounded() {
+            let height = bc.max().height - content_y - SECTION_SPACING - buttons_height;
+            BoxConstraints::tight(Size::new(width, height.max(0.0)))
+        } else {
+            BoxConstraints::new(Size::new(width, 0.0), Size::new(width, f64::INFINITY))
+        };
+        let from = self.transition.as_ref().map(|transition| transition.from);
+        let mut content_height: f64 = 0.0;
+        for (idx, step) in self.steps.iter_mut().enumerate() {
+            let shown = idx == self.current || Some(idx) == from;
+            ctx.set_stashed(&mut step.content, !shown);
+            if shown {
+                let size = ctx.run_layout(&mut step.content, &content_bc);
+                ctx.place_child(&mut step.content, Point::new(0.0, content_y));
+                content_height = content_height.max(size.height);
+            } else {
+                ctx.skip_layout(&mut step.content);
+            }
+        }
+
+        let buttons_y = content_y + content_height + SECTION_SPACING;
+        ctx.place_child(&mut self.back, Point::new(0.0, buttons_y));
+        let forward_origin = Point::new(width - forward_size.width, buttons_y);
+        ctx.place_child(forward, forward_origin);
+
+        let size = bc.constrain(Size::new(width, buttons_y + buttons_height));
+        // The steps slide in and out of the stepper.
+        ctx.set_clip_path(size.to_rect());
+        size
+    }
+
+    fn compose(&mut self, ctx: &mut ComposeCtx) {
+        let width = ctx.size().width;
+        let (from, from_offset, offset) = match &self.transition {
+            Some(transition) => {
+                let eased = 1.0 - (1.0 - transition.progress).powi(3);
+                let direction = if transition.forward { 1.0 } else { -1.0 };
+                let offset = (1.0 - eased) * width * direction;
+                (Some(transition.from), offset - width * direction, offset)
+            }
+            None => (None, 0.0, 0.0),
+        };
+        if let Some(step) = self.steps.get_mut(self.current) {
+            ctx.set_child_translation(&mut step.content, Vec2::new(offset, 0.0));
+        }
+        if let Some(step) = from.and_then(|from| self.steps.get_mut(from)) {
+            ctx.set_child_translation(&mut step.content, Vec2::new(from_offset, 0.0));
+        }
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, scene: &mut Scene) {
+        let center_y = CIRCLE_SIZE / 2.0;
+        let center_of = |idx: usize| Point::new(self.column_width * (idx as f64 + 0.5), center_y);
+
+        for idx in 1..self.steps.len() {
+            let color = if idx <= self.current {
+                theme::PRIMARY_DARK
+            } else {
+                theme::BORDER_LIGHT
+            };
+            let line = Line::new(
+                center_of(idx - 1) + Vec2::new(CIRCLE_SIZE / 2.0, 0.0),
+                center_of(idx) - Vec2::new(CIRCLE_SIZE / 2.0, 0.0),
+            );
+            stroke(scene, &line, color, 2.0);
+        }
+        for idx in 0..self.steps.len() {
+            let circle = Circle::new(center_of(idx), CIRCLE_SIZE / 2.0 - 1.0);
+            if idx < self.current {
+                fill_color(scene, &circle, theme::PRIMARY_DARK);
+            } else if idx == self.current {
+                fill_color(scene, &circle, theme::PRIMARY_LIGHT);
+                stroke(scene, &circle, theme::PRIMARY_DARK, 2.0);
+            } else {
+                stroke(scene, &circle, theme::BORDER_LIGHT, 2.0);
+            }
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut ids = SmallVec::new();
+        for step in &self.steps {
+            ids.push(step.title.id());
+            ids.push(step.content.id());
+        }
+        ids.extend([self.back.id(), self.next.id(), self.finish.id()]);
+        ids
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Stepper", id = ctx.widget_id().trace())
+    }
+}
+
+impl ActionSource for Stepper {
+    type Action = StepperAction;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::SizedBox;
+    use crate::Action;
+
+    #[test]
+    fn next_needs_valid_step() {
+        let [next_id, finish_id, first_id, second_id] = widget_ids();
+        let widget = Stepper::new_pods(
+            WidgetPod::new(Button::new("Back")),
+            WidgetPod::new_with_id(Button::new("Next"), next_id),
+            WidgetPod::new_with_id(Button::new("Finish"), finish_id),
+        )
+        .with_step_pod(
+            "Account",
+            WidgetPod::new_with_id(SizedBox::empty(), first_id).boxed(),
+        )
+        .valid(false)
+        .with_step_pod(
+            "Profile",
+            WidgetPod::new_with_id(SizedBox::empty(), second_id).boxed(),
+        );
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 300.));
+        assert!(harness.get_widget(next_id).ctx().is_disabled());
+        assert!(harness.get_widget(second_id).ctx().is_stashed());
+
+        harness.edit_root_widget(|mut stepper| {
+            Stepper::set_step_valid(&mut stepper.downcast(), 0, true);
+        });
+        harness.mouse_click_on(next_id);
+        let actions: Vec<_> = std::iter::from_fn(|| harness.pop_action()).collect();
+        assert_eq!(
+            actions.last().unwrap().0.downcast_ref::<StepperAction>(),
+            Some(&StepperAction::StepChanged(1))
+        );
+        // Both steps are shown during the slide
+        assert!(!harness.get_widget(first_id).ctx().is_stashed());
+        harness.animate_ms(500);
+        assert!(harness.get_widget(first_id).ctx().is_stashed());
+        assert!(!harness.get_widget(second_id).ctx().is_stashed());
+
+        harness.mouse_click_on(finish_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed(PointerButton::Primary), finish_id))
+        );
+        let (action, _) = harness.pop_action().unwrap();
+        assert_eq!(
+            action.downcast::<StepperAction>().ok(),
+            Some(StepperAction::Finished)
+        );
+    }
+}
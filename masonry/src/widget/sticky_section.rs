@@ -0,0 +1,168 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A section of a scrolling list, with a header pinned to the top of the viewport.
+
+use accesskit::{Node, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::kurbo::Vec2;
+use vello::Scene;
+
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, ComposeCtx, EventCtx, LayoutCtx, PaintCtx, Point,
+    PointerEvent, QueryCtx, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// A header followed by a body, where the header sticks to the top of the enclosing
+/// [`Portal`](super::Portal) while the section is scrolled through.
+///
+/// The header stays pinned until the bottom of the section reaches it, so that it's pushed
+/// off by the header of the next section.
+/// The header is painted over the body, so it should usually have an opaque background.
+pub struct StickySection {
+    header: WidgetPod<Box<dyn Widget>>,
+    body: WidgetPod<Box<dyn Widget>>,
+    header_height: f64,
+}
+
+// --- MARK: BUILDERS ---
+impl StickySection {
+    /// Create a new section with the given `header` and `body`.
+    pub fn new(header: impl Widget, body: impl Widget) -> Self {
+        Self::new_pod(WidgetPod::new(header).boxed(), WidgetPod::new(body).boxed())
+    }
+
+    /// Create a new section with the given `header` and `body` pods.
+    pub fn new_pod(header: WidgetPod<Box<dyn Widget>>, body: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            header,
+            body,
+            header_height: 0.0,
+        }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl StickySection {
+    /// Get mutable access to the header.
+    pub fn header_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.header)
+    }
+
+    /// Get mutable access to the body.
+    pub fn body_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Box<dyn Widget>> {
+        this.ctx.get_mut(&mut this.widget.body)
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for StickySection {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.header);
+        ctx.register_child(&mut self.body);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // The header is pinned when the section moves, which it does when it's scrolled.
+        ctx.set_compose_on_move(true);
+
+        let header_size = ctx.run_layout(&mut self.header, &bc.loosen());
+        let body_bc = bc.shrink((0.0, header_size.height)).loosen();
+        let body_size = ctx.run_layout(&mut self.body, &body_bc);
+        ctx.place_child(&mut self.header, Point::ORIGIN);
+        ctx.place_child(&mut self.body, Point::new(0.0, header_size.height));
+        self.header_height = header_size.height;
+
+        let size = Size::new(
+            header_size.width.max(body_size.width),
+            header_size.height + body_size.height,
+        );
+        let size = bc.constrain(size);
+        let baseline_offset = ctx.child_baseline_offset(&self.header);
+        ctx.set_baseline_offset(baseline_offset + size.height - header_size.height);
+        size
+    }
+
+    fn compose(&mut self, ctx: &mut ComposeCtx) {
+        let offset = ctx.ancestor_clip_rect().map_or(0.0, |clip| {
+            let max_offset = (ctx.size().height - self.header_height).max(0.0);
+            (clip.y0 - ctx.window_origin().y).clamp(0.0, max_offset)
+        });
+        ctx.set_child_translation(&mut self.header, Vec2::new(0.0, offset));
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::Section
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        // The header comes last, so that it's painted over the body.
+        smallvec![self.body.id(), self.header.id()]
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("StickySection", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Flex, Portal, SizedBox};
+
+    fn section(header_id: WidgetId) -> StickySection {
+        StickySection::new_pod(
+            WidgetPod::new_with_id(SizedBox::empty().width(100.).height(20.), header_id).boxed(),
+            WidgetPod::new(SizedBox::empty().width(100.).height(180.)).boxed(),
+        )
+    }
+
+    #[test]
+    fn header_sticks_until_pushed_off() {
+        let [first_header, second_header] = widget_ids();
+        let widget = Portal::new(
+            Flex::column()
+                .with_child(section(first_header))
+                .with_child(section(second_header)),
+        )
+        .constrain_horizontal(true);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(100., 100.));
+        let header_y = |harness: &TestHarness, id| harness.get_widget(id).ctx().window_origin().y;
+        let scroll_to = |harness: &mut TestHarness, y| {
+            harness.edit_root_widget(|mut portal| {
+                let mut portal = portal.downcast::<Portal<Flex>>();
+                Portal::set_viewport_pos(&mut portal, Point::new(0.0, y));
+            });
+        };
+        assert_eq!(header_y(&harness, first_header), 0.0);
+        assert_eq!(header_y(&harness, second_header), 200.0);
+
+        // The first header is pinned to the top of the viewport
+        scroll_to(&mut harness, 50.0);
+        assert_eq!(header_y(&harness, first_header), 0.0);
+        assert_eq!(header_y(&harness, second_header), 150.0);
+
+        // The second header pushes the first one off
+        scroll_to(&mut harness, 190.0);
+        assert_eq!(header_y(&harness, first_header), -10.0);
+        assert_eq!(header_y(&harness, second_header), 10.0);
+
+        scroll_to(&mut harness, 250.0);
+        assert_eq!(header_y(&harness, second_header), 0.0);
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget showing a placeholder until its content is ready.
+
+use std::time::Duration;
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::Scene;
+
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point, PointerEvent,
+    QueryCtx, RegisterCtx, Size, TextEvent, UpdateCtx, Widget, WidgetId,
+};
+
+/// A widget showing a placeholder, such as a [`Spinner`](crate::widget::Spinner), until its
+/// content is given, e.g. once it has been loaded.
+///
+/// The content then replaces the placeholder, fading in over the
+/// [transition](Self::with_transition) if there is one.
+pub struct Suspense {
+    placeholder: Option<WidgetPod<Box<dyn Widget>>>,
+    content: Option<WidgetPod<Box<dyn Widget>>>,
+    transition: Duration,
+    /// How far the content has faded in, between 0 and 1.
+    progress: f64,
+}
+
+// --- MARK: BUILDERS ---
+impl Suspense {
+    /// Create a new suspense widget showing `placeholder`.
+    pub fn new(placeholder: impl Widget) -> Self {
+        Self::new_pod(WidgetPod::new(placeholder).boxed())
+    }
+
+    /// Create a new suspense widget showing the placeholder in `placeholder`.
+    pub fn new_pod(placeholder: WidgetPod<Box<dyn Widget>>) -> Self {
+        Self {
+            placeholder: Some(placeholder),
+            content: None,
+            transition: Duration::ZERO,
+            progress: 0.,
+        }
+    }
+
+    /// Builder-style method to fade the content in over `transition` when it's given.
+    ///
+    /// By default, the content replaces the placeholder immediately.
+    pub fn with_transition(mut self, transition: Duration) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Builder-style method to show `content` from the start.
+    pub fn with_content(mut self, content: WidgetPod<Box<dyn Widget>>) -> Self {
+        self.content = Some(content);
+        self.placeholder = None;
+        self.progress = 1.;
+        self
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Suspense {
+    /// Replace the placeholder, or the previous content, with `content`.
+    ///
+    /// The transition is only played when the placeholder is replaced.
+    pub fn set_content(this: &mut WidgetMut<'_, Self>, content: WidgetPod<Box<dyn Widget>>) {
+        if let Some(previous) = this.widget.content.take() {
+            this.ctx.remove_child(previous);
+        }
+        this.widget.content = Some(content);
+        if this.widget.placeholder.is_some() && !this.widget.transition.is_zero() {
+            this.widget.progress = 0.;
+            this.ctx.request_anim_frame();
+        } else {
+            Self::remove_placeholder(this);
+        }
+        this.ctx.children_changed();
+    }
+
+    /// Set how long the content takes to fade in when it replaces the placeholder.
+    pub fn set_transition(this: &mut WidgetMut<'_, Self>, transition: Duration) {
+        this.widget.transition = transition;
+    }
+
+    /// Get a mutable reference to the placeholder, unless it has been replaced by the content.
+    ///
+    /// During the transition, the placeholder is still shown, but this returns `None`.
+    pub fn placeholder_mut<'t>(
+        this: &'t mut WidgetMut<'_, Self>,
+    ) -> Option<WidgetMut<'t, Box<dyn Widget>>> {
+        if this.widget.content.is_some() {
+            return None;
+        }
+        let placeholder = this.widget.placeholder.as_mut()?;
+        Some(this.ctx.get_mut(placeholder))
+    }
+
+    /// Get a mutable reference to the content, if it has been given.
+    pub fn content_mut<'t>(
+        this: &'t mut WidgetMut<'_, Self>,
+    ) -> Option<WidgetMut<'t, Box<dyn Widget>>> {
+        let content = this.widget.content.as_mut()?;
+        Some(this.ctx.get_mut(content))
+    }
+
+    fn remove_placeholder(this: &mut WidgetMut<'_, Self>) {
+        if let Some(placeholder) = this.widget.placeholder.take() {
+            this.ctx.remove_child(placeholder);
+        }
+        this.widget.progress = 1.;
+        this.ctx.request_layout();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Suspense {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_anim_frame(&mut self, ctx: &mut UpdateCtx, interval: u64) {
+        if self.placeholder.is_none() || self.content.is_none() {
+            return;
+        }
+        self.progress += (interval as f64) * 1e-9 / self.transition.as_secs_f64();
+        if self.progress >= 1.0 {
+            self.progress = 1.0;
+            if let Some(placeholder) = self.placeholder.take() {
+                ctx.remove_child(placeholder);
+            }
+            ctx.request_layout();
+        } else {
+            ctx.request_anim_frame();
+        }
+        if let Some(content) = &mut self.content {
+            ctx.set_child_opacity(content, self.progress);
+        }
+        if let Some(placeholder) = &mut self.placeholder {
+            ctx.set_child_opacity(placeholder, 1.0 - self.progress);
+        }
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        if let Some(placeholder) = &mut self.placeholder {
+            ctx.register_child(placeholder);
+        }
+        if let Some(content) = &mut self.content {
+            ctx.register_child(content);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // This is set here rather than in `set_content`, as the content isn't in the tree yet
+        // then, so that it doesn't appear fully opaque before the first animation frame.
+        if let (Some(placeholder), Some(content)) = (&mut self.placeholder, &mut self.content) {
+            ctx.set_child_opacity(placeholder, 1.0 - self.progress);
+            ctx.set_child_opacity(content, self.progress);
+        }
+
+        // During the transition, the placeholder and the content overlap.
+        let mut size = Size::ZERO;
+        for child in [&mut self.placeholder, &mut self.content]
+            .into_iter()
+            .flatten()
+        {
+            let child_size = ctx.run_layout(child, bc);
+            ctx.place_child(child, Point::ORIGIN);
+            size = Size::new(
+                size.width.max(child_size.width),
+                size.height.max(child_size.height),
+            );
+        }
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        if self.content.is_none() {
+            node.set_busy();
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        [&self.placeholder, &self.content]
+            .into_iter()
+            .flatten()
+            .map(|child| child.id())
+            .collect()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Suspense", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Label, Spinner};
+
+    #[test]
+    fn content_replaces_placeholder() {
+        let [placeholder_id, content_id] = widget_ids();
+        let widget = Suspense::new(Spinner::new().with_id(placeholder_id))
+            .with_transition(Duration::from_millis(200));
+
+        let mut harness = TestHarness::create(widget);
+        let suspense_id = harness.root_widget().id();
+        assert!(harness.try_get_widget(placeholder_id).is_some());
+
+        harness.edit_widget(suspense_id, |mut suspense| {
+            let mut suspense = suspense.downcast::<Suspense>();
+            let content = WidgetPod::new_with_id(Box::new(Label::new("Loaded")), content_id);
+            Suspense::set_content(&mut suspense, content);
+        });
+
+        // During the transition, both are shown.
+        harness.animate_ms(100);
+        assert!(harness.try_get_widget(placeholder_id).is_some());
+        assert!(harness.try_get_widget(content_id).is_some());
+
+        harness.animate_ms(150);
+        assert!(harness.try_get_widget(placeholder_id).is_none());
+        assert!(harness.try_get_widget(content_id).is_some());
+    }
+}
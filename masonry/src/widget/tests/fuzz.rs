@@ -0,0 +1,26 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fuzz tests of random widget trees.
+
+use crate::testing::{fuzz_widget_tree, FuzzOptions};
+
+#[test]
+fn random_widget_trees() {
+    let options = FuzzOptions::default();
+    for seed in 0..20 {
+        fuzz_widget_tree(seed, &options);
+    }
+}
+
+#[test]
+fn random_deep_widget_trees() {
+    let options = FuzzOptions {
+        max_depth: 10,
+        max_children: 2,
+        steps: 20,
+    };
+    for seed in 0..10 {
+        fuzz_widget_tree(seed, &options);
+    }
+}
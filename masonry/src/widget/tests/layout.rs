@@ -3,10 +3,11 @@
 
 //! Tests related to layout.
 
-use vello::kurbo::{Insets, Size};
+use vello::kurbo::{Insets, Rect, Size};
 
 use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt};
 use crate::widget::{Flex, SizedBox};
+use crate::WindowEvent;
 
 #[test]
 fn layout_simple() {
@@ -72,3 +73,28 @@ fn layout_insets() {
 // TODO - insets + flex
 // TODO - viewport
 // TODO - insets + viewport
+
+#[test]
+fn layout_window_insets() {
+    let [id_root] = widget_ids();
+    let widget = SizedBox::empty().expand().with_id(id_root);
+
+    let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+    assert_eq!(
+        harness.get_widget(id_root).ctx().window_layout_rect(),
+        Rect::new(0., 0., 400., 400.)
+    );
+
+    // E.g. a status bar at the top, and a soft keyboard covering the bottom half
+    harness.process_window_event(WindowEvent::SetInsets(Insets::new(0., 20., 0., 200.)));
+    assert_eq!(
+        harness.get_widget(id_root).ctx().window_layout_rect(),
+        Rect::new(0., 20., 400., 200.)
+    );
+
+    harness.process_window_event(WindowEvent::SetInsets(Insets::ZERO));
+    assert_eq!(
+        harness.get_widget(id_root).ctx().window_layout_rect(),
+        Rect::new(0., 0., 400., 400.)
+    );
+}
@@ -3,12 +3,19 @@
 
 #![allow(unused_imports)]
 
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use insta::assert_debug_snapshot;
 
+use crate::command::CommandTarget;
 use crate::testing::{
-    widget_ids, Record, Recording, ReplaceChild, TestHarness, TestWidgetExt as _,
+    widget_ids, ModularWidget, Record, Recording, ReplaceChild, TestHarness, TestWidgetExt as _,
 };
-use crate::widget::{Flex, Label, SizedBox};
+use crate::widget::{Flex, Label, SizedBox, WidgetMut};
 use crate::*;
 
 #[test]
@@ -93,3 +100,195 @@ fn register_after_adding_child() {
     assert!(root_state.children.may_contain(&id_2));
     assert!(root_state.children.may_contain(&id_3));
 }
+
+#[test]
+fn advance_time_drives_animation() {
+    let frames = Rc::new(RefCell::new(Vec::new()));
+    let frames_2 = frames.clone();
+    let widget = ModularWidget::new(())
+        .update_fn(|_, ctx, event| {
+            if matches!(event, Update::WidgetAdded) {
+                ctx.request_anim_frame();
+            }
+        })
+        .anim_frame_fn(move |_, ctx, interval| {
+            frames_2.borrow_mut().push((interval, ctx.now()));
+            ctx.request_anim_frame();
+        });
+
+    let mut harness = TestHarness::create(widget);
+    harness.advance_time(Duration::from_millis(100));
+    harness.advance_time(Duration::from_millis(50));
+
+    let frames = frames.borrow();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].0, 100_000_000);
+    assert_eq!(frames[1].0, 50_000_000);
+    assert_eq!(frames[1].1 - frames[0].1, Duration::from_millis(50));
+}
+
+#[test]
+fn idle_work_runs_in_chunks() {
+    fn run_chunk(chunks: Arc<AtomicUsize>, mut this: WidgetMut<'_, Box<dyn Widget>>) {
+        // Each chunk queues the next one, until there are three
+        if chunks.fetch_add(1, Ordering::Relaxed) < 2 {
+            this.ctx.schedule_idle(move |this| run_chunk(chunks, this));
+        }
+    }
+
+    let chunks = Arc::new(AtomicUsize::new(0));
+    let chunks_2 = chunks.clone();
+    let widget = ModularWidget::new(()).update_fn(move |_, ctx, event| {
+        if matches!(event, Update::WidgetAdded) {
+            let chunks = chunks_2.clone();
+            ctx.schedule_idle(move |this| run_chunk(chunks, this));
+        }
+    });
+
+    let mut harness = TestHarness::create(widget);
+    assert_eq!(chunks.load(Ordering::Relaxed), 0);
+
+    harness.run_idle_work();
+    assert_eq!(chunks.load(Ordering::Relaxed), 3);
+    harness.run_idle_work();
+    assert_eq!(chunks.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn timers_expire_after_their_duration() {
+    let record = Recording::default();
+    let requested = Rc::new(RefCell::new(Vec::new()));
+    let requested_2 = requested.clone();
+    let widget = ModularWidget::new(())
+        .update_fn(move |_, ctx, event| {
+            if matches!(event, Update::WidgetAdded) {
+                let mut requested = requested_2.borrow_mut();
+                requested.push(ctx.request_timer(Duration::from_millis(200)));
+                requested.push(ctx.request_timer(Duration::from_millis(100)));
+            }
+        })
+        .record(&record);
+
+    let mut harness = TestHarness::create(widget);
+    record.clear();
+
+    harness.advance_time(Duration::from_millis(50));
+    assert!(!record
+        .drain()
+        .iter()
+        .any(|event| matches!(event, Record::U(Update::Timer(_)))));
+
+    // The timers are triggered in the order of their deadlines
+    harness.advance_time(Duration::from_millis(200));
+    let timers: Vec<TimerToken> = record
+        .drain()
+        .into_iter()
+        .filter_map(|event| match event {
+            Record::U(Update::Timer(token)) => Some(token),
+            _ => None,
+        })
+        .collect();
+    let requested = requested.borrow();
+    assert_eq!(timers, [requested[1], requested[0]]);
+}
+
+#[test]
+fn timers_of_recycled_widgets_are_dropped() {
+    let [recycled_id] = widget_ids();
+    let recycled = ModularWidget::new(()).update_fn(|_, ctx, event| {
+        if matches!(event, Update::WidgetAdded) {
+            ctx.request_timer(Duration::from_millis(100));
+        }
+    });
+    let widget = Flex::column().with_child_id(recycled, recycled_id);
+    let mut harness = TestHarness::create(widget);
+
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>();
+        Flex::recycle_child(&mut flex, 0);
+    });
+    // The id is freed once a frame has been rendered
+    let _ = harness.render();
+
+    let record = Recording::default();
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>();
+        let pod = flex.ctx.new_pod(SizedBox::empty().record(&record));
+        assert_eq!(pod.id(), recycled_id);
+        Flex::insert_child_pod(&mut flex, 0, pod.boxed());
+    });
+    record.clear();
+
+    harness.advance_time(Duration::from_millis(200));
+    assert!(!record
+        .drain()
+        .iter()
+        .any(|event| matches!(event, Record::U(Update::Timer(_)))));
+}
+
+#[test]
+fn commands_are_routed_to_their_targets() {
+    /// A widget which records the `u32` commands it receives, and subscribes to them if `subscribe`.
+    fn receiver(
+        name: &'static str,
+        subscribe: bool,
+        received: &Rc<RefCell<Vec<(&'static str, u32)>>>,
+    ) -> ModularWidget<()> {
+        let received = received.clone();
+        ModularWidget::new(())
+            .accepts_focus(true)
+            .update_fn(move |_, ctx, event| match event {
+                Update::WidgetAdded if subscribe => ctx.subscribe_to_command::<u32>(),
+                Update::Command(command) => {
+                    received
+                        .borrow_mut()
+                        .push((name, *command.get::<u32>().unwrap()));
+                }
+                _ => (),
+            })
+    }
+
+    let [sender_id, subscriber_1, subscriber_2, other_id] = widget_ids();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let widget = Flex::row()
+        .with_child_pod(WidgetPod::new_with_id(ModularWidget::new(()), sender_id).boxed())
+        .with_child_pod(
+            WidgetPod::new_with_id(receiver("subscriber_1", true, &received), subscriber_1).boxed(),
+        )
+        .with_child_pod(
+            WidgetPod::new_with_id(receiver("subscriber_2", true, &received), subscriber_2).boxed(),
+        )
+        .with_child_pod(
+            WidgetPod::new_with_id(receiver("other", false, &received), other_id).boxed(),
+        );
+    let mut harness = TestHarness::create(widget);
+
+    let submit = |harness: &mut TestHarness, target, command: u32| {
+        harness.edit_widget(sender_id, |mut sender| {
+            sender.ctx.submit_command(target, command);
+        });
+        std::mem::take(&mut *received.borrow_mut())
+    };
+
+    assert_eq!(
+        submit(&mut harness, CommandTarget::Subscribers, 1),
+        [("subscriber_1", 1), ("subscriber_2", 1)]
+    );
+    assert_eq!(
+        submit(&mut harness, CommandTarget::Widget(other_id), 2),
+        [("other", 2)]
+    );
+    assert!(submit(&mut harness, CommandTarget::Focused, 3).is_empty());
+
+    harness.focus_on(Some(subscriber_2));
+    assert_eq!(
+        submit(&mut harness, CommandTarget::Focused, 4),
+        [("subscriber_2", 4)]
+    );
+
+    // Commands of other types aren't delivered to the subscribers of `u32`
+    harness.edit_widget(sender_id, |mut sender| {
+        sender.ctx.submit_command(CommandTarget::Subscribers, "5");
+    });
+    assert!(received.borrow().is_empty());
+}
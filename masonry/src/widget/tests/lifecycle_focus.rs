@@ -7,6 +7,7 @@ use std::cell::Cell;
 use std::rc::Rc;
 
 use smallvec::smallvec;
+use winit::keyboard::{KeyCode, ModifiersState};
 
 use crate::testing::{widget_ids, ModularWidget, ReplaceChild, TestHarness, TestWidgetExt as _};
 use crate::widget::Flex;
@@ -238,3 +239,104 @@ fn resign_focus_on_disable() {
     assert_eq!(harness.window().focus_chain(), &[focus_2]);
     assert_eq!(harness.window().focus, None);
 }
+
+fn focusable() -> impl Widget {
+    ModularWidget::new(()).accepts_focus(true)
+}
+
+#[test]
+fn tab_skips_over_focus_groups() {
+    let [id_1, group_id, id_2, id_3, id_4] = widget_ids();
+
+    let group = Flex::row()
+        .with_child_id(focusable(), id_2)
+        .with_child_id(focusable(), id_3);
+    let widget = Flex::column()
+        .with_child_id(focusable(), id_1)
+        .with_child_id(group, group_id)
+        .with_child_id(focusable(), id_4);
+
+    let mut harness = TestHarness::create(widget);
+    harness.edit_widget(group_id, |mut group| group.ctx.set_focus_group(true));
+    let focused = |harness: &TestHarness| harness.focused_widget().map(|widget| widget.id());
+
+    harness.focus_on(Some(id_1));
+    harness.press_focus_navigation_key(KeyCode::Tab, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_2));
+
+    // Arrow keys move inside the group
+    harness.press_focus_navigation_key(KeyCode::ArrowRight, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_3));
+    harness.press_focus_navigation_key(KeyCode::ArrowRight, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_2));
+
+    harness.press_focus_navigation_key(KeyCode::Tab, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_4));
+    harness.press_focus_navigation_key(KeyCode::Tab, ModifiersState::SHIFT);
+    assert_eq!(focused(&harness), Some(id_2));
+
+    // Arrow keys outside of a group don't move the focus
+    harness.focus_on(Some(id_1));
+    let handled = harness.press_focus_navigation_key(KeyCode::ArrowDown, ModifiersState::empty());
+    assert_eq!(handled, Handled::No);
+    assert_eq!(focused(&harness), Some(id_1));
+}
+
+#[test]
+fn tab_index_comes_first() {
+    let [id_1, id_2, id_3] = widget_ids();
+
+    let widget = Flex::column()
+        .with_child_id(focusable(), id_1)
+        .with_child_id(focusable(), id_2)
+        .with_child_id(focusable(), id_3);
+
+    let mut harness = TestHarness::create(widget);
+    harness.edit_widget(id_3, |mut widget| widget.ctx.set_tab_index(Some(1)));
+    let focused = |harness: &TestHarness| harness.focused_widget().map(|widget| widget.id());
+
+    harness.press_focus_navigation_key(KeyCode::Tab, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_3));
+    harness.press_focus_navigation_key(KeyCode::Tab, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_1));
+    harness.press_focus_navigation_key(KeyCode::Tab, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_2));
+}
+
+#[test]
+fn home_end_and_type_ahead_in_focus_group() {
+    let [group_id, id_1, id_2, id_3] = widget_ids();
+
+    let group = Flex::column()
+        .with_child_id(focusable(), id_1)
+        .with_child_id(focusable(), id_2)
+        .with_child_id(focusable(), id_3);
+    let widget = Flex::row().with_child_id(group, group_id);
+
+    let mut harness = TestHarness::create(widget);
+    harness.edit_widget(group_id, |mut group| group.ctx.set_focus_group(true));
+    for (id, name) in [(id_1, "Apple"), (id_2, "banana"), (id_3, "Blueberry")] {
+        harness.edit_widget(id, |mut widget| {
+            widget.ctx.access_overrides_mut().name = Some(name.into());
+        });
+    }
+    let focused = |harness: &TestHarness| harness.focused_widget().map(|widget| widget.id());
+
+    harness.focus_on(Some(id_1));
+    harness.press_focus_navigation_key(KeyCode::End, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_3));
+    harness.press_focus_navigation_key(KeyCode::Home, ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_1));
+
+    // Typing the same letter cycles through the matching widgets
+    harness.type_ahead("B");
+    assert_eq!(focused(&harness), Some(id_2));
+    harness.type_ahead("b");
+    assert_eq!(focused(&harness), Some(id_3));
+    harness.type_ahead("b");
+    assert_eq!(focused(&harness), Some(id_2));
+
+    let handled = harness.type_ahead("z");
+    assert_eq!(handled, Handled::No);
+    assert_eq!(focused(&harness), Some(id_2));
+}
@@ -5,10 +5,13 @@
 
 #![allow(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
 
+mod fuzz;
 mod layout;
 mod lifecycle_basic;
 mod lifecycle_disable;
 mod lifecycle_focus;
+mod paint_cache;
 mod safety_rails;
 mod status_change;
+mod visibility;
 mod widget_tree;
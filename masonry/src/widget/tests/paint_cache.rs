@@ -0,0 +1,38 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests related to reusing painted scenes across frames.
+
+use crate::testing::{widget_ids, TestHarness};
+use crate::widget::{Flex, Label};
+
+#[test]
+fn unchanged_frames_reuse_the_scene() {
+    let [label_id] = widget_ids();
+
+    let widget = Flex::column()
+        .with_child_id(Label::new("Hello"), label_id)
+        .with_child(Label::new("World"));
+
+    let mut harness = TestHarness::create(widget);
+    let _ = harness.render();
+    let first = harness.render_cache_stats();
+    assert_eq!(first.reused_frames, 0);
+
+    // Nothing changed, so no widget is painted again.
+    let _ = harness.render();
+    let stats = harness.render_cache_stats();
+    assert_eq!(stats.frames, first.frames + 1);
+    assert_eq!(stats.reused_frames, 1);
+    assert_eq!(stats.widgets_painted, first.widgets_painted);
+
+    // Only the label which asked for it is painted again.
+    harness.edit_widget(label_id, |mut label| {
+        label.ctx.request_paint_only();
+    });
+    let _ = harness.render();
+    let stats = harness.render_cache_stats();
+    assert_eq!(stats.reused_frames, 1);
+    assert_eq!(stats.widgets_painted, first.widgets_painted + 1);
+    assert!(stats.widget_scenes_reused > first.widget_scenes_reused);
+}
@@ -0,0 +1,105 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests related to hiding and collapsing widgets.
+
+use vello::kurbo::Size;
+
+use crate::testing::{widget_ids, TestHarness};
+use crate::widget::{Flex, SizedBox, TextArea};
+use crate::{Visibility, WidgetId};
+
+const BOX_WIDTH: f64 = 50.;
+
+fn set_visibility(harness: &mut TestHarness, id: WidgetId, visibility: Visibility) {
+    harness.edit_widget(id, |mut widget| {
+        widget.ctx.set_visibility(visibility);
+    });
+}
+
+#[test]
+fn hidden_and_collapsed_layout() {
+    let [first_id, second_id, third_id] = widget_ids();
+
+    let widget = Flex::row()
+        .with_child_id(
+            SizedBox::empty().width(BOX_WIDTH).height(BOX_WIDTH),
+            first_id,
+        )
+        .with_child_id(
+            SizedBox::empty().width(BOX_WIDTH).height(BOX_WIDTH),
+            second_id,
+        )
+        .with_child_id(
+            SizedBox::empty().width(BOX_WIDTH).height(BOX_WIDTH),
+            third_id,
+        )
+        .with_flex_spacer(1.0);
+
+    let mut harness = TestHarness::create(widget);
+    let third_origin = harness.get_widget(third_id).ctx().window_origin();
+    let second_center = harness
+        .get_widget(second_id)
+        .ctx()
+        .window_layout_rect()
+        .center();
+
+    // A hidden widget keeps its space, but isn't hit by the pointer.
+    set_visibility(&mut harness, second_id, Visibility::Hidden);
+    assert!(harness.get_widget(second_id).ctx().is_hidden());
+    assert_eq!(
+        harness.get_widget(third_id).ctx().window_origin(),
+        third_origin
+    );
+    let root = harness.root_widget();
+    let hit = root
+        .find_widget_at_pos(second_center)
+        .map(|widget| widget.id());
+    assert_ne!(hit, Some(second_id));
+
+    // A collapsed widget takes no space.
+    set_visibility(&mut harness, second_id, Visibility::Collapsed);
+    assert_eq!(harness.get_widget(second_id).ctx().size(), Size::ZERO);
+    assert!(harness.get_widget(third_id).ctx().window_origin().x < third_origin.x);
+
+    set_visibility(&mut harness, second_id, Visibility::Visible);
+    assert!(!harness.get_widget(second_id).ctx().is_hidden());
+    assert_eq!(
+        harness.get_widget(second_id).ctx().size(),
+        Size::new(BOX_WIDTH, BOX_WIDTH)
+    );
+    assert_eq!(
+        harness.get_widget(third_id).ctx().window_origin(),
+        third_origin
+    );
+}
+
+#[test]
+fn hiding_parent_hides_descendants() {
+    let [parent_id, area_id] = widget_ids();
+
+    let widget = Flex::column().with_child_id(
+        Flex::row().with_child_id(TextArea::new_editable("Text"), area_id),
+        parent_id,
+    );
+
+    let mut harness = TestHarness::create(widget);
+    harness.focus_on(Some(area_id));
+    assert_eq!(harness.focused_widget().map(|w| w.id()), Some(area_id));
+
+    // The hidden text area loses the focus, but keeps its text.
+    set_visibility(&mut harness, parent_id, Visibility::Hidden);
+    assert!(harness.get_widget(area_id).ctx().is_hidden());
+    assert_eq!(harness.focused_widget().map(|w| w.id()), None);
+
+    set_visibility(&mut harness, parent_id, Visibility::Visible);
+    assert!(!harness.get_widget(area_id).ctx().is_hidden());
+    let text: String = harness
+        .get_widget(area_id)
+        .downcast::<TextArea<true>>()
+        .unwrap()
+        .text()
+        .into_iter()
+        .collect();
+    assert_eq!(text, "Text");
+}
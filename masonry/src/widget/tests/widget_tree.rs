@@ -4,7 +4,8 @@
 use insta::assert_debug_snapshot;
 
 use crate::testing::{widget_ids, TestHarness};
-use crate::widget::{Flex, Label};
+use crate::widget::{Button, Flex, Label};
+use crate::WidgetPod;
 
 #[test]
 fn access_grandchild_widget() {
@@ -26,3 +27,56 @@ fn access_grandchild_widget() {
 
     assert_debug_snapshot!(harness.root_widget());
 }
+
+#[test]
+fn recycle_child_widget() {
+    let [id_label] = widget_ids();
+
+    let widget = Flex::column().with_child_id(Label::new("Recycled"), id_label);
+    let mut harness = TestHarness::create(widget);
+
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>();
+        Flex::recycle_child(&mut flex, 0);
+
+        let label = flex.ctx.take_recycled::<Label>();
+        assert_eq!(label.unwrap().text().as_ref(), "Recycled");
+        assert!(flex.ctx.take_recycled::<Label>().is_none());
+
+        // The id can't be reused before the next frame.
+        let pod = flex.ctx.new_pod(Label::new("New"));
+        assert_ne!(pod.id(), id_label);
+    });
+    assert!(harness.try_get_widget(id_label).is_none());
+
+    let _ = harness.render();
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>();
+        let pod = flex.ctx.new_pod(Label::new("New"));
+        assert_eq!(pod.id(), id_label);
+    });
+}
+
+#[test]
+fn find_widget_by_debug_tag() {
+    let widget = Flex::column()
+        .with_child(Label::new("Title"))
+        .with_child_pod(
+            WidgetPod::new(Box::new(Button::new("Save"))).with_debug_tag("save-button"),
+        );
+
+    let mut harness = TestHarness::create(widget);
+
+    let button = harness.get_widget_by_tag("save-button");
+    assert!(button.downcast::<Button>().is_some());
+    assert_eq!(button.ctx().debug_tag(), Some("save-button"));
+    assert!(format!("{button:?}").starts_with("Button#save-button"));
+    assert!(harness.try_get_widget_by_tag("cancel-button").is_none());
+
+    let button_id = button.id();
+    harness.edit_widget(button_id, |mut button| {
+        button.ctx.set_debug_tag(Some("cancel-button".into()));
+    });
+    assert_eq!(harness.get_widget_by_tag("cancel-button").id(), button_id);
+    assert!(harness.try_get_widget_by_tag("save-button").is_none());
+}
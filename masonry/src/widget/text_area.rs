@@ -4,27 +4,35 @@
 #![warn(missing_docs)]
 
 use std::mem::Discriminant;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 
 use crate::kurbo::{Affine, Point, Size};
-use crate::text::{default_styles, render_text};
+use crate::text::{caret_rect, default_styles, index_at_point, range_rects, render_text};
 use accesskit::{Node, NodeId, Role};
 use parley::editor::{Generation, SplitString};
 use parley::layout::Alignment;
-use parley::PlainEditor;
+use parley::{Layout, PlainEditor};
 use smallvec::SmallVec;
 use tracing::{trace_span, Span};
-use vello::kurbo::{Rect, Vec2};
+use vello::kurbo::{BezPath, Rect, Stroke, Vec2};
 use vello::peniko::{Brush, Color, Fill};
 use vello::Scene;
 use winit::keyboard::{Key, NamedKey};
 
-use crate::text::{BrushIndex, StyleProperty};
+use crate::text::{
+    BrushIndex, FindPattern, SpellChecker, SpellingSuggestions, StyleProperty, TextLayoutJob,
+};
 use crate::widget::{Padding, WidgetMut};
 use crate::{
     theme, AccessCtx, AccessEvent, BoxConstraints, CursorIcon, EventCtx, LayoutCtx, PaintCtx,
-    PointerButton, PointerEvent, QueryCtx, RegisterCtx, TextEvent, Update, UpdateCtx, Widget,
-    WidgetId,
+    PointerButton, PointerEvent, QueryCtx, RegisterCtx, TextEvent, TimerToken, Update, UpdateCtx,
+    Widget, WidgetId,
 };
 
 /// `TextArea` implements the core of interactive text.
@@ -103,6 +111,82 @@ pub struct TextArea<const USER_EDITABLE: bool> {
     /// Can be set using [`set_padding`](Self::set_padding).
     /// Immediate parent widgets should use [`with_padding_if_default`](Self::with_padding_if_default).
     padding: Padding,
+
+    /// The width of the cursor.
+    ///
+    /// Can be set using [`set_caret_width`](Self::set_caret_width).
+    caret_width: f32,
+    /// The brush for drawing the cursor.
+    ///
+    /// Can be set using [`set_caret_brush`](Self::set_caret_brush).
+    caret_brush: Brush,
+    /// How long the cursor stays shown, then hidden, when it blinks.
+    ///
+    /// When this is `None`, the cursor doesn't blink.
+    /// Can be set using [`set_caret_blink_interval`](Self::set_caret_blink_interval).
+    caret_blink_interval: Option<Duration>,
+    /// Whether the cursor is in the shown phase of its blinking.
+    caret_visible: bool,
+    /// The timer of the next phase of the cursor's blinking, while this area is focused.
+    caret_blink_timer: Option<TimerToken>,
+
+    /// The search whose matches are highlighted.
+    ///
+    /// Can be set using [`set_find_pattern`](Self::set_find_pattern).
+    find: Option<Find>,
+    /// The spell checking of the text.
+    ///
+    /// Can be set using [`set_spell_checker`](Self::set_spell_checker).
+    spelling: Option<Spelling>,
+    /// The keys which this area doesn't handle, so that they reach its parents.
+    ///
+    /// Can be set using [`with_ignored_keys`](Self::with_ignored_keys).
+    ignored_keys: Vec<Key>,
+}
+
+/// The state of a search in a [`TextArea`].
+struct Find {
+    pattern: FindPattern,
+    /// The byte ranges of the matches in the text.
+    matches: Vec<Range<usize>>,
+    /// The index in `matches` of the match which was navigated to.
+    active: Option<usize>,
+    /// The generation of the editor in which `matches` were found.
+    generation: Option<Generation>,
+}
+
+/// The state of the spell checking of a [`TextArea`].
+struct Spelling {
+    checker: Arc<dyn SpellChecker>,
+    /// The byte ranges of the misspelled words in the text.
+    misspelled: Vec<Range<usize>>,
+    /// The generation of the editor whose text was checked.
+    generation: Option<Generation>,
+    /// Whether a check is scheduled for when the event loop is idle.
+    check_scheduled: bool,
+}
+
+impl Spelling {
+    fn new(checker: Arc<dyn SpellChecker>) -> Self {
+        Self {
+            checker,
+            misspelled: Vec::new(),
+            generation: None,
+            check_scheduled: false,
+        }
+    }
+}
+
+impl Find {
+    /// A search for `pattern`, whose matches haven't been found yet.
+    fn new(pattern: FindPattern) -> Self {
+        Self {
+            pattern,
+            matches: Vec::new(),
+            active: None,
+            generation: None,
+        }
+    }
 }
 
 // --- MARK: BUILDERS ---
@@ -150,6 +234,14 @@ impl<const EDITABLE: bool> TextArea<EDITABLE> {
             // We use -0.0 to mark the default padding.
             // This allows parent views to overwrite it only if another source didn't configure it.
             padding: Padding::UNSET,
+            caret_width: theme::CURSOR_WIDTH,
+            caret_brush: theme::CURSOR_COLOR.into(),
+            caret_blink_interval: Some(theme::CURSOR_BLINK_INTERVAL),
+            caret_visible: true,
+            caret_blink_timer: None,
+            find: None,
+            spelling: None,
+            ignored_keys: Vec::new(),
         }
     }
 
@@ -162,6 +254,59 @@ impl<const EDITABLE: bool> TextArea<EDITABLE> {
         self.editor.text()
     }
 
+    /// The byte ranges of the matches of the [find pattern](Self::set_find_pattern) in the
+    /// text, in order.
+    pub fn find_matches(&self) -> &[Range<usize>] {
+        self.find.as_ref().map_or(&[], |find| &find.matches)
+    }
+
+    /// The index in [`find_matches`](Self::find_matches) of the match which was last
+    /// navigated to, if any.
+    pub fn active_find_match(&self) -> Option<usize> {
+        self.find.as_ref().and_then(|find| find.active)
+    }
+
+    /// The byte ranges of the words flagged by the [spell checker](Self::with_spell_checker),
+    /// as of the last check.
+    pub fn misspelled_ranges(&self) -> &[Range<usize>] {
+        self.spelling
+            .as_ref()
+            .map_or(&[], |spelling| &spelling.misspelled)
+    }
+
+    /// The byte index of the text nearest to `point`, which is in this widget's coordinate space.
+    ///
+    /// This is where a cursor would be placed by clicking at `point`.
+    /// Returns `None` if the text hasn't been laid out since it last changed.
+    pub fn index_at_point(&self, point: Point) -> Option<usize> {
+        let layout = self.editor.try_layout()?;
+        Some(index_at_point(layout, point - self.text_origin(layout)))
+    }
+
+    /// The rect of a cursor of the given `width` placed before the byte `index` of the text,
+    /// in this widget's coordinate space.
+    ///
+    /// Returns `None` if the text hasn't been laid out since it last changed.
+    pub fn caret_rect(&self, index: usize, width: f32) -> Option<Rect> {
+        let layout = self.editor.try_layout()?;
+        Some(caret_rect(layout, index, width) + self.text_origin(layout))
+    }
+
+    /// The rects covering the text in the byte `range`, one per line, in this widget's
+    /// coordinate space.
+    ///
+    /// This is empty if the text hasn't been laid out since it last changed.
+    pub fn range_rects(&self, range: Range<usize>) -> Vec<Rect> {
+        let Some(layout) = self.editor.try_layout() else {
+            return Vec::new();
+        };
+        let origin = self.text_origin(layout);
+        range_rects(layout, range)
+            .into_iter()
+            .map(|rect| rect + origin)
+            .collect()
+    }
+
     /// Set a style property for the new text area.
     ///
     /// Style properties set by this method include [text size](parley::StyleProperty::FontSize),
@@ -271,6 +416,64 @@ impl<const EDITABLE: bool> TextArea<EDITABLE> {
         self
     }
 
+    /// Set the width of the cursor, in logical pixels.
+    ///
+    /// To modify this on an active text area, use [`set_caret_width`](Self::set_caret_width).
+    pub fn with_caret_width(mut self, width: f32) -> Self {
+        self.caret_width = width;
+        self
+    }
+
+    /// Set the brush used to paint the cursor.
+    ///
+    /// To modify this on an active text area, use [`set_caret_brush`](Self::set_caret_brush).
+    #[doc(alias = "with_caret_color")]
+    pub fn with_caret_brush(mut self, brush: impl Into<Brush>) -> Self {
+        self.caret_brush = brush.into();
+        self
+    }
+
+    /// Set how long the cursor stays shown, then hidden, when it blinks.
+    ///
+    /// If this is `None`, the cursor doesn't blink.
+    /// The default is [`theme::CURSOR_BLINK_INTERVAL`]. Whatever the interval, the cursor stays
+    /// shown whilst the user is typing or moving it.
+    ///
+    /// To modify this on an active text area, use
+    /// [`set_caret_blink_interval`](Self::set_caret_blink_interval).
+    pub fn with_caret_blink_interval(mut self, interval: Option<Duration>) -> Self {
+        self.caret_blink_interval = interval;
+        self
+    }
+
+    /// Highlight the matches of `pattern` in the text, if it's not `None`.
+    ///
+    /// To modify this on an active text area, and to navigate the matches, use
+    /// [`set_find_pattern`](Self::set_find_pattern).
+    pub fn with_find_pattern(mut self, pattern: Option<FindPattern>) -> Self {
+        self.find = pattern.map(Find::new);
+        self
+    }
+
+    /// Check the spelling of the text with `checker`, underlining the misspelled words.
+    ///
+    /// See [`SpellChecker`] for details.
+    ///
+    /// To modify this on an active text area, use [`set_spell_checker`](Self::set_spell_checker).
+    pub fn with_spell_checker(mut self, checker: Arc<dyn SpellChecker>) -> Self {
+        self.spelling = Some(Spelling::new(checker));
+        self
+    }
+
+    /// Let presses of `keys` bubble up to the parents of this area, rather than handling them.
+    ///
+    /// This lets a parent widget give its own meaning to keys such as the arrow keys or
+    /// <kbd>Enter</kbd> while the area is focused, e.g. to navigate a list of suggestions.
+    pub fn with_ignored_keys(mut self, keys: impl IntoIterator<Item = Key>) -> Self {
+        self.ignored_keys = keys.into_iter().collect();
+        self
+    }
+
     /// Adds `padding` unless [`with_padding`](Self::with_padding) was previously called.
     ///
     /// This is expected to be called when creating parent widgets.
@@ -316,6 +519,100 @@ impl<const EDITABLE: bool> TextArea<EDITABLE> {
             .unwrap_or(false);
         self.editor.ime_cursor_area() + Vec2::new(self.padding.get_left(is_rtl), self.padding.top)
     }
+
+    /// The offset of `layout` from the origin of this widget, i.e. the padding before the text.
+    fn text_origin(&self, layout: &Layout<BrushIndex>) -> Vec2 {
+        Vec2::new(self.padding.get_left(layout.is_rtl()), self.padding.top)
+    }
+
+    /// Search the text again if it changed since the matches were found.
+    fn refresh_find_matches(&mut self) {
+        let generation = self.editor.generation();
+        // The text being composed isn't searched, as it would shift the matches
+        if self.editor.is_composing() {
+            return;
+        }
+        let Some(find) = &mut self.find else {
+            return;
+        };
+        if find.generation == Some(generation) {
+            return;
+        }
+        let text = self.editor.text().to_string();
+        find.matches = find.pattern.find_in(&text);
+        find.generation = Some(generation);
+        if find
+            .active
+            .is_some_and(|active| active >= find.matches.len())
+        {
+            find.active = None;
+        }
+    }
+
+    /// Whether the spelling should be checked, as the text changed since it was last checked.
+    ///
+    /// If this returns true, the check is considered scheduled, and the caller must schedule
+    /// [`check_spelling`] with [`EventCtx::schedule_idle`](crate::EventCtx::schedule_idle).
+    fn needs_spell_check(&mut self) -> bool {
+        let generation = self.editor.generation();
+        let Some(spelling) = &mut self.spelling else {
+            return false;
+        };
+        if spelling.check_scheduled || spelling.generation == Some(generation) {
+            return false;
+        }
+        spelling.check_scheduled = true;
+        true
+    }
+
+    /// Show the cursor, and restart its blinking, as the user is interacting with it.
+    ///
+    /// Returns the interval after which to hide the cursor, if it blinks.
+    /// The caller should request a timer of that length and store it in `caret_blink_timer`.
+    fn restart_caret_blink(&mut self) -> Option<Duration> {
+        self.caret_visible = true;
+        self.caret_blink_timer = None;
+        self.caret_blink_interval
+    }
+}
+
+/// Check the spelling of the text area `this`, as scheduled by
+/// [`TextArea::needs_spell_check`].
+fn check_spelling<const EDITABLE: bool>(mut this: WidgetMut<'_, Box<dyn Widget>>) {
+    let mut this = this.downcast::<TextArea<EDITABLE>>();
+    let generation = this.widget.editor.generation();
+    // The text being composed isn't checked, as it would shift the misspelled words.
+    // The text is checked again once the composition ends, as that changes the generation.
+    let is_composing = this.widget.editor.is_composing();
+    let text = this.widget.editor.text().to_string();
+    let Some(spelling) = &mut this.widget.spelling else {
+        return;
+    };
+    spelling.check_scheduled = false;
+    if is_composing {
+        return;
+    }
+    spelling.misspelled = spelling.checker.check(&text);
+    spelling.generation = Some(generation);
+    this.ctx.request_paint_only();
+}
+
+/// A squiggly line along the bottom of `rect`, to underline misspelled words.
+fn squiggle(rect: Rect) -> BezPath {
+    const AMPLITUDE: f64 = 1.;
+    const HALF_WAVELENGTH: f64 = 2.;
+    let y = rect.y1 - 2. * AMPLITUDE;
+    let mut path = BezPath::new();
+    path.move_to((rect.x0, y));
+    let mut x = rect.x0;
+    let mut up = true;
+    while x < rect.x1 {
+        x = (x + HALF_WAVELENGTH).min(rect.x1);
+        let offset = if up { -AMPLITUDE } else { AMPLITUDE };
+        path.line_to((x, y + offset));
+        up = !up;
+    }
+    path
 }
 
 // --- MARK: WIDGETMUT ---
@@ -465,6 +762,150 @@ impl<const EDITABLE: bool> TextArea<EDITABLE> {
         this.ctx.request_paint_only();
     }
 
+    /// Set the width of the cursor, in logical pixels.
+    ///
+    /// The runtime equivalent of [`with_caret_width`](Self::with_caret_width).
+    pub fn set_caret_width(this: &mut WidgetMut<'_, Self>, width: f32) {
+        this.widget.caret_width = width;
+        this.ctx.request_paint_only();
+    }
+
+    /// Set the brush used to paint the cursor.
+    ///
+    /// The runtime equivalent of [`with_caret_brush`](Self::with_caret_brush).
+    #[doc(alias = "set_caret_color")]
+    pub fn set_caret_brush(this: &mut WidgetMut<'_, Self>, brush: impl Into<Brush>) {
+        this.widget.caret_brush = brush.into();
+        this.ctx.request_paint_only();
+    }
+
+    /// Set how long the cursor stays shown, then hidden, when it blinks.
+    ///
+    /// The runtime equivalent of [`with_caret_blink_interval`](Self::with_caret_blink_interval).
+    /// For full documentation, see that method.
+    pub fn set_caret_blink_interval(this: &mut WidgetMut<'_, Self>, interval: Option<Duration>) {
+        this.widget.caret_blink_interval = interval;
+        if let Some(interval) = this.widget.restart_caret_blink() {
+            if this.ctx.is_focused() {
+                this.widget.caret_blink_timer = Some(this.ctx.request_timer(interval));
+            }
+        }
+        this.ctx.request_paint_only();
+    }
+
+    /// Search the text for `pattern`, highlighting all its matches, or stop searching if
+    /// `pattern` is `None`.
+    ///
+    /// Returns the number of matches. The matches are updated as the text changes, and can
+    /// be navigated with [`find_next`](Self::find_next) and
+    /// [`find_previous`](Self::find_previous).
+    pub fn set_find_pattern(this: &mut WidgetMut<'_, Self>, pattern: Option<FindPattern>) -> usize {
+        this.widget.find = pattern.map(Find::new);
+        this.widget.refresh_find_matches();
+        this.ctx.request_paint_only();
+        this.widget.find_matches().len()
+    }
+
+    /// Select the first match of the find pattern after the selection, wrapping around to
+    /// the start of the text, and scroll it into view.
+    ///
+    /// Returns the index of the match, or `None` if there are no matches.
+    pub fn find_next(this: &mut WidgetMut<'_, Self>) -> Option<usize> {
+        this.widget.refresh_find_matches();
+        let selection_end = this.widget.editor.raw_selection().text_range().end;
+        let matches = this.widget.find_matches();
+        let index = matches
+            .iter()
+            .position(|range| range.start >= selection_end)
+            .or((!matches.is_empty()).then_some(0))?;
+        Self::select_find_match(this, index);
+        Some(index)
+    }
+
+    /// Select the last match of the find pattern before the selection, wrapping around to
+    /// the end of the text, and scroll it into view.
+    ///
+    /// Returns the index of the match, or `None` if there are no matches.
+    pub fn find_previous(this: &mut WidgetMut<'_, Self>) -> Option<usize> {
+        this.widget.refresh_find_matches();
+        let selection_start = this.widget.editor.raw_selection().text_range().start;
+        let matches = this.widget.find_matches();
+        let index = matches
+            .iter()
+            .rposition(|range| range.end <= selection_start)
+            .or(matches.len().checked_sub(1))?;
+        Self::select_find_match(this, index);
+        Some(index)
+    }
+
+    /// Select the match of the find pattern at `index` in
+    /// [`find_matches`](Self::find_matches), and scroll it into view.
+    ///
+    /// Does nothing if there is no such match.
+    pub fn select_find_match(this: &mut WidgetMut<'_, Self>, index: usize) {
+        this.widget.refresh_find_matches();
+        let Some(find) = &mut this.widget.find else {
+            return;
+        };
+        let Some(range) = find.matches.get(index).cloned() else {
+            return;
+        };
+        find.active = Some(index);
+
+        let (fctx, lctx) = this.ctx.text_contexts();
+        this.widget
+            .editor
+            .driver(fctx, lctx)
+            .select_byte_range(range.start, range.end);
+        // Selecting the match doesn't change the text
+        this.widget.find.as_mut().unwrap().generation = Some(this.widget.editor.generation());
+
+        let (fctx, lctx) = this.ctx.text_contexts();
+        let layout = this.widget.editor.layout(fctx, lctx);
+        let is_rtl = layout.is_rtl();
+        let area = range_rects(layout, range)
+            .into_iter()
+            .reduce(|area, rect| area.union(rect));
+        if let Some(area) = area {
+            let origin = Vec2::new(
+                this.widget.padding.get_left(is_rtl),
+                this.widget.padding.top,
+            );
+            this.ctx.request_scroll_to(area + origin);
+        }
+        this.ctx.request_render();
+    }
+
+    /// Check the spelling of the text with `checker`, or stop checking it if `checker` is
+    /// `None`.
+    ///
+    /// The runtime equivalent of [`with_spell_checker`](Self::with_spell_checker).
+    pub fn set_spell_checker(
+        this: &mut WidgetMut<'_, Self>,
+        checker: Option<Arc<dyn SpellChecker>>,
+    ) {
+        this.widget.spelling = checker.map(Spelling::new);
+        if this.widget.needs_spell_check() {
+            this.ctx.schedule_idle(check_spelling::<EDITABLE>);
+        }
+        this.ctx.request_paint_only();
+    }
+
+    /// Replace the text in the byte `range` with `text`, e.g. to apply a
+    /// [spelling suggestion](SpellingSuggestions).
+    ///
+    /// The replacement is selected afterwards. Unlike edits made by the user, this doesn't
+    /// submit an [`Action::TextChanged`](crate::Action::TextChanged).
+    pub fn replace_range(this: &mut WidgetMut<'_, Self>, range: Range<usize>, text: &str) {
+        let (fctx, lctx) = this.ctx.text_contexts();
+        let mut driver = this.widget.editor.driver(fctx, lctx);
+        driver.clear_compose();
+        driver.select_byte_range(range.start, range.end);
+        driver.insert_or_replace_selection(text);
+
+        this.ctx.request_layout();
+    }
+
     /// Set the padding around the text.
     ///
     /// This is the area outside the tight bound on the text where pointer events will be detected.
@@ -493,9 +934,38 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
             window_origin.y + self.padding.top,
         );
         match event {
+            PointerEvent::PointerDown(PointerButton::Secondary, state) => {
+                let Some(spelling) = &self.spelling else {
+                    return;
+                };
+                let click_pos = Point::new(state.position.x, state.position.y) - inner_origin;
+                let (fctx, lctx) = ctx.text_contexts();
+                let layout = self.editor.layout(fctx, lctx);
+                let index = index_at_point(layout, click_pos.to_point());
+                let Some(range) = spelling
+                    .misspelled
+                    .iter()
+                    .find(|range| range.contains(&index))
+                    .cloned()
+                else {
+                    return;
+                };
+                let text = self.editor.text().to_string();
+                let Some(word) = text.get(range.clone()) else {
+                    return;
+                };
+                let suggestions = spelling.checker.suggestions(word);
+                ctx.submit_action(crate::Action::SpellingSuggestions(SpellingSuggestions {
+                    range,
+                    word: word.to_string(),
+                    suggestions,
+                    position: Point::new(state.position.x, state.position.y),
+                }));
+                ctx.set_handled();
+            }
             PointerEvent::PointerDown(button, state) => {
                 if !ctx.is_disabled() && *button == PointerButton::Primary {
-                    let now = Instant::now();
+                    let now = ctx.now();
                     if let Some(last) = self.last_click_time.take() {
                         if now.duration_since(last).as_secs_f64() < 0.25 {
                             self.click_count = (self.click_count + 1) % 4;
@@ -523,6 +993,10 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
                     }
                     ctx.request_focus();
                     ctx.capture_pointer();
+                    if let Some(interval) = self.restart_caret_blink() {
+                        self.caret_blink_timer = Some(ctx.request_timer(interval));
+                    }
+                    ctx.request_paint_only();
                 }
             }
             PointerEvent::PointerMove(state) => {
@@ -546,8 +1020,11 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
 
     fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
         match event {
-            TextEvent::KeyboardKey(key_event, modifiers_state) => {
-                if !key_event.state.is_pressed() || self.editor.is_composing() {
+            TextEvent::KeyboardKey(..) | TextEvent::VirtualKey(..) => {
+                let Some((key, key_text, modifiers_state)) = event.pressed_key() else {
+                    return;
+                };
+                if self.editor.is_composing() || self.ignored_keys.contains(key) {
                     return;
                 }
                 #[allow(unused)]
@@ -559,11 +1036,12 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
                         modifiers_state.control_key()
                     },
                 );
+                let command = edit_command(key, modifiers_state);
                 let (fctx, lctx) = ctx.text_contexts();
                 // Whether the text was changed.
                 let mut edited = false;
                 // Ideally we'd use key_without_modifiers, but that's broken
-                match &key_event.logical_key {
+                match key {
                     // Cut
                     #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
                     Key::Character(x)
@@ -599,106 +1077,10 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
                         // self.editor.drive(fcx, lcx, |drv| drv.insert_or_replace_selection(&text));
                         // edited = true;
                     }
-                    Key::Character(a) if action_mod && a.as_str().eq_ignore_ascii_case("a") => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-
-                        if shift {
-                            drv.collapse_selection();
-                        } else {
-                            drv.select_all();
-                        }
-                    }
-                    Key::Named(NamedKey::ArrowLeft) => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-                        if action_mod {
-                            if shift {
-                                drv.select_word_left();
-                            } else {
-                                drv.move_word_left();
-                            }
-                        } else if shift {
-                            drv.select_left();
-                        } else {
-                            drv.move_left();
-                        }
-                    }
-                    Key::Named(NamedKey::ArrowRight) => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-                        if action_mod {
-                            if shift {
-                                drv.select_word_right();
-                            } else {
-                                drv.move_word_right();
-                            }
-                        } else if shift {
-                            drv.select_right();
-                        } else {
-                            drv.move_right();
-                        }
-                    }
-                    Key::Named(NamedKey::ArrowUp) => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-                        if shift {
-                            drv.select_up();
-                        } else {
-                            drv.move_up();
-                        }
-                    }
-                    Key::Named(NamedKey::ArrowDown) => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-                        if shift {
-                            drv.select_down();
-                        } else {
-                            drv.move_down();
-                        }
-                    }
-                    Key::Named(NamedKey::Home) => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-                        if action_mod {
-                            if shift {
-                                drv.select_to_text_start();
-                            } else {
-                                drv.move_to_text_start();
-                            }
-                        } else if shift {
-                            drv.select_to_line_start();
-                        } else {
-                            drv.move_to_line_start();
-                        }
-                    }
-                    Key::Named(NamedKey::End) => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-                        if action_mod {
-                            if shift {
-                                drv.select_to_text_end();
-                            } else {
-                                drv.move_to_text_end();
-                            }
-                        } else if shift {
-                            drv.select_to_line_end();
-                        } else {
-                            drv.move_to_line_end();
-                        }
-                    }
-                    Key::Named(NamedKey::Delete) if EDITABLE => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-                        if action_mod {
-                            drv.delete_word();
-                        } else {
-                            drv.delete();
-                        }
-
-                        edited = true;
-                    }
-                    Key::Named(NamedKey::Backspace) if EDITABLE => {
-                        let mut drv = self.editor.driver(fctx, lctx);
-                        if action_mod {
-                            drv.backdelete_word();
-                        } else {
-                            drv.backdelete();
-                        }
-
-                        edited = true;
+                    _ if command.is_some_and(|command| EDITABLE || !command.edits()) => {
+                        let command = command.unwrap();
+                        apply_edit_command(&mut self.editor, fctx, lctx, command);
+                        edited = command.edits();
                     }
                     Key::Named(NamedKey::Space) if EDITABLE => {
                         self.editor
@@ -725,7 +1107,7 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
                         // Note that this doesn't allow input of the tab character; we need to be more clever here at some point
                         return;
                     }
-                    _ if EDITABLE => match &key_event.text {
+                    _ if EDITABLE => match key_text {
                         Some(text) => {
                             self.editor
                                 .driver(fctx, lctx)
@@ -743,6 +1125,11 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
                     }
                 }
                 ctx.set_handled();
+                // Keep the cursor shown whilst the user is typing
+                if let Some(interval) = self.restart_caret_blink() {
+                    self.caret_blink_timer = Some(ctx.request_timer(interval));
+                }
+                ctx.request_paint_only();
                 let new_generation = self.editor.generation();
                 if new_generation != self.rendered_generation {
                     if edited {
@@ -788,6 +1175,10 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
                 }
 
                 ctx.set_handled();
+                if let Some(interval) = self.restart_caret_blink() {
+                    self.caret_blink_timer = Some(ctx.request_timer(interval));
+                }
+                ctx.request_paint_only();
                 if edited {
                     let text = self.text().into_iter().collect();
                     ctx.submit_action(crate::Action::TextChanged(text));
@@ -822,6 +1213,8 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
                 self.editor
                     .driver(fctx, lctx)
                     .select_from_accesskit(selection);
+                ctx.request_render();
+                ctx.set_handled();
             }
         }
     }
@@ -830,9 +1223,21 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
 
     fn update(&mut self, ctx: &mut UpdateCtx, event: &Update) {
         match event {
-            Update::FocusChanged(_) => {
+            Update::FocusChanged(focused) => {
+                let interval = self.restart_caret_blink();
+                if let Some(interval) = interval.filter(|_| *focused) {
+                    self.caret_blink_timer = Some(ctx.request_timer(interval));
+                }
                 ctx.request_render();
             }
+            Update::Timer(token) if self.caret_blink_timer == Some(*token) => {
+                self.caret_blink_timer = None;
+                if let Some(interval) = self.caret_blink_interval {
+                    self.caret_visible = !self.caret_visible;
+                    self.caret_blink_timer = Some(ctx.request_timer(interval));
+                    ctx.request_paint_only();
+                }
+            }
             Update::DisabledChanged(_) => {
                 // We might need to use the disabled brush, and stop displaying the selection.
                 ctx.request_render();
@@ -869,6 +1274,10 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
         if new_generation != self.rendered_generation {
             self.rendered_generation = new_generation;
         }
+        self.refresh_find_matches();
+        if self.needs_spell_check() {
+            ctx.schedule_idle(check_spelling::<EDITABLE>);
+        }
 
         let (fctx, lctx) = ctx.text_contexts();
         let layout = self.editor.layout(fctx, lctx);
@@ -883,6 +1292,27 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
         bc.constrain(area_size)
     }
 
+    fn take_text_layout_job(&mut self) -> Option<TextLayoutJob> {
+        if self.editor.try_layout().is_some() {
+            return None;
+        }
+        // The editor breaks lines as it shapes the text, so wrapped text is only shaped ahead
+        // once a previous layout has given the editor its width.
+        if self.word_wrap && self.last_available_width.is_none() {
+            return None;
+        }
+        let editor = std::mem::replace(&mut self.editor, PlainEditor::new(theme::TEXT_SIZE_NORMAL));
+        Some(TextLayoutJob::for_editor(editor))
+    }
+
+    fn finish_text_layout_job(&mut self, job: TextLayoutJob) {
+        if let Some(editor) = job.into_editor() {
+            self.editor = editor;
+        } else {
+            debug_panic!("TextArea was given back a job it didn't hand out");
+        }
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
         let layout = if let Some(layout) = self.editor.try_layout() {
             layout
@@ -896,15 +1326,28 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
         let is_rtl = layout.is_rtl();
         let origin = Vec2::new(self.padding.get_left(is_rtl), self.padding.top);
         let transform = Affine::translate(origin);
+        if let Some(find) = &self.find {
+            for (index, range) in find.matches.iter().enumerate() {
+                let color = if find.active == Some(index) {
+                    theme::FIND_ACTIVE_MATCH_COLOR
+                } else {
+                    theme::FIND_MATCH_COLOR
+                };
+                for rect in range_rects(layout, range.clone()) {
+                    scene.fill(Fill::NonZero, transform, color, None, &rect);
+                }
+            }
+        }
         if ctx.is_focused() {
             for rect in self.editor.selection_geometry().iter() {
                 // TODO: If window not focused, use a different color
                 // TODO: Make configurable
                 scene.fill(Fill::NonZero, transform, Color::STEEL_BLUE, None, &rect);
             }
-            if let Some(cursor) = self.editor.cursor_geometry(1.5) {
-                // TODO: Make configurable
-                scene.fill(Fill::NonZero, transform, Color::WHITE, None, &cursor);
+            if let Some(cursor) = self.editor.cursor_geometry(self.caret_width) {
+                if self.caret_visible {
+                    scene.fill(Fill::NonZero, transform, &self.caret_brush, None, &cursor);
+                }
             };
         }
 
@@ -916,6 +1359,17 @@ impl<const EDITABLE: bool> Widget for TextArea<EDITABLE> {
             self.brush.clone()
         };
         render_text(scene, transform, layout, &[brush], self.hint);
+
+        if let Some(spelling) = &self.spelling {
+            let stroke = Stroke::new(1.);
+            for range in &spelling.misspelled {
+                for rect in range_rects(layout, range.clone()) {
+                    let squiggle = squiggle(rect);
+                    let color = theme::SPELLING_ERROR_COLOR;
+                    scene.stroke(&stroke, transform, color, None, &squiggle);
+                }
+            }
+        }
     }
 
     fn get_cursor(&self, _ctx: &QueryCtx, _pos: Point) -> CursorIcon {
@@ -975,6 +1429,44 @@ mod tests {
     use crate::testing::TestHarness;
     // Tests of alignment happen in Prose.
 
+    #[test]
+    fn text_layout_job() {
+        let mut area = TextArea::new_immutable("Hello");
+        // The text would need shaping again once the first layout sets the width.
+        assert!(area.take_text_layout_job().is_none());
+
+        area.last_available_width = Some(100.0);
+        let job = area.take_text_layout_job().unwrap();
+        area.finish_text_layout_job(job);
+        let text: String = area.text().into_iter().collect();
+        assert_eq!(text, "Hello");
+    }
+
+    #[test]
+    fn ime_composition() {
+        let area = TextArea::new_editable("");
+        let mut harness = TestHarness::create(area);
+        let area_id = harness.root_widget().id();
+        harness.focus_on(Some(area_id));
+        assert!(harness.has_ime_session());
+
+        harness.ime_compose(&["n", "ni", "nih", "niha", "nihao"], "你好");
+        let text: String = harness
+            .root_widget()
+            .downcast::<TextArea<true>>()
+            .unwrap()
+            .text()
+            .into_iter()
+            .collect();
+        assert_eq!(text, "你好");
+
+        let mut last_action = None;
+        while let Some((action, _)) = harness.pop_action() {
+            last_action = Some(action);
+        }
+        assert_eq!(last_action, Some(crate::Action::TextChanged("你好".into())));
+    }
+
     #[test]
     fn edit_wordwrap() {
         let base_with_wrapping = {
@@ -1018,6 +1510,97 @@ mod tests {
         };
     }
 
+    #[test]
+    fn caret_blinks_when_idle() {
+        let interval = Duration::from_millis(500);
+        let area = TextArea::new_editable("Text").with_caret_blink_interval(Some(interval));
+        let mut harness = TestHarness::create(area);
+        let area_id = harness.root_widget().id();
+        let caret_visible = |harness: &TestHarness| {
+            let root = harness.get_widget(area_id);
+            root.downcast::<TextArea<true>>().unwrap().caret_visible
+        };
+
+        harness.focus_on(Some(area_id));
+        assert!(caret_visible(&harness));
+        harness.advance_time(interval);
+        assert!(!caret_visible(&harness));
+        harness.advance_time(interval);
+        assert!(caret_visible(&harness));
+
+        // Typing shows the cursor, and restarts its blinking
+        harness.advance_time(interval);
+        harness.keyboard_type_chars("a");
+        assert!(caret_visible(&harness));
+        harness.advance_time(interval / 2);
+        assert!(caret_visible(&harness));
+        harness.advance_time(interval / 2);
+        assert!(!caret_visible(&harness));
+    }
+
+    #[test]
+    fn find_matches() {
+        let area = TextArea::new_immutable("One two one");
+        let mut harness = TestHarness::create(area);
+
+        harness.edit_root_widget(|mut root| {
+            let mut area = root.downcast::<TextArea<false>>();
+            let count = TextArea::set_find_pattern(&mut area, Some(FindPattern::text("one")));
+            assert_eq!(count, 2);
+
+            // Navigation wraps around the text
+            assert_eq!(TextArea::find_next(&mut area), Some(0));
+            assert_eq!(TextArea::find_next(&mut area), Some(1));
+            assert_eq!(TextArea::find_next(&mut area), Some(0));
+            assert_eq!(TextArea::find_previous(&mut area), Some(1));
+        });
+        let root = harness.root_widget();
+        let area = root.downcast::<TextArea<false>>().unwrap();
+        assert_eq!(area.find_matches(), [0..3, 8..11]);
+        assert_eq!(area.active_find_match(), Some(1));
+
+        // The matches follow the text
+        harness.edit_root_widget(|mut root| {
+            let mut area = root.downcast::<TextArea<false>>();
+            TextArea::reset_text(&mut area, "one");
+        });
+        let root = harness.root_widget();
+        let area = root.downcast::<TextArea<false>>().unwrap();
+        assert_eq!(area.find_matches(), [0..3]);
+        assert_eq!(area.active_find_match(), None);
+    }
+
+    #[test]
+    fn spell_check_when_idle() {
+        struct Teh;
+        impl SpellChecker for Teh {
+            fn check(&self, text: &str) -> Vec<Range<usize>> {
+                FindPattern::text("teh").find_in(text)
+            }
+        }
+
+        let area = TextArea::new_editable("teh cat").with_spell_checker(Arc::new(Teh));
+        let mut harness = TestHarness::create(area);
+        let area_id = harness.root_widget().id();
+        let misspelled = |harness: &TestHarness| {
+            let root = harness.get_widget(area_id);
+            let area = root.downcast::<TextArea<true>>().unwrap();
+            area.misspelled_ranges().to_vec()
+        };
+
+        // The text is only checked once the event loop is idle
+        assert_eq!(misspelled(&harness), []);
+        harness.run_idle_work();
+        assert_eq!(misspelled(&harness), [0..3]);
+
+        harness.edit_root_widget(|mut root| {
+            let mut area = root.downcast::<TextArea<true>>();
+            TextArea::replace_range(&mut area, 0..3, "the");
+        });
+        harness.run_idle_work();
+        assert_eq!(misspelled(&harness), []);
+    }
+
     #[test]
     fn edit_textarea() {
         let base_target = {
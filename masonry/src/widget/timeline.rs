@@ -0,0 +1,566 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Labeled bars against a time axis, like a Gantt chart or the trace view of a profiler.
+
+use std::ops::Range;
+
+use accesskit::{Node, Role};
+use parley::layout::Alignment;
+use parley::{FontContext, Layout, LayoutContext};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{Affine, Line, Vec2};
+use vello::peniko::Mix;
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text::{
+    default_style_properties, render_text, ArcStr, BrushIndex, StyleProperty, TextLayoutJob,
+};
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, ActionSource, BoxConstraints, EventCtx, LayoutCtx, PaintCtx,
+    Point, PointerEvent, QueryCtx, Rect, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The height of a row of bars.
+const ROW_HEIGHT: f64 = 24.0;
+/// The space between the bars and the edges of their row.
+const BAR_INSET: f64 = 2.0;
+/// The narrowest a bar is drawn, so that short items can still be hovered and clicked.
+const MIN_BAR_WIDTH: f64 = 2.0;
+/// The space between the start of a bar and its label.
+const LABEL_PADDING: f64 = 4.0;
+/// The height of the time axis, above the rows.
+const AXIS_HEIGHT: f64 = 20.0;
+/// The smallest space between two labeled ticks of the axis.
+const MIN_TICK_SPACING: f64 = 80.0;
+/// How much the view is zoomed by a step of the mouse wheel.
+const ZOOM_STEP: f64 = 1.25;
+/// The distance the view is panned by a line of horizontal wheel delta.
+const SCROLLING_SPEED: f64 = 10.0;
+/// How far the pointer must move for a press to become a pan instead of a click.
+const DRAG_THRESHOLD: f64 = 3.0;
+/// The offset of the details popup from the pointer.
+const DETAILS_OFFSET: f64 = 12.0;
+/// The space between the edges of the details popup and its text.
+const DETAILS_PADDING: f64 = 4.0;
+/// The width of a timeline without width constraints.
+const DEFAULT_WIDTH: f64 = 400.0;
+
+/// An item of a [`Timeline`]: a labeled bar in one of its rows, from `start` to `end`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimelineItem {
+    /// The row of the bar, from the top.
+    pub row: usize,
+    /// The time at which the item starts.
+    pub start: f64,
+    /// The time at which the item ends.
+    pub end: f64,
+    /// The text in the bar.
+    pub label: ArcStr,
+    /// The text shown next to the pointer while the bar is hovered.
+    ///
+    /// The label is shown instead if this is empty.
+    pub details: ArcStr,
+}
+
+impl TimelineItem {
+    /// Create a new item in `row`, from `start` to `end`.
+    pub fn new(row: usize, start: f64, end: f64, label: impl Into<ArcStr>) -> Self {
+        Self {
+            row,
+            start,
+            end,
+            label: label.into(),
+            details: "".into(),
+        }
+    }
+
+    /// Builder-style method to set the text shown while the item is hovered.
+    pub fn with_details(mut self, details: impl Into<ArcStr>) -> Self {
+        self.details = details.into();
+        self
+    }
+}
+
+/// The item at this index of a [`Timeline`] was clicked.
+///
+/// This is the [`ActionSource::Action`] of [`Timeline`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimelineItemClicked(pub usize);
+
+/// A press on the timeline, while the pointer is captured.
+struct Press {
+    /// The horizontal position of the pointer when it was pressed.
+    start_x: f64,
+    /// The start of the visible range when the pointer was pressed.
+    view_start: f64,
+    /// The item which was pressed, if any.
+    item: Option<usize>,
+    /// Whether the pointer moved far enough to pan the view.
+    panning: bool,
+}
+
+/// Labeled bars in rows against a time axis, like a Gantt chart or the trace view of a
+/// profiler.
+///
+/// The mouse wheel zooms in and out around the pointer, and dragging pans the view.
+/// Hovering a bar shows its [details](TimelineItem::details), and clicking it submits
+/// [`TimelineItemClicked`] as a [typed action](crate::Action::typed).
+///
+/// Times are plain numbers, in whichever unit the app uses.
+pub struct Timeline {
+    items: Vec<TimelineItem>,
+    /// The shaped labels of the items, in the same order.
+    labels: Vec<Layout<BrushIndex>>,
+    /// Whether `labels` need to be recreated, because the items changed.
+    labels_changed: bool,
+    /// The time at the left edge of the timeline.
+    view_start: f64,
+    /// The time shown over the width of the timeline.
+    view_duration: f64,
+    /// The times of the labeled ticks of the axis, with their labels.
+    ticks: Vec<(f64, Layout<BrushIndex>)>,
+    hovered: Option<usize>,
+    /// The position of the pointer, where the details of the hovered item are shown.
+    pointer_pos: Point,
+    /// The shaped details of the hovered item.
+    details: Layout<BrushIndex>,
+    press: Option<Press>,
+}
+
+// --- MARK: BUILDERS ---
+impl Timeline {
+    /// Create a new timeline showing `items`, zoomed to fit all of them.
+    pub fn new(items: impl IntoIterator<Item = TimelineItem>) -> Self {
+        let items: Vec<_> = items.into_iter().collect();
+        let start = items.iter().map(|item| item.start).reduce(f64::min);
+        let end = items.iter().map(|item| item.end).reduce(f64::max);
+        let (view_start, view_duration) = match (start, end) {
+            (Some(start), Some(end)) if end > start => (start, end - start),
+            _ => (0.0, 1.0),
+        };
+        Self {
+            items,
+            labels: Vec::new(),
+            labels_changed: true,
+            view_start,
+            view_duration,
+            ticks: Vec::new(),
+            hovered: None,
+            pointer_pos: Point::ORIGIN,
+            details: Layout::new(),
+            press: None,
+        }
+    }
+
+    /// Builder-style method to set the range of time shown.
+    pub fn with_visible_range(mut self, range: Range<f64>) -> Self {
+        self.set_view(range);
+        self
+    }
+
+    /// The items of the timeline.
+    pub fn items(&self) -> &[TimelineItem] {
+        &self.items
+    }
+
+    /// The range of time shown over the width of the timeline.
+    pub fn visible_range(&self) -> Range<f64> {
+        self.view_start..self.view_start + self.view_duration
+    }
+
+    /// The index of the item under the pointer, if any.
+    pub fn hovered_item(&self) -> Option<usize> {
+        self.hovered
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Timeline {
+    /// Replace the items of the timeline, keeping the range of time shown.
+    pub fn set_items(
+        this: &mut WidgetMut<'_, Self>,
+        items: impl IntoIterator<Item = TimelineItem>,
+    ) {
+        this.widget.items = items.into_iter().collect();
+        this.widget.labels_changed = true;
+        this.widget.hovered = None;
+        this.widget.press = None;
+        this.ctx.request_layout();
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Set the range of time shown.
+    pub fn set_visible_range(this: &mut WidgetMut<'_, Self>, range: Range<f64>) {
+        this.widget.set_view(range);
+        this.ctx.request_layout();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl Timeline {
+    fn set_view(&mut self, range: Range<f64>) {
+        if range.end > range.start {
+            self.view_start = range.start;
+            self.view_duration = range.end - range.start;
+        }
+    }
+
+    fn time_to_x(&self, time: f64, width: f64) -> f64 {
+        (time - self.view_start) / self.view_duration * width
+    }
+
+    fn x_to_time(&self, x: f64, width: f64) -> f64 {
+        self.view_start + x / width * self.view_duration
+    }
+
+    /// The rectangle of the bar of `item`, in a timeline of `width`.
+    fn bar_rect(&self, item: &TimelineItem, width: f64) -> Rect {
+        let x0 = self.time_to_x(item.start, width);
+        let x1 = self.time_to_x(item.end, width).max(x0 + MIN_BAR_WIDTH);
+        let y0 = AXIS_HEIGHT + item.row as f64 * ROW_HEIGHT;
+        Rect::new(x0, y0 + BAR_INSET, x1, y0 + ROW_HEIGHT - BAR_INSET)
+    }
+
+    /// The index of the item under `pos`, in a timeline of `width`.
+    ///
+    /// Items painted later are on top, so they're found first.
+    fn item_at(&self, pos: Point, width: f64) -> Option<usize> {
+        if pos.y < AXIS_HEIGHT {
+            return None;
+        }
+        self.items
+            .iter()
+            .rposition(|item| self.bar_rect(item, width).contains(pos))
+    }
+
+    /// Update the hovered item for a pointer at `pos`, in a timeline of `width`.
+    fn hover(&mut self, ctx: &mut EventCtx, pos: Option<Point>, width: f64) {
+        let hovered = pos.and_then(|pos| self.item_at(pos, width));
+        if let Some(pos) = pos {
+            self.pointer_pos = pos;
+        }
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            // The details of the new item are shaped in layout.
+            ctx.request_layout();
+        } else if hovered.is_some() {
+            // The details follow the pointer.
+            ctx.request_paint_only();
+        }
+    }
+
+    /// Zoom in or out by `factor`, keeping the time at `x` under it.
+    fn zoom(&mut self, x: f64, width: f64, factor: f64) {
+        let time = self.x_to_time(x, width);
+        self.view_duration *= factor;
+        self.view_start = time - x / width * self.view_duration;
+    }
+
+    /// The distance between two labeled ticks of the axis, as a round number of time units.
+    fn tick_step(&self, width: f64) -> f64 {
+        let min_step = self.view_duration * MIN_TICK_SPACING / width;
+        let magnitude = 10_f64.powf(min_step.log10().floor());
+        [1.0, 2.0, 5.0, 10.0]
+            .into_iter()
+            .map(|multiple| multiple * magnitude)
+            .find(|step| *step >= min_step)
+            .unwrap_or(10.0 * magnitude)
+    }
+
+    fn shape(
+        font_ctx: &mut FontContext,
+        layout_ctx: &mut LayoutContext<BrushIndex>,
+        text: ArcStr,
+        layout: &mut Layout<BrushIndex>,
+    ) {
+        let mut job = TextLayoutJob::new(
+            text,
+            default_style_properties()
+                .into_iter()
+                .chain([StyleProperty::FontSize(theme::TEXT_SIZE_NORMAL)]),
+            std::mem::replace(layout, Layout::new()),
+        );
+        job.run(font_ctx, layout_ctx);
+        job.layout.break_all_lines(None);
+        job.layout.align(None, Alignment::Start);
+        *layout = job.layout;
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Timeline {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let width = ctx.size().width;
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                ctx.capture_pointer();
+                self.press = Some(Press {
+                    start_x: local_pos.x,
+                    view_start: self.view_start,
+                    item: self.item_at(local_pos, width),
+                    panning: false,
+                });
+            }
+            PointerEvent::PointerMove(state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                match &mut self.press {
+                    Some(press) if ctx.has_pointer_capture() => {
+                        let dx = local_pos.x - press.start_x;
+                        press.panning |= dx.abs() > DRAG_THRESHOLD;
+                        if press.panning {
+                            self.view_start = press.view_start - dx / width * self.view_duration;
+                            // The labels of the ticks change.
+                            ctx.request_layout();
+                        }
+                    }
+                    _ => self.hover(ctx, Some(local_pos), width),
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if let Some(press) = self.press.take() {
+                    let item = self.item_at(local_pos, width);
+                    if !press.panning && press.item.is_some() && press.item == item {
+                        ctx.submit_typed_action(TimelineItemClicked(item.unwrap()));
+                    }
+                }
+                self.hover(ctx, Some(local_pos), width);
+            }
+            PointerEvent::PointerLeave(_) => {
+                if self.press.is_none() {
+                    self.hover(ctx, None, width);
+                }
+            }
+            PointerEvent::MouseWheel(delta, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                let delta = delta.to_pixels(SCROLLING_SPEED);
+                if delta.y != 0.0 {
+                    let factor = if delta.y > 0.0 {
+                        1.0 / ZOOM_STEP
+                    } else {
+                        ZOOM_STEP
+                    };
+                    self.zoom(local_pos.x, width, factor);
+                }
+                self.view_start -= delta.x / width * self.view_duration;
+                ctx.request_layout();
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            DEFAULT_WIDTH
+        };
+        let rows = self
+            .items
+            .iter()
+            .map(|item| item.row + 1)
+            .max()
+            .unwrap_or(0);
+        let size = bc.constrain(Size::new(width, AXIS_HEIGHT + rows as f64 * ROW_HEIGHT));
+
+        let (font_ctx, layout_ctx) = ctx.text_contexts();
+        if std::mem::take(&mut self.labels_changed) {
+            self.labels.resize_with(self.items.len(), Layout::new);
+            for (item, label) in self.items.iter().zip(&mut self.labels) {
+                Self::shape(font_ctx, layout_ctx, item.label.clone(), label);
+            }
+        }
+
+        if let Some(item) = self.hovered.map(|hovered| &self.items[hovered]) {
+            let details = if item.details.is_empty() {
+                &item.label
+            } else {
+                &item.details
+            };
+            Self::shape(font_ctx, layout_ctx, details.clone(), &mut self.details);
+        }
+
+        let mut old_ticks = std::mem::take(&mut self.ticks);
+        let step = self.tick_step(size.width);
+        if !step.is_finite() {
+            return size;
+        }
+        let decimals = (-step.log10().floor()).max(0.0) as usize;
+        let first_tick = (self.view_start / step).ceil() as i64;
+        let last_tick = ((self.view_start + self.view_duration) / step).floor() as i64;
+        for tick in first_tick..=last_tick {
+            let time = tick as f64 * step;
+            let mut layout = old_ticks
+                .pop()
+                .map_or_else(Layout::new, |(_, layout)| layout);
+            Self::shape(
+                font_ctx,
+                layout_ctx,
+                format!("{time:.decimals$}").into(),
+                &mut layout,
+            );
+            self.ticks.push((time, layout));
+        }
+
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        fill_color(scene, &size.to_rect(), theme::BACKGROUND_DARK);
+
+        for (time, label) in &self.ticks {
+            let x = self.time_to_x(*time, size.width).round() + 0.5;
+            stroke(
+                scene,
+                &Line::new((x, 0.0), (x, size.height)),
+                theme::BORDER_DARK,
+                1.0,
+            );
+            let label_y = (AXIS_HEIGHT - f64::from(label.height())) / 2.0;
+            let transform = Affine::translate(Vec2::new(x + LABEL_PADDING, label_y));
+            render_text(scene, transform, label, &[theme::TEXT_COLOR.into()], true);
+        }
+        let axis = Line::new((0.0, AXIS_HEIGHT - 0.5), (size.width, AXIS_HEIGHT - 0.5));
+        stroke(scene, &axis, theme::BORDER_LIGHT, 1.0);
+
+        let rows_rect = Rect::new(0.0, AXIS_HEIGHT, size.width, size.height);
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &rows_rect);
+        for (idx, (item, label)) in self.items.iter().zip(&self.labels).enumerate() {
+            let bar = self.bar_rect(item, size.width);
+            if bar.x1 < 0.0 || bar.x0 > size.width {
+                continue;
+            }
+            let color = if self.hovered == Some(idx) {
+                theme::PRIMARY_LIGHT
+            } else {
+                theme::PRIMARY_DARK
+            };
+            fill_color(scene, &bar.to_rounded_rect(2.0), color);
+
+            // The label is cut at the end of the bar, and stays visible while the start of
+            // the bar is scrolled out of view.
+            scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &bar);
+            let label_x = bar.x0.max(0.0) + LABEL_PADDING;
+            let label_y = bar.y0 + (bar.height() - f64::from(label.height())) / 2.0;
+            let transform = Affine::translate(Vec2::new(label_x, label_y));
+            render_text(scene, transform, label, &[theme::TEXT_COLOR.into()], true);
+            scene.pop_layer();
+        }
+        scene.pop_layer();
+
+        if self.hovered.is_some() && self.press.is_none() {
+            let details_size = Size::new(
+                f64::from(self.details.width()) + 2.0 * DETAILS_PADDING,
+                f64::from(self.details.height()) + 2.0 * DETAILS_PADDING,
+            );
+            // The popup is kept within the timeline.
+            let origin = Point::new(
+                (self.pointer_pos.x + DETAILS_OFFSET)
+                    .min(size.width - details_size.width)
+                    .max(0.0),
+                (self.pointer_pos.y + DETAILS_OFFSET)
+                    .min(size.height - details_size.height)
+                    .max(0.0),
+            );
+            let popup = Rect::from_origin_size(origin, details_size).to_rounded_rect(2.0);
+            fill_color(scene, &popup, theme::BACKGROUND_LIGHT);
+            stroke(scene, &popup, theme::BORDER_LIGHT, 1.0);
+            let transform =
+                Affine::translate(origin.to_vec2() + Vec2::new(DETAILS_PADDING, DETAILS_PADDING));
+            render_text(
+                scene,
+                transform,
+                &self.details,
+                &[theme::TEXT_COLOR.into()],
+                true,
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Figure
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        node.set_description(format!("{} items", self.items.len()));
+        if let Some(hovered) = self.hovered {
+            node.set_value(self.items[hovered].label.to_string());
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Timeline", id = ctx.widget_id().trace())
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("{:?}", self.visible_range()))
+    }
+}
+
+impl ActionSource for Timeline {
+    type Action = TimelineItemClicked;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn click_zoom_and_pan() {
+        let widget = Timeline::new([
+            TimelineItem::new(0, 0.0, 10.0, "Build"),
+            TimelineItem::new(1, 5.0, 20.0, "Test").with_details("Test: 15s"),
+        ]);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 100.));
+        let timeline = harness.root_widget().downcast::<Timeline>().unwrap();
+        assert_eq!(timeline.visible_range(), 0.0..20.0);
+
+        // 10 pixels per unit of time, so the second bar goes from 50 to 200.
+        let row_1_y = AXIS_HEIGHT + 1.5 * ROW_HEIGHT;
+        harness.mouse_move((150., row_1_y));
+        let timeline = harness.root_widget().downcast::<Timeline>().unwrap();
+        assert_eq!(timeline.hovered_item(), Some(1));
+        harness.mouse_button_press(PointerButton::Primary);
+        harness.mouse_button_release(PointerButton::Primary);
+        let (action, _) = harness.pop_action().unwrap();
+        assert_eq!(
+            action.downcast::<TimelineItemClicked>().ok(),
+            Some(TimelineItemClicked(1))
+        );
+
+        // Zooming in keeps the time under the pointer in place.
+        harness.mouse_move((100., row_1_y));
+        harness.mouse_wheel(Vec2::new(0., 1.));
+        let timeline = harness.root_widget().downcast::<Timeline>().unwrap();
+        let range = timeline.visible_range();
+        assert_eq!(range.end - range.start, 20.0 / ZOOM_STEP);
+        assert_eq!((range.start + range.end) / 2.0, 10.0);
+
+        // Dragging pans without clicking.
+        harness.drag_from_to((100., row_1_y), (50., row_1_y));
+        let timeline = harness.root_widget().downcast::<Timeline>().unwrap();
+        let panned = timeline.visible_range();
+        assert!((panned.start - range.start - 50.0 / 200.0 * 16.0).abs() < 1e-9);
+        assert!(harness.pop_action().is_none());
+    }
+}
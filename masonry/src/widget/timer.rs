@@ -0,0 +1,200 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An invisible widget which submits actions after a delay.
+
+use std::time::Duration;
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::Scene;
+
+use crate::widget::WidgetMut;
+use crate::{
+    AccessCtx, AccessEvent, ActionSource, BoxConstraints, EventCtx, LayoutCtx, PaintCtx,
+    PointerEvent, QueryCtx, RegisterCtx, Size, TextEvent, TimerToken, Update, UpdateCtx, Widget,
+    WidgetId,
+};
+
+/// The shortest period of a repeating [`Timer`], so that it can't starve the event loop.
+const MIN_PERIOD: Duration = Duration::from_millis(1);
+
+/// An invisible widget which submits a [`TimerFired`] action once its duration has elapsed
+/// after it is added to the tree, and then again after every duration if it repeats.
+///
+/// The timer is driven by the event loop, with [`EventCtx::request_timer`], so it doesn't
+/// need a thread or an async runtime. It takes no space, and is cancelled when the widget
+/// is removed.
+pub struct Timer {
+    duration: Duration,
+    repeat: bool,
+    /// The timer currently running. Timers with other tokens were cancelled.
+    token: Option<TimerToken>,
+}
+
+/// The [`Timer`] this action is for expired.
+///
+/// This is the [`ActionSource::Action`] of [`Timer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimerFired;
+
+// --- MARK: BUILDERS ---
+impl Timer {
+    /// Create a timer which fires once, `duration` after it is added to the tree.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            repeat: false,
+            token: None,
+        }
+    }
+
+    /// Create a timer which fires every `period`, starting one `period` after it is added
+    /// to the tree.
+    ///
+    /// If the app can't keep up with the ticks, the missed ticks are skipped.
+    pub fn repeating(period: Duration) -> Self {
+        Self {
+            duration: period,
+            repeat: true,
+            token: None,
+        }
+    }
+
+    /// How long until the timer next fires, from when it is (re)started.
+    fn delay(&self) -> Duration {
+        if self.repeat {
+            self.duration.max(MIN_PERIOD)
+        } else {
+            self.duration
+        }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Timer {
+    /// Set the duration of the timer, or the period if it repeats.
+    ///
+    /// This restarts the timer, so it next fires `duration` from now.
+    pub fn set_duration(this: &mut WidgetMut<'_, Self>, duration: Duration) {
+        this.widget.duration = duration;
+        this.widget.token = Some(this.ctx.request_timer(this.widget.delay()));
+    }
+
+    /// Set whether the timer fires again every [duration](Self::set_duration) after
+    /// it first fires.
+    ///
+    /// This restarts the timer, so it next fires one duration from now.
+    pub fn set_repeat(this: &mut WidgetMut<'_, Self>, repeat: bool) {
+        this.widget.repeat = repeat;
+        this.widget.token = Some(this.ctx.request_timer(this.widget.delay()));
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Timer {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, event: &Update) {
+        match event {
+            Update::WidgetAdded => self.token = Some(ctx.request_timer(self.delay())),
+            Update::Timer(token) if self.token == Some(*token) => {
+                ctx.submit_typed_action(TimerFired);
+                self.token = self.repeat.then(|| ctx.request_timer(self.delay()));
+            }
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        bc.constrain(Size::ZERO)
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::Unknown
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Timer", id = ctx.widget_id().trace())
+    }
+}
+
+impl ActionSource for Timer {
+    type Action = TimerFired;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    fn fired_count(harness: &mut TestHarness) -> usize {
+        let mut count = 0;
+        while let Some((action, _)) = harness.pop_action() {
+            assert_eq!(action.downcast::<TimerFired>().ok(), Some(TimerFired));
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn fires_once() {
+        let mut harness = TestHarness::create(Timer::new(Duration::from_millis(100)));
+
+        harness.advance_time(Duration::from_millis(60));
+        assert_eq!(fired_count(&mut harness), 0);
+
+        harness.advance_time(Duration::from_millis(60));
+        assert_eq!(fired_count(&mut harness), 1);
+
+        harness.advance_time(Duration::from_millis(500));
+        assert_eq!(fired_count(&mut harness), 0);
+    }
+
+    #[test]
+    fn repeating_fires_every_period() {
+        let mut harness = TestHarness::create(Timer::repeating(Duration::from_millis(100)));
+
+        for _ in 0..3 {
+            harness.advance_time(Duration::from_millis(100));
+            assert_eq!(fired_count(&mut harness), 1);
+        }
+
+        // Missed ticks are skipped
+        harness.advance_time(Duration::from_millis(350));
+        assert_eq!(fired_count(&mut harness), 1);
+    }
+
+    #[test]
+    fn set_duration_restarts() {
+        let mut harness = TestHarness::create(Timer::new(Duration::from_millis(100)));
+        harness.advance_time(Duration::from_millis(80));
+
+        harness.edit_root_widget(|mut timer| {
+            let mut timer = timer.downcast::<Timer>();
+            Timer::set_duration(&mut timer, Duration::from_millis(50));
+        });
+        harness.advance_time(Duration::from_millis(40));
+        // The first timer was cancelled
+        assert_eq!(fired_count(&mut harness), 0);
+
+        harness.advance_time(Duration::from_millis(20));
+        assert_eq!(fired_count(&mut harness), 1);
+    }
+}
@@ -0,0 +1,310 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A title bar for windows without platform decorations.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use accesskit::{Node, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::kurbo::{Affine, Line, Point, Rect, Stroke};
+use vello::peniko::{Color, Fill};
+use vello::Scene;
+
+use crate::dpi::LogicalPosition;
+use crate::event::PointerButton;
+use crate::text::ArcStr;
+use crate::widget::{Label, WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent,
+    QueryCtx, RegisterCtx, Size, TextEvent, Update, UpdateCtx, Widget, WidgetId,
+};
+
+/// The height of the title bar.
+const TITLE_BAR_HEIGHT: f64 = 32.;
+/// The width of each of the minimize, maximize and close buttons.
+const CAPTION_BUTTON_WIDTH: f64 = 46.;
+/// The size of the glyphs drawn in the caption buttons.
+const GLYPH_SIZE: f64 = 10.;
+/// The space between the left edge and the title.
+const TITLE_PADDING: f64 = 12.;
+/// The longest time between the clicks of a double click, in seconds.
+const DOUBLE_CLICK_TIME: f64 = 0.5;
+
+const CLOSE_HOVER_COLOR: Color = Color::rgb8(0xc4, 0x2b, 0x1c);
+
+/// The buttons on the right of a [`TitleBar`], in the order they are shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CaptionButton {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+const CAPTION_BUTTONS: [CaptionButton; 3] = [
+    CaptionButton::Minimize,
+    CaptionButton::Maximize,
+    CaptionButton::Close,
+];
+
+/// A title bar, for windows created without platform decorations
+/// (see [`WindowAttributes::with_decorations`]).
+///
+/// It shows a title, and buttons to minimize, maximize and close the window.
+/// The rest of the bar moves the window when dragged, which lets the platform snap the window
+/// to the edges of the screen, and toggles maximization when double-clicked.
+/// Right-clicking it shows the window menu, on platforms which have one.
+///
+/// The event loop runner lets undecorated windows be resized from their edges.
+///
+/// [`WindowAttributes::with_decorations`]: winit::window::WindowAttributes::with_decorations
+pub struct TitleBar {
+    title: WidgetPod<Label>,
+    /// The caption button under the pointer.
+    hovered_button: Option<CaptionButton>,
+    /// The caption button the pointer was pressed on.
+    pressed_button: Option<CaptionButton>,
+    /// Used to detect double clicks on the draggable part of the bar.
+    last_click_time: Option<Instant>,
+}
+
+// --- MARK: BUILDERS ---
+impl TitleBar {
+    /// Create a title bar showing `title`.
+    pub fn new(title: impl Into<ArcStr>) -> Self {
+        Self::from_label(Label::new(title))
+    }
+
+    /// Create a title bar showing the provided [`Label`].
+    pub fn from_label(label: Label) -> Self {
+        Self {
+            title: WidgetPod::new(label),
+            hovered_button: None,
+            pressed_button: None,
+            last_click_time: None,
+        }
+    }
+
+    /// The caption button at `pos`, in the widget's coordinates.
+    fn button_at(&self, size: Size, pos: Point) -> Option<CaptionButton> {
+        CAPTION_BUTTONS
+            .into_iter()
+            .find(|&button| button_rect(size, button).contains(pos))
+    }
+}
+
+/// The rect of `button` in a title bar of `size`.
+fn button_rect(size: Size, button: CaptionButton) -> Rect {
+    let index = CAPTION_BUTTONS.iter().position(|&b| b == button).unwrap();
+    let from_right = (CAPTION_BUTTONS.len() - index) as f64;
+    let x0 = size.width - from_right * CAPTION_BUTTON_WIDTH;
+    Rect::new(x0, 0., x0 + CAPTION_BUTTON_WIDTH, size.height)
+}
+
+// --- MARK: WIDGETMUT ---
+impl TitleBar {
+    /// Set the title.
+    pub fn set_title(this: &mut WidgetMut<'_, Self>, new_title: impl Into<ArcStr>) {
+        Label::set_text(&mut Self::label_mut(this), new_title);
+    }
+
+    pub fn label_mut<'t>(this: &'t mut WidgetMut<'_, Self>) -> WidgetMut<'t, Label> {
+        this.ctx.get_mut(&mut this.widget.title)
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for TitleBar {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerMove(state) => {
+                let pos = Point::new(state.position.x, state.position.y) - ctx.window_origin();
+                let hovered = self.button_at(ctx.size(), pos.to_point());
+                if hovered != self.hovered_button {
+                    self.hovered_button = hovered;
+                    ctx.request_paint_only();
+                }
+            }
+            PointerEvent::PointerLeave(_) => {
+                if self.hovered_button.take().is_some() {
+                    ctx.request_paint_only();
+                }
+            }
+            PointerEvent::PointerDown(button, state) => {
+                let pos = Point::new(state.position.x, state.position.y) - ctx.window_origin();
+                if let Some(caption_button) = self.button_at(ctx.size(), pos.to_point()) {
+                    if *button == PointerButton::Primary {
+                        self.pressed_button = Some(caption_button);
+                        ctx.capture_pointer();
+                        ctx.request_paint_only();
+                    }
+                    return;
+                }
+                match button {
+                    PointerButton::Primary => {
+                        let now = ctx.now();
+                        let double_click = self.last_click_time.take().is_some_and(|last| {
+                            now.duration_since(last).as_secs_f64() < DOUBLE_CLICK_TIME
+                        });
+                        if double_click {
+                            ctx.toggle_maximized();
+                        } else {
+                            self.last_click_time = Some(now);
+                            ctx.drag_window();
+                        }
+                    }
+                    PointerButton::Secondary => {
+                        ctx.show_window_menu(LogicalPosition::new(
+                            state.position.x,
+                            state.position.y,
+                        ));
+                    }
+                    _ => (),
+                }
+            }
+            PointerEvent::PointerUp(_, state) => {
+                let Some(pressed) = self.pressed_button.take() else {
+                    return;
+                };
+                ctx.request_paint_only();
+                let pos = Point::new(state.position.x, state.position.y) - ctx.window_origin();
+                if self.button_at(ctx.size(), pos.to_point()) != Some(pressed) {
+                    return;
+                }
+                match pressed {
+                    CaptionButton::Minimize => ctx.minimize(),
+                    CaptionButton::Maximize => ctx.toggle_maximized(),
+                    CaptionButton::Close => ctx.exit(),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx) {
+        ctx.register_child(&mut self.title);
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _event: &Update) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let buttons_width = CAPTION_BUTTONS.len() as f64 * CAPTION_BUTTON_WIDTH;
+        let title_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new(
+                (bc.max().width - buttons_width - TITLE_PADDING).max(0.),
+                TITLE_BAR_HEIGHT,
+            ),
+        );
+        let title_size = ctx.run_layout(&mut self.title, &title_bc);
+
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            TITLE_PADDING + title_size.width + buttons_width
+        };
+        let size = bc.constrain(Size::new(width, TITLE_BAR_HEIGHT));
+
+        let title_origin = Point::new(TITLE_PADDING, (size.height - title_size.height) / 2.);
+        ctx.place_child(&mut self.title, title_origin);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            theme::BACKGROUND_DARK,
+            None,
+            &size.to_rect(),
+        );
+
+        for button in CAPTION_BUTTONS {
+            let rect = button_rect(size, button);
+            let highlighted = self.pressed_button == Some(button)
+                || (self.pressed_button.is_none() && self.hovered_button == Some(button));
+            if highlighted {
+                let color = match button {
+                    CaptionButton::Close => CLOSE_HOVER_COLOR,
+                    _ => theme::BACKGROUND_LIGHT,
+                };
+                scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+            }
+
+            let glyph = Rect::from_center_size(rect.center(), Size::new(GLYPH_SIZE, GLYPH_SIZE));
+            let stroke = Stroke::new(1.);
+            let color = theme::FOREGROUND_DARK;
+            match button {
+                CaptionButton::Minimize => {
+                    let y = glyph.center().y;
+                    let line = Line::new((glyph.x0, y), (glyph.x1, y));
+                    scene.stroke(&stroke, Affine::IDENTITY, color, None, &line);
+                }
+                CaptionButton::Maximize => {
+                    scene.stroke(&stroke, Affine::IDENTITY, color, None, &glyph);
+                }
+                CaptionButton::Close => {
+                    let lines = [
+                        Line::new((glyph.x0, glyph.y0), (glyph.x1, glyph.y1)),
+                        Line::new((glyph.x0, glyph.y1), (glyph.x1, glyph.y0)),
+                    ];
+                    for line in lines {
+                        scene.stroke(&stroke, Affine::IDENTITY, color, None, &line);
+                    }
+                }
+            }
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::TitleBar
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.title.id()]
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("TitleBar", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::Flex;
+
+    #[test]
+    fn title_bar_layout() {
+        let [title_bar_id] = widget_ids();
+        let widget = Flex::column().with_child(TitleBar::new("Window").with_id(title_bar_id));
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 300.));
+
+        let title_bar = harness.get_widget(title_bar_id);
+        assert_eq!(title_bar.ctx().size(), Size::new(400., TITLE_BAR_HEIGHT));
+
+        // The buttons are laid out from the right edge
+        let size = title_bar.ctx().size();
+        assert_eq!(
+            button_rect(size, CaptionButton::Close),
+            Rect::new(400. - CAPTION_BUTTON_WIDTH, 0., 400., TITLE_BAR_HEIGHT)
+        );
+        assert_eq!(
+            TitleBar::new("").button_at(size, Point::new(400. - 3. * CAPTION_BUTTON_WIDTH, 1.)),
+            Some(CaptionButton::Minimize)
+        );
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget displaying frames produced outside the UI, such as decoded video.
+
+use std::sync::{Arc, Mutex};
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::Affine;
+use vello::peniko::{BlendMode, Image as ImageBuf};
+use vello::Scene;
+
+use crate::widget::{ObjectFit, WidgetMut};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent, QueryCtx,
+    RegisterCtx, Size, TextEvent, Update, UpdateCtx, Widget, WidgetId,
+};
+
+/// Counters describing how the frames sent to a [`VideoFrames`] were displayed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VideoStats {
+    /// The number of frames pushed by the producer.
+    pub frames_received: u64,
+    /// The number of frames which were painted.
+    pub frames_shown: u64,
+    /// The number of frames replaced by a newer one before they could be painted.
+    pub frames_dropped: u64,
+}
+
+#[derive(Debug, Default)]
+struct SharedFrames {
+    pending: Option<ImageBuf>,
+    stats: VideoStats,
+}
+
+/// The channel through which a producer, such as a video decoder or a camera, sends frames
+/// to a [`Video`] widget.
+///
+/// This is cheap to clone, and can be sent to the thread producing the frames.
+/// Only the latest frame is kept: frames pushed faster than the display refreshes are dropped,
+/// which is counted in the [stats](Self::stats).
+///
+/// Frames are CPU buffers. Frames rendered by wgpu have to be copied back from the GPU,
+/// as Vello can't yet draw external textures.
+#[derive(Clone, Debug, Default)]
+pub struct VideoFrames {
+    shared: Arc<Mutex<SharedFrames>>,
+}
+
+impl VideoFrames {
+    /// Create a channel with no frames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send a new frame, replacing the previous one if it wasn't painted yet.
+    pub fn push(&self, frame: ImageBuf) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.stats.frames_received += 1;
+        if shared.pending.replace(frame).is_some() {
+            shared.stats.frames_dropped += 1;
+        }
+    }
+
+    /// The frame counters since this channel was created.
+    pub fn stats(&self) -> VideoStats {
+        self.shared.lock().unwrap().stats
+    }
+
+    fn take(&self) -> Option<ImageBuf> {
+        let mut shared = self.shared.lock().unwrap();
+        let frame = shared.pending.take()?;
+        shared.stats.frames_shown += 1;
+        Some(frame)
+    }
+}
+
+/// Two `VideoFrames` are equal if they are handles to the same channel.
+impl PartialEq for VideoFrames {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl Eq for VideoFrames {}
+
+/// A widget that displays the frames sent to a [`VideoFrames`] channel.
+///
+/// The widget checks for a new frame on every animation frame, and only repaints itself
+/// when there is one, so the rest of the scene is not re-encoded at the video's frame rate.
+///
+/// By default, frames are scaled to fit in the widget, keeping their aspect ratio
+/// ([`ObjectFit::Contain`]).
+pub struct Video {
+    frames: VideoFrames,
+    current_frame: Option<ImageBuf>,
+    object_fit: ObjectFit,
+}
+
+// --- MARK: BUILDERS ---
+impl Video {
+    /// Create a widget displaying the frames sent to `frames`.
+    pub fn new(frames: VideoFrames) -> Self {
+        Self {
+            frames,
+            current_frame: None,
+            object_fit: ObjectFit::Contain,
+        }
+    }
+
+    /// Builder-style method for specifying the object fit.
+    pub fn fit_mode(mut self, mode: ObjectFit) -> Self {
+        self.object_fit = mode;
+        self
+    }
+
+    fn frame_size(&self) -> Size {
+        self.current_frame
+            .as_ref()
+            .map(|frame| Size::new(frame.width as f64, frame.height as f64))
+            .unwrap_or(Size::ZERO)
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Video {
+    /// Modify the widget's object fit.
+    pub fn set_fit_mode(this: &mut WidgetMut<'_, Self>, new_object_fit: ObjectFit) {
+        this.widget.object_fit = new_object_fit;
+        this.ctx.request_layout();
+    }
+
+    /// Display the frames sent to another channel.
+    ///
+    /// The current frame is kept until the new channel receives one.
+    pub fn set_frames(this: &mut WidgetMut<'_, Self>, frames: VideoFrames) {
+        this.widget.frames = frames;
+        this.ctx.request_anim_frame();
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Video {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_anim_frame(&mut self, ctx: &mut UpdateCtx, _interval: u64) {
+        if let Some(frame) = self.frames.take() {
+            let old_size = self.frame_size();
+            self.current_frame = Some(frame);
+            if self.frame_size() != old_size {
+                ctx.request_layout();
+            } else {
+                ctx.request_paint_only();
+            }
+        }
+        ctx.request_anim_frame();
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, event: &Update) {
+        match event {
+            Update::WidgetAdded => {
+                ctx.request_anim_frame();
+            }
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // Take all the available space, so that the layout doesn't change when the first
+        // frame arrives, unless the frames' aspect ratio is needed to pick a size.
+        if bc.is_width_bounded() && bc.is_height_bounded() {
+            return bc.max();
+        }
+        let frame_size = self.frame_size();
+        if frame_size.is_zero_area() {
+            return bc.min();
+        }
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            frame_size.width
+        };
+        bc.constrain_aspect_ratio(frame_size.height / frame_size.width, width)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let Some(frame) = &self.current_frame else {
+            return;
+        };
+        let frame_size = Size::new(frame.width as f64, frame.height as f64);
+        if frame_size.is_zero_area() {
+            return;
+        }
+        let transform = self.object_fit.affine_to_fill(ctx.size(), frame_size);
+
+        let clip_rect = ctx.size().to_rect();
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+        scene.draw_image(frame, transform);
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Video
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Video", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use vello::peniko::Format;
+
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::Flex;
+
+    fn frame(width: u32, height: u32) -> ImageBuf {
+        let data = vec![255; (4 * width * height) as usize];
+        ImageBuf::new(data.into(), Format::Rgba8, width, height)
+    }
+
+    #[test]
+    fn frame_stats() {
+        let frames = VideoFrames::new();
+        let mut harness = TestHarness::create(Video::new(frames.clone()));
+
+        frames.push(frame(4, 4));
+        harness.animate_ms(16);
+        assert_eq!(
+            frames.stats(),
+            VideoStats {
+                frames_received: 1,
+                frames_shown: 1,
+                frames_dropped: 0,
+            }
+        );
+
+        // Only the last of several frames pushed within one animation frame is shown
+        frames.push(frame(4, 4));
+        frames.push(frame(4, 4));
+        frames.push(frame(4, 4));
+        harness.animate_ms(16);
+        assert_eq!(
+            frames.stats(),
+            VideoStats {
+                frames_received: 4,
+                frames_shown: 2,
+                frames_dropped: 2,
+            }
+        );
+
+        // Painting without a new frame doesn't count as showing one
+        harness.animate_ms(16);
+        assert_eq!(frames.stats().frames_shown, 2);
+    }
+
+    #[test]
+    fn aspect_fit_layout() {
+        let frames = VideoFrames::new();
+        let video_id = WidgetId::next();
+        // The column doesn't bound its children's height
+        let widget = Flex::column().with_child_id(Video::new(frames.clone()), video_id);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 400.));
+
+        frames.push(frame(16, 9));
+        harness.animate_ms(16);
+        let size = harness.get_widget(video_id).ctx().size();
+        assert_eq!(size, Size::new(200., 112.5));
+    }
+}
@@ -0,0 +1,564 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An on-screen keyboard, for touchscreens without a hardware keyboard.
+
+use accesskit::{Node, Role};
+use parley::layout::Alignment;
+use parley::{FontContext, Layout, LayoutContext};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{Affine, Vec2};
+use vello::peniko::Color;
+use vello::Scene;
+use winit::keyboard::{Key, ModifiersState, NamedKey, SmolStr};
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text::{
+    default_style_properties, render_text, ArcStr, BrushIndex, StyleProperty, TextLayoutJob,
+};
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point,
+    PointerEvent, QueryCtx, Rect, RegisterCtx, Size, TextEvent, Widget, WidgetId,
+};
+
+/// The height of a key, which is large enough to be touched.
+const KEY_HEIGHT: f64 = 44.0;
+/// The space between two keys.
+const KEY_GAP: f64 = 6.0;
+/// The radius of the corners of the keys.
+const KEY_RADIUS: f64 = 4.0;
+/// The width of a keyboard without width constraints.
+const DEFAULT_WIDTH: f64 = 600.0;
+
+/// What pressing a key of a [`VirtualKeyboard`] does.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyAction {
+    /// Type the first text, or the second one while shift is active.
+    Text(ArcStr, ArcStr),
+    /// Press a key which doesn't type a text, like <kbd>Backspace</kbd> or <kbd>Enter</kbd>.
+    Named(NamedKey),
+    /// Make the next key shifted, or every key until shift is pressed again when pressed
+    /// twice.
+    Shift,
+    /// Show another layer of the layout, by its index, e.g. for digits and symbols.
+    Layer(usize),
+}
+
+/// A key of a [`KeyboardLayout`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyCap {
+    /// What pressing the key does.
+    pub action: KeyAction,
+    /// The text shown on the key, when it doesn't type a text.
+    pub label: ArcStr,
+    /// The width of the key, relative to the width of a letter.
+    pub width: f64,
+}
+
+impl KeyCap {
+    /// A key typing `text`, or its uppercase while shift is active.
+    pub fn text(text: &str) -> Self {
+        Self::shifted(text, &text.to_uppercase())
+    }
+
+    /// A key typing `text`, or `shifted` while shift is active.
+    pub fn shifted(text: &str, shifted: &str) -> Self {
+        Self {
+            action: KeyAction::Text(text.into(), shifted.into()),
+            label: text.into(),
+            width: 1.0,
+        }
+    }
+
+    /// A key pressing `key`, showing `label`.
+    pub fn named(key: NamedKey, label: impl Into<ArcStr>) -> Self {
+        Self {
+            action: KeyAction::Named(key),
+            label: label.into(),
+            width: 1.0,
+        }
+    }
+
+    /// The shift key.
+    pub fn shift() -> Self {
+        Self {
+            action: KeyAction::Shift,
+            label: "⇧".into(),
+            width: 1.5,
+        }
+    }
+
+    /// A key showing the layer at `layer`, showing `label`.
+    pub fn layer(layer: usize, label: impl Into<ArcStr>) -> Self {
+        Self {
+            action: KeyAction::Layer(layer),
+            label: label.into(),
+            width: 1.5,
+        }
+    }
+
+    /// Builder-style method to set the width of the key, relative to the width of a letter.
+    pub fn with_width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// A key for each character of `chars`.
+    fn row(chars: &str) -> impl Iterator<Item = Self> + '_ {
+        chars
+            .chars()
+            .map(|char| Self::text(char.encode_utf8(&mut [0; 4])))
+    }
+}
+
+/// The keys of a [`VirtualKeyboard`], in rows, for each of its layers.
+///
+/// The first layer is shown first. [`KeyCap::layer`] keys switch between layers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardLayout {
+    layers: Vec<Vec<Vec<KeyCap>>>,
+}
+
+impl KeyboardLayout {
+    /// A layout with no layers, to add them with [`with_layer`](Self::with_layer).
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Builder-style method to add a layer with the given rows of keys, from the top.
+    pub fn with_layer(
+        mut self,
+        rows: impl IntoIterator<Item = impl IntoIterator<Item = KeyCap>>,
+    ) -> Self {
+        let layer = rows.into_iter().map(|row| row.into_iter().collect());
+        self.layers.push(layer.collect());
+        self
+    }
+
+    /// The layers of the layout.
+    pub fn layers(&self) -> &[Vec<Vec<KeyCap>>] {
+        &self.layers
+    }
+
+    /// The layout used for `locale`, a BCP 47 language tag like `"fr-FR"`.
+    ///
+    /// French uses [AZERTY](Self::azerty), German [QWERTZ](Self::qwertz), and other
+    /// languages [QWERTY](Self::qwerty).
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "fr" => Self::azerty(),
+            "de" => Self::qwertz(),
+            _ => Self::qwerty(),
+        }
+    }
+
+    /// The US QWERTY layout.
+    pub fn qwerty() -> Self {
+        Self::latin(["qwertyuiop", "asdfghjkl", "zxcvbnm"])
+    }
+
+    /// The French AZERTY layout.
+    pub fn azerty() -> Self {
+        Self::latin(["azertyuiop", "qsdfghjklm", "wxcvbn"])
+    }
+
+    /// The German QWERTZ layout.
+    pub fn qwertz() -> Self {
+        Self::latin(["qwertzuiopü", "asdfghjklöä", "yxcvbnm"])
+    }
+
+    /// A layout with the given three rows of letters, and a layer of digits and symbols.
+    fn latin(letters: [&str; 3]) -> Self {
+        let bottom_row = |layer: usize, label: &str| {
+            [
+                KeyCap::layer(layer, label),
+                KeyCap::text(","),
+                KeyCap::named(NamedKey::Space, " ").with_width(5.0),
+                KeyCap::text("."),
+                KeyCap::named(NamedKey::Enter, "⏎").with_width(1.5),
+            ]
+        };
+        let backspace = || KeyCap::named(NamedKey::Backspace, "⌫").with_width(1.5);
+        let [top, middle, bottom] = letters;
+        Self::new()
+            .with_layer([
+                KeyCap::row(top).collect::<Vec<_>>(),
+                KeyCap::row(middle).collect(),
+                std::iter::once(KeyCap::shift())
+                    .chain(KeyCap::row(bottom))
+                    .chain([backspace()])
+                    .collect(),
+                bottom_row(1, "?123").into(),
+            ])
+            .with_layer([
+                KeyCap::row("1234567890").collect::<Vec<_>>(),
+                KeyCap::row("@#$%&-+()/").collect(),
+                KeyCap::row("*\"':;!?").chain([backspace()]).collect(),
+                bottom_row(0, "ABC").into(),
+            ])
+    }
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> Self {
+        Self::qwerty()
+    }
+}
+
+/// Whether the next keys are shifted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ShiftState {
+    Off,
+    /// Only the next key is shifted.
+    Once,
+    Locked,
+}
+
+/// An on-screen keyboard, for kiosks and touchscreens without a hardware keyboard.
+///
+/// Pressing a key sends a [`TextEvent::VirtualKey`] to the focused widget, e.g. a
+/// [`Textbox`](crate::widget::Textbox), with [`EventCtx::submit_text_event`]. The keyboard
+/// never takes the focus, so the user can focus a text field and then type on the keyboard.
+///
+/// The keys are given by a [`KeyboardLayout`], which can be picked for a locale with
+/// [`KeyboardLayout::for_locale`].
+pub struct VirtualKeyboard {
+    layout: KeyboardLayout,
+    layer: usize,
+    shift: ShiftState,
+    /// The rectangles of the keys of the current layer, row by row.
+    key_rects: Vec<Rect>,
+    /// The shaped labels of the keys of the current layer, row by row.
+    labels: Vec<Layout<BrushIndex>>,
+    /// Whether `labels` need to be shaped again, because the keys or their labels changed.
+    labels_changed: bool,
+    /// The index of the key being pressed.
+    pressed: Option<usize>,
+}
+
+// --- MARK: BUILDERS ---
+impl VirtualKeyboard {
+    /// Create a keyboard with the given layout.
+    pub fn new(layout: KeyboardLayout) -> Self {
+        Self {
+            layout,
+            layer: 0,
+            shift: ShiftState::Off,
+            key_rects: Vec::new(),
+            labels: Vec::new(),
+            labels_changed: true,
+            pressed: None,
+        }
+    }
+
+    /// The layout of the keyboard.
+    pub fn layout(&self) -> &KeyboardLayout {
+        &self.layout
+    }
+
+    /// The index of the layer shown.
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl VirtualKeyboard {
+    /// Replace the layout of the keyboard, e.g. when the locale changes.
+    pub fn set_layout(this: &mut WidgetMut<'_, Self>, layout: KeyboardLayout) {
+        this.widget.layout = layout;
+        this.widget.layer = 0;
+        this.widget.shift = ShiftState::Off;
+        this.widget.pressed = None;
+        this.widget.labels_changed = true;
+        this.ctx.request_layout();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl VirtualKeyboard {
+    /// The keys of the current layer, row by row.
+    fn keys(&self) -> impl Iterator<Item = &KeyCap> {
+        self.layout
+            .layers
+            .get(self.layer)
+            .into_iter()
+            .flatten()
+            .flatten()
+    }
+
+    fn key_at(&self, pos: Point) -> Option<usize> {
+        self.key_rects.iter().position(|rect| rect.contains(pos))
+    }
+
+    fn label(&self, key: &KeyCap) -> ArcStr {
+        match &key.action {
+            KeyAction::Text(_, shifted) if self.shift != ShiftState::Off => shifted.clone(),
+            KeyAction::Text(text, _) => text.clone(),
+            _ => key.label.clone(),
+        }
+    }
+
+    /// Press the key at `idx`.
+    fn press(&mut self, ctx: &mut EventCtx, idx: usize) {
+        let Some(action) = self.keys().nth(idx).map(|key| key.action.clone()) else {
+            return;
+        };
+        let shifted = self.shift != ShiftState::Off;
+        let mods = if shifted {
+            ModifiersState::SHIFT
+        } else {
+            ModifiersState::empty()
+        };
+        match action {
+            KeyAction::Text(text, shifted_text) => {
+                let text = if shifted { shifted_text } else { text };
+                let key = Key::Character(SmolStr::new(&*text));
+                ctx.submit_text_event(TextEvent::VirtualKey(key, mods));
+                if self.shift == ShiftState::Once {
+                    self.shift = ShiftState::Off;
+                    self.labels_changed = true;
+                }
+            }
+            KeyAction::Named(named) => {
+                ctx.submit_text_event(TextEvent::VirtualKey(Key::Named(named), mods));
+            }
+            KeyAction::Shift => {
+                self.shift = match self.shift {
+                    ShiftState::Off => ShiftState::Once,
+                    ShiftState::Once => ShiftState::Locked,
+                    ShiftState::Locked => ShiftState::Off,
+                };
+                self.labels_changed = true;
+            }
+            KeyAction::Layer(layer) => {
+                if layer < self.layout.layers.len() {
+                    self.layer = layer;
+                    self.shift = ShiftState::Off;
+                    self.labels_changed = true;
+                }
+            }
+        }
+        if self.labels_changed {
+            ctx.request_layout();
+        }
+    }
+
+    fn shape(
+        font_ctx: &mut FontContext,
+        layout_ctx: &mut LayoutContext<BrushIndex>,
+        text: ArcStr,
+        layout: &mut Layout<BrushIndex>,
+    ) {
+        let mut job = TextLayoutJob::new(
+            text,
+            default_style_properties()
+                .into_iter()
+                .chain([StyleProperty::FontSize(theme::TEXT_SIZE_NORMAL)]),
+            std::mem::replace(layout, Layout::new()),
+        );
+        job.run(font_ctx, layout_ctx);
+        job.layout.break_all_lines(None);
+        job.layout.align(None, Alignment::Start);
+        *layout = job.layout;
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for VirtualKeyboard {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                self.pressed = self.key_at(local_pos);
+                if self.pressed.is_some() {
+                    ctx.capture_pointer();
+                    ctx.request_paint_only();
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if let Some(pressed) = self.pressed.take() {
+                    if self.key_at(local_pos) == Some(pressed) {
+                        self.press(ctx, pressed);
+                    }
+                    ctx.request_paint_only();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let rows = self
+            .layout
+            .layers
+            .get(self.layer)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let width = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            DEFAULT_WIDTH
+        };
+        let size = bc.constrain(Size::new(
+            width,
+            rows.len() as f64 * (KEY_HEIGHT + KEY_GAP) + KEY_GAP,
+        ));
+
+        // The widest row fills the keyboard, and the other rows are centered.
+        let row_units = |row: &[KeyCap]| row.iter().map(|key| key.width).sum::<f64>();
+        let max_units = rows.iter().map(|row| row_units(row)).fold(0.0, f64::max);
+        let unit = if max_units > 0.0 {
+            (size.width - KEY_GAP) / max_units
+        } else {
+            0.0
+        };
+        self.key_rects.clear();
+        for (row_idx, row) in rows.iter().enumerate() {
+            let y = KEY_GAP + row_idx as f64 * (KEY_HEIGHT + KEY_GAP);
+            let mut x = KEY_GAP + (size.width - KEY_GAP - row_units(row) * unit) / 2.0;
+            for key in row {
+                let width = key.width * unit;
+                let rect = Rect::new(x, y, x + width - KEY_GAP, y + KEY_HEIGHT);
+                self.key_rects.push(rect);
+                x += width;
+            }
+        }
+
+        if std::mem::take(&mut self.labels_changed) {
+            let labels: Vec<_> = self.keys().map(|key| self.label(key)).collect();
+            self.labels.resize_with(labels.len(), Layout::new);
+            let (font_ctx, layout_ctx) = ctx.text_contexts();
+            for (text, layout) in labels.into_iter().zip(&mut self.labels) {
+                Self::shape(font_ctx, layout_ctx, text, layout);
+            }
+        }
+
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        fill_color(scene, &ctx.size().to_rect(), theme::BACKGROUND_DARK);
+        for (idx, (key, (rect, label))) in self
+            .keys()
+            .zip(self.key_rects.iter().zip(&self.labels))
+            .enumerate()
+        {
+            let active = match key.action {
+                KeyAction::Shift => self.shift != ShiftState::Off,
+                _ => false,
+            };
+            let color: Color = if self.pressed == Some(idx) || active {
+                theme::PRIMARY_DARK
+            } else if matches!(key.action, KeyAction::Text(..)) {
+                theme::BACKGROUND_LIGHT
+            } else {
+                theme::BUTTON_LIGHT
+            };
+            let key_shape = rect.to_rounded_rect(KEY_RADIUS);
+            fill_color(scene, &key_shape, color);
+            if self.shift == ShiftState::Locked && active {
+                stroke(scene, &key_shape, theme::FOREGROUND_LIGHT, 2.0);
+            }
+
+            let label_size = Size::new(f64::from(label.width()), f64::from(label.height()));
+            let origin = rect.center() - label_size.to_vec2() / 2.0;
+            let transform = Affine::translate(Vec2::new(origin.x, origin.y));
+            render_text(scene, transform, label, &[theme::TEXT_COLOR.into()], true);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        node.set_description("On-screen keyboard");
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("VirtualKeyboard", id = ctx.widget_id().trace())
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Flex, TextArea};
+
+    /// Click the key of the keyboard `keyboard_id` whose label is `label`.
+    fn click_key(harness: &mut TestHarness, keyboard_id: WidgetId, label: &str) {
+        let keyboard = harness.get_widget(keyboard_id);
+        let origin = keyboard.ctx().window_origin().to_vec2();
+        let keyboard = keyboard.downcast::<VirtualKeyboard>().unwrap();
+        let idx = keyboard
+            .keys()
+            .position(|key| keyboard.label(key).as_ref() == label)
+            .unwrap();
+        let pos = keyboard.key_rects[idx].center() + origin;
+        harness.drag_from_to(pos, pos);
+    }
+
+    #[test]
+    fn type_into_focused_widget() {
+        let area_id = WidgetId::next();
+        let keyboard_id = WidgetId::next();
+        let widget = Flex::column()
+            .with_child_id(TextArea::new_editable(""), area_id)
+            .with_child_id(VirtualKeyboard::new(KeyboardLayout::qwerty()), keyboard_id);
+        let mut harness = TestHarness::create(widget);
+        harness.focus_on(Some(area_id));
+
+        click_key(&mut harness, keyboard_id, "⇧");
+        click_key(&mut harness, keyboard_id, "H");
+        // Shift only applies to one key.
+        click_key(&mut harness, keyboard_id, "i");
+        click_key(&mut harness, keyboard_id, "?123");
+        click_key(&mut harness, keyboard_id, "!");
+        click_key(&mut harness, keyboard_id, "⌫");
+        click_key(&mut harness, keyboard_id, "1");
+
+        let area = harness.get_widget(area_id);
+        let text: String = area
+            .downcast::<TextArea<true>>()
+            .unwrap()
+            .text()
+            .into_iter()
+            .collect();
+        assert_eq!(text, "Hi1");
+        assert_eq!(
+            harness.focused_widget().map(|widget| widget.id()),
+            Some(area_id)
+        );
+    }
+
+    #[test]
+    fn layout_for_locale() {
+        assert_eq!(
+            KeyboardLayout::for_locale("fr-CA"),
+            KeyboardLayout::azerty()
+        );
+        assert_eq!(
+            KeyboardLayout::for_locale("de_AT"),
+            KeyboardLayout::qwertz()
+        );
+        assert_eq!(KeyboardLayout::for_locale("ja"), KeyboardLayout::qwerty());
+    }
+}
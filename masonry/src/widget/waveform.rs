@@ -0,0 +1,504 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The waveform of audio samples, as in an audio editor.
+
+use std::ops::Range;
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::kurbo::{BezPath, Line};
+use vello::Scene;
+
+use crate::event::PointerButton;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{AudioFeed, WidgetMut};
+use crate::{
+    theme, AccessCtx, AccessEvent, ActionSource, BoxConstraints, EventCtx, LayoutCtx, PaintCtx,
+    PointerEvent, QueryCtx, RegisterCtx, Size, TextEvent, Update, UpdateCtx, Widget, WidgetId,
+};
+
+/// The number of samples summarized by each precomputed peak.
+const BLOCK_LEN: usize = 256;
+/// The fewest samples shown over the width of the waveform.
+const MIN_VISIBLE_SAMPLES: f64 = 16.0;
+/// The space between the highest peaks and the edges of the waveform.
+const VERTICAL_PADDING: f64 = 2.0;
+/// How much the view is zoomed by a step of the mouse wheel.
+const ZOOM_STEP: f64 = 1.25;
+/// The distance the view is panned by a line of horizontal wheel delta.
+const SCROLLING_SPEED: f64 = 10.0;
+/// How far the pointer must move for a press to become a pan instead of a seek.
+const DRAG_THRESHOLD: f64 = 3.0;
+/// The size of a waveform without constraints.
+const DEFAULT_SIZE: Size = Size::new(400.0, 80.0);
+
+/// The user clicked a [`Waveform`] at this time, in seconds.
+///
+/// This is the [`ActionSource::Action`] of [`Waveform`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WaveformSeek(pub f64);
+
+/// A press on the waveform, while the pointer is captured.
+struct Press {
+    /// The horizontal position of the pointer when it was pressed.
+    start_x: f64,
+    /// The visible range when the pointer was pressed.
+    view: Range<f64>,
+    /// Whether the pointer moved far enough to pan the view.
+    panning: bool,
+}
+
+/// The waveform of mono audio samples, with a playhead.
+///
+/// Each column of pixels shows the lowest and highest samples in the time it covers,
+/// so long recordings are drawn without aliasing, from peaks precomputed as samples are added.
+///
+/// Samples can be given directly, or sent from another thread through an [`AudioFeed`],
+/// which is read on every animation frame. The channels of a feed are mixed down.
+///
+/// The mouse wheel zooms in and out around the pointer, and dragging pans the view.
+/// Clicking submits [`WaveformSeek`] as a [typed action](crate::Action::typed) and moves
+/// the playhead there.
+pub struct Waveform {
+    sample_rate: f64,
+    samples: Vec<f32>,
+    /// The lowest and highest sample of each complete block of `BLOCK_LEN` samples.
+    peaks: Vec<(f32, f32)>,
+    feed: Option<AudioFeed>,
+    /// The range of time shown, in seconds, or `None` to show all the samples.
+    view: Option<Range<f64>>,
+    playhead: Option<f64>,
+    press: Option<Press>,
+}
+
+// --- MARK: BUILDERS ---
+impl Waveform {
+    /// Create an empty waveform of samples played at `sample_rate` samples per second.
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            samples: Vec::new(),
+            peaks: Vec::new(),
+            feed: None,
+            view: None,
+            playhead: None,
+            press: None,
+        }
+    }
+
+    /// Builder-style method to set the samples, between -1 and 1.
+    pub fn with_samples(mut self, samples: impl IntoIterator<Item = f32>) -> Self {
+        self.samples.extend(samples);
+        self.update_peaks();
+        self
+    }
+
+    /// Builder-style method to add the samples sent to `feed`.
+    pub fn with_feed(mut self, feed: AudioFeed) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    /// Builder-style method to set the position of the playhead, in seconds.
+    pub fn with_playhead(mut self, playhead: f64) -> Self {
+        self.playhead = Some(playhead);
+        self
+    }
+
+    /// Builder-style method to set the range of time shown, in seconds.
+    pub fn with_visible_range(mut self, range: Range<f64>) -> Self {
+        self.set_view(range);
+        self
+    }
+
+    /// The samples of the waveform.
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// The length of the samples, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 / self.sample_rate
+    }
+
+    /// The position of the playhead, in seconds.
+    pub fn playhead(&self) -> Option<f64> {
+        self.playhead
+    }
+
+    /// The range of time shown over the width of the waveform, in seconds.
+    pub fn visible_range(&self) -> Range<f64> {
+        self.view.clone().unwrap_or_else(|| {
+            let min_duration = MIN_VISIBLE_SAMPLES / self.sample_rate;
+            0.0..self.duration().max(min_duration)
+        })
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl Waveform {
+    /// Replace the samples, keeping the range of time shown.
+    pub fn set_samples(this: &mut WidgetMut<'_, Self>, samples: impl IntoIterator<Item = f32>) {
+        this.widget.samples.clear();
+        this.widget.peaks.clear();
+        Self::append_samples(this, samples);
+    }
+
+    /// Add samples after the current ones.
+    pub fn append_samples(this: &mut WidgetMut<'_, Self>, samples: impl IntoIterator<Item = f32>) {
+        this.widget.samples.extend(samples);
+        this.widget.update_peaks();
+        this.ctx.request_paint_only();
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Set the number of samples played per second.
+    pub fn set_sample_rate(this: &mut WidgetMut<'_, Self>, sample_rate: f64) {
+        this.widget.sample_rate = sample_rate;
+        this.ctx.request_paint_only();
+        this.ctx.request_accessibility_update();
+    }
+
+    /// Add the samples sent to another feed, or stop reading them.
+    pub fn set_feed(this: &mut WidgetMut<'_, Self>, feed: Option<AudioFeed>) {
+        this.widget.feed = feed;
+        this.ctx.request_anim_frame();
+    }
+
+    /// Set the position of the playhead, in seconds.
+    pub fn set_playhead(this: &mut WidgetMut<'_, Self>, playhead: Option<f64>) {
+        this.widget.playhead = playhead;
+        this.ctx.request_paint_only();
+    }
+
+    /// Set the range of time shown, in seconds, or show all the samples.
+    pub fn set_visible_range(this: &mut WidgetMut<'_, Self>, range: Option<Range<f64>>) {
+        match range {
+            Some(range) => this.widget.set_view(range),
+            None => this.widget.view = None,
+        }
+        this.ctx.request_paint_only();
+    }
+}
+
+// --- MARK: INTERNALS ---
+impl Waveform {
+    fn set_view(&mut self, range: Range<f64>) {
+        if range.end > range.start {
+            self.view = Some(range);
+        }
+    }
+
+    /// Summarize the blocks of samples completed since the last call.
+    fn update_peaks(&mut self) {
+        let complete = self.samples.len() / BLOCK_LEN;
+        let start = self.peaks.len().min(complete) * BLOCK_LEN;
+        self.peaks.truncate(complete);
+        for block in self.samples[start..complete * BLOCK_LEN].chunks_exact(BLOCK_LEN) {
+            self.peaks.push(Self::raw_peak(block));
+        }
+    }
+
+    fn raw_peak(samples: &[f32]) -> (f32, f32) {
+        samples
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), sample| {
+                (lo.min(*sample), hi.max(*sample))
+            })
+    }
+
+    /// The lowest and highest samples in `range`, using the precomputed peaks of the blocks
+    /// it covers.
+    fn peak(&self, range: Range<usize>) -> Option<(f32, f32)> {
+        let first_block = range.start.div_ceil(BLOCK_LEN);
+        let last_block = (range.end / BLOCK_LEN).max(first_block);
+        let (lo, hi) = if last_block > first_block {
+            [
+                Self::raw_peak(&self.samples[range.start..first_block * BLOCK_LEN]),
+                Self::raw_peak(&self.samples[last_block * BLOCK_LEN..range.end]),
+            ]
+            .into_iter()
+            .chain(self.peaks[first_block..last_block].iter().copied())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), peak| {
+                (lo.min(peak.0), hi.max(peak.1))
+            })
+        } else {
+            Self::raw_peak(&self.samples[range])
+        };
+        (lo <= hi).then_some((lo, hi))
+    }
+
+    fn x_to_time(&self, x: f64, width: f64) -> f64 {
+        let view = self.visible_range();
+        view.start + x / width * (view.end - view.start)
+    }
+
+    fn time_to_x(&self, time: f64, width: f64) -> f64 {
+        let view = self.visible_range();
+        (time - view.start) / (view.end - view.start) * width
+    }
+
+    /// The index of the sample played at the time shown at `x`.
+    fn sample_at(&self, x: f64, width: f64) -> usize {
+        let sample = (self.x_to_time(x, width) * self.sample_rate).floor();
+        sample.clamp(0.0, self.samples.len() as f64) as usize
+    }
+
+    /// Zoom in or out by `factor`, keeping the time at `x` under it.
+    fn zoom(&mut self, x: f64, width: f64, factor: f64) {
+        let time = self.x_to_time(x, width);
+        let view = self.visible_range();
+        let min_duration = MIN_VISIBLE_SAMPLES / self.sample_rate;
+        let duration = ((view.end - view.start) * factor).max(min_duration);
+        let start = time - x / width * duration;
+        self.view = Some(start..start + duration);
+    }
+
+    /// Add the samples sent to the feed, mixed down to mono.
+    fn read_feed(&mut self) -> bool {
+        let Some(feed) = &self.feed else {
+            return false;
+        };
+        let channels = feed.channels();
+        let samples = feed.take();
+        if samples.is_empty() {
+            return false;
+        }
+        self.samples.extend(
+            samples
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32),
+        );
+        self.update_peaks();
+        true
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for Waveform {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let width = ctx.size().width;
+        match event {
+            PointerEvent::PointerDown(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                ctx.capture_pointer();
+                self.press = Some(Press {
+                    start_x: local_pos.x,
+                    view: self.visible_range(),
+                    panning: false,
+                });
+            }
+            PointerEvent::PointerMove(state) if ctx.has_pointer_capture() => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if let Some(press) = &mut self.press {
+                    let dx = local_pos.x - press.start_x;
+                    press.panning |= dx.abs() > DRAG_THRESHOLD;
+                    if press.panning {
+                        let offset = dx / width * (press.view.end - press.view.start);
+                        self.view = Some(press.view.start - offset..press.view.end - offset);
+                        ctx.request_paint_only();
+                    }
+                }
+            }
+            PointerEvent::PointerUp(PointerButton::Primary, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                if let Some(press) = self.press.take() {
+                    if !press.panning {
+                        let time = self
+                            .x_to_time(local_pos.x, width)
+                            .clamp(0.0, self.duration());
+                        self.playhead = Some(time);
+                        ctx.submit_typed_action(WaveformSeek(time));
+                        ctx.request_paint_only();
+                    }
+                }
+            }
+            PointerEvent::MouseWheel(delta, state) => {
+                let local_pos = state.position - ctx.window_origin().to_vec2();
+                let delta = delta.to_pixels(SCROLLING_SPEED);
+                if delta.y != 0.0 {
+                    let factor = if delta.y > 0.0 {
+                        1.0 / ZOOM_STEP
+                    } else {
+                        ZOOM_STEP
+                    };
+                    self.zoom(local_pos.x, width, factor);
+                }
+                if delta.x != 0.0 {
+                    let view = self.visible_range();
+                    let offset = delta.x / width * (view.end - view.start);
+                    self.view = Some(view.start - offset..view.end - offset);
+                }
+                ctx.request_paint_only();
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_anim_frame(&mut self, ctx: &mut UpdateCtx, _interval: u64) {
+        if self.read_feed() {
+            ctx.request_paint_only();
+            ctx.request_accessibility_update();
+        }
+        if self.feed.is_some() {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, event: &Update) {
+        match event {
+            Update::WidgetAdded => {
+                ctx.request_anim_frame();
+            }
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let max = bc.max();
+        bc.constrain(Size::new(
+            if max.width.is_finite() {
+                max.width
+            } else {
+                DEFAULT_SIZE.width
+            },
+            if max.height.is_finite() {
+                max.height
+            } else {
+                DEFAULT_SIZE.height
+            },
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        fill_color(scene, &size.to_rect(), theme::BACKGROUND_DARK);
+        let middle = size.height / 2.0;
+        let amplitude = (middle - VERTICAL_PADDING).max(0.0);
+        let y = |sample: f32| middle - sample.clamp(-1.0, 1.0) as f64 * amplitude;
+        stroke(
+            scene,
+            &Line::new((0.0, middle), (size.width, middle)),
+            theme::BORDER_LIGHT,
+            1.0,
+        );
+
+        // The lowest and highest sample shown in each column of pixels.
+        let columns: Vec<_> = (0..size.width.ceil() as usize)
+            .filter_map(|column| {
+                let x = column as f64;
+                let start = self.sample_at(x, size.width);
+                let end = self.sample_at(x + 1.0, size.width).max(start + 1);
+                let end = end.min(self.samples.len());
+                let (lo, hi) = self.peak(start..end.max(start))?;
+                Some((x + 0.5, y(hi), y(lo)))
+            })
+            .collect();
+        if let Some((first, rest)) = columns.split_first() {
+            let mut path = BezPath::new();
+            path.move_to((first.0, first.1));
+            for (x, top, _) in rest {
+                path.line_to((*x, *top));
+            }
+            for (x, _, bottom) in columns.iter().rev() {
+                path.line_to((*x, *bottom));
+            }
+            path.close_path();
+            fill_color(scene, &path, theme::PRIMARY_LIGHT);
+            stroke(scene, &path, theme::PRIMARY_LIGHT, 1.0);
+        }
+
+        if let Some(playhead) = self.playhead {
+            let x = self.time_to_x(playhead, size.width);
+            if (0.0..=size.width).contains(&x) {
+                stroke(
+                    scene,
+                    &Line::new((x, 0.0), (x, size.height)),
+                    theme::TEXT_COLOR,
+                    1.0,
+                );
+            }
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Figure
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, node: &mut Node) {
+        node.set_description(format!("Waveform of {:.1} seconds", self.duration()));
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("Waveform", id = ctx.widget_id().trace())
+    }
+}
+
+impl ActionSource for Waveform {
+    type Action = WaveformSeek;
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use vello::kurbo::Vec2;
+
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn peaks_match_samples() {
+        let samples: Vec<f32> = (0..3000).map(|i| (i as f32 * 0.01).sin()).collect();
+        let waveform = Waveform::new(1000.0).with_samples(samples.iter().copied());
+        for range in [0..3000, 10..20, 100..900, 255..257, 256..512, 1000..2999] {
+            assert_eq!(
+                waveform.peak(range.clone()),
+                Some(Waveform::raw_peak(&samples[range.clone()])),
+                "{range:?}"
+            );
+        }
+        assert_eq!(waveform.peak(5..5), None);
+    }
+
+    #[test]
+    fn feed_seek_and_zoom() {
+        let feed = AudioFeed::new(2);
+        let widget = Waveform::new(100.0).with_feed(feed.clone());
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 80.0));
+
+        // The channels are mixed down.
+        feed.push(&[0.5, -0.5].repeat(200));
+        harness.animate_ms(16);
+        let waveform = harness.root_widget().downcast::<Waveform>().unwrap();
+        assert_eq!(waveform.samples().len(), 200);
+        assert_eq!(waveform.duration(), 2.0);
+        assert_eq!(waveform.visible_range(), 0.0..2.0);
+
+        harness.drag_from_to((100.0, 40.0), (100.0, 40.0));
+        let (action, _) = harness.pop_action().unwrap();
+        assert_eq!(
+            action.downcast::<WaveformSeek>().ok(),
+            Some(WaveformSeek(0.5))
+        );
+
+        harness.scroll_at((200.0, 40.0), Vec2::new(0.0, 1.0));
+        let waveform = harness.root_widget().downcast::<Waveform>().unwrap();
+        let range = waveform.visible_range();
+        assert!((range.start - 0.2).abs() < 1e-9, "{range:?}");
+        assert!((range.end - 1.8).abs() < 1e-9, "{range:?}");
+        assert_eq!(waveform.playhead(), Some(0.5));
+    }
+}
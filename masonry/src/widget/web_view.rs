@@ -0,0 +1,132 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget showing web content in a native webview.
+
+use accesskit::{Node, Role};
+use smallvec::SmallVec;
+use tracing::{trace_span, Span};
+use vello::Scene;
+
+use crate::widget::WidgetMut;
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, PointerEvent, QueryCtx,
+    RegisterCtx, Size, TextEvent, Update, UpdateCtx, Widget, WidgetId,
+};
+
+/// The size of a web view which isn't constrained by its parent, as in HTML.
+const DEFAULT_SIZE: Size = Size::new(300., 150.);
+
+/// A request sent by a [`WebView`] to the native webview it owns.
+///
+/// These are handled by the event loop runner, which creates the native webviews as children
+/// of its window. Apps embedding Masonry receive them as [`RenderRootSignal::WebView`] signals.
+///
+/// [`RenderRootSignal::WebView`]: crate::RenderRootSignal::WebView
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WebViewRequest {
+    /// Create the native webview, loading `url`.
+    Create { url: String },
+    /// Navigate to `url`.
+    LoadUrl(String),
+    /// Run `script` in the current page.
+    EvaluateScript(String),
+}
+
+/// A widget that shows web content, in a native webview positioned over its layout rect.
+///
+/// The webview is created when the widget is added, and destroyed when it's removed.
+/// It is hidden while the widget is stashed.
+///
+/// Messages posted by the page with `window.ipc.postMessage(string)` are submitted as
+/// [`Action::WebViewMessage`](crate::Action::WebViewMessage), and an
+/// [`Action::WebViewLoaded`](crate::Action::WebViewLoaded) is submitted each time a page
+/// finishes loading.
+///
+/// As the webview is a native child window, it's always drawn above the other widgets, and
+/// it receives input directly from the platform. On Linux, wry needs the GTK event loop,
+/// which Masonry doesn't run, so webviews are currently only shown on Windows and macOS.
+pub struct WebView {
+    url: String,
+}
+
+// --- MARK: BUILDERS ---
+impl WebView {
+    /// Create a widget showing the page at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+// --- MARK: WIDGETMUT ---
+impl WebView {
+    /// Navigate to `url`.
+    pub fn load_url(this: &mut WidgetMut<'_, Self>, url: impl Into<String>) {
+        let url = url.into();
+        this.widget.url.clone_from(&url);
+        this.ctx.request_web_view(WebViewRequest::LoadUrl(url));
+    }
+
+    /// Run `script` in the current page.
+    pub fn evaluate_script(this: &mut WidgetMut<'_, Self>, script: impl Into<String>) {
+        this.ctx
+            .request_web_view(WebViewRequest::EvaluateScript(script.into()));
+    }
+}
+
+// --- MARK: IMPL WIDGET ---
+impl Widget for WebView {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, event: &Update) {
+        match event {
+            Update::WidgetAdded => {
+                ctx.request_web_view(WebViewRequest::Create {
+                    url: self.url.clone(),
+                });
+            }
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            DEFAULT_SIZE.width
+        };
+        let height = if bc.is_height_bounded() {
+            bc.max().height
+        } else {
+            DEFAULT_SIZE.height
+        };
+        bc.constrain(Size::new(width, height))
+    }
+
+    // The content is drawn by the native webview.
+    fn paint(&mut self, _ctx: &mut PaintCtx, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::WebView
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx, _node: &mut Node) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
+        trace_span!("WebView", id = ctx.widget_id().trace())
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.url.clone())
+    }
+}
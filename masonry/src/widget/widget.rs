@@ -16,10 +16,12 @@ use vello::Scene;
 
 use crate::contexts::ComposeCtx;
 use crate::event::{AccessEvent, PointerEvent, TextEvent};
+use crate::text::TextLayoutJob;
+use crate::widget::hit_index::ChildHitIndex;
 use crate::widget::WidgetRef;
 use crate::{
-    AccessCtx, AsAny, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point, QueryCtx, RegisterCtx,
-    Size, Update, UpdateCtx,
+    AccessCtx, AsAny, BoxConstraints, EventCtx, LayoutCtx, PaintCtx, Point, QueryCtx, Rect,
+    RegisterCtx, Size, Update, UpdateCtx,
 };
 
 /// A unique identifier for a single [`Widget`].
@@ -150,6 +152,23 @@ pub trait Widget: AsAny {
     /// The layout strategy is strongly inspired by Flutter.
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size;
 
+    /// Hand out text shaping work which can be done ahead of [`layout`](Self::layout).
+    ///
+    /// When the `parallel_text` feature is enabled, this is called on widgets which may be
+    /// laid out in the upcoming layout pass. The returned jobs are shaped on a thread pool,
+    /// then given back through [`finish_text_layout_job`](Self::finish_text_layout_job)
+    /// before `layout` is called.
+    ///
+    /// Widgets which don't own text, or whose text is already shaped, should return `None`.
+    fn take_text_layout_job(&mut self) -> Option<TextLayoutJob> {
+        None
+    }
+
+    /// Receive text shaping work previously handed out by [`take_text_layout_job`](Self::take_text_layout_job).
+    ///
+    /// This is always called before the next call to [`layout`](Self::layout).
+    fn finish_text_layout_job(&mut self, job: TextLayoutJob) {}
+
     fn compose(&mut self, ctx: &mut ComposeCtx) {}
 
     /// Paint the widget appearance.
@@ -197,6 +216,33 @@ pub trait Widget: AsAny {
         false
     }
 
+    /// Whether this widget is a focus group.
+    ///
+    /// Tab moves the focus across a focus group in one step, to its first focusable descendant.
+    /// Inside it, the arrow keys move the focus between its focusable descendants
+    /// (spatially if its role is [`Role::Grid`]), Home and End move to the first and last,
+    /// and typing a character moves to the next one whose name starts with that character.
+    /// The focused descendant is reported to assistive technologies as the group's
+    /// active descendant.
+    ///
+    /// By default, this is true for widgets whose role groups several controls,
+    /// such as lists, menus, tab strips and grids.
+    /// This can be overridden with [`MutateCtx::set_focus_group`](crate::MutateCtx::set_focus_group).
+    fn is_focus_group(&self) -> bool {
+        matches!(
+            self.accessibility_role(),
+            Role::RadioGroup
+                | Role::Toolbar
+                | Role::TabList
+                | Role::Menu
+                | Role::MenuBar
+                | Role::List
+                | Role::ListBox
+                | Role::Tree
+                | Role::Grid
+        )
+    }
+
     /// Whether this widget gets IME events. False by default.
     ///
     /// If true, focusing this widget will start an IME session.
@@ -214,12 +260,12 @@ pub trait Widget: AsAny {
     /// As methods recurse through the widget tree, trace spans are added for each child
     /// widget visited, and popped when control flow goes back to the parent. This method
     /// returns a static span (that you can use to filter traces and logs).
-    // TODO: Make include the widget's id?
     fn make_trace_span(&self, ctx: &QueryCtx<'_>) -> Span {
         trace_span!(
             "Widget",
             r#type = self.short_type_name(),
-            id = ctx.widget_id().trace()
+            id = ctx.widget_id().trace(),
+            tag = ctx.debug_tag()
         )
     }
 
@@ -246,12 +292,14 @@ pub trait Widget: AsAny {
     // --- Auto-generated implementations ---
 
     /// Return which child, if any, has the given `pos` in its layout rect. In case of overlapping
-    /// children, the last child as determined by [`Widget::children_ids`] is chosen. No child is
+    /// children, the one with the highest [z-index](crate::WidgetPod::set_z_index) is chosen,
+    /// then the last child as determined by [`Widget::children_ids`]. No child is
     /// returned if `pos` is outside the widget's clip path.
     ///
     /// The child returned is a direct child, not e.g. a grand-child.
     ///
-    /// Has a default implementation that can be overridden to search children more efficiently.
+    /// Has a default implementation, which only checks the children whose layout rect is
+    /// near `pos`, that can be overridden to search children more efficiently.
     /// Custom implementations must uphold the conditions outlined above.
     ///
     /// **pos** - the position in global coordinates (e.g. `(0,0)` is the top-left corner of the
@@ -308,30 +356,50 @@ pub(crate) fn get_child_at_pos<'c>(
     ctx: QueryCtx<'c>,
     pos: Point,
 ) -> Option<WidgetRef<'c, dyn Widget>> {
+    children_at_pos(widget, ctx, pos).next()
+}
+
+/// The children which have the given `pos` in their layout rect, from the top one to the
+/// bottom one. There are none if `pos` is outside the widget's clip path.
+pub(crate) fn children_at_pos<'c>(
+    widget: &(impl Widget + ?Sized),
+    ctx: QueryCtx<'c>,
+    pos: Point,
+) -> impl Iterator<Item = WidgetRef<'c, dyn Widget>> {
     let relative_pos = pos - ctx.window_origin().to_vec2();
-    if !ctx
+    let in_clip_path = ctx
         .clip_path()
-        .map_or(true, |clip| clip.contains(relative_pos))
-    {
-        return None;
-    }
-
-    // Assumes `Self::children_ids` is in increasing "z-order", picking the last child in case
-    // of overlapping children.
-    for child_id in widget.children_ids().iter().rev() {
-        let child = ctx.get(*child_id);
-
-        // The position must be inside the child's layout and inside the child's clip path (if
-        // any).
-        if !child.ctx().is_stashed()
-            && child.ctx().accepts_pointer_interaction()
-            && child.ctx().window_layout_rect().contains(pos)
-        {
-            return Some(child);
-        }
-    }
-
-    None
+        .map_or(true, |clip| clip.contains(relative_pos));
+    let mut children: SmallVec<[(WidgetId, usize); 16]> = if in_clip_path {
+        // Only the children around `pos` are checked, so that pointer events stay cheap
+        // in widgets with many children.
+        let index = ctx.widget_state.child_hit_index.get_or_init(|| {
+            ChildHitIndex::new(widget.children_ids().into_iter().map(|child_id| {
+                let child = ctx.get(child_id).ctx().widget_state;
+                let origin = child.origin + child.translation;
+                (child_id, Rect::from_origin_size(origin, child.size))
+            }))
+        });
+        index.children_at(relative_pos).collect()
+    } else {
+        SmallVec::new()
+    };
+    // Children are stacked by z-index, then in the order of `Self::children_ids`, picking the
+    // last child in case of overlapping children.
+    children.sort_by_key(|&(child_id, order)| (ctx.get(child_id).ctx().z_index(), order));
+
+    children
+        .into_iter()
+        .rev()
+        .map(move |(child_id, _)| ctx.get(child_id))
+        .filter(move |child| {
+            // The position must be inside the child's layout and inside the child's clip path (if
+            // any).
+            !child.ctx().is_stashed()
+                && !child.ctx().is_hidden()
+                && child.ctx().accepts_pointer_interaction()
+                && child.ctx().window_layout_rect().contains(pos)
+        })
 }
 
 /// Marker trait for Widgets whose parents can get a raw mutable reference to them.
@@ -440,6 +508,14 @@ impl Widget for Box<dyn Widget> {
         self.deref_mut().layout(ctx, bc)
     }
 
+    fn take_text_layout_job(&mut self) -> Option<TextLayoutJob> {
+        self.deref_mut().take_text_layout_job()
+    }
+
+    fn finish_text_layout_job(&mut self, job: TextLayoutJob) {
+        self.deref_mut().finish_text_layout_job(job);
+    }
+
     fn compose(&mut self, ctx: &mut ComposeCtx) {
         self.deref_mut().compose(ctx);
     }
@@ -476,6 +552,10 @@ impl Widget for Box<dyn Widget> {
         self.deref().accepts_focus()
     }
 
+    fn is_focus_group(&self) -> bool {
+        self.deref().is_focus_group()
+    }
+
     fn accepts_text_input(&self) -> bool {
         self.deref().accepts_text_input()
     }
@@ -0,0 +1,57 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use vello::peniko::{Brush, Color};
+
+use crate::event::PointerButton;
+use crate::widget::{Align, Clickable, Padding, SizedBox};
+use crate::{EventCtx, Widget};
+
+/// Methods wrapping a widget in the common container widgets.
+///
+/// This mirrors the builder style of Xilem's views for code using widgets directly:
+///
+/// ```
+/// use masonry::widget::{Label, WidgetExt};
+/// use masonry::Color;
+///
+/// let widget = Label::new("Click me")
+///     .padded(8.)
+///     .bordered(Color::WHITE, 1.)
+///     .on_click(|ctx, _button| ctx.request_paint_only());
+/// ```
+///
+/// This is implemented for all widgets.
+pub trait WidgetExt: Widget + Sized {
+    /// Wrap this widget in a [`SizedBox`] adding `padding` around it.
+    fn padded(self, padding: impl Into<Padding>) -> SizedBox {
+        SizedBox::new(self).padding(padding)
+    }
+
+    /// Wrap this widget in a [`SizedBox`] painting a border around it.
+    fn bordered(self, color: impl Into<Color>, width: impl Into<f64>) -> SizedBox {
+        SizedBox::new(self).border(color, width)
+    }
+
+    /// Wrap this widget in a [`SizedBox`] painting `brush` behind it.
+    fn with_background(self, brush: impl Into<Brush>) -> SizedBox {
+        SizedBox::new(self).background(brush)
+    }
+
+    /// Wrap this widget in a [`SizedBox`] of the given size.
+    fn fixed_size(self, width: f64, height: f64) -> SizedBox {
+        SizedBox::new(self).width(width).height(height)
+    }
+
+    /// Wrap this widget in an [`Align`] centering it.
+    fn centered(self) -> Align {
+        Align::centered(self)
+    }
+
+    /// Wrap this widget in a [`Clickable`] calling `on_click` when it's clicked.
+    fn on_click(self, on_click: impl FnMut(&mut EventCtx, PointerButton) + 'static) -> Clickable {
+        Clickable::new(self, on_click)
+    }
+}
+
+impl<W: Widget> WidgetExt for W {}
@@ -1,6 +1,9 @@
 // Copyright 2018 the Xilem Authors and the Druid Authors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::Arc;
+
+use crate::widget::AccessOverrides;
 use crate::{Widget, WidgetId};
 
 // TODO - rewrite links in doc
@@ -25,10 +28,72 @@ pub struct WidgetPod<W> {
 // Implementing that requires solving non-trivial design questions.
 
 enum WidgetPodInner<W> {
-    Created(W),
+    Created(W, PodSettings),
     Inserted,
 }
 
+/// The properties of a widget which can be set before it is added to the tree.
+#[derive(Default)]
+pub(crate) struct PodSettings {
+    pub(crate) access_overrides: AccessOverrides,
+    pub(crate) debug_tag: Option<Arc<str>>,
+    pub(crate) hit_test: HitTest,
+    pub(crate) z_index: i32,
+    pub(crate) visibility: Visibility,
+}
+
+/// Whether the pointer hits a widget, or passes through it to the widgets below.
+///
+/// This is set with [`WidgetPod::set_hit_test`] or [`MutateCtx::set_hit_test`], and is
+/// inherited by the descendants of the widget which don't set it themselves.
+///
+/// [`MutateCtx::set_hit_test`]: crate::MutateCtx::set_hit_test
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HitTest {
+    /// Be hit as the parent is, which is [`Opaque`](Self::Opaque) for the root widget.
+    #[default]
+    Inherit,
+    /// Let pointer events pass through, to the widgets painted below, e.g. for a watermark
+    /// or a drop shadow on top of other widgets.
+    ///
+    /// Descendants setting [`Opaque`](Self::Opaque) are still hit.
+    Transparent,
+    /// Be hit where the widget is, which stops pointer events from reaching the widgets
+    /// below, even inside a transparent parent.
+    Opaque,
+}
+
+impl HitTest {
+    /// Whether a widget with this mode is transparent, given whether its parent is.
+    pub(crate) fn is_transparent(self, parent_is_transparent: bool) -> bool {
+        match self {
+            Self::Inherit => parent_is_transparent,
+            Self::Transparent => true,
+            Self::Opaque => false,
+        }
+    }
+}
+
+/// Whether a widget is shown.
+///
+/// Unlike removing a widget, hiding it keeps its state, e.g. the text typed in a text box.
+/// This is set with [`WidgetPod::set_visibility`] or [`MutateCtx::set_visibility`], and
+/// applies to the descendants of the widget.
+///
+/// [`MutateCtx::set_visibility`]: crate::MutateCtx::set_visibility
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Visibility {
+    /// Be laid out, painted and get events.
+    #[default]
+    Visible,
+    /// Take the space of the widget in the layout, but don't paint it, and don't let it get
+    /// pointer events, text focus or be listed in the accessibility tree.
+    Hidden,
+    /// Like [`Hidden`](Self::Hidden), but also skip the layout of the widget, which takes
+    /// no space.
+    Collapsed,
+}
+
 impl<W: Widget> WidgetPod<W> {
     /// Create a new widget pod.
     ///
@@ -43,21 +108,129 @@ impl<W: Widget> WidgetPod<W> {
     pub fn new_with_id(inner: W, id: WidgetId) -> WidgetPod<W> {
         WidgetPod {
             id,
-            inner: WidgetPodInner::Created(inner),
+            inner: WidgetPodInner::Created(inner, PodSettings::default()),
         }
     }
 
     pub(crate) fn incomplete(&self) -> bool {
-        matches!(self.inner, WidgetPodInner::Created(_))
+        matches!(self.inner, WidgetPodInner::Created(..))
     }
 
-    pub(crate) fn take_inner(&mut self) -> Option<W> {
+    pub(crate) fn take_inner(&mut self) -> Option<(W, PodSettings)> {
         match std::mem::replace(&mut self.inner, WidgetPodInner::Inserted) {
-            WidgetPodInner::Created(widget) => Some(widget),
+            WidgetPodInner::Created(widget, settings) => Some((widget, settings)),
+            WidgetPodInner::Inserted => None,
+        }
+    }
+
+    /// The accessibility properties which will override the widget's own when it is added to the tree.
+    ///
+    /// Returns `None` if the widget has already been added to the tree, in which case
+    /// [`MutateCtx::access_overrides_mut`](crate::MutateCtx::access_overrides_mut) should be used instead.
+    pub fn access_overrides_mut(&mut self) -> Option<&mut AccessOverrides> {
+        match &mut self.inner {
+            WidgetPodInner::Created(_, settings) => Some(&mut settings.access_overrides),
             WidgetPodInner::Inserted => None,
         }
     }
 
+    /// Give the widget a string tag, which identifies it in trace spans, debug dumps and tests.
+    ///
+    /// Unlike [`WidgetId`]s, tags are chosen by the developer, so they are the same from one run to
+    /// the next. They don't need to be unique. See [`TestHarness::get_widget_by_tag`].
+    ///
+    /// Returns `false` if the widget has already been added to the tree, in which case
+    /// [`MutateCtx::set_debug_tag`](crate::MutateCtx::set_debug_tag) should be used instead.
+    ///
+    /// [`TestHarness::get_widget_by_tag`]: crate::testing::TestHarness::get_widget_by_tag
+    pub fn set_debug_tag(&mut self, tag: impl Into<Arc<str>>) -> bool {
+        match &mut self.inner {
+            WidgetPodInner::Created(_, settings) => {
+                settings.debug_tag = Some(tag.into());
+                true
+            }
+            WidgetPodInner::Inserted => false,
+        }
+    }
+
+    /// Builder-style method to give the widget a string tag.
+    ///
+    /// See [`set_debug_tag`](Self::set_debug_tag).
+    pub fn with_debug_tag(mut self, tag: impl Into<Arc<str>>) -> Self {
+        self.set_debug_tag(tag);
+        self
+    }
+
+    /// Set whether the pointer hits the widget, or passes through it to the widgets below.
+    ///
+    /// Returns `false` if the widget has already been added to the tree, in which case
+    /// [`MutateCtx::set_hit_test`](crate::MutateCtx::set_hit_test) should be used instead.
+    pub fn set_hit_test(&mut self, mode: HitTest) -> bool {
+        match &mut self.inner {
+            WidgetPodInner::Created(_, settings) => {
+                settings.hit_test = mode;
+                true
+            }
+            WidgetPodInner::Inserted => false,
+        }
+    }
+
+    /// Builder-style method to set whether the pointer hits the widget.
+    ///
+    /// See [`set_hit_test`](Self::set_hit_test).
+    pub fn with_hit_test(mut self, mode: HitTest) -> Self {
+        self.set_hit_test(mode);
+        self
+    }
+
+    /// Set where the widget is stacked among its siblings.
+    ///
+    /// Children with a higher z-index are painted above, and hit before, children with a
+    /// lower one, regardless of their order in their parent. Children with the same z-index
+    /// are stacked in the order of [`Widget::children_ids`]. The default z-index is 0.
+    ///
+    /// Returns `false` if the widget has already been added to the tree, in which case
+    /// [`MutateCtx::set_z_index`](crate::MutateCtx::set_z_index) should be used instead.
+    pub fn set_z_index(&mut self, z: i32) -> bool {
+        match &mut self.inner {
+            WidgetPodInner::Created(_, settings) => {
+                settings.z_index = z;
+                true
+            }
+            WidgetPodInner::Inserted => false,
+        }
+    }
+
+    /// Builder-style method to set where the widget is stacked among its siblings.
+    ///
+    /// See [`set_z_index`](Self::set_z_index).
+    pub fn with_z_index(mut self, z: i32) -> Self {
+        self.set_z_index(z);
+        self
+    }
+
+    /// Set whether the widget is shown.
+    ///
+    /// Returns `false` if the widget has already been added to the tree, in which case
+    /// [`MutateCtx::set_visibility`](crate::MutateCtx::set_visibility) should be used instead.
+    pub fn set_visibility(&mut self, visibility: Visibility) -> bool {
+        match &mut self.inner {
+            WidgetPodInner::Created(_, settings) => {
+                settings.visibility = visibility;
+                true
+            }
+            WidgetPodInner::Inserted => false,
+        }
+    }
+
+    /// Builder-style method to set whether the widget is shown.
+    ///
+    /// See [`set_visibility`](Self::set_visibility).
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.set_visibility(visibility);
+        self
+    }
+
     /// Get the identity of the widget.
     pub fn id(&self) -> WidgetId {
         self.id
@@ -71,7 +244,10 @@ impl<W: Widget + 'static> WidgetPod<W> {
     /// into a dynamically boxed widget.
     pub fn boxed(self) -> WidgetPod<Box<dyn Widget>> {
         match self.inner {
-            WidgetPodInner::Created(inner) => WidgetPod::new_with_id(Box::new(inner), self.id),
+            WidgetPodInner::Created(inner, settings) => WidgetPod {
+                id: self.id,
+                inner: WidgetPodInner::Created(Box::new(inner), settings),
+            },
             WidgetPodInner::Inserted => {
                 panic!("Cannot box a widget after it has been inserted into the widget graph")
             }
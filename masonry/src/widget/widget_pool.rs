@@ -0,0 +1,69 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::TypeId;
+use std::collections::HashMap;
+
+use crate::{Widget, WidgetId};
+
+/// The maximum number of recycled widgets kept for each widget type.
+const MAX_POOLED_PER_TYPE: usize = 64;
+
+/// Widgets and ids released by [`MutateCtx::recycle_child`](crate::MutateCtx::recycle_child),
+/// kept around so that containers with a lot of churn can reuse them.
+#[derive(Default)]
+pub(crate) struct WidgetPool {
+    /// Removed leaf widgets, keyed by their concrete type.
+    widgets: HashMap<TypeId, Vec<Box<dyn Widget>>>,
+    /// Ids of widgets removed since the last redraw.
+    ///
+    /// These can't be reused yet: the accessibility tree and the passes tracking
+    /// hovered and focused widgets may still refer to them.
+    released_ids: Vec<WidgetId>,
+    /// Ids which are no longer referenced anywhere, and can be given to new widgets.
+    free_ids: Vec<WidgetId>,
+}
+
+impl WidgetPool {
+    pub(crate) fn recycle_widget(&mut self, mut widget: Box<dyn Widget>) {
+        // Children stored as `WidgetPod<Box<dyn Widget>>` are boxed twice in the arena.
+        while (*widget).as_dyn_any().is::<Box<dyn Widget>>() {
+            widget = *widget
+                .into_dyn_any()
+                .downcast::<Box<dyn Widget>>()
+                .expect("type was checked above");
+        }
+        let type_id = (*widget).as_dyn_any().type_id();
+        let widgets = self.widgets.entry(type_id).or_default();
+        if widgets.len() < MAX_POOLED_PER_TYPE {
+            widgets.push(widget);
+        }
+    }
+
+    pub(crate) fn take_widget<W: Widget>(&mut self) -> Option<W> {
+        let widget = self.widgets.get_mut(&TypeId::of::<W>())?.pop()?;
+        let widget = widget
+            .into_dyn_any()
+            .downcast::<W>()
+            .expect("widget pool entry has the wrong type");
+        Some(*widget)
+    }
+
+    pub(crate) fn release_id(&mut self, id: WidgetId) {
+        self.released_ids.push(id);
+    }
+
+    /// Make the ids released since the last call available for reuse, and return them.
+    ///
+    /// This should be called once the rewrite passes and the accessibility pass
+    /// have seen the removal of the widgets owning these ids.
+    pub(crate) fn free_released_ids(&mut self) -> &[WidgetId] {
+        let start = self.free_ids.len();
+        self.free_ids.append(&mut self.released_ids);
+        &self.free_ids[start..]
+    }
+
+    pub(crate) fn next_id(&mut self) -> WidgetId {
+        self.free_ids.pop().unwrap_or_else(WidgetId::next)
+    }
+}
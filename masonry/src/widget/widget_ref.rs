@@ -6,6 +6,8 @@ use std::ops::Deref;
 use smallvec::SmallVec;
 use vello::kurbo::Point;
 
+use crate::widget::widget::children_at_pos;
+use crate::widget::HitTest;
 use crate::{QueryCtx, Widget, WidgetId};
 
 /// A rich reference to a [`Widget`].
@@ -42,10 +44,15 @@ impl<W: Widget + ?Sized> Copy for WidgetRef<'_, W> {}
 impl<W: Widget + ?Sized> std::fmt::Debug for WidgetRef<'_, W> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let widget_name = self.widget.short_type_name();
+        let widget_name = if let Some(tag) = self.ctx.debug_tag() {
+            format!("{widget_name}#{tag}").into()
+        } else {
+            std::borrow::Cow::Borrowed(widget_name)
+        };
         let display_name = if let Some(debug_text) = self.widget.get_debug_text() {
             format!("{widget_name}<{debug_text}>").into()
         } else {
-            std::borrow::Cow::Borrowed(widget_name)
+            widget_name
         };
 
         let children = self.children();
@@ -158,40 +165,130 @@ impl<'w> WidgetRef<'w, dyn Widget> {
         }
     }
 
+    /// Recursively find the first widget with the given [debug tag](crate::WidgetPod::set_debug_tag).
+    ///
+    /// The widgets are visited depth-first, starting with `self`.
+    pub fn find_widget_by_tag(&self, tag: &str) -> Option<WidgetRef<'w, dyn Widget>> {
+        self.find_widget(|widget| widget.ctx.debug_tag() == Some(tag))
+    }
+
+    /// Recursively find the first widget for which `predicate` returns true.
+    ///
+    /// The widgets are visited depth-first, starting with `self`.
+    pub fn find_widget(
+        &self,
+        mut predicate: impl FnMut(&WidgetRef<'w, dyn Widget>) -> bool,
+    ) -> Option<WidgetRef<'w, dyn Widget>> {
+        self.find_widget_inner(&mut predicate)
+    }
+
+    fn find_widget_inner(
+        &self,
+        predicate: &mut dyn FnMut(&WidgetRef<'w, dyn Widget>) -> bool,
+    ) -> Option<WidgetRef<'w, dyn Widget>> {
+        if predicate(self) {
+            Some(*self)
+        } else {
+            self.children()
+                .into_iter()
+                .find_map(|child| child.find_widget_inner(&mut *predicate))
+        }
+    }
+
+    /// Recursively find all the widgets for which `predicate` returns true.
+    ///
+    /// The widgets are returned in depth-first order, starting with `self`.
+    pub fn find_all_widgets(
+        &self,
+        mut predicate: impl FnMut(&WidgetRef<'w, dyn Widget>) -> bool,
+    ) -> Vec<WidgetRef<'w, dyn Widget>> {
+        let mut found = Vec::new();
+        self.visit(&mut |widget| {
+            if predicate(&widget) {
+                found.push(widget);
+            }
+        });
+        found
+    }
+
+    /// Recursively find all the widgets of type `W`.
+    ///
+    /// The widgets are returned in depth-first order, starting with `self`.
+    pub fn find_all<W: Widget>(&self) -> Vec<WidgetRef<'w, W>> {
+        let mut found = Vec::new();
+        self.visit(&mut |widget| {
+            if let Some(widget) = widget.downcast::<W>() {
+                found.push(widget);
+            }
+        });
+        found
+    }
+
+    /// Call `f` with `self` and each of its descendants, depth-first.
+    fn visit(&self, f: &mut dyn FnMut(WidgetRef<'w, dyn Widget>)) {
+        f(*self);
+        for child in self.children() {
+            child.visit(f);
+        }
+    }
+
     /// Recursively find the innermost widget at the given position, using
     /// [`Widget::get_child_at_pos`] to descend the widget tree. If `self` does not contain the
     /// given position in its layout rect or clip path, this returns `None`.
     ///
+    /// Widgets which are [transparent](HitTest::Transparent) are skipped: when the position
+    /// is only in transparent widgets of a subtree, the widgets below that subtree are
+    /// searched instead. `self` is assumed to be opaque if it doesn't set a [`HitTest`].
+    ///
     /// **pos** - the position in global coordinates (e.g. `(0,0)` is the top-left corner of the
     /// window).
     pub fn find_widget_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
-        let mut innermost_widget = *self;
-
         if !self.ctx.window_layout_rect().contains(pos) {
             return None;
         }
 
-        // TODO: add debug assertion to check whether the child returned by
-        // `Widget::get_child_at_pos` upholds the conditions of that method. See
-        // https://github.com/linebender/xilem/pull/565#discussion_r1756536870
-        while let Some(child) = innermost_widget
-            .widget
-            .get_child_at_pos(innermost_widget.ctx, pos)
-        {
-            innermost_widget = child;
-        }
+        find_hit_widget(*self, pos, false)
+    }
+}
 
-        Some(innermost_widget)
+/// The innermost widget of the subtree of `widget` which is hit at `pos`, if any.
+///
+/// `parent_is_transparent` is whether the pointer passes through the parent of `widget`.
+fn find_hit_widget<'w>(
+    widget: WidgetRef<'w, dyn Widget>,
+    pos: Point,
+    parent_is_transparent: bool,
+) -> Option<WidgetRef<'w, dyn Widget>> {
+    let is_transparent = widget.ctx.hit_test().is_transparent(parent_is_transparent);
+
+    // TODO: add debug assertion to check whether the child returned by
+    // `Widget::get_child_at_pos` upholds the conditions of that method. See
+    // https://github.com/linebender/xilem/pull/565#discussion_r1756536870
+    let Some(top_child) = widget.widget.get_child_at_pos(widget.ctx, pos) else {
+        return (!is_transparent).then_some(widget);
+    };
+    if let Some(hit) = find_hit_widget(top_child, pos, is_transparent) {
+        return Some(hit);
     }
+
+    // The pointer passes through the top child, so it may hit the children below it.
+    let top_child_id = top_child.ctx.widget_id();
+    children_at_pos(widget.widget, widget.ctx, pos)
+        .skip_while(|child| child.ctx.widget_id() != top_child_id)
+        .skip(1)
+        .find_map(|child| find_hit_widget(child, pos, is_transparent))
+        .or_else(|| (!is_transparent).then_some(widget))
 }
 
 // --- MARK: TESTS ---
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
+    use vello::kurbo::Point;
 
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
-    use crate::widget::{Button, Label};
+    use crate::widget::{Button, Flex, Grid, GridParams, HitTest, Label, SizedBox, WidgetPod};
+    use crate::WidgetId;
 
     #[test]
     fn downcast_ref_in_harness() {
@@ -203,4 +300,130 @@ mod tests {
         assert_matches!(harness.get_widget(label_id).downcast::<Label>(), Some(_));
         assert_matches!(harness.get_widget(label_id).downcast::<Button>(), None);
     }
+
+    #[test]
+    fn find_widgets_in_harness() {
+        let [button_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(Label::new("First"))
+            .with_child(Flex::row().with_child(Label::new("Second")))
+            .with_child_id(Button::new("Click"), button_id);
+
+        let harness = TestHarness::create(widget);
+        let root = harness.root_widget();
+
+        // The button's label is also found
+        let labels = root.find_all::<Label>();
+        let texts: Vec<&str> = labels.iter().map(|label| &**label.text()).collect();
+        assert_eq!(texts, ["First", "Second", "Click"]);
+
+        let second = root
+            .find_widget(|widget| {
+                widget
+                    .downcast::<Label>()
+                    .is_some_and(|label| &**label.text() == "Second")
+            })
+            .unwrap();
+        assert_eq!(second.id(), labels[1].id());
+
+        let buttons = root.find_all_widgets(|widget| widget.id() == button_id);
+        assert_eq!(buttons.len(), 1);
+        assert_matches!(buttons[0].downcast::<Button>(), Some(_));
+        assert_matches!(root.find_widget(|_| false), None);
+    }
+
+    #[test]
+    fn hit_test_transparency() {
+        let [button_id, overlay_id, badge_id] = widget_ids();
+        let badge =
+            WidgetPod::new_with_id(Box::new(SizedBox::empty().width(50.).height(50.)), badge_id)
+                .with_hit_test(HitTest::Opaque);
+        let overlay =
+            WidgetPod::new_with_id(Box::new(Flex::column().with_child_pod(badge)), overlay_id)
+                .with_hit_test(HitTest::Transparent);
+        let widget = Grid::with_dimensions(1, 1)
+            .with_child_id(Button::new("Click"), button_id, GridParams::new(0, 0, 1, 1))
+            .with_child_pod(overlay, GridParams::new(0, 0, 1, 1));
+
+        let mut harness = TestHarness::create(widget);
+        let hit_id = |harness: &TestHarness, pos: Point| {
+            let root = harness.root_widget();
+            root.find_widget_at_pos(pos).map(|widget| widget.id())
+        };
+
+        // The overlay covers the button, but lets the pointer through...
+        assert_eq!(hit_id(&harness, Point::new(100., 300.)), Some(button_id));
+        // ...except where its opaque badge is.
+        assert_eq!(hit_id(&harness, Point::new(200., 25.)), Some(badge_id));
+
+        harness.edit_widget(overlay_id, |mut overlay| {
+            overlay.ctx.set_hit_test(HitTest::Inherit);
+        });
+        assert_eq!(hit_id(&harness, Point::new(100., 300.)), Some(overlay_id));
+    }
+
+    #[test]
+    fn z_index_hit_order() {
+        let [first_id, second_id] = widget_ids();
+        let first =
+            WidgetPod::new_with_id(Box::new(Button::new("First")), first_id).with_z_index(1);
+        let widget = Grid::with_dimensions(1, 1)
+            .with_child_pod(first, GridParams::new(0, 0, 1, 1))
+            .with_child_id(
+                Button::new("Second"),
+                second_id,
+                GridParams::new(0, 0, 1, 1),
+            );
+
+        let mut harness = TestHarness::create(widget);
+        let hit_id = |harness: &TestHarness| {
+            let root = harness.root_widget();
+            root.find_widget_at_pos(Point::new(100., 300.))
+                .map(|widget| widget.id())
+        };
+
+        // The first child is raised above the second one.
+        assert_eq!(hit_id(&harness), Some(first_id));
+
+        harness.edit_widget(first_id, |mut first| {
+            first.ctx.set_z_index(0);
+        });
+        assert_eq!(hit_id(&harness), Some(second_id));
+    }
+
+    #[test]
+    fn hit_index_follows_layout() {
+        let ids: [WidgetId; 50] = widget_ids();
+        let mut column = Flex::column();
+        for id in ids {
+            column = column.with_child_id(SizedBox::empty().width(10.).height(10.), id);
+        }
+
+        let mut harness = TestHarness::create(column);
+        let hit_id = |harness: &TestHarness, pos: Point| {
+            let root = harness.root_widget();
+            root.find_widget_at_pos(pos).map(|widget| widget.id())
+        };
+        let pos = harness
+            .get_widget(ids[20])
+            .ctx()
+            .window_layout_rect()
+            .center();
+        assert_eq!(hit_id(&harness, pos), Some(ids[20]));
+
+        // Growing the first child pushes the others down, so the index must be rebuilt.
+        harness.edit_widget(ids[0], |mut first| {
+            let mut first = first.downcast::<SizedBox>();
+            SizedBox::set_height(&mut first, 110.);
+        });
+        let expected = ids.into_iter().find(|id| {
+            harness
+                .get_widget(*id)
+                .ctx()
+                .window_layout_rect()
+                .contains(pos)
+        });
+        assert_ne!(expected, Some(ids[20]));
+        assert_eq!(hit_id(&harness, pos), expected);
+    }
 }
@@ -3,10 +3,38 @@
 
 #![cfg(not(tarpaulin_include))]
 
+use std::any::TypeId;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
 use vello::kurbo::{Insets, Point, Rect, Size, Vec2};
 
+use crate::widget::hit_index::ChildHitIndex;
+use crate::widget::{HitTest, Visibility};
 use crate::WidgetId;
 
+/// Accessibility properties set from outside a widget, which take precedence over the ones
+/// it sets in [`Widget::accessibility`](crate::Widget::accessibility).
+///
+/// These are used when a widget's content doesn't describe it, e.g. to name an icon button,
+/// and to link a widget to the widgets which label or describe it, e.g. a text field to the
+/// label next to it.
+///
+/// They can be set with [`WidgetPod::access_overrides_mut`](crate::WidgetPod::access_overrides_mut)
+/// before the widget is added to the tree, and with
+/// [`MutateCtx::access_overrides_mut`](crate::MutateCtx::access_overrides_mut) afterwards.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessOverrides {
+    /// The name announced for the widget.
+    pub name: Option<String>,
+    /// A description of the widget, announced after its name.
+    pub description: Option<String>,
+    /// The widgets whose content names this widget.
+    pub labelled_by: Vec<WidgetId>,
+    /// The widgets whose content describes this widget.
+    pub described_by: Vec<WidgetId>,
+}
+
 // TODO - Reduce WidgetState size.
 // See https://github.com/linebender/xilem/issues/706
 
@@ -59,6 +87,18 @@ pub(crate) struct WidgetState {
     /// the baseline. Widgets that contain text or controls that expect to be
     /// laid out alongside text can set this as appropriate.
     pub(crate) baseline_offset: f64,
+    /// The opacity of the widget and its descendants, set by the parent with
+    /// [`set_child_opacity`](crate::EventCtx::set_child_opacity).
+    pub(crate) opacity: f64,
+    /// Whether the widget is shown, set with
+    /// [`MutateCtx::set_visibility`](crate::MutateCtx::set_visibility).
+    pub(crate) visibility: Visibility,
+    /// Where the widget is stacked among its siblings, set with
+    /// [`WidgetPod::set_z_index`](crate::WidgetPod::set_z_index).
+    pub(crate) z_index: i32,
+    /// The layout rects of the children, built when they are first hit-tested,
+    /// and cleared when they change or move.
+    pub(crate) child_hit_index: OnceLock<ChildHitIndex>,
 
     /// Tracks whether widget gets pointer events.
     /// Should be immutable after `WidgetAdded` event.
@@ -66,6 +106,14 @@ pub(crate) struct WidgetState {
     /// Tracks whether widget gets text focus.
     /// Should be immutable after `WidgetAdded` event.
     pub(crate) accepts_focus: bool,
+    /// Whether the pointer hits this widget, set with
+    /// [`WidgetPod::set_hit_test`](crate::WidgetPod::set_hit_test).
+    pub(crate) hit_test: HitTest,
+
+    /// Overrides [`Widget::is_focus_group`](crate::Widget::is_focus_group) if set.
+    pub(crate) is_focus_group: Option<bool>,
+    /// The position of this widget in the Tab order, before the widgets without one.
+    pub(crate) tab_index: Option<u32>,
 
     /// Tracks whether widget is eligible for IME events.
     /// Should be immutable after `WidgetAdded` event.
@@ -78,6 +126,12 @@ pub(crate) struct WidgetState {
     // Currently Kurbo doesn't really provide a type that lets us
     // efficiently hold an arbitrary shape.
     pub(crate) clip_path: Option<Rect>,
+    /// The intersection of the clip paths of the ancestors of this widget, in window
+    /// coordinates, as of the last compose pass.
+    pub(crate) ancestor_clip: Option<Rect>,
+
+    /// Accessibility properties set from outside the widget.
+    pub(crate) access_overrides: AccessOverrides,
 
     // TODO - Handle matrix transforms
     pub(crate) translation: Vec2,
@@ -99,6 +153,8 @@ pub(crate) struct WidgetState {
     pub(crate) request_compose: bool,
     /// The compose method must be called on this widget or a descendant
     pub(crate) needs_compose: bool,
+    /// The compose method must be called on this widget whenever it moves in the window
+    pub(crate) compose_on_move: bool,
 
     /// The paint method must be called on this widget
     pub(crate) request_paint: bool,
@@ -117,7 +173,7 @@ pub(crate) struct WidgetState {
 
     /// This widget or a descendant changed its `is_explicitly_disabled` value
     pub(crate) needs_update_disabled: bool,
-    /// This widget or a descendant changed its `is_explicitly_stashed` value
+    /// This widget or a descendant changed its `is_explicitly_stashed` or `visibility` value
     pub(crate) needs_update_stashed: bool,
 
     pub(crate) update_focus_chain: bool,
@@ -137,6 +193,8 @@ pub(crate) struct WidgetState {
     pub(crate) is_explicitly_stashed: bool,
     /// This widget or an ancestor has been stashed.
     pub(crate) is_stashed: bool,
+    /// This widget or an ancestor is hidden or collapsed.
+    pub(crate) is_hidden: bool,
 
     pub(crate) is_hovered: bool,
 
@@ -144,7 +202,22 @@ pub(crate) struct WidgetState {
     /// Descendants of the focused widget are not in the focused path.
     pub(crate) has_focus: bool,
 
+    /// The types of the commands sent to [`CommandTarget::Subscribers`] which this widget receives.
+    ///
+    /// [`CommandTarget::Subscribers`]: crate::command::CommandTarget::Subscribers
+    pub(crate) command_subscriptions: Vec<TypeId>,
+
     // --- DEBUG INFO ---
+    /// The developer-assigned tag identifying this widget, set with
+    /// [`WidgetPod::set_debug_tag`](crate::WidgetPod::set_debug_tag).
+    pub(crate) debug_tag: Option<Arc<str>>,
+    /// The time spent in the `layout` method of this widget, excluding its children, when it
+    /// was last measured for the [heat overlay](crate::RenderRoot::set_heat_overlay).
+    pub(crate) layout_time: Duration,
+    /// The time spent in the `paint` method of this widget when it was last measured for the
+    /// heat overlay.
+    pub(crate) paint_time: Duration,
+
     // TODO - document
     #[cfg(debug_assertions)]
     pub(crate) widget_name: &'static str,
@@ -160,17 +233,27 @@ impl WidgetState {
             is_expecting_place_child_call: false,
             paint_insets: Insets::ZERO,
             local_paint_rect: Rect::ZERO,
+            z_index: 0,
+            child_hit_index: OnceLock::new(),
+            opacity: 1.0,
+            visibility: Visibility::Visible,
             accepts_pointer_interaction: true,
+            hit_test: HitTest::Inherit,
             accepts_focus: false,
             accepts_text_input: false,
+            is_focus_group: None,
+            tab_index: None,
             ime_area: None,
             clip_path: Default::default(),
+            ancestor_clip: None,
+            access_overrides: AccessOverrides::default(),
             translation: Vec2::ZERO,
             translation_changed: false,
             is_explicitly_disabled: false,
             is_explicitly_stashed: false,
             is_disabled: false,
             is_stashed: false,
+            is_hidden: false,
             baseline_offset: 0.0,
             is_new: true,
             is_hovered: false,
@@ -178,11 +261,13 @@ impl WidgetState {
             needs_layout: true,
             request_compose: true,
             needs_compose: true,
+            compose_on_move: false,
             request_paint: true,
             needs_paint: true,
             request_accessibility: true,
             needs_accessibility: true,
             has_focus: false,
+            command_subscriptions: Vec::new(),
             request_anim: true,
             needs_anim: true,
             needs_update_disabled: true,
@@ -190,6 +275,9 @@ impl WidgetState {
             focus_chain: Vec::new(),
             children_changed: true,
             update_focus_chain: true,
+            debug_tag: None,
+            layout_time: Duration::ZERO,
+            paint_time: Duration::ZERO,
             #[cfg(debug_assertions)]
             widget_name,
         }
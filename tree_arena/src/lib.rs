@@ -1,16 +1,11 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
-//! This crate contains two implementations of a tree for use in [Masonry], one safe and the other unsafe. The safe tree is known to work, and serves as the baseline implementation and is used by default.
-//! The unsafe tree leverages a hashmap as an arena and is designed for higher performance: it leverages unsafe code to achieve this. The unsafe tree is not yet fully tested, and is not used by default.
+//! This crate contains two implementations of a tree for use in [Masonry], one safe and the other unsafe.
+//! The unsafe tree stores the nodes in an arena, and is designed for higher performance: it leverages unsafe code to achieve this. It is used by default.
+//! The safe tree is the baseline implementation, which the unsafe tree is tested against. It can be used instead by enabling the `safe_tree` feature.
 //!
-//! The safe tree is the priority. This means:
-//!
-//! * The safe version may have features / APIs that the unsafe version doesn't yet have.
-//!
-//! * If both versions are at feature parity, [Masonry] can switch on the unsafe version for best performance.
-//!
-//! * Otherwise, [Masonry] uses the safe version.
+//! Apart from the differences listed in [Migrating from the safe tree](#migrating-from-the-safe-tree), both trees have the same API, so [Masonry] works with either of them.
 //!
 //! ## Architecture
 //!
@@ -23,16 +18,22 @@
 //!
 //! ### Unsafe Tree
 //!
-//! The unsafe tree arena contains a `DataMap` which **owns** all nodes. The `DataMap` contains:
+//! The unsafe tree arena contains a `DataMap` which **owns** all nodes. Each node is stored in a slot, and the `DataMap` contains:
+//!
+//! * A `HashMap` associating each `NodeId` with its slot
+//!
+//! * A column with the id of the node in each slot and the slot of its parent, which is all that walking up the tree reads
+//!
+//! * The nodes themselves, item and children ids, in chunks of 64 slots. The chunks are never moved, so that nodes don't move when others are inserted, and the slots of removed nodes are reused
 //!
-//! * A `HashMap` associating `NodeId` with `Box<UnsafeCell<TreeNode<T>>>`, owning the node data, (boxed to prevent movement of the node when the `HashMap` is resized and `UnsafeCell` to express the interior mutability)
+//! * `Vec<NodeId>` containing the roots of the tree
 //!
-//! * A `HashMap` associating `NodeId` with `Option<NodeId>`, containing the parent information for the nodes
+//! Nodes inserted together, such as the children of a widget, end up next to each other, instead of in separate allocations.
 //!
-//! * `Box<UnsafeCell<Vec<NodeId>>>` containing the roots of the tree
+//! The items of the nodes aren't split into columns. [Masonry] keeps widgets and their `WidgetState` in two separate arenas, but a pass which only reads a few fields of the state still loads the whole `WidgetState`.
 //!
 //! It is possible to get shared (immutable) access or exclusive (mutable) access to the tree. These return `ArenaRef<'arena, T>` or `ArenaMut<'arena, T>` respectively.
-//! We do this by leveraging a hash map to store the nodes: from this we can obtain either shared or exclusive access to nodes.
+//! We do this by looking up the slot of a node: from this we can obtain either shared or exclusive access to nodes.
 //! To ensure that only one item is allowed to create new exclusive access to nodes, this action requires mutable access to the arena as a whole (and so is checked by the compiler) -
 //! what the compiler cannot check is that the nodes accessed mutably are distinct from one another - this is done by only allowing access to descendants of the node being accessed mutably.
 //! The aim of this is to reduce the time needed to access node, as given a node, we only need to determine whether it is a descendant of the node being accessed mutably,
@@ -62,6 +63,23 @@
 //! |Find child | O(Children)  | O(1)     |
 //! |Descendant | O(Depth)     | O(Depth) |
 //! |From root  | O(Depth)     | O(1)     |
+//! |Parent     | O(Depth)     | O(1)     |
+//!
+//! Checking whether a node is a descendant of another walks up the parent column without allocating.
+//!
+//! ## Migrating from the safe tree
+//!
+//! The unsafe tree used to be opt-in, and is now the default. Code which compiled against the safe tree compiles against the unsafe tree, with these exceptions:
+//!
+//! * `ArenaMapRef` and `ArenaMapMut`, the handles to the parents map of the safe tree, don't exist: use [`TreeArena::get_id_path`] or the `parent_id` of [`ArenaRef`] and [`ArenaMut`] instead.
+//! * `TreeArena<T>` implements `Default` for any `T`, rather than only when `T: Default`.
+//! * The children of a node are kept in insertion order, rather than in the arbitrary order of a `HashMap`. Code shouldn't rely on either.
+//!
+//! To keep using the safe tree, e.g. to check whether a bug is caused by the unsafe tree, enable the `safe_tree` feature:
+//!
+//! ```toml
+//! tree_arena = { version = "0.1.0", features = ["safe_tree"] }
+//! ```
 //!
 //! [Masonry]: https://crates.io/crates/masonry
 
@@ -4,25 +4,54 @@
 #![allow(unsafe_code, reason = "Purpose is unsafe abstraction")]
 use super::NodeId;
 
-use std::cell::UnsafeCell;
+use std::fmt;
+use std::ptr::NonNull;
 
 use hashbrown::HashMap;
 
+/// The number of nodes in each chunk of the arena.
+const CHUNK_SIZE: usize = 64;
+
+/// The index of a node in the arena.
+type Slot = usize;
+
 #[derive(Debug)]
 struct TreeNode<T> {
     item: T,
     children: Vec<NodeId>,
 }
 
+/// The id of the node in a slot, and the slot of its parent.
+#[derive(Clone, Copy, Debug)]
+struct SlotInfo {
+    id: NodeId,
+    /// The slot of the parent, or None if it is a root
+    parent: Option<Slot>,
+}
+
 /// Mapping of data for the Tree Arena
-#[derive(Debug)]
+///
+/// The nodes are stored in slots, which are reused once their node is removed.
+/// The structure of the tree is kept in a separate column from the items, so walking
+/// up the tree doesn't touch the items.
 struct DataMap<T> {
-    /// The items in the tree
-    items: HashMap<NodeId, Box<UnsafeCell<TreeNode<T>>>>,
-    /// The parent of each node, or None if it is the root
-    parents: HashMap<NodeId, Option<NodeId>>,
+    /// The slot of each node
+    slots: HashMap<NodeId, Slot>,
+    /// The id and parent of the node in each slot, or None if the slot is free
+    infos: Vec<Option<SlotInfo>>,
+    /// The nodes, in chunks of `CHUNK_SIZE` slots
+    ///
+    /// The chunks are allocated as boxed slices, and only freed when the arena is dropped,
+    /// so a node never moves whilst it is in the tree. They are only accessed through these
+    /// pointers, which is what allows handing out references to several nodes at once.
+    chunks: Vec<NonNull<Option<TreeNode<T>>>>,
+    /// The slots which have been freed, to be reused first
+    free: Vec<Slot>,
 }
 
+// SAFETY: The nodes are owned by the `DataMap`, as if they were in a `Vec<Option<TreeNode<T>>>`.
+unsafe impl<T: Send> Send for DataMap<T> {}
+
 /// A container type for a tree of items.
 ///
 /// This type is used to store zero, one or many trees of a given item type. It
@@ -121,9 +150,63 @@ impl<Item> Copy for ArenaRefChildren<'_, Item> {}
 impl<T> DataMap<T> {
     fn new() -> Self {
         Self {
-            items: HashMap::new(),
-            parents: HashMap::new(),
+            slots: HashMap::new(),
+            infos: Vec::new(),
+            chunks: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// A pointer to the node in `slot`.
+    fn node_ptr(&self, slot: Slot) -> *mut Option<TreeNode<T>> {
+        let chunk = self.chunks[slot / CHUNK_SIZE];
+        // SAFETY: Each chunk holds `CHUNK_SIZE` slots, so the offset is in bounds.
+        unsafe { chunk.as_ptr().add(slot % CHUNK_SIZE) }
+    }
+
+    /// The id of the parent of the node in `slot`.
+    fn parent_id(&self, slot: Slot) -> Option<NodeId> {
+        let parent = self.infos[slot]?.parent?;
+        self.infos[parent].map(|info| info.id)
+    }
+
+    /// Store `node` in a free slot, and return the slot.
+    fn insert_node(&mut self, id: NodeId, parent: Option<NodeId>, node: TreeNode<T>) -> Slot {
+        let parent = parent.map(|parent| self.slots[&parent]);
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                let slot = self.infos.len();
+                if slot % CHUNK_SIZE == 0 {
+                    let chunk: Box<[Option<TreeNode<T>>]> = (0..CHUNK_SIZE).map(|_| None).collect();
+                    let chunk = Box::into_raw(chunk).cast::<Option<TreeNode<T>>>();
+                    // SAFETY: `Box::into_raw` never returns a null pointer.
+                    self.chunks.push(unsafe { NonNull::new_unchecked(chunk) });
+                }
+                self.infos.push(None);
+                slot
+            }
+        };
+        self.infos[slot] = Some(SlotInfo { id, parent });
+        self.slots.insert(id, slot);
+        // SAFETY: The slot is free, so there are no references to it.
+        unsafe { *self.node_ptr(slot) = Some(node) };
+        slot
+    }
+
+    /// Remove the node with the given id, and all of its descendants.
+    ///
+    /// There must be no references to these nodes.
+    fn remove_node(&mut self, id: NodeId) -> Option<T> {
+        let slot = self.slots.remove(&id)?;
+        self.infos[slot] = None;
+        self.free.push(slot);
+        // SAFETY: The caller ensures that there are no references to the node.
+        let node = unsafe { (*self.node_ptr(slot)).take() }?;
+        for child_id in node.children {
+            self.remove_node(child_id);
         }
+        Some(node.item)
     }
 
     /// Find an item in the tree.
@@ -132,16 +215,15 @@ impl<T> DataMap<T> {
     ///
     /// Time Complexity O(1)
     fn find_inner(&self, id: NodeId) -> Option<ArenaRef<'_, T>> {
-        let parent_id = *self.parents.get(&id)?;
-        let node_cell = self.items.get(&id)?;
+        let slot = *self.slots.get(&id)?;
+        let parent_id = self.parent_id(slot);
 
         // SAFETY
         // We need there to be no mutable access to the node
         // Mutable access to the node would imply there is some &mut self
         // As we are taking &self, there can be no mutable access to the node
         // Thus this is safe
-
-        let TreeNode { item, .. } = unsafe { node_cell.get().as_ref()? };
+        let TreeNode { item, .. } = unsafe { (*self.node_ptr(slot)).as_ref()? };
 
         let children = ArenaRefChildren {
             parent_arena: self,
@@ -161,8 +243,8 @@ impl<T> DataMap<T> {
     ///
     /// Time Complexity O(1)
     fn find_mut_inner(&mut self, id: NodeId) -> Option<ArenaMut<'_, T>> {
-        let parent_id = *self.parents.get(&id)?;
-        let node_cell = self.items.get(&id)?;
+        let slot = *self.slots.get(&id)?;
+        let parent_id = self.parent_id(slot);
 
         // SAFETY
         //
@@ -173,7 +255,7 @@ impl<T> DataMap<T> {
         // Similarly we cannot take any other actions that would affect this node,
         // such as removing it or removing a parent (and thus this node) or violate
         // exclusivity by creating a shared reference to the node
-        let TreeNode { item, children } = unsafe { node_cell.get().as_mut()? };
+        let TreeNode { item, children } = unsafe { (*self.node_ptr(slot)).as_mut()? };
 
         let children = ArenaMutChildren {
             parent_arena: self,
@@ -199,27 +281,91 @@ impl<T> DataMap<T> {
     fn get_id_path(&self, id: NodeId, start_id: Option<NodeId>) -> Vec<NodeId> {
         let mut path = Vec::new();
 
-        if !self.parents.contains_key(&id) {
+        let Some(&slot) = self.slots.get(&id) else {
             return path;
-        }
+        };
+        let start_slot = match start_id {
+            Some(start_id) => match self.slots.get(&start_id) {
+                Some(&start_slot) => Some(start_slot),
+                None => return path,
+            },
+            None => None,
+        };
 
-        let mut current_id = Some(id);
-        while let Some(current) = current_id {
-            path.push(current);
-            current_id = *self.parents.get(&current).unwrap();
-            if current_id == start_id {
+        let mut current_slot = Some(slot);
+        while let Some(current) = current_slot {
+            let info = self.infos[current].unwrap();
+            path.push(info.id);
+            current_slot = info.parent;
+            if current_slot == start_slot {
                 break;
             }
         }
 
-        // current_id was the last parent node
-        // as such if current id is not start_id
+        // current_slot was the last parent node
+        // as such if current slot is not start_slot
         // we have gone to the root and we empty the vec
-        if current_id != start_id {
+        if current_slot != start_slot {
             path.clear();
         }
         path
     }
+
+    /// Returns true if `id` is a descendant of `ancestor_id`, or is in the tree if there is no ancestor.
+    ///
+    /// Unlike [`get_id_path`](Self::get_id_path), this doesn't allocate.
+    fn is_descendant(&self, id: NodeId, ancestor_id: Option<NodeId>) -> bool {
+        let Some(&slot) = self.slots.get(&id) else {
+            // if the id is not in the tree, it is not a descendant
+            return false;
+        };
+        let Some(ancestor_id) = ancestor_id else {
+            // Every node in the tree is a descendant of the roots
+            return true;
+        };
+        let Some(&ancestor_slot) = self.slots.get(&ancestor_id) else {
+            return false;
+        };
+        let mut current_slot = self.infos[slot].and_then(|info| info.parent);
+        while let Some(current) = current_slot {
+            if current == ancestor_slot {
+                return true;
+            }
+            current_slot = self.infos[current].and_then(|info| info.parent);
+        }
+        false
+    }
+
+    /// Returns true if the node with the given id is a child of `parent_id`, or a root if there is no parent.
+    fn is_child(&self, id: NodeId, parent_id: Option<NodeId>) -> bool {
+        self.slots
+            .get(&id)
+            .is_some_and(|&slot| self.parent_id(slot) == parent_id)
+    }
+}
+
+impl<T> Drop for DataMap<T> {
+    fn drop(&mut self) {
+        for chunk in self.chunks.drain(..) {
+            let chunk = std::ptr::slice_from_raw_parts_mut(chunk.as_ptr(), CHUNK_SIZE);
+            // SAFETY: The chunk was allocated as a boxed slice of `CHUNK_SIZE` slots in
+            // `insert_node`, and there are no references to the nodes left.
+            drop(unsafe { Box::from_raw(chunk) });
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DataMap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (id, &slot) in &self.slots {
+            // SAFETY: This has shared access to the arena, so there is no mutable access to the node.
+            if let Some(node) = unsafe { (*self.node_ptr(slot)).as_ref() } {
+                map.entry(id, node);
+            }
+        }
+        map.finish()
+    }
 }
 
 impl<T> TreeArena<T> {
@@ -305,32 +451,12 @@ impl<'arena, T> ArenaRefChildren<'arena, T> {
     /// O(depth) and the limiting factor for find methods
     /// not from the root
     fn is_descendant(&self, id: NodeId) -> bool {
-        if self.parent_arena.items.contains_key(&id) {
-            // the id of the parent
-            let parent_id = self.id;
-
-            // The arena is derived from the root, and the id is in the tree
-            if parent_id.is_none() {
-                return true;
-            }
-
-            // iff the path is empty, there is no path from id to self
-            !self.parent_arena.get_id_path(id, parent_id).is_empty()
-        } else {
-            // if the id is not in the tree, it is not a descendant
-            false
-        }
+        self.parent_arena.is_descendant(id, self.id)
     }
 
     /// Returns true if there is a child with the given id
     pub fn has_child(&self, id: impl Into<NodeId>) -> bool {
-        let child_id = id.into();
-        let parent_id = self.id;
-        self.parent_arena
-            .parents
-            .get(&child_id)
-            .map(|parent| *parent == parent_id) // check if the parent of child is the same as the parent of the arena
-            .unwrap_or_default()
+        self.parent_arena.is_child(id.into(), self.id)
     }
 
     /// Get the child of the item this handle is associated with, which has the given id.
@@ -418,13 +544,7 @@ impl<'arena, T> ArenaMutChildren<'arena, T> {
 
     /// returns true if there is a child with the given id
     pub fn has_child(&self, id: impl Into<NodeId>) -> bool {
-        let child_id = id.into();
-        let parent_id = self.id;
-        self.parent_arena
-            .parents
-            .get(&child_id)
-            .map(|parent| *parent == parent_id) // check if the parent of child is the same as the parent of the arena
-            .unwrap_or_default()
+        self.parent_arena.is_child(id.into(), self.id)
     }
 
     /// Get the child of the item this handle is associated with, which has the given id.
@@ -494,22 +614,17 @@ impl<'arena, T> ArenaMutChildren<'arena, T> {
     pub fn insert_child(&mut self, child_id: impl Into<NodeId>, value: T) {
         let child_id: NodeId = child_id.into();
         assert!(
-            !self.parent_arena.parents.contains_key(&child_id),
+            !self.parent_arena.slots.contains_key(&child_id),
             "Key already present"
         );
 
-        self.parent_arena.parents.insert(child_id, self.id);
-
-        self.child_arr.push(child_id);
-
         let node = TreeNode {
             item: value,
             children: Vec::new(),
         };
+        self.parent_arena.insert_node(child_id, self.id, node);
 
-        self.parent_arena
-            .items
-            .insert(child_id, Box::new(UnsafeCell::new(node)));
+        self.child_arr.push(child_id);
     }
 
     // TODO - How to handle when a subtree is removed?
@@ -524,16 +639,9 @@ impl<'arena, T> ArenaMutChildren<'arena, T> {
     pub fn remove_child(&mut self, child_id: impl Into<NodeId>) -> Option<T> {
         let child_id: NodeId = child_id.into();
         if self.has_child(child_id) {
-            fn remove_children<T>(id: NodeId, data_map: &mut DataMap<T>) -> T {
-                let node = data_map.items.remove(&id).unwrap().into_inner();
-                for child_id in node.children.into_iter() {
-                    remove_children(child_id, data_map);
-                }
-                data_map.parents.remove(&id);
-                node.item
-            }
             self.child_arr.retain(|i| *i != child_id);
-            Some(remove_children(child_id, self.parent_arena))
+            // The child and its descendants can't be borrowed, as this handle is borrowed mutably
+            self.parent_arena.remove_node(child_id)
         } else {
             None
         }
@@ -565,13 +673,8 @@ impl<'arena, T> ArenaMutChildren<'arena, T> {
     /// ## Complexity
     ///
     /// O(Depth). except access from root which is O(1).
-    pub fn find(self, id: impl Into<NodeId>) -> Option<ArenaRef<'arena, T>> {
-        let id = id.into();
-        if self.is_descendant(id) {
-            self.parent_arena.find_inner(id)
-        } else {
-            None
-        }
+    pub fn find(&self, id: impl Into<NodeId>) -> Option<ArenaRef<'_, T>> {
+        self.reborrow().find(id)
     }
 
     /// Find an arena item among descendants (this node not included).
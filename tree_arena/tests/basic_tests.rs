@@ -133,3 +133,41 @@ fn mem_swap() {
         "Node 2 item in tree b should be g"
     );
 }
+
+#[test]
+fn reinsert_removed_ids() {
+    let mut tree: TreeArena<u64> = TreeArena::new();
+    let mut roots = tree.root_token_mut();
+    roots.insert_child(1_u64, 1);
+    let mut node_1 = roots.get_child_mut(1_u64).expect("No child 1 found");
+    // Enough nodes to span several chunks of the arena
+    for id in 2_u64..200 {
+        node_1.children.insert_child(id, id);
+        let mut child = node_1.children.get_child_mut(id).unwrap();
+        child.children.insert_child(id + 1000, id + 1000);
+    }
+    // The references to earlier nodes stay valid whilst nodes are inserted
+    *node_1.item = 0;
+    for id in (2_u64..200).step_by(2) {
+        assert_eq!(node_1.children.remove_child(id), Some(id));
+    }
+    for id in (2_u64..200).step_by(2) {
+        node_1.children.insert_child(id, id * 10);
+    }
+
+    assert_eq!(*tree.find(1_u64).unwrap().item, 0);
+    for id in 2_u64..200 {
+        let child = tree.find(id).expect("child should be in the tree");
+        assert_eq!(child.parent_id, Some(1));
+        if id % 2 == 0 {
+            assert_eq!(*child.item, id * 10);
+            assert!(
+                tree.find(id + 1000).is_none(),
+                "grandchild should be removed"
+            );
+        } else {
+            assert_eq!(*child.item, id);
+            assert_eq!(tree.get_id_path(id + 1000), vec![id + 1000, id, 1]);
+        }
+    }
+}
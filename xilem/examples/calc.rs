@@ -331,3 +331,12 @@ fn android_main(app: winit::platform::android::activity::AndroidApp) {
 
     run(event_loop).expect("Can create app");
 }
+
+// Boilerplate code: Identical across all applications which support the web
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen(start)]
+fn web_main() {
+    // The event loop is run by the browser, so this returns once the app has started
+    run(EventLoop::with_user_event()).expect("Can create app");
+}
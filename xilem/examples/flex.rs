@@ -7,15 +7,17 @@
 use masonry::text::ArcStr;
 use masonry::widget::{CrossAxisAlignment, MainAxisAlignment};
 use winit::error::EventLoopError;
-use xilem::view::{button, flex, label, sized_box, Axis, FlexExt as _, FlexSpacer};
+use xilem::view::{button, flex, label, sized_box, AutoRepeat, Axis, FlexExt as _, FlexSpacer};
 use xilem::{EventLoop, WidgetView, Xilem};
 
-/// A component to make a bigger than usual button
+/// A component to make a bigger than usual button, which repeats while held down
 fn big_button(
     label: impl Into<ArcStr>,
     callback: impl Fn(&mut i32) + Send + Sync + 'static,
 ) -> impl WidgetView<i32> {
-    sized_box(button(label, callback)).width(40.).height(40.)
+    sized_box(button(label, callback).auto_repeat(AutoRepeat::default()))
+        .width(40.)
+        .height(40.)
 }
 
 fn app_logic(data: &mut i32) -> impl WidgetView<i32> {
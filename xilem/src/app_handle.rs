@@ -0,0 +1,148 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use crate::core::{DynMessage, Message, ProxyError, RawProxy};
+
+/// A handle which can be used to send messages of type `M` to a Xilem app from other threads.
+///
+/// These messages are given to the handler registered with
+/// [`Xilem::on_external_message`](crate::Xilem::on_external_message), which has access to
+/// the app state. The app is then rebuilt.
+///
+/// Handles can be created with [`Xilem::app_handle`](crate::Xilem::app_handle) before the app is run.
+/// Messages sent before the event loop is running are delivered once it starts.
+pub struct AppHandle<M> {
+    proxy: Arc<ExternalProxy>,
+    message: PhantomData<fn(M)>,
+}
+
+impl<M> Clone for AppHandle<M> {
+    fn clone(&self) -> Self {
+        Self {
+            proxy: self.proxy.clone(),
+            message: PhantomData,
+        }
+    }
+}
+
+impl<M> std::fmt::Debug for AppHandle<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppHandle")
+            .field("message", &std::any::type_name::<M>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: Message> AppHandle<M> {
+    pub(crate) fn new(proxy: Arc<ExternalProxy>) -> Self {
+        Self {
+            proxy,
+            message: PhantomData,
+        }
+    }
+
+    /// Send `message` to the app.
+    ///
+    /// # Errors
+    ///
+    /// - `DriverFinished`: If the event loop of the app has exited.
+    pub fn send(&self, message: M) -> Result<(), ProxyError> {
+        self.proxy.send(Box::new(message))
+    }
+}
+
+/// The message type used to route messages from [`AppHandle`]s to the external message handlers.
+#[derive(Debug)]
+pub(crate) struct ExternalMessage(pub(crate) DynMessage);
+
+enum ProxyState {
+    /// The app hasn't started yet, so the messages are queued.
+    Pending(Vec<DynMessage>),
+    Running(Arc<dyn RawProxy>),
+}
+
+/// The proxy shared by all the [`AppHandle`]s of an app.
+pub(crate) struct ExternalProxy {
+    state: Mutex<ProxyState>,
+}
+
+impl ExternalProxy {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Mutex::new(ProxyState::Pending(Vec::new())),
+        }
+    }
+
+    pub(crate) fn send(&self, message: DynMessage) -> Result<(), ProxyError> {
+        let mut state = self.state.lock().unwrap();
+        match &mut *state {
+            ProxyState::Pending(queue) => {
+                queue.push(message);
+                Ok(())
+            }
+            ProxyState::Running(proxy) => {
+                proxy.send_message(Arc::new([]), Box::new(ExternalMessage(message)))
+            }
+        }
+    }
+
+    /// Start sending messages through `proxy`, including the ones which were queued.
+    pub(crate) fn start(&self, proxy: Arc<dyn RawProxy>) {
+        let mut state = self.state.lock().unwrap();
+        if let ProxyState::Pending(queue) = &mut *state {
+            for message in queue.drain(..) {
+                // We choose not to handle the case where the event loop has ended
+                drop(proxy.send_message(Arc::new([]), Box::new(ExternalMessage(message))));
+            }
+        }
+        *state = ProxyState::Running(proxy);
+    }
+}
+
+type ExternalHandler<State> = Box<dyn FnMut(&mut State, DynMessage)>;
+
+/// The handlers for the messages sent through [`AppHandle`]s, keyed by message type.
+pub(crate) struct ExternalHandlers<State> {
+    handlers: HashMap<TypeId, ExternalHandler<State>>,
+}
+
+impl<State> Default for ExternalHandlers<State> {
+    fn default() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+}
+
+impl<State> ExternalHandlers<State> {
+    pub(crate) fn insert<M: Message>(&mut self, mut handler: impl FnMut(&mut State, M) + 'static) {
+        self.handlers.insert(
+            TypeId::of::<M>(),
+            Box::new(move |state: &mut State, message: DynMessage| {
+                let message = message.downcast::<M>().unwrap();
+                handler(state, *message);
+            }),
+        );
+    }
+
+    /// Give `message` to its handler.
+    ///
+    /// Returns `false` if there is no handler for messages of that type.
+    pub(crate) fn handle(&mut self, state: &mut State, message: DynMessage) -> bool {
+        let type_id = Any::type_id((*message).as_any());
+        let Some(handler) = self.handlers.get_mut(&type_id) else {
+            tracing::error!(
+                "Got external message {message:?}, but no handler was registered for its type. \
+                Did you forget to use `Xilem::on_external_message`?"
+            );
+            return false;
+        };
+        handler(state, message);
+        true
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Running the app logic on a worker thread.
+
+use std::fmt::Debug;
+use std::sync::mpsc;
+use std::sync::Arc;
+
+use crate::core::RawProxy;
+
+/// The view computed by the worker thread, sent to the driver to be diffed against the
+/// current view.
+pub(crate) struct BackgroundView<View>(pub(crate) View);
+
+impl<View> Debug for BackgroundView<View> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BackgroundView")
+            .field(&std::any::type_name::<View>())
+            .finish()
+    }
+}
+
+/// Starts the worker thread of an app, once its proxy is known.
+pub(crate) type BackgroundSpawner<State> =
+    Box<dyn FnOnce(Arc<dyn RawProxy>) -> BackgroundLogic<State>>;
+
+/// The handle of the worker thread which runs the app logic.
+///
+/// The worker computes one view at a time, from a snapshot of the state. Rebuilds requested
+/// whilst it does are coalesced into one, which starts once the view has been diffed.
+pub(crate) struct BackgroundLogic<State> {
+    snapshots: mpsc::Sender<State>,
+    snapshot: fn(&State) -> State,
+    /// Whether the worker is computing a view.
+    in_flight: bool,
+    /// Whether the state changed after the snapshot of the view being computed was taken.
+    stale: bool,
+}
+
+impl<State: Clone + Send + 'static> BackgroundLogic<State> {
+    /// Returns a function which spawns the worker thread, which runs `logic` and sends the
+    /// resulting views through the proxy.
+    pub(crate) fn spawner<Logic, View>(mut logic: Logic) -> BackgroundSpawner<State>
+    where
+        Logic: FnMut(&mut State) -> View + Send + 'static,
+        View: Send + 'static,
+    {
+        Box::new(move |proxy: Arc<dyn RawProxy>| {
+            let (snapshots, receiver) = mpsc::channel::<State>();
+            let spawned = std::thread::Builder::new()
+                .name("xilem-logic".into())
+                .spawn(move || {
+                    // The loop ends when the driver, which owns the sender, is dropped.
+                    while let Ok(mut state) = receiver.recv() {
+                        let view = logic(&mut state);
+                        if proxy
+                            .send_message(Arc::from([]), Box::new(BackgroundView(view)))
+                            .is_err()
+                        {
+                            // The event loop has exited.
+                            break;
+                        }
+                    }
+                });
+            if let Err(err) = spawned {
+                tracing::error!("Couldn't spawn the worker thread of the app logic: {err}");
+            }
+            Self {
+                snapshots,
+                snapshot: State::clone,
+                in_flight: false,
+                stale: false,
+            }
+        })
+    }
+}
+
+impl<State> BackgroundLogic<State> {
+    /// Compute a new view from `state` on the worker thread.
+    pub(crate) fn request(&mut self, state: &State) {
+        if self.in_flight {
+            self.stale = true;
+            return;
+        }
+        if self.snapshots.send((self.snapshot)(state)).is_err() {
+            tracing::error!("The worker thread of the app logic has exited");
+            return;
+        }
+        self.in_flight = true;
+    }
+
+    /// Called once the view computed by the worker has been diffed.
+    ///
+    /// If the state changed in the meantime, a new view is requested.
+    pub(crate) fn finished(&mut self, state: &State) {
+        self.in_flight = false;
+        if std::mem::take(&mut self.stale) {
+            self.request(state);
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use masonry::widget::Label;
+
+    use crate::testing::AppTestHarness;
+    use crate::view::{button, flex, label};
+    use crate::{WidgetView, Xilem};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn counter(count: &mut u32) -> impl WidgetView<u32> {
+        flex((
+            label(format!("Count: {count}")).debug_tag("count"),
+            button("Increment", |count: &mut u32| *count += 1).debug_tag("increment"),
+        ))
+    }
+
+    fn count_text<Logic, View>(harness: &AppTestHarness<u32, Logic, View>) -> String
+    where
+        Logic: FnMut(&mut u32) -> View,
+        View: WidgetView<u32>,
+    {
+        let label = harness.harness().get_widget_by_tag("count");
+        label.downcast::<Label>().unwrap().text().to_string()
+    }
+
+    #[test]
+    fn views_are_computed_on_the_worker_thread() {
+        let mut harness = AppTestHarness::create(Xilem::new(0, counter).with_background_logic());
+        assert_eq!(count_text(&harness), "Count: 0");
+
+        let button_id = harness.harness().get_widget_by_tag("increment").id();
+        harness.click(button_id);
+        harness.click(button_id);
+        assert_eq!(*harness.state(), 2);
+        // The current view is kept until the worker sends the next one
+        assert_eq!(count_text(&harness), "Count: 0");
+
+        assert!(harness.wait_for_messages(TIMEOUT) > 0);
+        // The second click happened whilst the first view was computed, so another view is
+        // computed once it has been applied.
+        if count_text(&harness) != "Count: 2" {
+            assert!(harness.wait_for_messages(TIMEOUT) > 0);
+        }
+        assert_eq!(count_text(&harness), "Count: 2");
+        assert_eq!(harness.wait_for_messages(Duration::from_millis(50)), 0);
+    }
+}
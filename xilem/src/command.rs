@@ -0,0 +1,133 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::app_handle::ExternalProxy;
+use crate::core::{ProxyError, ViewId};
+
+/// Which of the [`on_command`](crate::view::on_command) views a command is delivered to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommandTarget {
+    /// Every view subscribed to the command type, whether or not it has a scope.
+    All,
+    /// The views subscribed to the command type with this [`scope`](crate::view::OnCommand::scope).
+    Scope(u64),
+}
+
+/// A handle used to send commands to the [`on_command`](crate::view::on_command) views of an app.
+///
+/// This allows distant parts of the view tree to communicate without threading callbacks
+/// through their common ancestors, such as a toolbar button triggering an action in an editor.
+/// The bus can also be used from other threads.
+///
+/// A bus is connected to an app with [`Xilem::with_command_bus`](crate::Xilem::with_command_bus),
+/// and is usually stored in the app state or captured by callbacks.
+/// Commands are delivered after the current event has been handled, and the app is then rebuilt.
+#[derive(Clone)]
+pub struct CommandBus {
+    proxy: Arc<ExternalProxy>,
+}
+
+impl Debug for CommandBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandBus").finish_non_exhaustive()
+    }
+}
+
+impl Default for CommandBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandBus {
+    /// Create a bus which isn't connected to an app yet.
+    ///
+    /// Commands sent before the app starts are queued until then.
+    pub fn new() -> Self {
+        Self {
+            proxy: Arc::new(ExternalProxy::new()),
+        }
+    }
+
+    pub(crate) fn proxy(&self) -> Arc<ExternalProxy> {
+        self.proxy.clone()
+    }
+
+    /// Send `command` to the views subscribed to commands of type `C` which match `target`.
+    ///
+    /// # Errors
+    ///
+    /// - `DriverFinished`: If the event loop of the app has exited.
+    pub fn send<C>(&self, target: CommandTarget, command: C) -> Result<(), ProxyError>
+    where
+        C: Any + Debug + Send + Sync,
+    {
+        self.proxy.send(Box::new(SentCommand {
+            type_id: TypeId::of::<C>(),
+            target,
+            command: Arc::new(command),
+        }))
+    }
+}
+
+/// The message used to route a command from a [`CommandBus`] to the app driver.
+#[derive(Debug)]
+pub(crate) struct SentCommand {
+    pub(crate) type_id: TypeId,
+    pub(crate) target: CommandTarget,
+    pub(crate) command: Arc<dyn Any + Send + Sync>,
+}
+
+/// The message sent to each [`on_command`](crate::view::on_command) view a command is delivered to.
+#[derive(Debug)]
+pub(crate) struct DeliveredCommand(pub(crate) Arc<dyn Any + Send + Sync>);
+
+/// The paths of the [`on_command`](crate::view::on_command) views, by command type.
+#[derive(Default)]
+pub(crate) struct CommandSubscriptions {
+    subscriptions: HashMap<TypeId, Vec<(Option<u64>, Vec<ViewId>)>>,
+}
+
+impl CommandSubscriptions {
+    pub(crate) fn insert(&mut self, type_id: TypeId, scope: Option<u64>, path: Vec<ViewId>) {
+        self.subscriptions
+            .entry(type_id)
+            .or_default()
+            .push((scope, path));
+    }
+
+    pub(crate) fn remove(&mut self, type_id: TypeId, scope: Option<u64>, path: &[ViewId]) {
+        let Some(subscriptions) = self.subscriptions.get_mut(&type_id) else {
+            return;
+        };
+        if let Some(index) = subscriptions
+            .iter()
+            .position(|(other_scope, other_path)| *other_scope == scope && other_path == path)
+        {
+            subscriptions.remove(index);
+        }
+        if subscriptions.is_empty() {
+            self.subscriptions.remove(&type_id);
+        }
+    }
+
+    /// The paths of the views which a command of type `type_id` sent to `target` should be delivered to.
+    pub(crate) fn matching(&self, type_id: TypeId, target: CommandTarget) -> Vec<Vec<ViewId>> {
+        let Some(subscriptions) = self.subscriptions.get(&type_id) else {
+            return Vec::new();
+        };
+        subscriptions
+            .iter()
+            .filter(|(scope, _)| match target {
+                CommandTarget::All => true,
+                CommandTarget::Scope(target) => *scope == Some(target),
+            })
+            .map(|(_, path)| path.clone())
+            .collect()
+    }
+}
@@ -3,11 +3,21 @@
 
 use std::sync::Arc;
 
-use masonry::event_loop_runner::{self, EventLoopProxy, MasonryUserEvent};
+#[cfg(feature = "event_loop_runner")]
+use masonry::event_loop_runner::{self, EventLoopProxy};
+use masonry::gpu::{GpuInfo, GpuOptions, RenderError};
+use masonry::host::HostProxy;
+use masonry::monitor::{MonitorInfo, WindowPlacement};
 use masonry::widget::RootWidget;
-use masonry::{AppDriver, WidgetId};
+use masonry::{AppDriver, RenderRoot, WidgetId};
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::ModifiersState;
 
+use crate::app_handle::{ExternalHandlers, ExternalMessage};
+use crate::background::{BackgroundLogic, BackgroundView};
+use crate::command::{DeliveredCommand, SentCommand};
 use crate::core::{DynMessage, Message, MessageResult, ProxyError, RawProxy, ViewId};
+use crate::view::{KeyChord, ShortcutPressed};
 use crate::{ViewCtx, WidgetView};
 
 pub struct MasonryDriver<State, Logic, View, ViewState> {
@@ -18,6 +28,30 @@ pub struct MasonryDriver<State, Logic, View, ViewState> {
     pub(crate) view_state: ViewState,
     // Fonts which will be registered on startup.
     pub(crate) fonts: Vec<Vec<u8>>,
+    pub(crate) external_handlers: ExternalHandlers<State>,
+    #[cfg(feature = "persistence")]
+    pub(crate) persistence: Option<crate::persistence::Persistence<State>>,
+    #[cfg(feature = "tray")]
+    pub(crate) tray: Option<masonry::tray::TrayOptions>,
+    pub(crate) window_placement: Option<WindowPlacement>,
+    pub(crate) on_window_placement: Option<crate::WindowPlacementHandler<State>>,
+    pub(crate) gpu_options: Option<GpuOptions>,
+    pub(crate) on_gpu_adapter: Option<crate::GpuAdapterHandler<State>>,
+    pub(crate) on_render_error: Option<crate::RenderErrorHandler<State>>,
+    pub(crate) on_idle: Option<crate::IdleHandler<State>>,
+    pub(crate) resource_cache: Option<masonry::resource_cache::ResourceCache>,
+    /// The worker thread running `logic`, if it doesn't run on the UI thread.
+    pub(crate) background: Option<BackgroundLogic<State>>,
+}
+
+#[cfg(feature = "persistence")]
+impl<State, Logic, View, ViewState> Drop for MasonryDriver<State, Logic, View, ViewState> {
+    fn drop(&mut self) {
+        // The driver is dropped when the event loop exits
+        if let Some(persistence) = &mut self.persistence {
+            persistence.save(&self.state);
+        }
+    }
 }
 
 /// The `WidgetId` which async events should be sent to.
@@ -29,17 +63,17 @@ pub fn async_action(path: Arc<[ViewId]>, message: Box<dyn Message>) -> masonry::
 }
 
 /// The type used to send a message for async events.
-type MessagePackage = (Arc<[ViewId]>, DynMessage);
+pub(crate) type MessagePackage = (Arc<[ViewId]>, DynMessage);
 
 impl RawProxy for MasonryProxy {
     fn send_message(&self, path: Arc<[ViewId]>, message: DynMessage) -> Result<(), ProxyError> {
-        match self.0.send_event(MasonryUserEvent::Action(
-            async_action(path, message),
-            ASYNC_MARKER_WIDGET,
-        )) {
+        match self
+            .0
+            .send_action(async_action(path, message), ASYNC_MARKER_WIDGET)
+        {
             Ok(()) => Ok(()),
-            Err(err) => {
-                let MasonryUserEvent::Action(masonry::Action::Other(res), _) = err.0 else {
+            Err(action) => {
+                let masonry::Action::Other(res) = action else {
                     unreachable!(
                         "We know this is the value we just created, which matches this pattern"
                     )
@@ -55,15 +89,39 @@ impl RawProxy for MasonryProxy {
     }
 }
 
-#[derive(Debug)]
-pub struct MasonryProxy(pub(crate) EventLoopProxy);
+pub struct MasonryProxy(pub(crate) Arc<dyn HostProxy>);
 
 impl MasonryProxy {
+    #[cfg(feature = "event_loop_runner")]
     pub fn new(proxy: EventLoopProxy) -> Self {
+        Self(Arc::new(proxy))
+    }
+
+    /// Send the messages of the app through the proxy of an [`AppHost`](masonry::host::AppHost).
+    pub fn from_host(proxy: Arc<dyn HostProxy>) -> Self {
         Self(proxy)
     }
 }
 
+impl std::fmt::Debug for MasonryProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MasonryProxy").finish_non_exhaustive()
+    }
+}
+
+/// The proxy of an app rendered without an event loop, which discards every message.
+#[derive(Debug)]
+pub(crate) struct HeadlessProxy;
+
+impl RawProxy for HeadlessProxy {
+    fn send_message(&self, _path: Arc<[ViewId]>, message: DynMessage) -> Result<(), ProxyError> {
+        Err(ProxyError::DriverFinished(message))
+    }
+    fn dyn_debug(&self) -> &dyn std::fmt::Debug {
+        self
+    }
+}
+
 impl<State, Logic, View> AppDriver for MasonryDriver<State, Logic, View, View::ViewState>
 where
     Logic: FnMut(&mut State) -> View,
@@ -80,9 +138,33 @@ where
                 panic!();
             };
             let (path, message) = *action.downcast::<MessagePackage>().unwrap();
-            // Handle an async path
-            self.current_view
-                .message(&mut self.view_state, &path, message, &mut self.state)
+            let message = match message.downcast::<BackgroundView<View>>() {
+                // Apply a view computed by the worker thread
+                Ok(view) => {
+                    self.apply_background_view(masonry_ctx, view.0);
+                    return;
+                }
+                Err(message) => message,
+            };
+            match message.downcast::<ExternalMessage>() {
+                // Handle a message sent through an `AppHandle`
+                Ok(external) => match external.0.downcast::<SentCommand>() {
+                    // Handle a command sent through a `CommandBus`
+                    Ok(command) => self.deliver_command(*command),
+                    Err(message) => {
+                        if self.external_handlers.handle(&mut self.state, message) {
+                            MessageResult::RequestRebuild
+                        } else {
+                            MessageResult::Nop
+                        }
+                    }
+                },
+                // Handle an async path
+                Err(message) => {
+                    self.current_view
+                        .message(&mut self.view_state, &path, message, &mut self.state)
+                }
+            }
         } else if let Some(id_path) = self.ctx.widget_map.get(&widget_id) {
             self.current_view.message(
                 &mut self.view_state,
@@ -94,6 +176,197 @@ where
             tracing::error!("Got action {action:?} for unknown widget. Did you forget to use `with_action_widget`?");
             return;
         };
+        self.handle_message_result(masonry_ctx, message_result);
+    }
+
+    fn on_unhandled_key(
+        &mut self,
+        masonry_ctx: &mut masonry::DriverCtx<'_>,
+        event: &KeyEvent,
+        modifiers: ModifiersState,
+    ) {
+        if event.state != ElementState::Pressed || event.repeat {
+            return;
+        }
+        let chord = KeyChord::from_event(event, modifiers);
+        let Some(id_path) = self.ctx.shortcut_path(&chord) else {
+            return;
+        };
+        let id_path = id_path.to_vec();
+        let message_result = self.current_view.message(
+            &mut self.view_state,
+            &id_path,
+            Box::new(ShortcutPressed),
+            &mut self.state,
+        );
+        self.handle_message_result(masonry_ctx, message_result);
+    }
+
+    #[cfg(feature = "event_loop_runner")]
+    fn on_start(&mut self, state: &mut event_loop_runner::MasonryState) {
+        self.start(state.get_root());
+        if let Some(placement) = self.window_placement.take() {
+            state.set_window_placement(placement);
+        }
+        if let Some(options) = self.gpu_options.take() {
+            state.set_gpu_options(options);
+        }
+        #[cfg(feature = "tray")]
+        if let Some(options) = self.tray.take() {
+            state.set_tray(options);
+        }
+    }
+
+    fn on_host_start(&mut self, render_root: &mut RenderRoot) {
+        // The window placement is left to the host
+        self.start(render_root);
+    }
+
+    fn on_window_placement(
+        &mut self,
+        masonry_ctx: &mut masonry::DriverCtx<'_>,
+        placement: &WindowPlacement,
+        monitors: &[MonitorInfo],
+    ) {
+        #[cfg(feature = "persistence")]
+        if let Some(persistence) = &mut self.persistence {
+            persistence.set_window_placement(placement);
+            persistence.save_if_due(&self.state);
+        }
+        let Some(handler) = &mut self.on_window_placement else {
+            return;
+        };
+        handler(&mut self.state, placement, monitors);
+        self.handle_message_result(masonry_ctx, MessageResult::RequestRebuild);
+    }
+
+    fn on_gpu_adapter(&mut self, masonry_ctx: &mut masonry::DriverCtx<'_>, info: &GpuInfo) {
+        let Some(handler) = &mut self.on_gpu_adapter else {
+            return;
+        };
+        handler(&mut self.state, info);
+        self.handle_message_result(masonry_ctx, MessageResult::RequestRebuild);
+    }
+
+    fn on_render_error(&mut self, masonry_ctx: &mut masonry::DriverCtx<'_>, error: &RenderError) {
+        let Some(handler) = &mut self.on_render_error else {
+            return;
+        };
+        handler(&mut self.state, error);
+        self.handle_message_result(masonry_ctx, MessageResult::RequestRebuild);
+    }
+
+    fn on_idle(&mut self, masonry_ctx: &mut masonry::DriverCtx<'_>) -> bool {
+        let Some(handler) = &mut self.on_idle else {
+            return false;
+        };
+        let did_work = handler(&mut self.state);
+        if did_work {
+            self.handle_message_result(masonry_ctx, MessageResult::RequestRebuild);
+        }
+        did_work
+    }
+
+    #[cfg(feature = "tray")]
+    fn on_tray_menu(
+        &mut self,
+        masonry_ctx: &mut masonry::DriverCtx<'_>,
+        event: masonry::tray::TrayMenuEvent,
+    ) {
+        // Menu selections are handled like messages sent through an `AppHandle`
+        let message_result = if self
+            .external_handlers
+            .handle(&mut self.state, Box::new(event))
+        {
+            MessageResult::RequestRebuild
+        } else {
+            MessageResult::Nop
+        };
+        self.handle_message_result(masonry_ctx, message_result);
+    }
+
+    #[cfg(feature = "menu")]
+    fn on_menu(
+        &mut self,
+        masonry_ctx: &mut masonry::DriverCtx<'_>,
+        event: masonry::menu::MenuEvent,
+    ) {
+        let Some(id_path) = self.ctx.menu_path() else {
+            return;
+        };
+        let id_path = id_path.to_vec();
+        let message_result = self.current_view.message(
+            &mut self.view_state,
+            &id_path,
+            Box::new(crate::view::MenuItemSelected(event.id)),
+            &mut self.state,
+        );
+        self.handle_message_result(masonry_ctx, message_result);
+    }
+}
+
+impl<State, Logic, View> MasonryDriver<State, Logic, View, View::ViewState>
+where
+    Logic: FnMut(&mut State) -> View,
+    View: WidgetView<State>,
+{
+    /// Give the fonts, the resource cache and the menu of the app to the widgets, once they're created.
+    fn start(&mut self, root: &mut RenderRoot) {
+        // Register all provided fonts
+        // self.fonts is never used again, so we may as well deallocate it.
+        for font in std::mem::take(&mut self.fonts).drain(..) {
+            // We currently don't do anything with the resulting family information,
+            // because we don't have an easy way to return this to the application.
+            drop(root.register_fonts(font));
+        }
+        if let Some(cache) = self.resource_cache.take() {
+            root.set_resource_cache(cache);
+        }
+        #[cfg(feature = "menu")]
+        if let Some(entries) = self.ctx.take_menu_update() {
+            root.edit_root_widget(|mut root| root.ctx.set_menu(entries));
+        }
+    }
+
+    /// Deliver `command` to each of the [`on_command`](crate::view::on_command) views it targets.
+    ///
+    /// The app is rebuilt once, after all of the views have handled the command.
+    fn deliver_command(&mut self, command: SentCommand) -> MessageResult<()> {
+        let paths = self
+            .ctx
+            .command_subscriptions
+            .matching(command.type_id, command.target);
+        if paths.is_empty() {
+            tracing::debug!("Discarding command {command:?} with no subscribers");
+            return MessageResult::Nop;
+        }
+        let mut rebuild = false;
+        for path in paths {
+            let message_result = self.current_view.message(
+                &mut self.view_state,
+                &path,
+                Box::new(DeliveredCommand(command.command.clone())),
+                &mut self.state,
+            );
+            match message_result {
+                MessageResult::Action(()) | MessageResult::RequestRebuild => rebuild = true,
+                MessageResult::Nop => {}
+                MessageResult::Stale(_) => tracing::info!("Discarding command"),
+            }
+        }
+        if rebuild {
+            MessageResult::RequestRebuild
+        } else {
+            MessageResult::Nop
+        }
+    }
+
+    /// Rebuild the app if `message_result` requires it.
+    pub(crate) fn handle_message_result(
+        &mut self,
+        masonry_ctx: &mut masonry::DriverCtx<'_>,
+        message_result: MessageResult<()>,
+    ) {
         let rebuild = match message_result {
             MessageResult::Action(()) => {
                 // It's not entirely clear what to do here
@@ -106,31 +379,52 @@ where
                 false
             }
         };
-        if rebuild {
-            let next_view = (self.logic)(&mut self.state);
-
-            let mut root = masonry_ctx.get_root::<RootWidget<View::Widget>>();
-
-            next_view.rebuild(
-                &self.current_view,
-                &mut self.view_state,
-                &mut self.ctx,
-                RootWidget::child_mut(&mut root),
-            );
-            self.current_view = next_view;
+        if !rebuild {
+            return;
         }
-        if cfg!(debug_assertions) && rebuild && !masonry_ctx.content_changed() {
+        if let Some(background) = &mut self.background {
+            // The view is diffed once the worker thread sends it
+            background.request(&self.state);
+            return;
+        }
+        let next_view = {
+            #[cfg(feature = "profiling")]
+            profiling::scope!("xilem::logic");
+            (self.logic)(&mut self.state)
+        };
+        self.rebuild(masonry_ctx, next_view);
+        if cfg!(debug_assertions) && !masonry_ctx.content_changed() {
             tracing::debug!("Nothing changed as result of action");
         }
     }
-    fn on_start(&mut self, state: &mut event_loop_runner::MasonryState) {
-        let root = state.get_root();
-        // Register all provided fonts
-        // self.fonts is never used again, so we may as well deallocate it.
-        for font in std::mem::take(&mut self.fonts).drain(..) {
-            // We currently don't do anything with the resulting family information,
-            // because we don't have an easy way to return this to the application.
-            drop(root.register_fonts(font));
+
+    /// Diff the view computed by the worker thread against the current view.
+    fn apply_background_view(&mut self, masonry_ctx: &mut masonry::DriverCtx<'_>, view: View) {
+        self.rebuild(masonry_ctx, view);
+        if let Some(background) = &mut self.background {
+            background.finished(&self.state);
+        }
+    }
+
+    fn rebuild(&mut self, masonry_ctx: &mut masonry::DriverCtx<'_>, next_view: View) {
+        #[cfg(feature = "profiling")]
+        profiling::scope!("xilem::rebuild");
+        let mut root = masonry_ctx.get_root::<RootWidget<View::Widget>>();
+
+        next_view.rebuild(
+            &self.current_view,
+            &mut self.view_state,
+            &mut self.ctx,
+            RootWidget::child_mut(&mut root),
+        );
+        #[cfg(feature = "menu")]
+        if let Some(entries) = self.ctx.take_menu_update() {
+            root.ctx.set_menu(entries);
+        }
+        self.current_view = next_view;
+        #[cfg(feature = "persistence")]
+        if let Some(persistence) = &mut self.persistence {
+            persistence.save_if_due(&self.state);
         }
     }
 }
@@ -0,0 +1,257 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for reloading the app logic from a dynamic library whilst the app is running.
+//!
+//! This is only intended to shorten the iteration loop during development, and requires the
+//! `hot_reload` feature.
+//!
+//! The app logic is moved into a separate crate with `crate-type = ["dylib"]`, which exports it:
+//!
+//! ```ignore
+//! #[no_mangle]
+//! pub fn app_logic(state: &mut AppState) -> Box<AnyWidgetView<AppState>> {
+//!     flex((label(format!("Count: {}", state.count)), button("+", |state: &mut AppState| state.count += 1)))
+//!         .boxed()
+//! }
+//! ```
+//!
+//! The app then loads the library with [`HotReload`], and uses [`watch`] to rebuild whenever the library is recompiled:
+//!
+//! ```ignore
+//! const LIBRARY: &str = "target/debug/libmy_app_ui.so";
+//!
+//! // SAFETY: `my_app_ui` is built with the same compiler and dependencies as this app,
+//! // and exports `app_logic` with the expected signature.
+//! let mut library = unsafe { HotReload::new(LIBRARY, "app_logic") }?;
+//! let app = Xilem::new(AppState::default(), move |state: &mut AppState| library.app_logic(state))
+//!     .on_external_message(|_: &mut AppState, _: LibraryChanged| {});
+//! hot_reload::watch(LIBRARY, app.app_handle());
+//! app.run_windowed(EventLoop::with_user_event(), "My App".into())?;
+//! ```
+//!
+//! Running `cargo build -p my_app_ui` then updates the app. The app state is kept, and so is the state of
+//! the widgets (such as the text in a textbox), as long as the type of their view didn't change.
+
+#![allow(
+    unsafe_code,
+    reason = "Loading dynamic libraries is unsafe, and this module is only enabled by an opt-in feature"
+)]
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use libloading::Library;
+
+use crate::{AnyWidgetView, AppHandle};
+
+/// How often [`watch`] checks whether the library has changed.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type AppLogic<State, Action> = fn(&mut State) -> Box<AnyWidgetView<State, Action>>;
+
+/// App logic which is loaded from a dynamic library, and reloaded when the library changes.
+///
+/// See the [module level documentation](self) for how this is used.
+pub struct HotReload<State, Action = ()> {
+    path: PathBuf,
+    symbol: String,
+    logic: AppLogic<State, Action>,
+    /// The modification time of the library when it was last loaded.
+    modified: Option<SystemTime>,
+    /// All the libraries which have been loaded.
+    ///
+    /// These are never unloaded, as views created by an older library might still be in use.
+    libraries: Vec<Library>,
+    /// The temporary copy of the library which is currently in use, if it could be copied.
+    copy: Option<PathBuf>,
+    /// The temporary copies of older versions which couldn't be deleted yet.
+    ///
+    /// Some platforms don't allow deleting a library which is loaded.
+    stale_copies: Vec<PathBuf>,
+}
+
+impl<State, Action> std::fmt::Debug for HotReload<State, Action> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HotReload")
+            .field("path", &self.path)
+            .field("symbol", &self.symbol)
+            .field("loaded", &self.libraries.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<State, Action> HotReload<State, Action> {
+    /// Load the function named `symbol` from the dynamic library at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The library, and every later version of it, must export a function named `symbol`
+    /// with the signature `fn(&mut State) -> Box<AnyWidgetView<State, Action>>`.
+    /// It must be compiled with the same compiler and the same versions of its dependencies
+    /// as this app, which includes the crate defining `State`.
+    ///
+    /// The views of an older version of the library are rebuilt by the views of a newer version
+    /// when their types have the same name. Changing the fields of such a type (including the
+    /// variables captured by closures) without changing its name is undefined behaviour.
+    ///
+    /// Loading the library runs its initialisation routines, see [`Library::new`].
+    ///
+    /// # Errors
+    ///
+    /// If the library can't be loaded, or doesn't contain `symbol`.
+    pub unsafe fn new(
+        path: impl Into<PathBuf>,
+        symbol: impl Into<String>,
+    ) -> Result<Self, libloading::Error> {
+        let path = path.into();
+        let symbol = symbol.into();
+        let modified = modified_time(&path);
+        // SAFETY: Upheld by the caller
+        let (library, logic, copy) = unsafe { load(&path, &symbol, 0) }?;
+        Ok(Self {
+            path,
+            symbol,
+            logic,
+            modified,
+            libraries: vec![library],
+            copy,
+            stale_copies: Vec::new(),
+        })
+    }
+
+    /// Run the app logic of the library, after reloading it if it has changed.
+    ///
+    /// If the new version of the library can't be loaded, the error is logged and the
+    /// previous version is used.
+    pub fn app_logic(&mut self, state: &mut State) -> Box<AnyWidgetView<State, Action>> {
+        let modified = modified_time(&self.path);
+        if modified.is_some() && modified != self.modified {
+            self.modified = modified;
+            // SAFETY: The caller of `new` promised that all versions of the library are valid
+            match unsafe { load(&self.path, &self.symbol, self.libraries.len()) } {
+                Ok((library, logic, copy)) => {
+                    tracing::info!("Reloaded app logic from {}", self.path.display());
+                    self.logic = logic;
+                    self.libraries.push(library);
+                    self.stale_copies
+                        .extend(std::mem::replace(&mut self.copy, copy));
+                    self.stale_copies.retain(|copy| !remove_copy(copy));
+                }
+                Err(error) => {
+                    tracing::error!(
+                        "Failed to reload app logic from {}: {error}",
+                        self.path.display()
+                    );
+                }
+            }
+        }
+        (self.logic)(state)
+    }
+}
+
+impl<State, Action> Drop for HotReload<State, Action> {
+    fn drop(&mut self) {
+        for copy in self.stale_copies.iter().chain(&self.copy) {
+            remove_copy(copy);
+        }
+    }
+}
+
+/// Load a copy of the library at `path`, and get `symbol` from it.
+///
+/// The library is copied so that each version has a different path, as otherwise
+/// the dynamic loader can return the version which is already loaded.
+/// Returns the path of the copy, which should be deleted once it isn't needed.
+///
+/// # Safety
+///
+/// See [`HotReload::new`].
+unsafe fn load<State, Action>(
+    path: &Path,
+    symbol: &str,
+    version: usize,
+) -> Result<(Library, AppLogic<State, Action>, Option<PathBuf>), libloading::Error> {
+    let file_name = path
+        .file_name()
+        .map_or_else(|| "library".into(), |file_name| file_name.to_string_lossy());
+    let copy = std::env::temp_dir().join(format!(
+        "xilem-hot-reload-{}-{version}-{file_name}",
+        std::process::id()
+    ));
+    let copy = match std::fs::copy(path, &copy) {
+        Ok(_) => Some(copy),
+        Err(error) => {
+            tracing::warn!(
+                "Failed to copy {} to {}, loading it in place: {error}",
+                path.display(),
+                copy.display()
+            );
+            None
+        }
+    };
+    // SAFETY: Upheld by the caller
+    let loaded = unsafe {
+        Library::new(copy.as_deref().unwrap_or(path)).and_then(|library| {
+            let logic = *library.get::<AppLogic<State, Action>>(symbol.as_bytes())?;
+            Ok((library, logic))
+        })
+    };
+    match loaded {
+        Ok((library, logic)) => Ok((library, logic, copy)),
+        Err(error) => {
+            if let Some(copy) = &copy {
+                remove_copy(copy);
+            }
+            Err(error)
+        }
+    }
+}
+
+/// Delete a temporary copy of the library, returning whether it was deleted.
+fn remove_copy(copy: &Path) -> bool {
+    match std::fs::remove_file(copy) {
+        Ok(()) => true,
+        Err(error) => {
+            tracing::debug!("Failed to delete {}: {error}", copy.display());
+            false
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The message sent by [`watch`] when the library changes.
+///
+/// A handler for this message needs to be registered with
+/// [`Xilem::on_external_message`](crate::Xilem::on_external_message),
+/// so that the app is rebuilt with the new version of the library.
+#[derive(Debug, Clone, Copy)]
+pub struct LibraryChanged;
+
+/// Send [`LibraryChanged`] through `handle` whenever the file at `path` is modified.
+///
+/// This spawns a thread which polls the modification time of the file, which stops once the app has exited.
+pub fn watch(path: impl Into<PathBuf>, handle: AppHandle<LibraryChanged>) {
+    let path = path.into();
+    std::thread::spawn(move || {
+        let mut modified = modified_time(&path);
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let new_modified = modified_time(&path);
+            if new_modified.is_none() || new_modified == modified {
+                // The library is missing whilst it is being recompiled
+                continue;
+            }
+            modified = new_modified;
+            if handle.send(LibraryChanged).is_err() {
+                // The event loop has ended
+                break;
+            }
+        }
+    });
+}
@@ -40,20 +40,46 @@
 #![expect(clippy::allow_attributes, reason = "Deferred: Noisy")]
 #![expect(clippy::allow_attributes_without_reason, reason = "Deferred: Noisy")]
 
+use std::any::TypeId;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+#[cfg(feature = "event_loop_runner")]
 use masonry::dpi::LogicalSize;
+#[cfg(feature = "event_loop_runner")]
+use masonry::event_loop_runner::WinitHost;
+use masonry::gpu::{GpuInfo, GpuOptions, RenderError};
+use masonry::headless::{HeadlessError, HeadlessOptions, HeadlessRenderer, RgbaImage};
+use masonry::host::AppHost;
+use masonry::monitor::{MonitorInfo, WindowPlacement};
+use masonry::resource_cache::ResourceCache;
 use masonry::widget::{RootWidget, WidgetMut};
-use masonry::{event_loop_runner, Widget, WidgetId, WidgetPod};
+use masonry::{Visibility, Widget, WidgetId, WidgetPod};
+#[cfg(feature = "event_loop_runner")]
 use winit::error::EventLoopError;
+#[cfg(feature = "event_loop_runner")]
 use winit::window::{Window, WindowAttributes};
 
+use crate::app_handle::{ExternalHandlers, ExternalProxy};
+use crate::background::{BackgroundLogic, BackgroundSpawner};
+use crate::command::CommandSubscriptions;
 use crate::core::{
-    AsyncCtx, MessageResult, Mut, RawProxy, SuperElement, View, ViewElement, ViewId,
+    AsyncCtx, Message, MessageResult, Mut, RawProxy, SuperElement, View, ViewElement, ViewId,
     ViewPathTracker, ViewSequence,
 };
+use crate::driver::HeadlessProxy;
+use crate::view::KeyChord;
+#[cfg(feature = "event_loop_runner")]
 pub use masonry::event_loop_runner::{EventLoop, EventLoopBuilder};
+pub use masonry::gpu;
+#[cfg(feature = "menu")]
+pub use masonry::menu;
+pub use masonry::monitor;
+#[cfg(feature = "notifications")]
+pub use masonry::notification;
+pub use masonry::resource_cache;
+#[cfg(feature = "tray")]
+pub use masonry::tray;
 pub use masonry::{dpi, Color, FontWeight, TextAlignment};
 pub use xilem_core as core;
 
@@ -61,11 +87,21 @@ pub use xilem_core as core;
 pub use tokio;
 
 mod any_view;
+mod app_handle;
+mod background;
+mod command;
 mod driver;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
 mod one_of;
+#[cfg(feature = "persistence")]
+mod persistence;
 
+pub mod testing;
 pub mod view;
 pub use any_view::AnyWidgetView;
+pub use app_handle::AppHandle;
+pub use command::{CommandBus, CommandTarget};
 pub use driver::{async_action, MasonryDriver, MasonryProxy, ASYNC_MARKER_WIDGET};
 
 #[must_use = "A Xilem app does nothing unless ran."]
@@ -76,24 +112,230 @@ pub struct Xilem<State, Logic> {
     background_color: Color,
     // Font data to include in loading.
     fonts: Vec<Vec<u8>>,
+    external_proxy: Arc<ExternalProxy>,
+    external_handlers: ExternalHandlers<State>,
+    command_proxies: Vec<Arc<ExternalProxy>>,
+    #[cfg(feature = "persistence")]
+    persistence: Option<persistence::Persistence<State>>,
+    #[cfg(feature = "tray")]
+    tray: Option<tray::TrayOptions>,
+    window_placement: Option<WindowPlacement>,
+    on_window_placement: Option<WindowPlacementHandler<State>>,
+    gpu_options: Option<GpuOptions>,
+    on_gpu_adapter: Option<GpuAdapterHandler<State>>,
+    on_render_error: Option<RenderErrorHandler<State>>,
+    on_idle: Option<IdleHandler<State>>,
+    resource_cache: Option<ResourceCache>,
+    background: Option<BackgroundSpawner<State>>,
 }
 
+pub(crate) type WindowPlacementHandler<State> =
+    Box<dyn FnMut(&mut State, &WindowPlacement, &[MonitorInfo])>;
+pub(crate) type IdleHandler<State> = Box<dyn FnMut(&mut State) -> bool>;
+pub(crate) type GpuAdapterHandler<State> = Box<dyn FnMut(&mut State, &GpuInfo)>;
+pub(crate) type RenderErrorHandler<State> = Box<dyn FnMut(&mut State, &RenderError)>;
+
 impl<State, Logic, View> Xilem<State, Logic>
 where
     Logic: FnMut(&mut State) -> View,
     View: WidgetView<State>,
 {
+    /// Create an app with the initial `state`, whose views are built by `logic`.
+    ///
+    /// On the web, the async runtime has a single thread, which nothing drives, so the async
+    /// views (`task`, `worker`, `memoized_await`, `suspense` and `task_pool`) aren't available there.
     pub fn new(state: State, logic: Logic) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
         let runtime = tokio::runtime::Runtime::new().unwrap();
+        // There are no threads on the web
+        #[cfg(target_arch = "wasm32")]
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
         Xilem {
             state,
             logic,
             runtime,
             background_color: Color::BLACK,
             fonts: Vec::new(),
+            external_proxy: Arc::new(ExternalProxy::new()),
+            external_handlers: ExternalHandlers::default(),
+            command_proxies: Vec::new(),
+            #[cfg(feature = "persistence")]
+            persistence: None,
+            #[cfg(feature = "tray")]
+            tray: None,
+            window_placement: None,
+            on_window_placement: None,
+            gpu_options: None,
+            on_gpu_adapter: None,
+            on_render_error: None,
+            on_idle: None,
+            resource_cache: None,
+            background: None,
         }
     }
 
+    /// Create a handle which other threads can use to send messages of type `M` to this app.
+    ///
+    /// The messages are given to the handler registered with
+    /// [`on_external_message`](Self::on_external_message).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let app = Xilem::new(AppState::default(), app_logic)
+    ///     .on_external_message(|state: &mut AppState, reading: SensorReading| {
+    ///         state.readings.push(reading);
+    ///     });
+    /// let handle = app.app_handle::<SensorReading>();
+    /// std::thread::spawn(move || loop {
+    ///     if handle.send(read_sensor()).is_err() {
+    ///         // The app was closed
+    ///         break;
+    ///     }
+    /// });
+    /// app.run_windowed(EventLoop::with_user_event(), "Sensors".into())?;
+    /// ```
+    pub fn app_handle<M: Message>(&self) -> AppHandle<M> {
+        AppHandle::new(self.external_proxy.clone())
+    }
+
+    /// Deliver the commands sent through `bus` to the [`on_command`](view::on_command) views of this app.
+    ///
+    /// As the bus is usually stored in the app state, it is created before the app.
+    /// Commands sent before the app is run are delivered once it starts.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let commands = CommandBus::new();
+    /// let state = AppState {
+    ///     commands: commands.clone(),
+    ///     ..Default::default()
+    /// };
+    /// let app = Xilem::new(state, app_logic).with_command_bus(&commands);
+    /// app.run_windowed(EventLoop::with_user_event(), "Editor".into())?;
+    /// ```
+    pub fn with_command_bus(mut self, bus: &CommandBus) -> Self {
+        self.command_proxies.push(bus.proxy());
+        self
+    }
+
+    /// Use `cache` as the resource cache of the window, so that the views and the widgets
+    /// of this app share the images and fonts decoded with it.
+    ///
+    /// As the cache is usually stored in the app state, it is created before the app.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let cache = ResourceCache::default();
+    /// let state = AppState {
+    ///     avatars: cache.clone(),
+    ///     ..Default::default()
+    /// };
+    /// let app = Xilem::new(state, app_logic).with_resource_cache(&cache);
+    ///
+    /// fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+    ///     let rows = state.messages.iter().map(|message| {
+    ///         // Each avatar is decoded once, however many messages its sender wrote.
+    ///         let avatar = state.avatars.image(&*message.avatar_url, &message.avatar_bytes);
+    ///         flex((avatar.ok().map(|avatar| image(&avatar)), label(message.text.clone())))
+    ///     });
+    ///     portal(flex(rows.collect::<Vec<_>>()))
+    /// }
+    /// ```
+    pub fn with_resource_cache(mut self, cache: &ResourceCache) -> Self {
+        self.resource_cache = Some(cache.clone());
+        self
+    }
+
+    /// Handle the messages of type `M` sent through an [`AppHandle`].
+    ///
+    /// The app is rebuilt after each message.
+    /// Registering a second handler for the same message type replaces the first one.
+    pub fn on_external_message<M: Message>(
+        mut self,
+        handler: impl FnMut(&mut State, M) + 'static,
+    ) -> Self {
+        self.external_handlers.insert(handler);
+        self
+    }
+
+    /// Show an icon with a menu in the system tray whilst the app runs.
+    ///
+    /// Selecting an item of the menu sends a [`TrayMenuEvent`](tray::TrayMenuEvent), which can
+    /// be handled with [`on_external_message`](Self::on_external_message).
+    #[cfg(feature = "tray")]
+    pub fn with_tray(mut self, options: tray::TrayOptions) -> Self {
+        self.tray = Some(options);
+        self
+    }
+
+    /// Place the window at `placement` when it's created, e.g. to restore the placement
+    /// saved when the app last exited.
+    ///
+    /// If the placement isn't on any of the connected monitors, the window is centered on
+    /// the primary monitor instead.
+    pub fn with_window_placement(mut self, placement: WindowPlacement) -> Self {
+        self.window_placement = Some(placement);
+        self
+    }
+
+    /// Call `handler` when the window is moved or resized, with its new placement and the
+    /// monitors of the system.
+    ///
+    /// The app is rebuilt after each call.
+    pub fn on_window_placement(
+        mut self,
+        handler: impl FnMut(&mut State, &WindowPlacement, &[MonitorInfo]) + 'static,
+    ) -> Self {
+        self.on_window_placement = Some(Box::new(handler));
+        self
+    }
+
+    /// Choose how the GPU adapter which renders the window is picked, e.g. to prefer the
+    /// integrated GPU of laptops.
+    ///
+    /// See the [`gpu`] module for details.
+    pub fn with_gpu_options(mut self, options: GpuOptions) -> Self {
+        self.gpu_options = Some(options);
+        self
+    }
+
+    /// Call `handler` with the GPU adapter the window is rendered with, once it's chosen.
+    ///
+    /// The app is rebuilt after each call.
+    pub fn on_gpu_adapter(mut self, handler: impl FnMut(&mut State, &GpuInfo) + 'static) -> Self {
+        self.on_gpu_adapter = Some(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` when the window couldn't be rendered, e.g. to tell the user their GPU
+    /// isn't supported.
+    ///
+    /// The app is rebuilt after each call. After a [fatal](RenderError::is_fatal) error,
+    /// the app exits once `handler` returns.
+    pub fn on_render_error(
+        mut self,
+        handler: impl FnMut(&mut State, &RenderError) + 'static,
+    ) -> Self {
+        self.on_render_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Call `handler` when the event loop is idle, to do background work in small chunks,
+    /// such as indexing or prefetching, without blocking input.
+    ///
+    /// `handler` should do a chunk of work which takes at most a few milliseconds, and return
+    /// whether it did any work. While it does, it's called again when the event loop is next
+    /// idle, and the app is rebuilt after each call.
+    pub fn on_idle(mut self, handler: impl FnMut(&mut State) -> bool + 'static) -> Self {
+        self.on_idle = Some(Box::new(handler));
+        self
+    }
+
     /// Load a font when this `Xilem` is run.
     ///
     /// This is an interim API whilst font lifecycles are determined.
@@ -103,12 +345,16 @@ where
     }
 
     /// Sets main window background color.
+    ///
+    /// A color with an alpha below 1 makes the window see-through, if it's created
+    /// with [`WindowAttributes::with_transparent`] in [`run_windowed_in`](Self::run_windowed_in).
     pub fn background_color(mut self, color: Color) -> Self {
         self.background_color = color;
         self
     }
 
     // TODO: Make windows a specific view
+    #[cfg(feature = "event_loop_runner")]
     pub fn run_windowed(
         self,
         // We pass in the event loop builder to allow
@@ -130,6 +376,7 @@ where
     }
 
     // TODO: Make windows into a custom view
+    #[cfg(feature = "event_loop_runner")]
     pub fn run_windowed_in(
         self,
         mut event_loop: EventLoopBuilder,
@@ -141,10 +388,44 @@ where
         View: 'static,
     {
         let event_loop = event_loop.build()?;
-        let proxy = event_loop.create_proxy();
+        self.run_in_host(WinitHost::new(event_loop, window_attributes))
+    }
+
+    /// Run the app in `host`, such as the event loop of a game engine.
+    ///
+    /// Messages sent by async views and [`AppHandle`]s are delivered through the proxy of
+    /// the host. See [`masonry::host`] for how to implement a host.
+    pub fn run_in_host<H: AppHost>(self, host: H) -> Result<(), H::Error>
+    where
+        State: 'static,
+        Logic: 'static,
+        View: 'static,
+    {
         let bg_color = self.background_color;
-        let (root_widget, driver) = self.into_driver(Arc::new(MasonryProxy(proxy)));
-        event_loop_runner::run_with(event_loop, window_attributes, root_widget, driver, bg_color)
+        let proxy = MasonryProxy::from_host(host.proxy());
+        let (root_widget, driver) = self.into_driver(Arc::new(proxy));
+        host.run(root_widget, driver, bg_color)
+    }
+
+    /// Build the first view of this app and render it to an image, without a window.
+    ///
+    /// This is intended for generating thumbnails and screenshots for documentation.
+    /// The fonts added with [`with_font`](Self::with_font) are registered as well as
+    /// `options.fonts`. Messages sent by async views and [`AppHandle`]s are discarded.
+    pub fn render_headless(
+        self,
+        renderer: &mut HeadlessRenderer,
+        options: &HeadlessOptions,
+    ) -> Result<RgbaImage, HeadlessError> {
+        let (root_widget, mut driver) = self.into_driver(Arc::new(HeadlessProxy));
+        // The app never ran, so there is nothing to save
+        #[cfg(feature = "persistence")]
+        {
+            driver.persistence = None;
+        }
+        let mut options = options.clone();
+        options.fonts.append(&mut driver.fonts);
+        renderer.render_widget(root_widget, &options)
     }
 
     pub fn into_driver(
@@ -157,12 +438,22 @@ where
         let first_view = (self.logic)(&mut self.state);
         let mut ctx = ViewCtx {
             widget_map: WidgetMap::default(),
+            shortcuts: HashMap::new(),
+            command_subscriptions: CommandSubscriptions::default(),
+            #[cfg(feature = "menu")]
+            menus: Vec::new(),
+            #[cfg(feature = "menu")]
+            menu_changed: false,
             id_path: Vec::new(),
             proxy,
             runtime: self.runtime,
         };
         let (pod, view_state) = first_view.build(&mut ctx);
         let root_widget = RootWidget::from_pod(pod.inner);
+        self.external_proxy.start(ctx.proxy.clone());
+        for proxy in &self.command_proxies {
+            proxy.start(ctx.proxy.clone());
+        }
         let driver = MasonryDriver {
             current_view: first_view,
             logic: self.logic,
@@ -170,11 +461,123 @@ where
             ctx,
             view_state,
             fonts: self.fonts,
+            external_handlers: self.external_handlers,
+            #[cfg(feature = "persistence")]
+            persistence: self.persistence,
+            #[cfg(feature = "tray")]
+            tray: self.tray,
+            window_placement: self.window_placement,
+            on_window_placement: self.on_window_placement,
+            gpu_options: self.gpu_options,
+            on_gpu_adapter: self.on_gpu_adapter,
+            on_render_error: self.on_render_error,
+            on_idle: self.on_idle,
+            resource_cache: self.resource_cache,
+            background: self.background.map(|spawn| spawn(ctx.proxy.clone())),
         };
         (root_widget, driver)
     }
 }
 
+impl<State, Logic, View> Xilem<State, Logic>
+where
+    State: Clone + Send + 'static,
+    Logic: FnMut(&mut State) -> View + Clone + Send + 'static,
+    View: WidgetView<State>,
+{
+    /// Run the app logic on a worker thread, so that input is handled and the previous
+    /// view stays shown whilst an expensive `logic` runs.
+    ///
+    /// When the app needs to be rebuilt, `logic` is called on a snapshot of the state on the
+    /// worker thread. The resulting view is then diffed against the current one on the UI
+    /// thread, between two frames. Messages handled in the meantime are still delivered to
+    /// the current view, and rebuilds they request are coalesced into one, which starts once
+    /// the pending view has been applied.
+    ///
+    /// As `logic` is called on a clone of the state, the changes it makes to the state aren't
+    /// kept. The first view is built on the UI thread, before the app starts.
+    ///
+    /// On the web, where there are no threads, `logic` still runs on the UI thread.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+    ///     // Laying out thousands of rows is expensive.
+    ///     portal(flex(state.rows.iter().map(row_view).collect::<Vec<_>>()))
+    /// }
+    ///
+    /// let app = Xilem::new(AppState::default(), app_logic).with_background_logic();
+    /// app.run_windowed(EventLoop::with_user_event(), "Spreadsheet".into())?;
+    /// ```
+    pub fn with_background_logic(mut self) -> Self {
+        if cfg!(not(target_arch = "wasm32")) {
+            self.background = Some(BackgroundLogic::spawner(self.logic.clone()));
+        }
+        self
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl<State, Logic> Xilem<State, Logic>
+where
+    State: serde::Serialize + serde::de::DeserializeOwned,
+{
+    /// Restore the app state saved by a previous run of the app named `app_name`, and save
+    /// the state when the app exits, as well as periodically whilst it runs.
+    ///
+    /// The state is stored as JSON in the platform's configuration directory:
+    /// `%APPDATA%\app_name` on Windows, `~/Library/Application Support/app_name` on macOS,
+    /// and `$XDG_CONFIG_HOME/app_name` (which defaults to `~/.config/app_name`) elsewhere.
+    /// If there is no saved state, or it can't be read (e.g. because the state type has
+    /// changed), the state given to [`Xilem::new`] is used.
+    ///
+    /// The placement of the window is saved next to the state, in `state.window.json`, whenever
+    /// the state is saved. It is restored when the window is created, unless another placement
+    /// is given with [`with_window_placement`](Self::with_window_placement).
+    ///
+    /// Requires the `persistence` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// #[derive(Default, Serialize, Deserialize)]
+    /// struct Settings {
+    ///     dark_mode: bool,
+    /// }
+    ///
+    /// Xilem::new(Settings::default(), app_logic)
+    ///     .persist("my-tool")
+    ///     .run_windowed(EventLoop::with_user_event(), "My Tool".into())?;
+    /// ```
+    pub fn persist(self, app_name: &str) -> Self {
+        let Some(dir) = persistence::config_dir(app_name) else {
+            tracing::warn!(
+                "Couldn't find a configuration directory, so the app state won't be saved"
+            );
+            return self;
+        };
+        self.persist_to(dir.join("state.json"))
+    }
+
+    /// Restore the app state from the file at `path`, and save the state to it when the app exits,
+    /// as well as periodically whilst it runs.
+    ///
+    /// The placement of the window is saved next to it, with the extension `window.json`.
+    /// See [`persist`](Self::persist) for more details.
+    pub fn persist_to(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        let persistence = persistence::Persistence::new(path.into());
+        if let Some(state) = persistence.load() {
+            self.state = state;
+        }
+        if self.window_placement.is_none() {
+            self.window_placement = persistence.load_window_placement();
+        }
+        self.persistence = Some(persistence);
+        self
+    }
+}
+
 /// A container for a [Masonry](masonry) widget to be used with Xilem.
 ///
 /// Equivalent to [`WidgetPod<W>`], but in the [`xilem`](crate) crate to work around the orphan rule.
@@ -226,6 +629,135 @@ pub trait WidgetView<State, Action = ()>:
     {
         Box::new(self)
     }
+
+    /// Set the name announced by screen readers for this widget, overriding its own.
+    ///
+    /// This is useful when the widget's content doesn't describe it, such as for an icon button.
+    ///
+    /// # Examples
+    /// ```
+    /// use xilem::{view::button, WidgetView};
+    ///
+    /// # fn view<State: 'static>() -> impl WidgetView<State> {
+    /// button("🔍", |_| {}).accessible_name("Search")
+    /// # }
+    /// ```
+    fn accessible_name(self, name: impl Into<String>) -> view::Accessible<Self, State, Action>
+    where
+        State: 'static,
+        Action: 'static,
+        Self: Sized,
+    {
+        view::Accessible::new(self).accessible_name(name)
+    }
+
+    /// Set the description announced by screen readers after this widget's name.
+    fn accessible_description(
+        self,
+        description: impl Into<String>,
+    ) -> view::Accessible<Self, State, Action>
+    where
+        State: 'static,
+        Action: 'static,
+        Self: Sized,
+    {
+        view::Accessible::new(self).accessible_description(description)
+    }
+
+    /// Show `label` before this widget, and tell screen readers that it names this widget.
+    ///
+    /// This replaces the [`labelled_by`](masonry::widget::AccessOverrides::labelled_by) relation
+    /// of this widget.
+    ///
+    /// # Examples
+    /// ```
+    /// use xilem::{view::{label, textbox}, WidgetView};
+    ///
+    /// # fn view() -> impl WidgetView<String> {
+    /// textbox(String::new(), |email: &mut String, new_email| *email = new_email)
+    ///     .labelled_by(label("Email"))
+    /// # }
+    /// ```
+    fn labelled_by<L>(self, label: L) -> view::Labelled<L, Self, State, Action>
+    where
+        State: 'static,
+        Action: 'static,
+        L: WidgetView<State, Action>,
+        Self: Sized,
+    {
+        view::Labelled::new(self, label)
+    }
+
+    /// Give this widget a string tag, which identifies it in trace spans, debug dumps and tests.
+    ///
+    /// Unlike widget ids, tags are the same from one run of the app to the next.
+    ///
+    /// # Examples
+    /// ```
+    /// use xilem::{view::button, WidgetView};
+    ///
+    /// # fn view<State: 'static>() -> impl WidgetView<State> {
+    /// button("Save", |_| {}).debug_tag("save-button")
+    /// # }
+    /// ```
+    ///
+    /// The widget can then be found with
+    /// [`TestHarness::get_widget_by_tag`](masonry::testing::TestHarness::get_widget_by_tag).
+    fn debug_tag(self, tag: impl Into<Arc<str>>) -> view::DebugTag<Self, State, Action>
+    where
+        State: 'static,
+        Action: 'static,
+        Self: Sized,
+    {
+        view::DebugTag::new(self, tag.into())
+    }
+
+    /// Show or hide this widget, which keeps its space in the layout while hidden.
+    ///
+    /// Unlike leaving the view out, e.g. with [`bool::then`], this keeps the state of
+    /// the widget while it's hidden. Hidden widgets don't get pointer events or text focus.
+    ///
+    /// Use either this or [`collapsed`](Self::collapsed) on a view, not both.
+    ///
+    /// # Examples
+    /// ```
+    /// use xilem::{view::textbox, WidgetView};
+    ///
+    /// # fn view(show_search: bool) -> impl WidgetView<String> {
+    /// textbox(String::new(), |query: &mut String, new_query| *query = new_query)
+    ///     .visible(show_search)
+    /// # }
+    /// ```
+    fn visible(self, visible: bool) -> view::Hideable<Self, State, Action>
+    where
+        State: 'static,
+        Action: 'static,
+        Self: Sized,
+    {
+        let visibility = if visible {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        view::Hideable::new(self, visibility)
+    }
+
+    /// Collapse this widget, which then takes no space in the layout, or show it.
+    ///
+    /// Like [`visible`](Self::visible), this keeps the state of the widget while it's collapsed.
+    fn collapsed(self, collapsed: bool) -> view::Hideable<Self, State, Action>
+    where
+        State: 'static,
+        Action: 'static,
+        Self: Sized,
+    {
+        let visibility = if collapsed {
+            Visibility::Collapsed
+        } else {
+            Visibility::Visible
+        };
+        view::Hideable::new(self, visibility)
+    }
 }
 
 impl<V, State, Action, W> WidgetView<State, Action> for V
@@ -267,6 +799,16 @@ pub struct ViewCtx {
     ///
     /// This includes only the widgets which might send actions
     widget_map: WidgetMap,
+    /// The paths of the [`shortcut`](view::shortcut) views for each chord, in the order they were added.
+    shortcuts: HashMap<KeyChord, Vec<Vec<ViewId>>>,
+    /// The paths of the [`on_command`](view::on_command) views for each command type.
+    command_subscriptions: CommandSubscriptions,
+    /// The paths of the [`menu`](view::menu) views, along with their entries, in the order they were added.
+    #[cfg(feature = "menu")]
+    menus: Vec<(Vec<ViewId>, Vec<menu::MenuEntry>)>,
+    /// Whether the menu shown in the window needs to be set again.
+    #[cfg(feature = "menu")]
+    menu_changed: bool,
     id_path: Vec<ViewId>,
     proxy: Arc<dyn RawProxy>,
     runtime: tokio::runtime::Runtime,
@@ -323,6 +865,100 @@ impl ViewCtx {
         self.widget_map.remove(&widget.ctx.widget_id());
     }
 
+    /// Record that presses of `chord` should be routed to this view.
+    pub fn record_shortcut(&mut self, chord: KeyChord) {
+        let path = self.id_path.clone();
+        self.shortcuts.entry(chord).or_default().push(path);
+    }
+
+    /// Stop routing presses of `chord` to this view.
+    pub fn remove_shortcut(&mut self, chord: &KeyChord) {
+        let Some(paths) = self.shortcuts.get_mut(chord) else {
+            return;
+        };
+        if let Some(index) = paths.iter().rposition(|path| *path == self.id_path) {
+            paths.remove(index);
+        }
+        if paths.is_empty() {
+            self.shortcuts.remove(chord);
+        }
+    }
+
+    /// The path of the view which presses of `chord` should be routed to.
+    pub(crate) fn shortcut_path(&self, chord: &KeyChord) -> Option<&[ViewId]> {
+        self.shortcuts
+            .get(chord)
+            .and_then(|paths| paths.last())
+            .map(Vec::as_slice)
+    }
+
+    /// Record that the commands of type `type_id` sent to `scope` should be routed to this view.
+    ///
+    /// Commands sent to [`CommandTarget::All`] are routed to this view whatever its `scope`.
+    pub fn record_command_subscription(&mut self, type_id: TypeId, scope: Option<u64>) {
+        let path = self.id_path.clone();
+        self.command_subscriptions.insert(type_id, scope, path);
+    }
+
+    /// Stop routing the commands of type `type_id` sent to `scope` to this view.
+    pub fn remove_command_subscription(&mut self, type_id: TypeId, scope: Option<u64>) {
+        self.command_subscriptions
+            .remove(type_id, scope, &self.id_path);
+    }
+
+    /// Record that this view shows `entries` in the menu bar, and that their selections should
+    /// be routed to it.
+    #[cfg(feature = "menu")]
+    pub fn record_menu(&mut self, entries: Vec<menu::MenuEntry>) {
+        let path = self.id_path.clone();
+        self.menus.push((path, entries));
+        self.menu_changed = true;
+    }
+
+    /// Change the entries this view shows in the menu bar.
+    #[cfg(feature = "menu")]
+    pub fn update_menu(&mut self, entries: Vec<menu::MenuEntry>) {
+        if let Some((_, menu)) = self
+            .menus
+            .iter_mut()
+            .rfind(|(path, _)| *path == self.id_path)
+        {
+            *menu = entries;
+            self.menu_changed = true;
+        }
+    }
+
+    /// Stop showing the menu of this view.
+    #[cfg(feature = "menu")]
+    pub fn remove_menu(&mut self) {
+        if let Some(index) = self
+            .menus
+            .iter()
+            .rposition(|(path, _)| *path == self.id_path)
+        {
+            self.menus.remove(index);
+            self.menu_changed = true;
+        }
+    }
+
+    /// The path of the view whose menu is shown, which its selections should be routed to.
+    #[cfg(feature = "menu")]
+    pub(crate) fn menu_path(&self) -> Option<&[ViewId]> {
+        self.menus.last().map(|(path, _)| path.as_slice())
+    }
+
+    /// The entries to show in the menu bar, if they changed since this was last called.
+    ///
+    /// If several menu views are in the tree, the one added most recently is shown.
+    #[cfg(feature = "menu")]
+    pub(crate) fn take_menu_update(&mut self) -> Option<Vec<menu::MenuEntry>> {
+        if !std::mem::take(&mut self.menu_changed) {
+            return None;
+        }
+        let entries = self.menus.last().map(|(_, entries)| entries.clone());
+        Some(entries.unwrap_or_default())
+    }
+
     pub fn runtime(&self) -> &tokio::runtime::Runtime {
         &self.runtime
     }
@@ -0,0 +1,196 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use masonry::dpi::{PhysicalPosition, PhysicalSize};
+use masonry::monitor::WindowPlacement;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// The minimum time between two saves of the state whilst the app is running.
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+type Serializer<State> = Box<dyn Fn(&State) -> serde_json::Result<String>>;
+
+/// Saves the app state to a file, so that it can be restored the next time the app runs.
+///
+/// The placement of the window is saved next to it, see [`placement_path`].
+pub(crate) struct Persistence<State> {
+    path: PathBuf,
+    serialize: Serializer<State>,
+    last_saved: Instant,
+    /// The placement of the window, if it has been moved or resized since the app started.
+    window_placement: Option<WindowPlacement>,
+}
+
+impl<State> Persistence<State> {
+    pub(crate) fn new(path: PathBuf) -> Self
+    where
+        State: Serialize,
+    {
+        Self {
+            path,
+            serialize: Box::new(|state| serde_json::to_string_pretty(state)),
+            last_saved: Instant::now(),
+            window_placement: None,
+        }
+    }
+
+    /// Load the state saved by a previous run of the app.
+    ///
+    /// Returns `None` if there is no saved state, or if it can't be read, in which case the error is logged.
+    pub(crate) fn load(&self) -> Option<State>
+    where
+        State: DeserializeOwned,
+    {
+        let data = match std::fs::read_to_string(&self.path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                tracing::warn!(
+                    "Failed to read the app state from {}: {error}",
+                    self.path.display()
+                );
+                return None;
+            }
+        };
+        match serde_json::from_str(&data) {
+            Ok(state) => Some(state),
+            Err(error) => {
+                tracing::warn!(
+                    "Failed to parse the app state from {}, using the default state: {error}",
+                    self.path.display()
+                );
+                None
+            }
+        }
+    }
+
+    /// Load the placement of the window saved by a previous run of the app.
+    ///
+    /// Returns `None` if there is no saved placement, or if it can't be read, in which case the error is logged.
+    pub(crate) fn load_window_placement(&self) -> Option<WindowPlacement> {
+        let path = placement_path(&self.path);
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                tracing::warn!(
+                    "Failed to read the window placement from {}: {error}",
+                    path.display()
+                );
+                return None;
+            }
+        };
+        let placement = serde_json::from_str(&data)
+            .ok()
+            .and_then(|value| placement_from_json(&value));
+        if placement.is_none() {
+            tracing::warn!(
+                "Failed to parse the window placement from {}",
+                path.display()
+            );
+        }
+        placement
+    }
+
+    /// Record the placement of the window, which is saved with the state.
+    pub(crate) fn set_window_placement(&mut self, placement: &WindowPlacement) {
+        self.window_placement = Some(placement.clone());
+    }
+
+    /// Save `state`, unless it was saved recently.
+    pub(crate) fn save_if_due(&mut self, state: &State) {
+        if self.last_saved.elapsed() >= SAVE_INTERVAL {
+            self.save(state);
+        }
+    }
+
+    /// Save `state`, logging any errors.
+    pub(crate) fn save(&mut self, state: &State) {
+        self.last_saved = Instant::now();
+        let result = (self.serialize)(state)
+            .map_err(std::io::Error::from)
+            .and_then(|data| write_atomically(&self.path, &data));
+        if let Err(error) = result {
+            tracing::error!(
+                "Failed to save the app state to {}: {error}",
+                self.path.display()
+            );
+        }
+        if let Some(placement) = &self.window_placement {
+            let path = placement_path(&self.path);
+            let data = placement_to_json(placement).to_string();
+            if let Err(error) = write_atomically(&path, &data) {
+                tracing::error!(
+                    "Failed to save the window placement to {}: {error}",
+                    path.display()
+                );
+            }
+        }
+    }
+}
+
+/// The file the window placement is saved to, next to the state saved at `path`.
+///
+/// This is `state.window.json` for `state.json`.
+fn placement_path(path: &Path) -> PathBuf {
+    path.with_extension("window.json")
+}
+
+fn placement_to_json(placement: &WindowPlacement) -> Value {
+    json!({
+        "x": placement.position.x,
+        "y": placement.position.y,
+        "width": placement.size.width,
+        "height": placement.size.height,
+        "maximized": placement.maximized,
+        "monitor": placement.monitor,
+    })
+}
+
+fn placement_from_json(value: &Value) -> Option<WindowPlacement> {
+    let int = |key: &str| -> Option<i32> { value.get(key)?.as_i64()?.try_into().ok() };
+    let uint = |key: &str| -> Option<u32> { value.get(key)?.as_u64()?.try_into().ok() };
+    Some(WindowPlacement {
+        position: PhysicalPosition::new(int("x")?, int("y")?),
+        size: PhysicalSize::new(uint("width")?, uint("height")?),
+        maximized: value.get("maximized")?.as_bool()?,
+        monitor: value
+            .get("monitor")
+            .and_then(Value::as_u64)
+            .and_then(|monitor| monitor.try_into().ok()),
+    })
+}
+
+/// Write `data` to a temporary file which then replaces `path`, so that
+/// the previous state isn't lost if the app exits whilst writing.
+fn write_atomically(path: &Path, data: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temporary = path.with_extension("json.tmp");
+    std::fs::write(&temporary, data)?;
+    std::fs::rename(&temporary, path)
+}
+
+/// The platform's directory for the configuration of the app named `app_name`.
+///
+/// This is `%APPDATA%\app_name` on Windows, `~/Library/Application Support/app_name` on macOS,
+/// and `$XDG_CONFIG_HOME/app_name` (which defaults to `~/.config/app_name`) elsewhere.
+pub(crate) fn config_dir(app_name: &str) -> Option<PathBuf> {
+    let env_path = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+    let base = if cfg!(target_os = "windows") {
+        PathBuf::from(env_path("APPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(env_path("HOME")?).join("Library/Application Support")
+    } else {
+        env_path("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from(env_path("HOME")?).join(".config")))?
+    };
+    Some(base.join(app_name))
+}
@@ -0,0 +1,308 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tools for testing the view logic of Xilem apps.
+//!
+//! An [`AppTestHarness`] runs an app in Masonry's [`TestHarness`], without a window or GPU.
+//! Tests can interact with the widgets as a user would, or dispatch actions and messages to
+//! views directly, and then check the app state and the rebuilt widget tree.
+//!
+//! ```ignore
+//! use xilem::testing::AppTestHarness;
+//! use xilem::view::button;
+//! use xilem::{WidgetView, Xilem};
+//!
+//! fn app_logic(count: &mut u32) -> impl WidgetView<u32> {
+//!     button(format!("Clicked {count} times"), |count| *count += 1).debug_tag("counter")
+//! }
+//!
+//! let mut harness = AppTestHarness::create(Xilem::new(0, app_logic));
+//! let button_id = harness.harness().get_widget_by_tag("counter").id();
+//! harness.click(button_id);
+//! assert_eq!(*harness.state(), 1);
+//! ```
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use masonry::testing::{TestHarness, HARNESS_DEFAULT_SIZE};
+use masonry::{Action, AppDriver as _, DriverCtx, Size, WidgetId};
+
+use crate::core::{DynMessage, Message, MessageResult, ProxyError, RawProxy, ViewId};
+use crate::driver::{async_action, MessagePackage, ASYNC_MARKER_WIDGET};
+use crate::{MasonryDriver, WidgetView, Xilem};
+
+/// A Xilem app running in a [`TestHarness`].
+///
+/// Every action emitted by a widget is routed to its view, as it would be by the event loop,
+/// and the app is rebuilt as needed. Messages sent to the app from async views, [`AppHandle`]s
+/// and [`CommandBus`]es are queued until [`process_messages`](Self::process_messages) is called,
+/// so tests decide when they are delivered.
+///
+/// [`AppHandle`]: crate::AppHandle
+/// [`CommandBus`]: crate::CommandBus
+pub struct AppTestHarness<State, Logic, View>
+where
+    Logic: FnMut(&mut State) -> View,
+    View: WidgetView<State>,
+{
+    harness: TestHarness,
+    driver: MasonryDriver<State, Logic, View, View::ViewState>,
+    messages: Receiver<MessagePackage>,
+}
+
+/// The proxy of an app in an [`AppTestHarness`], which queues every message.
+#[derive(Debug)]
+struct TestProxy(Mutex<Sender<MessagePackage>>);
+
+impl RawProxy for TestProxy {
+    fn send_message(&self, path: Arc<[ViewId]>, message: DynMessage) -> Result<(), ProxyError> {
+        match self.0.lock().unwrap().send((path, message)) {
+            Ok(()) => Ok(()),
+            Err(err) => Err(ProxyError::DriverFinished(err.0 .1)),
+        }
+    }
+    fn dyn_debug(&self) -> &dyn std::fmt::Debug {
+        self
+    }
+}
+
+impl<State, Logic, View> AppTestHarness<State, Logic, View>
+where
+    Logic: FnMut(&mut State) -> View,
+    View: WidgetView<State>,
+{
+    /// Build the first view of `app` in a harness of [`HARNESS_DEFAULT_SIZE`].
+    pub fn create(app: Xilem<State, Logic>) -> Self {
+        Self::create_with_size(app, HARNESS_DEFAULT_SIZE)
+    }
+
+    /// Build the first view of `app` in a harness of the given window size.
+    ///
+    /// The fonts added with [`Xilem::with_font`] aren't registered, so that text is laid out
+    /// with the harness's test font.
+    pub fn create_with_size(app: Xilem<State, Logic>, window_size: Size) -> Self {
+        let (sender, messages) = channel();
+        let background_color = app.background_color;
+        let (root_widget, mut driver) = app.into_driver(Arc::new(TestProxy(Mutex::new(sender))));
+        // Tests shouldn't overwrite the state saved by the real app
+        #[cfg(feature = "persistence")]
+        {
+            driver.persistence = None;
+        }
+        driver.fonts.clear();
+        let harness = TestHarness::create_with(root_widget, window_size, background_color);
+        let mut this = Self {
+            harness,
+            driver,
+            messages,
+        };
+        // The event loop runner shows the menu of the first view when it starts
+        #[cfg(feature = "menu")]
+        if let Some(entries) = this.driver.ctx.take_menu_update() {
+            this.harness
+                .edit_root_widget(|mut root| root.ctx.set_menu(entries));
+        }
+        // Deliver the messages sent before the app was built
+        this.process_messages();
+        this
+    }
+
+    // --- MARK: INTERACT ---
+
+    /// Interact with the widgets of the app, e.g. through [`TestHarness::mouse_click_on`]
+    /// or [`TestHarness::keyboard_type_chars`].
+    ///
+    /// The actions emitted by the widgets are then routed to their views.
+    pub fn interact<R>(&mut self, f: impl FnOnce(&mut TestHarness) -> R) -> R {
+        let res = f(&mut self.harness);
+        self.process_actions();
+        res
+    }
+
+    /// Click on the widget `id`, e.g. to press a button.
+    pub fn click(&mut self, id: WidgetId) {
+        self.interact(|harness| harness.mouse_click_on(id));
+    }
+
+    /// Focus the widget `id` and type `text` into it, e.g. to edit a text input.
+    pub fn type_text(&mut self, id: WidgetId, text: &str) {
+        self.interact(|harness| {
+            harness.focus_on(Some(id));
+            harness.keyboard_type_chars(text);
+        });
+    }
+
+    /// Route `action` to the view of the widget `id`, as if the widget had emitted it.
+    ///
+    /// This is useful for actions which are hard to trigger through events.
+    pub fn dispatch_action(&mut self, id: WidgetId, action: Action) {
+        self.with_driver_ctx(|driver, ctx| driver.on_action(ctx, id, action));
+        self.process_actions();
+    }
+
+    /// Select the item `id` of the menu bar, as set by a [`menu`](crate::view::menu) view.
+    ///
+    /// See [`TestHarness::menu`] for the entries of the menu bar.
+    #[cfg(feature = "menu")]
+    pub fn select_menu_item(&mut self, id: &str) {
+        let event = masonry::menu::MenuEvent { id: id.into() };
+        self.with_driver_ctx(|driver, ctx| driver.on_menu(ctx, event));
+        self.process_actions();
+    }
+
+    /// Send `message` to the view at `path`, as an async view would.
+    ///
+    /// See [`view_path`](Self::view_path) to find the path of the view of a widget.
+    pub fn send_message(&mut self, path: &[ViewId], message: impl Message) {
+        let action = async_action(path.into(), Box::new(message));
+        self.dispatch_action(ASYNC_MARKER_WIDGET, action);
+    }
+
+    /// Deliver the queued messages sent from async views, [`AppHandle`](crate::AppHandle)s
+    /// and [`CommandBus`](crate::CommandBus)es, in the order they were sent.
+    ///
+    /// Returns the number of messages delivered, including those sent whilst delivering them.
+    pub fn process_messages(&mut self) -> usize {
+        let mut count = 0;
+        while let Ok((path, message)) = self.messages.try_recv() {
+            count += 1;
+            self.dispatch_action(ASYNC_MARKER_WIDGET, async_action(path, message));
+        }
+        count
+    }
+
+    /// Wait up to `timeout` for a message sent from another thread, e.g. by a worker or a
+    /// [background app logic](Xilem::with_background_logic), then deliver the queued messages.
+    ///
+    /// Returns the number of messages delivered, which is zero if none arrived in time.
+    pub fn wait_for_messages(&mut self, timeout: Duration) -> usize {
+        match self.messages.recv_timeout(timeout) {
+            Ok((path, message)) => {
+                self.dispatch_action(ASYNC_MARKER_WIDGET, async_action(path, message));
+                1 + self.process_messages()
+            }
+            Err(_) => 0,
+        }
+    }
+
+    /// Change the app state, then rebuild the app.
+    pub fn edit_state<R>(&mut self, f: impl FnOnce(&mut State) -> R) -> R {
+        let res = f(&mut self.driver.state);
+        self.with_driver_ctx(|driver, ctx| {
+            driver.handle_message_result(ctx, MessageResult::RequestRebuild);
+        });
+        self.process_actions();
+        res
+    }
+
+    /// Route the actions emitted by widgets to their views, until there are none left.
+    fn process_actions(&mut self) {
+        while let Some((action, id)) = self.harness.pop_action() {
+            self.with_driver_ctx(|driver, ctx| {
+                driver.on_action(ctx, id, action);
+            });
+        }
+    }
+
+    fn with_driver_ctx(
+        &mut self,
+        f: impl FnOnce(&mut MasonryDriver<State, Logic, View, View::ViewState>, &mut DriverCtx<'_>),
+    ) {
+        let driver = &mut self.driver;
+        self.harness.edit_root_widget(|root| {
+            let mut ctx = DriverCtx {
+                main_root_widget: root,
+            };
+            f(driver, &mut ctx);
+        });
+    }
+
+    // --- MARK: GETTERS ---
+
+    /// The current app state.
+    pub fn state(&self) -> &State {
+        &self.driver.state
+    }
+
+    /// The view returned by the last run of the app logic.
+    pub fn view(&self) -> &View {
+        &self.driver.current_view
+    }
+
+    /// The harness the app's widgets run in.
+    ///
+    /// Use [`interact`](Self::interact) to send it events.
+    pub fn harness(&self) -> &TestHarness {
+        &self.harness
+    }
+
+    /// The path of the view which the actions of the widget `id` are routed to.
+    pub fn view_path(&self, id: WidgetId) -> Option<&[ViewId]> {
+        self.driver.ctx.widget_map.get(&id).map(Vec::as_slice)
+    }
+
+    /// The widget whose actions are routed to the view at `path`, if there is one.
+    pub fn widget_at_path(&self, path: &[ViewId]) -> Option<WidgetId> {
+        self.driver
+            .ctx
+            .widget_map
+            .iter()
+            .find(|(_, widget_path)| widget_path.as_slice() == path)
+            .map(|(id, _)| *id)
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use masonry::widget::Label;
+    use masonry::PointerButton;
+
+    use super::*;
+    use crate::view::{button, flex, label};
+
+    fn counter(count: &mut u32) -> impl WidgetView<u32> {
+        flex((
+            label(format!("Count: {count}")).debug_tag("count"),
+            button("Increment", |count: &mut u32| *count += 1).debug_tag("increment"),
+        ))
+    }
+
+    fn count_text(harness: &TestHarness) -> String {
+        let label = harness.get_widget_by_tag("count");
+        label.downcast::<Label>().unwrap().text().to_string()
+    }
+
+    #[test]
+    fn click_updates_state_and_view() {
+        let mut harness = AppTestHarness::create(Xilem::new(0, counter));
+        assert_eq!(count_text(harness.harness()), "Count: 0");
+
+        let button_id = harness.harness().get_widget_by_tag("increment").id();
+        harness.click(button_id);
+        assert_eq!(*harness.state(), 1);
+        assert_eq!(count_text(harness.harness()), "Count: 1");
+    }
+
+    #[test]
+    fn dispatched_actions_reach_their_view() {
+        let mut harness = AppTestHarness::create(Xilem::new(0, counter));
+        let button_id = harness.harness().get_widget_by_tag("increment").id();
+        assert!(harness.view_path(button_id).is_some());
+
+        harness.dispatch_action(button_id, Action::ButtonPressed(PointerButton::Primary));
+        harness.dispatch_action(button_id, Action::ButtonPressed(PointerButton::Secondary));
+        // Only primary clicks press the button
+        assert_eq!(*harness.state(), 1);
+        assert_eq!(count_text(harness.harness()), "Count: 1");
+    }
+
+    #[test]
+    fn edited_state_rebuilds_view() {
+        let mut harness = AppTestHarness::create(Xilem::new(0, counter));
+        harness.edit_state(|count| *count = 41);
+        assert_eq!(count_text(harness.harness()), "Count: 41");
+    }
+}
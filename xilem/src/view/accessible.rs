@@ -0,0 +1,99 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use crate::core::{DynMessage, MessageResult, Mut, View, ViewId, ViewMarker};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// The View for [`WidgetView::accessible_name`] and [`WidgetView::accessible_description`].
+///
+/// This overrides the accessibility properties of the widget created by the inner view,
+/// which is useful when the widget's content doesn't describe it, such as for an icon button.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Accessible<V, State, Action = ()> {
+    inner: V,
+    name: Option<String>,
+    description: Option<String>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> Accessible<V, State, Action> {
+    pub(crate) fn new(inner: V) -> Self {
+        Self {
+            inner,
+            name: None,
+            description: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Set the name announced by screen readers for this widget.
+    pub fn accessible_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the description announced by screen readers after this widget's name.
+    pub fn accessible_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+impl<V, State, Action> ViewMarker for Accessible<V, State, Action> {}
+impl<V, State, Action> View<State, Action, ViewCtx> for Accessible<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = Pod<V::Widget>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (mut pod, state) = self.inner.build(ctx);
+        if let Some(overrides) = pod.inner.access_overrides_mut() {
+            overrides.name.clone_from(&self.name);
+            overrides.description.clone_from(&self.description);
+        }
+        (pod, state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        self.inner
+            .rebuild(&prev.inner, view_state, ctx, element.reborrow_mut());
+        // Compare with the widget rather than `prev`, in case the inner view replaced its widget
+        let overrides = element.ctx.access_overrides();
+        if overrides.name != self.name || overrides.description != self.description {
+            let overrides = element.ctx.access_overrides_mut();
+            overrides.name.clone_from(&self.name);
+            overrides.description.clone_from(&self.description);
+        }
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<Self::Element>,
+    ) {
+        self.inner.teardown(view_state, ctx, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.inner.message(view_state, id_path, message, app_state)
+    }
+}
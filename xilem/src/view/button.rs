@@ -3,6 +3,7 @@
 
 use masonry::text::ArcStr;
 use masonry::widget;
+pub use masonry::widget::AutoRepeat;
 pub use masonry::PointerButton;
 
 use crate::core::{DynMessage, Mut, View, ViewMarker};
@@ -16,6 +17,7 @@ pub fn button<State, Action>(
 {
     Button {
         label: label.into(),
+        auto_repeat: None,
         callback: move |state: &mut State, button| match button {
             PointerButton::Primary => MessageResult::Action(callback(state)),
             _ => MessageResult::Nop,
@@ -31,6 +33,7 @@ pub fn button_any_pointer<State, Action>(
 {
     Button {
         label: label.into(),
+        auto_repeat: None,
         callback: move |state: &mut State, button| MessageResult::Action(callback(state, button)),
     }
 }
@@ -38,9 +41,21 @@ pub fn button_any_pointer<State, Action>(
 #[must_use = "View values do nothing unless provided to Xilem."]
 pub struct Button<F> {
     label: ArcStr,
+    auto_repeat: Option<AutoRepeat>,
     callback: F,
 }
 
+impl<F> Button<F> {
+    /// Call the callback repeatedly while the button is held down, as well as when it's
+    /// first pressed, instead of when it's released.
+    ///
+    /// See [`widget::Button::with_auto_repeat`].
+    pub fn auto_repeat(mut self, auto_repeat: AutoRepeat) -> Self {
+        self.auto_repeat = Some(auto_repeat);
+        self
+    }
+}
+
 impl<F> ViewMarker for Button<F> {}
 impl<F, State, Action> View<State, Action, ViewCtx> for Button<F>
 where
@@ -50,7 +65,13 @@ where
     type ViewState = ();
 
     fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
-        ctx.with_leaf_action_widget(|ctx| ctx.new_pod(widget::Button::new(self.label.clone())))
+        ctx.with_leaf_action_widget(|ctx| {
+            let mut button = widget::Button::new(self.label.clone());
+            if let Some(auto_repeat) = self.auto_repeat {
+                button = button.with_auto_repeat(auto_repeat);
+            }
+            ctx.new_pod(button)
+        })
     }
 
     fn rebuild(
@@ -63,6 +84,9 @@ where
         if prev.label != self.label {
             widget::Button::set_text(&mut element, self.label.clone());
         }
+        if prev.auto_repeat != self.auto_repeat {
+            widget::Button::set_auto_repeat(&mut element, self.auto_repeat);
+        }
     }
 
     fn teardown(&self, _: &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
@@ -0,0 +1,249 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::text::ArcStr;
+use masonry::widget::{self, FilterListAction, WidgetMut};
+
+use crate::core::{DynMessage, MessageResult, Mut, View, ViewId, ViewMarker};
+use crate::view::KeyChord;
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// A searchable list of commands over `content`, opened and closed by pressing `chord`.
+///
+/// The palette is shown above `content`, with the keyboard focus in its search box. Typing
+/// filters the commands, the arrow keys select one, and <kbd>Enter</kbd> or clicking runs it
+/// by calling `on_choose` with its index in `commands`. The palette closes when a command is
+/// chosen, or when <kbd>Escape</kbd> is pressed.
+///
+/// Like [`shortcut`](crate::view::shortcut), the chord is only used whilst no widget handles
+/// the key press.
+///
+/// This uses the Masonry [`FilterList`](masonry::widget::FilterList) widget.
+///
+/// # Examples
+///
+/// ```ignore
+/// const COMMANDS: [&str; 3] = ["New file", "Open file", "Quit"];
+///
+/// fn app_logic(state: &mut Editor) -> impl WidgetView<Editor> {
+///     command_palette(
+///         editor_view(state),
+///         KeyChord::primary(Key::Character("p".into())),
+///         COMMANDS,
+///         |state: &mut Editor, command| match command {
+///             0 => state.new_file(),
+///             1 => state.open_file(),
+///             _ => state.quit(),
+///         },
+///     )
+/// }
+/// ```
+pub fn command_palette<State, Action, V, F>(
+    content: V,
+    chord: KeyChord,
+    commands: impl IntoIterator<Item = impl Into<ArcStr>>,
+    on_choose: F,
+) -> CommandPalette<V, F, State, Action>
+where
+    V: WidgetView<State, Action>,
+    F: Fn(&mut State, usize) -> Action + Send + Sync + 'static,
+{
+    CommandPalette {
+        content,
+        chord,
+        commands: commands.into_iter().map(Into::into).collect(),
+        on_choose,
+        phantom: PhantomData,
+    }
+}
+
+/// The View for [`command_palette`].
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct CommandPalette<V, F, State, Action = ()> {
+    content: V,
+    chord: KeyChord,
+    commands: Vec<ArcStr>,
+    on_choose: F,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+/// The view state of a [`CommandPalette`].
+pub struct CommandPaletteState<ContentState> {
+    content: ContentState,
+    /// Whether the palette should be open.
+    open: bool,
+    /// Whether the palette is in the widget tree.
+    shown: bool,
+}
+
+const CONTENT_VIEW_ID: ViewId = ViewId::new(0);
+const SHORTCUT_VIEW_ID: ViewId = ViewId::new(1);
+const PALETTE_VIEW_ID: ViewId = ViewId::new(2);
+
+impl<V, F, State, Action> CommandPalette<V, F, State, Action> {
+    fn show_palette(&self, ctx: &mut ViewCtx, element: &mut WidgetMut<'_, widget::Flex>) {
+        let list = widget::FilterList::new(self.commands.iter().cloned());
+        let search_id = list.search_id();
+        let pod = ctx.with_id(PALETTE_VIEW_ID, |ctx| {
+            // The search box submits an action for each edit, which we ignore.
+            ctx.record_action(search_id);
+            ctx.with_action_widget(|ctx| ctx.new_pod(list))
+        });
+        widget::Flex::insert_child_pod(element, 0, pod.inner.boxed());
+        element.ctx.set_focus(search_id);
+    }
+
+    fn hide_palette(&self, ctx: &mut ViewCtx, element: &mut WidgetMut<'_, widget::Flex>) {
+        {
+            let mut child = widget::Flex::child_mut(element, 0)
+                .expect("the command palette should be the first child");
+            let mut list = child.downcast::<widget::FilterList>();
+            ctx.teardown_leaf(widget::FilterList::search_mut(&mut list));
+            ctx.teardown_leaf(list);
+        }
+        widget::Flex::remove_child(element, 0);
+    }
+}
+
+impl<V, F, State, Action> ViewMarker for CommandPalette<V, F, State, Action> {}
+
+impl<State, Action, V, F> View<State, Action, ViewCtx> for CommandPalette<V, F, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+    F: Fn(&mut State, usize) -> Action + Send + Sync + 'static,
+{
+    type Element = Pod<widget::Flex>;
+
+    type ViewState = CommandPaletteState<V::ViewState>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (content, content_state) = ctx.with_id(CONTENT_VIEW_ID, |ctx| self.content.build(ctx));
+        ctx.with_id(SHORTCUT_VIEW_ID, |ctx| {
+            ctx.record_shortcut(self.chord.clone());
+        });
+        let widget = widget::Flex::column()
+            .must_fill_main_axis(true)
+            .with_flex_child_pod(content.inner.boxed(), 1.0);
+        let state = CommandPaletteState {
+            content: content_state,
+            open: false,
+            shown: false,
+        };
+        (ctx.new_pod(widget), state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.chord != self.chord {
+            ctx.with_id(SHORTCUT_VIEW_ID, |ctx| {
+                ctx.remove_shortcut(&prev.chord);
+                ctx.record_shortcut(self.chord.clone());
+            });
+        }
+
+        match (view_state.shown, view_state.open) {
+            (false, true) => self.show_palette(ctx, &mut element),
+            (true, false) => prev.hide_palette(ctx, &mut element),
+            (true, true) if prev.commands != self.commands => {
+                let mut child = widget::Flex::child_mut(&mut element, 0)
+                    .expect("the command palette should be the first child");
+                widget::FilterList::set_items(&mut child.downcast(), self.commands.iter().cloned());
+            }
+            _ => (),
+        }
+        view_state.shown = view_state.open;
+
+        let content_idx = usize::from(view_state.shown);
+        let mut content = widget::Flex::child_mut(&mut element, content_idx)
+            .expect("the content of a command palette should be a widget");
+        ctx.with_id(CONTENT_VIEW_ID, |ctx| {
+            self.content.rebuild(
+                &prev.content,
+                &mut view_state.content,
+                ctx,
+                content.downcast(),
+            );
+        });
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if view_state.shown {
+            self.hide_palette(ctx, &mut element);
+            view_state.shown = false;
+        }
+        ctx.with_id(SHORTCUT_VIEW_ID, |ctx| {
+            ctx.remove_shortcut(&self.chord);
+        });
+        let mut content = widget::Flex::child_mut(&mut element, 0)
+            .expect("the content of a command palette should be a widget");
+        ctx.with_id(CONTENT_VIEW_ID, |ctx| {
+            self.content
+                .teardown(&mut view_state.content, ctx, content.downcast());
+        });
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&CONTENT_VIEW_ID, rest)) => {
+                self.content
+                    .message(&mut view_state.content, rest, message, app_state)
+            }
+            Some((&SHORTCUT_VIEW_ID, [])) => {
+                view_state.open = !view_state.open;
+                MessageResult::RequestRebuild
+            }
+            Some((&PALETTE_VIEW_ID, [])) => {
+                if !view_state.shown {
+                    return MessageResult::Stale(message);
+                }
+                let action = match message.downcast::<masonry::Action>() {
+                    Ok(action) => *action,
+                    Err(message) => {
+                        tracing::error!("Wrong message type in CommandPalette::message");
+                        return MessageResult::Stale(message);
+                    }
+                };
+                match action.downcast::<FilterListAction>() {
+                    Ok(FilterListAction::Chosen(command)) => {
+                        view_state.open = false;
+                        MessageResult::Action((self.on_choose)(app_state, command))
+                    }
+                    Ok(FilterListAction::Dismissed) => {
+                        view_state.open = false;
+                        MessageResult::RequestRebuild
+                    }
+                    // The edits of the query are handled by the palette itself.
+                    Err(masonry::Action::TextChanged(_)) => MessageResult::Nop,
+                    Err(action) => {
+                        tracing::error!("Wrong action type in CommandPalette::message: {action:?}");
+                        MessageResult::Stale(Box::new(action))
+                    }
+                }
+            }
+            _ => {
+                tracing::error!("Invalid id path in CommandPalette::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
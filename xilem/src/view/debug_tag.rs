@@ -0,0 +1,80 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::core::{DynMessage, MessageResult, Mut, View, ViewId, ViewMarker};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// The View for [`WidgetView::debug_tag`].
+///
+/// This gives the widget created by the inner view a string tag, which identifies it
+/// in trace spans, debug dumps and tests.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct DebugTag<V, State, Action = ()> {
+    inner: V,
+    tag: Arc<str>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> DebugTag<V, State, Action> {
+    pub(crate) fn new(inner: V, tag: Arc<str>) -> Self {
+        Self {
+            inner,
+            tag,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<V, State, Action> ViewMarker for DebugTag<V, State, Action> {}
+impl<V, State, Action> View<State, Action, ViewCtx> for DebugTag<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = Pod<V::Widget>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (mut pod, state) = self.inner.build(ctx);
+        pod.inner.set_debug_tag(self.tag.clone());
+        (pod, state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        self.inner
+            .rebuild(&prev.inner, view_state, ctx, element.reborrow_mut());
+        // Compare with the widget rather than `prev`, in case the inner view replaced its widget
+        if element.ctx.debug_tag() != Some(&*self.tag) {
+            element.ctx.set_debug_tag(Some(self.tag.clone()));
+        }
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<Self::Element>,
+    ) {
+        self.inner.teardown(view_state, ctx, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.inner.message(view_state, id_path, message, app_state)
+    }
+}
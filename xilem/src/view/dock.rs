@@ -0,0 +1,418 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+pub use masonry::widget::{DockArea, DockEdge, DockLayout, FloatingPanel};
+
+use masonry::text::ArcStr;
+use masonry::widget::{self, DockLayoutChanged, WidgetMut};
+use masonry::Widget;
+
+use crate::core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewElement,
+    ViewId, ViewMarker, ViewSequence,
+};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// Panels docked to the edges of `center`, tabbed together, or floating over it.
+///
+/// The panels are arranged by `layout`, which refers to them by the keys given to
+/// [`dock_panel`]. When the user drags a tab, a floating panel or a splitter, `on_layout_change`
+/// is called with the new layout, which the app should store and pass back.
+/// [`DockLayout`] is serializable, so that it can also be saved between runs of the app.
+///
+/// This corresponds to the Masonry [`Dock`](masonry::widget::Dock) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{dock, dock_panel, label, DockEdge, DockLayout};
+/// # use xilem::WidgetView;
+///
+/// struct Ide {
+///     dock_layout: DockLayout,
+/// }
+///
+/// fn view(ide: &mut Ide) -> impl WidgetView<Ide> {
+///     dock(
+///         label("Editor"),
+///         (
+///             dock_panel("files", "Files", label("src/main.rs")),
+///             dock_panel("terminal", "Terminal", label("$ cargo run")),
+///         ),
+///         ide.dock_layout.clone(),
+///         |ide: &mut Ide, layout| ide.dock_layout = layout,
+///     )
+/// }
+///
+/// let mut ide = Ide {
+///     dock_layout: DockLayout::new()
+///         .with_panel(DockEdge::Left, "files")
+///         .with_panel(DockEdge::Bottom, "terminal"),
+/// };
+/// # let _ = view(&mut ide);
+/// ```
+pub fn dock<State, Action, V, Seq, F>(
+    center: V,
+    panels: Seq,
+    layout: DockLayout,
+    on_layout_change: F,
+) -> Dock<V, Seq, F, State, Action>
+where
+    V: WidgetView<State, Action>,
+    Seq: DockSequence<State, Action>,
+    F: Fn(&mut State, DockLayout) -> Action + Send + Sync + 'static,
+{
+    Dock {
+        center,
+        panels,
+        layout,
+        on_layout_change,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Dock<V, Seq, F, State, Action = ()> {
+    center: V,
+    panels: Seq,
+    layout: DockLayout,
+    on_layout_change: F,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+/// The view state of a [`Dock`].
+pub struct DockState<CenterState, PanelsState> {
+    center: CenterState,
+    panels: PanelsState,
+}
+
+const CENTER_VIEW_ID: ViewId = ViewId::new(0);
+const PANELS_VIEW_ID: ViewId = ViewId::new(1);
+
+impl<V, Seq, F, State, Action> ViewMarker for Dock<V, Seq, F, State, Action> {}
+
+impl<State, Action, V, Seq, F> View<State, Action, ViewCtx> for Dock<V, Seq, F, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+    Seq: DockSequence<State, Action>,
+    F: Fn(&mut State, DockLayout) -> Action + Send + Sync + 'static,
+{
+    type Element = Pod<widget::Dock>;
+
+    type ViewState = DockState<V::ViewState, Seq::SeqState>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (center, center_state) = ctx.with_id(CENTER_VIEW_ID, |ctx| self.center.build(ctx));
+        let mut elements = AppendVec::default();
+        let panels_state = ctx.with_id(PANELS_VIEW_ID, |ctx| {
+            self.panels.seq_build(ctx, &mut elements)
+        });
+        let pod = ctx.with_action_widget(|ctx| {
+            let mut widget =
+                widget::Dock::new_pod(center.inner.boxed()).with_layout(self.layout.clone());
+            for element in elements.into_inner() {
+                widget = widget.with_panel_pod(element.key, element.title, element.content.inner);
+            }
+            ctx.new_pod(widget)
+        });
+        let state = DockState {
+            center: center_state,
+            panels: panels_state,
+        };
+        (pod, state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        // The layout may already have been changed by the widget itself, in which case
+        // this does nothing.
+        if prev.layout != self.layout {
+            widget::Dock::set_layout(&mut element, self.layout.clone());
+        }
+        {
+            let mut center = widget::Dock::center_mut(&mut element);
+            ctx.with_id(CENTER_VIEW_ID, |ctx| {
+                self.center
+                    .rebuild(&prev.center, &mut view_state.center, ctx, center.downcast());
+            });
+        }
+        ctx.with_id(PANELS_VIEW_ID, |ctx| {
+            let mut splice = DockSplice::new(element);
+            self.panels
+                .seq_rebuild(&prev.panels, &mut view_state.panels, ctx, &mut splice);
+            debug_assert!(splice.scratch.is_empty());
+        });
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(PANELS_VIEW_ID, |ctx| {
+            let mut splice = DockSplice::new(element.reborrow_mut());
+            self.panels
+                .seq_teardown(&mut view_state.panels, ctx, &mut splice);
+            debug_assert!(splice.scratch.into_inner().is_empty());
+        });
+        {
+            let mut center = widget::Dock::center_mut(&mut element);
+            ctx.with_id(CENTER_VIEW_ID, |ctx| {
+                self.center
+                    .teardown(&mut view_state.center, ctx, center.downcast());
+            });
+        }
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&CENTER_VIEW_ID, rest)) => {
+                self.center
+                    .message(&mut view_state.center, rest, message, app_state)
+            }
+            Some((&PANELS_VIEW_ID, rest)) => {
+                self.panels
+                    .seq_message(&mut view_state.panels, rest, message, app_state)
+            }
+            None => match message.downcast::<masonry::Action>() {
+                Ok(action) => match (*action).downcast::<DockLayoutChanged>() {
+                    Ok(DockLayoutChanged(layout)) => {
+                        MessageResult::Action((self.on_layout_change)(app_state, layout))
+                    }
+                    Err(action) => {
+                        tracing::error!("Wrong action type in Dock::message: {action:?}");
+                        MessageResult::Stale(Box::new(action))
+                    }
+                },
+                Err(message) => {
+                    tracing::error!("Wrong message type in Dock::message");
+                    MessageResult::Stale(message)
+                }
+            },
+            _ => {
+                tracing::error!("Invalid id path in Dock::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// A panel of a [`dock`], identified by `key` in the [`DockLayout`], with a tab showing `title`.
+pub fn dock_panel<State, Action, V>(
+    key: impl Into<String>,
+    title: impl Into<ArcStr>,
+    content: V,
+) -> DockPanel<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+{
+    DockPanel {
+        key: key.into(),
+        title: title.into(),
+        content,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct DockPanel<V, State, Action = ()> {
+    key: String,
+    title: ArcStr,
+    content: V,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> ViewMarker for DockPanel<V, State, Action> {}
+
+impl<State, Action, V> View<State, Action, ViewCtx> for DockPanel<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = DockElement;
+
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (content, state) = self.content.build(ctx);
+        let element = DockElement {
+            key: self.key.clone(),
+            title: self.title.clone(),
+            content: ctx.boxed_pod(content),
+        };
+        (element, state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.key != self.key {
+            widget::Dock::set_panel_key(&mut element.parent, element.idx, self.key.clone());
+        }
+        if prev.title != self.title {
+            widget::Dock::set_panel_title(&mut element.parent, element.idx, self.title.clone());
+        }
+        let mut content = widget::Dock::panel_mut(&mut element.parent, element.idx);
+        self.content
+            .rebuild(&prev.content, view_state, ctx, content.downcast());
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        let mut content = widget::Dock::panel_mut(&mut element.parent, element.idx);
+        self.content.teardown(view_state, ctx, content.downcast());
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.content
+            .message(view_state, id_path, message, app_state)
+    }
+}
+
+/// A panel of a [`Dock`].
+pub struct DockElement {
+    key: String,
+    title: ArcStr,
+    content: Pod<Box<dyn Widget>>,
+}
+
+pub struct DockElementMut<'w> {
+    parent: WidgetMut<'w, widget::Dock>,
+    idx: usize,
+}
+
+impl ViewElement for DockElement {
+    type Mut<'w> = DockElementMut<'w>;
+}
+
+impl SuperElement<DockElement, ViewCtx> for DockElement {
+    fn upcast(_ctx: &mut ViewCtx, child: DockElement) -> Self {
+        child
+    }
+
+    fn with_downcast_val<R>(
+        mut this: Mut<Self>,
+        f: impl FnOnce(Mut<DockElement>) -> R,
+    ) -> (Self::Mut<'_>, R) {
+        let r = {
+            let parent = this.parent.reborrow_mut();
+            let reborrow = DockElementMut {
+                idx: this.idx,
+                parent,
+            };
+            f(reborrow)
+        };
+        (this, r)
+    }
+}
+
+// Used for building and rebuilding the ViewSequence
+pub struct DockSplice<'w> {
+    idx: usize,
+    element: WidgetMut<'w, widget::Dock>,
+    scratch: AppendVec<DockElement>,
+}
+
+impl<'w> DockSplice<'w> {
+    fn new(element: WidgetMut<'w, widget::Dock>) -> Self {
+        Self {
+            idx: 0,
+            element,
+            scratch: AppendVec::default(),
+        }
+    }
+
+    fn insert_element(&mut self, element: DockElement) {
+        widget::Dock::insert_panel_pod(
+            &mut self.element,
+            self.idx,
+            element.key,
+            element.title,
+            element.content.inner,
+        );
+        self.idx += 1;
+    }
+}
+
+impl ElementSplice<DockElement> for DockSplice<'_> {
+    fn with_scratch<R>(&mut self, f: impl FnOnce(&mut AppendVec<DockElement>) -> R) -> R {
+        let ret = f(&mut self.scratch);
+        let elements: Vec<_> = self.scratch.drain().collect();
+        for element in elements {
+            self.insert_element(element);
+        }
+        ret
+    }
+
+    fn insert(&mut self, element: DockElement) {
+        self.insert_element(element);
+    }
+
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<DockElement>) -> R) -> R {
+        let child = DockElementMut {
+            parent: self.element.reborrow_mut(),
+            idx: self.idx,
+        };
+        let ret = f(child);
+        self.idx += 1;
+        ret
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.idx += n;
+    }
+
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<DockElement>) -> R) -> R {
+        let ret = {
+            let child = DockElementMut {
+                parent: self.element.reborrow_mut(),
+                idx: self.idx,
+            };
+            f(child)
+        };
+        widget::Dock::remove_panel(&mut self.element, self.idx);
+        ret
+    }
+}
+
+/// A sequence of [`dock_panel`]s, the panels of a [`Dock`].
+pub trait DockSequence<State, Action = ()>:
+    ViewSequence<State, Action, ViewCtx, DockElement>
+{
+}
+
+impl<Seq, State, Action> DockSequence<State, Action> for Seq where
+    Seq: ViewSequence<State, Action, ViewCtx, DockElement>
+{
+}
@@ -0,0 +1,271 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::any_view::DynWidget;
+use crate::core::{
+    AnyElement, DynMessage, MessageResult, Mut, SuperElement, View, ViewId, ViewMarker,
+    ViewPathTracker,
+};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// Display the view created by `view_fn`, or the view created by `fallback_fn` if
+/// creating, building or rebuilding that view panics.
+///
+/// The panic is caught, and its message is given to `fallback_fn`, so that the rest
+/// of the app keeps working. This is useful for views provided by plugins or scripts,
+/// which might not be trusted to not panic.
+///
+/// Whilst the fallback is shown, each rebuild tries creating and building the view again,
+/// which allows recovering once the app state has changed.
+///
+/// Note that the panic is still reported by the panic hook, and that the state of the
+/// view which panicked is dropped without being torn down.
+/// Panics whilst handling messages are not caught, and nothing can be caught when the app
+/// is compiled with `panic = "abort"`.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+///     error_boundary(
+///         || state.plugin.view(),
+///         |message| label(format!("The plugin crashed: {message}")),
+///     )
+/// }
+/// ```
+pub fn error_boundary<State, Action, V, F, VF, FF>(
+    view_fn: VF,
+    fallback_fn: FF,
+) -> ErrorBoundary<VF, FF>
+where
+    VF: Fn() -> V,
+    V: WidgetView<State, Action>,
+    FF: Fn(String) -> F,
+    F: WidgetView<State, Action>,
+{
+    ErrorBoundary {
+        view_fn,
+        fallback_fn,
+    }
+}
+
+/// The View for [`error_boundary`].
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct ErrorBoundary<VF, FF> {
+    view_fn: VF,
+    fallback_fn: FF,
+}
+
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct ErrorBoundaryState<V, VState, F, FState> {
+    content: Content<V, VState, F, FState>,
+    /// Incremented whenever the view is replaced by the fallback or the other way around,
+    /// so that messages sent to the replaced view are not routed to the new one.
+    generation: u64,
+}
+
+enum Content<V, VState, F, FState> {
+    View { view: V, state: VState },
+    Fallback { view: F, state: FState },
+}
+
+/// Get the message of a panic from its payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+/// Run `f`, catching any panic.
+///
+/// If `f` panics, the ids it pushed onto the view path are removed.
+fn catch_view_panic<R>(ctx: &mut ViewCtx, f: impl FnOnce(&mut ViewCtx) -> R) -> Result<R, String> {
+    let path_len = ctx.view_path().len();
+    catch_unwind(AssertUnwindSafe(|| f(ctx))).map_err(|payload| {
+        while ctx.view_path().len() > path_len {
+            ctx.pop_id();
+        }
+        panic_message(&*payload)
+    })
+}
+
+impl<VF, FF> ErrorBoundary<VF, FF> {
+    fn build_fallback<State, Action, F>(
+        &self,
+        ctx: &mut ViewCtx,
+        message: String,
+    ) -> (Pod<F::Widget>, F, F::ViewState)
+    where
+        FF: Fn(String) -> F,
+        F: WidgetView<State, Action>,
+    {
+        let view = (self.fallback_fn)(message);
+        let (pod, state) = view.build(ctx);
+        (pod, view, state)
+    }
+}
+
+impl<VF, FF> ViewMarker for ErrorBoundary<VF, FF> {}
+impl<State, Action, V, F, VF, FF> View<State, Action, ViewCtx> for ErrorBoundary<VF, FF>
+where
+    VF: Fn() -> V + 'static,
+    V: WidgetView<State, Action>,
+    FF: Fn(String) -> F + 'static,
+    F: WidgetView<State, Action>,
+{
+    type Element = Pod<DynWidget>;
+
+    type ViewState = ErrorBoundaryState<V, V::ViewState, F, F::ViewState>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let generation = 0;
+        let (element, content) = ctx.with_id(ViewId::new(generation), |ctx| {
+            let built = catch_view_panic(ctx, |ctx| {
+                let view = (self.view_fn)();
+                let (pod, state) = view.build(ctx);
+                (pod, view, state)
+            });
+            match built {
+                Ok((pod, view, state)) => (
+                    Pod::<DynWidget>::upcast(ctx, pod),
+                    Content::View { view, state },
+                ),
+                Err(message) => {
+                    let (pod, view, state) = self.build_fallback(ctx, message);
+                    (
+                        Pod::<DynWidget>::upcast(ctx, pod),
+                        Content::Fallback { view, state },
+                    )
+                }
+            }
+        });
+        (
+            element,
+            ErrorBoundaryState {
+                content,
+                generation,
+            },
+        )
+    }
+
+    fn rebuild(
+        &self,
+        _prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<Self::Element>,
+    ) {
+        let generation = view_state.generation;
+        // Overflow handling: u64 starts at 0, incremented by 1 always.
+        // Can never realistically overflow, scale is too large.
+        let next_generation = generation.wrapping_add(1);
+        let (element, message) = match &mut view_state.content {
+            Content::View { view, state } => {
+                let (element, result) = Pod::<DynWidget>::with_downcast_val(element, |child| {
+                    ctx.with_id(ViewId::new(generation), |ctx| {
+                        catch_view_panic(ctx, |ctx| {
+                            let new_view = (self.view_fn)();
+                            new_view.rebuild(view, state, ctx, child);
+                            *view = new_view;
+                        })
+                    })
+                });
+                match result {
+                    Ok(()) => return,
+                    Err(message) => (element, message),
+                }
+            }
+            Content::Fallback { view, state } => {
+                // Try creating the view again, as the app state might have changed.
+                let built = ctx.with_id(ViewId::new(next_generation), |ctx| {
+                    catch_view_panic(ctx, |ctx| {
+                        let new_view = (self.view_fn)();
+                        let (pod, new_state) = new_view.build(ctx);
+                        (pod, new_view, new_state)
+                    })
+                });
+                match built {
+                    Ok((pod, new_view, new_state)) => {
+                        let (element, ()) = Pod::<DynWidget>::with_downcast_val(element, |child| {
+                            ctx.with_id(ViewId::new(generation), |ctx| {
+                                view.teardown(state, ctx, child);
+                            });
+                        });
+                        Pod::<DynWidget>::replace_inner(element, pod);
+                        view_state.content = Content::View {
+                            view: new_view,
+                            state: new_state,
+                        };
+                        view_state.generation = next_generation;
+                    }
+                    Err(message) => {
+                        let new_view = (self.fallback_fn)(message);
+                        Pod::<DynWidget>::with_downcast_val(element, |child| {
+                            ctx.with_id(ViewId::new(generation), |ctx| {
+                                new_view.rebuild(view, state, ctx, child);
+                            });
+                        });
+                        *view = new_view;
+                    }
+                }
+                return;
+            }
+        };
+        let (pod, view, state) = ctx.with_id(ViewId::new(next_generation), |ctx| {
+            self.build_fallback(ctx, message)
+        });
+        Pod::<DynWidget>::replace_inner(element, pod);
+        view_state.content = Content::Fallback { view, state };
+        view_state.generation = next_generation;
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<Self::Element>,
+    ) {
+        let generation = view_state.generation;
+        ctx.with_id(ViewId::new(generation), |ctx| {
+            match &mut view_state.content {
+                Content::View { view, state } => {
+                    Pod::<DynWidget>::with_downcast_val(element, |child| {
+                        view.teardown(state, ctx, child);
+                    });
+                }
+                Content::Fallback { view, state } => {
+                    Pod::<DynWidget>::with_downcast_val(element, |child| {
+                        view.teardown(state, ctx, child);
+                    });
+                }
+            }
+        });
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        let (start, rest) = id_path
+            .split_first()
+            .expect("Id path has elements for ErrorBoundary");
+        if start.routing_id() != view_state.generation {
+            // The message was sent to a view which has since been replaced
+            return MessageResult::Stale(message);
+        }
+        match &mut view_state.content {
+            Content::View { view, state } => view.message(state, rest, message, app_state),
+            Content::Fallback { view, state } => view.message(state, rest, message, app_state),
+        }
+    }
+}
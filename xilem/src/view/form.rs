@@ -0,0 +1,436 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::text::ArcStr;
+use masonry::widget::{self, WidgetMut};
+
+use crate::core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewElement,
+    ViewId, ViewMarker, ViewSequence,
+};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// A column of [`form_field`]s followed by a submit button, which calls `on_submit`.
+///
+/// The submit button is disabled while any field is invalid. Clicking it anyway focuses
+/// the first invalid field.
+///
+/// This corresponds to the Masonry [`Form`](masonry::widget::Form) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{form, form_field, textbox, validators};
+/// # use xilem::WidgetView;
+///
+/// struct Signup {
+///     name: String,
+///     email: String,
+/// }
+///
+/// fn view(signup: &mut Signup) -> impl WidgetView<Signup> {
+///     form(
+///         (
+///             form_field(
+///                 "Name",
+///                 textbox(signup.name.clone(), |signup: &mut Signup, name| signup.name = name),
+///             )
+///             .validate(signup.name.as_str(), validators::required),
+///             form_field(
+///                 "Email",
+///                 textbox(signup.email.clone(), |signup: &mut Signup, email| signup.email = email),
+///             )
+///             .validate(signup.email.as_str(), validators::required)
+///             .validate(signup.email.as_str(), |email: &str| {
+///                 if email.contains('@') {
+///                     Ok(())
+///                 } else {
+///                     Err("Enter an email address".into())
+///                 }
+///             }),
+///         ),
+///         "Sign up",
+///         |signup: &mut Signup| println!("Welcome, {}", signup.name),
+///     )
+/// }
+/// ```
+pub fn form<State, Action, Seq, F>(
+    fields: Seq,
+    submit_label: impl Into<ArcStr>,
+    on_submit: F,
+) -> Form<Seq, F, State, Action>
+where
+    Seq: FormSequence<State, Action>,
+    F: Fn(&mut State) -> Action + Send + Sync + 'static,
+{
+    Form {
+        fields,
+        submit_label: submit_label.into(),
+        on_submit,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Form<Seq, F, State, Action = ()> {
+    fields: Seq,
+    submit_label: ArcStr,
+    on_submit: F,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+const FIELDS_VIEW_ID: ViewId = ViewId::new(0);
+const SUBMIT_VIEW_ID: ViewId = ViewId::new(1);
+
+impl<Seq, F, State, Action> ViewMarker for Form<Seq, F, State, Action> {}
+
+impl<State, Action, Seq, F> View<State, Action, ViewCtx> for Form<Seq, F, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    Seq: FormSequence<State, Action>,
+    F: Fn(&mut State) -> Action + Send + Sync + 'static,
+{
+    type Element = Pod<widget::Form>;
+
+    type ViewState = Seq::SeqState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let mut elements = AppendVec::default();
+        let seq_state = ctx.with_id(FIELDS_VIEW_ID, |ctx| {
+            self.fields.seq_build(ctx, &mut elements)
+        });
+        let submit = ctx.with_id(SUBMIT_VIEW_ID, |ctx| {
+            ctx.with_action_widget(|ctx| {
+                ctx.new_pod(widget::Button::new(self.submit_label.clone()))
+            })
+        });
+        let mut widget = widget::Form::new_pod(submit.inner);
+        for element in elements.into_inner() {
+            widget = widget.with_field_pod(element.field.inner);
+        }
+        (ctx.new_pod(widget), seq_state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.submit_label != self.submit_label {
+            widget::Form::set_submit_label(&mut element, self.submit_label.clone());
+        }
+        ctx.with_id(FIELDS_VIEW_ID, |ctx| {
+            let mut splice = FormSplice::new(element);
+            self.fields
+                .seq_rebuild(&prev.fields, view_state, ctx, &mut splice);
+            debug_assert!(splice.scratch.is_empty());
+        });
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(SUBMIT_VIEW_ID, |ctx| {
+            ctx.teardown_leaf(widget::Form::submit_mut(&mut element));
+        });
+        ctx.with_id(FIELDS_VIEW_ID, |ctx| {
+            let mut splice = FormSplice::new(element);
+            self.fields.seq_teardown(view_state, ctx, &mut splice);
+            debug_assert!(splice.scratch.into_inner().is_empty());
+        });
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&FIELDS_VIEW_ID, rest)) => self
+                .fields
+                .seq_message(view_state, rest, message, app_state),
+            Some((&SUBMIT_VIEW_ID, [])) => match message.downcast::<masonry::Action>() {
+                Ok(action) => {
+                    if let masonry::Action::ButtonPressed(_) = *action {
+                        MessageResult::Action((self.on_submit)(app_state))
+                    } else {
+                        tracing::error!("Wrong action type in Form::message: {action:?}");
+                        MessageResult::Stale(action)
+                    }
+                }
+                Err(message) => {
+                    tracing::error!("Wrong message type in Form::message: {message:?}");
+                    MessageResult::Stale(message)
+                }
+            },
+            _ => {
+                tracing::error!("Invalid id path in Form::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// A labelled input of a [`form`], with validators.
+///
+/// The validators are applied to the values shown by the input, and the error message of
+/// the first one which fails is shown under the input.
+///
+/// This corresponds to the Masonry [`FormField`](masonry::widget::FormField) widget.
+pub fn form_field<State, Action, V>(
+    label: impl Into<ArcStr>,
+    input: V,
+) -> FormField<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+{
+    FormField {
+        label: label.into(),
+        input,
+        error: None,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct FormField<V, State, Action = ()> {
+    label: ArcStr,
+    input: V,
+    error: Option<ArcStr>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> FormField<V, State, Action> {
+    /// Check `value` with `validator`, unless a previous validator already failed.
+    ///
+    /// The validator returns the error message to show when the value is invalid.
+    /// See [`validators`] for common ones.
+    pub fn validate<T: ?Sized>(
+        mut self,
+        value: &T,
+        validator: impl FnOnce(&T) -> Result<(), String>,
+    ) -> Self {
+        if self.error.is_none() {
+            self.error = validator(value).err().map(Into::into);
+        }
+        self
+    }
+
+    /// Whether all the validators passed.
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl<V, State, Action> ViewMarker for FormField<V, State, Action> {}
+
+impl<State, Action, V> View<State, Action, ViewCtx> for FormField<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = FormElement;
+
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (input, state) = self.input.build(ctx);
+        let field = widget::FormField::new_pod(self.label.clone(), ctx.boxed_pod(input).inner)
+            .with_error(self.error.clone());
+        (
+            FormElement {
+                field: ctx.new_pod(field),
+            },
+            state,
+        )
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        let mut field = widget::Form::field_mut(&mut element.parent, element.idx);
+        if prev.label != self.label {
+            widget::FormField::set_label(&mut field, self.label.clone());
+        }
+        if prev.error != self.error {
+            widget::FormField::set_error(&mut field, self.error.clone());
+        }
+        let mut input = widget::FormField::input_mut(&mut field);
+        self.input
+            .rebuild(&prev.input, view_state, ctx, input.downcast());
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        let mut field = widget::Form::field_mut(&mut element.parent, element.idx);
+        let mut input = widget::FormField::input_mut(&mut field);
+        self.input.teardown(view_state, ctx, input.downcast());
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.input.message(view_state, id_path, message, app_state)
+    }
+}
+
+/// Common validators for [`FormField::validate`].
+pub mod validators {
+    /// Fails if the text is empty or only whitespace.
+    pub fn required(text: &str) -> Result<(), String> {
+        if text.trim().is_empty() {
+            Err("This field is required".into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Fails if the text has fewer than `min` characters.
+    pub fn min_length(min: usize) -> impl FnOnce(&str) -> Result<(), String> {
+        move |text| {
+            if text.chars().count() < min {
+                Err(format!("Enter at least {min} characters"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Fails if the text has more than `max` characters.
+    pub fn max_length(max: usize) -> impl FnOnce(&str) -> Result<(), String> {
+        move |text| {
+            if text.chars().count() > max {
+                Err(format!("Enter at most {max} characters"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A field of a [`Form`].
+pub struct FormElement {
+    field: Pod<widget::FormField>,
+}
+
+pub struct FormElementMut<'w> {
+    parent: WidgetMut<'w, widget::Form>,
+    idx: usize,
+}
+
+impl ViewElement for FormElement {
+    type Mut<'w> = FormElementMut<'w>;
+}
+
+impl SuperElement<FormElement, ViewCtx> for FormElement {
+    fn upcast(_ctx: &mut ViewCtx, child: FormElement) -> Self {
+        child
+    }
+
+    fn with_downcast_val<R>(
+        mut this: Mut<Self>,
+        f: impl FnOnce(Mut<FormElement>) -> R,
+    ) -> (Self::Mut<'_>, R) {
+        let r = {
+            let parent = this.parent.reborrow_mut();
+            let reborrow = FormElementMut {
+                idx: this.idx,
+                parent,
+            };
+            f(reborrow)
+        };
+        (this, r)
+    }
+}
+
+// Used for building and rebuilding the ViewSequence
+pub struct FormSplice<'w> {
+    idx: usize,
+    element: WidgetMut<'w, widget::Form>,
+    scratch: AppendVec<FormElement>,
+}
+
+impl<'w> FormSplice<'w> {
+    fn new(element: WidgetMut<'w, widget::Form>) -> Self {
+        Self {
+            idx: 0,
+            element,
+            scratch: AppendVec::default(),
+        }
+    }
+}
+
+impl ElementSplice<FormElement> for FormSplice<'_> {
+    fn with_scratch<R>(&mut self, f: impl FnOnce(&mut AppendVec<FormElement>) -> R) -> R {
+        let ret = f(&mut self.scratch);
+        for element in self.scratch.drain() {
+            widget::Form::insert_field_pod(&mut self.element, self.idx, element.field.inner);
+            self.idx += 1;
+        }
+        ret
+    }
+
+    fn insert(&mut self, element: FormElement) {
+        widget::Form::insert_field_pod(&mut self.element, self.idx, element.field.inner);
+        self.idx += 1;
+    }
+
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<FormElement>) -> R) -> R {
+        let child = FormElementMut {
+            parent: self.element.reborrow_mut(),
+            idx: self.idx,
+        };
+        let ret = f(child);
+        self.idx += 1;
+        ret
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.idx += n;
+    }
+
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<FormElement>) -> R) -> R {
+        let ret = {
+            let child = FormElementMut {
+                parent: self.element.reborrow_mut(),
+                idx: self.idx,
+            };
+            f(child)
+        };
+        widget::Form::remove_field(&mut self.element, self.idx);
+        ret
+    }
+}
+
+/// A sequence of [`form_field`]s, the fields of a [`Form`].
+pub trait FormSequence<State, Action = ()>:
+    ViewSequence<State, Action, ViewCtx, FormElement>
+{
+}
+
+impl<Seq, State, Action> FormSequence<State, Action> for Seq where
+    Seq: ViewSequence<State, Action, ViewCtx, FormElement>
+{
+}
@@ -10,7 +10,7 @@ use crate::core::{
     AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewElement,
     ViewId, ViewMarker, ViewSequence,
 };
-use crate::{Pod, ViewCtx, WidgetView};
+use crate::{AnyWidgetView, Pod, ViewCtx, WidgetView};
 
 pub fn grid<State, Action, Seq: GridSequence<State, Action>>(
     sequence: Seq,
@@ -297,6 +297,31 @@ pub trait GridExt<State, Action>: WidgetView<State, Action> {
     {
         grid_item(self, GridParams::new(x, y, 1, 1))
     }
+
+    /// Applies [`impl Into<GridParams>`](`GridParams`) to this view and turns it into an [`AnyGridChild`],
+    /// which allows grid children of different view types to be used in the same collection.
+    ///
+    /// # Examples
+    /// ```
+    /// use masonry::widget::GridParams;
+    /// use xilem::{view::{button, prose, grid, GridExt}};
+    /// # use xilem::{WidgetView};
+    ///
+    /// # fn view<State: 'static>() -> impl WidgetView<State> {
+    /// grid(vec![
+    ///     button("click me", |_| ()).into_any_grid(GridParams::new(0, 0, 2, 1)),
+    ///     prose("a prose").into_any_grid(GridParams::new(1, 1, 1, 1)),
+    /// ], 2, 2)
+    /// # }
+    /// ```
+    fn into_any_grid(self, params: impl Into<GridParams>) -> AnyGridChild<State, Action>
+    where
+        State: 'static,
+        Action: 'static,
+        Self: Sized,
+    {
+        grid_item(self.boxed(), params)
+    }
 }
 
 impl<State, Action, V: WidgetView<State, Action>> GridExt<State, Action> for V {}
@@ -350,6 +375,37 @@ where
     }
 }
 
+/// A widget-type-erased grid child [`View`], can be used within a [`Grid`] [`View`]
+///
+/// Unlike the children of a [`Flex`](crate::view::Flex), grid children are always widgets,
+/// so this is a [`GridItem`] with a boxed view.
+pub type AnyGridChild<State, Action = ()> =
+    GridItem<Box<AnyWidgetView<State, Action>>, State, Action>;
+
+impl<State, Action, V> GridItem<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    /// Turns this [`GridItem`] into an [`AnyGridChild`]
+    ///
+    /// # Examples
+    /// ```
+    /// use masonry::widget::GridParams;
+    /// use xilem::view::{grid, grid_item, label};
+    /// # use xilem::{WidgetView};
+    ///
+    /// # fn view<State: 'static>() -> impl WidgetView<State> {
+    /// grid(vec![grid_item(label("Industry"), GridParams::new(0, 0, 1, 1)).into_any_grid()], 1, 1)
+    /// # }
+    ///
+    /// ```
+    pub fn into_any_grid(self) -> AnyGridChild<State, Action> {
+        grid_item(Box::new(self.view), self.params)
+    }
+}
+
 impl<V, State, Action> ViewMarker for GridItem<V, State, Action> {}
 
 impl<State, Action, V> View<State, Action, ViewCtx> for GridItem<V, State, Action>
@@ -0,0 +1,152 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+pub use masonry::widget::ColorScale;
+
+use masonry::text::ArcStr;
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// A matrix of `rows` by `columns` values, given row by row, shown as colored cells.
+///
+/// Hovering a cell shows its value. Only the cells in view are painted, so large matrices
+/// can be shown in a [`portal`](crate::view::portal).
+///
+/// This corresponds to the Masonry [`Heatmap`](masonry::widget::Heatmap) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{heatmap, portal, ColorScale};
+/// use xilem::{Color, WidgetView};
+///
+/// struct Correlations {
+///     names: Vec<String>,
+///     matrix: Vec<f64>,
+/// }
+///
+/// fn view(data: &mut Correlations) -> impl WidgetView<Correlations> {
+///     let len = data.names.len();
+///     portal(
+///         heatmap(len, len, data.matrix.iter().copied())
+///             .range(-1.0, 1.0)
+///             .color_scale(ColorScale::diverging(Color::BLUE, Color::WHITE, Color::RED))
+///             .row_labels(data.names.iter().map(String::as_str))
+///             .column_labels(data.names.iter().map(String::as_str)),
+///     )
+/// }
+/// ```
+pub fn heatmap(rows: usize, columns: usize, values: impl IntoIterator<Item = f64>) -> Heatmap {
+    Heatmap {
+        rows,
+        columns,
+        values: values.into_iter().collect(),
+        range: None,
+        color_scale: ColorScale::default(),
+        row_labels: Vec::new(),
+        column_labels: Vec::new(),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Heatmap {
+    rows: usize,
+    columns: usize,
+    values: Vec<f64>,
+    range: Option<(f64, f64)>,
+    color_scale: ColorScale,
+    row_labels: Vec<ArcStr>,
+    column_labels: Vec<ArcStr>,
+}
+
+impl Heatmap {
+    /// Set the values at the ends of the color scale, which are the lowest and highest
+    /// values by default.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Set the colors of the values.
+    pub fn color_scale(mut self, color_scale: ColorScale) -> Self {
+        self.color_scale = color_scale;
+        self
+    }
+
+    /// Set the labels of the rows, from the top.
+    pub fn row_labels(mut self, labels: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        self.row_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the labels of the columns, from the left.
+    pub fn column_labels(mut self, labels: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        self.column_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl ViewMarker for Heatmap {}
+impl<State, Action> View<State, Action, ViewCtx> for Heatmap {
+    type Element = Pod<widget::Heatmap>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.with_leaf_action_widget(|ctx| {
+            let mut widget =
+                widget::Heatmap::new(self.rows, self.columns, self.values.iter().copied())
+                    .with_color_scale(self.color_scale.clone())
+                    .with_row_labels(self.row_labels.iter().cloned())
+                    .with_column_labels(self.column_labels.iter().cloned());
+            if let Some((min, max)) = self.range {
+                widget = widget.with_range(min, max);
+            }
+            ctx.new_pod(widget)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if (prev.rows, prev.columns) != (self.rows, self.columns) || prev.values != self.values {
+            widget::Heatmap::set_values(
+                &mut element,
+                self.rows,
+                self.columns,
+                self.values.iter().copied(),
+            );
+        }
+        if prev.range != self.range {
+            widget::Heatmap::set_range(&mut element, self.range);
+        }
+        if prev.color_scale != self.color_scale {
+            widget::Heatmap::set_color_scale(&mut element, self.color_scale.clone());
+        }
+        if prev.row_labels != self.row_labels {
+            widget::Heatmap::set_row_labels(&mut element, self.row_labels.iter().cloned());
+        }
+        if prev.column_labels != self.column_labels {
+            widget::Heatmap::set_column_labels(&mut element, self.column_labels.iter().cloned());
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _id_path: &[ViewId],
+        message: DynMessage,
+        _app_state: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in Heatmap::message, but Heatmap doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
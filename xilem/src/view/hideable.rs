@@ -0,0 +1,81 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::Visibility;
+
+use crate::core::{DynMessage, MessageResult, Mut, View, ViewId, ViewMarker};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// The View for [`WidgetView::visible`] and [`WidgetView::collapsed`].
+///
+/// This hides the widget created by the inner view without removing it, so it keeps its
+/// state, e.g. the text typed in a text box, while it isn't shown.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Hideable<V, State, Action = ()> {
+    inner: V,
+    visibility: Visibility,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> Hideable<V, State, Action> {
+    pub(crate) fn new(inner: V, visibility: Visibility) -> Self {
+        Self {
+            inner,
+            visibility,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<V, State, Action> ViewMarker for Hideable<V, State, Action> {}
+impl<V, State, Action> View<State, Action, ViewCtx> for Hideable<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = Pod<V::Widget>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (mut pod, state) = self.inner.build(ctx);
+        pod.inner.set_visibility(self.visibility);
+        (pod, state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        self.inner
+            .rebuild(&prev.inner, view_state, ctx, element.reborrow_mut());
+        // Compare with the widget rather than `prev`, in case the inner view replaced its widget
+        if element.ctx.visibility() != self.visibility {
+            element.ctx.set_visibility(self.visibility);
+        }
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<Self::Element>,
+    ) {
+        self.inner.teardown(view_state, ctx, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.inner.message(view_state, id_path, message, app_state)
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::widget::{self, CrossAxisAlignment, WidgetMut};
+use masonry::WidgetId;
+
+use crate::core::{DynMessage, MessageResult, Mut, View, ViewId, ViewMarker};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// The View for [`WidgetView::labelled_by`].
+///
+/// This lays out the label before the widget of the inner view in a row, and tells
+/// assistive technologies that the label names that widget.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Labelled<L, V, State, Action = ()> {
+    label: L,
+    inner: V,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<L, V, State, Action> Labelled<L, V, State, Action> {
+    pub(crate) fn new(inner: V, label: L) -> Self {
+        Self {
+            label,
+            inner,
+            phantom: PhantomData,
+        }
+    }
+}
+
+const LABEL_VIEW_ID: ViewId = ViewId::new(0);
+const INNER_VIEW_ID: ViewId = ViewId::new(1);
+
+/// Point the `labelled_by` relation of the inner widget at the label widget.
+///
+/// This is checked after each rebuild, in case either view replaced its widget.
+fn link_label(element: &mut WidgetMut<'_, widget::Flex>) {
+    let label_id: WidgetId = widget::Flex::child_mut(element, 0)
+        .expect("the label should be the first child")
+        .ctx
+        .widget_id();
+    let mut inner = widget::Flex::child_mut(element, 1)
+        .expect("the labelled widget should be the second child");
+    if inner.ctx.access_overrides().labelled_by != [label_id] {
+        inner.ctx.access_overrides_mut().labelled_by = vec![label_id];
+    }
+}
+
+impl<L, V, State, Action> ViewMarker for Labelled<L, V, State, Action> {}
+impl<L, V, State, Action> View<State, Action, ViewCtx> for Labelled<L, V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    L: WidgetView<State, Action>,
+    V: WidgetView<State, Action>,
+{
+    type Element = Pod<widget::Flex>;
+    type ViewState = (L::ViewState, V::ViewState);
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (label, label_state) = ctx.with_id(LABEL_VIEW_ID, |ctx| self.label.build(ctx));
+        let (mut inner, inner_state) = ctx.with_id(INNER_VIEW_ID, |ctx| self.inner.build(ctx));
+        if let Some(overrides) = inner.inner.access_overrides_mut() {
+            overrides.labelled_by = vec![label.inner.id()];
+        }
+        let widget = widget::Flex::row()
+            .cross_axis_alignment(CrossAxisAlignment::Center)
+            .with_child_pod(label.inner.boxed())
+            .with_child_pod(inner.inner.boxed());
+        (ctx.new_pod(widget), (label_state, inner_state))
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (label_state, inner_state): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        {
+            let mut label = widget::Flex::child_mut(&mut element, 0)
+                .expect("the label should be the first child");
+            ctx.with_id(LABEL_VIEW_ID, |ctx| {
+                self.label
+                    .rebuild(&prev.label, label_state, ctx, label.downcast());
+            });
+        }
+        {
+            let mut inner = widget::Flex::child_mut(&mut element, 1)
+                .expect("the labelled widget should be the second child");
+            ctx.with_id(INNER_VIEW_ID, |ctx| {
+                self.inner
+                    .rebuild(&prev.inner, inner_state, ctx, inner.downcast());
+            });
+        }
+        link_label(&mut element);
+    }
+
+    fn teardown(
+        &self,
+        (label_state, inner_state): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        {
+            let mut label = widget::Flex::child_mut(&mut element, 0)
+                .expect("the label should be the first child");
+            ctx.with_id(LABEL_VIEW_ID, |ctx| {
+                self.label.teardown(label_state, ctx, label.downcast());
+            });
+        }
+        let mut inner = widget::Flex::child_mut(&mut element, 1)
+            .expect("the labelled widget should be the second child");
+        ctx.with_id(INNER_VIEW_ID, |ctx| {
+            self.inner.teardown(inner_state, ctx, inner.downcast());
+        });
+    }
+
+    fn message(
+        &self,
+        (label_state, inner_state): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&LABEL_VIEW_ID, rest)) => {
+                self.label.message(label_state, rest, message, app_state)
+            }
+            Some((&INNER_VIEW_ID, rest)) => {
+                self.inner.message(inner_state, rest, message, app_state)
+            }
+            _ => {
+                tracing::error!("Invalid id path in Labelled::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use crate::testing::AppTestHarness;
+    use crate::view::{label, textbox};
+    use crate::{WidgetView, Xilem};
+
+    fn app_logic(email: &mut String) -> impl WidgetView<String> {
+        textbox(email.clone(), |email: &mut String, new_email| {
+            *email = new_email;
+        })
+        .debug_tag("field")
+        .labelled_by(label("Email").debug_tag("label"))
+    }
+
+    #[test]
+    fn field_is_labelled_by_label() {
+        let mut harness = AppTestHarness::create(Xilem::new(String::new(), app_logic));
+        let label_id = harness.harness().get_widget_by_tag("label").id();
+        let field_id = harness.harness().get_widget_by_tag("field").id();
+
+        let labelled_by = harness.interact(|harness| {
+            harness.edit_widget(field_id, |widget| {
+                widget.ctx.access_overrides().labelled_by.clone()
+            })
+        });
+        assert_eq!(labelled_by, [label_id]);
+
+        // The relation survives rebuilds
+        harness.edit_state(|email| *email = "a@b.c".to_string());
+        let labelled_by = harness.interact(|harness| {
+            harness.edit_widget(field_id, |widget| {
+                widget.ctx.access_overrides().labelled_by.clone()
+            })
+        });
+        assert_eq!(labelled_by, [label_id]);
+    }
+}
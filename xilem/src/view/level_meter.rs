@@ -0,0 +1,81 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::widget::{self, AudioFeed};
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// Bars showing the level of each channel of the samples sent to `feed`, in decibels,
+/// e.g. by an audio callback running on another thread.
+///
+/// The bars go from -60 to 0 decibels by default. To configure this, call
+/// [`range`](LevelMeter::range) on the returned value.
+///
+/// Corresponds to the [`LevelMeter`](widget::LevelMeter) widget.
+pub fn level_meter(feed: &AudioFeed) -> LevelMeter {
+    LevelMeter {
+        feed: feed.clone(),
+        range: (-60.0, 0.0),
+    }
+}
+
+/// The [`View`] created by [`level_meter`].
+///
+/// See `level_meter`'s docs for more details.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct LevelMeter {
+    feed: AudioFeed,
+    range: (f64, f64),
+}
+
+impl LevelMeter {
+    /// Set the levels at the bottom and the top of the bars, in decibels.
+    pub fn range(mut self, min_db: f64, max_db: f64) -> Self {
+        self.range = (min_db, max_db);
+        self
+    }
+}
+
+impl ViewMarker for LevelMeter {}
+impl<State, Action> View<State, Action, ViewCtx> for LevelMeter {
+    type Element = Pod<widget::LevelMeter>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (min_db, max_db) = self.range;
+        let widget = widget::LevelMeter::new(self.feed.channels())
+            .with_feed(self.feed.clone())
+            .with_range(min_db, max_db);
+        (ctx.new_pod(widget), ())
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.range != self.range {
+            let (min_db, max_db) = self.range;
+            widget::LevelMeter::set_range(&mut element, min_db, max_db);
+        }
+        if prev.feed != self.feed {
+            widget::LevelMeter::set_feed(&mut element, Some(self.feed.clone()));
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, _: &mut ViewCtx, _: Mut<Self::Element>) {}
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _: &[ViewId],
+        message: DynMessage,
+        _: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in LevelMeter::message, but LevelMeter doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
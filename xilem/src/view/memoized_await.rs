@@ -0,0 +1,174 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+
+use crate::core::{
+    DynMessage, MessageProxy, MessageResult, Mut, NoElement, View, ViewId, ViewMarker,
+    ViewPathTracker,
+};
+use crate::ViewCtx;
+
+/// Await a future created from `data`, and give its output to `on_output`.
+///
+/// The future is spawned when the view is added to the tree, and spawned again
+/// whenever `data` changes, in which case the previous future is cancelled.
+/// The future is also cancelled when the view is removed from the tree.
+/// Outputs of cancelled futures are never given to `on_output`.
+///
+/// This can be used to load data which depends on the app state, such as fetching
+/// a resource whenever its URL changes.
+///
+/// Note that `init_future` cannot capture, as the future would not be spawned again
+/// when the captured variables change. Use `data` for anything the future depends on.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+///     fork(
+///         label(state.page.as_deref().unwrap_or("Loading...")),
+///         memoized_await(
+///             state.url.clone(),
+///             |url| fetch_page(url.clone()),
+///             |state: &mut AppState, page: String| state.page = Some(page),
+///         ),
+///     )
+/// }
+/// ```
+pub fn memoized_await<State, Action, Data, F, H, Fut>(
+    data: Data,
+    init_future: F,
+    on_output: H,
+) -> MemoizedAwait<Data, F, H, Fut::Output>
+where
+    Data: PartialEq + 'static,
+    F: Fn(&Data) -> Fut + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: Debug + Send + 'static,
+    H: Fn(&mut State, Fut::Output) -> Action + 'static,
+{
+    const {
+        assert!(
+            size_of::<F>() == 0,
+            "`memoized_await` will not be ran again when its captured variables are updated.\n\
+            Pass the values the future depends on as `data` instead."
+        );
+    };
+    MemoizedAwait {
+        data,
+        init_future,
+        on_output,
+        output: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct MemoizedAwait<Data, F, H, Output> {
+    data: Data,
+    init_future: F,
+    on_output: H,
+    output: PhantomData<fn() -> Output>,
+}
+
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct MemoizedAwaitState {
+    handle: JoinHandle<()>,
+    /// Incremented whenever a new future is spawned, to ignore the outputs of cancelled ones.
+    generation: u64,
+}
+
+/// The message sent by the future of a [`MemoizedAwait`].
+#[derive(Debug)]
+struct AwaitOutput<Output> {
+    generation: u64,
+    output: Output,
+}
+
+impl<Data, F, H, Output> MemoizedAwait<Data, F, H, Output>
+where
+    Output: Debug + Send + 'static,
+{
+    fn spawn<Fut>(&self, ctx: &mut ViewCtx, generation: u64) -> JoinHandle<()>
+    where
+        F: Fn(&Data) -> Fut,
+        Fut: Future<Output = Output> + Send + 'static,
+    {
+        let path: Arc<[ViewId]> = ctx.view_path().into();
+        let proxy = MessageProxy::new(ctx.proxy.clone(), path);
+        let future = (self.init_future)(&self.data);
+        ctx.runtime().spawn(async move {
+            let output = future.await;
+            // We choose not to handle the case where the event loop has ended
+            drop(proxy.message(AwaitOutput { generation, output }));
+        })
+    }
+}
+
+impl<Data, F, H, Output> ViewMarker for MemoizedAwait<Data, F, H, Output> {}
+impl<State, Action, Data, F, H, Fut, Output> View<State, Action, ViewCtx>
+    for MemoizedAwait<Data, F, H, Output>
+where
+    Data: PartialEq + 'static,
+    F: Fn(&Data) -> Fut + 'static,
+    Fut: Future<Output = Output> + Send + 'static,
+    Output: Debug + Send + 'static,
+    H: Fn(&mut State, Output) -> Action + 'static,
+{
+    type Element = NoElement;
+
+    type ViewState = MemoizedAwaitState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let handle = self.spawn(ctx, 0);
+        (
+            NoElement,
+            MemoizedAwaitState {
+                handle,
+                generation: 0,
+            },
+        )
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        (): Mut<Self::Element>,
+    ) {
+        if self.data != prev.data {
+            view_state.handle.abort();
+            view_state.generation += 1;
+            view_state.handle = self.spawn(ctx, view_state.generation);
+        }
+    }
+
+    fn teardown(&self, view_state: &mut Self::ViewState, _: &mut ViewCtx, _: Mut<Self::Element>) {
+        view_state.handle.abort();
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in MemoizedAwait::message"
+        );
+        let message = message.downcast::<AwaitOutput<Output>>().unwrap();
+        if message.generation != view_state.generation {
+            // The output of a future which was cancelled after it completed.
+            return MessageResult::Nop;
+        }
+        MessageResult::Action((self.on_output)(app_state, message.output))
+    }
+}
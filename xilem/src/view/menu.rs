@@ -0,0 +1,173 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::core::{DynMessage, MessageResult, Mut, NoElement, View, ViewId, ViewMarker};
+use crate::menu::MenuEntry;
+use crate::ViewCtx;
+
+/// Show `entries` in the menu bar of the window, calling `on_select` with the id of the
+/// selected item.
+///
+/// As with [`shortcut`](crate::view::shortcut), this allows the menu to depend on the state of
+/// the app, e.g. to disable saving whilst there are no changes. This view doesn't create any
+/// widgets, and so is used alongside other views with `fork`.
+/// The menu is only shown whilst this view is in the tree. If several menu views are in the
+/// tree, the one added most recently is shown.
+///
+/// The menu bar is only shown on Windows and macOS; see the [`menu`](crate::menu) module for
+/// details.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut Editor) -> impl WidgetView<Editor> {
+///     fork(
+///         editor_view(state),
+///         menu(
+///             [MenuEntry::submenu(
+///                 "File",
+///                 [
+///                     MenuEntry::item("save", "Save").with_enabled(state.has_changes()),
+///                     MenuEntry::Separator,
+///                     MenuEntry::item("quit", "Quit"),
+///                 ],
+///             )],
+///             |state: &mut Editor, id| match id {
+///                 "save" => state.save(),
+///                 "quit" => state.quit(),
+///                 _ => (),
+///             },
+///         ),
+///     )
+/// }
+/// ```
+pub fn menu<State, Action, F>(entries: impl IntoIterator<Item = MenuEntry>, on_select: F) -> Menu<F>
+where
+    F: Fn(&mut State, &str) -> Action + 'static,
+{
+    Menu {
+        entries: entries.into_iter().collect(),
+        callback: on_select,
+    }
+}
+
+/// The View for [`menu`].
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Menu<F> {
+    entries: Vec<MenuEntry>,
+    callback: F,
+}
+
+/// The message sent to a [`Menu`] when one of its items is selected, with the id of the item.
+#[derive(Debug)]
+pub(crate) struct MenuItemSelected(pub(crate) String);
+
+impl<F> ViewMarker for Menu<F> {}
+impl<State, Action, F> View<State, Action, ViewCtx> for Menu<F>
+where
+    F: Fn(&mut State, &str) -> Action + 'static,
+{
+    type Element = NoElement;
+
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.record_menu(self.entries.clone());
+        (NoElement, ())
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        (): Mut<Self::Element>,
+    ) {
+        if self.entries != prev.entries {
+            ctx.update_menu(self.entries.clone());
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, (): Mut<Self::Element>) {
+        ctx.remove_menu();
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Menu::message"
+        );
+        let selected = message.downcast::<MenuItemSelected>();
+        debug_assert!(
+            selected.is_ok(),
+            "Menu views only receive `MenuItemSelected` messages"
+        );
+        match selected {
+            Ok(selected) => MessageResult::Action((self.callback)(app_state, &selected.0)),
+            Err(message) => MessageResult::Stale(message),
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fork;
+    use crate::testing::AppTestHarness;
+    use crate::view::label;
+    use crate::{WidgetView, Xilem};
+
+    #[derive(Default)]
+    struct Editor {
+        changes: u32,
+        saves: u32,
+    }
+
+    fn file_menu(save_enabled: bool) -> Vec<MenuEntry> {
+        vec![MenuEntry::submenu(
+            "File",
+            [
+                MenuEntry::item("edit", "Edit"),
+                MenuEntry::item("save", "Save").with_enabled(save_enabled),
+            ],
+        )]
+    }
+
+    fn app_logic(state: &mut Editor) -> impl WidgetView<Editor> {
+        fork(
+            label(format!("Saved {} times", state.saves)),
+            menu(
+                file_menu(state.changes > 0),
+                |state: &mut Editor, id| match id {
+                    "edit" => state.changes += 1,
+                    "save" => {
+                        state.changes = 0;
+                        state.saves += 1;
+                    }
+                    _ => unreachable!(),
+                },
+            ),
+        )
+    }
+
+    #[test]
+    fn menu_follows_the_state() {
+        let mut harness = AppTestHarness::create(Xilem::new(Editor::default(), app_logic));
+        assert_eq!(harness.harness().menu(), file_menu(false));
+
+        harness.select_menu_item("edit");
+        assert_eq!(harness.state().changes, 1);
+        assert_eq!(harness.harness().menu(), file_menu(true));
+
+        harness.select_menu_item("save");
+        assert_eq!(harness.state().saves, 1);
+        assert_eq!(harness.harness().menu(), file_menu(false));
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// A gauge showing `value` within a range, which is from 0 to 1 unless set with
+/// [`Meter::range`].
+///
+/// This corresponds to the Masonry [`Meter`](masonry::widget::Meter) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::meter;
+/// # use xilem::WidgetView;
+///
+/// fn view(battery_percent: &mut f64) -> impl WidgetView<f64> {
+///     meter(*battery_percent)
+///         .range(0., 100.)
+///         .low(20.)
+///         .high(80.)
+///         .optimum(100.)
+/// }
+/// ```
+pub fn meter(value: f64) -> Meter {
+    Meter {
+        value,
+        range: (0.0, 1.0),
+        low: None,
+        high: None,
+        optimum: None,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Meter {
+    value: f64,
+    range: (f64, f64),
+    low: Option<f64>,
+    high: Option<f64>,
+    optimum: Option<f64>,
+}
+
+impl Meter {
+    /// Set the range of the value.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = (min, max);
+        self
+    }
+
+    /// Set the value under which the value is in the low band.
+    pub fn low(mut self, low: f64) -> Self {
+        self.low = Some(low);
+        self
+    }
+
+    /// Set the value over which the value is in the high band.
+    pub fn high(mut self, high: f64) -> Self {
+        self.high = Some(high);
+        self
+    }
+
+    /// Set the best value, which is the middle of the range by default.
+    pub fn optimum(mut self, optimum: f64) -> Self {
+        self.optimum = Some(optimum);
+        self
+    }
+}
+
+impl ViewMarker for Meter {}
+impl<State, Action> View<State, Action, ViewCtx> for Meter {
+    type Element = Pod<widget::Meter>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.with_leaf_action_widget(|ctx| {
+            let (min, max) = self.range;
+            let mut widget = widget::Meter::new(self.value).with_range(min, max);
+            if let Some(low) = self.low {
+                widget = widget.with_low(low);
+            }
+            if let Some(high) = self.high {
+                widget = widget.with_high(high);
+            }
+            if let Some(optimum) = self.optimum {
+                widget = widget.with_optimum(optimum);
+            }
+            ctx.new_pod(widget)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.value != self.value {
+            widget::Meter::set_value(&mut element, self.value);
+        }
+        if prev.range != self.range {
+            let (min, max) = self.range;
+            widget::Meter::set_range(&mut element, min, max);
+        }
+        if prev.low != self.low {
+            widget::Meter::set_low(&mut element, self.low);
+        }
+        if prev.high != self.high {
+            widget::Meter::set_high(&mut element, self.high);
+        }
+        if prev.optimum != self.optimum {
+            widget::Meter::set_optimum(&mut element, self.optimum);
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _id_path: &[ViewId],
+        message: DynMessage,
+        _app_state: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in Meter::message, but Meter doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
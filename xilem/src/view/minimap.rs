@@ -0,0 +1,105 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, View, ViewId, ViewMarker};
+use crate::{MessageResult, Pod, ViewCtx, WidgetView};
+
+/// A view which puts `child` into a scrollable region, with a map of the whole child on
+/// its right.
+///
+/// The map shows the part of the child in the viewport, and the user can click or drag on
+/// it to scroll there.
+///
+/// This corresponds to the Masonry [`Minimap`](masonry::widget::Minimap) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{minimap, prose};
+/// # use xilem::WidgetView;
+///
+/// fn view(source: &mut String) -> impl WidgetView<String> {
+///     minimap(prose(source.clone())).map_width(80.)
+/// }
+/// ```
+pub fn minimap<Child, State, Action>(child: Child) -> Minimap<Child, State, Action>
+where
+    Child: WidgetView<State, Action>,
+{
+    Minimap {
+        child,
+        map_width: widget::Minimap::<Child::Widget>::DEFAULT_MAP_WIDTH,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Minimap<V, State, Action = ()> {
+    child: V,
+    map_width: f64,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> Minimap<V, State, Action> {
+    /// Set the width of the map.
+    pub fn map_width(mut self, width: f64) -> Self {
+        self.map_width = width;
+        self
+    }
+}
+
+impl<V, State, Action> ViewMarker for Minimap<V, State, Action> {}
+impl<Child, State, Action> View<State, Action, ViewCtx> for Minimap<Child, State, Action>
+where
+    Child: WidgetView<State, Action>,
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::Minimap<Child::Widget>>;
+    type ViewState = Child::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (child, child_state) = self.child.build(ctx);
+        let widget = widget::Minimap::new_pod(child.inner).with_map_width(self.map_width);
+        (ctx.new_pod(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.map_width != self.map_width {
+            widget::Minimap::set_map_width(&mut element, self.map_width);
+        }
+        let mut portal = widget::Minimap::portal_mut(&mut element);
+        let child = widget::Portal::child_mut(&mut portal);
+        self.child.rebuild(&prev.child, view_state, ctx, child);
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        let mut portal = widget::Minimap::portal_mut(&mut element);
+        let child = widget::Portal::child_mut(&mut portal);
+        self.child.teardown(view_state, ctx, child);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(view_state, id_path, message, app_state)
+    }
+}
@@ -3,12 +3,50 @@
 
 //! Views for the widgets which are built-in to Masonry. These are the primitives your Xilem app's view tree will generally be constructed from.
 
+// The async views run their futures on the Tokio runtime of the app, which nothing drives on
+// the web, so they aren't available there.
+#[cfg(not(target_arch = "wasm32"))]
 mod task;
+#[cfg(not(target_arch = "wasm32"))]
 pub use task::*;
 
+#[cfg(not(target_arch = "wasm32"))]
 mod worker;
+#[cfg(not(target_arch = "wasm32"))]
 pub use worker::*;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod memoized_await;
+#[cfg(not(target_arch = "wasm32"))]
+pub use memoized_await::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod suspense;
+#[cfg(not(target_arch = "wasm32"))]
+pub use suspense::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod task_pool;
+#[cfg(not(target_arch = "wasm32"))]
+pub use task_pool::*;
+
+mod timer;
+pub use timer::*;
+
+mod shortcut;
+pub use shortcut::*;
+
+#[cfg(feature = "menu")]
+mod menu;
+#[cfg(feature = "menu")]
+pub use menu::*;
+
+mod command_palette;
+pub use command_palette::*;
+
+mod on_command;
+pub use on_command::*;
+
 mod button;
 pub use button::*;
 
@@ -36,14 +74,91 @@ pub use label::*;
 mod variable_label;
 pub use variable_label::*;
 
+mod title_bar;
+pub use title_bar::*;
+
+mod video;
+pub use video::*;
+
+mod waveform;
+pub use waveform::*;
+
+mod level_meter;
+pub use level_meter::*;
+
+mod virtual_keyboard;
+pub use virtual_keyboard::*;
+
+#[cfg(feature = "webview")]
+mod web_view;
+#[cfg(feature = "webview")]
+pub use web_view::*;
+
 mod progress_bar;
 pub use progress_bar::*;
 
+mod meter;
+pub use meter::*;
+
+mod heatmap;
+pub use heatmap::*;
+
 mod prose;
 pub use prose::*;
 
+mod render_viewport;
+pub use render_viewport::*;
+
 mod textbox;
 pub use textbox::*;
 
 mod portal;
 pub use portal::*;
+
+mod minimap;
+pub use minimap::*;
+
+mod sticky_section;
+pub use sticky_section::*;
+
+mod reorderable_list;
+pub use reorderable_list::*;
+
+mod selectable;
+pub use selectable::*;
+
+mod form;
+pub use form::*;
+
+mod dock;
+pub use dock::*;
+
+mod node_editor;
+pub use node_editor::*;
+
+mod page_viewer;
+pub use page_viewer::*;
+
+mod stepper;
+pub use stepper::*;
+
+mod status_bar;
+pub use status_bar::*;
+
+mod timeline;
+pub use timeline::*;
+
+mod error_boundary;
+pub use error_boundary::*;
+
+mod accessible;
+pub use accessible::*;
+
+mod labelled;
+pub use labelled::*;
+
+mod debug_tag;
+pub use debug_tag::*;
+
+mod hideable;
+pub use hideable::*;
@@ -0,0 +1,460 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+pub use masonry::widget::{Edge, Port};
+
+use masonry::widget::{self, NodeEditorAction, WidgetMut};
+use masonry::{Point, Widget};
+
+use crate::core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewElement,
+    ViewId, ViewMarker, ViewSequence,
+};
+use crate::{Pod, ViewCtx, WidgetView};
+
+type EdgeCallback<State, Action> = Box<dyn Fn(&mut State, Edge) -> Action + Send + Sync + 'static>;
+type MoveCallback<State, Action> =
+    Box<dyn Fn(&mut State, Vec<(usize, Point)>) -> Action + Send + Sync + 'static>;
+type SelectionCallback<State, Action> =
+    Box<dyn Fn(&mut State, Vec<usize>) -> Action + Send + Sync + 'static>;
+
+/// A canvas of `nodes`, which are [`graph_node`]s, linked by `edges`.
+///
+/// The user drags nodes by their header, drags edges between their ports, selects them
+/// within a rectangle, zooms with the mouse wheel and pans with the middle button.
+/// Nodes and edges refer to nodes by their index in `nodes`.
+///
+/// The editor moves the nodes while they're dragged, and then reports their new positions
+/// to [`on_nodes_moved`](NodeEditor::on_nodes_moved), which should store them. New edges are
+/// only reported to [`on_edge_created`](NodeEditor::on_edge_created), and are shown once
+/// they're in `edges`.
+///
+/// This corresponds to the Masonry [`NodeEditor`](masonry::widget::NodeEditor) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{graph_node, label, node_editor, Edge};
+/// use xilem::WidgetView;
+/// use masonry::Point;
+///
+/// struct Graph {
+///     nodes: Vec<(String, Point)>,
+///     edges: Vec<Edge>,
+/// }
+///
+/// fn view(graph: &mut Graph) -> impl WidgetView<Graph> {
+///     let nodes = graph
+///         .nodes
+///         .iter()
+///         .map(|(name, position)| graph_node(*position, 1, 1, label(name.clone())))
+///         .collect::<Vec<_>>();
+///     node_editor(nodes, graph.edges.clone())
+///         .on_edge_created(|graph: &mut Graph, edge| graph.edges.push(edge))
+///         .on_nodes_moved(|graph: &mut Graph, moves| {
+///             for (idx, position) in moves {
+///                 graph.nodes[idx].1 = position;
+///             }
+///         })
+/// }
+/// ```
+pub fn node_editor<State, Action, Seq>(
+    nodes: Seq,
+    edges: impl IntoIterator<Item = Edge>,
+) -> NodeEditor<Seq, State, Action>
+where
+    Seq: NodeSequence<State, Action>,
+{
+    NodeEditor {
+        nodes,
+        edges: edges.into_iter().collect(),
+        on_edge_created: None,
+        on_nodes_moved: None,
+        on_selection_changed: None,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct NodeEditor<Seq, State, Action = ()> {
+    nodes: Seq,
+    edges: Vec<Edge>,
+    on_edge_created: Option<EdgeCallback<State, Action>>,
+    on_nodes_moved: Option<MoveCallback<State, Action>>,
+    on_selection_changed: Option<SelectionCallback<State, Action>>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<Seq, State, Action> NodeEditor<Seq, State, Action> {
+    /// Set the function called with the edges the user drags between two ports.
+    pub fn on_edge_created<F>(mut self, on_edge_created: F) -> Self
+    where
+        F: Fn(&mut State, Edge) -> Action + Send + Sync + 'static,
+    {
+        self.on_edge_created = Some(Box::new(on_edge_created));
+        self
+    }
+
+    /// Set the function called with the indices and new positions of the nodes the user
+    /// dragged.
+    pub fn on_nodes_moved<F>(mut self, on_nodes_moved: F) -> Self
+    where
+        F: Fn(&mut State, Vec<(usize, Point)>) -> Action + Send + Sync + 'static,
+    {
+        self.on_nodes_moved = Some(Box::new(on_nodes_moved));
+        self
+    }
+
+    /// Set the function called with the indices of the selected nodes when they change.
+    pub fn on_selection_changed<F>(mut self, on_selection_changed: F) -> Self
+    where
+        F: Fn(&mut State, Vec<usize>) -> Action + Send + Sync + 'static,
+    {
+        self.on_selection_changed = Some(Box::new(on_selection_changed));
+        self
+    }
+}
+
+const NODES_VIEW_ID: ViewId = ViewId::new(0);
+
+impl<Seq, State, Action> ViewMarker for NodeEditor<Seq, State, Action> {}
+
+impl<State, Action, Seq> View<State, Action, ViewCtx> for NodeEditor<Seq, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    Seq: NodeSequence<State, Action>,
+{
+    type Element = Pod<widget::NodeEditor>;
+
+    type ViewState = Seq::SeqState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let mut elements = AppendVec::default();
+        let seq_state = ctx.with_id(NODES_VIEW_ID, |ctx| {
+            self.nodes.seq_build(ctx, &mut elements)
+        });
+        let pod = ctx.with_action_widget(|ctx| {
+            let mut widget = widget::NodeEditor::new();
+            for element in elements.into_inner() {
+                widget = widget.with_node_pod(
+                    element.content.inner,
+                    element.position,
+                    element.inputs,
+                    element.outputs,
+                );
+            }
+            for edge in &self.edges {
+                widget = widget.with_edge(*edge);
+            }
+            ctx.new_pod(widget)
+        });
+        (pod, seq_state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<Self::Element>,
+    ) {
+        let mut splice = NodeSplice::new(element);
+        ctx.with_id(NODES_VIEW_ID, |ctx| {
+            self.nodes
+                .seq_rebuild(&prev.nodes, view_state, ctx, &mut splice);
+        });
+        debug_assert!(splice.scratch.is_empty());
+        // The edges are set after the nodes, whose changes may have moved them.
+        if prev.edges != self.edges || splice.changed {
+            widget::NodeEditor::set_edges(&mut splice.element, self.edges.iter().copied());
+        }
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(NODES_VIEW_ID, |ctx| {
+            let mut splice = NodeSplice::new(element.reborrow_mut());
+            self.nodes.seq_teardown(view_state, ctx, &mut splice);
+            debug_assert!(splice.scratch.into_inner().is_empty());
+        });
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&NODES_VIEW_ID, rest)) => {
+                self.nodes.seq_message(view_state, rest, message, app_state)
+            }
+            None => match message.downcast::<masonry::Action>() {
+                Ok(action) => match (*action).downcast::<NodeEditorAction>() {
+                    Ok(NodeEditorAction::EdgeCreated(edge)) => match &self.on_edge_created {
+                        Some(on_edge_created) => {
+                            MessageResult::Action(on_edge_created(app_state, edge))
+                        }
+                        None => MessageResult::Nop,
+                    },
+                    Ok(NodeEditorAction::NodesMoved(moves)) => match &self.on_nodes_moved {
+                        Some(on_nodes_moved) => {
+                            MessageResult::Action(on_nodes_moved(app_state, moves))
+                        }
+                        None => MessageResult::Nop,
+                    },
+                    Ok(NodeEditorAction::SelectionChanged(selection)) => {
+                        match &self.on_selection_changed {
+                            Some(on_selection_changed) => {
+                                MessageResult::Action(on_selection_changed(app_state, selection))
+                            }
+                            None => MessageResult::Nop,
+                        }
+                    }
+                    Err(action) => {
+                        tracing::error!("Wrong action type in NodeEditor::message: {action:?}");
+                        MessageResult::Stale(Box::new(action))
+                    }
+                },
+                Err(message) => {
+                    tracing::error!("Wrong message type in NodeEditor::message");
+                    MessageResult::Stale(message)
+                }
+            },
+            _ => {
+                tracing::error!("Invalid id path in NodeEditor::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// A node of a [`node_editor`], showing `content` at `position` in the coordinates of the
+/// graph, with `inputs` input ports on its left and `outputs` output ports on its right.
+pub fn graph_node<State, Action, V>(
+    position: Point,
+    inputs: usize,
+    outputs: usize,
+    content: V,
+) -> GraphNode<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+{
+    GraphNode {
+        position,
+        inputs,
+        outputs,
+        content,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct GraphNode<V, State, Action = ()> {
+    position: Point,
+    inputs: usize,
+    outputs: usize,
+    content: V,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> ViewMarker for GraphNode<V, State, Action> {}
+
+impl<State, Action, V> View<State, Action, ViewCtx> for GraphNode<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = NodeElement;
+
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (content, state) = self.content.build(ctx);
+        let element = NodeElement {
+            position: self.position,
+            inputs: self.inputs,
+            outputs: self.outputs,
+            content: ctx.boxed_pod(content),
+        };
+        (element, state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        // The node may already have been moved there by the user.
+        if prev.position != self.position {
+            widget::NodeEditor::set_node_position(&mut element.parent, element.idx, self.position);
+        }
+        if (prev.inputs, prev.outputs) != (self.inputs, self.outputs) {
+            widget::NodeEditor::set_node_ports(
+                &mut element.parent,
+                element.idx,
+                self.inputs,
+                self.outputs,
+            );
+        }
+        let mut content = widget::NodeEditor::node_mut(&mut element.parent, element.idx);
+        self.content
+            .rebuild(&prev.content, view_state, ctx, content.downcast());
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        let mut content = widget::NodeEditor::node_mut(&mut element.parent, element.idx);
+        self.content.teardown(view_state, ctx, content.downcast());
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.content
+            .message(view_state, id_path, message, app_state)
+    }
+}
+
+/// A node of a [`NodeEditor`].
+pub struct NodeElement {
+    position: Point,
+    inputs: usize,
+    outputs: usize,
+    content: Pod<Box<dyn Widget>>,
+}
+
+pub struct NodeElementMut<'w> {
+    parent: WidgetMut<'w, widget::NodeEditor>,
+    idx: usize,
+}
+
+impl ViewElement for NodeElement {
+    type Mut<'w> = NodeElementMut<'w>;
+}
+
+impl SuperElement<NodeElement, ViewCtx> for NodeElement {
+    fn upcast(_ctx: &mut ViewCtx, child: NodeElement) -> Self {
+        child
+    }
+
+    fn with_downcast_val<R>(
+        mut this: Mut<Self>,
+        f: impl FnOnce(Mut<NodeElement>) -> R,
+    ) -> (Self::Mut<'_>, R) {
+        let r = {
+            let parent = this.parent.reborrow_mut();
+            let reborrow = NodeElementMut {
+                idx: this.idx,
+                parent,
+            };
+            f(reborrow)
+        };
+        (this, r)
+    }
+}
+
+// Used for building and rebuilding the ViewSequence
+pub struct NodeSplice<'w> {
+    idx: usize,
+    element: WidgetMut<'w, widget::NodeEditor>,
+    scratch: AppendVec<NodeElement>,
+    /// Whether nodes were inserted or removed, which changes the indices in the edges.
+    changed: bool,
+}
+
+impl<'w> NodeSplice<'w> {
+    fn new(element: WidgetMut<'w, widget::NodeEditor>) -> Self {
+        Self {
+            idx: 0,
+            element,
+            scratch: AppendVec::default(),
+            changed: false,
+        }
+    }
+
+    fn insert_element(&mut self, element: NodeElement) {
+        widget::NodeEditor::insert_node_pod(
+            &mut self.element,
+            self.idx,
+            element.content.inner,
+            element.position,
+            element.inputs,
+            element.outputs,
+        );
+        self.idx += 1;
+        self.changed = true;
+    }
+}
+
+impl ElementSplice<NodeElement> for NodeSplice<'_> {
+    fn with_scratch<R>(&mut self, f: impl FnOnce(&mut AppendVec<NodeElement>) -> R) -> R {
+        let ret = f(&mut self.scratch);
+        let elements: Vec<_> = self.scratch.drain().collect();
+        for element in elements {
+            self.insert_element(element);
+        }
+        ret
+    }
+
+    fn insert(&mut self, element: NodeElement) {
+        self.insert_element(element);
+    }
+
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<NodeElement>) -> R) -> R {
+        let child = NodeElementMut {
+            parent: self.element.reborrow_mut(),
+            idx: self.idx,
+        };
+        let ret = f(child);
+        self.idx += 1;
+        ret
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.idx += n;
+    }
+
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<NodeElement>) -> R) -> R {
+        let ret = {
+            let child = NodeElementMut {
+                parent: self.element.reborrow_mut(),
+                idx: self.idx,
+            };
+            f(child)
+        };
+        widget::NodeEditor::remove_node(&mut self.element, self.idx);
+        self.changed = true;
+        ret
+    }
+}
+
+/// A sequence of [`graph_node`]s, the nodes of a [`NodeEditor`].
+pub trait NodeSequence<State, Action = ()>:
+    ViewSequence<State, Action, ViewCtx, NodeElement>
+{
+}
+
+impl<Seq, State, Action> NodeSequence<State, Action> for Seq where
+    Seq: ViewSequence<State, Action, ViewCtx, NodeElement>
+{
+}
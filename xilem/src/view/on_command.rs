@@ -0,0 +1,115 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+
+use crate::command::DeliveredCommand;
+use crate::core::{DynMessage, MessageResult, Mut, NoElement, View, ViewId, ViewMarker};
+use crate::ViewCtx;
+
+/// Call `handler` with the commands of type `C` sent through a [`CommandBus`](crate::CommandBus).
+///
+/// This view doesn't create any widgets, and so is used alongside other views with `fork`.
+/// It only receives commands whilst it is in the tree.
+/// Use [`OnCommand::scope`] to only receive the commands sent to a specific
+/// [`CommandTarget::Scope`](crate::CommandTarget::Scope), e.g. for one of several documents.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(Debug)]
+/// struct InsertDate;
+///
+/// fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+///     let bus = state.commands.clone();
+///     flex((
+///         button("Insert date", move |_: &mut AppState| {
+///             drop(bus.send(CommandTarget::All, InsertDate));
+///         }),
+///         fork(
+///             editor(&state.document),
+///             on_command(|state: &mut AppState, _: &InsertDate| state.document.insert_date()),
+///         ),
+///     ))
+/// }
+/// ```
+pub fn on_command<State, Action, C, F>(handler: F) -> OnCommand<C, F>
+where
+    C: Any + Send + Sync,
+    F: Fn(&mut State, &C) -> Action + 'static,
+{
+    OnCommand {
+        scope: None,
+        handler,
+        phantom: PhantomData,
+    }
+}
+
+/// The View for [`on_command`].
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct OnCommand<C, F> {
+    scope: Option<u64>,
+    handler: F,
+    phantom: PhantomData<fn(&C)>,
+}
+
+impl<C, F> OnCommand<C, F> {
+    /// Only receive the commands sent to `CommandTarget::Scope(scope)`, or to all views.
+    pub fn scope(mut self, scope: u64) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+}
+
+impl<C, F> ViewMarker for OnCommand<C, F> {}
+impl<State, Action, C, F> View<State, Action, ViewCtx> for OnCommand<C, F>
+where
+    C: Any + Send + Sync,
+    F: Fn(&mut State, &C) -> Action + 'static,
+{
+    type Element = NoElement;
+
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.record_command_subscription(TypeId::of::<C>(), self.scope);
+        (NoElement, ())
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        (): Mut<Self::Element>,
+    ) {
+        if self.scope != prev.scope {
+            ctx.remove_command_subscription(TypeId::of::<C>(), prev.scope);
+            ctx.record_command_subscription(TypeId::of::<C>(), self.scope);
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, (): Mut<Self::Element>) {
+        ctx.remove_command_subscription(TypeId::of::<C>(), self.scope);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in OnCommand::message"
+        );
+        let command = message.downcast::<DeliveredCommand>().unwrap();
+        let command = command
+            .0
+            .downcast_ref::<C>()
+            .expect("Commands are only delivered to the views subscribed to their type");
+        MessageResult::Action((self.handler)(app_state, command))
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+pub use masonry::widget::{PageSource, PageWord};
+
+use masonry::widget::{self, PageViewerAction};
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+type PageCallback<State, Action> = Box<dyn Fn(&mut State, usize) -> Action + Send + Sync + 'static>;
+type TextCallback<State, Action> =
+    Box<dyn Fn(&mut State, String) -> Action + Send + Sync + 'static>;
+
+/// The pages of a document, such as a PDF file, one below the other, with their thumbnails
+/// in a sidebar.
+///
+/// The pages are rendered by `source` as they come into view. The user scrolls through
+/// them, zooms with Ctrl and the mouse wheel, and selects their [text](PageSource::page_text)
+/// by dragging over it. The source is only replaced when another [`Arc`] is given.
+///
+/// This corresponds to the Masonry [`PageViewer`](masonry::widget::PageViewer) widget.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use xilem::view::{page_viewer, PageSource};
+/// use xilem::WidgetView;
+///
+/// struct Reader {
+///     document: Arc<dyn PageSource>,
+///     page: usize,
+///     clipboard: String,
+/// }
+///
+/// fn view(reader: &mut Reader) -> impl WidgetView<Reader> {
+///     page_viewer(reader.document.clone())
+///         .on_page_change(|reader: &mut Reader, page| reader.page = page)
+///         .on_text_selected(|reader: &mut Reader, text| reader.clipboard = text)
+/// }
+/// ```
+pub fn page_viewer<State, Action>(source: Arc<dyn PageSource>) -> PageViewer<State, Action> {
+    PageViewer {
+        source,
+        zoom: None,
+        page: None,
+        thumbnails: true,
+        on_page_change: None,
+        on_text_selected: None,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct PageViewer<State, Action = ()> {
+    source: Arc<dyn PageSource>,
+    zoom: Option<f64>,
+    page: Option<usize>,
+    thumbnails: bool,
+    on_page_change: Option<PageCallback<State, Action>>,
+    on_text_selected: Option<TextCallback<State, Action>>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<State, Action> PageViewer<State, Action> {
+    /// Set how much the pages are scaled.
+    ///
+    /// The pages are scaled when this changes between two rebuilds. In between, the user
+    /// can zoom freely.
+    pub fn zoom(mut self, zoom: f64) -> Self {
+        self.zoom = Some(zoom);
+        self
+    }
+
+    /// Scroll to the top of `page`.
+    ///
+    /// The view is moved when this changes between two rebuilds. In between, the user
+    /// can scroll freely.
+    pub fn page(mut self, page: usize) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Show or hide the thumbnails of the pages, which are shown by default.
+    pub fn thumbnails(mut self, thumbnails: bool) -> Self {
+        self.thumbnails = thumbnails;
+        self
+    }
+
+    /// Set the callback called with the page at the top of the view when it changes.
+    pub fn on_page_change<F>(mut self, on_page_change: F) -> Self
+    where
+        F: Fn(&mut State, usize) -> Action + Send + Sync + 'static,
+    {
+        self.on_page_change = Some(Box::new(on_page_change));
+        self
+    }
+
+    /// Set the callback called with the text selected by the user, with words separated by
+    /// spaces and pages by new lines.
+    pub fn on_text_selected<F>(mut self, on_text_selected: F) -> Self
+    where
+        F: Fn(&mut State, String) -> Action + Send + Sync + 'static,
+    {
+        self.on_text_selected = Some(Box::new(on_text_selected));
+        self
+    }
+}
+
+impl<State, Action> ViewMarker for PageViewer<State, Action> {}
+impl<State: 'static, Action: 'static> View<State, Action, ViewCtx> for PageViewer<State, Action> {
+    type Element = Pod<widget::PageViewer>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.with_leaf_action_widget(|ctx| {
+            let mut widget =
+                widget::PageViewer::new(self.source.clone()).with_thumbnails(self.thumbnails);
+            if let Some(zoom) = self.zoom {
+                widget = widget.with_zoom(zoom);
+            }
+            if let Some(page) = self.page {
+                widget = widget.with_page(page);
+            }
+            ctx.new_pod(widget)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if !Arc::ptr_eq(&prev.source, &self.source) {
+            widget::PageViewer::set_source(&mut element, self.source.clone());
+        }
+        if let Some(zoom) = self.zoom {
+            if prev.zoom != Some(zoom) {
+                widget::PageViewer::set_zoom(&mut element, zoom);
+            }
+        }
+        if let Some(page) = self.page {
+            if prev.page != Some(page) {
+                widget::PageViewer::go_to_page(&mut element, page);
+            }
+        }
+        if prev.thumbnails != self.thumbnails {
+            widget::PageViewer::set_thumbnails(&mut element, self.thumbnails);
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in PageViewer::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match (*action).downcast::<PageViewerAction>() {
+                Ok(PageViewerAction::PageChanged(page)) => match &self.on_page_change {
+                    Some(on_page_change) => MessageResult::Action(on_page_change(app_state, page)),
+                    None => MessageResult::Nop,
+                },
+                Ok(PageViewerAction::TextSelected(text)) => match &self.on_text_selected {
+                    Some(on_text_selected) => {
+                        MessageResult::Action(on_text_selected(app_state, text))
+                    }
+                    None => MessageResult::Nop,
+                },
+                Err(action) => {
+                    tracing::error!("Wrong action type in PageViewer::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in PageViewer::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
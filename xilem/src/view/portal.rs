@@ -3,11 +3,16 @@
 
 use std::marker::PhantomData;
 
-use masonry::widget;
+use masonry::widget::{self, OverscrollBehavior, ScrollConfig, ScrollPosition};
+use vello::kurbo::Point;
 
 use crate::core::{DynMessage, Mut, ViewMarker};
 use crate::{MessageResult, Pod, View, ViewCtx, ViewId, WidgetView};
 
+type ScrollCallback<State, Action> =
+    Box<dyn Fn(&mut State, ScrollPosition) -> Action + Send + Sync + 'static>;
+type EdgeCallback<State, Action> = Box<dyn Fn(&mut State) -> Action + Send + Sync + 'static>;
+
 /// A view which puts `child` into a scrollable region.
 ///
 /// This corresponds to the Masonry [`Portal`](masonry::widget::Portal) widget.
@@ -17,6 +22,14 @@ where
 {
     Portal {
         child,
+        viewport_pos: None,
+        on_scroll: None,
+        on_reach_top: None,
+        on_reach_bottom: None,
+        on_refresh: None,
+        refreshing: false,
+        overscroll_behavior: OverscrollBehavior::default(),
+        scroll_config: ScrollConfig::default(),
         phantom: PhantomData,
     }
 }
@@ -24,9 +37,112 @@ where
 #[must_use = "View values do nothing unless provided to Xilem."]
 pub struct Portal<V, State, Action> {
     child: V,
+    viewport_pos: Option<Point>,
+    on_scroll: Option<ScrollCallback<State, Action>>,
+    on_reach_top: Option<EdgeCallback<State, Action>>,
+    on_reach_bottom: Option<EdgeCallback<State, Action>>,
+    on_refresh: Option<EdgeCallback<State, Action>>,
+    refreshing: bool,
+    overscroll_behavior: OverscrollBehavior,
+    scroll_config: ScrollConfig,
     phantom: PhantomData<(State, Action)>,
 }
 
+impl<V, State, Action> Portal<V, State, Action> {
+    /// Set the position of the viewport in the content, e.g. to restore a reading position.
+    ///
+    /// The viewport is moved when this position changes between two rebuilds, so the
+    /// position reported by [`on_scroll`](Self::on_scroll) can be stored in the app state
+    /// and passed back here.
+    pub fn viewport_pos(mut self, position: Point) -> Self {
+        self.viewport_pos = Some(position);
+        self
+    }
+
+    /// Set what is shown when the content is scrolled past its edges.
+    ///
+    /// The default is [`OverscrollBehavior::Clamp`].
+    pub fn overscroll_behavior(mut self, behavior: OverscrollBehavior) -> Self {
+        self.overscroll_behavior = behavior;
+        self
+    }
+
+    /// Set how the viewport moves when the mouse wheel or the trackpad is scrolled.
+    ///
+    /// The default scrolls by the platform's distance per wheel tick, follows the trackpad,
+    /// and scrolls horizontally with <kbd>Shift</kbd> held.
+    pub fn scroll_config(mut self, config: ScrollConfig) -> Self {
+        self.scroll_config = config;
+        self
+    }
+
+    /// Set the callback called when the viewport is moved by the user, or to show a child.
+    pub fn on_scroll<F>(mut self, on_scroll: F) -> Self
+    where
+        F: Fn(&mut State, ScrollPosition) -> Action + Send + Sync + 'static,
+    {
+        self.on_scroll = Some(Box::new(on_scroll));
+        self
+    }
+
+    /// Set the callback called when scrolling reaches the top of the content.
+    ///
+    /// If both are set, the action of this callback is returned instead of that of
+    /// [`on_scroll`](Self::on_scroll), which is still called.
+    pub fn on_reach_top<F>(mut self, on_reach_top: F) -> Self
+    where
+        F: Fn(&mut State) -> Action + Send + Sync + 'static,
+    {
+        self.on_reach_top = Some(Box::new(on_reach_top));
+        self
+    }
+
+    /// Set the callback called when scrolling reaches the bottom of the content, e.g. to
+    /// load more items for infinite scrolling.
+    ///
+    /// If both are set, the action of this callback is returned instead of that of
+    /// [`on_scroll`](Self::on_scroll), which is still called.
+    pub fn on_reach_bottom<F>(mut self, on_reach_bottom: F) -> Self
+    where
+        F: Fn(&mut State) -> Action + Send + Sync + 'static,
+    {
+        self.on_reach_bottom = Some(Box::new(on_reach_bottom));
+        self
+    }
+
+    /// Enable pull-to-refresh, calling `on_refresh` when the content is pulled down far enough.
+    ///
+    /// A spinner is shown above the content while [`refreshing`](Self::refreshing) is set,
+    /// so the callback should set it in the app state, and unset it when the refresh is done.
+    ///
+    /// See [`widget::Portal::with_pull_to_refresh`].
+    pub fn on_refresh<F>(mut self, on_refresh: F) -> Self
+    where
+        F: Fn(&mut State) -> Action + Send + Sync + 'static,
+    {
+        self.on_refresh = Some(Box::new(on_refresh));
+        self
+    }
+
+    /// Set whether a refresh started with [`on_refresh`](Self::on_refresh) is in progress.
+    pub fn refreshing(mut self, refreshing: bool) -> Self {
+        self.refreshing = refreshing;
+        self
+    }
+}
+
+/// The view state of [`Portal`].
+pub struct PortalState<ChildState> {
+    child: ChildState,
+    /// The last scroll position submitted by the widget, used to only call the
+    /// edge callbacks when an edge is reached.
+    position: Option<ScrollPosition>,
+}
+
+// The child is built with this id, so that the messages of the portal itself,
+// which have an empty path, can be told apart from those of the child.
+const CHILD_VIEW_ID: ViewId = ViewId::new(0);
+
 impl<V, State, Action> ViewMarker for Portal<V, State, Action> {}
 impl<Child, State, Action> View<State, Action, ViewCtx> for Portal<Child, State, Action>
 where
@@ -35,14 +151,26 @@ where
     Action: 'static,
 {
     type Element = Pod<widget::Portal<Child::Widget>>;
-    type ViewState = Child::ViewState;
+    type ViewState = PortalState<Child::ViewState>;
 
     fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
-        // The Portal `View` doesn't get any messages directly (yet - scroll events?), so doesn't need to
-        // use ctx.with_id.
-        let (child, child_state) = self.child.build(ctx);
-        let widget_pod = ctx.new_pod(widget::Portal::new_pod(child.inner));
-        (widget_pod, child_state)
+        let (child, child_state) = ctx.with_id(CHILD_VIEW_ID, |ctx| self.child.build(ctx));
+        let widget_pod = ctx.with_action_widget(|ctx| {
+            let mut widget = widget::Portal::new_pod(child.inner);
+            if let Some(position) = self.viewport_pos {
+                widget = widget.with_viewport_pos(position);
+            }
+            widget = widget
+                .with_pull_to_refresh(self.on_refresh.is_some())
+                .with_overscroll_behavior(self.overscroll_behavior)
+                .with_scroll_config(self.scroll_config);
+            ctx.new_pod(widget)
+        });
+        let view_state = PortalState {
+            child: child_state,
+            position: None,
+        };
+        (widget_pod, view_state)
     }
 
     fn rebuild(
@@ -53,8 +181,26 @@ where
         mut element: Mut<Self::Element>,
     ) {
         let child_element = widget::Portal::child_mut(&mut element);
-        self.child
-            .rebuild(&prev.child, view_state, ctx, child_element);
+        ctx.with_id(CHILD_VIEW_ID, |ctx| {
+            self.child
+                .rebuild(&prev.child, &mut view_state.child, ctx, child_element);
+        });
+        if let Some(position) = self.viewport_pos {
+            if prev.viewport_pos != self.viewport_pos {
+                widget::Portal::set_viewport_pos(&mut element, position);
+            }
+        }
+        if prev.overscroll_behavior != self.overscroll_behavior {
+            widget::Portal::set_overscroll_behavior(&mut element, self.overscroll_behavior);
+        }
+        if prev.scroll_config != self.scroll_config {
+            widget::Portal::set_scroll_config(&mut element, self.scroll_config);
+        }
+        if prev.on_refresh.is_some() != self.on_refresh.is_some() {
+            widget::Portal::set_pull_to_refresh(&mut element, self.on_refresh.is_some());
+        }
+        // The widget starts refreshing by itself, so this is always synced with the app state.
+        widget::Portal::set_refreshing(&mut element, self.refreshing && self.on_refresh.is_some());
     }
 
     fn teardown(
@@ -64,7 +210,11 @@ where
         mut element: Mut<Self::Element>,
     ) {
         let child_element = widget::Portal::child_mut(&mut element);
-        self.child.teardown(view_state, ctx, child_element);
+        ctx.with_id(CHILD_VIEW_ID, |ctx| {
+            self.child
+                .teardown(&mut view_state.child, ctx, child_element);
+        });
+        ctx.teardown_leaf(element);
     }
 
     fn message(
@@ -74,6 +224,49 @@ where
         message: DynMessage,
         app_state: &mut State,
     ) -> MessageResult<Action> {
-        self.child.message(view_state, id_path, message, app_state)
+        if let Some((start, rest)) = id_path.split_first() {
+            if *start != CHILD_VIEW_ID {
+                return MessageResult::Stale(message);
+            }
+            return self
+                .child
+                .message(&mut view_state.child, rest, message, app_state);
+        }
+        let action = match message.downcast::<masonry::Action>() {
+            Ok(action) => *action,
+            Err(message) => {
+                tracing::error!("Wrong message type in Portal::message");
+                return MessageResult::Stale(message);
+            }
+        };
+        match action.downcast::<widget::PortalAction>() {
+            Ok(widget::PortalAction::RefreshRequested) => match &self.on_refresh {
+                Some(on_refresh) => MessageResult::Action(on_refresh(app_state)),
+                None => MessageResult::Nop,
+            },
+            Ok(widget::PortalAction::Scrolled(position)) => {
+                let prev = view_state.position.replace(position);
+                let reached_top = position.at_top() && !prev.is_some_and(|p| p.at_top());
+                let reached_bottom = position.at_bottom() && !prev.is_some_and(|p| p.at_bottom());
+
+                let mut result = MessageResult::Nop;
+                if let Some(on_scroll) = &self.on_scroll {
+                    result = MessageResult::Action(on_scroll(app_state, position));
+                }
+                if let Some(on_reach_top) = self.on_reach_top.as_ref().filter(|_| reached_top) {
+                    result = MessageResult::Action(on_reach_top(app_state));
+                }
+                if let Some(on_reach_bottom) =
+                    self.on_reach_bottom.as_ref().filter(|_| reached_bottom)
+                {
+                    result = MessageResult::Action(on_reach_bottom(app_state));
+                }
+                result
+            }
+            Err(action) => {
+                tracing::error!("Wrong action type in Portal::message: {action:?}");
+                MessageResult::Stale(Box::new(action))
+            }
+        }
     }
 }
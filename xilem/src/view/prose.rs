@@ -1,7 +1,7 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use masonry::text::{ArcStr, StyleProperty};
+use masonry::text::{ArcStr, FindPattern, StyleProperty};
 use masonry::widget::{self, LineBreaking};
 use vello::peniko::Brush;
 
@@ -15,6 +15,8 @@ pub fn prose(content: impl Into<ArcStr>) -> Prose {
         alignment: TextAlignment::default(),
         text_size: masonry::theme::TEXT_SIZE_NORMAL,
         line_break_mode: LineBreaking::WordWrap,
+        find: None,
+        active_find_match: None,
     }
 }
 
@@ -36,6 +38,8 @@ pub struct Prose {
     alignment: TextAlignment,
     text_size: f32,
     line_break_mode: LineBreaking,
+    find: Option<FindPattern>,
+    active_find_match: Option<usize>,
     // TODO: disabled: bool,
     // TODO: add more attributes of `masonry::widget::Prose`
 }
@@ -61,6 +65,24 @@ impl Prose {
         self.line_break_mode = line_break_mode;
         self
     }
+
+    /// Highlight the matches of `pattern` in the text, e.g. for a search bar.
+    ///
+    /// The matches are the ranges returned by [`FindPattern::find_in`] for the text, which
+    /// lets the app count them to navigate with [`active_find_match`](Self::active_find_match).
+    pub fn find(mut self, pattern: Option<FindPattern>) -> Self {
+        self.find = pattern;
+        self
+    }
+
+    /// Select the match of the [`find`](Self::find) pattern at `index`, and scroll it into view.
+    ///
+    /// The match is selected when the index or the pattern changes, so it isn't selected
+    /// when the view is first built.
+    pub fn active_find_match(mut self, index: Option<usize>) -> Self {
+        self.active_find_match = index;
+        self
+    }
 }
 
 fn line_break_clips(linebreaking: LineBreaking) -> bool {
@@ -77,7 +99,8 @@ impl<State, Action> View<State, Action, ViewCtx> for Prose {
             .with_brush(self.text_brush.clone())
             .with_alignment(self.alignment)
             .with_style(StyleProperty::FontSize(self.text_size))
-            .with_word_wrap(self.line_break_mode == LineBreaking::WordWrap);
+            .with_word_wrap(self.line_break_mode == LineBreaking::WordWrap)
+            .with_find_pattern(self.find.clone());
         let widget_pod = ctx.new_pod(
             widget::Prose::from_text_area(text_area)
                 .with_clip(line_break_clips(self.line_break_mode)),
@@ -105,6 +128,14 @@ impl<State, Action> View<State, Action, ViewCtx> for Prose {
         if prev.text_size != self.text_size {
             widget::TextArea::insert_style(&mut text_area, StyleProperty::FontSize(self.text_size));
         }
+        if prev.find != self.find {
+            widget::TextArea::set_find_pattern(&mut text_area, self.find.clone());
+        }
+        if prev.find != self.find || prev.active_find_match != self.active_find_match {
+            if let Some(index) = self.active_find_match {
+                widget::TextArea::select_find_match(&mut text_area, index);
+            }
+        }
         if prev.line_break_mode != self.line_break_mode {
             widget::TextArea::set_word_wrap(
                 &mut text_area,
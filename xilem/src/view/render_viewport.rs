@@ -0,0 +1,85 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The widget whose content is rendered with wgpu.
+
+use masonry::widget::{self, ViewportTarget};
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// A viewport rendered by `callback` with wgpu, e.g. to show a 3D model.
+///
+/// The callback is given a texture the size of the view each time the window is redrawn,
+/// which happens at least once after each rebuild. It is only taken from the view when
+/// the widget is created, so it should read the data to render from state it shares
+/// with the app, e.g. through an `Arc<Mutex<_>>`.
+///
+/// Corresponds to the [`RenderViewport`](widget::RenderViewport) widget.
+pub fn render_viewport<F>(callback: F) -> RenderViewport<F>
+where
+    F: FnMut(&ViewportTarget<'_>) + Clone + Send + Sync + 'static,
+{
+    RenderViewport {
+        callback,
+        animated: false,
+    }
+}
+
+/// The [`View`] created by [`render_viewport`].
+///
+/// See `render_viewport`'s docs for more details.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct RenderViewport<F> {
+    callback: F,
+    animated: bool,
+}
+
+impl<F> RenderViewport<F> {
+    /// Redraw the viewport on every frame, e.g. for a spinning model.
+    pub fn animated(mut self, animated: bool) -> Self {
+        self.animated = animated;
+        self
+    }
+}
+
+impl<F> ViewMarker for RenderViewport<F> {}
+impl<F, State, Action> View<State, Action, ViewCtx> for RenderViewport<F>
+where
+    F: FnMut(&ViewportTarget<'_>) + Clone + Send + Sync + 'static,
+{
+    type Element = Pod<widget::RenderViewport>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let widget = widget::RenderViewport::new(self.callback.clone()).animated(self.animated);
+        (ctx.new_pod(widget), ())
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.animated != self.animated {
+            widget::RenderViewport::set_animated(&mut element, self.animated);
+        }
+        // The app state which the callback renders may have changed
+        widget::RenderViewport::request_render(&mut element);
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, _: &mut ViewCtx, _: Mut<Self::Element>) {}
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _: &[ViewId],
+        message: DynMessage,
+        _: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in RenderViewport::message, but RenderViewport doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::widget::{self, RowMoved, WidgetMut};
+use masonry::Widget;
+
+use crate::core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewElement,
+    ViewId, ViewMarker, ViewSequence,
+};
+use crate::{Pod, ViewCtx};
+
+/// A vertical list whose rows can be dragged to new positions by their handles.
+///
+/// When a row is dropped, `on_move` is called with the index of the row before and after
+/// the move, so that the app can reorder its data with `vec.insert(to, vec.remove(from))`.
+///
+/// This corresponds to the Masonry [`ReorderableList`](masonry::widget::ReorderableList) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{label, reorderable_list};
+/// # use xilem::WidgetView;
+///
+/// # fn view() -> impl WidgetView<Vec<String>> {
+/// # let songs: &Vec<String> = &vec![];
+/// reorderable_list(
+///     songs.iter().map(|song| label(song.clone())).collect::<Vec<_>>(),
+///     |songs: &mut Vec<String>, from, to| {
+///         let song = songs.remove(from);
+///         songs.insert(to, song);
+///     },
+/// )
+/// # }
+/// ```
+pub fn reorderable_list<State, Action, Seq, F>(
+    sequence: Seq,
+    on_move: F,
+) -> ReorderableList<Seq, F, State, Action>
+where
+    Seq: ReorderableListSequence<State, Action>,
+    F: Fn(&mut State, usize, usize) -> Action + Send + Sync + 'static,
+{
+    ReorderableList {
+        sequence,
+        on_move,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct ReorderableList<Seq, F, State, Action = ()> {
+    sequence: Seq,
+    on_move: F,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<Seq, F, State, Action> ViewMarker for ReorderableList<Seq, F, State, Action> {}
+
+impl<State, Action, Seq, F> View<State, Action, ViewCtx> for ReorderableList<Seq, F, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    Seq: ReorderableListSequence<State, Action>,
+    F: Fn(&mut State, usize, usize) -> Action + Send + Sync + 'static,
+{
+    type Element = Pod<widget::ReorderableList>;
+
+    type ViewState = Seq::SeqState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let mut elements = AppendVec::default();
+        let seq_state = self.sequence.seq_build(ctx, &mut elements);
+        let pod = ctx.with_action_widget(|ctx| {
+            let mut widget = widget::ReorderableList::new();
+            for element in elements.into_inner() {
+                widget = widget.with_child_pod(element.child.inner);
+            }
+            ctx.new_pod(widget)
+        });
+        (pod, seq_state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<Self::Element>,
+    ) {
+        let mut splice = ReorderableListSplice::new(element);
+        self.sequence
+            .seq_rebuild(&prev.sequence, view_state, ctx, &mut splice);
+        debug_assert!(splice.scratch.is_empty());
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        let mut splice = ReorderableListSplice::new(element.reborrow_mut());
+        self.sequence.seq_teardown(view_state, ctx, &mut splice);
+        debug_assert!(splice.scratch.into_inner().is_empty());
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if !id_path.is_empty() {
+            return self
+                .sequence
+                .seq_message(view_state, id_path, message, app_state);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match (*action).downcast::<RowMoved>() {
+                Ok(RowMoved { from, to }) => {
+                    MessageResult::Action((self.on_move)(app_state, from, to))
+                }
+                Err(action) => {
+                    tracing::error!("Wrong action type in ReorderableList::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in ReorderableList::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// A row of a [`ReorderableList`].
+pub struct ReorderableListElement {
+    child: Pod<Box<dyn Widget>>,
+}
+
+pub struct ReorderableListElementMut<'w> {
+    parent: WidgetMut<'w, widget::ReorderableList>,
+    idx: usize,
+}
+
+impl ViewElement for ReorderableListElement {
+    type Mut<'w> = ReorderableListElementMut<'w>;
+}
+
+impl SuperElement<ReorderableListElement, ViewCtx> for ReorderableListElement {
+    fn upcast(_ctx: &mut ViewCtx, child: ReorderableListElement) -> Self {
+        child
+    }
+
+    fn with_downcast_val<R>(
+        mut this: Mut<Self>,
+        f: impl FnOnce(Mut<ReorderableListElement>) -> R,
+    ) -> (Self::Mut<'_>, R) {
+        let r = {
+            let parent = this.parent.reborrow_mut();
+            let reborrow = ReorderableListElementMut {
+                idx: this.idx,
+                parent,
+            };
+            f(reborrow)
+        };
+        (this, r)
+    }
+}
+
+impl<W: Widget> SuperElement<Pod<W>, ViewCtx> for ReorderableListElement {
+    fn upcast(ctx: &mut ViewCtx, child: Pod<W>) -> Self {
+        ReorderableListElement {
+            child: ctx.boxed_pod(child),
+        }
+    }
+
+    fn with_downcast_val<R>(
+        mut this: Mut<Self>,
+        f: impl FnOnce(Mut<Pod<W>>) -> R,
+    ) -> (Mut<Self>, R) {
+        let ret = {
+            let mut child = widget::ReorderableList::child_mut(&mut this.parent, this.idx);
+            f(child.downcast())
+        };
+        (this, ret)
+    }
+}
+
+// Used for building and rebuilding the ViewSequence
+pub struct ReorderableListSplice<'w> {
+    idx: usize,
+    element: WidgetMut<'w, widget::ReorderableList>,
+    scratch: AppendVec<ReorderableListElement>,
+}
+
+impl<'w> ReorderableListSplice<'w> {
+    fn new(element: WidgetMut<'w, widget::ReorderableList>) -> Self {
+        Self {
+            idx: 0,
+            element,
+            scratch: AppendVec::default(),
+        }
+    }
+}
+
+impl ElementSplice<ReorderableListElement> for ReorderableListSplice<'_> {
+    fn with_scratch<R>(
+        &mut self,
+        f: impl FnOnce(&mut AppendVec<ReorderableListElement>) -> R,
+    ) -> R {
+        let ret = f(&mut self.scratch);
+        for element in self.scratch.drain() {
+            widget::ReorderableList::insert_child_pod(
+                &mut self.element,
+                self.idx,
+                element.child.inner,
+            );
+            self.idx += 1;
+        }
+        ret
+    }
+
+    fn insert(&mut self, element: ReorderableListElement) {
+        widget::ReorderableList::insert_child_pod(&mut self.element, self.idx, element.child.inner);
+        self.idx += 1;
+    }
+
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<ReorderableListElement>) -> R) -> R {
+        let child = ReorderableListElementMut {
+            parent: self.element.reborrow_mut(),
+            idx: self.idx,
+        };
+        let ret = f(child);
+        self.idx += 1;
+        ret
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.idx += n;
+    }
+
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<ReorderableListElement>) -> R) -> R {
+        let ret = {
+            let child = ReorderableListElementMut {
+                parent: self.element.reborrow_mut(),
+                idx: self.idx,
+            };
+            f(child)
+        };
+        widget::ReorderableList::remove_child(&mut self.element, self.idx);
+        ret
+    }
+}
+
+/// A sequence of views which are the rows of a [`ReorderableList`].
+pub trait ReorderableListSequence<State, Action = ()>:
+    ViewSequence<State, Action, ViewCtx, ReorderableListElement>
+{
+}
+
+impl<Seq, State, Action> ReorderableListSequence<State, Action> for Seq where
+    Seq: ViewSequence<State, Action, ViewCtx, ReorderableListElement>
+{
+}
@@ -0,0 +1,136 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::selection::{Selection, SelectionInput};
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, View, ViewId, ViewMarker};
+use crate::{MessageResult, Pod, ViewCtx, WidgetView};
+
+/// The item at `index` of a list, table or tree whose selection is stored in the app state.
+///
+/// Clicks and keys on the item are applied to the [`Selection`] returned by `lens`, and the
+/// app is rebuilt. The `child` should show whether the item is selected, which is usually
+/// done by checking [`Selection::is_selected`] when it's built.
+/// The item is focused when the focus of the selection moves to it.
+///
+/// This corresponds to the Masonry [`Selectable`](masonry::widget::Selectable) widget.
+pub fn selectable<State, Action, Child, F>(
+    index: usize,
+    selection: &Selection,
+    child: Child,
+    lens: F,
+) -> Selectable<Child, F, State, Action>
+where
+    Child: WidgetView<State, Action>,
+    F: Fn(&mut State) -> &mut Selection + Send + Sync + 'static,
+{
+    Selectable {
+        index,
+        selected: selection.is_selected(index),
+        focused: selection.focus() == Some(index),
+        child,
+        lens,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Selectable<Child, F, State, Action = ()> {
+    index: usize,
+    selected: bool,
+    focused: bool,
+    child: Child,
+    lens: F,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+const CHILD_VIEW_ID: ViewId = ViewId::new(0);
+
+impl<Child, F, State, Action> ViewMarker for Selectable<Child, F, State, Action> {}
+impl<Child, F, State, Action> View<State, Action, ViewCtx> for Selectable<Child, F, State, Action>
+where
+    Child: WidgetView<State, Action>,
+    F: Fn(&mut State) -> &mut Selection + Send + Sync + 'static,
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::Selectable>;
+    type ViewState = Child::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (child, child_state) = ctx.with_id(CHILD_VIEW_ID, |ctx| self.child.build(ctx));
+        let pod = ctx.with_action_widget(|ctx| {
+            let widget = widget::Selectable::new_pod(ctx.boxed_pod(child).inner)
+                .with_selected(self.selected);
+            ctx.new_pod(widget)
+        });
+        (pod, child_state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        child_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(CHILD_VIEW_ID, |ctx| {
+            let mut child = widget::Selectable::child_mut(&mut element);
+            self.child
+                .rebuild(&prev.child, child_state, ctx, child.downcast());
+        });
+        if prev.selected != self.selected {
+            widget::Selectable::set_selected(&mut element, self.selected);
+        }
+        if self.focused && !prev.focused {
+            widget::Selectable::focus(&mut element);
+        }
+    }
+
+    fn teardown(
+        &self,
+        child_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(CHILD_VIEW_ID, |ctx| {
+            let mut child = widget::Selectable::child_mut(&mut element);
+            self.child.teardown(child_state, ctx, child.downcast());
+        });
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((start, rest)) = id_path.split_first() {
+            if *start != CHILD_VIEW_ID {
+                return MessageResult::Stale(message);
+            }
+            return self.child.message(child_state, rest, message, app_state);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match (*action).downcast::<SelectionInput>() {
+                Ok(input) => {
+                    (self.lens)(app_state).apply(self.index, input);
+                    MessageResult::RequestRebuild
+                }
+                Err(action) => {
+                    tracing::error!("Wrong action type in Selectable::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in Selectable::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use winit::event::KeyEvent;
+use winit::keyboard::{Key, ModifiersState};
+
+use crate::core::{DynMessage, MessageResult, Mut, NoElement, View, ViewId, ViewMarker};
+use crate::ViewCtx;
+
+/// A key, along with the modifiers which need to be held for a [`shortcut`].
+///
+/// Character keys are matched ignoring their case, so `KeyChord::character(ModifiersState::CONTROL | ModifiersState::SHIFT, "s")`
+/// matches even though the logical key is `"S"` whilst shift is held.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    modifiers: ModifiersState,
+    key: Key,
+}
+
+impl KeyChord {
+    /// A chord of `key` whilst exactly `modifiers` are held.
+    pub fn new(modifiers: ModifiersState, key: Key) -> Self {
+        Self {
+            modifiers,
+            key: normalize(key),
+        }
+    }
+
+    /// A chord of the key which produces `character` whilst exactly `modifiers` are held.
+    pub fn character(modifiers: ModifiersState, character: &str) -> Self {
+        Self::new(modifiers, Key::Character(character.into()))
+    }
+
+    /// A chord of `key` whilst the platform's primary modifier is held.
+    ///
+    /// This is Command on macOS, and Control on other platforms.
+    pub fn primary(key: Key) -> Self {
+        let modifiers = if cfg!(target_os = "macos") {
+            ModifiersState::SUPER
+        } else {
+            ModifiersState::CONTROL
+        };
+        Self::new(modifiers, key)
+    }
+
+    /// The chord pressed in `event`.
+    pub(crate) fn from_event(event: &KeyEvent, modifiers: ModifiersState) -> Self {
+        Self::new(modifiers, event.logical_key.clone())
+    }
+}
+
+/// Character keys are stored in lowercase, so that shift doesn't change the key.
+fn normalize(key: Key) -> Key {
+    match key {
+        Key::Character(character) => Key::Character(character.to_lowercase().into()),
+        key => key,
+    }
+}
+
+/// Call `on_press` when `chord` is pressed, whilst no widget handles the key press.
+///
+/// This allows declaring window-level keyboard shortcuts, such as <kbd>Ctrl</kbd>+<kbd>S</kbd>
+/// for saving, next to the state they depend on. This view doesn't create any widgets, and so
+/// is used alongside other views with `fork`.
+/// The shortcut is only active whilst this view is in the tree, so it can be disabled by
+/// removing the view, e.g. using an `Option`.
+///
+/// If several shortcut views use the same chord, the one added most recently is used.
+/// Key repeats, from holding the keys down, are ignored.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut Editor) -> impl WidgetView<Editor> {
+///     fork(
+///         editor_view(state),
+///         state.has_changes().then(|| {
+///             shortcut(KeyChord::primary(Key::Character("s".into())), |state: &mut Editor| {
+///                 state.save();
+///             })
+///         }),
+///     )
+/// }
+/// ```
+pub fn shortcut<State, Action, F>(chord: KeyChord, on_press: F) -> Shortcut<F>
+where
+    F: Fn(&mut State) -> Action + 'static,
+{
+    Shortcut {
+        chord,
+        callback: on_press,
+    }
+}
+
+/// The View for [`shortcut`].
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Shortcut<F> {
+    chord: KeyChord,
+    callback: F,
+}
+
+/// The message sent to a [`Shortcut`] when its chord is pressed.
+#[derive(Debug)]
+pub(crate) struct ShortcutPressed;
+
+impl<F> ViewMarker for Shortcut<F> {}
+impl<State, Action, F> View<State, Action, ViewCtx> for Shortcut<F>
+where
+    F: Fn(&mut State) -> Action + 'static,
+{
+    type Element = NoElement;
+
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.record_shortcut(self.chord.clone());
+        (NoElement, ())
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        (): Mut<Self::Element>,
+    ) {
+        if self.chord != prev.chord {
+            ctx.remove_shortcut(&prev.chord);
+            ctx.record_shortcut(self.chord.clone());
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, (): Mut<Self::Element>) {
+        ctx.remove_shortcut(&self.chord);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Shortcut::message"
+        );
+        let pressed = message.downcast::<ShortcutPressed>();
+        debug_assert!(
+            pressed.is_ok(),
+            "Shortcut views only receive `ShortcutPressed` messages"
+        );
+        match pressed {
+            Ok(_) => MessageResult::Action((self.callback)(app_state)),
+            Err(message) => MessageResult::Stale(message),
+        }
+    }
+}
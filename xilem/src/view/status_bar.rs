@@ -0,0 +1,140 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::text::ArcStr;
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, View, ViewId, ViewMarker};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// A status bar pinned under `content`, with items on its left and right, and a message
+/// in its center.
+///
+/// The message is ellipsized when the other items leave too little room for it.
+///
+/// This corresponds to the Masonry [`StatusBar`](masonry::widget::StatusBar) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{label, status_bar};
+/// # use xilem::WidgetView;
+///
+/// struct Editor {
+///     branch: String,
+///     line: usize,
+///     column: usize,
+///     message: String,
+/// }
+///
+/// fn view(editor: &mut Editor) -> impl WidgetView<Editor> {
+///     status_bar(label("fn main() {}"))
+///         .left([editor.branch.clone()])
+///         .center(editor.message.clone())
+///         .right([format!("Ln {}, Col {}", editor.line, editor.column), "UTF-8".into()])
+/// }
+/// ```
+pub fn status_bar<State, Action, V>(content: V) -> StatusBar<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+{
+    StatusBar {
+        content,
+        left: Vec::new(),
+        center: "".into(),
+        right: Vec::new(),
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct StatusBar<V, State, Action = ()> {
+    content: V,
+    left: Vec<ArcStr>,
+    center: ArcStr,
+    right: Vec<ArcStr>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> StatusBar<V, State, Action> {
+    /// Set the items on the left of the bar, from left to right.
+    pub fn left(mut self, items: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        self.left = items.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the message in the center of the bar.
+    pub fn center(mut self, text: impl Into<ArcStr>) -> Self {
+        self.center = text.into();
+        self
+    }
+
+    /// Set the items on the right of the bar, from left to right.
+    pub fn right(mut self, items: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        self.right = items.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl<V, State, Action> ViewMarker for StatusBar<V, State, Action> {}
+impl<V, State, Action> View<State, Action, ViewCtx> for StatusBar<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = Pod<widget::StatusBar>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (content, content_state) = self.content.build(ctx);
+        let widget = widget::StatusBar::new_pod(content.inner.boxed())
+            .with_left(self.left.iter().cloned())
+            .with_center(self.center.clone())
+            .with_right(self.right.iter().cloned());
+        (ctx.new_pod(widget), content_state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if self.left != prev.left {
+            widget::StatusBar::set_left(&mut element, self.left.iter().cloned());
+        }
+        if self.center != prev.center {
+            widget::StatusBar::set_center(&mut element, self.center.clone());
+        }
+        if self.right != prev.right {
+            widget::StatusBar::set_right(&mut element, self.right.iter().cloned());
+        }
+        let mut content = widget::StatusBar::content_mut(&mut element);
+        self.content
+            .rebuild(&prev.content, view_state, ctx, content.downcast());
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        let mut content = widget::StatusBar::content_mut(&mut element);
+        self.content.teardown(view_state, ctx, content.downcast());
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> crate::MessageResult<Action> {
+        self.content
+            .message(view_state, id_path, message, app_state)
+    }
+}
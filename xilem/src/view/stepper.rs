@@ -0,0 +1,379 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::text::ArcStr;
+use masonry::widget::{self, StepperAction, WidgetMut};
+use masonry::Widget;
+
+use crate::core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewElement,
+    ViewId, ViewMarker, ViewSequence,
+};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// A wizard showing one [`step`] at a time, which calls `on_finish` when the last step is done.
+///
+/// The user goes from one step to the next with the back and next buttons. Steps marked as
+/// invalid with [`Step::valid`] must be fixed before going further.
+///
+/// This corresponds to the Masonry [`Stepper`](masonry::widget::Stepper) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{label, step, stepper, textbox};
+/// # use xilem::WidgetView;
+///
+/// struct Setup {
+///     name: String,
+///     done: bool,
+/// }
+///
+/// fn view(setup: &mut Setup) -> impl WidgetView<Setup> {
+///     stepper(
+///         (
+///             step("Welcome", label("Let's set up your account")),
+///             step(
+///                 "Name",
+///                 textbox(setup.name.clone(), |setup: &mut Setup, name| setup.name = name),
+///             )
+///             .valid(!setup.name.is_empty()),
+///             step("Done", label("You're all set")),
+///         ),
+///         |setup: &mut Setup| setup.done = true,
+///     )
+/// }
+/// ```
+pub fn stepper<State, Action, Seq, F>(steps: Seq, on_finish: F) -> Stepper<Seq, F, State, Action>
+where
+    Seq: StepperSequence<State, Action>,
+    F: Fn(&mut State) -> Action + Send + Sync + 'static,
+{
+    Stepper {
+        steps,
+        on_finish,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Stepper<Seq, F, State, Action = ()> {
+    steps: Seq,
+    on_finish: F,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+const STEPS_VIEW_ID: ViewId = ViewId::new(0);
+
+impl<Seq, F, State, Action> ViewMarker for Stepper<Seq, F, State, Action> {}
+
+impl<State, Action, Seq, F> View<State, Action, ViewCtx> for Stepper<Seq, F, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    Seq: StepperSequence<State, Action>,
+    F: Fn(&mut State) -> Action + Send + Sync + 'static,
+{
+    type Element = Pod<widget::Stepper>;
+
+    type ViewState = Seq::SeqState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let mut elements = AppendVec::default();
+        let seq_state = ctx.with_id(STEPS_VIEW_ID, |ctx| {
+            self.steps.seq_build(ctx, &mut elements)
+        });
+        let pod = ctx.with_action_widget(|ctx| {
+            let mut widget = widget::Stepper::new();
+            // The buttons submit their own actions, which we ignore.
+            for id in [widget.back_id(), widget.next_id(), widget.finish_id()] {
+                ctx.record_action(id);
+            }
+            for element in elements.into_inner() {
+                widget = widget
+                    .with_step_pod(element.title, element.content.inner)
+                    .valid(element.valid);
+            }
+            ctx.new_pod(widget)
+        });
+        (pod, seq_state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(STEPS_VIEW_ID, |ctx| {
+            let mut splice = StepperSplice::new(element);
+            self.steps
+                .seq_rebuild(&prev.steps, view_state, ctx, &mut splice);
+            debug_assert!(splice.scratch.is_empty());
+        });
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(STEPS_VIEW_ID, |ctx| {
+            let mut splice = StepperSplice::new(element.reborrow_mut());
+            self.steps.seq_teardown(view_state, ctx, &mut splice);
+            debug_assert!(splice.scratch.into_inner().is_empty());
+        });
+        ctx.teardown_leaf(widget::Stepper::back_mut(&mut element));
+        ctx.teardown_leaf(widget::Stepper::next_mut(&mut element));
+        ctx.teardown_leaf(widget::Stepper::finish_mut(&mut element));
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&STEPS_VIEW_ID, rest)) => {
+                self.steps.seq_message(view_state, rest, message, app_state)
+            }
+            None => match message.downcast::<masonry::Action>() {
+                Ok(action) => match (*action).downcast::<StepperAction>() {
+                    Ok(StepperAction::Finished) => {
+                        MessageResult::Action((self.on_finish)(app_state))
+                    }
+                    // The stepper moves between the steps by itself.
+                    Ok(StepperAction::StepChanged(_)) | Err(masonry::Action::ButtonPressed(_)) => {
+                        MessageResult::Nop
+                    }
+                    Err(action) => {
+                        tracing::error!("Wrong action type in Stepper::message: {action:?}");
+                        MessageResult::Stale(Box::new(action))
+                    }
+                },
+                Err(message) => {
+                    tracing::error!("Wrong message type in Stepper::message");
+                    MessageResult::Stale(message)
+                }
+            },
+            _ => {
+                tracing::error!("Invalid id path in Stepper::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// A step of a [`stepper`], with a title shown in the progress header.
+pub fn step<State, Action, V>(title: impl Into<ArcStr>, content: V) -> Step<V, State, Action>
+where
+    V: WidgetView<State, Action>,
+{
+    Step {
+        title: title.into(),
+        content,
+        valid: true,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Step<V, State, Action = ()> {
+    title: ArcStr,
+    content: V,
+    valid: bool,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<V, State, Action> Step<V, State, Action> {
+    /// Set whether the user can go past this step, which is the case by default.
+    pub fn valid(mut self, valid: bool) -> Self {
+        self.valid = valid;
+        self
+    }
+}
+
+impl<V, State, Action> ViewMarker for Step<V, State, Action> {}
+
+impl<State, Action, V> View<State, Action, ViewCtx> for Step<V, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: WidgetView<State, Action>,
+{
+    type Element = StepperElement;
+
+    type ViewState = V::ViewState;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (content, state) = self.content.build(ctx);
+        let element = StepperElement {
+            title: self.title.clone(),
+            valid: self.valid,
+            content: ctx.boxed_pod(content),
+        };
+        (element, state)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.title != self.title {
+            widget::Stepper::set_step_title(&mut element.parent, element.idx, self.title.clone());
+        }
+        if prev.valid != self.valid {
+            widget::Stepper::set_step_valid(&mut element.parent, element.idx, self.valid);
+        }
+        let mut content = widget::Stepper::step_mut(&mut element.parent, element.idx);
+        self.content
+            .rebuild(&prev.content, view_state, ctx, content.downcast());
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        let mut content = widget::Stepper::step_mut(&mut element.parent, element.idx);
+        self.content.teardown(view_state, ctx, content.downcast());
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.content
+            .message(view_state, id_path, message, app_state)
+    }
+}
+
+/// A step of a [`Stepper`].
+pub struct StepperElement {
+    title: ArcStr,
+    valid: bool,
+    content: Pod<Box<dyn Widget>>,
+}
+
+pub struct StepperElementMut<'w> {
+    parent: WidgetMut<'w, widget::Stepper>,
+    idx: usize,
+}
+
+impl ViewElement for StepperElement {
+    type Mut<'w> = StepperElementMut<'w>;
+}
+
+impl SuperElement<StepperElement, ViewCtx> for StepperElement {
+    fn upcast(_ctx: &mut ViewCtx, child: StepperElement) -> Self {
+        child
+    }
+
+    fn with_downcast_val<R>(
+        mut this: Mut<Self>,
+        f: impl FnOnce(Mut<StepperElement>) -> R,
+    ) -> (Self::Mut<'_>, R) {
+        let r = {
+            let parent = this.parent.reborrow_mut();
+            let reborrow = StepperElementMut {
+                idx: this.idx,
+                parent,
+            };
+            f(reborrow)
+        };
+        (this, r)
+    }
+}
+
+// Used for building and rebuilding the ViewSequence
+pub struct StepperSplice<'w> {
+    idx: usize,
+    element: WidgetMut<'w, widget::Stepper>,
+    scratch: AppendVec<StepperElement>,
+}
+
+impl<'w> StepperSplice<'w> {
+    fn new(element: WidgetMut<'w, widget::Stepper>) -> Self {
+        Self {
+            idx: 0,
+            element,
+            scratch: AppendVec::default(),
+        }
+    }
+
+    fn insert_element(&mut self, element: StepperElement) {
+        widget::Stepper::insert_step_pod(
+            &mut self.element,
+            self.idx,
+            element.title,
+            element.content.inner,
+        );
+        widget::Stepper::set_step_valid(&mut self.element, self.idx, element.valid);
+        self.idx += 1;
+    }
+}
+
+impl ElementSplice<StepperElement> for StepperSplice<'_> {
+    fn with_scratch<R>(&mut self, f: impl FnOnce(&mut AppendVec<StepperElement>) -> R) -> R {
+        let ret = f(&mut self.scratch);
+        let elements: Vec<_> = self.scratch.drain().collect();
+        for element in elements {
+            self.insert_element(element);
+        }
+        ret
+    }
+
+    fn insert(&mut self, element: StepperElement) {
+        self.insert_element(element);
+    }
+
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<StepperElement>) -> R) -> R {
+        let child = StepperElementMut {
+            parent: self.element.reborrow_mut(),
+            idx: self.idx,
+        };
+        let ret = f(child);
+        self.idx += 1;
+        ret
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.idx += n;
+    }
+
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<StepperElement>) -> R) -> R {
+        let ret = {
+            let child = StepperElementMut {
+                parent: self.element.reborrow_mut(),
+                idx: self.idx,
+            };
+            f(child)
+        };
+        widget::Stepper::remove_step(&mut self.element, self.idx);
+        ret
+    }
+}
+
+/// A sequence of [`step`]s, the steps of a [`Stepper`].
+pub trait StepperSequence<State, Action = ()>:
+    ViewSequence<State, Action, ViewCtx, StepperElement>
+{
+}
+
+impl<Seq, State, Action> StepperSequence<State, Action> for Seq where
+    Seq: ViewSequence<State, Action, ViewCtx, StepperElement>
+{
+}
@@ -0,0 +1,113 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, View, ViewId, ViewMarker};
+use crate::{MessageResult, Pod, ViewCtx, WidgetView};
+
+/// A section of a scrolling list, whose `header` sticks to the top of the enclosing
+/// [`portal`](super::portal) while its `body` is scrolled through.
+///
+/// This corresponds to the Masonry [`StickySection`](masonry::widget::StickySection) widget.
+pub fn sticky_section<Header, Body, State, Action>(
+    header: Header,
+    body: Body,
+) -> StickySection<Header, Body, State, Action>
+where
+    Header: WidgetView<State, Action>,
+    Body: WidgetView<State, Action>,
+{
+    StickySection {
+        header,
+        body,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct StickySection<Header, Body, State, Action = ()> {
+    header: Header,
+    body: Body,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+const HEADER_VIEW_ID: ViewId = ViewId::new(0);
+const BODY_VIEW_ID: ViewId = ViewId::new(1);
+
+impl<Header, Body, State, Action> ViewMarker for StickySection<Header, Body, State, Action> {}
+impl<Header, Body, State, Action> View<State, Action, ViewCtx>
+    for StickySection<Header, Body, State, Action>
+where
+    Header: WidgetView<State, Action>,
+    Body: WidgetView<State, Action>,
+    State: 'static,
+    Action: 'static,
+{
+    type Element = Pod<widget::StickySection>;
+    type ViewState = (Header::ViewState, Body::ViewState);
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let (header, header_state) = ctx.with_id(HEADER_VIEW_ID, |ctx| self.header.build(ctx));
+        let (body, body_state) = ctx.with_id(BODY_VIEW_ID, |ctx| self.body.build(ctx));
+        let widget =
+            widget::StickySection::new_pod(ctx.boxed_pod(header).inner, ctx.boxed_pod(body).inner);
+        (ctx.new_pod(widget), (header_state, body_state))
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (header_state, body_state): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(HEADER_VIEW_ID, |ctx| {
+            let mut header = widget::StickySection::header_mut(&mut element);
+            self.header
+                .rebuild(&prev.header, header_state, ctx, header.downcast());
+        });
+        ctx.with_id(BODY_VIEW_ID, |ctx| {
+            let mut body = widget::StickySection::body_mut(&mut element);
+            self.body
+                .rebuild(&prev.body, body_state, ctx, body.downcast());
+        });
+    }
+
+    fn teardown(
+        &self,
+        (header_state, body_state): &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        ctx.with_id(HEADER_VIEW_ID, |ctx| {
+            let mut header = widget::StickySection::header_mut(&mut element);
+            self.header.teardown(header_state, ctx, header.downcast());
+        });
+        ctx.with_id(BODY_VIEW_ID, |ctx| {
+            let mut body = widget::StickySection::body_mut(&mut element);
+            self.body.teardown(body_state, ctx, body.downcast());
+        });
+    }
+
+    fn message(
+        &self,
+        (header_state, body_state): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&HEADER_VIEW_ID, rest)) => {
+                self.header.message(header_state, rest, message, app_state)
+            }
+            Some((&BODY_VIEW_ID, rest)) => self.body.message(body_state, rest, message, app_state),
+            _ => {
+                tracing::error!("Invalid id path in StickySection::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,296 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+use masonry::widget;
+use tokio::task::JoinHandle;
+
+use crate::core::{
+    DynMessage, MessageProxy, MessageResult, Mut, View, ViewId, ViewMarker, ViewPathTracker,
+};
+use crate::{Pod, ViewCtx, WidgetView};
+
+/// Show `placeholder` until the future created from `data` completes, then the view
+/// created from its output by `content_fn`.
+///
+/// Like in [`memoized_await`](crate::view::memoized_await), the future is spawned when the
+/// view is added to the tree, and spawned again whenever `data` changes. Until the new
+/// future completes, the content created from the previous output stays shown.
+/// The output is kept by the view, so `content_fn` is called on each rebuild and can depend
+/// on the app state. The placeholder is torn down once the content is shown.
+///
+/// Note that `init_future` cannot capture, as the future would not be spawned again
+/// when the captured variables change. Use `data` for anything the future depends on.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+///     suspense(
+///         state.url.clone(),
+///         |url| fetch_page(url.clone()),
+///         |page: &String| prose(page.clone()),
+///         spinner(),
+///     )
+///     .transition(Duration::from_millis(200))
+/// }
+/// ```
+pub fn suspense<State, Action, Data, F, Fut, CF, V, P>(
+    data: Data,
+    init_future: F,
+    content_fn: CF,
+    placeholder: P,
+) -> Suspense<Data, F, CF, P, Fut::Output>
+where
+    Data: PartialEq + 'static,
+    F: Fn(&Data) -> Fut + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: Debug + Send + 'static,
+    CF: Fn(&Fut::Output) -> V + 'static,
+    V: WidgetView<State, Action>,
+    P: WidgetView<State, Action>,
+{
+    const {
+        assert!(
+            size_of::<F>() == 0,
+            "`suspense` will not spawn its future again when its captured variables are updated.\n\
+            Pass the values the future depends on as `data` instead."
+        );
+    };
+    Suspense {
+        data,
+        init_future,
+        content_fn,
+        placeholder,
+        transition: Duration::ZERO,
+        output: PhantomData,
+    }
+}
+
+/// The View for [`suspense`].
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Suspense<Data, F, CF, P, Output> {
+    data: Data,
+    init_future: F,
+    content_fn: CF,
+    placeholder: P,
+    transition: Duration,
+    output: PhantomData<fn() -> Output>,
+}
+
+impl<Data, F, CF, P, Output> Suspense<Data, F, CF, P, Output> {
+    /// Fade the content in over `transition` when it replaces the placeholder.
+    ///
+    /// By default, the content replaces the placeholder immediately.
+    pub fn transition(mut self, transition: Duration) -> Self {
+        self.transition = transition;
+        self
+    }
+}
+
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct SuspenseState<Output, V, VState, PState> {
+    handle: JoinHandle<()>,
+    /// Incremented whenever a new future is spawned, to ignore the outputs of cancelled ones.
+    generation: u64,
+    /// The output of the last future which completed.
+    output: Option<Output>,
+    content: Option<(V, VState)>,
+    /// `None` once the placeholder has been replaced by the content.
+    placeholder: Option<PState>,
+}
+
+/// The message sent by the future of a [`Suspense`].
+#[derive(Debug)]
+struct SuspenseOutput<Output> {
+    generation: u64,
+    output: Output,
+}
+
+const PLACEHOLDER_VIEW_ID: ViewId = ViewId::new(0);
+const CONTENT_VIEW_ID: ViewId = ViewId::new(1);
+const FUTURE_VIEW_ID: ViewId = ViewId::new(2);
+
+impl<Data, F, CF, P, Output> Suspense<Data, F, CF, P, Output>
+where
+    Output: Debug + Send + 'static,
+{
+    fn spawn<Fut>(&self, ctx: &mut ViewCtx, generation: u64) -> JoinHandle<()>
+    where
+        F: Fn(&Data) -> Fut,
+        Fut: Future<Output = Output> + Send + 'static,
+    {
+        ctx.with_id(FUTURE_VIEW_ID, |ctx| {
+            let path: Arc<[ViewId]> = ctx.view_path().into();
+            let proxy = MessageProxy::new(ctx.proxy.clone(), path);
+            let future = (self.init_future)(&self.data);
+            ctx.runtime().spawn(async move {
+                let output = future.await;
+                // We choose not to handle the case where the event loop has ended
+                drop(proxy.message(SuspenseOutput { generation, output }));
+            })
+        })
+    }
+}
+
+impl<Data, F, CF, P, Output> ViewMarker for Suspense<Data, F, CF, P, Output> {}
+impl<State, Action, Data, F, Fut, CF, V, P, Output> View<State, Action, ViewCtx>
+    for Suspense<Data, F, CF, P, Output>
+where
+    State: 'static,
+    Action: 'static,
+    Data: PartialEq + 'static,
+    F: Fn(&Data) -> Fut + 'static,
+    Fut: Future<Output = Output> + Send + 'static,
+    Output: Debug + Send + 'static,
+    CF: Fn(&Output) -> V + 'static,
+    V: WidgetView<State, Action>,
+    P: WidgetView<State, Action>,
+{
+    type Element = Pod<widget::Suspense>;
+
+    type ViewState = SuspenseState<Output, V, V::ViewState, P::ViewState>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let handle = self.spawn(ctx, 0);
+        let (placeholder, placeholder_state) =
+            ctx.with_id(PLACEHOLDER_VIEW_ID, |ctx| self.placeholder.build(ctx));
+        let widget =
+            widget::Suspense::new_pod(placeholder.inner.boxed()).with_transition(self.transition);
+        (
+            ctx.new_pod(widget),
+            SuspenseState {
+                handle,
+                generation: 0,
+                output: None,
+                content: None,
+                placeholder: Some(placeholder_state),
+            },
+        )
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if self.data != prev.data {
+            view_state.handle.abort();
+            view_state.generation += 1;
+            view_state.handle = self.spawn(ctx, view_state.generation);
+        }
+        if self.transition != prev.transition {
+            widget::Suspense::set_transition(&mut element, self.transition);
+        }
+
+        let Some(output) = &view_state.output else {
+            // Still loading.
+            let placeholder_state = view_state
+                .placeholder
+                .as_mut()
+                .expect("the placeholder is only removed once there is an output");
+            let mut placeholder = widget::Suspense::placeholder_mut(&mut element)
+                .expect("the placeholder is only removed once there is an output");
+            ctx.with_id(PLACEHOLDER_VIEW_ID, |ctx| {
+                self.placeholder.rebuild(
+                    &prev.placeholder,
+                    placeholder_state,
+                    ctx,
+                    placeholder.downcast(),
+                );
+            });
+            return;
+        };
+
+        let new_content = (self.content_fn)(output);
+        if let Some((content, content_state)) = &mut view_state.content {
+            let mut child = widget::Suspense::content_mut(&mut element)
+                .expect("the content is set when its view is built");
+            ctx.with_id(CONTENT_VIEW_ID, |ctx| {
+                new_content.rebuild(content, content_state, ctx, child.downcast());
+            });
+            *content = new_content;
+            return;
+        }
+
+        // The first output: swap the placeholder for the content.
+        if let Some(mut placeholder_state) = view_state.placeholder.take() {
+            let mut placeholder = widget::Suspense::placeholder_mut(&mut element)
+                .expect("the placeholder is only removed once there is an output");
+            ctx.with_id(PLACEHOLDER_VIEW_ID, |ctx| {
+                prev.placeholder
+                    .teardown(&mut placeholder_state, ctx, placeholder.downcast());
+            });
+        }
+        let (pod, content_state) = ctx.with_id(CONTENT_VIEW_ID, |ctx| new_content.build(ctx));
+        widget::Suspense::set_content(&mut element, pod.inner.boxed());
+        view_state.content = Some((new_content, content_state));
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        view_state.handle.abort();
+        if let Some((content, content_state)) = &mut view_state.content {
+            let mut child = widget::Suspense::content_mut(&mut element)
+                .expect("the content is set when its view is built");
+            ctx.with_id(CONTENT_VIEW_ID, |ctx| {
+                content.teardown(content_state, ctx, child.downcast());
+            });
+        } else if let Some(placeholder_state) = &mut view_state.placeholder {
+            let mut placeholder = widget::Suspense::placeholder_mut(&mut element)
+                .expect("the placeholder is only removed once there is an output");
+            ctx.with_id(PLACEHOLDER_VIEW_ID, |ctx| {
+                self.placeholder
+                    .teardown(placeholder_state, ctx, placeholder.downcast());
+            });
+        }
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        match id_path.split_first() {
+            Some((&FUTURE_VIEW_ID, [])) => {
+                let message = message.downcast::<SuspenseOutput<Output>>().unwrap();
+                if message.generation != view_state.generation {
+                    // The output of a future which was cancelled after it completed.
+                    return MessageResult::Nop;
+                }
+                view_state.output = Some(message.output);
+                MessageResult::RequestRebuild
+            }
+            Some((&PLACEHOLDER_VIEW_ID, rest)) => match &mut view_state.placeholder {
+                Some(placeholder_state) => {
+                    self.placeholder
+                        .message(placeholder_state, rest, message, app_state)
+                }
+                None => MessageResult::Stale(message),
+            },
+            Some((&CONTENT_VIEW_ID, rest)) => match &mut view_state.content {
+                Some((content, content_state)) => {
+                    content.message(content_state, rest, message, app_state)
+                }
+                None => MessageResult::Stale(message),
+            },
+            _ => {
+                tracing::error!("Invalid id path in Suspense::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
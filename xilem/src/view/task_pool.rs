@@ -0,0 +1,390 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::future::Future;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+use crate::core::{
+    DynMessage, MessageProxy, MessageResult, Mut, NoElement, View, ViewId, ViewMarker,
+    ViewPathTracker,
+};
+use crate::ViewCtx;
+
+/// Run a future for each of the `keys`, concurrently, and give their outputs to `on_output`.
+///
+/// A future is spawned for each key which wasn't in the previous `keys`, and the futures
+/// of keys which were removed are cancelled. Each future only runs once: it isn't spawned
+/// again after completing, unless its key is removed and later added back.
+/// All futures are cancelled when the view is removed from the tree.
+///
+/// This can be used to load a collection of resources, such as the thumbnails currently
+/// visible in a gallery. Use [`TaskPool::max_concurrent`] to limit how many of the futures
+/// run at the same time.
+///
+/// Note that `init_future` cannot capture, as the futures would not be spawned again
+/// when the captured variables change. Use the keys for anything the futures depend on.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut Gallery) -> impl WidgetView<Gallery> {
+///     fork(
+///         gallery_view(state),
+///         task_pool(
+///             state.visible_image_urls(),
+///             |url| download_image(url.clone()),
+///             |state: &mut Gallery, url, image| state.add_image(url, image),
+///         )
+///         .max_concurrent(4),
+///     )
+/// }
+/// ```
+pub fn task_pool<State, Action, K, F, H, Fut>(
+    keys: Vec<K>,
+    init_future: F,
+    on_output: H,
+) -> TaskPool<K, F, H, Fut::Output>
+where
+    K: Clone + Eq + Hash + Debug + Send + 'static,
+    F: Fn(&K) -> Fut + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: Debug + Send + 'static,
+    H: Fn(&mut State, K, Fut::Output) -> Action + 'static,
+{
+    const {
+        assert!(
+            size_of::<F>() == 0,
+            "`task_pool` will not be ran again when its captured variables are updated.\n\
+            Pass the values the futures depend on as the keys instead."
+        );
+    };
+    TaskPool {
+        keys,
+        init_future,
+        on_output,
+        max_concurrent: None,
+        output: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct TaskPool<K, F, H, Output> {
+    keys: Vec<K>,
+    init_future: F,
+    on_output: H,
+    max_concurrent: Option<usize>,
+    output: PhantomData<fn() -> Output>,
+}
+
+impl<K, F, H, Output> TaskPool<K, F, H, Output> {
+    /// Limit the number of futures which run at the same time.
+    ///
+    /// Futures beyond this limit wait for others to complete before starting.
+    /// This limit is only read when the view is built.
+    pub fn max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = Some(max_concurrent.max(1));
+        self
+    }
+}
+
+#[doc(hidden)] // Implementation detail, public because of trait visibility rules
+pub struct TaskPoolState<K> {
+    /// The futures of the current keys, which may have completed.
+    tasks: HashMap<K, PoolTask>,
+    permits: Option<Arc<Semaphore>>,
+    /// Incremented whenever a future is spawned, to ignore the outputs of cancelled ones.
+    generation: u64,
+}
+
+/// The future spawned for a key of a [`TaskPool`].
+struct PoolTask {
+    handle: JoinHandle<()>,
+    generation: u64,
+}
+
+/// The message sent by the futures of a [`TaskPool`].
+#[derive(Debug)]
+struct PoolOutput<K, Output> {
+    key: K,
+    generation: u64,
+    output: Output,
+}
+
+impl<K, F, H, Output> TaskPool<K, F, H, Output>
+where
+    K: Clone + Eq + Hash + Debug + Send + 'static,
+    Output: Debug + Send + 'static,
+{
+    fn spawn<Fut>(
+        &self,
+        key: &K,
+        permits: Option<&Arc<Semaphore>>,
+        generation: u64,
+        ctx: &mut ViewCtx,
+    ) -> PoolTask
+    where
+        F: Fn(&K) -> Fut,
+        Fut: Future<Output = Output> + Send + 'static,
+    {
+        let path: Arc<[ViewId]> = ctx.view_path().into();
+        let proxy = MessageProxy::new(ctx.proxy.clone(), path);
+        let future = (self.init_future)(key);
+        let permits = permits.cloned();
+        let key = key.clone();
+        let handle = ctx.runtime().spawn(async move {
+            // The semaphore is never closed.
+            let _permit = match &permits {
+                Some(permits) => Some(permits.acquire().await.unwrap()),
+                None => None,
+            };
+            let output = future.await;
+            // We choose not to handle the case where the event loop has ended
+            drop(proxy.message(PoolOutput {
+                key,
+                generation,
+                output,
+            }));
+        });
+        PoolTask { handle, generation }
+    }
+}
+
+impl<K, F, H, Output> ViewMarker for TaskPool<K, F, H, Output> {}
+impl<State, Action, K, F, H, Fut, Output> View<State, Action, ViewCtx> for TaskPool<K, F, H, Output>
+where
+    K: Clone + Eq + Hash + Debug + Send + 'static,
+    F: Fn(&K) -> Fut + 'static,
+    Fut: Future<Output = Output> + Send + 'static,
+    Output: Debug + Send + 'static,
+    H: Fn(&mut State, K, Output) -> Action + 'static,
+{
+    type Element = NoElement;
+
+    type ViewState = TaskPoolState<K>;
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let permits = self.max_concurrent.map(|it| Arc::new(Semaphore::new(it)));
+        let mut generation = 0;
+        let mut tasks = HashMap::with_capacity(self.keys.len());
+        for key in &self.keys {
+            tasks.insert(
+                key.clone(),
+                self.spawn(key, permits.as_ref(), generation, ctx),
+            );
+            generation += 1;
+        }
+        (
+            NoElement,
+            TaskPoolState {
+                tasks,
+                permits,
+                generation,
+            },
+        )
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut ViewCtx,
+        (): Mut<Self::Element>,
+    ) {
+        if self.keys == prev.keys {
+            return;
+        }
+        let keys: HashSet<&K> = self.keys.iter().collect();
+        view_state.tasks.retain(|key, task| {
+            let keep = keys.contains(key);
+            if !keep {
+                task.handle.abort();
+            }
+            keep
+        });
+        for key in &self.keys {
+            if !view_state.tasks.contains_key(key) {
+                let task = self.spawn(key, view_state.permits.as_ref(), view_state.generation, ctx);
+                view_state.generation += 1;
+                view_state.tasks.insert(key.clone(), task);
+            }
+        }
+    }
+
+    fn teardown(&self, view_state: &mut Self::ViewState, _: &mut ViewCtx, _: Mut<Self::Element>) {
+        for task in view_state.tasks.values() {
+            task.handle.abort();
+        }
+        view_state.tasks.clear();
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in TaskPool::message"
+        );
+        let message = message.downcast::<PoolOutput<K, Output>>().unwrap();
+        let PoolOutput {
+            key,
+            generation,
+            output,
+        } = *message;
+        if !view_state
+            .tasks
+            .get(&key)
+            .is_some_and(|task| task.generation == generation)
+        {
+            // The output of a future which was cancelled after it completed,
+            // possibly before its key was added back.
+            return MessageResult::Nop;
+        }
+        MessageResult::Action((self.on_output)(app_state, key, output))
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use std::future::pending;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::core::fork;
+    use crate::testing::AppTestHarness;
+    use crate::view::label;
+    use crate::{WidgetView, Xilem};
+
+    #[derive(Default)]
+    struct Loader {
+        keys: Vec<u32>,
+        outputs: Vec<(u32, u32)>,
+    }
+
+    /// The futures of the pool never complete, so tests deliver their outputs by hand.
+    fn app_logic(state: &mut Loader) -> impl WidgetView<Loader> {
+        fork(
+            label("Loading"),
+            task_pool(
+                state.keys.clone(),
+                |_: &u32| pending::<u32>(),
+                |state: &mut Loader, key, output| state.outputs.push((key, output)),
+            ),
+        )
+    }
+
+    /// The path of the `task_pool` in [`app_logic`].
+    fn pool_path() -> [ViewId; 1] {
+        [ViewId::new(1)]
+    }
+
+    fn output(key: u32, generation: u64, output: u32) -> PoolOutput<u32, u32> {
+        PoolOutput {
+            key,
+            generation,
+            output,
+        }
+    }
+
+    #[test]
+    fn removed_keys_are_cancelled() {
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+        struct DropCounter;
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                DROPPED.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        fn app_logic(keys: &mut Vec<u32>) -> impl WidgetView<Vec<u32>> {
+            fork(
+                label("Loading"),
+                task_pool(
+                    keys.clone(),
+                    |_: &u32| {
+                        // Created outside of the future, in case it is cancelled before it runs
+                        let counter = DropCounter;
+                        async move {
+                            let _counter = counter;
+                            pending::<()>().await;
+                        }
+                    },
+                    |_: &mut Vec<u32>, _, ()| {},
+                ),
+            )
+        }
+
+        let mut harness = AppTestHarness::create(Xilem::new(vec![1, 2, 3], app_logic));
+        harness.edit_state(|keys| keys.retain(|key| *key != 2));
+
+        // Aborted futures are dropped by the runtime's threads
+        let start = Instant::now();
+        while DROPPED.load(Ordering::SeqCst) < 1 {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "the future of the removed key wasn't cancelled"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn outputs_of_current_keys_are_delivered() {
+        let state = Loader {
+            keys: vec![1, 2],
+            ..Default::default()
+        };
+        let mut harness = AppTestHarness::create(Xilem::new(state, app_logic));
+
+        harness.send_message(&pool_path(), output(2, 1, 20));
+        harness.send_message(&pool_path(), output(1, 0, 10));
+        assert_eq!(harness.state().outputs, [(2, 20), (1, 10)]);
+    }
+
+    #[test]
+    fn outputs_of_removed_keys_are_ignored() {
+        let state = Loader {
+            keys: vec![1, 2],
+            ..Default::default()
+        };
+        let mut harness = AppTestHarness::create(Xilem::new(state, app_logic));
+        harness.edit_state(|state| state.keys = vec![2]);
+
+        // Sent by the future of key 1 after it completed, but before it was cancelled
+        harness.send_message(&pool_path(), output(1, 0, 10));
+        assert!(harness.state().outputs.is_empty());
+    }
+
+    #[test]
+    fn outputs_from_before_rekeying_are_ignored() {
+        let state = Loader {
+            keys: vec![1],
+            ..Default::default()
+        };
+        let mut harness = AppTestHarness::create(Xilem::new(state, app_logic));
+        harness.edit_state(|state| state.keys.clear());
+        harness.edit_state(|state| state.keys = vec![1]);
+
+        // The output of the cancelled future, which was spawned for the same key
+        harness.send_message(&pool_path(), output(1, 0, 10));
+        assert!(harness.state().outputs.is_empty());
+
+        // The output of the future spawned when the key was added back
+        harness.send_message(&pool_path(), output(1, 1, 11));
+        assert_eq!(harness.state().outputs, [(1, 11)]);
+    }
+}
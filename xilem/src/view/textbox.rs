@@ -1,7 +1,11 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use masonry::widget;
+use std::sync::Arc;
+use std::time::Duration;
+
+use masonry::text::{FindPattern, SpellChecker, SpellingSuggestions};
+use masonry::{theme, widget};
 use vello::peniko::Brush;
 
 use crate::core::{DynMessage, Mut, View, ViewMarker};
@@ -12,6 +16,8 @@ use crate::{Color, MessageResult, Pod, TextAlignment, ViewCtx, ViewId};
 // the textbox will always be reset to the initial state. This will be very annoying for the user.
 
 type Callback<State, Action> = Box<dyn Fn(&mut State, String) -> Action + Send + Sync + 'static>;
+type SuggestionsCallback<State, Action> =
+    Box<dyn Fn(&mut State, SpellingSuggestions) -> Action + Send + Sync + 'static>;
 
 pub fn textbox<F, State, Action>(contents: String, on_changed: F) -> Textbox<State, Action>
 where
@@ -24,6 +30,13 @@ where
         on_enter: None,
         text_brush: Color::WHITE.into(),
         alignment: TextAlignment::default(),
+        caret_brush: theme::CURSOR_COLOR.into(),
+        caret_width: theme::CURSOR_WIDTH,
+        caret_blink_interval: Some(theme::CURSOR_BLINK_INTERVAL),
+        find: None,
+        active_find_match: None,
+        spell_checker: None,
+        on_spelling_suggestions: None,
         // TODO?: disabled: false,
     }
 }
@@ -35,6 +48,13 @@ pub struct Textbox<State, Action> {
     on_enter: Option<Callback<State, Action>>,
     text_brush: Brush,
     alignment: TextAlignment,
+    caret_brush: Brush,
+    caret_width: f32,
+    caret_blink_interval: Option<Duration>,
+    find: Option<FindPattern>,
+    active_find_match: Option<usize>,
+    spell_checker: Option<Arc<dyn SpellChecker>>,
+    on_spelling_suggestions: Option<SuggestionsCallback<State, Action>>,
     // TODO: add more attributes of `masonry::widget::TextBox`
 }
 
@@ -50,6 +70,65 @@ impl<State, Action> Textbox<State, Action> {
         self
     }
 
+    /// Set the brush used to paint the cursor.
+    #[doc(alias = "caret_color")]
+    pub fn caret_brush(mut self, brush: impl Into<Brush>) -> Self {
+        self.caret_brush = brush.into();
+        self
+    }
+
+    /// Set the width of the cursor, in logical pixels.
+    pub fn caret_width(mut self, width: f32) -> Self {
+        self.caret_width = width;
+        self
+    }
+
+    /// Set how long the cursor stays shown, then hidden, when it blinks, or `None` for a
+    /// cursor which doesn't blink.
+    ///
+    /// See [`widget::TextArea::with_caret_blink_interval`].
+    pub fn caret_blink_interval(mut self, interval: Option<Duration>) -> Self {
+        self.caret_blink_interval = interval;
+        self
+    }
+
+    /// Highlight the matches of `pattern` in the text.
+    ///
+    /// See [`Prose::find`](super::Prose::find).
+    pub fn find(mut self, pattern: Option<FindPattern>) -> Self {
+        self.find = pattern;
+        self
+    }
+
+    /// Select the match of the [`find`](Self::find) pattern at `index`, and scroll it into view.
+    ///
+    /// See [`Prose::active_find_match`](super::Prose::active_find_match).
+    pub fn active_find_match(mut self, index: Option<usize>) -> Self {
+        self.active_find_match = index;
+        self
+    }
+
+    /// Check the spelling of the text with `checker`, underlining the misspelled words.
+    ///
+    /// See [`widget::TextArea::with_spell_checker`].
+    pub fn spell_checker(mut self, checker: Option<Arc<dyn SpellChecker>>) -> Self {
+        self.spell_checker = checker;
+        self
+    }
+
+    /// Set the callback called when a misspelled word is right-clicked, e.g. to show the
+    /// suggestions in a menu.
+    ///
+    /// The chosen suggestion is applied by updating the contents of the textbox,
+    /// replacing the [`range`](SpellingSuggestions::range) of the word.
+    pub fn on_spelling_suggestions<F>(mut self, on_spelling_suggestions: F) -> Self
+    where
+        F: Fn(&mut State, SpellingSuggestions) -> Action + Send + Sync + 'static,
+    {
+        self.on_spelling_suggestions = Some(Box::new(on_spelling_suggestions));
+        self
+    }
+
     pub fn on_enter<F>(mut self, on_enter: F) -> Self
     where
         F: Fn(&mut State, String) -> Action + Send + Sync + 'static,
@@ -66,9 +145,16 @@ impl<State: 'static, Action: 'static> View<State, Action, ViewCtx> for Textbox<S
 
     fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
         // TODO: Maybe we want a shared TextArea View?
-        let text_area = widget::TextArea::new_editable(&self.contents)
+        let mut text_area = widget::TextArea::new_editable(&self.contents)
             .with_brush(self.text_brush.clone())
-            .with_alignment(self.alignment);
+            .with_alignment(self.alignment)
+            .with_caret_brush(self.caret_brush.clone())
+            .with_caret_width(self.caret_width)
+            .with_caret_blink_interval(self.caret_blink_interval)
+            .with_find_pattern(self.find.clone());
+        if let Some(checker) = &self.spell_checker {
+            text_area = text_area.with_spell_checker(checker.clone());
+        }
         let textbox = widget::Textbox::from_text_area(text_area);
 
         // Ensure that the actions from the *inner* TextArea get routed correctly.
@@ -104,6 +190,31 @@ impl<State: 'static, Action: 'static> View<State, Action, ViewCtx> for Textbox<S
         if prev.alignment != self.alignment {
             widget::TextArea::set_alignment(&mut text_area, self.alignment);
         }
+        if prev.caret_brush != self.caret_brush {
+            widget::TextArea::set_caret_brush(&mut text_area, self.caret_brush.clone());
+        }
+        if prev.caret_width != self.caret_width {
+            widget::TextArea::set_caret_width(&mut text_area, self.caret_width);
+        }
+        if prev.caret_blink_interval != self.caret_blink_interval {
+            widget::TextArea::set_caret_blink_interval(&mut text_area, self.caret_blink_interval);
+        }
+        if prev.find != self.find {
+            widget::TextArea::set_find_pattern(&mut text_area, self.find.clone());
+        }
+        if prev.find != self.find || prev.active_find_match != self.active_find_match {
+            if let Some(index) = self.active_find_match {
+                widget::TextArea::select_find_match(&mut text_area, index);
+            }
+        }
+        let same_checker = match (&prev.spell_checker, &self.spell_checker) {
+            (Some(prev), Some(checker)) => Arc::ptr_eq(prev, checker),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same_checker {
+            widget::TextArea::set_spell_checker(&mut text_area, self.spell_checker.clone());
+        }
     }
 
     fn teardown(&self, _: &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
@@ -133,6 +244,13 @@ impl<State: 'static, Action: 'static> View<State, Action, ViewCtx> for Textbox<S
                     tracing::error!("Textbox::message: on_enter is not set");
                     MessageResult::Stale(action)
                 }
+                masonry::Action::SpellingSuggestions(suggestions)
+                    if self.on_spelling_suggestions.is_some() =>
+                {
+                    let on_spelling_suggestions = self.on_spelling_suggestions.as_ref().unwrap();
+                    MessageResult::Action(on_spelling_suggestions(app_state, suggestions))
+                }
+                masonry::Action::SpellingSuggestions(_) => MessageResult::Nop,
                 _ => {
                     tracing::error!("Wrong action type in Textbox::message: {action:?}");
                     MessageResult::Stale(action)
@@ -0,0 +1,137 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+
+pub use masonry::widget::TimelineItem;
+
+use masonry::widget::{self, TimelineItemClicked};
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// Labeled bars in rows against a time axis, which calls `on_click` with the index of an
+/// item when its bar is clicked.
+///
+/// The user zooms with the mouse wheel and pans by dragging. Hovering a bar shows the
+/// [details](TimelineItem::details) of its item.
+///
+/// This corresponds to the Masonry [`Timeline`](masonry::widget::Timeline) widget.
+///
+/// # Examples
+/// ```
+/// use xilem::view::{timeline, TimelineItem};
+/// # use xilem::WidgetView;
+///
+/// struct Profile {
+///     spans: Vec<(usize, f64, f64, String)>,
+///     selected: Option<usize>,
+/// }
+///
+/// fn view(profile: &mut Profile) -> impl WidgetView<Profile> {
+///     let items = profile.spans.iter().map(|(thread, start, end, name)| {
+///         TimelineItem::new(*thread, *start, *end, name.clone())
+///             .with_details(format!("{name}: {:.1} ms", end - start))
+///     });
+///     timeline(items, |profile: &mut Profile, idx| profile.selected = Some(idx))
+/// }
+/// ```
+pub fn timeline<F, State, Action>(
+    items: impl IntoIterator<Item = TimelineItem>,
+    on_click: F,
+) -> Timeline<F>
+where
+    F: Fn(&mut State, usize) -> Action + Send + Sync + 'static,
+{
+    Timeline {
+        items: items.into_iter().collect(),
+        visible_range: None,
+        on_click,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Timeline<F> {
+    items: Vec<TimelineItem>,
+    visible_range: Option<Range<f64>>,
+    on_click: F,
+}
+
+impl<F> Timeline<F> {
+    /// Set the range of time shown, which fits all the items by default.
+    ///
+    /// The view is moved when this range changes between two rebuilds. In between, the
+    /// user can zoom and pan freely.
+    pub fn visible_range(mut self, range: Range<f64>) -> Self {
+        self.visible_range = Some(range);
+        self
+    }
+}
+
+impl<F> ViewMarker for Timeline<F> {}
+impl<F, State, Action> View<State, Action, ViewCtx> for Timeline<F>
+where
+    F: Fn(&mut State, usize) -> Action + Send + Sync + 'static,
+{
+    type Element = Pod<widget::Timeline>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.with_leaf_action_widget(|ctx| {
+            let mut widget = widget::Timeline::new(self.items.iter().cloned());
+            if let Some(range) = self.visible_range.clone() {
+                widget = widget.with_visible_range(range);
+            }
+            ctx.new_pod(widget)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.items != self.items {
+            widget::Timeline::set_items(&mut element, self.items.iter().cloned());
+        }
+        if let Some(range) = self.visible_range.clone() {
+            if prev.visible_range.as_ref() != Some(&range) {
+                widget::Timeline::set_visible_range(&mut element, range);
+            }
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Timeline::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match (*action).downcast::<TimelineItemClicked>() {
+                Ok(TimelineItemClicked(idx)) => {
+                    MessageResult::Action((self.on_click)(app_state, idx))
+                }
+                Err(action) => {
+                    tracing::error!("Wrong action type in Timeline::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in Timeline::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
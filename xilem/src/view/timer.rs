@@ -0,0 +1,180 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use masonry::widget::{self, TimerFired};
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// Call `on_fire` once, `duration` after this view is added to the tree.
+///
+/// If `duration` changes before the timer fires, the timer is restarted with the new duration.
+/// The timer is cancelled when the view is removed from the tree.
+///
+/// This is an invisible widget which takes no space, so it has to be placed in the widget tree.
+/// The timer is driven by the event loop, so it doesn't need a thread or the async runtime.
+///
+/// This corresponds to the Masonry [`Timer`](masonry::widget::Timer) widget.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+///     flex((
+///         label(&state.notification),
+///         state.notification_visible.then(|| {
+///             timer(Duration::from_secs(3), |state: &mut AppState| {
+///                 state.notification_visible = false;
+///             })
+///         }),
+///     ))
+/// }
+/// ```
+pub fn timer<State, Action, F>(duration: Duration, on_fire: F) -> Timer<F>
+where
+    F: Fn(&mut State) -> Action + 'static,
+{
+    Timer {
+        duration,
+        repeat: false,
+        callback: on_fire,
+    }
+}
+
+/// Call `on_tick` every `period`, starting one `period` after this view is added to the tree.
+///
+/// If `period` changes, the interval is restarted with the new period.
+/// The interval is cancelled when the view is removed from the tree.
+/// If the app can't keep up with the ticks, the missed ticks are skipped.
+///
+/// Like [`timer`], this is an invisible widget which has to be placed in the widget tree.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut Clock) -> impl WidgetView<Clock> {
+///     flex((
+///         label(state.now.to_string()),
+///         interval(Duration::from_secs(1), |state: &mut Clock| state.update_time()),
+///     ))
+/// }
+/// ```
+pub fn interval<State, Action, F>(period: Duration, on_tick: F) -> Timer<F>
+where
+    F: Fn(&mut State) -> Action + 'static,
+{
+    Timer {
+        duration: period,
+        repeat: true,
+        callback: on_tick,
+    }
+}
+
+/// The View for [`timer`] and [`interval`].
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Timer<F> {
+    duration: Duration,
+    repeat: bool,
+    callback: F,
+}
+
+impl<F> ViewMarker for Timer<F> {}
+impl<State, Action, F> View<State, Action, ViewCtx> for Timer<F>
+where
+    F: Fn(&mut State) -> Action + 'static,
+{
+    type Element = Pod<widget::Timer>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.with_leaf_action_widget(|ctx| {
+            ctx.new_pod(if self.repeat {
+                widget::Timer::repeating(self.duration)
+            } else {
+                widget::Timer::new(self.duration)
+            })
+        })
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if self.repeat != prev.repeat {
+            widget::Timer::set_repeat(&mut element, self.repeat);
+        }
+        if self.duration != prev.duration {
+            widget::Timer::set_duration(&mut element, self.duration);
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Timer::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match (*action).downcast::<TimerFired>() {
+                Ok(TimerFired) => MessageResult::Action((self.callback)(app_state)),
+                Err(action) => {
+                    tracing::error!("Wrong action type in Timer::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in Timer::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+// --- MARK: TESTS ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::AppTestHarness;
+    use crate::view::{flex, label};
+    use crate::{WidgetView, Xilem};
+
+    fn app_logic(ticks: &mut u32) -> impl WidgetView<u32> {
+        flex((
+            label(format!("{ticks} ticks")),
+            (*ticks < 2)
+                .then(|| interval(Duration::from_millis(100), |ticks: &mut u32| *ticks += 1)),
+        ))
+    }
+
+    #[test]
+    fn interval_ticks_until_removed() {
+        let mut harness = AppTestHarness::create(Xilem::new(0, app_logic));
+
+        harness.interact(|harness| harness.advance_time(Duration::from_millis(50)));
+        assert_eq!(*harness.state(), 0);
+
+        harness.interact(|harness| harness.advance_time(Duration::from_millis(50)));
+        assert_eq!(*harness.state(), 1);
+
+        harness.interact(|harness| harness.advance_time(Duration::from_millis(100)));
+        assert_eq!(*harness.state(), 2);
+
+        // The interval was removed from the tree
+        harness.interact(|harness| harness.advance_time(Duration::from_millis(500)));
+        assert_eq!(*harness.state(), 2);
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The title bar of undecorated windows.
+
+use masonry::text::ArcStr;
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// A title bar showing `title`, with buttons to minimize, maximize and close the window,
+/// for windows created without platform decorations.
+///
+/// Dragging the bar moves the window, and double-clicking it toggles maximization.
+///
+/// Corresponds to the [`TitleBar`](widget::TitleBar) widget.
+pub fn title_bar(title: impl Into<ArcStr>) -> TitleBar {
+    TitleBar {
+        title: title.into(),
+    }
+}
+
+/// The [`View`] created by [`title_bar`].
+///
+/// See `title_bar`'s docs for more details.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct TitleBar {
+    title: ArcStr,
+}
+
+impl ViewMarker for TitleBar {}
+impl<State, Action> View<State, Action, ViewCtx> for TitleBar {
+    type Element = Pod<widget::TitleBar>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        (ctx.new_pod(widget::TitleBar::new(self.title.clone())), ())
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.title != self.title {
+            widget::TitleBar::set_title(&mut element, self.title.clone());
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, _: &mut ViewCtx, _: Mut<Self::Element>) {}
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _: &[ViewId],
+        message: DynMessage,
+        _: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in TitleBar::message, but TitleBar doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
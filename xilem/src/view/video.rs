@@ -0,0 +1,78 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The widget displaying externally produced frames.
+
+use masonry::widget::{self, ObjectFit, VideoFrames};
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// Displays the frames sent to `frames`, e.g. by a video decoder running on another thread.
+///
+/// By default, frames are scaled to fit in the view, keeping their aspect ratio ([`ObjectFit::Contain`]).
+/// To configure this, call [`fit`](Video::fit) on the returned value.
+///
+/// Corresponds to the [`Video`](widget::Video) widget.
+pub fn video(frames: &VideoFrames) -> Video {
+    Video {
+        frames: frames.clone(),
+        object_fit: ObjectFit::Contain,
+    }
+}
+
+/// The [`View`] created by [`video`].
+///
+/// See `video`'s docs for more details.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Video {
+    frames: VideoFrames,
+    object_fit: ObjectFit,
+}
+
+impl Video {
+    /// Specify the object fit.
+    pub fn fit(mut self, fill: ObjectFit) -> Self {
+        self.object_fit = fill;
+        self
+    }
+}
+
+impl ViewMarker for Video {}
+impl<State, Action> View<State, Action, ViewCtx> for Video {
+    type Element = Pod<widget::Video>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let widget = widget::Video::new(self.frames.clone()).fit_mode(self.object_fit);
+        (ctx.new_pod(widget), ())
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.object_fit != self.object_fit {
+            widget::Video::set_fit_mode(&mut element, self.object_fit);
+        }
+        if prev.frames != self.frames {
+            widget::Video::set_frames(&mut element, self.frames.clone());
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, _: &mut ViewCtx, _: Mut<Self::Element>) {}
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _: &[ViewId],
+        message: DynMessage,
+        _: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in Video::message, but Video doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
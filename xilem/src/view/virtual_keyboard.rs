@@ -0,0 +1,67 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An on-screen keyboard typing into the focused widget.
+
+pub use masonry::widget::{KeyAction, KeyCap, KeyboardLayout};
+
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// An on-screen keyboard with the keys of `layout`, for kiosks and touchscreens without a
+/// hardware keyboard.
+///
+/// Pressing a key types into the focused widget, e.g. a [`textbox`](crate::view::textbox),
+/// as if the key was pressed on a hardware keyboard. Use [`KeyboardLayout::for_locale`] to
+/// pick the layout of the user's language.
+///
+/// Corresponds to the [`VirtualKeyboard`](widget::VirtualKeyboard) widget.
+pub fn virtual_keyboard(layout: KeyboardLayout) -> VirtualKeyboard {
+    VirtualKeyboard { layout }
+}
+
+/// The [`View`] created by [`virtual_keyboard`].
+///
+/// See `virtual_keyboard`'s docs for more details.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct VirtualKeyboard {
+    layout: KeyboardLayout,
+}
+
+impl ViewMarker for VirtualKeyboard {}
+impl<State, Action> View<State, Action, ViewCtx> for VirtualKeyboard {
+    type Element = Pod<widget::VirtualKeyboard>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        let widget = widget::VirtualKeyboard::new(self.layout.clone());
+        (ctx.new_pod(widget), ())
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.layout != self.layout {
+            widget::VirtualKeyboard::set_layout(&mut element, self.layout.clone());
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, _: &mut ViewCtx, _: Mut<Self::Element>) {}
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        _: &[ViewId],
+        message: DynMessage,
+        _: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in VirtualKeyboard::message, but VirtualKeyboard doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
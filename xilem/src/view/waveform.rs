@@ -0,0 +1,160 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+pub use masonry::widget::AudioFeed;
+
+use masonry::widget::{self, WaveformSeek};
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+type SeekCallback<State, Action> = Box<dyn Fn(&mut State, f64) -> Action + Send + Sync + 'static>;
+
+/// The waveform of mono audio `samples`, played at `sample_rate` samples per second.
+///
+/// The samples are only replaced when another [`Arc`] is given. Samples recorded on another
+/// thread can instead be sent to an [`AudioFeed`] given to [`feed`](Waveform::feed).
+///
+/// The user zooms with the mouse wheel and pans by dragging. Clicking moves the playhead
+/// and calls [`on_seek`](Waveform::on_seek) with the time clicked, in seconds.
+///
+/// This corresponds to the Masonry [`Waveform`](masonry::widget::Waveform) widget.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use xilem::view::waveform;
+/// use xilem::WidgetView;
+///
+/// struct Player {
+///     samples: Arc<[f32]>,
+///     position: f64,
+/// }
+///
+/// fn view(player: &mut Player) -> impl WidgetView<Player> {
+///     waveform(44_100.0, player.samples.clone())
+///         .playhead(player.position)
+///         .on_seek(|player: &mut Player, time| player.position = time)
+/// }
+/// ```
+pub fn waveform<State, Action>(sample_rate: f64, samples: Arc<[f32]>) -> Waveform<State, Action> {
+    Waveform {
+        sample_rate,
+        samples,
+        feed: None,
+        playhead: None,
+        on_seek: None,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Waveform<State, Action = ()> {
+    sample_rate: f64,
+    samples: Arc<[f32]>,
+    feed: Option<AudioFeed>,
+    playhead: Option<f64>,
+    on_seek: Option<SeekCallback<State, Action>>,
+    phantom: PhantomData<fn() -> (State, Action)>,
+}
+
+impl<State, Action> Waveform<State, Action> {
+    /// Add the samples sent to `feed` after the given ones.
+    pub fn feed(mut self, feed: &AudioFeed) -> Self {
+        self.feed = Some(feed.clone());
+        self
+    }
+
+    /// Set the position of the playhead, in seconds.
+    pub fn playhead(mut self, playhead: f64) -> Self {
+        self.playhead = Some(playhead);
+        self
+    }
+
+    /// Set the callback called with the time clicked by the user, in seconds.
+    pub fn on_seek<F>(mut self, on_seek: F) -> Self
+    where
+        F: Fn(&mut State, f64) -> Action + Send + Sync + 'static,
+    {
+        self.on_seek = Some(Box::new(on_seek));
+        self
+    }
+}
+
+impl<State, Action> ViewMarker for Waveform<State, Action> {}
+impl<State: 'static, Action: 'static> View<State, Action, ViewCtx> for Waveform<State, Action> {
+    type Element = Pod<widget::Waveform>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.with_leaf_action_widget(|ctx| {
+            let mut widget =
+                widget::Waveform::new(self.sample_rate).with_samples(self.samples.iter().copied());
+            if let Some(feed) = &self.feed {
+                widget = widget.with_feed(feed.clone());
+            }
+            if let Some(playhead) = self.playhead {
+                widget = widget.with_playhead(playhead);
+            }
+            ctx.new_pod(widget)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.sample_rate != self.sample_rate {
+            widget::Waveform::set_sample_rate(&mut element, self.sample_rate);
+        }
+        if !Arc::ptr_eq(&prev.samples, &self.samples) {
+            widget::Waveform::set_samples(&mut element, self.samples.iter().copied());
+        }
+        if prev.feed != self.feed {
+            widget::Waveform::set_feed(&mut element, self.feed.clone());
+        }
+        if prev.playhead != self.playhead {
+            widget::Waveform::set_playhead(&mut element, self.playhead);
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Waveform::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match (*action).downcast::<WaveformSeek>() {
+                Ok(WaveformSeek(time)) => match &self.on_seek {
+                    Some(on_seek) => MessageResult::Action(on_seek(app_state, time)),
+                    None => MessageResult::Nop,
+                },
+                Err(action) => {
+                    tracing::error!("Wrong action type in Waveform::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in Waveform::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The widget showing web content.
+
+use masonry::widget;
+
+use crate::core::{DynMessage, Mut, ViewMarker};
+use crate::{MessageResult, Pod, View, ViewCtx, ViewId};
+
+/// Shows the page at `url` in a native webview.
+///
+/// `on_message` is called with the strings the page posts with `window.ipc.postMessage`.
+/// When `url` changes, the webview navigates to the new URL.
+///
+/// Corresponds to the [`WebView`](widget::WebView) widget; see its docs for platform support.
+/// Requires the `webview` feature.
+pub fn web_view<F, State, Action>(url: impl Into<String>, on_message: F) -> WebView<F>
+where
+    F: Fn(&mut State, String) -> Action + Send + Sync + 'static,
+{
+    WebView {
+        url: url.into(),
+        on_message,
+    }
+}
+
+/// The [`View`] created by [`web_view`].
+///
+/// See `web_view`'s docs for more details.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct WebView<F> {
+    url: String,
+    on_message: F,
+}
+
+impl<F> ViewMarker for WebView<F> {}
+impl<F, State, Action> View<State, Action, ViewCtx> for WebView<F>
+where
+    F: Fn(&mut State, String) -> Action + Send + Sync + 'static,
+{
+    type Element = Pod<widget::WebView>;
+    type ViewState = ();
+
+    fn build(&self, ctx: &mut ViewCtx) -> (Self::Element, Self::ViewState) {
+        ctx.with_leaf_action_widget(|ctx| ctx.new_pod(widget::WebView::new(self.url.clone())))
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (): &mut Self::ViewState,
+        _ctx: &mut ViewCtx,
+        mut element: Mut<Self::Element>,
+    ) {
+        if prev.url != self.url {
+            widget::WebView::load_url(&mut element, self.url.clone());
+        }
+    }
+
+    fn teardown(&self, (): &mut Self::ViewState, ctx: &mut ViewCtx, element: Mut<Self::Element>) {
+        ctx.teardown_leaf(element);
+    }
+
+    fn message(
+        &self,
+        (): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in WebView::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::WebViewMessage(message) => {
+                    MessageResult::Action((self.on_message)(app_state, message))
+                }
+                masonry::Action::WebViewLoaded(_) => MessageResult::Nop,
+                action => {
+                    tracing::error!("Wrong action type in WebView::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in WebView::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
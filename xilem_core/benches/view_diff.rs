@@ -0,0 +1,240 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmarks of rebuilding large view sequences.
+//!
+//! Run with `cargo bench -p xilem_core --bench view_diff`.
+
+#![expect(
+    missing_docs,
+    reason = "`criterion_group!` generates undocumented functions"
+)]
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use xilem_core::{
+    AppendVec, DynMessage, ElementSplice, MessageResult, Mut, SuperElement, View, ViewElement,
+    ViewId, ViewMarker, ViewPathTracker, ViewSequence,
+};
+
+const LIST_LEN: usize = 10_000;
+
+#[derive(Default)]
+struct BenchCtx(Vec<ViewId>);
+
+impl ViewPathTracker for BenchCtx {
+    fn push_id(&mut self, id: ViewId) {
+        self.0.push(id);
+    }
+    fn pop_id(&mut self) {
+        self.0.pop();
+    }
+    fn view_path(&mut self) -> &[ViewId] {
+        &self.0
+    }
+}
+
+/// A stand-in for a widget: a leaf holding a value, or a list of children.
+enum Element {
+    Leaf(
+        #[expect(
+            dead_code,
+            reason = "Stands in for widget properties, which aren't read here"
+        )]
+        u64,
+    ),
+    List(Vec<Self>),
+}
+
+impl ViewElement for Element {
+    type Mut<'a> = &'a mut Self;
+}
+
+impl SuperElement<Self, BenchCtx> for Element {
+    fn upcast(_ctx: &mut BenchCtx, child: Self) -> Self {
+        child
+    }
+
+    fn with_downcast_val<R>(
+        this: Self::Mut<'_>,
+        f: impl FnOnce(Mut<'_, Self>) -> R,
+    ) -> (Self::Mut<'_>, R) {
+        let ret = f(this);
+        (this, ret)
+    }
+}
+
+/// A view with a single value, which updates its element when the value changes.
+struct Item(u64);
+
+impl ViewMarker for Item {}
+impl View<(), (), BenchCtx> for Item {
+    type Element = Element;
+    type ViewState = ();
+
+    fn build(&self, _: &mut BenchCtx) -> (Self::Element, Self::ViewState) {
+        (Element::Leaf(self.0), ())
+    }
+
+    fn rebuild(&self, prev: &Self, _: &mut (), _: &mut BenchCtx, element: Mut<'_, Element>) {
+        if prev.0 != self.0 {
+            *element = Element::Leaf(self.0);
+        }
+    }
+
+    fn teardown(&self, _: &mut (), _: &mut BenchCtx, _: Mut<'_, Element>) {}
+
+    fn message(
+        &self,
+        _: &mut (),
+        _: &[ViewId],
+        message: DynMessage,
+        _: &mut (),
+    ) -> MessageResult<()> {
+        MessageResult::Stale(message)
+    }
+}
+
+/// A view containing a sequence of views.
+struct List<Seq>(Seq);
+
+struct ListSplice<'a> {
+    children: &'a mut Vec<Element>,
+    scratch: &'a mut AppendVec<Element>,
+    ix: usize,
+}
+
+impl ElementSplice<Element> for ListSplice<'_> {
+    fn with_scratch<R>(&mut self, f: impl FnOnce(&mut AppendVec<Element>) -> R) -> R {
+        let ret = f(self.scratch);
+        for element in self.scratch.drain() {
+            self.children.insert(self.ix, element);
+            self.ix += 1;
+        }
+        ret
+    }
+    fn insert(&mut self, element: Element) {
+        self.children.insert(self.ix, element);
+        self.ix += 1;
+    }
+    fn mutate<R>(&mut self, f: impl FnOnce(Mut<'_, Element>) -> R) -> R {
+        let ret = f(&mut self.children[self.ix]);
+        self.ix += 1;
+        ret
+    }
+    fn skip(&mut self, n: usize) {
+        self.ix += n;
+    }
+    fn delete<R>(&mut self, f: impl FnOnce(Mut<'_, Element>) -> R) -> R {
+        let ret = f(&mut self.children[self.ix]);
+        self.children.remove(self.ix);
+        ret
+    }
+}
+
+impl<Seq> ViewMarker for List<Seq> {}
+impl<Seq> View<(), (), BenchCtx> for List<Seq>
+where
+    Seq: ViewSequence<(), (), BenchCtx, Element>,
+{
+    type Element = Element;
+    type ViewState = (Seq::SeqState, AppendVec<Element>);
+
+    fn build(&self, ctx: &mut BenchCtx) -> (Self::Element, Self::ViewState) {
+        let mut elements = AppendVec::default();
+        let state = self.0.seq_build(ctx, &mut elements);
+        (
+            Element::List(elements.into_inner()),
+            (state, AppendVec::default()),
+        )
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        (seq_state, scratch): &mut Self::ViewState,
+        ctx: &mut BenchCtx,
+        element: Mut<'_, Element>,
+    ) {
+        let Element::List(children) = element else {
+            unreachable!("List views always build list elements");
+        };
+        let mut splice = ListSplice {
+            children,
+            scratch,
+            ix: 0,
+        };
+        self.0.seq_rebuild(&prev.0, seq_state, ctx, &mut splice);
+    }
+
+    fn teardown(
+        &self,
+        (seq_state, scratch): &mut Self::ViewState,
+        ctx: &mut BenchCtx,
+        element: Mut<'_, Element>,
+    ) {
+        let Element::List(children) = element else {
+            unreachable!("List views always build list elements");
+        };
+        let mut splice = ListSplice {
+            children,
+            scratch,
+            ix: 0,
+        };
+        self.0.seq_teardown(seq_state, ctx, &mut splice);
+    }
+
+    fn message(
+        &self,
+        (seq_state, _): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: DynMessage,
+        app_state: &mut (),
+    ) -> MessageResult<()> {
+        self.0.seq_message(seq_state, id_path, message, app_state)
+    }
+}
+
+fn list(len: usize) -> List<Vec<Item>> {
+    List((0..len as u64).map(Item).collect())
+}
+
+fn rebuild_list_with_one_change(c: &mut Criterion) {
+    let mut ctx = BenchCtx::default();
+    let base = list(LIST_LEN);
+    let mut changed = list(LIST_LEN);
+    changed.0[LIST_LEN / 2] = Item(u64::MAX);
+    let (mut element, mut state) = base.build(&mut ctx);
+
+    // Alternate between the two lists, so that every rebuild has exactly one change.
+    let views = [base, changed];
+    let mut current = 0;
+    c.bench_function("rebuild: 10k item list with one change", |b| {
+        b.iter(|| {
+            let next = 1 - current;
+            views[next].rebuild(&views[current], &mut state, &mut ctx, &mut element);
+            current = next;
+        });
+    });
+}
+
+fn rebuild_list_with_one_insertion(c: &mut Criterion) {
+    let mut ctx = BenchCtx::default();
+    let base = list(LIST_LEN);
+    let mut longer = list(LIST_LEN);
+    longer.0.push(Item(u64::MAX));
+
+    c.bench_function("rebuild: 10k item list with one appended item", |b| {
+        b.iter_batched_ref(
+            || base.build(&mut BenchCtx::default()),
+            |(element, state)| longer.rebuild(&base, state, &mut ctx, element),
+            BatchSize::LargeInput,
+        );
+    });
+}
+
+criterion_group!(
+    benches,
+    rebuild_list_with_one_change,
+    rebuild_list_with_one_insertion
+);
+criterion_main!(benches);
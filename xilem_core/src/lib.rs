@@ -21,6 +21,13 @@
 #![warn(clippy::print_stdout, clippy::print_stderr)]
 // END LINEBENDER LINT SET
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![cfg_attr(
+    test,
+    expect(
+        unused_crate_dependencies,
+        reason = "False-positive with dev-dependencies only used in benches"
+    )
+)]
 #![cfg_attr(not(test), no_std)]
 #![forbid(unsafe_code)]
 // TODO: Remove any items listed as "Deferred"
@@ -41,8 +48,9 @@ pub use view::{View, ViewId, ViewMarker, ViewPathTracker};
 
 mod views;
 pub use views::{
-    adapt, fork, frozen, lens, map_action, map_state, memoize, one_of, run_once, run_once_raw,
-    Adapt, AdaptThunk, Fork, Frozen, MapAction, MapState, Memoize, OrphanView, RunOnce,
+    adapt, component, fork, frozen, keyed, lens, map_action, map_state, memoize, one_of, run_once,
+    run_once_raw, undoable, Adapt, AdaptThunk, Component, Fork, Frozen, History, Keyed, MapAction,
+    MapState, Memoize, OrphanView, RunOnce, Undoable,
 };
 
 mod message;
@@ -0,0 +1,215 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use core::{fmt::Debug, marker::PhantomData};
+
+use crate::{MessageResult, Mut, View, ViewId, ViewMarker, ViewPathTracker};
+
+/// The types [`Component`] is generic over without storing them.
+type ComponentTypes<State, Local, ChildAction, Action, Context, Message> =
+    PhantomData<fn() -> (State, Local, ChildAction, Action, Context, Message)>;
+
+/// The View for [`component`].
+///
+/// See its documentation for more context.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Component<
+    InitLocal,
+    ViewFn,
+    ActionFn,
+    State,
+    Local,
+    ChildAction,
+    Action,
+    Context,
+    Message,
+> {
+    init_local: InitLocal,
+    view_fn: ViewFn,
+    action_fn: ActionFn,
+    phantom: ComponentTypes<State, Local, ChildAction, Action, Context, Message>,
+}
+
+impl<InitLocal, ViewFn, ActionFn, State, Local, ChildAction, Action, Context, Message> Debug
+    for Component<InitLocal, ViewFn, ActionFn, State, Local, ChildAction, Action, Context, Message>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Component").finish_non_exhaustive()
+    }
+}
+
+/// A view with private local state, which persists across rebuilds.
+///
+/// This is useful for ephemeral UI state (e.g. whether a section is expanded),
+/// which would otherwise have to be stored in the app state.
+///
+/// The parameters of this view are:
+/// - `init_local`: Creates the local state. This is called once, when the component is built.
+/// - `view_fn`: Creates the child view from the local state. This is called on every rebuild.
+///   The callbacks of the child view can only access the local state.
+/// - `action_fn`: Handles the actions of the child view, with access to both the app state and the local state.
+///   This is the boundary between the component and the rest of the app.
+///
+/// If the child view requests a rebuild, or `action_fn` doesn't return [`MessageResult::Nop`],
+/// `view_fn` is called with the updated local state in the next rebuild.
+/// When the local state changed without any change to the app state, `action_fn`
+/// should return [`MessageResult::RequestRebuild`].
+///
+/// Data from the app state which should be shown by the child view can be captured by `view_fn`.
+///
+/// # Examples
+///
+/// (From the Xilem implementation)
+///
+/// ```ignore
+/// enum SectionAction {
+///     Toggle,
+///     Delete,
+/// }
+///
+/// fn section(index: usize, title: String) -> impl WidgetView<AppState> {
+///     component(
+///         || false,
+///         move |expanded: &mut bool| {
+///             flex((
+///                 button(title.clone(), |_| SectionAction::Toggle),
+///                 button("Delete", |_| SectionAction::Delete),
+///                 expanded.then(|| label("Contents")),
+///             ))
+///         },
+///         move |state: &mut AppState, expanded: &mut bool, action| match action {
+///             SectionAction::Toggle => {
+///                 *expanded = !*expanded;
+///                 MessageResult::RequestRebuild
+///             }
+///             SectionAction::Delete => {
+///                 state.sections.remove(index);
+///                 MessageResult::Action(())
+///             }
+///         },
+///     )
+/// }
+/// ```
+pub fn component<
+    State,
+    Local,
+    ChildAction,
+    Action,
+    Context,
+    Message,
+    V,
+    InitLocal,
+    ViewFn,
+    ActionFn,
+>(
+    init_local: InitLocal,
+    view_fn: ViewFn,
+    action_fn: ActionFn,
+) -> Component<InitLocal, ViewFn, ActionFn, State, Local, ChildAction, Action, Context, Message>
+where
+    State: 'static,
+    Local: 'static,
+    ChildAction: 'static,
+    Action: 'static,
+    Context: ViewPathTracker + 'static,
+    Message: 'static,
+    V: View<Local, ChildAction, Context, Message>,
+    InitLocal: Fn() -> Local + 'static,
+    ViewFn: Fn(&mut Local) -> V + 'static,
+    ActionFn: Fn(&mut State, &mut Local, ChildAction) -> MessageResult<Action, Message> + 'static,
+{
+    Component {
+        init_local,
+        view_fn,
+        action_fn,
+        phantom: PhantomData,
+    }
+}
+
+#[allow(unnameable_types)] // reason: Implementation detail, public because of trait visibility rules
+#[derive(Debug)]
+pub struct ComponentState<Local, V, VState> {
+    local: Local,
+    view: V,
+    view_state: VState,
+}
+
+impl<InitLocal, ViewFn, ActionFn, State, Local, ChildAction, Action, Context, Message> ViewMarker
+    for Component<InitLocal, ViewFn, ActionFn, State, Local, ChildAction, Action, Context, Message>
+{
+}
+impl<InitLocal, ViewFn, ActionFn, State, Local, ChildAction, Action, Context, Message, V>
+    View<State, Action, Context, Message>
+    for Component<InitLocal, ViewFn, ActionFn, State, Local, ChildAction, Action, Context, Message>
+where
+    State: 'static,
+    Local: 'static,
+    ChildAction: 'static,
+    Action: 'static,
+    Context: ViewPathTracker + 'static,
+    Message: 'static,
+    V: View<Local, ChildAction, Context, Message>,
+    InitLocal: Fn() -> Local + 'static,
+    ViewFn: Fn(&mut Local) -> V + 'static,
+    ActionFn: Fn(&mut State, &mut Local, ChildAction) -> MessageResult<Action, Message> + 'static,
+{
+    type ViewState = ComponentState<Local, V, V::ViewState>;
+
+    type Element = V::Element;
+
+    fn build(&self, ctx: &mut Context) -> (Self::Element, Self::ViewState) {
+        let mut local = (self.init_local)();
+        let view = (self.view_fn)(&mut local);
+        let (element, view_state) = view.build(ctx);
+        let component_state = ComponentState {
+            local,
+            view,
+            view_state,
+        };
+        (element, component_state)
+    }
+
+    fn rebuild(
+        &self,
+        _prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut Context,
+        element: Mut<'_, Self::Element>,
+    ) {
+        // `view_fn` may capture parts of the app state, so the child view always has to be recreated.
+        let view = (self.view_fn)(&mut view_state.local);
+        view.rebuild(&view_state.view, &mut view_state.view_state, ctx, element);
+        view_state.view = view;
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut Context,
+        element: Mut<'_, Self::Element>,
+    ) {
+        view_state
+            .view
+            .teardown(&mut view_state.view_state, ctx, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Message,
+        app_state: &mut State,
+    ) -> MessageResult<Action, Message> {
+        let ComponentState {
+            local,
+            view,
+            view_state,
+        } = view_state;
+        match view.message(view_state, id_path, message, local) {
+            MessageResult::Action(action) => (self.action_fn)(app_state, local, action),
+            MessageResult::RequestRebuild => MessageResult::RequestRebuild,
+            MessageResult::Nop => MessageResult::Nop,
+            MessageResult::Stale(message) => MessageResult::Stale(message),
+        }
+    }
+}
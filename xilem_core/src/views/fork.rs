@@ -9,6 +9,22 @@ use crate::{
 /// Create a view which acts as `active_view`, whilst also running `alongside_view`, without inserting it into the tree.
 ///
 /// `alongside_view` must be a `ViewSequence` with an element type of [`NoElement`].
+/// These views don't create any widgets, but are built, rebuilt and torn down along with `active_view`,
+/// and can handle messages. This is used for effects such as timers, async tasks or subscriptions,
+/// so that they can be declared next to the part of the UI they are used for.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(state: &mut AppState) -> impl WidgetView<AppState> {
+///     fork(
+///         label(format!("{} seconds", state.seconds)),
+///         state.running.then(|| {
+///             interval(Duration::from_secs(1), |state: &mut AppState| state.seconds += 1)
+///         }),
+///     )
+/// }
+/// ```
 pub fn fork<Active, Alongside>(
     active_view: Active,
     alongside_view: Alongside,
@@ -76,11 +92,11 @@ where
         ctx: &mut Context,
         element: Mut<'_, Self::Element>,
     ) {
-        ctx.with_id(ViewId::new(0), |ctx| {
+        ctx.with_id(ViewId::new(1), |ctx| {
             self.alongside_view
                 .seq_teardown(alongside_state, ctx, &mut NoElements);
         });
-        ctx.with_id(ViewId::new(1), |ctx| {
+        ctx.with_id(ViewId::new(0), |ctx| {
             self.active_view.teardown(active_state, ctx, element);
         });
     }
@@ -0,0 +1,220 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use crate::{MessageResult, Mut, View, ViewId, ViewMarker, ViewPathTracker};
+
+/// App state which records its previous values, to support undo and redo.
+///
+/// The state is recorded by the views wrapped with [`undoable`], which take a snapshot of
+/// the state before handling each message.
+/// Changes made outside of those views (such as through [`History::state_mut`]) aren't recorded.
+#[derive(Debug, Clone)]
+pub struct History<State> {
+    current: State,
+    /// The previous states, with the most recent at the back.
+    undo: VecDeque<State>,
+    /// The states which were undone, with the most recently undone at the back.
+    redo: Vec<State>,
+    limit: Option<usize>,
+}
+
+impl<State: Default> Default for History<State> {
+    fn default() -> Self {
+        Self::new(State::default())
+    }
+}
+
+impl<State> History<State> {
+    /// Create a history starting at `state`, which keeps all recorded states.
+    pub fn new(state: State) -> Self {
+        Self {
+            current: state,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Keep at most `limit` states which can be undone, dropping the oldest ones.
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self.enforce_limit();
+        self
+    }
+
+    /// The current state.
+    pub fn state(&self) -> &State {
+        &self.current
+    }
+
+    /// The current state, which can be changed without being recorded.
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.current
+    }
+
+    /// Whether there is a state to go back to with [`undo`](Self::undo).
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether there is a state to go forward to with [`redo`](Self::redo).
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Go back to the state before the most recent recorded change.
+    ///
+    /// Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(previous) = self.undo.pop_back() else {
+            return false;
+        };
+        let current = core::mem::replace(&mut self.current, previous);
+        self.redo.push(current);
+        true
+    }
+
+    /// Go forward to the state before the most recent [`undo`](Self::undo).
+    ///
+    /// Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        let current = core::mem::replace(&mut self.current, next);
+        self.undo.push_back(current);
+        true
+    }
+
+    /// Forget all the recorded states.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Record that the state was changed from `previous`.
+    fn record(&mut self, previous: State) {
+        self.undo.push_back(previous);
+        self.redo.clear();
+        self.enforce_limit();
+    }
+
+    fn enforce_limit(&mut self) {
+        if let Some(limit) = self.limit {
+            while self.undo.len() > limit {
+                self.undo.pop_front();
+            }
+        }
+    }
+}
+
+/// The View for [`undoable`].
+///
+/// See its documentation for more context.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Undoable<V, State> {
+    child: V,
+    phantom: PhantomData<fn() -> State>,
+}
+
+impl<V: Debug, State> Debug for Undoable<V, State> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Undoable")
+            .field("child", &self.child)
+            .finish()
+    }
+}
+
+/// An adapter which records the changes `view` makes to the state in a [`History`].
+///
+/// Before each message is handled by `view`, a snapshot of the state is taken.
+/// If the state is different after the message was handled, the snapshot is recorded,
+/// so that the change can be undone.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn app_logic(history: &mut History<Drawing>) -> impl WidgetView<History<Drawing>> {
+///     flex((
+///         flex((
+///             button("Undo", |history: &mut History<Drawing>| {
+///                 history.undo();
+///             }),
+///             button("Redo", |history: &mut History<Drawing>| {
+///                 history.redo();
+///             }),
+///         ))
+///         .direction(Axis::Horizontal),
+///         undoable(drawing_view(history.state())),
+///     ))
+/// }
+/// ```
+pub fn undoable<State, Action, Context, Message, V>(view: V) -> Undoable<V, State>
+where
+    State: Clone + PartialEq,
+    Context: ViewPathTracker,
+    V: View<State, Action, Context, Message>,
+{
+    Undoable {
+        child: view,
+        phantom: PhantomData,
+    }
+}
+
+impl<V, State> ViewMarker for Undoable<V, State> {}
+impl<V, State, Action, Context, Message> View<History<State>, Action, Context, Message>
+    for Undoable<V, State>
+where
+    State: Clone + PartialEq + 'static,
+    Context: ViewPathTracker,
+    V: View<State, Action, Context, Message>,
+{
+    type ViewState = V::ViewState;
+    type Element = V::Element;
+
+    fn build(&self, ctx: &mut Context) -> (Self::Element, Self::ViewState) {
+        self.child.build(ctx)
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut Context,
+        element: Mut<'_, Self::Element>,
+    ) {
+        self.child.rebuild(&prev.child, view_state, ctx, element);
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut Context,
+        element: Mut<'_, Self::Element>,
+    ) {
+        self.child.teardown(view_state, ctx, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Message,
+        app_state: &mut History<State>,
+    ) -> MessageResult<Action, Message> {
+        let previous = app_state.current.clone();
+        let result = self
+            .child
+            .message(view_state, id_path, message, &mut app_state.current);
+        if app_state.current != previous {
+            app_state.record(previous);
+        }
+        result
+    }
+}
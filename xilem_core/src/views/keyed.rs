@@ -0,0 +1,280 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use crate::{
+    AppendVec, ElementSplice, MessageResult, ViewElement, ViewId, ViewPathTracker, ViewSequence,
+};
+
+/// The [`ViewSequence`] for [`keyed`].
+///
+/// See its documentation for more context.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Keyed<K, Seq> {
+    items: Vec<(K, Seq)>,
+}
+
+impl<K: Debug, Seq> Debug for Keyed<K, Seq> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Keyed")
+            .field(
+                "keys",
+                &self.items.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+/// A sequence of views which are matched to the views of the previous rebuild by key,
+/// rather than by index.
+///
+/// The sequence for `Vec<impl ViewSequence>` diffs its items by index, so inserting an
+/// item at the front of the vector rebuilds every item, and the elements of the items
+/// after it (along with their state, e.g. scroll position or text selection) end up
+/// associated with a different item.
+/// With `keyed`, items whose key was already present in the previous rebuild are
+/// rebuilt against their previous view, and keep their elements and state.
+///
+/// The parameters of this sequence are:
+/// - `items`: The data to create the sequence from.
+/// - `key`: A function returning the key of an item. Keys should be unique in the sequence.
+///   If a key is repeated, the repeated items are treated as new items.
+/// - `view`: A function creating the view (or sequence) for an item.
+///
+/// Items can only be kept when their relative order didn't change.
+/// When items are reordered, the largest set of items which stayed in order is kept,
+/// and the other ones are built again (as would happen if they were removed and added back).
+///
+/// # Examples
+///
+/// ```ignore
+/// fn todo_list(state: &mut TodoList) -> impl WidgetView<TodoList> {
+///     flex(keyed(
+///         state.todos.iter(),
+///         |todo| todo.id,
+///         |todo| textbox(todo.label.clone(), move |state: &mut TodoList, label| { /* ... */ }),
+///     ))
+/// }
+/// ```
+pub fn keyed<T, K, Seq>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> K,
+    view: impl Fn(T) -> Seq,
+) -> Keyed<K, Seq>
+where
+    K: Ord,
+{
+    Keyed {
+        items: items
+            .into_iter()
+            .map(|item| (key(&item), view(item)))
+            .collect(),
+    }
+}
+
+#[allow(unnameable_types)] // reason: Implementation detail, public because of trait visibility rules
+#[derive(Debug)]
+pub struct KeyedState<InnerState> {
+    /// The state of each item, in the same order as the items.
+    items: Vec<KeyedItemState<InnerState>>,
+    /// The routing id which will be given to the next new item.
+    next_id: u64,
+}
+
+#[derive(Debug)]
+struct KeyedItemState<InnerState> {
+    /// A routing id which stays the same for as long as the item is kept.
+    id: u64,
+    inner: InnerState,
+}
+
+impl<InnerState> KeyedState<InnerState> {
+    fn next_id(&mut self) -> ViewId {
+        let id = self.next_id;
+        self.next_id += 1;
+        ViewId::new(id)
+    }
+}
+
+const TAKEN_ONCE: &str = "Each previous item is either kept or torn down, once";
+
+/// Returns, for each position, whether it is part of a longest strictly increasing
+/// subsequence of the `Some` values in `values`.
+fn longest_increasing_subsequence(values: &[Option<usize>]) -> Vec<bool> {
+    // `tails[len]` is the position of the smallest last value of an increasing subsequence of length `len + 1`.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut predecessors: Vec<Option<usize>> = alloc::vec![None; values.len()];
+    for (position, value) in values.iter().enumerate() {
+        let Some(value) = value else {
+            continue;
+        };
+        let len = tails.partition_point(|&tail| values[tail].is_some_and(|tail| tail < *value));
+        predecessors[position] = len.checked_sub(1).map(|previous| tails[previous]);
+        if len == tails.len() {
+            tails.push(position);
+        } else {
+            tails[len] = position;
+        }
+    }
+
+    let mut in_sequence = alloc::vec![false; values.len()];
+    let mut position = tails.last().copied();
+    while let Some(current) = position {
+        in_sequence[current] = true;
+        position = predecessors[current];
+    }
+    in_sequence
+}
+
+impl<State, Action, Context, Element, K, Seq, Message>
+    ViewSequence<State, Action, Context, Element, Message> for Keyed<K, Seq>
+where
+    K: Ord + 'static,
+    Seq: ViewSequence<State, Action, Context, Element, Message>,
+    Context: ViewPathTracker,
+    Element: ViewElement,
+{
+    type SeqState = KeyedState<Seq::SeqState>;
+
+    fn seq_build(&self, ctx: &mut Context, elements: &mut AppendVec<Element>) -> Self::SeqState {
+        let mut seq_state = KeyedState {
+            items: Vec::with_capacity(self.items.len()),
+            next_id: 0,
+        };
+        for (_, seq) in &self.items {
+            let id = seq_state.next_id();
+            let inner = ctx.with_id(id, |ctx| seq.seq_build(ctx, elements));
+            seq_state.items.push(KeyedItemState {
+                id: id.routing_id(),
+                inner,
+            });
+        }
+        seq_state
+    }
+
+    fn seq_rebuild(
+        &self,
+        prev: &Self,
+        seq_state: &mut Self::SeqState,
+        ctx: &mut Context,
+        elements: &mut impl ElementSplice<Element>,
+    ) {
+        // Match the new items to the previous ones by key.
+        let mut prev_indices: BTreeMap<&K, usize> = BTreeMap::new();
+        for (index, (key, _)) in prev.items.iter().enumerate().rev() {
+            prev_indices.insert(key, index);
+        }
+        let matches: Vec<Option<usize>> = self
+            .items
+            .iter()
+            .map(|(key, _)| prev_indices.remove(key))
+            .collect();
+        // Elements can't be moved, so only the items which stayed in order are kept.
+        let kept = longest_increasing_subsequence(&matches);
+
+        let mut prev_states: Vec<Option<KeyedItemState<Seq::SeqState>>> =
+            seq_state.items.drain(..).map(Some).collect();
+        // The index of the first previous item which wasn't kept or torn down yet.
+        let mut prev_cursor = 0;
+        let mut items = Vec::with_capacity(self.items.len());
+        for ((_, seq), (prev_index, is_kept)) in self.items.iter().zip(matches.iter().zip(kept)) {
+            match prev_index {
+                Some(prev_index) if is_kept => {
+                    // Remove the previous items which come before this one.
+                    let removed = prev_cursor..*prev_index;
+                    for ((_, prev_seq), item) in prev.items[removed.clone()]
+                        .iter()
+                        .zip(&mut prev_states[removed])
+                    {
+                        let mut item = item.take().expect(TAKEN_ONCE);
+                        ctx.with_id(ViewId::new(item.id), |ctx| {
+                            prev_seq.seq_teardown(&mut item.inner, ctx, elements);
+                        });
+                    }
+                    prev_cursor = *prev_index + 1;
+                    let mut item = prev_states[*prev_index].take().expect(TAKEN_ONCE);
+                    ctx.with_id(ViewId::new(item.id), |ctx| {
+                        seq.seq_rebuild(&prev.items[*prev_index].1, &mut item.inner, ctx, elements);
+                    });
+                    items.push(item);
+                }
+                _ => {
+                    let id = seq_state.next_id();
+                    let inner = elements.with_scratch(|elements| {
+                        ctx.with_id(id, |ctx| seq.seq_build(ctx, elements))
+                    });
+                    items.push(KeyedItemState {
+                        id: id.routing_id(),
+                        inner,
+                    });
+                }
+            }
+        }
+        for ((_, prev_seq), item) in prev.items[prev_cursor..]
+            .iter()
+            .zip(&mut prev_states[prev_cursor..])
+        {
+            let mut item = item.take().expect(TAKEN_ONCE);
+            ctx.with_id(ViewId::new(item.id), |ctx| {
+                prev_seq.seq_teardown(&mut item.inner, ctx, elements);
+            });
+        }
+        seq_state.items = items;
+    }
+
+    fn seq_teardown(
+        &self,
+        seq_state: &mut Self::SeqState,
+        ctx: &mut Context,
+        elements: &mut impl ElementSplice<Element>,
+    ) {
+        for ((_, seq), item) in self.items.iter().zip(&mut seq_state.items) {
+            ctx.with_id(ViewId::new(item.id), |ctx| {
+                seq.seq_teardown(&mut item.inner, ctx, elements);
+            });
+        }
+    }
+
+    fn seq_message(
+        &self,
+        seq_state: &mut Self::SeqState,
+        id_path: &[ViewId],
+        message: Message,
+        app_state: &mut State,
+    ) -> MessageResult<Action, Message> {
+        let (start, rest) = id_path
+            .split_first()
+            .expect("Id path has elements for Keyed");
+        let Some(index) = seq_state
+            .items
+            .iter()
+            .position(|item| item.id == start.routing_id())
+        else {
+            // The item was removed, or rebuilt after being reordered
+            return MessageResult::Stale(message);
+        };
+        self.items[index]
+            .1
+            .seq_message(&mut seq_state.items[index].inner, rest, message, app_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_increasing_subsequence_skips_moved_items() {
+        let values = [Some(0), None, Some(3), Some(1), Some(2), None, Some(4)];
+        let kept = longest_increasing_subsequence(&values);
+        assert_eq!(kept, [true, false, false, true, true, false, true]);
+        assert!(longest_increasing_subsequence(&[]).is_empty());
+        assert_eq!(
+            longest_increasing_subsequence(&[None, None]),
+            [false, false]
+        );
+    }
+}
@@ -21,7 +21,7 @@ where
     V: Debug,
 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("MapAction")
+        f.debug_struct("MapState")
             .field("child", &self.child)
             .finish_non_exhaustive()
     }
@@ -229,9 +229,7 @@ where
     ) {
         if core::mem::take(&mut view_state.dirty) {
             let view = (self.init_view)();
-            view_state
-                .view
-                .rebuild(&view_state.view, &mut view_state.view_state, ctx, element);
+            view.rebuild(&view_state.view, &mut view_state.view_state, ctx, element);
             view_state.view = view;
         }
     }
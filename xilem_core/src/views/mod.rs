@@ -19,6 +19,15 @@ pub use fork::{fork, Fork};
 mod memoize;
 pub use memoize::{frozen, memoize, Frozen, Memoize};
 
+mod component;
+pub use component::{component, Component};
+
+mod keyed;
+pub use keyed::{keyed, Keyed};
+
+mod history;
+pub use history::{undoable, History, Undoable};
+
 pub mod one_of;
 
 mod orphan;
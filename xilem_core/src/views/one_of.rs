@@ -21,6 +21,28 @@ pub trait PhantomElementCtx: ViewPathTracker {
 }
 
 /// A [`View`] which can be one of nine inner view types.
+///
+/// This allows returning different view types from the branches of a conditional,
+/// without boxing them into an [`AnyView`](crate::AnyView). The aliases [`OneOf2`]
+/// to [`OneOf9`] (and [`Either`]) name the variants in use.
+///
+/// When the variant stays the same between two rebuilds, the inner view is rebuilt,
+/// so its element and view state are kept. When the variant changes, the previous inner
+/// view is torn down and the new one is built from scratch, so switching back to a
+/// previously shown branch doesn't restore its state. State which should survive this
+/// should be stored in the app state (or in a [`component`](crate::component) above the `OneOf`).
+///
+/// # Examples
+///
+/// ```ignore
+/// fn status_view(status: &Status) -> impl WidgetView<AppState> {
+///     match status {
+///         Status::Loading => OneOf3::A(spinner()),
+///         Status::Loaded(text) => OneOf3::B(label(text.clone())),
+///         Status::Failed => OneOf3::C(button("Retry", |state: &mut AppState| state.retry())),
+///     }
+/// }
+/// ```
 #[allow(missing_docs)] // On variants
 #[derive(Debug)]
 #[must_use = "View values do nothing unless provided to Xilem."]
@@ -0,0 +1,71 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for [`component`].
+
+use xilem_core::{adapt, component, DynMessage, MessageResult, View};
+
+mod common;
+use common::*;
+
+/// A component whose local state counts the messages it received,
+/// and which adds them to the app state every other message.
+fn counter(offset: u32) -> impl View<u32, Action, TestCtx, Element = TestElement> {
+    component(
+        || 0_u32,
+        move |count: &mut u32| {
+            adapt(
+                OperationView::<0>(offset + *count),
+                |count: &mut u32, thunk| -> MessageResult<Action> {
+                    *count += 1;
+                    thunk.call(&mut ())
+                },
+            )
+        },
+        |total: &mut u32, count: &mut u32, action: Action| {
+            if *count % 2 == 0 {
+                *total += *count;
+                MessageResult::Action(action)
+            } else {
+                MessageResult::RequestRebuild
+            }
+        },
+    )
+}
+
+#[test]
+fn component_local_state_persists() {
+    let view = counter(10);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(10)]);
+
+    let mut total = 0;
+    let result = view.message(&mut state, &[], DynMessage::from(Box::new(())), &mut total);
+    assert!(matches!(result, MessageResult::RequestRebuild));
+    assert_eq!(total, 0);
+
+    // The local state is kept, and used to create the new child view.
+    let view2 = counter(20);
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        element.operations,
+        &[
+            Operation::Build(10),
+            Operation::Rebuild { from: 10, to: 21 }
+        ]
+    );
+
+    let MessageResult::Action(action) =
+        view2.message(&mut state, &[], DynMessage::from(Box::new(())), &mut total)
+    else {
+        panic!("Expected an action");
+    };
+    assert_eq!(action.id, 21);
+    assert_eq!(total, 2);
+
+    view2.teardown(&mut state, &mut ctx, &mut element);
+    assert_eq!(element.operations.last(), Some(&Operation::Teardown(21)));
+}
@@ -0,0 +1,128 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the behaviour of [`fork`].
+//!
+//! This is an integration test so that it can use the infrastructure in [`common`].
+
+#![expect(clippy::missing_assert_message, reason = "Deferred: Noisy")]
+
+use xilem_core::{
+    fork, DynMessage, MessageResult, Mut, NoElement, View, ViewId, ViewMarker, ViewPathTracker,
+};
+
+mod common;
+use common::*;
+
+/// A view without an element, which records the operations ran on it in its view state.
+struct EffectView(u32);
+
+struct EffectState {
+    view_path: Vec<ViewId>,
+    operations: Vec<Operation>,
+}
+
+impl ViewMarker for EffectView {}
+impl View<(), Action, TestCtx> for EffectView {
+    type Element = NoElement;
+
+    type ViewState = EffectState;
+
+    fn build(&self, ctx: &mut TestCtx) -> (Self::Element, Self::ViewState) {
+        (
+            NoElement,
+            EffectState {
+                view_path: ctx.view_path().to_vec(),
+                operations: vec![Operation::Build(self.0)],
+            },
+        )
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut TestCtx,
+        (): Mut<'_, Self::Element>,
+    ) {
+        assert_eq!(view_state.view_path, ctx.view_path());
+        view_state.operations.push(Operation::Rebuild {
+            from: prev.0,
+            to: self.0,
+        });
+    }
+
+    fn teardown(
+        &self,
+        view_state: &mut Self::ViewState,
+        ctx: &mut TestCtx,
+        (): Mut<'_, Self::Element>,
+    ) {
+        assert_eq!(view_state.view_path, ctx.view_path());
+        view_state.operations.push(Operation::Teardown(self.0));
+    }
+
+    fn message(
+        &self,
+        _: &mut Self::ViewState,
+        _: &[ViewId],
+        _: DynMessage,
+        _: &mut (),
+    ) -> MessageResult<Action> {
+        // The active view returns an `Action`, so this distinguishes the two.
+        MessageResult::RequestRebuild
+    }
+}
+
+#[test]
+fn fork_runs_lifecycle_of_both_views() {
+    let view = fork(OperationView::<0>(0), EffectView(1));
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(0)]);
+    assert_eq!(state.1.operations, &[Operation::Build(1)]);
+
+    let view2 = fork(OperationView::<0>(2), EffectView(3));
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        element.operations,
+        &[Operation::Build(0), Operation::Rebuild { from: 0, to: 2 }]
+    );
+    assert_eq!(
+        state.1.operations,
+        &[Operation::Build(1), Operation::Rebuild { from: 1, to: 3 }]
+    );
+
+    // Both views check that they are torn down with the path they were built with.
+    view2.teardown(&mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(element.operations.last(), Some(&Operation::Teardown(2)));
+    assert_eq!(state.1.operations.last(), Some(&Operation::Teardown(3)));
+}
+
+#[test]
+fn fork_routes_messages() {
+    let view = fork(OperationView::<0>(0), EffectView(1));
+    let mut ctx = TestCtx::default();
+    let (_, mut state) = view.build(&mut ctx);
+
+    let active_path = [ViewId::new(0)];
+    let active_result = view.message(
+        &mut state,
+        &active_path,
+        DynMessage::from(Box::new(())),
+        &mut (),
+    );
+    assert_action(active_result, 0);
+
+    let alongside_path = [ViewId::new(1)];
+    let alongside_result = view.message(
+        &mut state,
+        &alongside_path,
+        DynMessage::from(Box::new(())),
+        &mut (),
+    );
+    assert!(matches!(alongside_result, MessageResult::RequestRebuild));
+}
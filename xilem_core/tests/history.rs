@@ -0,0 +1,79 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for [`undoable`] and [`History`].
+
+#![expect(clippy::missing_assert_message, reason = "Deferred: Noisy")]
+
+use xilem_core::{adapt, undoable, DynMessage, History, MessageResult, View};
+
+mod common;
+use common::*;
+
+/// A view which adds `amount` to the state whenever it gets a message.
+fn add(amount: u32) -> impl View<u32, Action, TestCtx, Element = TestElement> {
+    adapt(
+        OperationView::<0>(amount),
+        move |count: &mut u32, thunk| -> MessageResult<Action> {
+            *count += amount;
+            thunk.call(&mut ())
+        },
+    )
+}
+
+fn send_message(
+    view: &impl View<History<u32>, Action, TestCtx, Element = TestElement>,
+    history: &mut History<u32>,
+) {
+    let mut ctx = TestCtx::default();
+    let (_, mut state) = view.build(&mut ctx);
+    let result = view.message(&mut state, &[], DynMessage::from(Box::new(())), history);
+    assert!(matches!(result, MessageResult::Action(_)));
+}
+
+#[test]
+fn undoable_records_changes() {
+    let mut history = History::new(1);
+    send_message(&undoable(add(2)), &mut history);
+    send_message(&undoable(add(3)), &mut history);
+    assert_eq!(*history.state(), 6);
+    assert!(history.can_undo());
+    assert!(!history.can_redo());
+
+    assert!(history.undo());
+    assert_eq!(*history.state(), 3);
+    assert!(history.undo());
+    assert_eq!(*history.state(), 1);
+    assert!(!history.undo());
+    assert_eq!(*history.state(), 1);
+
+    assert!(history.redo());
+    assert_eq!(*history.state(), 3);
+
+    // A new change discards the states which could have been redone.
+    send_message(&undoable(add(10)), &mut history);
+    assert_eq!(*history.state(), 13);
+    assert!(!history.can_redo());
+    assert!(history.undo());
+    assert_eq!(*history.state(), 3);
+}
+
+#[test]
+fn undoable_ignores_unchanged_state() {
+    let mut history = History::new(1);
+    send_message(&undoable(add(0)), &mut history);
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn history_limit_drops_oldest_states() {
+    let mut history = History::new(0).with_limit(2);
+    for _ in 0..4 {
+        send_message(&undoable(add(1)), &mut history);
+    }
+    assert_eq!(*history.state(), 4);
+    assert!(history.undo());
+    assert!(history.undo());
+    assert!(!history.undo());
+    assert_eq!(*history.state(), 2);
+}
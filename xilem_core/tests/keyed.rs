@@ -0,0 +1,141 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the behaviour of [`keyed`] sequences.
+
+#![expect(
+    clippy::shadow_unrelated,
+    reason = "Deferred: Noisy. Fix is to use scopes"
+)]
+
+use xilem_core::{keyed, DynMessage, MessageResult, View, ViewId};
+
+mod common;
+use common::*;
+
+/// A sequence with an item for each key, whose view records the operations with the key as id.
+fn keyed_sequence(id: u32, keys: &[u32]) -> impl View<(), Action, TestCtx, Element = TestElement> {
+    sequence(id, keyed(keys.to_vec(), |key| *key, OperationView::<0>))
+}
+
+fn active_operations(element: &TestElement) -> Vec<Vec<Operation>> {
+    let children = element.children.as_ref().unwrap();
+    children
+        .active
+        .iter()
+        .map(|child| child.operations.clone())
+        .collect()
+}
+
+fn active_path(element: &TestElement, index: usize) -> Vec<ViewId> {
+    element.children.as_ref().unwrap().active[index]
+        .view_path
+        .clone()
+}
+
+#[test]
+fn insert_at_front_keeps_items() {
+    let view = keyed_sequence(0, &[1, 2]);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+
+    let view2 = keyed_sequence(1, &[0, 1, 2]);
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        active_operations(&element),
+        [
+            vec![Operation::Build(0)],
+            vec![Operation::Build(1), Operation::Rebuild { from: 1, to: 1 }],
+            vec![Operation::Build(2), Operation::Rebuild { from: 2, to: 2 }],
+        ]
+    );
+    assert!(element.children.as_ref().unwrap().deleted.is_empty());
+}
+
+#[test]
+fn remove_from_middle_keeps_items() {
+    let view = keyed_sequence(0, &[1, 2, 3]);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+
+    let view2 = keyed_sequence(1, &[1, 3]);
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        active_operations(&element),
+        [
+            vec![Operation::Build(1), Operation::Rebuild { from: 1, to: 1 }],
+            vec![Operation::Build(3), Operation::Rebuild { from: 3, to: 3 }],
+        ]
+    );
+    let deleted = &element.children.as_ref().unwrap().deleted;
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].0, 1);
+    assert_eq!(
+        deleted[0].1.operations,
+        [Operation::Build(2), Operation::Teardown(2)]
+    );
+}
+
+#[test]
+fn reordered_items_are_rebuilt() {
+    let view = keyed_sequence(0, &[1, 2, 3]);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+
+    // 1 and 2 stay in order, so only 3 has to be built again.
+    let view2 = keyed_sequence(1, &[3, 1, 2]);
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        active_operations(&element),
+        [
+            vec![Operation::Build(3)],
+            vec![Operation::Build(1), Operation::Rebuild { from: 1, to: 1 }],
+            vec![Operation::Build(2), Operation::Rebuild { from: 2, to: 2 }],
+        ]
+    );
+    let deleted = &element.children.as_ref().unwrap().deleted;
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(
+        deleted[0].1.operations,
+        [Operation::Build(3), Operation::Teardown(3)]
+    );
+}
+
+#[test]
+fn messages_follow_items() {
+    let view = keyed_sequence(0, &[1, 2]);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    let path_of_1 = active_path(&element, 0);
+    let path_of_2 = active_path(&element, 1);
+
+    let view2 = keyed_sequence(1, &[0, 2]);
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+
+    // The item for key 2 moved to another index, but still gets its messages.
+    assert_eq!(active_path(&element, 1), path_of_2);
+    let result = view2.message(
+        &mut state,
+        &path_of_2,
+        DynMessage::from(Box::new(())),
+        &mut (),
+    );
+    assert_action(result, 2);
+
+    // The item for key 1 was removed.
+    let result = view2.message(
+        &mut state,
+        &path_of_1,
+        DynMessage::from(Box::new(())),
+        &mut (),
+    );
+    assert!(matches!(result, MessageResult::Stale(_)));
+
+    view2.teardown(&mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+}
@@ -0,0 +1,75 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for [`map_state`] and [`lens`].
+
+use xilem_core::{adapt, lens, map_state, DynMessage, MessageResult, View};
+
+mod common;
+use common::*;
+
+#[derive(Default)]
+struct App {
+    settings: u32,
+    other: u32,
+}
+
+/// A component which only knows about its own part of the state.
+fn counter(count: &mut u32) -> impl View<u32, Action, TestCtx, Element = TestElement> {
+    adapt(
+        OperationView::<0>(*count),
+        |count: &mut u32, thunk| -> MessageResult<Action> {
+            *count += 1;
+            thunk.call(&mut ())
+        },
+    )
+}
+
+fn settings_counter(count: u32) -> impl View<App, Action, TestCtx, Element = TestElement> {
+    map_state(counter(&mut { count }), |app: &mut App| &mut app.settings)
+}
+
+#[test]
+fn map_state_scopes_child_state() {
+    let mut app = App::default();
+    let view = settings_counter(7);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(7)]);
+
+    let result = view.message(&mut state, &[], DynMessage::from(Box::new(())), &mut app);
+    assert_action(result, 7);
+    assert_eq!(app.settings, 1);
+    assert_eq!(app.other, 0);
+
+    let view2 = settings_counter(8);
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    view2.teardown(&mut state, &mut ctx, &mut element);
+    assert_eq!(
+        element.operations,
+        &[
+            Operation::Build(7),
+            Operation::Rebuild { from: 7, to: 8 },
+            Operation::Teardown(8)
+        ]
+    );
+}
+
+#[test]
+fn lens_creates_component_from_sub_state() {
+    let mut app = App {
+        settings: 3,
+        other: 5,
+    };
+    let view = lens(counter, &mut app, |app| &mut app.settings);
+    let mut ctx = TestCtx::default();
+    let (element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(3)]);
+
+    let result = view.message(&mut state, &[], DynMessage::from(Box::new(())), &mut app);
+    assert_action(result, 3);
+    assert_eq!(app.settings, 4);
+    assert_eq!(app.other, 5);
+}
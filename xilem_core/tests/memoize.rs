@@ -0,0 +1,99 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for [`memoize`] and [`frozen`].
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use xilem_core::{adapt, frozen, memoize, DynMessage, MessageResult, View};
+
+mod common;
+use common::*;
+
+/// A view which requests a rebuild whenever it gets a message.
+fn rebuild_on_message(id: u32) -> impl View<(), Action, TestCtx, Element = TestElement> {
+    adapt(
+        OperationView::<0>(id),
+        |state: &mut (), thunk| -> MessageResult<Action> {
+            drop(thunk.call(state));
+            MessageResult::RequestRebuild
+        },
+    )
+}
+
+fn memoized(data: u32) -> impl View<(), Action, TestCtx, Element = TestElement> {
+    memoize(data, |data: &u32| OperationView::<0>(*data))
+}
+
+fn memoized_rebuild_on_message(data: u32) -> impl View<(), Action, TestCtx, Element = TestElement> {
+    memoize(data, |data: &u32| rebuild_on_message(*data))
+}
+
+#[test]
+fn memoize_skips_unchanged_data() {
+    let view = memoized(1);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(1)]);
+
+    let view2 = memoized(1);
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(element.operations, &[Operation::Build(1)]);
+
+    let view3 = memoized(2);
+    view3.rebuild(&view2, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        element.operations,
+        &[Operation::Build(1), Operation::Rebuild { from: 1, to: 2 }]
+    );
+
+    view3.teardown(&mut state, &mut ctx, &mut element);
+    assert_eq!(element.operations.last(), Some(&Operation::Teardown(2)));
+}
+
+#[test]
+fn memoize_rebuilds_after_requested_rebuild() {
+    let view = memoized_rebuild_on_message(1);
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+
+    let result = view.message(&mut state, &[], DynMessage::from(Box::new(())), &mut ());
+    assert!(matches!(result, MessageResult::RequestRebuild));
+
+    // The data is unchanged, but the child asked to be rebuilt.
+    let view2 = memoized_rebuild_on_message(1);
+    view2.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        element.operations,
+        &[Operation::Build(1), Operation::Rebuild { from: 1, to: 1 }]
+    );
+
+    // That request is only honoured once.
+    view.rebuild(&view2, &mut state, &mut ctx, &mut element);
+    assert_eq!(element.operations.len(), 2);
+}
+
+#[test]
+fn frozen_rebuilds_freshly_created_view() {
+    static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+    let view = frozen(|| rebuild_on_message(NEXT_ID.fetch_add(1, Ordering::Relaxed)));
+    let mut ctx = TestCtx::default();
+    let (mut element, mut state) = view.build(&mut ctx);
+    assert_eq!(element.operations, &[Operation::Build(0)]);
+
+    view.rebuild(&view, &mut state, &mut ctx, &mut element);
+    assert_eq!(element.operations, &[Operation::Build(0)]);
+
+    let result = view.message(&mut state, &[], DynMessage::from(Box::new(())), &mut ());
+    assert!(matches!(result, MessageResult::RequestRebuild));
+    view.rebuild(&view, &mut state, &mut ctx, &mut element);
+    ctx.assert_empty();
+    assert_eq!(
+        element.operations,
+        &[Operation::Build(0), Operation::Rebuild { from: 0, to: 1 }]
+    );
+}